@@ -0,0 +1,88 @@
+//! Channel selection for multichannel recordings.
+//!
+//! By default all channels are mixed to mono. `--channel 2` analyzes only the
+//! second channel, and `--channel all` analyzes every channel separately,
+//! e.g. the left and right microphones of a stereo Song Meter.
+
+use std::fmt;
+
+/// Which channels of a recording to analyze.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChannelSelection {
+    /// Mix all channels to mono.
+    #[default]
+    Mix,
+    /// Analyze one channel (1-based).
+    Channel(u32),
+    /// Analyze each channel separately.
+    All,
+}
+
+impl ChannelSelection {
+    /// Parse `mix`, `all` or a 1-based channel number.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "mix" => Ok(Self::Mix),
+            "all" => Ok(Self::All),
+            other => match other.parse::<u32>() {
+                Ok(channel) if channel >= 1 => Ok(Self::Channel(channel)),
+                _ => Err(format!(
+                    "'{s}' is not a channel (expected a channel number from 1, 'all' or 'mix')"
+                )),
+            },
+        }
+    }
+
+    /// Channels to analyze in a recording with `count` channels, one pass
+    /// each; `None` is the mono mix.
+    ///
+    /// Mono recordings are analyzed once without a channel, unless a
+    /// channel other than the first is selected.
+    pub fn passes(self, count: usize) -> Vec<Option<u32>> {
+        match self {
+            Self::Channel(channel) if count > 1 || channel > 1 => vec![Some(channel)],
+            Self::All if count > 1 => (1..=u32::try_from(count).unwrap_or(u32::MAX))
+                .map(Some)
+                .collect(),
+            _ => vec![None],
+        }
+    }
+}
+
+impl fmt::Display for ChannelSelection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mix => f.write_str("mix"),
+            Self::Channel(channel) => write!(f, "{channel}"),
+            Self::All => f.write_str("all"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_channel_selection() {
+        assert_eq!(ChannelSelection::parse("mix"), Ok(ChannelSelection::Mix));
+        assert_eq!(ChannelSelection::parse("ALL"), Ok(ChannelSelection::All));
+        assert_eq!(
+            ChannelSelection::parse("2"),
+            Ok(ChannelSelection::Channel(2))
+        );
+        assert!(ChannelSelection::parse("0").is_err());
+        assert!(ChannelSelection::parse("left").is_err());
+    }
+
+    #[test]
+    fn test_channel_passes() {
+        assert_eq!(ChannelSelection::Mix.passes(2), vec![None]);
+        assert_eq!(ChannelSelection::All.passes(2), vec![Some(1), Some(2)]);
+        assert_eq!(ChannelSelection::All.passes(1), vec![None]);
+        assert_eq!(ChannelSelection::Channel(2).passes(4), vec![Some(2)]);
+        assert_eq!(ChannelSelection::Channel(1).passes(1), vec![None]);
+        // Missing channels are reported by the decoder
+        assert_eq!(ChannelSelection::Channel(2).passes(1), vec![Some(2)]);
+    }
+}
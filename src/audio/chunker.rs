@@ -1,10 +1,64 @@
 //! Audio chunking with overlap support.
 
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// Samples of one segment, viewed in a shared block.
+///
+/// Overlapping segments cut by [`chunk_audio`] share the recording's samples
+/// instead of each holding a copy, so high-overlap runs do not multiply
+/// allocations and memory traffic. Segments from the streaming decoder are
+/// resampled one at a time and own their samples; wrapping them with
+/// `SegmentSamples::from` does not copy. Cloning is cheap.
+#[derive(Debug, Clone)]
+pub struct SegmentSamples {
+    block: Arc<Vec<f32>>,
+    start: usize,
+    len: usize,
+}
+
+impl SegmentSamples {
+    /// View `len` samples of `block` starting at `start`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range lies outside `block`.
+    pub fn new(block: Arc<Vec<f32>>, start: usize, len: usize) -> Self {
+        assert!(
+            start + len <= block.len(),
+            "segment {start}..{} outside block of {} samples",
+            start + len,
+            block.len()
+        );
+        Self { block, start, len }
+    }
+
+    /// The segment's samples.
+    pub fn as_slice(&self) -> &[f32] {
+        &self.block[self.start..self.start + self.len]
+    }
+}
+
+impl Deref for SegmentSamples {
+    type Target = [f32];
+
+    fn deref(&self) -> &[f32] {
+        self.as_slice()
+    }
+}
+
+impl From<Vec<f32>> for SegmentSamples {
+    fn from(samples: Vec<f32>) -> Self {
+        let len = samples.len();
+        Self::new(Arc::new(samples), 0, len)
+    }
+}
+
 /// A chunk of audio with its time offset.
 #[derive(Debug, Clone)]
 pub struct AudioChunk {
     /// Audio samples for this chunk.
-    pub samples: Vec<f32>,
+    pub samples: SegmentSamples,
     /// Start time in seconds.
     pub start_time: f32,
     /// End time in seconds.
@@ -13,6 +67,9 @@ pub struct AudioChunk {
 
 /// Chunk audio samples with overlap.
 ///
+/// Chunks share one copy of `samples`; only chunks running past the end,
+/// which are zero-padded, get samples of their own.
+///
 /// # Arguments
 ///
 /// * `samples` - Audio samples to chunk
@@ -48,15 +105,19 @@ pub fn chunk_audio(
         return Vec::new();
     }
 
+    let block = Arc::new(samples.to_vec());
     let mut chunks = Vec::new();
     let mut pos = 0;
 
     while pos < samples.len() {
-        let end = (pos + chunk_samples).min(samples.len());
-        let mut chunk_data = samples[pos..end].to_vec();
-
-        // Zero-pad if needed
-        chunk_data.resize(chunk_samples, 0.0);
+        let chunk_data = if pos + chunk_samples <= samples.len() {
+            SegmentSamples::new(Arc::clone(&block), pos, chunk_samples)
+        } else {
+            // Zero-pad the tail
+            let mut padded = samples[pos..].to_vec();
+            padded.resize(chunk_samples, 0.0);
+            SegmentSamples::from(padded)
+        };
 
         #[allow(clippy::cast_precision_loss)]
         let start_time = pos as f32 / sample_rate as f32;
@@ -80,6 +141,21 @@ pub fn chunk_audio(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_overlapping_chunks_share_samples() {
+        #[allow(clippy::cast_precision_loss)]
+        let samples: Vec<f32> = (0..144_000).map(|i| i as f32).collect();
+        let chunks = chunk_audio(&samples, 48_000, 1.0, 0.5);
+
+        assert!(Arc::ptr_eq(
+            &chunks[0].samples.block,
+            &chunks[1].samples.block
+        ));
+        assert_eq!(chunks[1].samples.len(), 48_000);
+        assert_eq!(chunks[1].samples[0], 24_000.0);
+        assert_eq!(&chunks[2].samples[..], &samples[48_000..96_000]);
+    }
+
     #[test]
     fn test_chunk_audio_no_overlap() {
         let samples = vec![0.0; 96_000]; // 2 seconds at 48kHz
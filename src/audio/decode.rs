@@ -6,10 +6,13 @@ use std::path::Path;
 use symphonia::core::audio::{AudioBufferRef, Signal};
 use symphonia::core::codecs::{CODEC_TYPE_NULL, Decoder, DecoderOptions};
 use symphonia::core::formats::FormatOptions;
-use symphonia::core::formats::FormatReader;
-use symphonia::core::io::{MediaSourceStream, MediaSourceStreamOptions};
+use symphonia::core::formats::{FormatReader, SeekMode, SeekTo};
+use symphonia::core::io::{
+    MediaSource, MediaSourceStream, MediaSourceStreamOptions, ReadOnlySource,
+};
 use symphonia::core::meta::MetadataOptions;
 use symphonia::core::probe::Hint;
+use symphonia::core::units::{Time, TimeBase};
 
 /// Decoded audio data.
 #[derive(Debug, Clone)]
@@ -38,7 +41,13 @@ pub struct StreamingDecoder {
     track_id: u32,
     sample_rate: u32,
     channels: usize,
+    /// Channel to decode (0-based); `None` mixes all channels to mono.
+    channel: Option<usize>,
+    /// Pre-filter applied to decoded samples, if any.
+    filter: Option<super::AudioFilter>,
     duration_secs: Option<f64>,
+    /// Time base of the track's timestamps, if known.
+    time_base: Option<TimeBase>,
     /// Buffer for accumulating decoded samples.
     buffer: Vec<f32>,
     /// Total samples emitted so far (for tracking position).
@@ -47,6 +56,14 @@ pub struct StreamingDecoder {
     path: std::path::PathBuf,
     /// Whether we've reached end of stream.
     eof: bool,
+    /// Number of corrupt packets skipped during decoding.
+    skipped_packets: usize,
+    /// Recording that continues this one, decoded once this file ends.
+    continuation: Option<Box<StreamingDecoder>>,
+    /// Stream position at which the continuation's audio begins, once reached.
+    continuation_start: Option<usize>,
+    /// URL of a network stream, reopened when the connection drops.
+    source_url: Option<String>,
 }
 
 impl StreamingDecoder {
@@ -57,16 +74,51 @@ impl StreamingDecoder {
             source: Box::new(e),
         })?;
 
-        let mss = MediaSourceStream::new(Box::new(file), MediaSourceStreamOptions::default());
-
         let mut hint = Hint::new();
         if let Some(ext) = path.extension() {
             hint.with_extension(&ext.to_string_lossy());
         }
+        Self::from_source(Box::new(file), &hint, path)
+    }
+
+    /// Open a network audio stream (HTTP, HTTPS or Icecast) for streaming decode.
+    ///
+    /// A live stream has no known duration. When it ends or the connection
+    /// drops, the decoder reconnects, waiting longer after each failed attempt,
+    /// and positions carry on from where they left off; audio sent while
+    /// disconnected is lost. A file served over HTTP is decoded once.
+    pub fn open_url(url: &str) -> Result<Self> {
+        let (decoder, live) = Self::connect(url)?;
+        Ok(Self {
+            source_url: live.then(|| url.to_string()),
+            ..decoder
+        })
+    }
+
+    /// Connect to a network stream and probe its format.
+    ///
+    /// Also returns whether the stream is live rather than a file.
+    fn connect(url: &str) -> Result<(Self, bool)> {
+        let (source, extension) = super::network::HttpSource::connect(url)?;
+        let live = source.is_live();
+        let mut hint = Hint::new();
+        if let Some(extension) = &extension {
+            hint.with_extension(extension);
+        }
+        let decoder =
+            Self::from_source(Box::new(ReadOnlySource::new(source)), &hint, Path::new(url))?;
+        Ok((decoder, live))
+    }
+
+    /// Probe `source` and set up a decoder for its first audio track.
+    ///
+    /// `path` names the source in errors.
+    fn from_source(source: Box<dyn MediaSource>, hint: &Hint, path: &Path) -> Result<Self> {
+        let mss = MediaSourceStream::new(source, MediaSourceStreamOptions::default());
 
         let probed = symphonia::default::get_probe()
             .format(
-                &hint,
+                hint,
                 mss,
                 &FormatOptions::default(),
                 &MetadataOptions::default(),
@@ -106,6 +158,8 @@ impl StreamingDecoder {
             .n_frames
             .map(|frames| frames as f64 / f64::from(sample_rate));
 
+        let time_base = track.codec_params.time_base;
+
         let decoder = symphonia::default::get_codecs()
             .make(&track.codec_params, &DecoderOptions::default())
             .map_err(|e| Error::AudioDecode {
@@ -119,11 +173,18 @@ impl StreamingDecoder {
             track_id,
             sample_rate,
             channels,
+            channel: None,
+            filter: None,
             duration_secs,
+            time_base,
             buffer: Vec::new(),
             samples_emitted: 0,
             path: path.to_path_buf(),
             eof: false,
+            skipped_packets: 0,
+            continuation: None,
+            continuation_start: None,
+            source_url: None,
         })
     }
 
@@ -137,6 +198,151 @@ impl StreamingDecoder {
         self.sample_rate
     }
 
+    /// Number of channels in the recording.
+    pub fn channel_count(&self) -> usize {
+        self.channels
+    }
+
+    /// Decode only `channel` (1-based) instead of mixing all channels to mono.
+    ///
+    /// # Errors
+    /// Returns an error if the recording has no such channel.
+    pub fn select_channel(&mut self, channel: u32) -> Result<()> {
+        let index = usize::try_from(channel)
+            .ok()
+            .and_then(|channel| channel.checked_sub(1))
+            .filter(|&index| index < self.channels)
+            .ok_or_else(|| Error::AudioChannel {
+                path: self.path.clone(),
+                channel,
+                channels: self.channels,
+            })?;
+        self.channel = Some(index);
+        Ok(())
+    }
+
+    /// Filter decoded audio with `spec` before it is split into segments.
+    pub fn set_filter(&mut self, spec: super::FilterSpec) {
+        self.filter = Some(super::AudioFilter::new(spec, self.sample_rate));
+    }
+
+    /// Number of corrupt packets skipped so far.
+    pub fn skipped_packets(&self) -> usize {
+        self.skipped_packets
+    }
+
+    /// Continue the stream with `next` when this file ends.
+    ///
+    /// A segment that runs past the end of this file is completed with audio
+    /// from the start of `next` instead of being zero-padded. Positions keep
+    /// counting from the start of this file.
+    ///
+    /// # Errors
+    /// Returns an error if `next` has a different sample rate.
+    pub fn continue_with(&mut self, next: Self) -> Result<()> {
+        if next.sample_rate != self.sample_rate {
+            return Err(Error::StitchSampleRate {
+                path: next.path,
+                sample_rate: next.sample_rate,
+                expected: self.sample_rate,
+            });
+        }
+        self.continuation = Some(Box::new(next));
+        Ok(())
+    }
+
+    /// Stream position at which the continuation's audio begins, once the
+    /// decoder has reached it.
+    pub fn continuation_start(&self) -> Option<usize> {
+        self.continuation_start
+    }
+
+    /// Jump to `seconds` from the start of the file.
+    ///
+    /// Seeks the container to the packet at or before the target and decodes
+    /// forward from there, so the next segment starts exactly at the requested
+    /// sample. If the format cannot seek, audio is decoded and discarded from
+    /// the current position instead.
+    ///
+    /// # Errors
+    /// Returns an error if the target lies before the current position of a
+    /// stream that cannot seek, or if decoding fails.
+    pub fn seek(&mut self, seconds: f64) -> Result<()> {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let target = (seconds.max(0.0) * f64::from(self.sample_rate)).round() as usize;
+
+        let seeked = self.format.seek(
+            SeekMode::Accurate,
+            SeekTo::Time {
+                time: Time::from(seconds.max(0.0)),
+                track_id: Some(self.track_id),
+            },
+        );
+        match seeked {
+            Ok(seeked) => {
+                self.decoder.reset();
+                if let Some(filter) = &mut self.filter {
+                    filter.reset();
+                }
+                self.buffer.clear();
+                self.eof = false;
+                self.samples_emitted = self.ts_to_sample(seeked.actual_ts);
+                tracing::debug!(
+                    "Seeked to sample {} for target {}",
+                    self.samples_emitted,
+                    target
+                );
+            }
+            Err(e) if target >= self.samples_emitted => {
+                tracing::debug!("Seek failed ({e}), decoding forward to sample {target}");
+            }
+            Err(e) => {
+                return Err(Error::AudioDecode {
+                    path: self.path.clone(),
+                    source: Box::new(e),
+                });
+            }
+        }
+        self.skip_to(target)
+    }
+
+    /// Convert a track timestamp to a sample position.
+    fn ts_to_sample(&self, ts: u64) -> usize {
+        let Some(time_base) = self.time_base else {
+            return usize::try_from(ts).unwrap_or(usize::MAX);
+        };
+        let time = time_base.calc_time(ts);
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss
+        )]
+        let sample =
+            ((time.seconds as f64 + time.frac) * f64::from(self.sample_rate)).round() as usize;
+        sample
+    }
+
+    /// Discard audio up to `sample`, so the next segment starts there.
+    ///
+    /// Skipped audio is decoded and dropped, and segment positions stay
+    /// relative to the start of the file. Skipping past the end leaves the
+    /// decoder exhausted.
+    pub fn skip_to(&mut self, sample: usize) -> Result<()> {
+        while self.samples_emitted < sample {
+            if self.buffer.is_empty() {
+                if self.eof {
+                    break;
+                }
+                self.decode_next_packet()?;
+                continue;
+            }
+            let skip = (sample - self.samples_emitted).min(self.buffer.len());
+            self.buffer.drain(..skip);
+            self.samples_emitted += skip;
+        }
+        Ok(())
+    }
+
     /// Yield the next segment of decoded audio.
     ///
     /// # Arguments
@@ -173,12 +379,11 @@ impl StreamingDecoder {
 
         // Build the segment
         let take_samples = segment_samples.min(self.buffer.len());
-        let mut samples = self.buffer[..take_samples].to_vec();
+        let mut samples = Vec::with_capacity(segment_samples);
+        samples.extend_from_slice(&self.buffer[..take_samples]);
 
         // Zero-pad if needed (for final segment)
-        if samples.len() < segment_samples {
-            samples.resize(segment_samples, 0.0);
-        }
+        samples.resize(segment_samples, 0.0);
 
         let start_sample = self.samples_emitted;
 
@@ -201,6 +406,78 @@ impl StreamingDecoder {
         }))
     }
 
+    /// Carry on decoding from `next` after the current source's last packet.
+    fn switch_to(&mut self, next: Self) {
+        tracing::debug!(
+            "Continuing into {} at sample position {}",
+            next.path.display(),
+            self.samples_emitted + self.buffer.len()
+        );
+        self.format = next.format;
+        self.decoder = next.decoder;
+        self.track_id = next.track_id;
+        self.channels = next.channels;
+        self.time_base = next.time_base;
+        self.path = next.path;
+        self.buffer.extend(next.buffer);
+        self.skipped_packets += next.skipped_packets;
+    }
+
+    /// Reopen the network stream, retrying with a growing delay.
+    fn reconnect(&mut self) -> Result<()> {
+        use crate::constants::stream::{
+            MAX_RECONNECT_ATTEMPTS, MAX_RECONNECT_DELAY_SECS, RECONNECT_DELAY_SECS,
+        };
+
+        let Some(url) = self.source_url.clone() else {
+            return Ok(());
+        };
+        let mut delay = RECONNECT_DELAY_SECS;
+        let mut attempts = 0;
+        loop {
+            tracing::warn!(
+                "Stream {url} interrupted at {:.1}s, reconnecting in {delay}s",
+                self.position_secs()
+            );
+            std::thread::sleep(std::time::Duration::from_secs(delay));
+            match Self::connect(&url) {
+                Ok((next, _)) if next.sample_rate == self.sample_rate => {
+                    tracing::info!("Reconnected to {url}");
+                    self.switch_to(next);
+                    return Ok(());
+                }
+                Ok((next, _)) => {
+                    return Err(Error::AudioStream {
+                        url,
+                        reason: format!(
+                            "sample rate changed from {} Hz to {} Hz after reconnecting",
+                            self.sample_rate, next.sample_rate
+                        ),
+                    });
+                }
+                Err(e) => {
+                    attempts += 1;
+                    if attempts >= MAX_RECONNECT_ATTEMPTS {
+                        return Err(Error::AudioStream {
+                            url,
+                            reason: format!("gave up after {attempts} reconnection attempts: {e}"),
+                        });
+                    }
+                    tracing::debug!("Reconnection attempt {attempts} failed: {e}");
+                    delay = (delay * 2).min(MAX_RECONNECT_DELAY_SECS);
+                }
+            }
+        }
+    }
+
+    /// Seconds of audio decoded so far; the recording's duration once all
+    /// segments have been read.
+    pub fn position_secs(&self) -> f64 {
+        #[allow(clippy::cast_precision_loss)]
+        let samples = (self.samples_emitted + self.buffer.len()) as f64;
+        samples / f64::from(self.sample_rate)
+    }
+
     /// Decode the next packet and append samples to buffer.
     fn decode_next_packet(&mut self) -> Result<()> {
         let packet = match self.format.next_packet() {
@@ -212,7 +489,20 @@ impl StreamingDecoder {
                     "EOF reached at sample position {} (actual end of file)",
                     self.samples_emitted
                 );
-                self.eof = true;
+                if let Some(next) = self.continuation.take() {
+                    self.continuation_start = Some(self.samples_emitted + self.buffer.len());
+                    self.switch_to(*next);
+                } else if self.source_url.is_some() {
+                    self.reconnect()?;
+                } else {
+                    self.eof = true;
+                }
+                return Ok(());
+            }
+            // A dropped connection is reopened like a finished stream
+            Err(symphonia::core::errors::Error::IoError(e)) if self.source_url.is_some() => {
+                tracing::debug!("Stream read failed: {e}");
+                self.reconnect()?;
                 return Ok(());
             }
             Err(e) => {
@@ -232,15 +522,31 @@ impl StreamingDecoder {
             return Ok(());
         }
 
-        let decoded = self
-            .decoder
-            .decode(&packet)
-            .map_err(|e| Error::AudioDecode {
-                path: self.path.clone(),
-                source: Box::new(e),
-            })?;
+        let decoded = match self.decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // Corrupt packet: skip it and keep decoding the rest of the stream
+            Err(symphonia::core::errors::Error::DecodeError(reason)) => {
+                tracing::debug!(
+                    "Skipping corrupt packet at sample position {}: {}",
+                    self.samples_emitted,
+                    reason
+                );
+                self.skipped_packets += 1;
+                return Ok(());
+            }
+            Err(e) => {
+                return Err(Error::AudioDecode {
+                    path: self.path.clone(),
+                    source: Box::new(e),
+                });
+            }
+        };
 
-        append_samples(&decoded, self.channels, &mut self.buffer);
+        let start = self.buffer.len();
+        append_samples(&decoded, self.channels, self.channel, &mut self.buffer);
+        if let Some(filter) = &mut self.filter {
+            filter.process(&mut self.buffer[start..]);
+        }
         Ok(())
     }
 }
@@ -336,7 +642,7 @@ pub fn decode_audio_file(path: &Path) -> Result<DecodedAudio> {
             source: Box::new(e),
         })?;
 
-        append_samples(&decoded, channels, &mut samples);
+        append_samples(&decoded, channels, None, &mut samples);
     }
 
     #[allow(clippy::cast_precision_loss)]
@@ -350,11 +656,24 @@ pub fn decode_audio_file(path: &Path) -> Result<DecodedAudio> {
 }
 
 /// Append decoded samples to the output buffer, converting to mono.
-fn append_samples(buffer: &AudioBufferRef, channels: usize, output: &mut Vec<f32>) {
+///
+/// With `channel` (0-based), only that channel is taken; otherwise all
+/// channels are mixed. A channel the buffer lacks falls back to the mix.
+fn append_samples(
+    buffer: &AudioBufferRef,
+    channels: usize,
+    channel: Option<usize>,
+    output: &mut Vec<f32>,
+) {
+    let single = match channel {
+        Some(channel) if channel < channels => Some(channel),
+        _ if channels == 1 => Some(0),
+        _ => None,
+    };
     match buffer {
         AudioBufferRef::F32(buf) => {
-            if channels == 1 {
-                output.extend(buf.chan(0));
+            if let Some(channel) = single {
+                output.extend(buf.chan(channel));
             } else {
                 // Mix to mono
                 let frames = buf.frames();
@@ -370,8 +689,8 @@ fn append_samples(buffer: &AudioBufferRef, channels: usize, output: &mut Vec<f32
         }
         AudioBufferRef::S16(buf) => {
             const I16_NORM: f32 = 32768.0;
-            if channels == 1 {
-                output.extend(buf.chan(0).iter().map(|&s| f32::from(s) / I16_NORM));
+            if let Some(channel) = single {
+                output.extend(buf.chan(channel).iter().map(|&s| f32::from(s) / I16_NORM));
             } else {
                 let frames = buf.frames();
                 for i in 0..frames {
@@ -386,9 +705,9 @@ fn append_samples(buffer: &AudioBufferRef, channels: usize, output: &mut Vec<f32
         }
         AudioBufferRef::S32(buf) => {
             const I32_NORM: f32 = 2_147_483_648.0;
-            if channels == 1 {
+            if let Some(channel) = single {
                 #[allow(clippy::cast_precision_loss)]
-                output.extend(buf.chan(0).iter().map(|&s| s as f32 / I32_NORM));
+                output.extend(buf.chan(channel).iter().map(|&s| s as f32 / I32_NORM));
             } else {
                 let frames = buf.frames();
                 for i in 0..frames {
@@ -471,8 +790,32 @@ pub fn get_audio_duration(path: &Path) -> Result<Option<f64>> {
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
+    use std::path::PathBuf;
+
+    /// Write a 3-second, 16 kHz mono recording of a synthetic call to `dir`.
+    fn fixture(dir: &Path) -> PathBuf {
+        let path = dir.join("fixture.wav");
+        let wav =
+            crate::clipper::encode_wav(&crate::audio::test_signal(16_000, 48_000), 16_000).unwrap();
+        std::fs::write(&path, wav).unwrap();
+        path
+    }
+
+    /// 10-second, 16 kHz mono MPEG-2 Layer III file of silent frames whose
+    /// bitrate varies from 8 to 64 kbps, without a seek table.
+    const VBR_FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data/fixtures/vbr.mp3");
+
+    /// Read `decoder` to the end and return the position reached.
+    ///
+    /// After a seek this equals the file length only if the position the
+    /// seek reported was exact.
+    fn end_position(decoder: &mut StreamingDecoder) -> usize {
+        while decoder.next_segment(4096, 0).unwrap().is_some() {}
+        decoder.samples_emitted
+    }
 
     #[test]
     fn test_raw_segment_construction() {
@@ -484,4 +827,140 @@ mod tests {
         assert_eq!(segment.samples.len(), 3);
         assert_eq!(segment.start_sample, 0);
     }
+
+    #[test]
+    fn test_skip_to_keeps_absolute_positions() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = fixture(dir.path());
+        let mut decoder = StreamingDecoder::open(&fixture).unwrap();
+        decoder.skip_to(16_000).unwrap();
+
+        let segment = decoder.next_segment(16_000, 0).unwrap().unwrap();
+        assert_eq!(segment.start_sample, 16_000);
+        let segment = decoder.next_segment(16_000, 0).unwrap().unwrap();
+        assert_eq!(segment.start_sample, 32_000);
+        assert!(decoder.next_segment(16_000, 0).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_skip_to_past_end_exhausts_decoder() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = fixture(dir.path());
+        let mut decoder = StreamingDecoder::open(&fixture).unwrap();
+        decoder.skip_to(1_000_000).unwrap();
+        assert!(decoder.next_segment(16_000, 0).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_continuation_completes_segment_across_boundary() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = fixture(dir.path());
+        let mut decoder = StreamingDecoder::open(&fixture).unwrap();
+        decoder
+            .continue_with(StreamingDecoder::open(&fixture).unwrap())
+            .unwrap();
+        let mut reference = StreamingDecoder::open(&fixture).unwrap();
+        let head = reference.next_segment(12_000, 0).unwrap().unwrap();
+
+        decoder.skip_to(40_000).unwrap();
+        assert_eq!(decoder.continuation_start(), None);
+        let segment = decoder.next_segment(20_000, 0).unwrap().unwrap();
+        assert_eq!(segment.start_sample, 40_000);
+        assert_eq!(decoder.continuation_start(), Some(48_000));
+        // The tail comes from the next recording instead of zero padding
+        assert_eq!(segment.samples[8_000..], head.samples[..]);
+
+        // Positions keep counting through the second recording
+        assert_eq!(end_position(&mut decoder), 96_000);
+    }
+
+    #[test]
+    fn test_continuation_rejects_other_sample_rate() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = fixture(dir.path());
+        let other = dir.path().join("48k.wav");
+        let wav = crate::clipper::encode_wav(&[0.0; 4_800], 48_000).unwrap();
+        std::fs::write(&other, wav).unwrap();
+
+        let mut decoder = StreamingDecoder::open(&fixture).unwrap();
+        let other = StreamingDecoder::open(&other).unwrap();
+        assert!(matches!(
+            decoder.continue_with(other),
+            Err(Error::StitchSampleRate {
+                sample_rate: 48_000,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_select_channel_rejects_missing_channel() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = fixture(dir.path());
+
+        let mut decoder = StreamingDecoder::open(&fixture).unwrap();
+        assert!(decoder.select_channel(1).is_ok());
+        assert!(matches!(
+            decoder.select_channel(2),
+            Err(Error::AudioChannel {
+                channel: 2,
+                channels: 1,
+                ..
+            })
+        ));
+        assert!(matches!(
+            decoder.select_channel(0),
+            Err(Error::AudioChannel { .. })
+        ));
+    }
+
+    #[test]
+    fn test_seek_wav_starts_at_requested_sample() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = fixture(dir.path());
+        let mut decoder = StreamingDecoder::open(&fixture).unwrap();
+        decoder.seek(1.0).unwrap();
+
+        let segment = decoder.next_segment(16_000, 0).unwrap().unwrap();
+        assert_eq!(segment.start_sample, 16_000);
+
+        // Seeking backwards works too
+        decoder.seek(0.5).unwrap();
+        let segment = decoder.next_segment(16_000, 0).unwrap().unwrap();
+        assert_eq!(segment.start_sample, 8_000);
+    }
+
+    #[test]
+    fn test_seek_matches_sequential_decode_for_wav() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = fixture(dir.path());
+        let mut sequential = StreamingDecoder::open(&fixture).unwrap();
+        sequential.skip_to(20_000).unwrap();
+        let expected = sequential.next_segment(8_000, 0).unwrap().unwrap();
+
+        let mut seeking = StreamingDecoder::open(&fixture).unwrap();
+        seeking.seek(1.25).unwrap();
+        let segment = seeking.next_segment(8_000, 0).unwrap().unwrap();
+        assert_eq!(segment.start_sample, expected.start_sample);
+        assert_eq!(segment.samples, expected.samples);
+    }
+
+    #[test]
+    fn test_seek_vbr_mp3_is_sample_accurate() {
+        let mut decoder = StreamingDecoder::open(Path::new(VBR_FIXTURE)).unwrap();
+        let total = end_position(&mut decoder);
+        assert!(total >= 150_000, "decoded only {total} samples");
+
+        // Without a seek table the bitrate varies too much for an estimate to
+        // land on the right frame, so positions must come from the stream
+        for seconds in [0.75, 4.0, 7.3] {
+            let mut decoder = StreamingDecoder::open(Path::new(VBR_FIXTURE)).unwrap();
+            decoder.seek(seconds).unwrap();
+            let first = decoder.next_segment(4096, 0).unwrap().unwrap();
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let target = (seconds * 16_000.0).round() as usize;
+            assert_eq!(first.start_sample, target);
+            assert_eq!(end_position(&mut decoder), total, "seek to {seconds}s");
+        }
+    }
 }
@@ -0,0 +1,211 @@
+//! High-pass and band-pass pre-filtering.
+//!
+//! Wind and traffic rumble sit far below most bird song, and out-of-band
+//! noise can push the model towards false detections. `--highpass 250` cuts
+//! everything below 250 Hz, and `--bandpass 150:12000` also cuts above
+//! 12 kHz, like BirdNET-Analyzer's `fmin`/`fmax`. Each edge is a
+//! second-order Butterworth section applied to the decoded audio at its
+//! source sample rate, before it is split into segments.
+
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+use std::fmt;
+
+/// Cutoff frequencies of a pre-filter, in Hz.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FilterSpec {
+    /// Frequencies below this are attenuated.
+    pub low: Option<f32>,
+    /// Frequencies above this are attenuated.
+    pub high: Option<f32>,
+}
+
+impl FilterSpec {
+    /// Parse a high-pass cutoff such as `250`.
+    pub fn parse_highpass(s: &str) -> Result<Self, String> {
+        Ok(Self {
+            low: Some(parse_frequency(s)?),
+            high: None,
+        })
+    }
+
+    /// Parse a band such as `150:12000`.
+    pub fn parse_bandpass(s: &str) -> Result<Self, String> {
+        let (low, high) = s
+            .split_once(':')
+            .ok_or_else(|| format!("'{s}' is not a band (expected LOW:HIGH in Hz)"))?;
+        let (low, high) = (parse_frequency(low)?, parse_frequency(high)?);
+        if low >= high {
+            return Err(format!(
+                "band low edge ({low} Hz) must be below its high edge ({high} Hz)"
+            ));
+        }
+        Ok(Self {
+            low: Some(low),
+            high: Some(high),
+        })
+    }
+}
+
+impl fmt::Display for FilterSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.low, self.high) {
+            (Some(low), Some(high)) => write!(f, "band-pass {low}-{high} Hz"),
+            (Some(low), None) => write!(f, "high-pass {low} Hz"),
+            (None, Some(high)) => write!(f, "low-pass {high} Hz"),
+            (None, None) => f.write_str("none"),
+        }
+    }
+}
+
+/// Parse a positive frequency in Hz.
+fn parse_frequency(s: &str) -> Result<f32, String> {
+    s.trim()
+        .parse::<f32>()
+        .ok()
+        .filter(|hz| hz.is_finite() && *hz > 0.0)
+        .ok_or_else(|| format!("'{s}' is not a frequency in Hz"))
+}
+
+/// Second-order IIR section (transposed direct form II).
+#[derive(Debug, Clone)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    z: [f64; 2],
+}
+
+impl Biquad {
+    /// Butterworth high-pass (`high_pass`) or low-pass section at `cutoff` Hz.
+    fn butterworth(cutoff: f64, sample_rate: f64, high_pass: bool) -> Self {
+        let w0 = 2.0 * PI * cutoff / sample_rate;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * FRAC_1_SQRT_2);
+        let a0 = 1.0 + alpha;
+        let b = if high_pass {
+            [(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0]
+        } else {
+            [(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0]
+        };
+        Self {
+            b: b.map(|b| b / a0),
+            a: [-2.0 * cos / a0, (1.0 - alpha) / a0],
+            z: [0.0; 2],
+        }
+    }
+
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0].mul_add(x, self.z[0]);
+        self.z[0] = self.b[1].mul_add(x, self.a[0].mul_add(-y, self.z[1]));
+        self.z[1] = self.b[2].mul_add(x, -self.a[1] * y);
+        y
+    }
+}
+
+/// Pre-filter running over a continuous stream of samples.
+#[derive(Debug, Clone)]
+pub struct AudioFilter {
+    sections: Vec<Biquad>,
+}
+
+impl AudioFilter {
+    /// Filter for audio at `sample_rate` Hz.
+    ///
+    /// A cutoff at or above the Nyquist frequency has no effect and is left
+    /// out.
+    pub fn new(spec: FilterSpec, sample_rate: u32) -> Self {
+        let rate = f64::from(sample_rate);
+        let nyquist = rate / 2.0;
+        let mut sections = Vec::new();
+        if let Some(low) = spec.low.map(f64::from).filter(|&hz| hz < nyquist) {
+            sections.push(Biquad::butterworth(low, rate, true));
+        }
+        if let Some(high) = spec.high.map(f64::from).filter(|&hz| hz < nyquist) {
+            sections.push(Biquad::butterworth(high, rate, false));
+        }
+        Self { sections }
+    }
+
+    /// Filter `samples` in place, continuing from the previous call.
+    pub fn process(&mut self, samples: &mut [f32]) {
+        for sample in samples {
+            let mut value = f64::from(*sample);
+            for section in &mut self.sections {
+                value = section.process(value);
+            }
+            #[allow(clippy::cast_possible_truncation)]
+            {
+                *sample = value as f32;
+            }
+        }
+    }
+
+    /// Forget the filter's history, e.g. after seeking.
+    pub fn reset(&mut self) {
+        for section in &mut self.sections {
+            section.z = [0.0; 2];
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    /// RMS level of a sine at `hz` after filtering, skipping the settling time.
+    fn filtered_rms(spec: FilterSpec, hz: f32) -> f32 {
+        let rate = 48_000;
+        #[allow(clippy::cast_precision_loss)]
+        let mut samples: Vec<f32> = (0..rate)
+            .map(|i| (2.0 * std::f32::consts::PI * hz * i as f32 / rate as f32).sin())
+            .collect();
+        AudioFilter::new(spec, rate).process(&mut samples);
+        let tail = &samples[samples.len() / 2..];
+        #[allow(clippy::cast_precision_loss)]
+        let mean_square = tail.iter().map(|s| s * s).sum::<f32>() / tail.len() as f32;
+        mean_square.sqrt()
+    }
+
+    #[test]
+    fn test_parse_filter_spec() {
+        assert_eq!(
+            FilterSpec::parse_highpass("250").unwrap(),
+            FilterSpec {
+                low: Some(250.0),
+                high: None
+            }
+        );
+        assert_eq!(
+            FilterSpec::parse_bandpass("150:12000").unwrap(),
+            FilterSpec {
+                low: Some(150.0),
+                high: Some(12000.0)
+            }
+        );
+        assert!(FilterSpec::parse_highpass("-5").is_err());
+        assert!(FilterSpec::parse_bandpass("12000:150").is_err());
+        assert!(FilterSpec::parse_bandpass("150").is_err());
+    }
+
+    #[test]
+    fn test_highpass_removes_rumble() {
+        let spec = FilterSpec::parse_highpass("250").unwrap();
+        // A sine has an RMS of 1/sqrt(2)
+        assert!(filtered_rms(spec, 30.0) < 0.02);
+        assert!((filtered_rms(spec, 3000.0) - std::f32::consts::FRAC_1_SQRT_2).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_bandpass_removes_both_ends() {
+        let spec = FilterSpec::parse_bandpass("150:8000").unwrap();
+        assert!(filtered_rms(spec, 20.0) < 0.02);
+        assert!(filtered_rms(spec, 20_000.0) < 0.05);
+        assert!(filtered_rms(spec, 2000.0) > 0.69);
+    }
+
+    #[test]
+    fn test_cutoff_above_nyquist_is_ignored() {
+        let spec = FilterSpec::parse_bandpass("100:30000").unwrap();
+        assert_eq!(AudioFilter::new(spec, 48_000).sections.len(), 1);
+    }
+}
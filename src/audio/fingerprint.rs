@@ -0,0 +1,95 @@
+//! Short perceptual fingerprints of audio segments.
+//!
+//! The fingerprint is a 64-bit difference hash over the RMS envelope: the
+//! segment is split into 65 equal frames and each bit records whether the
+//! energy rises from one frame to the next. It is independent of gain and
+//! survives lossy re-encoding, so the same audio produces the same (or a very
+//! close) hash across runs, stations, and extracted clips.
+
+use crate::constants::fingerprint::BITS;
+
+/// Compute the fingerprint of a segment as 16 lowercase hex digits.
+///
+/// Segments shorter than the frame count produce an all-zero fingerprint.
+pub fn segment_fingerprint(samples: &[f32]) -> String {
+    format!("{:016x}", fingerprint_bits(samples))
+}
+
+/// Compute the raw 64-bit fingerprint of a segment.
+pub fn fingerprint_bits(samples: &[f32]) -> u64 {
+    let frames = BITS + 1;
+    let frame_len = samples.len() / frames;
+    if frame_len == 0 {
+        return 0;
+    }
+
+    let energies: Vec<f64> = samples
+        .chunks_exact(frame_len)
+        .take(frames)
+        .map(|frame| frame.iter().map(|&s| f64::from(s) * f64::from(s)).sum())
+        .collect();
+
+    energies
+        .windows(2)
+        .enumerate()
+        .fold(0u64, |hash, (i, pair)| {
+            if pair[1] > pair[0] {
+                hash | (1 << i)
+            } else {
+                hash
+            }
+        })
+}
+
+/// Number of differing bits between two fingerprints.
+///
+/// Returns `None` if either string is not a valid hex fingerprint.
+pub fn fingerprint_distance(a: &str, b: &str) -> Option<u32> {
+    let a = u64::from_str_radix(a, 16).ok()?;
+    let b = u64::from_str_radix(b, 16).ok()?;
+    Some((a ^ b).count_ones())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rising-and-falling envelope with some structure.
+    fn test_signal(len: usize) -> Vec<f32> {
+        #[allow(clippy::cast_precision_loss)]
+        (0..len)
+            .map(|i| {
+                let t = i as f32 / len as f32;
+                (t * 40.0).sin() * (t * 7.0).sin().abs()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fingerprint_format() {
+        let fp = segment_fingerprint(&test_signal(144_000));
+        assert_eq!(fp.len(), 16);
+        assert!(fp.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_gain() {
+        let signal = test_signal(144_000);
+        let quiet: Vec<f32> = signal.iter().map(|s| s * 0.25).collect();
+        assert_eq!(segment_fingerprint(&signal), segment_fingerprint(&quiet));
+    }
+
+    #[test]
+    fn test_fingerprint_distance() {
+        assert_eq!(
+            fingerprint_distance("0000000000000000", "000000000000000f"),
+            Some(4)
+        );
+        assert_eq!(fingerprint_distance("zz", "00"), None);
+    }
+
+    #[test]
+    fn test_short_segment_fingerprint() {
+        assert_eq!(segment_fingerprint(&[0.5; 10]), "0000000000000000");
+    }
+}
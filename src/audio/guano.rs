@@ -0,0 +1,143 @@
+//! GUANO metadata (`guan` WAV chunks).
+//!
+//! GUANO is the metadata format of most bioacoustic recorders: UTF-8 text in
+//! a `guan` RIFF chunk, one `Key: value` field per line. Keys may carry a
+//! vendor namespace (`WA|Firmware Version`); birda reads the standard keys
+//! and ignores the rest. See <https://github.com/riggsd/guano-spec>.
+
+use std::path::Path;
+
+use super::RecorderMetadata;
+use super::recorder::valid_position;
+use super::riff::read_chunk;
+use crate::error::Result;
+
+/// Read GUANO metadata from a WAV file.
+///
+/// Returns `Ok(None)` for files that are not RIFF/WAVE or have no `guan`
+/// chunk.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read.
+pub fn read_guano(path: &Path) -> Result<Option<RecorderMetadata>> {
+    Ok(read_chunk(path, b"guan")?.map(|data| parse_guano_chunk(&data)))
+}
+
+/// Parse the body of a `guan` chunk.
+pub(super) fn parse_guano_chunk(data: &[u8]) -> RecorderMetadata {
+    parse_guano(&String::from_utf8_lossy(data))
+}
+
+/// Parse the fields of a `guan` chunk, ignoring unknown and malformed ones.
+fn parse_guano(text: &str) -> RecorderMetadata {
+    let mut metadata = RecorderMetadata::default();
+    let mut make = None;
+    for line in text.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().trim_end_matches('\0').trim();
+        if value.is_empty() {
+            continue;
+        }
+        let text = || Some(value.to_string());
+        match key.trim() {
+            "Make" => make = text(),
+            "Model" => metadata.model = text(),
+            "Serial" => metadata.serial = text(),
+            "Firmware Version" => metadata.firmware = text(),
+            "Timestamp" => metadata.timestamp = text(),
+            "Temperature Int" => metadata.temperature_int = value.parse().ok(),
+            "Temperature Ext" => metadata.temperature_ext = value.parse().ok(),
+            "Loc Position" => {
+                if let Some((lat, lon)) = parse_position(value) {
+                    metadata.latitude = Some(lat);
+                    metadata.longitude = Some(lon);
+                }
+            }
+            _ => {}
+        }
+    }
+    // `Model` alone is often ambiguous ("Mini"), so name the maker with it
+    metadata.model = match (make, metadata.model) {
+        (Some(make), Some(model)) if !model.starts_with(&make) => Some(format!("{make} {model}")),
+        (make, model) => model.or(make),
+    };
+    metadata
+}
+
+/// Parse a `Loc Position` such as `60.17020 24.93840` (WGS84 decimal
+/// degrees, latitude first).
+fn parse_position(value: &str) -> Option<(f64, f64)> {
+    let mut parts = value.split([' ', ',']).filter(|part| !part.is_empty());
+    let lat: f64 = parts.next()?.parse().ok()?;
+    let lon: f64 = parts.next()?.parse().ok()?;
+    valid_position(lat, lon)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_guano() {
+        let metadata = parse_guano(
+            "GUANO|Version: 1.0\n\
+             Make: Open Acoustic Devices\n\
+             Model: AudioMoth\n\
+             Serial: 24F319045F6A3A1B\n\
+             Firmware Version: 1.8.1\n\
+             Timestamp: 2024-05-01T05:00:00+03:00\n\
+             Loc Position: 60.17020 24.93840\n\
+             Temperature Int: 12.5\n\
+             OAD|Battery Voltage: 4.2\n\
+             malformed line\n",
+        );
+        assert_eq!(
+            metadata.model.as_deref(),
+            Some("Open Acoustic Devices AudioMoth")
+        );
+        assert_eq!(metadata.serial.as_deref(), Some("24F319045F6A3A1B"));
+        assert_eq!(metadata.firmware.as_deref(), Some("1.8.1"));
+        assert_eq!(
+            metadata.timestamp.as_deref(),
+            Some("2024-05-01T05:00:00+03:00")
+        );
+        assert_eq!(metadata.location(), Some((60.1702, 24.9384)));
+        assert_eq!(metadata.temperature(), Some(12.5));
+    }
+
+    #[test]
+    fn test_invalid_position_is_ignored() {
+        assert!(
+            parse_guano("Loc Position: 95.0 24.9\n")
+                .location()
+                .is_none()
+        );
+        assert!(parse_guano("Loc Position: 60.1\n").location().is_none());
+    }
+
+    #[test]
+    fn test_read_guano() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("20240501_050000.WAV");
+
+        let guano = b"GUANO|Version: 1.0\nModel: Song Meter Mini\n";
+        let mut body = b"WAVE".to_vec();
+        body.extend(b"fmt ");
+        body.extend(16u32.to_le_bytes());
+        body.extend([0u8; 16]);
+        body.extend(b"guan");
+        body.extend(u32::try_from(guano.len()).unwrap().to_le_bytes());
+        body.extend(guano);
+        let mut bytes = b"RIFF".to_vec();
+        bytes.extend(u32::try_from(body.len()).unwrap().to_le_bytes());
+        bytes.extend(body);
+        std::fs::write(&path, bytes).unwrap();
+
+        let metadata = read_guano(&path).unwrap().unwrap();
+        assert_eq!(metadata.model.as_deref(), Some("Song Meter Mini"));
+    }
+}
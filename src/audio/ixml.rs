@@ -0,0 +1,60 @@
+//! Broadcast Wave (BWF) iXML metadata (`iXML` WAV chunks).
+//!
+//! Field recorders writing BWF files can store their GPS position in the
+//! iXML `LOCATION` block as `<LOCATION_GPS>latitude, longitude</LOCATION_GPS>`
+//! in decimal degrees. birda reads only the position.
+
+use std::path::Path;
+
+use super::RecorderMetadata;
+use super::recorder::valid_position;
+use super::riff::read_chunk;
+use crate::error::Result;
+
+/// Read the iXML position of a WAV file.
+///
+/// Returns `Ok(None)` for files that are not RIFF/WAVE or have no `iXML`
+/// chunk with a valid `LOCATION_GPS`.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read.
+pub fn read_ixml(path: &Path) -> Result<Option<RecorderMetadata>> {
+    Ok(read_chunk(path, b"iXML")?.and_then(|data| parse_ixml_chunk(&data)))
+}
+
+/// Parse the position in the body of an `iXML` chunk, if it has one.
+pub(super) fn parse_ixml_chunk(data: &[u8]) -> Option<RecorderMetadata> {
+    parse_location_gps(&String::from_utf8_lossy(data)).map(|(lat, lon)| RecorderMetadata {
+        latitude: Some(lat),
+        longitude: Some(lon),
+        ..RecorderMetadata::default()
+    })
+}
+
+/// Parse the `LOCATION_GPS` element of an iXML document.
+fn parse_location_gps(xml: &str) -> Option<(f64, f64)> {
+    let start = xml.find("<LOCATION_GPS>")? + "<LOCATION_GPS>".len();
+    let end = start + xml[start..].find("</LOCATION_GPS>")?;
+    let (lat, lon) = xml[start..end].split_once(',')?;
+    let lat: f64 = lat.trim().parse().ok()?;
+    let lon: f64 = lon.trim().parse().ok()?;
+    valid_position(lat, lon)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_location_gps() {
+        let xml = "<BWFXML><LOCATION><LOCATION_NAME>Pond</LOCATION_NAME>\
+                   <LOCATION_GPS>60.17020, -24.93840</LOCATION_GPS></LOCATION></BWFXML>";
+        assert_eq!(parse_location_gps(xml), Some((60.1702, -24.9384)));
+        assert_eq!(parse_location_gps("<BWFXML></BWFXML>"), None);
+        assert_eq!(
+            parse_location_gps("<LOCATION_GPS>200, 10</LOCATION_GPS>"),
+            None
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! Live audio capture for `birda listen`.
+//!
+//! Capturing from a sound device needs the `listen` feature (cpal). The
+//! rolling window that cuts the captured stream into analysis segments does
+//! not, so it is always built.
+
+/// Cuts a continuous sample stream into fixed-size, possibly overlapping windows.
+#[derive(Debug)]
+pub struct RollingWindow {
+    buffer: Vec<f32>,
+    window: usize,
+    hop: usize,
+    /// Stream position of the first buffered sample.
+    position: usize,
+}
+
+impl RollingWindow {
+    /// Create a window of `window` samples that advances by `hop` samples.
+    ///
+    /// `hop` is clamped to `1..=window`.
+    pub fn new(window: usize, hop: usize) -> Self {
+        Self {
+            buffer: Vec::with_capacity(window * 2),
+            window,
+            hop: hop.clamp(1, window.max(1)),
+            position: 0,
+        }
+    }
+
+    /// Append samples and return every window that became complete, with the
+    /// stream position of its first sample.
+    pub fn push(&mut self, samples: &[f32]) -> Vec<(usize, Vec<f32>)> {
+        self.buffer.extend_from_slice(samples);
+        let mut windows = Vec::new();
+        let mut offset = 0;
+        while self.buffer.len() - offset >= self.window {
+            windows.push((
+                self.position + offset,
+                self.buffer[offset..offset + self.window].to_vec(),
+            ));
+            offset += self.hop;
+        }
+        self.buffer.drain(..offset.min(self.buffer.len()));
+        self.position += offset;
+        windows
+    }
+}
+
+#[cfg(feature = "listen")]
+pub use imp::{LiveInput, list_input_devices};
+
+#[cfg(feature = "listen")]
+mod imp {
+    use crate::constants::listen::CAPTURE_QUEUE_BUFFERS;
+    use crate::error::{Error, Result};
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+    use cpal::{FromSample, SampleFormat, SizedSample};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::mpsc::{Receiver, SyncSender, TrySendError, sync_channel};
+
+    /// Names of the available input devices.
+    pub fn list_input_devices() -> Result<Vec<String>> {
+        let devices = cpal::default_host()
+            .input_devices()
+            .map_err(|e| device_error(&e))?;
+        Ok(devices.filter_map(|d| d.name().ok()).collect())
+    }
+
+    /// An open input stream delivering mono samples.
+    ///
+    /// Capture stops when the value is dropped.
+    pub struct LiveInput {
+        _stream: cpal::Stream,
+        receiver: Receiver<Vec<f32>>,
+        name: String,
+        sample_rate: u32,
+        dropped: Arc<AtomicUsize>,
+    }
+
+    impl LiveInput {
+        /// Open the input device called `name`, or the default input device.
+        pub fn open(name: Option<&str>) -> Result<Self> {
+            let host = cpal::default_host();
+            let device = match name {
+                Some(name) => host
+                    .input_devices()
+                    .map_err(|e| device_error(&e))?
+                    .find(|d| d.name().is_ok_and(|n| n == name))
+                    .ok_or_else(|| Error::AudioDevice {
+                        reason: format!(
+                            "no input device named '{name}' (see `birda listen --list-devices`)"
+                        ),
+                    })?,
+                None => host
+                    .default_input_device()
+                    .ok_or_else(|| Error::AudioDevice {
+                        reason: "no default input device".to_string(),
+                    })?,
+            };
+            let name = device.name().map_err(|e| device_error(&e))?;
+
+            let supported = device
+                .default_input_config()
+                .map_err(|e| device_error(&e))?;
+            let sample_format = supported.sample_format();
+            let config: cpal::StreamConfig = supported.into();
+            let channels = usize::from(config.channels);
+
+            let (sender, receiver) = sync_channel(CAPTURE_QUEUE_BUFFERS);
+            let dropped = Arc::new(AtomicUsize::new(0));
+            let stream = match sample_format {
+                SampleFormat::F32 => {
+                    build_stream::<f32>(&device, &config, channels, sender, &dropped)
+                }
+                SampleFormat::I16 => {
+                    build_stream::<i16>(&device, &config, channels, sender, &dropped)
+                }
+                SampleFormat::I32 => {
+                    build_stream::<i32>(&device, &config, channels, sender, &dropped)
+                }
+                SampleFormat::U16 => {
+                    build_stream::<u16>(&device, &config, channels, sender, &dropped)
+                }
+                other => {
+                    return Err(Error::AudioDevice {
+                        reason: format!("unsupported sample format {other} on '{name}'"),
+                    });
+                }
+            }?;
+            stream.play().map_err(|e| device_error(&e))?;
+
+            Ok(Self {
+                _stream: stream,
+                receiver,
+                name,
+                sample_rate: config.sample_rate.0,
+                dropped,
+            })
+        }
+
+        /// Device name.
+        pub fn name(&self) -> &str {
+            &self.name
+        }
+
+        /// Capture sample rate in Hz.
+        pub fn sample_rate(&self) -> u32 {
+            self.sample_rate
+        }
+
+        /// Wait for the next captured buffer; `None` once the stream has ended.
+        pub fn recv(&self) -> Option<Vec<f32>> {
+            self.receiver.recv().ok()
+        }
+
+        /// Number of buffers dropped because analysis fell behind, since the
+        /// last call.
+        pub fn take_dropped(&self) -> usize {
+            self.dropped.swap(0, Ordering::Relaxed)
+        }
+    }
+
+    /// Build an input stream that downmixes `T` samples to mono f32.
+    ///
+    /// The audio callback never blocks: when the queue is full the buffer is
+    /// dropped and counted.
+    fn build_stream<T>(
+        device: &cpal::Device,
+        config: &cpal::StreamConfig,
+        channels: usize,
+        sender: SyncSender<Vec<f32>>,
+        dropped: &Arc<AtomicUsize>,
+    ) -> Result<cpal::Stream>
+    where
+        T: SizedSample,
+        f32: FromSample<T>,
+    {
+        let dropped = Arc::clone(dropped);
+        #[allow(clippy::cast_precision_loss)]
+        let scale = 1.0 / channels.max(1) as f32;
+        device
+            .build_input_stream(
+                config,
+                move |data: &[T], _: &cpal::InputCallbackInfo| {
+                    let mono = data
+                        .chunks(channels.max(1))
+                        .map(|frame| {
+                            frame.iter().map(|&s| f32::from_sample(s)).sum::<f32>() * scale
+                        })
+                        .collect();
+                    if let Err(TrySendError::Full(_)) = sender.try_send(mono) {
+                        dropped.fetch_add(1, Ordering::Relaxed);
+                    }
+                },
+                |e| tracing::warn!("Audio input error: {e}"),
+                None,
+            )
+            .map_err(|e| device_error(&e))
+    }
+
+    fn device_error(e: &dyn std::fmt::Display) -> Error {
+        Error::AudioDevice {
+            reason: e.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rolling_window_without_overlap() {
+        let mut window = RollingWindow::new(4, 4);
+        assert!(window.push(&[0.0, 1.0, 2.0]).is_empty());
+
+        let windows = window.push(&[3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+        assert_eq!(windows.len(), 2);
+        assert_eq!(windows[0], (0, vec![0.0, 1.0, 2.0, 3.0]));
+        assert_eq!(windows[1], (4, vec![4.0, 5.0, 6.0, 7.0]));
+
+        let windows = window.push(&[9.0, 10.0, 11.0]);
+        assert_eq!(windows, vec![(8, vec![8.0, 9.0, 10.0, 11.0])]);
+    }
+
+    #[test]
+    fn test_rolling_window_with_overlap() {
+        let mut window = RollingWindow::new(4, 2);
+        let samples: Vec<f32> = (0..8u8).map(f32::from).collect();
+        let starts: Vec<usize> = window.push(&samples).iter().map(|(s, _)| *s).collect();
+        assert_eq!(starts, vec![0, 2, 4]);
+
+        // The overlapping tail is kept for the next window
+        let windows = window.push(&[8.0, 9.0]);
+        assert_eq!(windows, vec![(6, vec![6.0, 7.0, 8.0, 9.0])]);
+    }
+}
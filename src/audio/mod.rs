@@ -1,11 +1,36 @@
 //! Audio processing pipeline.
 
+mod channel;
 mod chunker;
 mod decode;
+mod filter;
+mod fingerprint;
+mod guano;
+mod ixml;
+mod live;
+mod network;
+mod recorder;
 mod resample;
+mod riff;
+mod synthetic;
+mod time_range;
+mod wamd;
 
-pub use chunker::{AudioChunk, chunk_audio};
+pub use channel::ChannelSelection;
+pub use chunker::{AudioChunk, SegmentSamples, chunk_audio};
 pub use decode::{
     DecodedAudio, RawSegment, StreamingDecoder, decode_audio_file, get_audio_duration,
 };
+pub use filter::{AudioFilter, FilterSpec};
+pub use fingerprint::{fingerprint_bits, fingerprint_distance, segment_fingerprint};
+pub use guano::read_guano;
+pub use ixml::read_ixml;
+pub use live::RollingWindow;
+#[cfg(feature = "listen")]
+pub use live::{LiveInput, list_input_devices};
+pub use network::{extension_for_content_type, is_stream_url};
+pub use recorder::{RecorderMetadata, read_recorder_metadata};
 pub use resample::{resample, resample_chunk};
+pub use synthetic::test_signal;
+pub use time_range::{TimeRange, parse_timestamp, split_input_range};
+pub use wamd::read_wamd;
@@ -0,0 +1,262 @@
+//! Network audio sources for streaming decode.
+//!
+//! HTTP(S) streams, including Icecast and Shoutcast radio, are downloaded on a
+//! background thread and read by the decoder as an unseekable byte stream.
+//! RTSP is not supported: its audio arrives as RTP packets, which Symphonia
+//! cannot demultiplex.
+
+use crate::constants::stream::{CONNECT_TIMEOUT_SECS, QUEUE_CHUNKS, READ_TIMEOUT_SECS};
+use crate::error::{Error, Result};
+use futures_util::StreamExt;
+use std::io::Read;
+use std::sync::Mutex;
+use std::sync::mpsc::{Receiver, sync_channel};
+use std::time::Duration;
+
+/// Chunks of a response body, or the error that ended it.
+type Chunk = std::io::Result<Vec<u8>>;
+
+/// Whether `input` names a network stream rather than a file.
+pub fn is_stream_url(input: &str) -> bool {
+    let lower = input.to_ascii_lowercase();
+    ["http://", "https://", "rtsp://", "rtsps://"]
+        .iter()
+        .any(|scheme| lower.starts_with(scheme))
+}
+
+/// An HTTP response body, read as it downloads.
+pub struct HttpSource {
+    chunks: Mutex<Receiver<Chunk>>,
+    pending: Vec<u8>,
+    offset: usize,
+    live: bool,
+}
+
+impl HttpSource {
+    /// Connect to `url`.
+    ///
+    /// Returns the source and a file extension hinting at the stream's format,
+    /// from the response's content type or else the URL.
+    pub fn connect(url: &str) -> Result<(Self, Option<String>)> {
+        if url.to_ascii_lowercase().starts_with("rtsp") {
+            return Err(stream_error(
+                url,
+                "RTSP is not supported; restream the audio over HTTP (e.g. with Icecast)",
+            ));
+        }
+
+        let (chunk_tx, chunk_rx) = sync_channel::<Chunk>(QUEUE_CHUNKS);
+        let (ready_tx, ready_rx) = sync_channel(1);
+        let target = url.to_string();
+        std::thread::spawn(move || {
+            let runtime = match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e.to_string()));
+                    return;
+                }
+            };
+            runtime.block_on(async move {
+                let response = match request(&target).await {
+                    Ok(response) => response,
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(e));
+                        return;
+                    }
+                };
+                let content_type = response
+                    .headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_string);
+                let live = response.content_length().is_none();
+                if ready_tx.send(Ok((content_type, live))).is_err() {
+                    return;
+                }
+
+                // Runs until the stream ends, fails or the decoder goes away
+                let mut body = response.bytes_stream();
+                while let Some(chunk) = body.next().await {
+                    let chunk = chunk
+                        .map(|bytes| bytes.to_vec())
+                        .map_err(std::io::Error::other);
+                    let failed = chunk.is_err();
+                    if chunk_tx.send(chunk).is_err() || failed {
+                        return;
+                    }
+                }
+            });
+        });
+
+        let (content_type, live) = ready_rx
+            .recv()
+            .map_err(|_| stream_error(url, "connection thread exited"))?
+            .map_err(|reason| stream_error(url, &reason))?;
+        let extension = content_type
+            .as_deref()
+            .and_then(extension_for_content_type)
+            .map(str::to_string)
+            .or_else(|| extension_from_url(url));
+
+        let mut source = Self::from_receiver(chunk_rx);
+        source.live = live;
+        Ok((source, extension))
+    }
+
+    fn from_receiver(chunks: Receiver<Chunk>) -> Self {
+        Self {
+            chunks: Mutex::new(chunks),
+            pending: Vec::new(),
+            offset: 0,
+            live: false,
+        }
+    }
+
+    /// Whether the response has no length, as with live radio streams.
+    ///
+    /// A download of known length is a file, which ends rather than drops.
+    pub fn is_live(&self) -> bool {
+        self.live
+    }
+}
+
+impl Read for HttpSource {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.offset >= self.pending.len() {
+            let chunks = self
+                .chunks
+                .get_mut()
+                .map_err(|_| std::io::Error::other("stream reader poisoned"))?;
+            match chunks.recv() {
+                Ok(chunk) => {
+                    self.pending = chunk?;
+                    self.offset = 0;
+                }
+                // The download finished
+                Err(_) => return Ok(0),
+            }
+        }
+        let len = buf.len().min(self.pending.len() - self.offset);
+        buf[..len].copy_from_slice(&self.pending[self.offset..self.offset + len]);
+        self.offset += len;
+        Ok(len)
+    }
+}
+
+/// Send the request and check the response status.
+async fn request(url: &str) -> std::result::Result<reqwest::Response, String> {
+    let client = reqwest::Client::builder()
+        .user_agent(format!("birda/{}", env!("CARGO_PKG_VERSION")))
+        .connect_timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS))
+        .read_timeout(Duration::from_secs(READ_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client.get(url).send().await.map_err(|e| e.to_string())?;
+    response.error_for_status().map_err(|e| e.to_string())
+}
+
+/// File extension of the format a stream's content type names.
+pub fn extension_for_content_type(content_type: &str) -> Option<&'static str> {
+    let mime = content_type.split(';').next()?.trim().to_ascii_lowercase();
+    match mime.as_str() {
+        "audio/mpeg" | "audio/mp3" => Some("mp3"),
+        "audio/aac" | "audio/aacp" | "audio/x-aac" => Some("aac"),
+        "audio/flac" | "audio/x-flac" => Some("flac"),
+        "audio/wav" | "audio/wave" | "audio/x-wav" => Some("wav"),
+        _ => None,
+    }
+}
+
+/// File extension at the end of a URL's path, if any.
+fn extension_from_url(url: &str) -> Option<String> {
+    let path = url.split(['?', '#']).next()?;
+    let name = path
+        .split_once("://")?
+        .1
+        .split_once('/')?
+        .1
+        .rsplit('/')
+        .next()?;
+    let (_, extension) = name.rsplit_once('.')?;
+    (!extension.is_empty() && extension.chars().all(|c| c.is_ascii_alphanumeric()))
+        .then(|| extension.to_ascii_lowercase())
+}
+
+fn stream_error(url: &str, reason: &str) -> Error {
+    Error::AudioStream {
+        url: url.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_stream_url() {
+        assert!(is_stream_url("http://radio.example/live"));
+        assert!(is_stream_url("HTTPS://radio.example/live.mp3"));
+        assert!(is_stream_url("rtsp://camera.local/audio"));
+        assert!(!is_stream_url("recordings/http_capture.wav"));
+        assert!(!is_stream_url("/data/site1"));
+    }
+
+    #[test]
+    fn test_extension_hints() {
+        assert_eq!(extension_for_content_type("audio/mpeg"), Some("mp3"));
+        assert_eq!(
+            extension_for_content_type("audio/aacp; charset=binary"),
+            Some("aac")
+        );
+        assert_eq!(extension_for_content_type("application/ogg"), None);
+
+        assert_eq!(
+            extension_from_url("https://radio.example/stream/dawn.MP3?token=1"),
+            Some("mp3".to_string())
+        );
+        assert_eq!(extension_from_url("http://radio.example/live"), None);
+        assert_eq!(extension_from_url("http://radio.example"), None);
+    }
+
+    #[test]
+    fn test_http_source_reads_across_chunks() {
+        let (tx, rx) = sync_channel(4);
+        tx.send(Ok(vec![1, 2, 3])).unwrap();
+        tx.send(Ok(vec![4, 5])).unwrap();
+        drop(tx);
+
+        let mut bytes = Vec::new();
+        HttpSource::from_receiver(rx)
+            .read_to_end(&mut bytes)
+            .unwrap();
+        assert_eq!(bytes, vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_http_source_reports_dropped_connection() {
+        let (tx, rx) = sync_channel(4);
+        tx.send(Ok(vec![1, 2])).unwrap();
+        tx.send(Err(std::io::Error::from(
+            std::io::ErrorKind::ConnectionReset,
+        )))
+        .unwrap();
+
+        let mut source = HttpSource::from_receiver(rx);
+        let mut buf = [0; 8];
+        assert_eq!(source.read(&mut buf).unwrap(), 2);
+        assert!(source.read(&mut buf).is_err());
+    }
+
+    #[test]
+    fn test_rtsp_is_rejected() {
+        let err = HttpSource::connect("rtsp://camera.local/audio")
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("RTSP is not supported"));
+    }
+}
@@ -0,0 +1,191 @@
+//! Recorder metadata embedded in WAV files.
+//!
+//! Song Meter recorders write a `wamd` chunk and most other bioacoustic
+//! recorders (AudioMoth, Titley, Pettersson, ...) a GUANO `guan` chunk; newer
+//! Song Meter firmware writes both. Field recorders writing Broadcast Wave
+//! files can record their position in an `iXML` chunk.
+//! [`read_recorder_metadata`] merges them, preferring WAMD, then GUANO values.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+
+use super::guano::parse_guano_chunk;
+use super::ixml::parse_ixml_chunk;
+use super::riff::read_chunks;
+use super::wamd::parse_wamd;
+
+/// Recorder settings and location read from a recording.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecorderMetadata {
+    /// Recorder model (e.g. `SM4`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Recorder serial number.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial: Option<String>,
+    /// Recorder firmware version.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub firmware: Option<String>,
+    /// Recording start as written by the recorder.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+    /// Recording schedule program.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    /// Schedule state the recording was made in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runstate: Option<String>,
+    /// Microphone type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub microphone: Option<String>,
+    /// Microphone gain/sensitivity setting.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gain: Option<String>,
+    /// Internal temperature in degrees Celsius.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature_int: Option<f32>,
+    /// External sensor temperature in degrees Celsius.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature_ext: Option<f32>,
+    /// Recording latitude in decimal degrees.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latitude: Option<f64>,
+    /// Recording longitude in decimal degrees.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longitude: Option<f64>,
+}
+
+impl RecorderMetadata {
+    /// Temperature at the recorder, preferring the external sensor.
+    pub fn temperature(&self) -> Option<f32> {
+        self.temperature_ext.or(self.temperature_int)
+    }
+
+    /// Recording location as (latitude, longitude), if both are known.
+    pub fn location(&self) -> Option<(f64, f64)> {
+        self.latitude.zip(self.longitude)
+    }
+
+    /// Fill the values missing here from `other`.
+    #[must_use]
+    pub fn or(self, other: Self) -> Self {
+        Self {
+            model: self.model.or(other.model),
+            serial: self.serial.or(other.serial),
+            firmware: self.firmware.or(other.firmware),
+            timestamp: self.timestamp.or(other.timestamp),
+            schedule: self.schedule.or(other.schedule),
+            runstate: self.runstate.or(other.runstate),
+            microphone: self.microphone.or(other.microphone),
+            gain: self.gain.or(other.gain),
+            temperature_int: self.temperature_int.or(other.temperature_int),
+            temperature_ext: self.temperature_ext.or(other.temperature_ext),
+            latitude: self.latitude.or(other.latitude),
+            longitude: self.longitude.or(other.longitude),
+        }
+    }
+
+    /// Stamp `detections` with the recorder columns, and with the recording
+    /// location where they have none.
+    pub fn apply(&self, detections: &mut [crate::output::Detection]) {
+        for detection in detections {
+            detection.metadata.recorder_model.clone_from(&self.model);
+            detection.metadata.recorder_serial.clone_from(&self.serial);
+            detection.metadata.recorder_gain.clone_from(&self.gain);
+            detection.metadata.recorder_temperature = self.temperature();
+            detection
+                .metadata
+                .recorder_schedule
+                .clone_from(&self.schedule);
+            if let Some((lat, lon)) = self.location()
+                && detection.metadata.lat.is_none()
+            {
+                detection.metadata.lat = Some(lat);
+                detection.metadata.lon = Some(lon);
+            }
+        }
+    }
+}
+
+/// Read the WAMD, GUANO and BWF iXML metadata of a WAV file.
+///
+/// The chunks are collected in one pass over the file. Returns `Ok(None)`
+/// for files with none of them.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read.
+pub fn read_recorder_metadata(path: &Path) -> Result<Option<RecorderMetadata>> {
+    let [wamd, guano, ixml] = read_chunks(path, [b"wamd", b"guan", b"iXML"])?;
+    Ok([
+        wamd.map(|data| parse_wamd(&data)),
+        guano.map(|data| parse_guano_chunk(&data)),
+        ixml.and_then(|data| parse_ixml_chunk(&data)),
+    ]
+    .into_iter()
+    .flatten()
+    .reduce(RecorderMetadata::or))
+}
+
+/// `(latitude, longitude)` if both are valid decimal degrees.
+pub(super) fn valid_position(lat: f64, lon: f64) -> Option<(f64, f64)> {
+    ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon)).then_some((lat, lon))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_recorder_metadata_merges_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("S4A01234_20240501_050000.wav");
+
+        let mut wamd = 1u16.to_le_bytes().to_vec();
+        wamd.extend(3u32.to_le_bytes());
+        wamd.extend(b"SM4");
+        let guano = b"GUANO|Version: 1.0\nLoc Position: 60.17 24.94\n";
+        let mut body = b"WAVE".to_vec();
+        for (id, data) in [
+            (b"wamd", &wamd[..]),
+            (b"data", &[0u8; 4][..]),
+            (b"guan", &guano[..]),
+        ] {
+            body.extend(id);
+            body.extend(u32::try_from(data.len()).unwrap().to_le_bytes());
+            body.extend(data);
+            if data.len() % 2 == 1 {
+                body.push(0);
+            }
+        }
+        let mut bytes = b"RIFF".to_vec();
+        bytes.extend(u32::try_from(body.len()).unwrap().to_le_bytes());
+        bytes.extend(body);
+        std::fs::write(&path, bytes).unwrap();
+
+        let metadata = read_recorder_metadata(&path).unwrap().unwrap();
+        assert_eq!(metadata.model.as_deref(), Some("SM4"));
+        assert_eq!(metadata.location(), Some((60.17, 24.94)));
+    }
+
+    #[test]
+    fn test_or_prefers_own_values() {
+        let wamd = RecorderMetadata {
+            model: Some("SM4".to_string()),
+            ..RecorderMetadata::default()
+        };
+        let guano = RecorderMetadata {
+            model: Some("Song Meter SM4".to_string()),
+            latitude: Some(60.17),
+            longitude: Some(24.94),
+            ..RecorderMetadata::default()
+        };
+        let merged = wamd.or(guano);
+        assert_eq!(merged.model.as_deref(), Some("SM4"));
+        assert_eq!(merged.location(), Some((60.17, 24.94)));
+    }
+}
@@ -0,0 +1,77 @@
+//! RIFF/WAVE chunk lookup for recorder metadata.
+//!
+//! Only chunk headers are read while searching, so finding the metadata
+//! chunks costs a few small reads per recording even when they follow the
+//! audio data.
+
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::constants::recorder::MAX_METADATA_CHUNK;
+use crate::error::Result;
+
+/// Read the body of the first chunk named `id` in a WAV file.
+///
+/// Returns `Ok(None)` for files that are not RIFF/WAVE, have no such chunk,
+/// or whose chunk is larger than [`MAX_METADATA_CHUNK`] or truncated.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read.
+pub(super) fn read_chunk(path: &Path, id: &[u8; 4]) -> Result<Option<Vec<u8>>> {
+    let [chunk] = read_chunks(path, [id])?;
+    Ok(chunk)
+}
+
+/// Read the bodies of the first chunks named `ids` in a WAV file, in one
+/// pass over its chunk headers.
+///
+/// Each body is `None` as for [`read_chunk`].
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read.
+pub(super) fn read_chunks<const N: usize>(
+    path: &Path,
+    ids: [&[u8; 4]; N],
+) -> Result<[Option<Vec<u8>>; N]> {
+    let mut chunks = [const { None }; N];
+    let mut reader = BufReader::new(File::open(path)?);
+
+    let mut riff = [0u8; 12];
+    if reader.read_exact(&mut riff).is_err() || &riff[0..4] != b"RIFF" || &riff[8..12] != b"WAVE" {
+        return Ok(chunks);
+    }
+
+    let mut seen = [false; N];
+    let mut header = [0u8; 8];
+    while !seen.iter().all(|&seen| seen) && reader.read_exact(&mut header).is_ok() {
+        let size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+        let wanted = ids
+            .iter()
+            .zip(&seen)
+            .position(|(id, &seen)| !seen && header[0..4] == id[..]);
+        if let Some(index) = wanted {
+            seen[index] = true;
+            if size <= MAX_METADATA_CHUNK {
+                // The size is untrusted: read through `take` so a corrupt
+                // header cannot allocate more than the file holds
+                let mut data = Vec::new();
+                reader
+                    .by_ref()
+                    .take(u64::from(size))
+                    .read_to_end(&mut data)?;
+                if u64::try_from(data.len()).ok() != Some(u64::from(size)) {
+                    break;
+                }
+                chunks[index] = Some(data);
+                reader.seek(SeekFrom::Current(i64::from(size % 2)))?;
+                continue;
+            }
+        }
+        // Chunks are padded to an even length
+        reader.seek(SeekFrom::Current(i64::from(size) + i64::from(size % 2)))?;
+    }
+    Ok(chunks)
+}
@@ -0,0 +1,230 @@
+//! Time ranges for analyzing part of a recording.
+//!
+//! A range is given on the command line as `--from`/`--to` or appended to an
+//! input path as `file.wav@600-1500`. Times are `HH:MM:SS`, `MM:SS` or plain
+//! seconds, each optionally with a fractional part.
+
+use std::path::{Path, PathBuf};
+
+/// Part of a recording to analyze, in seconds from the start of the file.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TimeRange {
+    /// Start of the range.
+    pub start: f64,
+    /// End of the range; `None` analyzes to the end of the file.
+    pub end: Option<f64>,
+}
+
+impl TimeRange {
+    /// Build a range, checking that `end` lies after `start`.
+    pub fn new(start: Option<f64>, end: Option<f64>) -> Result<Self, String> {
+        let start = start.unwrap_or(0.0);
+        if let Some(end) = end
+            && end <= start
+        {
+            return Err(format!(
+                "range end ({end}s) must be after its start ({start}s)"
+            ));
+        }
+        Ok(Self { start, end })
+    }
+
+    /// Parse a `START-END` range, where either side may be empty.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let (start, end) = s
+            .split_once('-')
+            .ok_or_else(|| format!("'{s}' is not a range (expected START-END)"))?;
+        let parse_side = |side: &str| {
+            let side = side.trim();
+            if side.is_empty() {
+                Ok(None)
+            } else {
+                parse_timestamp(side).map(Some)
+            }
+        };
+        Self::new(parse_side(start)?, parse_side(end)?)
+    }
+
+    /// First sample of the range at `sample_rate`.
+    pub fn start_sample(&self, sample_rate: u32) -> usize {
+        seconds_to_samples(self.start, sample_rate)
+    }
+
+    /// Sample at which the range ends at `sample_rate`, if bounded.
+    pub fn end_sample(&self, sample_rate: u32) -> Option<usize> {
+        self.end.map(|end| seconds_to_samples(end, sample_rate))
+    }
+
+    /// Length of the range within a recording of `duration` seconds.
+    pub fn duration_within(&self, duration: f64) -> f64 {
+        let end = self.end.map_or(duration, |end| end.min(duration));
+        (end - self.start).max(0.0)
+    }
+}
+
+impl std::fmt::Display for TimeRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-", format_timestamp(self.start))?;
+        if let Some(end) = self.end {
+            write!(f, "{}", format_timestamp(end))?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse a time as `HH:MM:SS`, `MM:SS` or seconds.
+pub fn parse_timestamp(s: &str) -> Result<f64, String> {
+    let invalid = || format!("'{s}' is not a valid time (expected HH:MM:SS, MM:SS or seconds)");
+
+    let parts: Vec<&str> = s.trim().split(':').collect();
+    if parts.len() > 3 {
+        return Err(invalid());
+    }
+    let mut seconds = 0.0;
+    for (i, part) in parts.iter().enumerate() {
+        let last = i == parts.len() - 1;
+        let value: f64 = part.parse().map_err(|_| invalid())?;
+        // Minutes and seconds after a colon stay below 60
+        if !value.is_finite() || value < 0.0 || (i > 0 && value >= 60.0) {
+            return Err(invalid());
+        }
+        // Only the seconds field may be fractional
+        if !last && value.fract() > 0.0 {
+            return Err(invalid());
+        }
+        seconds = seconds.mul_add(60.0, value);
+    }
+    Ok(seconds)
+}
+
+/// Split a `path@START-END` input into the path and its range.
+///
+/// The suffix is only treated as a range when the full path does not exist
+/// and the suffix contains `-`, so files whose names contain `@` keep working.
+pub fn split_input_range(input: &Path) -> Result<(PathBuf, Option<TimeRange>), String> {
+    if input.exists() {
+        return Ok((input.to_path_buf(), None));
+    }
+    let Some((path, range)) = input
+        .to_str()
+        .and_then(|s| s.rsplit_once('@'))
+        .filter(|(_, range)| range.contains('-'))
+    else {
+        return Ok((input.to_path_buf(), None));
+    };
+    let range = TimeRange::parse(range).map_err(|e| format!("{}: {e}", input.display()))?;
+    Ok((PathBuf::from(path), Some(range)))
+}
+
+/// Format seconds as `HH:MM:SS`, keeping any fractional part.
+fn format_timestamp(seconds: f64) -> String {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let whole = seconds as u64;
+    #[allow(clippy::cast_precision_loss)]
+    let fraction = seconds - whole as f64;
+    let base = format!(
+        "{:02}:{:02}:{:02}",
+        whole / 3600,
+        whole / 60 % 60,
+        whole % 60
+    );
+    if fraction > 0.0 {
+        let fraction = format!("{fraction:.3}");
+        format!(
+            "{base}{}",
+            fraction.trim_start_matches('0').trim_end_matches('0')
+        )
+    } else {
+        base
+    }
+}
+
+/// Convert seconds to a sample count at `sample_rate`.
+fn seconds_to_samples(seconds: f64, sample_rate: u32) -> usize {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let samples = (seconds * f64::from(sample_rate)).round() as usize;
+    samples
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timestamp_forms() {
+        assert!((parse_timestamp("00:10:00").unwrap() - 600.0).abs() < f64::EPSILON);
+        assert!((parse_timestamp("1:30").unwrap() - 90.0).abs() < f64::EPSILON);
+        assert!((parse_timestamp("1500").unwrap() - 1500.0).abs() < f64::EPSILON);
+        assert!((parse_timestamp("01:02:03.5").unwrap() - 3723.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_parse_timestamp_rejects_invalid() {
+        for s in ["", "abc", "1:60", "1:2:3:4", "-5", "1.5:00", "inf"] {
+            assert!(parse_timestamp(s).is_err(), "{s} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_time_range_parse() {
+        let range = TimeRange::parse("600-1500").unwrap();
+        assert_eq!(
+            range,
+            TimeRange {
+                start: 600.0,
+                end: Some(1500.0)
+            }
+        );
+        assert_eq!(TimeRange::parse("10:00-").unwrap().end, None);
+        assert!(TimeRange::parse("1500-600").is_err());
+        assert!(TimeRange::parse("600").is_err());
+    }
+
+    #[test]
+    fn test_time_range_samples_and_duration() {
+        let range = TimeRange::new(Some(1.5), Some(4.0)).unwrap();
+        assert_eq!(range.start_sample(48_000), 72_000);
+        assert_eq!(range.end_sample(48_000), Some(192_000));
+        assert!((range.duration_within(10.0) - 2.5).abs() < f64::EPSILON);
+        assert!((range.duration_within(3.0) - 1.5).abs() < f64::EPSILON);
+        assert!(range.duration_within(1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_time_range_display() {
+        let range = TimeRange::new(Some(600.0), Some(1500.5)).unwrap();
+        assert_eq!(range.to_string(), "00:10:00-00:25:00.5");
+        assert_eq!(
+            TimeRange::new(Some(90.0), None).unwrap().to_string(),
+            "00:01:30-"
+        );
+    }
+
+    #[test]
+    fn test_split_input_range() {
+        let (path, range) = split_input_range(Path::new("/no/such/rec.wav@600-1500")).unwrap();
+        assert_eq!(path, PathBuf::from("/no/such/rec.wav"));
+        assert_eq!(range.unwrap().end, Some(1500.0));
+
+        let (path, range) = split_input_range(Path::new("/no/such/rec.wav")).unwrap();
+        assert_eq!(path, PathBuf::from("/no/such/rec.wav"));
+        assert!(range.is_none());
+
+        let (path, range) = split_input_range(Path::new("/no/such/rec@home.wav")).unwrap();
+        assert_eq!(path, PathBuf::from("/no/such/rec@home.wav"));
+        assert!(range.is_none());
+
+        assert!(split_input_range(Path::new("/no/such/rec.wav@ab-cd")).is_err());
+    }
+
+    #[test]
+    fn test_split_input_range_keeps_existing_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("site@1-2.wav");
+        std::fs::write(&file, b"").unwrap();
+        let (path, range) = split_input_range(&file).unwrap();
+        assert_eq!(path, file);
+        assert!(range.is_none());
+    }
+}
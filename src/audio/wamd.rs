@@ -0,0 +1,207 @@
+//! Wildlife Acoustics Song Meter metadata (`wamd` WAV chunks).
+//!
+//! Song Meter recorders store their settings in a `wamd` RIFF chunk made of
+//! sub-chunks, each a little-endian `u16` id and `u32` length followed by the
+//! value.
+
+use std::path::Path;
+
+use super::RecorderMetadata;
+use super::recorder::valid_position;
+use super::riff::read_chunk;
+use crate::error::Result;
+
+/// Sub-chunk ids of the values birda uses.
+mod id {
+    pub const MODEL: u16 = 0x01;
+    pub const SERIAL: u16 = 0x02;
+    pub const FIRMWARE: u16 = 0x03;
+    pub const TIMESTAMP: u16 = 0x05;
+    pub const GPS_FIRST: u16 = 0x06;
+    pub const PROGRAM: u16 = 0x10;
+    pub const RUNSTATE: u16 = 0x11;
+    pub const MICROPHONE: u16 = 0x12;
+    pub const SENSITIVITY: u16 = 0x13;
+    pub const TEMP_INT: u16 = 0x14;
+    pub const TEMP_EXT: u16 = 0x15;
+}
+
+/// Read Song Meter metadata from a WAV file.
+///
+/// Returns `Ok(None)` for files that are not RIFF/WAVE or have no `wamd`
+/// chunk.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read.
+pub fn read_wamd(path: &Path) -> Result<Option<RecorderMetadata>> {
+    Ok(read_chunk(path, b"wamd")?.map(|data| parse_wamd(&data)))
+}
+
+/// Parse the sub-chunks of a `wamd` chunk, ignoring unknown and truncated
+/// ones.
+pub(super) fn parse_wamd(mut data: &[u8]) -> RecorderMetadata {
+    let mut metadata = RecorderMetadata::default();
+    while data.len() >= 6 {
+        let id = u16::from_le_bytes([data[0], data[1]]);
+        #[allow(clippy::cast_possible_truncation)]
+        let len = u32::from_le_bytes([data[2], data[3], data[4], data[5]]) as usize;
+        let Some(value) = data.get(6..6 + len) else {
+            break;
+        };
+        data = &data[6 + len..];
+
+        let text = || {
+            Some(
+                String::from_utf8_lossy(value)
+                    .trim_end_matches('\0')
+                    .trim()
+                    .to_string(),
+            )
+            .filter(|s| !s.is_empty())
+        };
+        match id {
+            id::MODEL => metadata.model = text(),
+            id::SERIAL => metadata.serial = text(),
+            id::FIRMWARE => metadata.firmware = text(),
+            id::TIMESTAMP => metadata.timestamp = text(),
+            id::GPS_FIRST => {
+                if let Some((lat, lon)) = text().and_then(|t| parse_gps(&t)) {
+                    metadata.latitude = Some(lat);
+                    metadata.longitude = Some(lon);
+                }
+            }
+            id::PROGRAM => metadata.schedule = text(),
+            id::RUNSTATE => metadata.runstate = text(),
+            id::MICROPHONE => metadata.microphone = text(),
+            id::SENSITIVITY => metadata.gain = text(),
+            id::TEMP_INT => metadata.temperature_int = text().and_then(|t| parse_celsius(&t)),
+            id::TEMP_EXT => metadata.temperature_ext = text().and_then(|t| parse_celsius(&t)),
+            _ => {}
+        }
+    }
+    metadata
+}
+
+/// Parse a GPS fix such as `WGS84,60.17020,N,24.93840,E,12` into signed
+/// decimal degrees, or `None` if it is out of range.
+fn parse_gps(text: &str) -> Option<(f64, f64)> {
+    let fields: Vec<&str> = text.split(',').map(str::trim).collect();
+    let [_datum, lat, lat_hemisphere, lon, lon_hemisphere, ..] = fields.as_slice() else {
+        return None;
+    };
+    let signed = |value: &str, hemisphere: &str, negative: &str| {
+        let value: f64 = value.parse().ok()?;
+        Some(if hemisphere.eq_ignore_ascii_case(negative) {
+            -value
+        } else {
+            value
+        })
+    };
+    valid_position(
+        signed(lat, lat_hemisphere, "S")?,
+        signed(lon, lon_hemisphere, "W")?,
+    )
+}
+
+/// Parse a temperature such as `21.5C` or `21.5`.
+fn parse_celsius(text: &str) -> Option<f32> {
+    text.trim_end_matches(['C', 'c', '°', ' ']).parse().ok()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn subchunk(id: u16, value: &[u8]) -> Vec<u8> {
+        let mut bytes = id.to_le_bytes().to_vec();
+        bytes.extend(u32::try_from(value.len()).unwrap().to_le_bytes());
+        bytes.extend(value);
+        bytes
+    }
+
+    fn chunk(name: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut bytes = name.to_vec();
+        bytes.extend(u32::try_from(data.len()).unwrap().to_le_bytes());
+        bytes.extend(data);
+        if data.len() % 2 == 1 {
+            bytes.push(0);
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_parse_wamd() {
+        let mut data = subchunk(0x00, &1u16.to_le_bytes());
+        data.extend(subchunk(id::MODEL, b"SM4\0"));
+        data.extend(subchunk(id::SERIAL, b"S4A01234"));
+        data.extend(subchunk(id::SENSITIVITY, b"16dB"));
+        data.extend(subchunk(id::TEMP_INT, b"21.5C"));
+        data.extend(subchunk(id::PROGRAM, b"Sunrise to sunset"));
+        // Truncated sub-chunk at the end is ignored
+        data.extend([0x15, 0x00, 0xff, 0x00, 0x00, 0x00]);
+
+        let metadata = parse_wamd(&data);
+        assert_eq!(metadata.model.as_deref(), Some("SM4"));
+        assert_eq!(metadata.serial.as_deref(), Some("S4A01234"));
+        assert_eq!(metadata.gain.as_deref(), Some("16dB"));
+        assert_eq!(metadata.temperature(), Some(21.5));
+        assert_eq!(metadata.schedule.as_deref(), Some("Sunrise to sunset"));
+        assert!(metadata.temperature_ext.is_none());
+    }
+
+    #[test]
+    fn test_parse_gps() {
+        assert_eq!(
+            parse_gps("WGS84,60.17020,N,24.93840,W,12"),
+            Some((60.1702, -24.9384))
+        );
+        assert_eq!(parse_gps("WGS84,95.0,N,24.9,E"), None);
+        assert_eq!(parse_gps("WGS84,60.1,S,190.0,E"), None);
+        assert_eq!(parse_gps("WGS84,60.1,N"), None);
+    }
+
+    #[test]
+    fn test_read_wamd_skips_other_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("S4A01234_20240501_050000.wav");
+
+        let mut body = b"WAVE".to_vec();
+        body.extend(chunk(b"fmt ", &[0u8; 16]));
+        body.extend(chunk(b"junk", &[0u8; 3]));
+        body.extend(chunk(b"wamd", &subchunk(id::MODEL, b"SM4")));
+        body.extend(chunk(b"data", &[0u8; 4]));
+        let mut bytes = b"RIFF".to_vec();
+        bytes.extend(u32::try_from(body.len()).unwrap().to_le_bytes());
+        bytes.extend(body);
+        std::fs::write(&path, bytes).unwrap();
+
+        let metadata = read_wamd(&path).unwrap().unwrap();
+        assert_eq!(metadata.model.as_deref(), Some("SM4"));
+
+        let plain = dir.path().join("plain.wav");
+        std::fs::write(&plain, b"RIFF\x04\0\0\0WAVE").unwrap();
+        assert!(read_wamd(&plain).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_read_wamd_ignores_oversized_chunk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("corrupt.wav");
+
+        // A corrupt size larger than the file must not be allocated
+        let mut bytes = b"RIFF\x10\0\0\0WAVEwamd".to_vec();
+        bytes.extend(0xffff_fff0u32.to_le_bytes());
+        bytes.extend(subchunk(id::MODEL, b"SM4"));
+        std::fs::write(&path, bytes).unwrap();
+        assert!(read_wamd(&path).unwrap().is_none());
+
+        // Nor is a chunk that runs past the end of the file
+        let mut bytes = b"RIFF\x10\0\0\0WAVEwamd".to_vec();
+        bytes.extend(64u32.to_le_bytes());
+        bytes.extend(subchunk(id::MODEL, b"SM4"));
+        std::fs::write(&path, bytes).unwrap();
+        assert!(read_wamd(&path).unwrap().is_none());
+    }
+}
@@ -1,10 +1,25 @@
 //! CLI argument definitions.
 
-use crate::config::{ModelType, OutputFormat, OutputMode};
+use crate::config::{
+    ActivityFormat, ArenaExtendStrategy, BatchSize, DeadLetterMode, LowGpuMemAction, ModelType,
+    OutputFormat, OutputMode, ParquetLayout, SkipPolicy,
+};
+use crate::constants::examples::DEFAULT_DIR as DEFAULT_EXAMPLES_DIR;
+use crate::constants::registry::{DEFAULT_DOWNLOAD_CONNECTIONS, DEFAULT_TEST_PREDICTIONS};
+use crate::constants::runs::DEFAULT_LIST_LIMIT;
+use crate::constants::watch::DEFAULT_POLL_INTERVAL_SECS;
+use crate::output::LogEventLevel;
+use crate::utils::glob::Glob;
 use clap::{Args, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
+use super::bench::BenchArgs;
+use super::calibrate::CalibrateArgs;
 use super::clip::ClipArgs;
+use super::embed::EmbedArgs;
+use super::listen::ListenArgs;
+use super::report::{AuditArgs, ExportArgs, QueryArgs, ReportAction};
+use super::serve::ServeArgs;
 
 /// Sort order for species list.
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -33,6 +48,26 @@ pub struct Cli {
     #[arg(long, value_enum, global = true, env = "BIRDA_OUTPUT_MODE")]
     pub output_mode: Option<OutputMode>,
 
+    /// Warn about unknown keys in the config file instead of failing.
+    #[arg(long, global = true, env = "BIRDA_LENIENT_CONFIG")]
+    pub lenient_config: bool,
+
+    /// Lowest severity of diagnostics (including ONNX Runtime's) reported as
+    /// `log` events in json/ndjson output modes instead of as text on stderr.
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "LEVEL",
+        default_value_t = LogEventLevel::Warn,
+        env = "BIRDA_LOG_EVENTS"
+    )]
+    pub log_events: LogEventLevel,
+
+    /// Apply the settings of a `[profiles.<name>]` config section.
+    #[arg(long, global = true, value_name = "NAME", env = "BIRDA_PROFILE")]
+    pub profile: Option<String>,
+
     /// Common options for analysis.
     #[command(flatten)]
     pub analyze: AnalyzeArgs,
@@ -55,8 +90,69 @@ pub enum Command {
     },
     /// Show available execution providers (CPU, CUDA, etc.).
     Providers,
+    /// Run a generated recording through every pipeline stage and report
+    /// which stages pass.
+    Selftest {
+        /// Model name from configuration (default: `defaults.model`).
+        #[arg(short, long)]
+        model: Option<String>,
+    },
+    /// Describe the detection output formats.
+    Formats {
+        /// Formats action to perform.
+        #[command(subcommand)]
+        action: FormatsAction,
+    },
+    /// Inspect cached `TensorRT` engines.
+    Cache {
+        /// Cache action to perform.
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Download curated example recordings with their expected detections.
+    Examples {
+        /// Examples action to perform.
+        #[command(subcommand)]
+        action: ExamplesAction,
+
+        /// Example set catalog (JSON) to use instead of the bundled set.
+        #[arg(long, global = true, value_name = "FILE")]
+        catalog: Option<PathBuf>,
+    },
+    /// Show the log of past analysis runs.
+    Runs {
+        /// Runs action to perform.
+        #[command(subcommand)]
+        action: RunsAction,
+    },
+    /// Analyze audio files (the same as giving inputs without a command).
+    Analyze(AnalyzeCommandArgs),
+    /// Watch directories and analyze new recordings as they appear.
+    Watch(WatchArgs),
+    /// Extract per-segment embeddings for clustering or classifier training.
+    Embed(EmbedArgs),
+    /// Analyze live audio from a sound device and report detections as they happen.
+    Listen(ListenArgs),
+    /// Compare detections and runtime across overlaps on a sample of recordings.
+    Calibrate(CalibrateArgs),
+    /// Measure inference throughput and compare it with a saved baseline.
+    Bench(BenchArgs),
+    /// Serve an HTTP API that keeps the model loaded between requests.
+    Serve(ServeArgs),
     /// Extract audio clips from detection results.
     Clip(ClipArgs),
+    /// List existing results that are stale against the current configuration.
+    Audit(AuditArgs),
+    /// Run SQL over detection results in a DuckDB database or Parquet files.
+    Query(QueryArgs),
+    /// Write a sanitized copy of detection results for sharing.
+    Export(ExportArgs),
+    /// Generate aggregate reports from a directory of detection results.
+    Report {
+        /// Report to generate.
+        #[command(subcommand)]
+        action: ReportAction,
+    },
     /// Check for and install updates from GitHub.
     Update {
         /// Only check for updates, don't install.
@@ -118,17 +214,126 @@ pub enum Command {
 pub enum ConfigAction {
     /// Create default configuration file.
     Init,
-    /// Display current configuration.
-    Show,
+    /// Display current configuration, with secrets redacted.
+    Show {
+        /// Show passwords, tokens and URL credentials instead of redacting them.
+        #[arg(long)]
+        show_secrets: bool,
+    },
     /// Print configuration file path.
     Path,
-    /// Set a configuration value.
+    /// Set a configuration value, keeping comments in the file.
+    ///
+    /// The value is read as TOML (`0.3`, `true`, `["lat", "lon"]`) unless the
+    /// key holds a string. An empty value removes the key, like `unset`.
     Set {
-        /// Configuration key (dotted path, e.g., "defaults.model").
+        /// Configuration key (dotted path, e.g., "defaults.min_confidence").
         key: String,
         /// Value to set.
         value: String,
     },
+    /// Print a configuration value, including defaults.
+    Get {
+        /// Configuration key (dotted path, e.g., "inference.device").
+        key: String,
+        /// Show passwords, tokens and URL credentials instead of redacting them.
+        #[arg(long)]
+        show_secrets: bool,
+    },
+    /// Remove a configuration value, restoring its default.
+    Unset {
+        /// Configuration key (dotted path, e.g., "defaults.latitude").
+        key: String,
+    },
+    /// Check the configuration file and list every problem found.
+    ///
+    /// Reports unknown keys (with suggestions), out-of-range values, missing
+    /// files and invalid profiles. Exits with an error if any are found.
+    Validate,
+    /// Manage integration credentials, stored apart from the config file.
+    Credentials {
+        /// Credentials action to perform.
+        #[command(subcommand)]
+        action: CredentialsAction,
+    },
+}
+
+/// Credentials subcommand actions.
+#[derive(Debug, Clone, Subcommand)]
+pub enum CredentialsAction {
+    /// List the names of stored credentials (never their values).
+    List,
+    /// Store a credential, prompting for its value without echo (or reading
+    /// it from piped stdin).
+    ///
+    /// A value of the form `${VAR}` is read from the environment variable
+    /// `VAR` whenever the credential is used.
+    Set {
+        /// Credential key (dotted path, e.g., "mqtt.password").
+        key: String,
+    },
+    /// Remove a stored credential.
+    Unset {
+        /// Credential key (dotted path, e.g., "mqtt.password").
+        key: String,
+    },
+}
+
+/// Cache subcommand actions.
+#[derive(Debug, Clone, Subcommand)]
+pub enum CacheAction {
+    /// List cached `TensorRT` engines and the batch sizes they were built for.
+    List,
+}
+
+/// Runs subcommand actions.
+#[derive(Debug, Clone, Subcommand)]
+pub enum RunsAction {
+    /// List recent analysis runs, newest first.
+    List {
+        /// Number of runs to show.
+        #[arg(short = 'n', long, default_value_t = DEFAULT_LIST_LIMIT)]
+        limit: usize,
+    },
+    /// Show the full record of one run.
+    Show {
+        /// Run ID, or a unique prefix of it.
+        run_id: String,
+    },
+}
+
+/// Examples subcommand actions.
+#[derive(Debug, Clone, Subcommand)]
+pub enum ExamplesAction {
+    /// List the example recordings and the species expected in each.
+    List,
+    /// Download the example recordings into a directory.
+    Fetch {
+        /// Directory to download into.
+        #[arg(long, value_name = "DIR", default_value = DEFAULT_EXAMPLES_DIR)]
+        dir: PathBuf,
+
+        /// Download recordings again even if they are already present.
+        #[arg(long)]
+        force: bool,
+
+        /// Maximum number of files downloaded at once (1-16).
+        #[arg(long, value_name = "N", default_value_t = DEFAULT_DOWNLOAD_CONNECTIONS,
+              value_parser = parse_connections)]
+        connections: usize,
+    },
+}
+
+/// Formats subcommand actions.
+#[derive(Debug, Clone, Subcommand)]
+pub enum FormatsAction {
+    /// Show the column schema (names, types, units) and version of each
+    /// output format.
+    Describe {
+        /// Format to describe (default: all formats).
+        #[arg(value_enum)]
+        format: Option<OutputFormat>,
+    },
 }
 
 /// Models subcommand actions.
@@ -154,9 +359,19 @@ pub enum ModelsAction {
         /// Set as the default model.
         #[arg(long)]
         default: bool,
+        /// Update the model if one with this name is already configured,
+        /// keeping its other settings.
+        #[arg(long)]
+        update: bool,
     },
     /// Verify model files exist and are valid.
     Check,
+    /// Re-hash the files of an installed registry model and compare them with
+    /// the registry checksums and the lockfile.
+    Verify {
+        /// Model ID from the registry.
+        id: String,
+    },
     /// Show details for a specific model.
     Info {
         /// Model ID from registry or name from configuration.
@@ -164,6 +379,19 @@ pub enum ModelsAction {
         /// Show available languages (for registry models).
         #[arg(long)]
         languages: bool,
+        /// Load the configured model and measure its file size, memory use,
+        /// label count and batch context support.
+        #[arg(long, conflicts_with = "languages")]
+        footprint: bool,
+    },
+    /// Run a synthetic call through a configured model to verify inference
+    /// on the configured device.
+    Test {
+        /// Name of the model (as shown in `models list`).
+        id: String,
+        /// Number of top predictions to show.
+        #[arg(long, default_value_t = DEFAULT_TEST_PREDICTIONS)]
+        top: usize,
     },
     /// Remove a model from configuration.
     Remove {
@@ -173,6 +401,14 @@ pub enum ModelsAction {
         #[arg(long)]
         purge: bool,
     },
+    /// Move a model's files to another directory and update the configured
+    /// paths, e.g. when moving models to another disk.
+    Relocate {
+        /// Name of the model (as shown in `models list`).
+        id: String,
+        /// Directory to move the files into (created if missing).
+        new_dir: PathBuf,
+    },
     /// Install a model from the registry.
     Install {
         /// Model ID to install.
@@ -183,9 +419,72 @@ pub enum ModelsAction {
         /// Set as default model.
         #[arg(short, long)]
         default: bool,
+        /// Directory to install into (overrides `registry.models_dir`).
+        #[arg(long, value_name = "DIR")]
+        dest: Option<PathBuf>,
+        /// Allow changing a model version or files pinned in the lockfile.
+        #[arg(long)]
+        upgrade: bool,
+        /// Number of files to download concurrently.
+        #[arg(long, value_name = "N", default_value_t = DEFAULT_DOWNLOAD_CONNECTIONS,
+              value_parser = parse_connections)]
+        connections: usize,
+        /// Retries of a failed file download (overrides
+        /// `registry.download_retries`, default: 3).
+        #[arg(long, value_name = "N")]
+        retries: Option<u32>,
+        /// Seconds before the first retry, doubled per retry (overrides
+        /// `registry.retry_delay_secs`, default: 2).
+        #[arg(long, value_name = "SECS")]
+        retry_delay: Option<u64>,
+    },
+    /// Reinstall installed models that have a newer version in the registry.
+    Update {
+        /// Model ID to update (default: every installed model).
+        id: Option<String>,
+        /// Update without asking for confirmation.
+        #[arg(short, long)]
+        yes: bool,
+        /// Only list outdated models.
+        #[arg(long, conflicts_with = "yes")]
+        check: bool,
+        /// Number of files to download concurrently.
+        #[arg(long, value_name = "N", default_value_t = DEFAULT_DOWNLOAD_CONNECTIONS,
+              value_parser = parse_connections)]
+        connections: usize,
     },
 }
 
+/// Arguments for the analyze command.
+#[derive(Debug, Args)]
+pub struct AnalyzeCommandArgs {
+    /// Input files or directories to analyze.
+    #[arg(required_unless_present = "file_list")]
+    pub inputs: Vec<PathBuf>,
+
+    /// Analysis options.
+    #[command(flatten)]
+    pub analyze: AnalyzeArgs,
+}
+
+/// Arguments for the watch command.
+#[derive(Debug, Args)]
+pub struct WatchArgs {
+    /// Directories to watch (recursively) for new audio files.
+    #[arg(required = true)]
+    pub dirs: Vec<PathBuf>,
+
+    /// Seconds between directory scans. A file is analyzed once its size is
+    /// unchanged between two scans.
+    #[arg(long, value_name = "SECS", default_value_t = DEFAULT_POLL_INTERVAL_SECS,
+          value_parser = clap::value_parser!(u64).range(1..))]
+    pub poll_interval: u64,
+
+    /// Analysis options.
+    #[command(flatten)]
+    pub analyze: AnalyzeArgs,
+}
+
 /// Arguments for the analyze command.
 ///
 /// # Default Implementation
@@ -220,7 +519,7 @@ pub struct AnalyzeArgs {
     #[arg(long, value_name = "REGION")]
     pub bat: Option<crate::config::BatRegion>,
 
-    /// Output formats (comma-separated: csv,raven,audacity,kaleidoscope).
+    /// Output formats (comma-separated: csv,birdnet-csv,raven,audacity,kaleidoscope).
     #[arg(short, long, value_delimiter = ',', env = "BIRDA_FORMAT")]
     pub format: Option<Vec<OutputFormat>>,
 
@@ -236,18 +535,247 @@ pub struct AnalyzeArgs {
     #[arg(long, env = "BIRDA_OVERLAP")]
     pub overlap: Option<f32>,
 
-    /// Inference batch size (must be at least 1).
-    #[arg(short, long, value_parser = parse_batch_size, env = "BIRDA_BATCH_SIZE")]
-    pub batch_size: Option<usize>,
-
-    /// Generate combined results file.
+    /// Detection sensitivity (0.5-1.5), as in BirdNET-Analyzer. Higher values
+    /// raise low confidences, giving more detections.
+    #[arg(long, value_parser = parse_sensitivity, env = "BIRDA_SENSITIVITY")]
+    pub sensitivity: Option<f32>,
+
+    /// Inference batch size (at least 1), or `auto` to benchmark sizes that
+    /// fit in free GPU memory during warmup.
+    #[arg(short, long, value_parser = parse_batch_size_setting, env = "BIRDA_BATCH_SIZE")]
+    pub batch_size: Option<BatchSize>,
+
+    /// Analyze each file from this time on (HH:MM:SS, MM:SS or seconds).
+    /// Detection timestamps stay relative to the start of the file.
+    #[arg(long, alias = "analyze-start", value_name = "TIME", value_parser = parse_timestamp)]
+    pub from: Option<f64>,
+
+    /// Stop analyzing each file at this time (HH:MM:SS, MM:SS or seconds).
+    #[arg(long, alias = "analyze-end", value_name = "TIME", value_parser = parse_timestamp)]
+    pub to: Option<f64>,
+
+    /// Channels to analyze: a channel number (from 1), `all` to analyze
+    /// each channel separately, or `mix` (default) to mix them to mono.
+    ///
+    /// Detections from a selected channel record it in the `channel` column.
+    #[arg(long, value_name = "N|all|mix", value_parser = crate::audio::ChannelSelection::parse)]
+    pub channel: Option<crate::audio::ChannelSelection>,
+
+    /// Filter out audio below this frequency in Hz (e.g. 250 for wind and
+    /// traffic rumble) before analysis.
+    #[arg(long, value_name = "HZ", value_parser = crate::audio::FilterSpec::parse_highpass, conflicts_with = "bandpass")]
+    pub highpass: Option<crate::audio::FilterSpec>,
+
+    /// Filter out audio outside this band in Hz (e.g. 150:12000) before
+    /// analysis, like BirdNET-Analyzer's fmin/fmax.
+    #[arg(long, value_name = "LOW:HIGH", value_parser = crate::audio::FilterSpec::parse_bandpass)]
+    pub bandpass: Option<crate::audio::FilterSpec>,
+
+    /// Make analysis fail at POINT (decode, timeout, oom or write) once N
+    /// operations there have succeeded, to test error handling.
+    #[arg(
+        long,
+        value_name = "POINT[:N]",
+        env = "BIRDA_FAULT_INJECT",
+        hide = true,
+        value_parser = crate::pipeline::FaultSpec::parse
+    )]
+    pub fault_inject: Option<crate::pipeline::FaultSpec>,
+
+    /// Number of operations that succeed before the injected fault.
+    #[arg(long, value_name = "N", hide = true, requires = "fault_inject")]
+    pub fail_after: Option<usize>,
+
+    /// Read more inputs from a file, one path per line (`-` for stdin).
+    ///
+    /// Avoids command line length limits for batches of many files. Blank
+    /// lines and lines starting with `#` are skipped.
+    #[arg(long, value_name = "PATH")]
+    pub file_list: Option<PathBuf>,
+
+    /// Skip recordings shorter than this (HH:MM:SS, MM:SS or seconds).
+    #[arg(long, value_name = "TIME", value_parser = parse_timestamp)]
+    pub min_duration: Option<f64>,
+
+    /// Skip recordings longer than this (HH:MM:SS, MM:SS or seconds), such as
+    /// accidental multi-day recordings.
+    #[arg(long, value_name = "TIME", value_parser = parse_timestamp)]
+    pub max_duration: Option<f64>,
+
+    /// Skip files smaller than this (e.g. 1K, 10M), such as corrupted
+    /// zero-byte files.
+    #[arg(long, value_name = "SIZE", value_parser = parse_byte_size)]
+    pub min_size: Option<u64>,
+
+    /// Leave out input files and directories matching a glob pattern (repeatable).
+    ///
+    /// A pattern without `/` matches any file or directory name (`calibration`,
+    /// `*_noise.wav`); one with `/` matches the path below the input directory
+    /// (`site1/**/reference`).
+    #[arg(long, value_name = "PATTERN", value_parser = Glob::new)]
+    pub exclude: Vec<Glob>,
+
+    /// Treat consecutive recordings (by file name timestamp) in a directory as
+    /// one continuous stream, so calls spanning a file boundary are analyzed once.
+    #[arg(long, conflicts_with_all = ["from", "to", "bat"])]
+    pub stitch: bool,
+
+    /// Analyze a random share of the input files, e.g. 5% (for pilot runs
+    /// over large archives).
+    #[arg(long, value_name = "PERCENT", value_parser = parse_sample_percent,
+          conflicts_with_all = ["sample_n", "stitch"])]
+    pub sample: Option<f64>,
+
+    /// Analyze a random sample of this many input files.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..),
+          conflicts_with = "stitch")]
+    pub sample_n: Option<u64>,
+
+    /// Seed selecting the --sample/--sample-n files (default: random). The
+    /// seed is recorded in the run manifest, so a sample can be repeated.
+    #[arg(long, value_name = "SEED")]
+    pub sample_seed: Option<u64>,
+
+    /// Also write combined results across all analyzed files.
     #[arg(long)]
     pub combine: bool,
 
-    /// Reprocess files even if output exists.
+    /// Append to existing combined results instead of replacing them (CSV rows,
+    /// new Parquet part files), e.g. for repeated or continuous runs.
+    #[arg(long, requires = "combine")]
+    pub append: bool,
+
+    /// Write an HTML summary of the run (species, confidence histograms,
+    /// per-file statistics and timelines) to this file.
+    #[arg(long, value_name = "PATH")]
+    pub report: Option<PathBuf>,
+
+    /// Suggest minimum confidence thresholds, overall and per species, from
+    /// the run's confidence distributions.
+    #[arg(long)]
+    pub suggest_thresholds: bool,
+
+    /// Also write all detections of the run into a DuckDB database (table `detections`).
+    #[arg(long, value_name = "PATH")]
+    pub duckdb: Option<PathBuf>,
+
+    /// Write Parquet as one file per recording or as a hive-partitioned dataset.
+    #[arg(long, value_name = "LAYOUT", value_enum)]
+    pub parquet_layout: Option<ParquetLayout>,
+
+    /// Decimal places for confidences in all result formats (default: 4 in
+    /// text formats, full precision in JSON).
+    #[arg(long, value_name = "N", value_parser = parse_decimal_places)]
+    pub confidence_decimals: Option<usize>,
+
+    /// Decimal places for start and end times in all result formats
+    /// (default: 1 in text formats, full precision in JSON).
+    #[arg(long, value_name = "N", value_parser = parse_decimal_places)]
+    pub time_decimals: Option<usize>,
+
+    /// Roll CSV/Parquet outputs over into numbered parts after this many detections.
+    #[arg(long, value_name = "ROWS", value_parser = parse_row_count)]
+    pub max_rows_per_file: Option<u64>,
+
+    /// Roll CSV/Parquet outputs over into numbered parts at about this size (e.g. 100M, 2G).
+    #[arg(long, value_name = "SIZE", value_parser = parse_byte_size)]
+    pub max_file_size: Option<u64>,
+
+    /// Write a per-minute activity summary (segments above the energy threshold,
+    /// detections, species richness) alongside the detection results.
+    #[arg(long, value_name = "FORMAT", num_args = 0..=1, default_missing_value = "csv")]
+    pub activity: Option<ActivityFormat>,
+
+    /// RMS level in dBFS above which a segment counts as active (default: -50).
+    #[arg(
+        long,
+        value_name = "DBFS",
+        allow_hyphen_values = true,
+        requires = "activity"
+    )]
+    pub activity_threshold: Option<f32>,
+
+    /// Add a perceptual fingerprint of each detection's audio segment to outputs.
+    ///
+    /// Fingerprints allow duplicate detection across runs and stations, and
+    /// checking extracted clips against their source detections.
+    #[arg(long)]
+    pub fingerprint: bool,
+
+    /// Add provenance columns (device, model_hash, processed_at) to outputs.
+    ///
+    /// Keeps the execution provider, model file hash and processing time on
+    /// every row, so datasets mixing CPU/GPU runs or several machines stay
+    /// traceable after combining.
+    #[arg(long)]
+    pub provenance: bool,
+
+    /// Add Song Meter recorder columns (model, serial, gain, temperature,
+    /// schedule) read from the WAMD metadata of each WAV file.
+    ///
+    /// Lets detectability be compared against recorder settings and
+    /// conditions. Files without WAMD metadata leave the columns empty.
+    #[arg(long)]
+    pub recorder_metadata: bool,
+
+    /// Add absolute start and end times (datetime_start, datetime_end) to
+    /// outputs, from the recording start in the file name or Song Meter
+    /// metadata. Files without a start time leave the columns empty.
+    #[arg(long)]
+    pub wall_clock: bool,
+
+    /// `strftime` pattern of the recording start in file names, e.g.
+    /// `%Y-%m-%d_%H-%M-%S` (repeatable); tried before the built-in
+    /// `YYYYMMDD_HHMMSS` convention.
+    #[arg(long, value_name = "FORMAT")]
+    pub timestamp_pattern: Vec<String>,
+
+    /// GPS track (GPX or KML) of a mobile survey; each detection gets the
+    /// position interpolated at its time as lat/lon columns.
+    #[arg(long, value_name = "PATH")]
+    pub track: Option<PathBuf>,
+
+    /// UTC offset in hours of recording timestamps, for matching them to the
+    /// track (default: 0, timestamps are UTC).
+    #[arg(
+        long,
+        value_name = "HOURS",
+        allow_hyphen_values = true,
+        requires = "track"
+    )]
+    pub track_utc_offset: Option<f32>,
+
+    /// Executable that post-processes each file's predictions (JSON on stdin/stdout).
+    ///
+    /// The hook can rescore, drop, or annotate predictions before results are written.
+    #[arg(long, value_name = "PROGRAM")]
+    pub postprocess_hook: Option<PathBuf>,
+
+    /// Merge overlapping detections of the same species into single events.
+    ///
+    /// Each event spans all of its detections and keeps the highest confidence.
+    /// Useful with --overlap, where one call is detected in several segments.
+    #[arg(long)]
+    pub merge_overlapping: bool,
+
+    /// Analyze segments around each detection again with this overlap in seconds.
+    ///
+    /// The first pass runs with --overlap (0 by default); only the audio around
+    /// its detections gets the finer pass, for precise timing at a fraction of
+    /// the cost of a full high-overlap run.
+    #[arg(long, value_name = "SECS")]
+    pub zoom_overlap: Option<f32>,
+
+    /// Reprocess files even if output exists (same as --skip-policy never).
     #[arg(long)]
     pub force: bool,
 
+    /// When to skip files that already have results: exists (all outputs
+    /// present), newer (outputs newer than the audio), hash (recorded model
+    /// and settings match), never.
+    #[arg(long, value_enum, env = "BIRDA_SKIP_POLICY")]
+    pub skip_policy: Option<SkipPolicy>,
+
     /// Stop on first error.
     #[arg(long)]
     pub fail_fast: bool,
@@ -278,6 +806,29 @@ pub struct AnalyzeArgs {
     #[arg(long, group = "provider")]
     pub cpu: bool,
 
+    /// Run this many independent model sessions in parallel on the CPU, each
+    /// taking a share of every batch (capped by cores and available memory).
+    #[arg(long, value_name = "N", value_parser = parse_cpu_sessions, env = "BIRDA_CPU_SESSIONS")]
+    pub cpu_sessions: Option<usize>,
+
+    /// Cap the GPU memory the CUDA provider may hold (e.g. 4G), leaving room
+    /// for other GPU workloads.
+    #[arg(long, value_name = "SIZE", value_parser = parse_byte_size, env = "BIRDA_GPU_MEM_LIMIT")]
+    pub gpu_mem_limit: Option<u64>,
+
+    /// How the CUDA provider's memory arena grows.
+    #[arg(long, value_name = "STRATEGY")]
+    pub arena_extend_strategy: Option<ArenaExtendStrategy>,
+
+    /// Check before loading the model that the GPU responds and has this much
+    /// memory free (e.g. 2G), or `auto` for the model's expected footprint.
+    #[arg(long, value_name = "SIZE|auto", value_parser = crate::gpu::MemoryRequirement::parse, env = "BIRDA_MIN_FREE_GPU_MEM")]
+    pub min_free_gpu_mem: Option<crate::gpu::MemoryRequirement>,
+
+    /// What to do when the GPU fails the --min-free-gpu-mem check.
+    #[arg(long, value_name = "ACTION")]
+    pub low_gpu_mem: Option<LowGpuMemAction>,
+
     /// Use `CUDA` provider explicitly (fail if unavailable).
     #[arg(long, group = "provider")]
     pub cuda: bool,
@@ -364,6 +915,27 @@ pub struct AnalyzeArgs {
     #[arg(long, env = "BIRDA_SPECIES_LIST")]
     pub slist: Option<PathBuf>,
 
+    /// Never write into input directories: requires --output-dir (outside the
+    /// inputs) and keeps lock files in a run-specific temp directory.
+    #[arg(long, env = "BIRDA_READ_ONLY_INPUTS")]
+    pub read_only_inputs: bool,
+
+    /// Central directory for lock files instead of beside the outputs.
+    /// Lock files are named by a hash of the input path.
+    #[arg(long, env = "BIRDA_LOCK_DIR")]
+    pub lock_dir: Option<PathBuf>,
+
+    /// Set aside recordings that fail to decode: move or symlink them into
+    /// `_failed/` with an `.error.json` record, or only write the record
+    /// beside the file's results (sidecar).
+    #[arg(long, value_name = "MODE", env = "BIRDA_DEAD_LETTER")]
+    pub dead_letter: Option<DeadLetterMode>,
+
+    /// Directory failed recordings are moved or linked into, instead of
+    /// `_failed/` in each file's output directory.
+    #[arg(long, value_name = "DIR", env = "BIRDA_DEAD_LETTER_DIR")]
+    pub dead_letter_dir: Option<PathBuf>,
+
     /// Remove locks older than this duration (e.g., 1h, 30m).
     #[arg(long)]
     pub stale_lock_timeout: Option<String>,
@@ -371,15 +943,30 @@ pub struct AnalyzeArgs {
     /// Write results to stdout as NDJSON stream (single file only).
     #[arg(long, conflicts_with_all = ["output_dir", "combine", "format"])]
     pub stdout: bool,
+
+    /// Write results to stdout in the given format instead of to files (single file only).
+    /// Progress and logs go to stderr, so output can be piped into other tools.
+    #[arg(
+        long,
+        value_enum,
+        conflicts_with_all = ["output_dir", "combine", "format", "stdout"]
+    )]
+    pub stdout_format: Option<OutputFormat>,
 }
 
 // Re-use shared validators
-use super::validators::{parse_batch_size, parse_confidence, parse_latitude, parse_longitude};
+use super::validators::{
+    parse_batch_size_setting, parse_byte_size, parse_confidence, parse_connections,
+    parse_cpu_sessions, parse_decimal_places, parse_latitude, parse_longitude, parse_row_count,
+    parse_sample_percent, parse_sensitivity,
+};
+use crate::audio::parse_timestamp;
 
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::float_cmp)]
 mod tests {
     use super::*;
+    use crate::config::EmbeddingFormat;
 
     #[test]
     fn test_cli_parse_simple() {
@@ -400,12 +987,789 @@ mod tests {
         assert!(cli.analyze.quiet);
     }
 
+    #[test]
+    fn test_cli_parse_lenient_config() {
+        let cli = Cli::try_parse_from(["birda", "models", "list", "--lenient-config"]).unwrap();
+        assert!(cli.lenient_config);
+    }
+
+    #[test]
+    fn test_cli_parse_profile() {
+        let cli = Cli::try_parse_from(["birda", "--profile", "quick-scan", "test.wav"]).unwrap();
+        assert_eq!(cli.profile.as_deref(), Some("quick-scan"));
+
+        let cli =
+            Cli::try_parse_from(["birda", "config", "show", "--profile", "archival"]).unwrap();
+        assert_eq!(cli.profile.as_deref(), Some("archival"));
+    }
+
+    #[test]
+    fn test_cli_parse_config_show_secrets() {
+        let cli = Cli::try_parse_from(["birda", "config", "show"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Config {
+                action: ConfigAction::Show {
+                    show_secrets: false
+                }
+            })
+        ));
+
+        let cli = Cli::try_parse_from(["birda", "config", "show", "--show-secrets"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Config {
+                action: ConfigAction::Show { show_secrets: true }
+            })
+        ));
+    }
+
+    #[test]
+    fn test_cli_parse_log_events() {
+        let cli = Cli::try_parse_from(["birda", "test.wav"]).unwrap();
+        assert_eq!(cli.log_events, LogEventLevel::Warn);
+
+        let cli =
+            Cli::try_parse_from(["birda", "models", "list", "--log-events", "debug"]).unwrap();
+        assert_eq!(cli.log_events, LogEventLevel::Debug);
+
+        assert!(Cli::try_parse_from(["birda", "--log-events", "loud", "test.wav"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_batch_size_auto() {
+        let cli = Cli::try_parse_from(["birda", "test.wav", "-b", "auto"]).unwrap();
+        assert_eq!(cli.analyze.batch_size, Some(BatchSize::Auto));
+        let cli = Cli::try_parse_from(["birda", "test.wav", "-b", "16"]).unwrap();
+        assert_eq!(cli.analyze.batch_size, Some(BatchSize::Fixed(16)));
+        assert!(Cli::try_parse_from(["birda", "test.wav", "-b", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_time_range() {
+        let cli = Cli::try_parse_from(["birda", "test.wav", "--from", "00:10:00", "--to", "1500"])
+            .unwrap();
+        assert_eq!(cli.analyze.from, Some(600.0));
+        assert_eq!(cli.analyze.to, Some(1500.0));
+        assert!(Cli::try_parse_from(["birda", "test.wav", "--from", "10 min"]).is_err());
+
+        let cli = Cli::try_parse_from([
+            "birda",
+            "long.flac",
+            "--analyze-start",
+            "3600",
+            "--analyze-end",
+            "7200",
+        ])
+        .unwrap();
+        assert_eq!(cli.analyze.from, Some(3600.0));
+        assert_eq!(cli.analyze.to, Some(7200.0));
+    }
+
+    #[test]
+    fn test_cli_parse_channel() {
+        use crate::audio::ChannelSelection;
+
+        let cli = Cli::try_parse_from(["birda", "sm4.wav"]).unwrap();
+        assert_eq!(cli.analyze.channel, None);
+        let cli = Cli::try_parse_from(["birda", "sm4.wav", "--channel", "all"]).unwrap();
+        assert_eq!(cli.analyze.channel, Some(ChannelSelection::All));
+        let cli = Cli::try_parse_from(["birda", "sm4.wav", "--channel", "2"]).unwrap();
+        assert_eq!(cli.analyze.channel, Some(ChannelSelection::Channel(2)));
+        assert!(Cli::try_parse_from(["birda", "sm4.wav", "--channel", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_min_free_gpu_mem() {
+        use crate::config::LowGpuMemAction;
+        use crate::gpu::MemoryRequirement;
+
+        let cli = Cli::try_parse_from([
+            "birda",
+            "test.wav",
+            "--min-free-gpu-mem",
+            "auto",
+            "--low-gpu-mem",
+            "cpu",
+        ])
+        .unwrap();
+        assert_eq!(cli.analyze.min_free_gpu_mem, Some(MemoryRequirement::Auto));
+        assert_eq!(cli.analyze.low_gpu_mem, Some(LowGpuMemAction::Cpu));
+        let cli = Cli::try_parse_from(["birda", "test.wav", "--min-free-gpu-mem", "2G"]).unwrap();
+        assert_eq!(
+            cli.analyze.min_free_gpu_mem,
+            Some(MemoryRequirement::Bytes(2 << 30))
+        );
+        assert!(Cli::try_parse_from(["birda", "test.wav", "--min-free-gpu-mem", "some"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_prefilter() {
+        use crate::audio::FilterSpec;
+
+        let cli = Cli::try_parse_from(["birda", "test.wav", "--highpass", "250"]).unwrap();
+        assert_eq!(
+            cli.analyze.highpass,
+            Some(FilterSpec {
+                low: Some(250.0),
+                high: None
+            })
+        );
+        let cli = Cli::try_parse_from(["birda", "test.wav", "--bandpass", "150:12000"]).unwrap();
+        assert_eq!(
+            cli.analyze.bandpass,
+            Some(FilterSpec {
+                low: Some(150.0),
+                high: Some(12000.0)
+            })
+        );
+        assert!(
+            Cli::try_parse_from([
+                "birda",
+                "test.wav",
+                "--highpass",
+                "250",
+                "--bandpass",
+                "150:12000"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_cli_parse_fault_inject() {
+        use crate::pipeline::{FaultPoint, FaultSpec};
+
+        let cli = Cli::try_parse_from([
+            "birda",
+            "test.wav",
+            "--fault-inject",
+            "write",
+            "--fail-after",
+            "2",
+        ])
+        .unwrap();
+        assert_eq!(
+            cli.analyze.fault_inject,
+            Some(FaultSpec {
+                point: FaultPoint::Write,
+                after: 0
+            })
+        );
+        assert_eq!(cli.analyze.fail_after, Some(2));
+        assert!(Cli::try_parse_from(["birda", "test.wav", "--fail-after", "2"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_stitch() {
+        let cli = Cli::try_parse_from(["birda", "recordings/", "--stitch"]).unwrap();
+        assert!(cli.analyze.stitch);
+        assert!(Cli::try_parse_from(["birda", "recordings/", "--stitch", "--from", "60"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_sample() {
+        let cli =
+            Cli::try_parse_from(["birda", "archive/", "--sample", "5%", "--sample-seed", "7"])
+                .unwrap();
+        assert_eq!(cli.analyze.sample, Some(5.0));
+        assert_eq!(cli.analyze.sample_seed, Some(7));
+
+        let cli = Cli::try_parse_from(["birda", "archive/", "--sample-n", "100"]).unwrap();
+        assert_eq!(cli.analyze.sample_n, Some(100));
+
+        assert!(
+            Cli::try_parse_from(["birda", "archive/", "--sample", "5%", "--sample-n", "100"])
+                .is_err()
+        );
+        assert!(Cli::try_parse_from(["birda", "archive/", "--sample-n", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_runs() {
+        let cli = Cli::try_parse_from(["birda", "runs", "list"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Runs {
+                action: RunsAction::List {
+                    limit: DEFAULT_LIST_LIMIT
+                }
+            })
+        ));
+
+        let cli = Cli::try_parse_from(["birda", "runs", "show", "20250101"]).unwrap();
+        match cli.command {
+            Some(Command::Runs {
+                action: RunsAction::Show { run_id },
+            }) => assert_eq!(run_id, "20250101"),
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_examples_fetch() {
+        let cli = Cli::try_parse_from(["birda", "examples", "fetch"]).unwrap();
+        match cli.command {
+            Some(Command::Examples {
+                action: ExamplesAction::Fetch { dir, force, .. },
+                catalog: None,
+            }) => {
+                assert_eq!(dir, PathBuf::from(DEFAULT_EXAMPLES_DIR));
+                assert!(!force);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        let cli = Cli::try_parse_from(["birda", "examples", "fetch", "--dir", "demo", "--force"])
+            .unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Examples {
+                action: ExamplesAction::Fetch { force: true, .. },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_cli_parse_cache_list() {
+        let cli = Cli::try_parse_from(["birda", "cache", "list"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Cache {
+                action: CacheAction::List
+            })
+        ));
+    }
+
+    #[test]
+    fn test_cli_parse_report_phenology() {
+        let cli = Cli::try_parse_from([
+            "birda",
+            "report",
+            "phenology",
+            "results/",
+            "--confidence",
+            "0.5",
+        ])
+        .unwrap();
+        match cli.command {
+            Some(Command::Report {
+                action: ReportAction::Phenology(args),
+            }) => {
+                assert_eq!(args.input, PathBuf::from("results/"));
+                assert_eq!(args.output, PathBuf::from("phenology.csv"));
+                assert!((args.confidence - 0.5).abs() < f32::EPSILON);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_report_calendar() {
+        let cli = Cli::try_parse_from([
+            "birda", "report", "calendar", "results/", "--charts", "charts/",
+        ])
+        .unwrap();
+        match cli.command {
+            Some(Command::Report {
+                action: ReportAction::Calendar(args),
+            }) => {
+                assert_eq!(args.output, PathBuf::from("calendar.csv"));
+                assert_eq!(args.charts, Some(PathBuf::from("charts/")));
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_report_accumulation() {
+        let cli = Cli::try_parse_from([
+            "birda",
+            "report",
+            "accumulation",
+            "results/",
+            "--html",
+            "accumulation.html",
+            "-c",
+            "0.7",
+        ])
+        .unwrap();
+        match cli.command {
+            Some(Command::Report {
+                action: ReportAction::Accumulation(args),
+            }) => {
+                assert_eq!(args.output, PathBuf::from("accumulation.csv"));
+                assert_eq!(args.html, Some(PathBuf::from("accumulation.html")));
+                assert!((args.confidence - 0.7).abs() < f32::EPSILON);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_report_review() {
+        let cli = Cli::try_parse_from([
+            "birda", "report", "review", "results/", "--top", "5", "--embed",
+        ])
+        .unwrap();
+        match cli.command {
+            Some(Command::Report {
+                action: ReportAction::Review(args),
+            }) => {
+                assert_eq!(args.output, PathBuf::from("species_review.html"));
+                assert_eq!(args.top, 5);
+                assert!(args.embed);
+                assert!(args.audio_dir.is_none());
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_audit() {
+        let cli = Cli::try_parse_from(["birda", "audit", "results/", "--stale-only"]).unwrap();
+        match cli.command {
+            Some(Command::Audit(args)) => {
+                assert_eq!(args.input, PathBuf::from("results/"));
+                assert!(args.stale_only);
+                assert!(args.model.is_none());
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_query() {
+        let cli = Cli::try_parse_from([
+            "birda",
+            "query",
+            "SELECT COUNT(*) FROM detections",
+            "--source",
+            "results.duckdb",
+        ])
+        .unwrap();
+        match cli.command {
+            Some(Command::Query(args)) => {
+                assert_eq!(args.sql, "SELECT COUNT(*) FROM detections");
+                assert_eq!(args.source, PathBuf::from("results.duckdb"));
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_export() {
+        let cli = Cli::try_parse_from(["birda", "export", "results/", "-o", "shared/"]).unwrap();
+        match cli.command {
+            Some(Command::Export(args)) => {
+                assert_eq!(args.output, PathBuf::from("shared/"));
+                assert!((args.grid - 0.1).abs() < f64::EPSILON);
+                assert!(args.mapping.is_none());
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+        assert!(
+            Cli::try_parse_from([
+                "birda", "export", "results/", "-o", "shared/", "--grid", "0"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_cli_parse_watch() {
+        let cli = Cli::try_parse_from([
+            "birda",
+            "watch",
+            "/data/aru",
+            "--poll-interval",
+            "30",
+            "-c",
+            "0.5",
+        ])
+        .unwrap();
+        match cli.command {
+            Some(Command::Watch(args)) => {
+                assert_eq!(args.dirs, vec![PathBuf::from("/data/aru")]);
+                assert_eq!(args.poll_interval, 30);
+                assert_eq!(args.analyze.min_confidence, Some(0.5));
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        assert!(Cli::try_parse_from(["birda", "watch"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_analyze_subcommand() {
+        let cli = Cli::try_parse_from([
+            "birda",
+            "analyze",
+            "a.wav",
+            "recordings/",
+            "-m",
+            "birdnet",
+            "-c",
+            "0.5",
+        ])
+        .unwrap();
+        match cli.command {
+            Some(Command::Analyze(args)) => {
+                assert_eq!(
+                    args.inputs,
+                    vec![PathBuf::from("a.wav"), PathBuf::from("recordings/")]
+                );
+                assert_eq!(args.analyze.model.as_deref(), Some("birdnet"));
+                assert_eq!(args.analyze.min_confidence, Some(0.5));
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+        assert!(cli.inputs.is_empty());
+
+        // Bare inputs stay an alias for `analyze`
+        let cli = Cli::try_parse_from(["birda", "a.wav", "-c", "0.5"]).unwrap();
+        assert!(cli.command.is_none());
+        assert_eq!(cli.inputs, vec![PathBuf::from("a.wav")]);
+
+        assert!(Cli::try_parse_from(["birda", "analyze"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_models_update() {
+        let cli = Cli::try_parse_from(["birda", "models", "update", "--yes"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Models {
+                action: ModelsAction::Update {
+                    id: None,
+                    yes: true,
+                    check: false,
+                    ..
+                }
+            })
+        ));
+        assert!(Cli::try_parse_from(["birda", "models", "update", "--check", "--yes"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_models_relocate() {
+        let cli =
+            Cli::try_parse_from(["birda", "models", "relocate", "birdnet", "/mnt/models"]).unwrap();
+        match cli.command {
+            Some(Command::Models {
+                action: ModelsAction::Relocate { id, new_dir },
+            }) => {
+                assert_eq!(id, "birdnet");
+                assert_eq!(new_dir, PathBuf::from("/mnt/models"));
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "birda",
+            "models",
+            "add",
+            "birdnet",
+            "--path",
+            "m.onnx",
+            "--labels",
+            "l.txt",
+            "--type",
+            "birdnet-v24",
+            "--update",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Models {
+                action: ModelsAction::Add { update: true, .. }
+            })
+        ));
+    }
+
+    #[test]
+    fn test_cli_parse_models_install_connections() {
+        let cli = Cli::try_parse_from(["birda", "models", "install", "birdnet-v24"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Models {
+                action: ModelsAction::Install {
+                    connections: DEFAULT_DOWNLOAD_CONNECTIONS,
+                    ..
+                }
+            })
+        ));
+
+        let cli = Cli::try_parse_from([
+            "birda",
+            "models",
+            "install",
+            "birdnet-v24",
+            "--connections",
+            "2",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Models {
+                action: ModelsAction::Install { connections: 2, .. }
+            })
+        ));
+    }
+
+    #[test]
+    fn test_cli_parse_embed() {
+        let cli = Cli::try_parse_from([
+            "birda",
+            "embed",
+            "rec.wav",
+            "-m",
+            "birdnet",
+            "-f",
+            "npy",
+            "--overlap",
+            "1.5",
+        ])
+        .unwrap();
+        match cli.command {
+            Some(Command::Embed(args)) => {
+                assert_eq!(args.inputs, vec![PathBuf::from("rec.wav")]);
+                assert_eq!(args.model.as_deref(), Some("birdnet"));
+                assert_eq!(args.format, EmbeddingFormat::Npy);
+                assert_eq!(args.overlap, Some(1.5));
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+        assert!(Cli::try_parse_from(["birda", "embed"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_listen() {
+        let cli = Cli::try_parse_from([
+            "birda",
+            "listen",
+            "-d",
+            "USB Mic",
+            "-c",
+            "0.5",
+            "--overlap",
+            "1",
+        ])
+        .unwrap();
+        match cli.command {
+            Some(Command::Listen(args)) => {
+                assert_eq!(args.device.as_deref(), Some("USB Mic"));
+                assert_eq!(args.min_confidence, Some(0.5));
+                assert_eq!(args.overlap, Some(1.0));
+                assert!(!args.list_devices);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+        assert!(Cli::try_parse_from(["birda", "listen", "--list-devices", "-d", "x"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_bench() {
+        let cli = Cli::try_parse_from(["birda", "bench", "--save-baseline", "base.json"]).unwrap();
+        match cli.command {
+            Some(Command::Bench(args)) => {
+                assert_eq!(args.segments, 512);
+                assert_eq!(args.tolerance, 10.0);
+                assert_eq!(args.save_baseline, Some(PathBuf::from("base.json")));
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "birda",
+            "bench",
+            "--baseline",
+            "base.json",
+            "--tolerance",
+            "5",
+        ])
+        .unwrap();
+        match cli.command {
+            Some(Command::Bench(args)) => {
+                assert_eq!(args.baseline, Some(PathBuf::from("base.json")));
+                assert_eq!(args.tolerance, 5.0);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        assert!(
+            Cli::try_parse_from([
+                "birda",
+                "bench",
+                "--baseline",
+                "a.json",
+                "--save-baseline",
+                "b.json"
+            ])
+            .is_err()
+        );
+        assert!(Cli::try_parse_from(["birda", "bench", "--segments", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_serve() {
+        let cli = Cli::try_parse_from(["birda", "serve"]).unwrap();
+        match cli.command {
+            Some(Command::Serve(args)) => {
+                assert_eq!(args.bind.to_string(), "127.0.0.1:8787");
+                assert!(args.model.is_none());
+                assert!(args.allow_path.is_none());
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "birda",
+            "serve",
+            "--bind",
+            "0.0.0.0:9000",
+            "-c",
+            "0.5",
+            "--allow-path",
+            "/data",
+        ])
+        .unwrap();
+        match cli.command {
+            Some(Command::Serve(args)) => {
+                assert_eq!(args.bind.port(), 9000);
+                assert_eq!(args.min_confidence, Some(0.5));
+                assert_eq!(args.allow_path, Some(PathBuf::from("/data")));
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        assert!(Cli::try_parse_from(["birda", "serve", "--bind", "localhost"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_calibrate() {
+        let cli = Cli::try_parse_from(["birda", "calibrate", "recordings/"]).unwrap();
+        match cli.command {
+            Some(Command::Calibrate(args)) => {
+                assert_eq!(args.overlaps, vec![0.0, 1.0, 2.0]);
+                assert_eq!(args.sample, 10);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "birda",
+            "calibrate",
+            "recordings/",
+            "--overlaps",
+            "0,1.5",
+            "--sample",
+            "3",
+        ])
+        .unwrap();
+        match cli.command {
+            Some(Command::Calibrate(args)) => {
+                assert_eq!(args.overlaps, vec![0.0, 1.5]);
+                assert_eq!(args.sample, 3);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+        assert!(Cli::try_parse_from(["birda", "calibrate", "x/", "--sample", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_selftest() {
+        let cli = Cli::try_parse_from(["birda", "selftest", "-m", "perch"]).unwrap();
+        match cli.command {
+            Some(Command::Selftest { model }) => assert_eq!(model.as_deref(), Some("perch")),
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_models_test() {
+        let cli =
+            Cli::try_parse_from(["birda", "models", "test", "birdnet", "--top", "3"]).unwrap();
+        match cli.command {
+            Some(Command::Models {
+                action: ModelsAction::Test { id, top },
+            }) => {
+                assert_eq!(id, "birdnet");
+                assert_eq!(top, 3);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
     #[test]
     fn test_cli_parse_config_subcommand() {
         let cli = Cli::try_parse_from(["birda", "config", "show"]);
         assert!(cli.is_ok());
     }
 
+    #[test]
+    fn test_cli_parse_config_get_set_unset() {
+        let cli = Cli::try_parse_from(["birda", "config", "set", "defaults.min_confidence", "0.3"])
+            .unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Config {
+                action: ConfigAction::Set { ref key, ref value }
+            }) if key == "defaults.min_confidence" && value == "0.3"
+        ));
+
+        let cli = Cli::try_parse_from(["birda", "config", "get", "inference.device"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Config {
+                action: ConfigAction::Get {
+                    ref key,
+                    show_secrets: false
+                }
+            }) if key == "inference.device"
+        ));
+
+        let cli = Cli::try_parse_from(["birda", "config", "unset", "defaults.latitude"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Config {
+                action: ConfigAction::Unset { ref key }
+            }) if key == "defaults.latitude"
+        ));
+
+        let cli = Cli::try_parse_from(["birda", "config", "validate"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Config {
+                action: ConfigAction::Validate
+            })
+        ));
+
+        let cli = Cli::try_parse_from(["birda", "config", "credentials", "set", "mqtt.password"])
+            .unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Config {
+                action: ConfigAction::Credentials {
+                    action: CredentialsAction::Set { ref key }
+                }
+            }) if key == "mqtt.password"
+        ));
+        // Values never go on the command line
+        assert!(
+            Cli::try_parse_from([
+                "birda",
+                "config",
+                "credentials",
+                "set",
+                "mqtt.password",
+                "x"
+            ])
+            .is_err()
+        );
+    }
+
     #[test]
     fn test_parse_latitude_valid() {
         assert_eq!(parse_latitude("0.0").ok(), Some(0.0));
@@ -723,6 +2087,113 @@ mod tests {
         assert!(cli.is_err());
     }
 
+    #[test]
+    fn test_cli_parse_stdout_format() {
+        let cli = Cli::try_parse_from(["birda", "--stdout-format", "csv", "test.wav"]).unwrap();
+        assert_eq!(cli.analyze.stdout_format, Some(OutputFormat::Csv));
+
+        let cli =
+            Cli::try_parse_from(["birda", "--stdout-format", "raven", "--stdout", "test.wav"]);
+        assert!(cli.is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_read_only_inputs() {
+        let cli = Cli::try_parse_from([
+            "birda",
+            "--read-only-inputs",
+            "--output-dir",
+            "/out",
+            "test.wav",
+        ])
+        .unwrap();
+        assert!(cli.analyze.read_only_inputs);
+    }
+
+    #[test]
+    fn test_cli_parse_dead_letter() {
+        let cli = Cli::try_parse_from(["birda", "--dead-letter", "symlink", "test.wav"]).unwrap();
+        assert_eq!(cli.analyze.dead_letter, Some(DeadLetterMode::Symlink));
+        assert_eq!(cli.analyze.dead_letter_dir, None);
+
+        assert!(Cli::try_parse_from(["birda", "--dead-letter", "delete", "test.wav"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_skip_policy() {
+        let cli = Cli::try_parse_from(["birda", "--skip-policy", "hash", "test.wav"]).unwrap();
+        assert_eq!(cli.analyze.skip_policy, Some(SkipPolicy::Hash));
+
+        assert!(Cli::try_parse_from(["birda", "--skip-policy", "sometimes", "test.wav"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_decimals() {
+        let cli = Cli::try_parse_from([
+            "birda",
+            "--confidence-decimals",
+            "3",
+            "--time-decimals",
+            "2",
+            "test.wav",
+        ])
+        .unwrap();
+        assert_eq!(cli.analyze.confidence_decimals, Some(3));
+        assert_eq!(cli.analyze.time_decimals, Some(2));
+
+        assert!(Cli::try_parse_from(["birda", "--time-decimals", "12", "test.wav"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_parquet_layout() {
+        let cli = Cli::try_parse_from([
+            "birda",
+            "-f",
+            "parquet",
+            "--parquet-layout",
+            "partitioned",
+            "test.wav",
+        ])
+        .unwrap();
+        assert_eq!(cli.analyze.parquet_layout, Some(ParquetLayout::Partitioned));
+    }
+
+    #[test]
+    fn test_cli_parse_split_limits() {
+        let cli = Cli::try_parse_from([
+            "birda",
+            "--max-rows-per-file",
+            "1000000",
+            "--max-file-size",
+            "100M",
+            "test.wav",
+        ])
+        .unwrap();
+        assert_eq!(cli.analyze.max_rows_per_file, Some(1_000_000));
+        assert_eq!(cli.analyze.max_file_size, Some(100 * 1024 * 1024));
+
+        let result = Cli::try_parse_from(["birda", "--max-rows-per-file", "0", "test.wav"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_append() {
+        let cli = Cli::try_parse_from(["birda", "--combine", "--append", "test.wav"]).unwrap();
+        assert!(cli.analyze.append);
+
+        assert!(Cli::try_parse_from(["birda", "--append", "test.wav"]).is_err());
+        let cli = Cli::try_parse_from([
+            "birda",
+            "--combine",
+            "--append",
+            "--max-rows-per-file",
+            "10",
+            "test.wav",
+        ])
+        .unwrap();
+        assert_eq!(cli.analyze.max_rows_per_file, Some(10));
+    }
+
     #[test]
     fn test_cli_stdout_conflicts_with_combine() {
         let cli = Cli::try_parse_from(["birda", "--stdout", "--combine", "test.wav"]);
@@ -734,4 +2205,164 @@ mod tests {
         let cli = Cli::try_parse_from(["birda", "--stdout", "--format", "csv", "test.wav"]);
         assert!(cli.is_err());
     }
+
+    #[test]
+    fn test_fingerprint_flag() {
+        let cli = Cli::try_parse_from(["birda", "test.wav", "--fingerprint"]).unwrap();
+        assert!(cli.analyze.fingerprint);
+    }
+
+    #[test]
+    fn test_provenance_flag() {
+        let cli = Cli::try_parse_from(["birda", "test.wav", "--provenance"]).unwrap();
+        assert!(cli.analyze.provenance);
+    }
+
+    #[test]
+    fn test_wall_clock_flags() {
+        let cli = Cli::try_parse_from([
+            "birda",
+            "test.wav",
+            "--wall-clock",
+            "--timestamp-pattern",
+            "%Y-%m-%d_%H-%M-%S",
+            "--timestamp-pattern",
+            "rec_%d%m%Y",
+        ])
+        .unwrap();
+        assert!(cli.analyze.wall_clock);
+        assert_eq!(
+            cli.analyze.timestamp_pattern,
+            ["%Y-%m-%d_%H-%M-%S", "rec_%d%m%Y"]
+        );
+    }
+
+    #[test]
+    fn test_recorder_metadata_flag() {
+        let cli = Cli::try_parse_from(["birda", "test.wav", "--recorder-metadata"]).unwrap();
+        assert!(cli.analyze.recorder_metadata);
+    }
+
+    #[test]
+    fn test_track_args() {
+        let cli = Cli::try_parse_from([
+            "birda",
+            "--track",
+            "walk.gpx",
+            "--track-utc-offset",
+            "-5",
+            "test.wav",
+        ])
+        .unwrap();
+        assert_eq!(cli.analyze.track, Some(PathBuf::from("walk.gpx")));
+        assert_eq!(cli.analyze.track_utc_offset, Some(-5.0));
+
+        let result = Cli::try_parse_from(["birda", "--track-utc-offset", "2", "test.wav"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_postprocess_hook_parsing() {
+        let cli = Cli::try_parse_from(["birda", "test.wav", "--postprocess-hook", "./rescore.py"])
+            .unwrap();
+        assert_eq!(
+            cli.analyze.postprocess_hook,
+            Some(PathBuf::from("./rescore.py"))
+        );
+    }
+
+    #[test]
+    fn test_merge_overlapping_flag() {
+        let cli = Cli::try_parse_from(["birda", "test.wav"]).unwrap();
+        assert!(!cli.analyze.merge_overlapping);
+        let cli = Cli::try_parse_from(["birda", "test.wav", "--merge-overlapping"]).unwrap();
+        assert!(cli.analyze.merge_overlapping);
+    }
+
+    #[test]
+    fn test_file_list_flag() {
+        let cli = Cli::try_parse_from(["birda", "--file-list", "batch.txt"]).unwrap();
+        assert!(cli.inputs.is_empty());
+        assert_eq!(cli.analyze.file_list, Some(PathBuf::from("batch.txt")));
+
+        // `analyze` needs inputs unless they come from a list
+        assert!(Cli::try_parse_from(["birda", "analyze"]).is_err());
+        let cli = Cli::try_parse_from(["birda", "analyze", "--file-list", "-"]).unwrap();
+        match cli.command {
+            Some(Command::Analyze(args)) => {
+                assert_eq!(args.analyze.file_list, Some(PathBuf::from("-")));
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_file_filter_flags() {
+        let cli = Cli::try_parse_from([
+            "birda",
+            "recordings/",
+            "--min-duration",
+            "10",
+            "--max-duration",
+            "24:00:00",
+            "--min-size",
+            "1K",
+        ])
+        .unwrap();
+        assert_eq!(cli.analyze.min_duration, Some(10.0));
+        assert_eq!(cli.analyze.max_duration, Some(86_400.0));
+        assert_eq!(cli.analyze.min_size, Some(1024));
+    }
+
+    #[test]
+    fn test_exclude_flag() {
+        let cli = Cli::try_parse_from([
+            "birda",
+            "recordings/",
+            "--exclude",
+            "calibration",
+            "--exclude",
+            "site1/**/noise",
+        ])
+        .unwrap();
+        assert_eq!(cli.analyze.exclude.len(), 2);
+        assert!(cli.analyze.exclude[0].matches_name("calibration"));
+        assert!(Cli::try_parse_from(["birda", "recordings/", "--exclude", "[ab"]).is_err());
+    }
+
+    #[test]
+    fn test_zoom_overlap_flag() {
+        let cli = Cli::try_parse_from(["birda", "test.wav"]).unwrap();
+        assert_eq!(cli.analyze.zoom_overlap, None);
+        let cli = Cli::try_parse_from(["birda", "test.wav", "--zoom-overlap", "2.5"]).unwrap();
+        assert_eq!(cli.analyze.zoom_overlap, Some(2.5));
+    }
+
+    #[test]
+    fn test_activity_defaults_to_csv() {
+        let cli = Cli::try_parse_from(["birda", "test.wav", "--activity"]).unwrap();
+        assert_eq!(cli.analyze.activity, Some(ActivityFormat::Csv));
+        assert!(cli.analyze.activity_threshold.is_none());
+    }
+
+    #[test]
+    fn test_activity_parquet_with_threshold() {
+        let cli = Cli::try_parse_from([
+            "birda",
+            "--activity",
+            "parquet",
+            "--activity-threshold",
+            "-40",
+            "test.wav",
+        ])
+        .unwrap();
+        assert_eq!(cli.analyze.activity, Some(ActivityFormat::Parquet));
+        assert_eq!(cli.analyze.activity_threshold, Some(-40.0));
+    }
+
+    #[test]
+    fn test_activity_threshold_requires_activity() {
+        let result = Cli::try_parse_from(["birda", "test.wav", "--activity-threshold", "-40"]);
+        assert!(result.is_err());
+    }
 }
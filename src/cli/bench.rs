@@ -0,0 +1,53 @@
+//! CLI for the inference benchmark subcommand.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use super::validators::{parse_batch_size, parse_bounded_float};
+use crate::constants::bench::{DEFAULT_SEGMENTS, DEFAULT_TOLERANCE_PERCENT};
+
+/// Arguments for the bench subcommand.
+#[derive(Debug, Args)]
+pub struct BenchArgs {
+    /// Model name from configuration (default: `defaults.model`).
+    #[arg(short, long)]
+    pub model: Option<String>,
+
+    /// Inference batch size (default: `defaults.batch_size`).
+    #[arg(short, long, value_parser = parse_batch_size)]
+    pub batch_size: Option<usize>,
+
+    /// Number of segments to classify, after warm-up.
+    #[arg(long, default_value_t = DEFAULT_SEGMENTS, value_parser = parse_segments)]
+    pub segments: usize,
+
+    /// Compare throughput with a baseline and fail if it regressed.
+    #[arg(long, value_name = "FILE", conflicts_with = "save_baseline")]
+    pub baseline: Option<PathBuf>,
+
+    /// Save the result as a baseline for later runs.
+    #[arg(long, value_name = "FILE")]
+    pub save_baseline: Option<PathBuf>,
+
+    /// Allowed throughput drop below the baseline in percent.
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        default_value_t = DEFAULT_TOLERANCE_PERCENT,
+        value_parser = parse_tolerance
+    )]
+    pub tolerance: f64,
+}
+
+fn parse_segments(s: &str) -> Result<usize, String> {
+    match s.trim().parse::<usize>() {
+        Ok(0) => Err("segments must be at least 1".to_string()),
+        Ok(value) => Ok(value),
+        Err(_) => Err(format!("'{s}' is not a valid number")),
+    }
+}
+
+fn parse_tolerance(s: &str) -> Result<f64, String> {
+    parse_bounded_float(s, 0.0, 100.0, "tolerance")
+}
@@ -0,0 +1,35 @@
+//! CLI for the overlap calibration subcommand.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use super::validators::{parse_batch_size, parse_confidence};
+
+/// Arguments for the calibrate subcommand.
+#[derive(Debug, Args)]
+pub struct CalibrateArgs {
+    /// Audio files or directories to sample recordings from.
+    #[arg(required = true)]
+    pub inputs: Vec<PathBuf>,
+
+    /// Model name from configuration (default: `defaults.model`).
+    #[arg(short, long)]
+    pub model: Option<String>,
+
+    /// Overlaps to compare in seconds (comma-separated); the first is the baseline.
+    #[arg(long, value_delimiter = ',', default_value = "0,1,2")]
+    pub overlaps: Vec<f32>,
+
+    /// Number of recordings to analyze, spread evenly over the inputs.
+    #[arg(long, default_value = "10", value_parser = clap::value_parser!(u32).range(1..))]
+    pub sample: u32,
+
+    /// Minimum confidence threshold (default: `defaults.min_confidence`).
+    #[arg(short = 'c', long, value_parser = parse_confidence)]
+    pub min_confidence: Option<f32>,
+
+    /// Inference batch size (default: `defaults.batch_size`).
+    #[arg(short, long, value_parser = parse_batch_size)]
+    pub batch_size: Option<usize>,
+}
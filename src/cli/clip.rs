@@ -5,6 +5,8 @@ use std::path::PathBuf;
 use clap::Args;
 
 use super::validators::parse_confidence;
+use crate::audio::parse_timestamp;
+use crate::clipper::ClipFormat;
 use crate::constants::clipper::{
     DEFAULT_OUTPUT_DIR, DEFAULT_POST_PADDING, DEFAULT_PRE_PADDING, MAX_PADDING,
 };
@@ -21,18 +23,42 @@ pub struct ClipArgs {
     #[arg(short, long, default_value = DEFAULT_OUTPUT_DIR)]
     pub output: PathBuf,
 
+    /// Audio format of the clips; flac, mp3 and ogg are encoded with ffmpeg.
+    #[arg(long, value_enum, default_value_t = ClipFormat::Wav)]
+    pub clip_format: ClipFormat,
+
     /// Minimum confidence threshold (0.0-1.0).
     #[arg(short, long, default_value = "0.0", value_parser = parse_confidence)]
     pub confidence: f32,
 
+    /// Only write clips whose most confident detection reaches this
+    /// confidence (0.0-1.0); weaker detections can still extend a clip.
+    #[arg(long, value_name = "CONFIDENCE", value_parser = parse_confidence)]
+    pub min_clip_confidence: Option<f32>,
+
+    /// Write at most this many clips per species, the most confident ones
+    /// across all detection files.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    pub max_per_species: Option<u32>,
+
     /// Seconds of audio to include before each detection.
-    #[arg(long, default_value_t = DEFAULT_PRE_PADDING, value_parser = parse_padding)]
+    #[arg(long, visible_alias = "pre-pad", default_value_t = DEFAULT_PRE_PADDING,
+          value_parser = parse_padding)]
     pub pre: f64,
 
     /// Seconds of audio to include after each detection.
-    #[arg(long, default_value_t = DEFAULT_POST_PADDING, value_parser = parse_padding)]
+    #[arg(long, visible_alias = "post-pad", default_value_t = DEFAULT_POST_PADDING,
+          value_parser = parse_padding)]
     pub post: f64,
 
+    /// Seconds over which each clip fades in from silence.
+    #[arg(long, value_name = "SECONDS", default_value_t = 0.0, value_parser = parse_timestamp)]
+    pub fade_in: f64,
+
+    /// Seconds over which each clip fades out to silence.
+    #[arg(long, value_name = "SECONDS", default_value_t = 0.0, value_parser = parse_timestamp)]
+    pub fade_out: f64,
+
     /// Source audio file (auto-detected from detection file if omitted in CSV mode,
     /// required in direct extraction mode).
     #[arg(short, long)]
@@ -43,14 +69,14 @@ pub struct ClipArgs {
     #[arg(long)]
     pub base_dir: Option<PathBuf>,
 
-    /// Start time in seconds for direct extraction mode.
+    /// Start time for direct extraction mode (`HH:MM:SS`, `MM:SS` or seconds).
     /// Requires --end and --audio.
-    #[arg(long, requires = "end", requires = "audio", value_parser = parse_time)]
+    #[arg(long, requires = "end", requires = "audio", value_parser = parse_timestamp)]
     pub start: Option<f64>,
 
-    /// End time in seconds for direct extraction mode.
+    /// End time for direct extraction mode (`HH:MM:SS`, `MM:SS` or seconds).
     /// Requires --start and --audio.
-    #[arg(long, requires = "start", requires = "audio", value_parser = parse_time)]
+    #[arg(long, requires = "start", requires = "audio", value_parser = parse_timestamp)]
     pub end: Option<f64>,
 }
 
@@ -71,15 +97,3 @@ fn parse_padding(s: &str) -> Result<f64, String> {
 
     Ok(value)
 }
-
-fn parse_time(s: &str) -> Result<f64, String> {
-    let value: f64 = s
-        .parse()
-        .map_err(|_| format!("'{s}' is not a valid number"))?;
-
-    if value < 0.0 {
-        return Err(format!("time cannot be negative, got {value}"));
-    }
-
-    Ok(value)
-}
@@ -0,0 +1,36 @@
+//! CLI for the embedding extraction subcommand.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use super::validators::parse_batch_size;
+use crate::config::EmbeddingFormat;
+
+/// Arguments for the embed subcommand.
+#[derive(Debug, Args)]
+pub struct EmbedArgs {
+    /// Audio files or directories to extract embeddings from.
+    #[arg(required = true)]
+    pub inputs: Vec<PathBuf>,
+
+    /// Model name from configuration (default: `defaults.model`).
+    #[arg(short, long)]
+    pub model: Option<String>,
+
+    /// Output directory (default: same as input).
+    #[arg(short, long)]
+    pub output_dir: Option<PathBuf>,
+
+    /// Embedding file format.
+    #[arg(short, long, value_enum, default_value_t = EmbeddingFormat::Parquet)]
+    pub format: EmbeddingFormat,
+
+    /// Segment overlap in seconds (default: `defaults.overlap`).
+    #[arg(long)]
+    pub overlap: Option<f32>,
+
+    /// Inference batch size (default: `defaults.batch_size`).
+    #[arg(short, long, value_parser = parse_batch_size)]
+    pub batch_size: Option<usize>,
+}
@@ -31,16 +31,16 @@ pub fn print_first_time_help() {
     println!("   birda models install birdnet-v24 --default");
     println!();
     println!("3. Analyze audio files:");
-    println!("   birda recording.wav");
+    println!("   birda analyze recording.wav");
     println!();
     println!("Run 'birda -h' for all options.");
 }
 
 /// Print brief usage reminder for configured users.
 pub fn print_configured_help() {
-    println!("Usage: birda [FILES]... [OPTIONS]");
+    println!("Usage: birda analyze [FILES]... [OPTIONS]");
     println!();
-    println!("Example: birda recording.wav -m birdnet -c 0.25");
+    println!("Example: birda analyze recording.wav -m birdnet -c 0.25");
     println!();
     println!("Run 'birda -h' for all options or 'birda models list' to see configured models.");
 }
@@ -83,6 +83,7 @@ mod tests {
                 bsg_calibration: None,
                 bsg_migration: None,
                 bsg_distribution_maps: None,
+                custom_head: None,
             },
         );
 
@@ -0,0 +1,29 @@
+//! CLI for the live audio subcommand.
+
+use clap::Args;
+
+use super::validators::parse_confidence;
+
+/// Arguments for the listen subcommand.
+#[derive(Debug, Args)]
+pub struct ListenArgs {
+    /// Model name from configuration (default: `defaults.model`).
+    #[arg(short, long)]
+    pub model: Option<String>,
+
+    /// Input device name (default: the system's default input device).
+    #[arg(short, long)]
+    pub device: Option<String>,
+
+    /// List available input devices and exit.
+    #[arg(long, conflicts_with_all = ["model", "device"])]
+    pub list_devices: bool,
+
+    /// Minimum confidence threshold (default: `defaults.min_confidence`).
+    #[arg(short = 'c', long, value_parser = parse_confidence)]
+    pub min_confidence: Option<f32>,
+
+    /// Window overlap in seconds (default: `defaults.overlap`).
+    #[arg(long)]
+    pub overlap: Option<f32>,
+}
@@ -1,10 +1,28 @@
 //! CLI argument parsing and command handling.
 
 mod args;
+pub mod bench;
+pub mod calibrate;
 pub mod clip;
+pub mod embed;
 pub mod help;
+pub mod listen;
+pub mod report;
+pub mod serve;
 pub mod species;
-mod validators;
+pub(crate) mod validators;
 
-pub use args::{AnalyzeArgs, Cli, Command, ConfigAction, ModelsAction, SortOrder};
+pub use args::{
+    AnalyzeArgs, AnalyzeCommandArgs, CacheAction, Cli, Command, ConfigAction, CredentialsAction,
+    ExamplesAction, FormatsAction, ModelsAction, RunsAction, SortOrder, WatchArgs,
+};
+pub use bench::BenchArgs;
+pub use calibrate::CalibrateArgs;
 pub use clip::ClipArgs;
+pub use embed::EmbedArgs;
+pub use listen::ListenArgs;
+pub use report::{
+    AccumulationArgs, AuditArgs, CalendarArgs, ExportArgs, PhenologyArgs, QueryArgs, ReportAction,
+    ReviewArgs,
+};
+pub use serve::ServeArgs;
@@ -0,0 +1,166 @@
+//! CLI for the report subcommand.
+
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+
+use super::validators::{parse_confidence, parse_grid_degrees};
+use crate::constants::export::DEFAULT_GRID_DEGREES;
+use crate::constants::report::{
+    DEFAULT_ACCUMULATION_OUTPUT, DEFAULT_CALENDAR_OUTPUT, DEFAULT_PHENOLOGY_OUTPUT,
+    DEFAULT_REVIEW_OUTPUT, DEFAULT_REVIEW_SNIPPETS,
+};
+
+/// Report subcommand actions.
+#[derive(Debug, Subcommand)]
+pub enum ReportAction {
+    /// First and last detection date per species, site, and year.
+    Phenology(PhenologyArgs),
+    /// Detections per species by day and hour of day.
+    Calendar(CalendarArgs),
+    /// HTML page with audio snippets of each species' top detections.
+    Review(ReviewArgs),
+    /// Species found against hours of audio analyzed.
+    Accumulation(AccumulationArgs),
+}
+
+/// Arguments for the phenology report.
+#[derive(Debug, Args)]
+pub struct PhenologyArgs {
+    /// Directory of detection results (CSV format), searched recursively.
+    /// Each first-level subdirectory is treated as a site.
+    pub input: PathBuf,
+
+    /// Output CSV file for the phenology table.
+    #[arg(short, long, default_value = DEFAULT_PHENOLOGY_OUTPUT)]
+    pub output: PathBuf,
+
+    /// Minimum confidence threshold (0.0-1.0).
+    #[arg(short, long, default_value = "0.0", value_parser = parse_confidence)]
+    pub confidence: f32,
+}
+
+/// Arguments for the activity calendar report.
+#[derive(Debug, Args)]
+pub struct CalendarArgs {
+    /// Directory of detection results (CSV format), searched recursively.
+    pub input: PathBuf,
+
+    /// Output CSV file with one row per species and day, one column per hour.
+    #[arg(short, long, default_value = DEFAULT_CALENDAR_OUTPUT)]
+    pub output: PathBuf,
+
+    /// Directory to write one SVG heatmap per species.
+    #[arg(long)]
+    pub charts: Option<PathBuf>,
+
+    /// Minimum confidence threshold (0.0-1.0).
+    #[arg(short, long, default_value = "0.0", value_parser = parse_confidence)]
+    pub confidence: f32,
+}
+
+/// Arguments for the species review report.
+#[derive(Debug, Args)]
+pub struct ReviewArgs {
+    /// Directory of detection results (CSV format), searched recursively.
+    pub input: PathBuf,
+
+    /// Output HTML file.
+    #[arg(short, long, default_value = DEFAULT_REVIEW_OUTPUT)]
+    pub output: PathBuf,
+
+    /// Minimum confidence threshold (0.0-1.0).
+    #[arg(short, long, default_value = "0.0", value_parser = parse_confidence)]
+    pub confidence: f32,
+
+    /// Number of top detections per species with audio snippets.
+    #[arg(long, default_value_t = DEFAULT_REVIEW_SNIPPETS)]
+    pub top: usize,
+
+    /// Embed snippets in the HTML file instead of linking WAV files written
+    /// next to it.
+    #[arg(long)]
+    pub embed: bool,
+
+    /// Directory containing the source audio, if results do not record it.
+    #[arg(long)]
+    pub audio_dir: Option<PathBuf>,
+}
+
+/// Arguments for the species accumulation report.
+#[derive(Debug, Args)]
+pub struct AccumulationArgs {
+    /// Directory of detection results (CSV format), searched recursively.
+    pub input: PathBuf,
+
+    /// Output CSV file with one row per recording, in recording order.
+    #[arg(short, long, default_value = DEFAULT_ACCUMULATION_OUTPUT)]
+    pub output: PathBuf,
+
+    /// HTML page to write with the accumulation chart.
+    #[arg(long)]
+    pub html: Option<PathBuf>,
+
+    /// Minimum confidence threshold (0.0-1.0).
+    #[arg(short, long, default_value = "0.0", value_parser = parse_confidence)]
+    pub confidence: f32,
+
+    /// Directory containing the source audio, if results do not record it.
+    #[arg(long)]
+    pub audio_dir: Option<PathBuf>,
+}
+
+/// Arguments for the audit subcommand.
+#[derive(Debug, Args)]
+pub struct AuditArgs {
+    /// Directory of detection results, searched recursively.
+    pub input: PathBuf,
+
+    /// Model to compare against (defaults to the configured default model).
+    #[arg(short, long)]
+    pub model: Option<String>,
+
+    /// Minimum confidence to compare against (defaults to config).
+    #[arg(short = 'c', long, value_parser = parse_confidence)]
+    pub min_confidence: Option<f32>,
+
+    /// Segment overlap to compare against (defaults to config).
+    #[arg(long)]
+    pub overlap: Option<f32>,
+
+    /// Only list stale and unknown results.
+    #[arg(long)]
+    pub stale_only: bool,
+}
+
+/// Arguments for the export subcommand.
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// Directory of detection results, searched recursively.
+    pub input: PathBuf,
+
+    /// Directory for the sanitized copy.
+    #[arg(short, long)]
+    pub output: PathBuf,
+
+    /// Grid that coordinates are snapped to, in degrees.
+    #[arg(long, default_value_t = DEFAULT_GRID_DEGREES, value_parser = parse_grid_degrees)]
+    pub grid: f64,
+
+    /// File mapping original to exported values, kept out of the export
+    /// (defaults to `<OUTPUT>.mapping.csv` next to the output directory).
+    #[arg(long)]
+    pub mapping: Option<PathBuf>,
+}
+
+/// Arguments for the query subcommand.
+#[derive(Debug, Args)]
+pub struct QueryArgs {
+    /// SQL to run; Parquet sources are available as the `detections` view.
+    pub sql: String,
+
+    /// DuckDB database (`.duckdb`/`.db`), Parquet file, or directory of
+    /// Parquet results (searched recursively, hive partitions become columns).
+    #[arg(short, long, default_value = ".")]
+    pub source: PathBuf,
+}
@@ -0,0 +1,37 @@
+//! CLI for the HTTP API server subcommand.
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use clap::Args;
+
+use super::validators::{parse_batch_size, parse_confidence};
+use crate::constants::serve::DEFAULT_ADDRESS;
+
+/// Arguments for the serve subcommand.
+#[derive(Debug, Args)]
+pub struct ServeArgs {
+    /// Address and port to listen on.
+    #[arg(long, default_value = DEFAULT_ADDRESS)]
+    pub bind: SocketAddr,
+
+    /// Allow `POST /analyze` to name local files under this directory.
+    #[arg(long, value_name = "DIR")]
+    pub allow_path: Option<PathBuf>,
+
+    /// Model name from configuration (default: `defaults.model`).
+    #[arg(short, long)]
+    pub model: Option<String>,
+
+    /// Default minimum confidence threshold (default: `defaults.min_confidence`).
+    #[arg(short = 'c', long, value_parser = parse_confidence)]
+    pub min_confidence: Option<f32>,
+
+    /// Default segment overlap in seconds (default: `defaults.overlap`).
+    #[arg(long)]
+    pub overlap: Option<f32>,
+
+    /// Inference batch size (default: `defaults.batch_size`).
+    #[arg(short, long, value_parser = parse_batch_size)]
+    pub batch_size: Option<usize>,
+}
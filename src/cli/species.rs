@@ -1,7 +1,7 @@
 //! Species list generation from range filter.
 
 use crate::cli::SortOrder;
-use crate::config::{OutputMode, load_default_config};
+use crate::config::{Config, OutputMode};
 use crate::error::{Error, Result};
 use crate::inference::range_filter::RangeFilter;
 use crate::output::{ResultType, SpeciesEntry, SpeciesListPayload, emit_json_result};
@@ -26,10 +26,10 @@ const DEFAULT_OUTPUT_FILE: &str = "species_list.txt";
 /// - `sort`: Sort order (Freq or Alpha)
 /// - `model`: Model name to use
 /// - `output_mode`: Output mode (Human, Json, Ndjson)
+/// - `config`: Loaded configuration
 ///
 /// # Errors
 /// Returns error if:
-/// - Config cannot be loaded
 /// - Model not found or has no meta model
 /// - Meta model file not found
 /// - Range filter prediction fails
@@ -46,10 +46,8 @@ pub fn generate_species_list(
     sort: SortOrder,
     model: Option<String>,
     output_mode: OutputMode,
+    config: &Config,
 ) -> Result<()> {
-    // Load configuration
-    let config = load_default_config()?;
-
     // Determine model to use
     let model_name = model
         .or_else(|| config.defaults.model.clone())
@@ -57,7 +55,7 @@ pub fn generate_species_list(
             message: "no model specified (use -m or set defaults.model in config)".to_string(),
         })?;
 
-    let model_config = crate::config::get_model(&config, &model_name)?;
+    let model_config = crate::config::get_model(config, &model_name)?;
 
     // Get meta model path
     let meta_model_path = model_config
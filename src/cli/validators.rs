@@ -2,7 +2,11 @@
 //!
 //! Shared validation functions for CLI argument parsing.
 
-use crate::constants::MAX_BATCH_SIZE;
+use crate::config::BatchSize;
+use crate::constants::export::{MAX_GRID_DEGREES, MIN_GRID_DEGREES};
+use crate::constants::precision::MAX_DECIMAL_PLACES;
+use crate::constants::registry::MAX_DOWNLOAD_CONNECTIONS;
+use crate::constants::{MAX_BATCH_SIZE, MAX_CPU_SESSIONS, sensitivity};
 
 /// Parse and validate confidence value (0.0-1.0).
 pub fn parse_confidence(s: &str) -> Result<f32, String> {
@@ -41,6 +45,35 @@ pub fn parse_bounded_float(s: &str, min: f64, max: f64, name: &str) -> Result<f6
     Ok(value)
 }
 
+/// Parse and validate detection sensitivity (0.5-1.5).
+pub fn parse_sensitivity(s: &str) -> Result<f32, String> {
+    #[allow(clippy::cast_possible_truncation)]
+    parse_bounded_float(
+        s,
+        f64::from(sensitivity::MIN),
+        f64::from(sensitivity::MAX),
+        "sensitivity",
+    )
+    .map(|value| value as f32)
+}
+
+/// Parse a share of the input files, as `5%` or `5` (more than 0, at most 100).
+pub fn parse_sample_percent(s: &str) -> Result<f64, String> {
+    let trimmed = s.trim();
+    let value: f64 = trimmed
+        .strip_suffix('%')
+        .unwrap_or(trimmed)
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{s}' is not a valid percentage (e.g. 5%)"))?;
+
+    if !(value > 0.0 && value <= 100.0) {
+        return Err(format!("sample must be above 0% and at most 100%, got {s}"));
+    }
+
+    Ok(value)
+}
+
 /// Parse and validate latitude value (-90.0 to 90.0).
 pub fn parse_latitude(s: &str) -> Result<f64, String> {
     parse_bounded_float(s, -90.0, 90.0, "latitude")
@@ -51,6 +84,11 @@ pub fn parse_longitude(s: &str) -> Result<f64, String> {
     parse_bounded_float(s, -180.0, 180.0, "longitude")
 }
 
+/// Parse and validate a coordinate grid for sanitized exports, in degrees.
+pub fn parse_grid_degrees(s: &str) -> Result<f64, String> {
+    parse_bounded_float(s, MIN_GRID_DEGREES, MAX_GRID_DEGREES, "grid")
+}
+
 /// Parse and validate batch size (must be between 1 and `MAX_BATCH_SIZE`).
 pub fn parse_batch_size(s: &str) -> Result<usize, String> {
     let value: usize = s
@@ -73,11 +111,126 @@ pub fn parse_batch_size(s: &str) -> Result<usize, String> {
     Ok(value)
 }
 
+/// Parse a batch size setting: `auto` or a size accepted by [`parse_batch_size`].
+pub fn parse_batch_size_setting(s: &str) -> Result<BatchSize, String> {
+    if s.trim().eq_ignore_ascii_case("auto") {
+        return Ok(BatchSize::Auto);
+    }
+    parse_batch_size(s).map(BatchSize::Fixed)
+}
+
+/// Parse and validate the number of CPU sessions (1 to `MAX_CPU_SESSIONS`).
+pub fn parse_cpu_sessions(s: &str) -> Result<usize, String> {
+    let value: usize = s
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{s}' is not a valid number"))?;
+
+    if !(1..=MAX_CPU_SESSIONS).contains(&value) {
+        return Err(format!(
+            "cpu sessions must be between 1 and {MAX_CPU_SESSIONS}, got {value}"
+        ));
+    }
+
+    Ok(value)
+}
+
+/// Parse a download connection limit (between 1 and `MAX_DOWNLOAD_CONNECTIONS`).
+pub fn parse_connections(s: &str) -> Result<usize, String> {
+    let value: usize = s
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{s}' is not a valid number"))?;
+
+    if !(1..=MAX_DOWNLOAD_CONNECTIONS).contains(&value) {
+        return Err(format!(
+            "connections must be between 1 and {MAX_DOWNLOAD_CONNECTIONS}, got {value}"
+        ));
+    }
+
+    Ok(value)
+}
+
+/// Parse a number of decimal places (0 to `MAX_DECIMAL_PLACES`).
+pub fn parse_decimal_places(s: &str) -> Result<usize, String> {
+    let value: usize = s
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{s}' is not a valid number"))?;
+
+    if value > MAX_DECIMAL_PLACES {
+        return Err(format!(
+            "decimal places must be between 0 and {MAX_DECIMAL_PLACES}, got {value}"
+        ));
+    }
+
+    Ok(value)
+}
+
+/// Parse a row count (must be at least 1).
+pub fn parse_row_count(s: &str) -> Result<u64, String> {
+    let value: u64 = s
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{s}' is not a valid number"))?;
+
+    if value < 1 {
+        return Err(format!("row count must be at least 1, got {value}"));
+    }
+
+    Ok(value)
+}
+
+/// Parse a byte size with an optional `K`, `M` or `G` suffix (powers of 1024).
+///
+/// A trailing `B` or `iB` is accepted, so `500M`, `500MB` and `500MiB` are equal.
+pub fn parse_byte_size(s: &str) -> Result<u64, String> {
+    let trimmed = s.trim();
+    let upper = trimmed.to_ascii_uppercase();
+    let number = upper
+        .strip_suffix("IB")
+        .or_else(|| upper.strip_suffix('B'))
+        .unwrap_or(&upper);
+    let (digits, multiplier) = match number.chars().last() {
+        Some('K') => (&number[..number.len() - 1], 1u64 << 10),
+        Some('M') => (&number[..number.len() - 1], 1u64 << 20),
+        Some('G') => (&number[..number.len() - 1], 1u64 << 30),
+        _ => (number, 1),
+    };
+
+    let value: u64 = digits
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{s}' is not a valid size (e.g. 500K, 100M, 2G)"))?;
+    if value < 1 {
+        return Err(format!("size must be at least 1 byte, got '{s}'"));
+    }
+
+    value
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("size '{s}' is too large"))
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::float_cmp)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_connections() {
+        assert_eq!(parse_connections("4").ok(), Some(4));
+        assert!(parse_connections("0").is_err());
+        assert!(parse_connections("17").is_err());
+    }
+
+    #[test]
+    fn test_parse_decimal_places() {
+        assert_eq!(parse_decimal_places("0").ok(), Some(0));
+        assert_eq!(parse_decimal_places("6").ok(), Some(6));
+        assert!(parse_decimal_places("10").is_err());
+        assert!(parse_decimal_places("-1").is_err());
+    }
+
     #[test]
     fn test_parse_confidence_valid() {
         assert_eq!(parse_confidence("0.5").ok(), Some(0.5));
@@ -136,6 +289,35 @@ mod tests {
         assert!(parse_batch_size("abc").is_err());
     }
 
+    #[test]
+    fn test_parse_sensitivity() {
+        assert_eq!(parse_sensitivity("1.25").ok(), Some(1.25));
+        assert_eq!(parse_sensitivity("0.5").ok(), Some(0.5));
+        assert!(parse_sensitivity("1.6").is_err());
+        assert!(parse_sensitivity("high").is_err());
+    }
+
+    #[test]
+    fn test_parse_sample_percent() {
+        assert_eq!(parse_sample_percent("5%").ok(), Some(5.0));
+        assert_eq!(parse_sample_percent("0.5").ok(), Some(0.5));
+        assert_eq!(parse_sample_percent("100 %").ok(), Some(100.0));
+        assert!(parse_sample_percent("0%").is_err());
+        assert!(parse_sample_percent("150%").is_err());
+        assert!(parse_sample_percent("most").is_err());
+    }
+
+    #[test]
+    fn test_parse_batch_size_setting() {
+        assert_eq!(parse_batch_size_setting("auto").ok(), Some(BatchSize::Auto));
+        assert_eq!(parse_batch_size_setting("AUTO").ok(), Some(BatchSize::Auto));
+        assert_eq!(
+            parse_batch_size_setting("16").ok(),
+            Some(BatchSize::Fixed(16))
+        );
+        assert!(parse_batch_size_setting("0").is_err());
+    }
+
     #[test]
     fn test_parse_batch_size_at_maximum() {
         assert_eq!(parse_batch_size("512").ok(), Some(MAX_BATCH_SIZE));
@@ -171,4 +353,29 @@ mod tests {
         assert_eq!(parse_batch_size(" 32 ").ok(), Some(32));
         assert_eq!(parse_batch_size("  64  ").ok(), Some(64));
     }
+
+    #[test]
+    fn test_parse_cpu_sessions() {
+        assert_eq!(parse_cpu_sessions("8").ok(), Some(8));
+        assert!(parse_cpu_sessions("0").is_err());
+        assert!(parse_cpu_sessions(&(MAX_CPU_SESSIONS + 1).to_string()).is_err());
+    }
+
+    #[test]
+    fn test_parse_row_count() {
+        assert_eq!(parse_row_count("1000000").ok(), Some(1_000_000));
+        assert!(parse_row_count("0").is_err());
+        assert!(parse_row_count("-5").is_err());
+    }
+
+    #[test]
+    fn test_parse_byte_size() {
+        assert_eq!(parse_byte_size("4096").ok(), Some(4096));
+        assert_eq!(parse_byte_size("500K").ok(), Some(500 * 1024));
+        assert_eq!(parse_byte_size("100mb").ok(), Some(100 * 1024 * 1024));
+        assert_eq!(parse_byte_size("2GiB").ok(), Some(2 * 1024 * 1024 * 1024));
+        assert!(parse_byte_size("0").is_err());
+        assert!(parse_byte_size("big").is_err());
+        assert!(parse_byte_size("M").is_err());
+    }
 }
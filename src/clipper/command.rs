@@ -12,16 +12,19 @@ use crate::constants::{clipper, output_extensions};
 use crate::output::{ClipExtractionEntry, ClipExtractionPayload, ResultType, emit_json_result};
 
 use super::{
-    ClipExtractor, DetectionGroup, ParsedDetection, WavWriter, group_detections,
-    parse_detection_file,
+    ClipExtractor, ClipWriter, DetectionGroup, ParsedDetection, group_detections,
+    parse_detection_file, top_per_species,
 };
 
 /// Execute the clip command.
 ///
 /// # Errors
 ///
-/// Returns an error if clip extraction fails.
+/// Returns an error if the clip format cannot be encoded or clip extraction
+/// fails.
 pub fn execute(args: &ClipArgs, output_mode: OutputMode) -> Result<(), Error> {
+    args.clip_format.check_encoder()?;
+
     // Detect mode based on presence of --start/--end
     if let (Some(start), Some(end)) = (args.start, args.end) {
         execute_direct_extraction(args, start, end, output_mode)
@@ -31,18 +34,51 @@ pub fn execute(args: &ClipArgs, output_mode: OutputMode) -> Result<(), Error> {
 }
 
 /// Execute clip extraction from CSV detection files.
+///
+/// All files are grouped first, so `--max-per-species` can pick the best
+/// clips across the whole set before any audio is read.
 #[allow(clippy::unnecessary_wraps)]
 fn execute_csv_mode(args: &ClipArgs, output_mode: OutputMode) -> Result<(), Error> {
-    let extractor = ClipExtractor::new();
-    let writer = WavWriter::new(args.output.clone());
+    let extractor = ClipExtractor::new().with_fades(args.fade_in, args.fade_out);
+    let writer = ClipWriter::new(args.output.clone()).with_format(args.clip_format);
     let is_json = output_mode.is_structured();
 
+    let mut planned: Vec<(usize, DetectionGroup)> = Vec::new();
+    let mut readable = Vec::with_capacity(args.files.len());
+    for (index, detection_file) in args.files.iter().enumerate() {
+        match plan_detection_file(detection_file, args) {
+            Ok(groups) => {
+                planned.extend(groups.into_iter().map(|group| (index, group)));
+                readable.push(index);
+            }
+            Err(e) => {
+                warn!("Failed to process {}: {e}", detection_file.display());
+            }
+        }
+    }
+
+    if let Some(max) = args.max_per_species {
+        let before = planned.len();
+        planned = top_per_species(planned, max as usize);
+        info!(
+            "Keeping {} of {before} clips (at most {max} per species)",
+            planned.len()
+        );
+    }
+
     let mut total_clips = 0;
     let mut total_files = 0;
     let mut all_clips: Vec<ClipExtractionEntry> = Vec::new();
 
-    for detection_file in &args.files {
-        match process_detection_file(detection_file, args, &extractor, &writer, is_json) {
+    let mut groups_by_file: Vec<Vec<DetectionGroup>> = vec![Vec::new(); args.files.len()];
+    for (index, group) in planned {
+        groups_by_file[index].push(group);
+    }
+
+    for index in readable {
+        let detection_file = &args.files[index];
+        let groups = std::mem::take(&mut groups_by_file[index]);
+        match extract_groups(detection_file, &groups, args, &extractor, &writer, is_json) {
             Ok((clip_count, clips)) => {
                 total_clips += clip_count;
                 total_files += 1;
@@ -115,8 +151,8 @@ fn execute_direct_extraction(
     };
 
     // Extract and write clip
-    let extractor = ClipExtractor::new();
-    let writer = WavWriter::new(args.output.clone());
+    let extractor = ClipExtractor::new().with_fades(args.fade_in, args.fade_out);
+    let writer = ClipWriter::new(args.output.clone()).with_format(args.clip_format);
 
     let clip = extractor.extract_clip(audio_path, &group)?;
     let output_path = writer.write_clip(
@@ -154,13 +190,11 @@ fn execute_direct_extraction(
     Ok(())
 }
 
-fn process_detection_file(
+/// Read a detection file and group its detections into clips.
+fn plan_detection_file(
     detection_file: &Path,
     args: &ClipArgs,
-    extractor: &ClipExtractor,
-    writer: &WavWriter,
-    is_json: bool,
-) -> Result<(usize, Vec<ClipExtractionEntry>), Error> {
+) -> Result<Vec<DetectionGroup>, Error> {
     info!("Processing {}", detection_file.display());
 
     // Parse detections
@@ -178,7 +212,7 @@ fn process_detection_file(
             args.confidence,
             detection_file.display()
         );
-        return Ok((0, Vec::new()));
+        return Ok(Vec::new());
     }
 
     info!(
@@ -188,9 +222,27 @@ fn process_detection_file(
     );
 
     // Group detections
-    let groups = group_detections(filtered, args.pre, args.post);
+    let mut groups = group_detections(filtered, args.pre, args.post);
+    if let Some(min) = args.min_clip_confidence {
+        groups.retain(|g| g.max_confidence >= min);
+    }
 
     info!("Grouped into {} clips", groups.len());
+    Ok(groups)
+}
+
+/// Extract and write the clips of one detection file.
+fn extract_groups(
+    detection_file: &Path,
+    groups: &[DetectionGroup],
+    args: &ClipArgs,
+    extractor: &ClipExtractor,
+    writer: &ClipWriter,
+    is_json: bool,
+) -> Result<(usize, Vec<ClipExtractionEntry>), Error> {
+    if groups.is_empty() {
+        return Ok((0, Vec::new()));
+    }
 
     // Find source audio file
     let audio_path =
@@ -219,7 +271,7 @@ fn process_detection_file(
     let mut clip_count = 0;
     let mut clip_entries: Vec<ClipExtractionEntry> = Vec::new();
 
-    for group in &groups {
+    for group in groups {
         pb.set_message(group.scientific_name.clone());
 
         match extractor.extract_clip(&audio_path, group) {
@@ -283,7 +335,7 @@ fn process_detection_file(
 /// 1. Explicit --audio path if provided
 /// 2. Infer from detection filename in --base-dir (if provided)
 /// 3. Infer from detection filename in detection file's directory
-fn find_source_audio(
+pub(crate) fn find_source_audio(
     detection_file: &Path,
     explicit_audio: Option<&PathBuf>,
     base_dir: Option<&PathBuf>,
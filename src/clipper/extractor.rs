@@ -31,7 +31,12 @@ pub struct ExtractedClip {
 ///
 /// Note: Padding is applied during detection grouping, not during extraction.
 /// The extractor receives groups with already-padded time ranges.
-pub struct ClipExtractor;
+pub struct ClipExtractor {
+    /// Length of the linear fade-in at the start of each clip, in seconds.
+    fade_in: f64,
+    /// Length of the linear fade-out at the end of each clip, in seconds.
+    fade_out: f64,
+}
 
 impl Default for ClipExtractor {
     fn default() -> Self {
@@ -42,8 +47,20 @@ impl Default for ClipExtractor {
 impl ClipExtractor {
     /// Create a new clip extractor.
     #[must_use]
-    pub fn new() -> Self {
-        Self
+    pub const fn new() -> Self {
+        Self {
+            fade_in: 0.0,
+            fade_out: 0.0,
+        }
+    }
+
+    /// Fade each clip in and out linearly over the given seconds, so clips
+    /// cut in the middle of a sound do not start or end with a click.
+    #[must_use]
+    pub const fn with_fades(mut self, fade_in: f64, fade_out: f64) -> Self {
+        self.fade_in = fade_in;
+        self.fade_out = fade_out;
+        self
     }
 
     /// Extract a clip from the source audio file.
@@ -126,10 +143,12 @@ impl ClipExtractor {
 
         // Attempt to seek if start time is beyond threshold.
         // If seek fails, fall back to sequential decoding from start.
+        // Accurate mode reports the true timestamp of the packet it lands on,
+        // which a coarse seek only estimates in VBR MP3 files.
         let mut current_sample: u64 = 0;
         if group.start >= SEEK_THRESHOLD_SECS
             && let Ok(seeked_to) = format.seek(
-                SeekMode::Coarse,
+                SeekMode::Accurate,
                 SeekTo::Time {
                     time: Time::from(group.start),
                     track_id: Some(track_id),
@@ -213,9 +232,31 @@ impl ClipExtractor {
             }
         }
 
+        apply_fades(&mut samples, sample_rate, self.fade_in, self.fade_out);
+
         Ok(ExtractedClip {
             samples,
             sample_rate,
         })
     }
 }
+
+/// Ramp the gain of `samples` linearly from 0 over the first `fade_in`
+/// seconds and to 0 over the last `fade_out` seconds.
+///
+/// Fades longer than the clip are shortened to its length.
+fn apply_fades(samples: &mut [f32], sample_rate: u32, fade_in: f64, fade_out: f64) {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let frames = |secs: f64| ((secs * f64::from(sample_rate)) as usize).min(samples.len());
+    let (fade_in, fade_out) = (frames(fade_in), frames(fade_out));
+    let len = samples.len();
+
+    #[allow(clippy::cast_precision_loss)]
+    for (i, sample) in samples.iter_mut().take(fade_in).enumerate() {
+        *sample *= i as f32 / fade_in as f32;
+    }
+    #[allow(clippy::cast_precision_loss)]
+    for (i, sample) in samples[len - fade_out..].iter_mut().rev().enumerate() {
+        *sample *= i as f32 / fade_out as f32;
+    }
+}
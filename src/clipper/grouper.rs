@@ -138,6 +138,44 @@ pub fn group_detections(
     groups
 }
 
+/// Keep the `max_per_species` most confident groups of each species.
+///
+/// Each group is paired with a key, such as the detection file it came from,
+/// so the best clips can be chosen across many files at once. Groups keep
+/// their order; of equally confident groups the earlier one wins.
+#[must_use]
+pub fn top_per_species<K>(
+    groups: Vec<(K, DetectionGroup)>,
+    max_per_species: usize,
+) -> Vec<(K, DetectionGroup)> {
+    let mut ranked: Vec<usize> = (0..groups.len()).collect();
+    ranked.sort_by(|&a, &b| {
+        groups[b]
+            .1
+            .max_confidence
+            .total_cmp(&groups[a].1.max_confidence)
+            .then(a.cmp(&b))
+    });
+
+    let mut kept = vec![false; groups.len()];
+    let mut per_species: HashMap<&str, usize> = HashMap::new();
+    for index in ranked {
+        let count = per_species
+            .entry(groups[index].1.scientific_name.as_str())
+            .or_default();
+        if *count < max_per_species {
+            *count += 1;
+            kept[index] = true;
+        }
+    }
+
+    groups
+        .into_iter()
+        .zip(kept)
+        .filter_map(|(group, keep)| keep.then_some(group))
+        .collect()
+}
+
 /// Merge overlapping time ranges into consolidated ranges.
 ///
 /// Assumes ranges are already sorted by start time.
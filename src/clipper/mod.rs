@@ -11,6 +11,7 @@ mod parser;
 mod writer;
 
 pub use extractor::{ClipExtractor, ExtractedClip};
-pub use grouper::{DetectionGroup, group_detections};
+pub use grouper::{DetectionGroup, group_detections, top_per_species};
 pub use parser::{ParsedDetection, parse_detection_file};
-pub use writer::WavWriter;
+pub use writer::{ClipFormat, ClipWriter};
+pub(crate) use writer::{encode_wav, sanitize_filename};
@@ -3,7 +3,7 @@
 //! Parses birda CSV detection files to extract detection information
 //! for clip extraction. Uses the `csv` crate for robust parsing.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use serde::Deserialize;
 
@@ -22,6 +22,8 @@ struct DetectionRecord {
     common_name: String,
     #[serde(rename = "Confidence")]
     confidence: f32,
+    #[serde(rename = "File", default)]
+    file: Option<PathBuf>,
 }
 
 /// A detection parsed from a results file.
@@ -37,6 +39,8 @@ pub struct ParsedDetection {
     pub common_name: String,
     /// Detection confidence (0.0-1.0).
     pub confidence: f32,
+    /// Source audio file from the `File` column, if present.
+    pub file: Option<PathBuf>,
 }
 
 /// Parse a detection file and return detections.
@@ -90,6 +94,7 @@ pub fn parse_detection_file(path: &Path) -> Result<Vec<ParsedDetection>, Error>
             scientific_name: record.scientific_name,
             common_name: record.common_name,
             confidence: record.confidence,
+            file: record.file.filter(|f| !f.as_os_str().is_empty()),
         });
     }
 
@@ -1,28 +1,129 @@
-//! WAV file writing.
+//! Clip file writing.
 //!
-//! Writes audio clips to WAV files organized by species.
+//! Writes audio clips organized by species. WAV is written directly; FLAC,
+//! MP3 and Ogg Vorbis are encoded by piping WAV through `ffmpeg`.
 
 use std::fs;
+use std::io::{Cursor, Seek, Write};
 use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
 use hound::{SampleFormat, WavSpec, WavWriter as HoundWriter};
 
 use crate::Error;
+use crate::constants::clipper::FFMPEG;
 
-/// Writes audio data to WAV files.
-pub struct WavWriter {
+/// Audio format of written clips.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ClipFormat {
+    /// Uncompressed 16-bit PCM WAV.
+    #[default]
+    Wav,
+    /// Lossless FLAC.
+    Flac,
+    /// MP3 (VBR, about 190 kbps).
+    Mp3,
+    /// Ogg Vorbis (quality 5, about 160 kbps).
+    Ogg,
+}
+
+impl ClipFormat {
+    /// File extension of clips in this format.
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Wav => "wav",
+            Self::Flac => "flac",
+            Self::Mp3 => "mp3",
+            Self::Ogg => "ogg",
+        }
+    }
+
+    /// `ffmpeg` encoder arguments, or `None` for WAV, which is written directly.
+    const fn encoder_args(self) -> Option<&'static [&'static str]> {
+        match self {
+            Self::Wav => None,
+            Self::Flac => Some(&["-c:a", "flac"]),
+            Self::Mp3 => Some(&["-c:a", "libmp3lame", "-q:a", "2"]),
+            Self::Ogg => Some(&["-c:a", "libvorbis", "-q:a", "5"]),
+        }
+    }
+
+    /// Check that clips in this format can be encoded.
+    ///
+    /// WAV is always available. The other formats need `ffmpeg` on the
+    /// `PATH`, built with their encoder; checking up front fails the command
+    /// once with a clear error instead of once per clip.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `ffmpeg` cannot be run or lacks the encoder.
+    pub fn check_encoder(self) -> Result<(), Error> {
+        let Some(args) = self.encoder_args() else {
+            return Ok(());
+        };
+        let encoder = args[1];
+        let unavailable = |reason: String| Error::ClipEncoderUnavailable {
+            format: self.extension().to_string(),
+            program: FFMPEG,
+            encoder,
+            reason,
+        };
+
+        let output = Command::new(FFMPEG)
+            .args(["-hide_banner", "-encoders"])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .map_err(|e| {
+                unavailable(format!(
+                    "cannot run {FFMPEG} ({e}); install it or write WAV clips"
+                ))
+            })?;
+        if !output.status.success() {
+            return Err(unavailable(format!(
+                "{FFMPEG} -encoders exited with {}",
+                output.status
+            )));
+        }
+        let listed = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .any(|line| line.split_whitespace().nth(1) == Some(encoder));
+        if listed {
+            Ok(())
+        } else {
+            Err(unavailable(format!(
+                "this {FFMPEG} build does not include it"
+            )))
+        }
+    }
+}
+
+/// Writes audio clips to files.
+pub struct ClipWriter {
     /// Output directory for clips.
     output_dir: PathBuf,
+    /// Audio format of the clips.
+    format: ClipFormat,
 }
 
-impl WavWriter {
-    /// Create a new WAV writer with the given output directory.
+impl ClipWriter {
+    /// Create a new WAV clip writer with the given output directory.
     #[must_use]
     pub fn new(output_dir: PathBuf) -> Self {
-        Self { output_dir }
+        Self {
+            output_dir,
+            format: ClipFormat::Wav,
+        }
+    }
+
+    /// Write clips in `format` instead.
+    #[must_use]
+    pub const fn with_format(mut self, format: ClipFormat) -> Self {
+        self.format = format;
+        self
     }
 
-    /// Write audio samples to a WAV file.
+    /// Write audio samples to a clip file.
     ///
     /// Creates a species subdirectory and writes the clip with a descriptive
     /// filename containing species, confidence, and time range.
@@ -60,11 +161,19 @@ impl WavWriter {
         })?;
 
         // Generate filename
-        let filename = generate_filename(&safe_species, confidence, start_time, end_time);
+        let filename = generate_filename(
+            &safe_species,
+            confidence,
+            start_time,
+            end_time,
+            self.format.extension(),
+        );
         let output_path = species_dir.join(filename);
 
-        // Write WAV file
-        write_wav_file(&output_path, samples, sample_rate)?;
+        match self.format.encoder_args() {
+            None => write_wav_file(&output_path, samples, sample_rate)?,
+            Some(encoder) => encode_with_ffmpeg(&output_path, samples, sample_rate, encoder)?,
+        }
 
         Ok(output_path)
     }
@@ -74,7 +183,7 @@ impl WavWriter {
 ///
 /// Replaces characters that are invalid in filenames across platforms
 /// and prevents path traversal attacks.
-fn sanitize_filename(name: &str) -> String {
+pub(crate) fn sanitize_filename(name: &str) -> String {
     let sanitized: String = name
         .chars()
         .map(|c| match c {
@@ -89,49 +198,134 @@ fn sanitize_filename(name: &str) -> String {
 
 /// Generate a filename for a clip.
 ///
-/// Format: `species_confidence_start-end.wav`
+/// Format: `species_confidence_start-end.extension`
 /// Example: `Parus major_85p_10.5-13.5.wav`
-fn generate_filename(species: &str, confidence: f32, start_time: f64, end_time: f64) -> String {
+fn generate_filename(
+    species: &str,
+    confidence: f32,
+    start_time: f64,
+    end_time: f64,
+    extension: &str,
+) -> String {
     #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
     let confidence_pct = (confidence * 100.0).round() as u32;
-    format!("{species}_{confidence_pct}p_{start_time:.1}-{end_time:.1}.wav")
+    format!("{species}_{confidence_pct}p_{start_time:.1}-{end_time:.1}.{extension}")
 }
 
-/// Write samples to a WAV file.
-fn write_wav_file(path: &Path, samples: &[f32], sample_rate: u32) -> Result<(), Error> {
-    let spec = WavSpec {
+/// WAV format of written clips: mono 16-bit PCM.
+const fn wav_spec(sample_rate: u32) -> WavSpec {
+    WavSpec {
         channels: 1,
         sample_rate,
         bits_per_sample: 16,
         sample_format: SampleFormat::Int,
-    };
+    }
+}
+
+/// Write samples to a WAV file.
+fn write_wav_file(path: &Path, samples: &[f32], sample_rate: u32) -> Result<(), Error> {
+    HoundWriter::create(path, wav_spec(sample_rate))
+        .and_then(|writer| write_samples(writer, samples))
+        .map_err(|e| Error::WavWriteFailed {
+            path: path.to_path_buf(),
+            source: e,
+        })
+}
 
-    let mut writer = HoundWriter::create(path, spec).map_err(|e| Error::WavWriteFailed {
+/// Encode samples with `ffmpeg` using the `encoder` arguments.
+///
+/// The samples are piped in as WAV, so the clip matches a WAV clip decoded.
+fn encode_with_ffmpeg(
+    path: &Path,
+    samples: &[f32],
+    sample_rate: u32,
+    encoder: &[&str],
+) -> Result<(), Error> {
+    let error = |reason: String| Error::ClipEncodeFailed {
+        path: path.to_path_buf(),
+        reason,
+    };
+    let wav = encode_wav(samples, sample_rate).map_err(|e| Error::WavWriteFailed {
         path: path.to_path_buf(),
         source: e,
     })?;
 
-    // Convert f32 samples to i16
+    let mut child = Command::new(FFMPEG)
+        .args([
+            "-hide_banner",
+            "-loglevel",
+            "error",
+            "-y",
+            "-f",
+            "wav",
+            "-i",
+            "pipe:0",
+        ])
+        .args(encoder)
+        .arg(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            error(format!(
+                "cannot run {FFMPEG} ({e}); install it or write WAV clips"
+            ))
+        })?;
+
+    // Write stdin on a separate thread so errors ffmpeg prints while
+    // reading cannot deadlock on full pipes
+    let stdin = child.stdin.take();
+    let writer = std::thread::spawn(move || -> std::io::Result<()> {
+        if let Some(mut stdin) = stdin {
+            stdin.write_all(&wav)?;
+        }
+        Ok(())
+    });
+
+    let output = child.wait_with_output().map_err(|e| error(e.to_string()))?;
+    let written = writer.join();
+    if !output.status.success() {
+        let _ = fs::remove_file(path);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(error(format!(
+            "{FFMPEG} exited with {}: {}",
+            output.status,
+            stderr.trim()
+        )));
+    }
+    match written {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => Err(error(format!("failed to write audio to {FFMPEG}: {e}"))),
+        Err(_) => Err(error("audio writer thread panicked".to_string())),
+    }
+}
+
+/// Encode samples as an in-memory WAV file, e.g. for embedding in a report.
+pub(crate) fn encode_wav(samples: &[f32], sample_rate: u32) -> hound::Result<Vec<u8>> {
+    let mut cursor = Cursor::new(Vec::new());
+    write_samples(
+        HoundWriter::new(&mut cursor, wav_spec(sample_rate))?,
+        samples,
+    )?;
+    Ok(cursor.into_inner())
+}
+
+/// Convert f32 samples to i16 and finish the WAV file.
+fn write_samples<W: Write + Seek>(
+    mut writer: HoundWriter<W>,
+    samples: &[f32],
+) -> hound::Result<()> {
     for &sample in samples {
         #[allow(clippy::cast_possible_truncation)]
         let sample_i16 = (sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16;
-        writer
-            .write_sample(sample_i16)
-            .map_err(|e| Error::WavWriteFailed {
-                path: path.to_path_buf(),
-                source: e,
-            })?;
+        writer.write_sample(sample_i16)?;
     }
-
-    writer.finalize().map_err(|e| Error::WavWriteFailed {
-        path: path.to_path_buf(),
-        source: e,
-    })?;
-
-    Ok(())
+    writer.finalize()
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
 
@@ -153,9 +347,32 @@ mod tests {
         assert_eq!(sanitize_filename("sp."), "sp.");
     }
 
+    #[test]
+    fn test_wav_needs_no_encoder() {
+        assert!(ClipFormat::Wav.check_encoder().is_ok());
+    }
+
+    #[test]
+    fn test_encode_wav() {
+        let bytes = encode_wav(&[0.0, 0.5, -0.5], 48_000).unwrap();
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(bytes.len(), 44 + 3 * 2);
+    }
+
     #[test]
     fn test_generate_filename() {
-        let filename = generate_filename("Species", 0.8542, 10.5, 13.5);
+        let filename = generate_filename("Species", 0.8542, 10.5, 13.5, "wav");
         assert_eq!(filename, "Species_85p_10.5-13.5.wav");
+        let filename = generate_filename("Species", 0.8542, 10.5, 13.5, "flac");
+        assert_eq!(filename, "Species_85p_10.5-13.5.flac");
+    }
+
+    #[test]
+    fn test_clip_format_encoders() {
+        assert!(ClipFormat::Wav.encoder_args().is_none());
+        for format in [ClipFormat::Flac, ClipFormat::Mp3, ClipFormat::Ogg] {
+            assert!(format.encoder_args().is_some());
+        }
+        assert_eq!(ClipFormat::Ogg.extension(), "ogg");
     }
 }
@@ -0,0 +1,216 @@
+//! Config file diagnostics for `config validate`.
+//!
+//! Loading stops at the first problem; checking collects every problem it can
+//! find, including unknown keys, missing files and invalid profiles, so all of
+//! them can be fixed in one go.
+
+use super::file::{resolve_paths, unknown_config_keys};
+use super::{Config, apply_profile, validate_config, validate_model_config};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// How serious a config problem is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticSeverity {
+    /// The config cannot be used as is.
+    Error,
+    /// The config works but probably not as intended.
+    Warning,
+}
+
+/// One problem found in the config file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigDiagnostic {
+    /// How serious the problem is.
+    pub severity: DiagnosticSeverity,
+    /// Dotted key the problem is about, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// What is wrong and how to fix it.
+    pub message: String,
+}
+
+impl ConfigDiagnostic {
+    fn error(key: Option<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: DiagnosticSeverity::Error,
+            key,
+            message: message.into(),
+        }
+    }
+
+    fn warning(key: Option<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: DiagnosticSeverity::Warning,
+            key,
+            message: message.into(),
+        }
+    }
+}
+
+/// Check the config file at `path` and list every problem found.
+///
+/// A missing file is reported as a warning, since defaults are used then.
+///
+/// # Errors
+///
+/// Returns an error if the file exists but cannot be read.
+pub fn check_config_file(path: &Path) -> Result<Vec<ConfigDiagnostic>> {
+    if !path.exists() {
+        return Ok(vec![ConfigDiagnostic::warning(
+            None,
+            "config file does not exist; defaults are used (create it with 'birda config init')",
+        )]);
+    }
+    let contents = std::fs::read_to_string(path).map_err(|e| Error::ConfigRead {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    Ok(check_config(&contents, path))
+}
+
+/// Check config file `contents` read from `path`.
+fn check_config(contents: &str, path: &Path) -> Vec<ConfigDiagnostic> {
+    let table: toml::Table = match toml::from_str(contents) {
+        Ok(table) => table,
+        Err(e) => return vec![ConfigDiagnostic::error(None, e.to_string())],
+    };
+    let mut config: Config = match toml::from_str(contents) {
+        Ok(config) => config,
+        Err(e) => return vec![ConfigDiagnostic::error(None, e.to_string())],
+    };
+
+    let mut diagnostics: Vec<ConfigDiagnostic> = unknown_config_keys(&table, &config)
+        .into_iter()
+        .map(|(key, suggestion)| {
+            let message = suggestion.map_or_else(
+                || "unknown key".to_string(),
+                |s| format!("unknown key (did you mean '{s}'?)"),
+            );
+            ConfigDiagnostic::error(Some(key), message)
+        })
+        .collect();
+
+    if let Err(e) = resolve_paths(&mut config, path) {
+        diagnostics.push(ConfigDiagnostic::error(None, e.to_string()));
+        return diagnostics;
+    }
+    if let Err(e) = validate_config(&config) {
+        diagnostics.push(ConfigDiagnostic::error(None, e.to_string()));
+    }
+
+    for (name, model) in &config.models {
+        if let Err(e) = validate_model_config(name, model) {
+            diagnostics.push(ConfigDiagnostic::error(
+                Some(format!("models.{name}")),
+                e.to_string(),
+            ));
+        }
+    }
+
+    let files = [
+        ("defaults.meta_model", config.defaults.meta_model.as_ref()),
+        (
+            "defaults.species_list_file",
+            config.defaults.species_list_file.as_ref(),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(key, file)| file.map(|file| (key, file)))
+    .chain(
+        config
+            .sensitive
+            .lists
+            .iter()
+            .map(|file| ("sensitive.lists", file)),
+    );
+    for (key, file) in files {
+        if !file.exists() {
+            diagnostics.push(ConfigDiagnostic::error(
+                Some(key.to_string()),
+                format!("file not found: {}", file.display()),
+            ));
+        }
+    }
+
+    for name in config.profiles.keys() {
+        let mut profiled = config.clone();
+        if let Err(e) = apply_profile(&mut profiled, name).and_then(|()| validate_config(&profiled))
+        {
+            diagnostics.push(ConfigDiagnostic::error(
+                Some(format!("profiles.{name}")),
+                e.to_string(),
+            ));
+        }
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn errors(contents: &str) -> Vec<(Option<String>, String)> {
+        check_config(contents, Path::new("/tmp/config.toml"))
+            .into_iter()
+            .filter(|d| d.severity == DiagnosticSeverity::Error)
+            .map(|d| (d.key, d.message))
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_config_has_no_diagnostics() {
+        assert!(errors("[defaults]\nmin_confidence = 0.2\n").is_empty());
+    }
+
+    #[test]
+    fn test_reports_all_unknown_keys_with_suggestions() {
+        let errors = errors("[defaults]\nmin_confidnce = 0.2\nfoo = 1\n\n[inferense]\n");
+        assert_eq!(errors.len(), 3);
+        assert!(errors.contains(&(
+            Some("defaults.min_confidnce".to_string()),
+            "unknown key (did you mean 'defaults.min_confidence'?)".to_string()
+        )));
+        assert!(errors.contains(&(
+            Some("inferense".to_string()),
+            "unknown key (did you mean 'inference'?)".to_string()
+        )));
+    }
+
+    #[test]
+    fn test_reports_range_missing_files_and_profiles() {
+        let errors = errors(
+            "[defaults]\nmin_confidence = 1.5\nspecies_list_file = \"/nonexistent/species.txt\"\n\n[profiles.loose]\noverlap = -1.0\n",
+        );
+        assert!(
+            errors.iter().any(|(key, message)| key.is_none()
+                && message.contains("min_confidence must be between"))
+        );
+        assert!(errors.iter().any(|(key, message)| key.as_deref()
+            == Some("defaults.species_list_file")
+            && message.contains("/nonexistent/species.txt")));
+        assert!(
+            errors
+                .iter()
+                .any(|(key, _)| key.as_deref() == Some("profiles.loose"))
+        );
+    }
+
+    #[test]
+    fn test_syntax_error_is_reported() {
+        let errors = errors("[defaults\n");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].0.is_none());
+    }
+
+    #[test]
+    fn test_missing_file_is_a_warning() {
+        let diagnostics = check_config_file(Path::new("/nonexistent/config.toml")).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Warning);
+    }
+}
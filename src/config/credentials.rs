@@ -0,0 +1,299 @@
+//! Credentials for integrations, kept apart from the main config.
+//!
+//! Secrets (database URLs, MQTT passwords, webhook and Hugging Face tokens)
+//! live in `credentials.toml` in the config directory, which birda writes with
+//! owner-only permissions and refuses to read when other users can access it.
+//! The file holds tables of string values addressed by dotted keys
+//! (`mqtt.password`); a value of the form `${VAR}` is read from the
+//! environment instead, so a secret can come from a service manager or CI
+//! without being stored on disk.
+
+use crate::error::{Error, Result};
+use std::path::Path;
+
+/// Credentials loaded from a credentials file.
+#[derive(Debug, Clone, Default)]
+pub struct Credentials {
+    /// Raw file contents.
+    table: toml::Table,
+}
+
+impl Credentials {
+    /// Load credentials from `path`; a missing file has no credentials.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed, or (on Unix)
+    /// if users other than the owner can access it.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        check_permissions(path)?;
+        let contents = std::fs::read_to_string(path).map_err(|e| Error::CredentialsRead {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+        let table = contents.parse().map_err(|e| Error::CredentialsParse {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+        Ok(Self { table })
+    }
+
+    /// Value of dotted `key`, with `${VAR}` read from the environment.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value names an environment variable that is
+    /// not set.
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        let Some(toml::Value::String(value)) = lookup(&self.table, key) else {
+            return Ok(None);
+        };
+        let Some(var) = value
+            .strip_prefix("${")
+            .and_then(|rest| rest.strip_suffix('}'))
+        else {
+            return Ok(Some(value.clone()));
+        };
+        std::env::var(var)
+            .map(Some)
+            .map_err(|_| Error::CredentialEnvMissing {
+                key: key.to_string(),
+                var: var.to_string(),
+            })
+    }
+
+    /// Dotted keys of every credential, sorted. Values are never listed.
+    pub fn keys(&self) -> Vec<String> {
+        let mut keys = Vec::new();
+        collect_keys(&self.table, "", &mut keys);
+        keys.sort();
+        keys
+    }
+
+    /// Set dotted `key` to `value`, or remove it with `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `key` is empty or a part of it already holds a
+    /// value rather than a table.
+    pub fn set(&mut self, key: &str, value: Option<&str>) -> Result<()> {
+        let parts: Vec<&str> = key.split('.').collect();
+        let invalid = |reason: &str| Error::ConfigValidation {
+            message: format!("invalid credential key '{key}': {reason}"),
+        };
+        if parts.iter().any(|part| part.is_empty()) {
+            return Err(invalid("empty key part"));
+        }
+        let (name, tables) = parts.split_last().ok_or_else(|| invalid("empty key"))?;
+
+        let mut table = &mut self.table;
+        for part in tables {
+            if value.is_none() && !table.contains_key(*part) {
+                return Ok(());
+            }
+            table = table
+                .entry(part.to_string())
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+                .as_table_mut()
+                .ok_or_else(|| invalid(&format!("'{part}' is not a table")))?;
+        }
+        match value {
+            Some(value) => {
+                if table.get(*name).is_some_and(toml::Value::is_table) {
+                    return Err(invalid(&format!("'{name}' is a table")));
+                }
+                table.insert((*name).to_string(), toml::Value::String(value.to_string()));
+            }
+            None => {
+                table.remove(*name);
+            }
+        }
+        prune_empty_tables(&mut self.table);
+        Ok(())
+    }
+
+    /// Write the credentials to `path`, readable only by the owner.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let write_error = |e| Error::CredentialsWrite {
+            path: path.to_path_buf(),
+            source: e,
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(write_error)?;
+        }
+        let contents = toml::to_string_pretty(&self.table)
+            .map_err(|e| Error::ConfigSerialize { source: e })?;
+
+        // Write a sibling file created owner-only and rename it over the
+        // credentials, so a crash never leaves a truncated or readable file
+        let tmp_path = path.with_extension("toml.tmp");
+        let _ = std::fs::remove_file(&tmp_path);
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(crate::constants::credentials::FILE_MODE);
+        }
+        options
+            .open(&tmp_path)
+            .and_then(|mut file| {
+                std::io::Write::write_all(&mut file, contents.as_bytes())?;
+                file.sync_all()
+            })
+            .and_then(|()| std::fs::rename(&tmp_path, path))
+            .map_err(|e| {
+                let _ = std::fs::remove_file(&tmp_path);
+                write_error(e)
+            })
+    }
+}
+
+/// Value of dotted `key` in `table`.
+fn lookup<'a>(table: &'a toml::Table, key: &str) -> Option<&'a toml::Value> {
+    let mut parts = key.split('.');
+    let name = parts.next_back()?;
+    parts
+        .try_fold(table, |table, part| table.get(part)?.as_table())?
+        .get(name)
+}
+
+/// Collect dotted keys of the values in `table` into `keys`.
+fn collect_keys(table: &toml::Table, prefix: &str, keys: &mut Vec<String>) {
+    for (key, value) in table {
+        let path = format!("{prefix}{key}");
+        match value {
+            toml::Value::Table(nested) => collect_keys(nested, &format!("{path}."), keys),
+            _ => keys.push(path),
+        }
+    }
+}
+
+/// Remove tables left empty by removing credentials.
+fn prune_empty_tables(table: &mut toml::Table) {
+    table.retain(|_, value| match value {
+        toml::Value::Table(nested) => {
+            prune_empty_tables(nested);
+            !nested.is_empty()
+        }
+        _ => true,
+    });
+}
+
+/// Reject a credentials file that users other than the owner can access.
+#[cfg(unix)]
+fn check_permissions(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = std::fs::metadata(path)
+        .map_err(|e| Error::CredentialsRead {
+            path: path.to_path_buf(),
+            source: e,
+        })?
+        .permissions()
+        .mode()
+        & 0o777;
+    if mode & 0o077 == 0 {
+        Ok(())
+    } else {
+        Err(Error::CredentialsPermissions {
+            path: path.to_path_buf(),
+            mode,
+        })
+    }
+}
+
+/// File permissions are left to the platform's ACLs outside Unix.
+#[cfg(not(unix))]
+fn check_permissions(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_credentials_set_get_and_keys() {
+        let mut credentials = Credentials::default();
+        credentials.set("mqtt.password", Some("hunter2")).unwrap();
+        credentials
+            .set("huggingface.token", Some("hf_abc"))
+            .unwrap();
+        assert_eq!(
+            credentials.get("mqtt.password").unwrap().as_deref(),
+            Some("hunter2")
+        );
+        assert_eq!(credentials.get("mqtt.username").unwrap(), None);
+        assert_eq!(credentials.keys(), ["huggingface.token", "mqtt.password"]);
+
+        assert!(credentials.set("mqtt", Some("x")).is_err());
+        assert!(credentials.set("mqtt..password", Some("x")).is_err());
+
+        credentials.set("mqtt.password", None).unwrap();
+        assert_eq!(credentials.keys(), ["huggingface.token"]);
+        assert!(!credentials.table.contains_key("mqtt"));
+    }
+
+    #[test]
+    fn test_credentials_env_expansion() {
+        let mut credentials = Credentials::default();
+        credentials
+            .set("webhook.token", Some("${BIRDA_TEST_UNSET_WEBHOOK_TOKEN}"))
+            .unwrap();
+        assert!(matches!(
+            credentials.get("webhook.token"),
+            Err(Error::CredentialEnvMissing { .. })
+        ));
+
+        let path = std::env::var("PATH").unwrap();
+        credentials.set("webhook.token", Some("${PATH}")).unwrap();
+        assert_eq!(credentials.get("webhook.token").unwrap(), Some(path));
+    }
+
+    #[test]
+    fn test_credentials_file_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("credentials.toml");
+        assert!(Credentials::load(&path).unwrap().keys().is_empty());
+
+        let mut credentials = Credentials::default();
+        credentials
+            .set("postgres.url", Some("postgres://birda:secret@db/birda"))
+            .unwrap();
+        credentials.save(&path).unwrap();
+
+        let loaded = Credentials::load(&path).unwrap();
+        assert_eq!(
+            loaded.get("postgres.url").unwrap().as_deref(),
+            Some("postgres://birda:secret@db/birda")
+        );
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+            assert!(matches!(
+                Credentials::load(&path),
+                Err(Error::CredentialsPermissions { .. })
+            ));
+
+            // Saving replaces the file with an owner-only one
+            credentials.save(&path).unwrap();
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+            assert!(!path.with_extension("toml.tmp").exists());
+        }
+    }
+}
@@ -0,0 +1,300 @@
+//! Editing single keys of the configuration file.
+//!
+//! `config set` and `config unset` change only the line holding the key, so
+//! comments and layout of a hand-written config survive. When the key is not
+//! on a line of its own (inline tables, multi-line arrays), the file is
+//! rewritten from the edited table instead.
+
+use super::file::{parse_config, resolve_paths, write_config_file};
+use super::{Config, validate_config};
+use crate::error::{Error, Result};
+use std::path::Path;
+
+/// Look up the value of dotted `key` in `config`, including defaults.
+///
+/// Returns `None` for keys that are not set.
+pub fn get_config_value(config: &Config, key: &str) -> Option<toml::Value> {
+    let root = toml::Value::try_from(config).ok()?;
+    key.split('.')
+        .try_fold(&root, |value, part| value.get(part))
+        .cloned()
+}
+
+/// Set dotted `key` to `value` in the config file at `path`.
+///
+/// `value` is read as a TOML value (`0.3`, `true`, `["lat", "lon"]`) unless
+/// the key holds a string, and falls back to a plain string. The edited
+/// config is validated before it is written.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or written, `key` is not a
+/// config key, or the value is invalid for it.
+pub fn set_config_value(path: &Path, key: &str, value: &str) -> Result<Config> {
+    let contents = read_config_text(path)?;
+    let current = parse_config(&contents, path, true)?;
+    let value = parse_value(value, get_config_value(&current, key).as_ref());
+    edit_config(path, &contents, key, Some(value))
+}
+
+/// Remove dotted `key` from the config file at `path`, restoring its default.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or written, or the config
+/// is invalid without the key.
+pub fn unset_config_value(path: &Path, key: &str) -> Result<Config> {
+    let contents = read_config_text(path)?;
+    edit_config(path, &contents, key, None)
+}
+
+/// Config file contents, empty if the file does not exist yet.
+fn read_config_text(path: &Path) -> Result<String> {
+    if !path.exists() {
+        return Ok(String::new());
+    }
+    std::fs::read_to_string(path).map_err(|e| Error::ConfigRead {
+        path: path.to_path_buf(),
+        source: e,
+    })
+}
+
+/// Apply the edit to `contents`, validate the result and write it to `path`.
+fn edit_config(
+    path: &Path,
+    contents: &str,
+    key: &str,
+    value: Option<toml::Value>,
+) -> Result<Config> {
+    let mut expected: toml::Table = toml::from_str(contents).map_err(|e| Error::ConfigParse {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    apply_edit(&mut expected, key, value.clone())?;
+
+    // Keep the line edit only if it parses to exactly the edited table
+    let mut edited = edit_lines(contents, key, value.as_ref());
+    if !toml::from_str::<toml::Table>(&edited).is_ok_and(|t| t == expected) {
+        edited =
+            toml::to_string_pretty(&expected).map_err(|e| Error::ConfigSerialize { source: e })?;
+    }
+
+    let mut config = parse_config(&edited, path, true)?;
+    resolve_paths(&mut config, path)?;
+    validate_config(&config)?;
+    write_config_file(path, &edited)?;
+    Ok(config)
+}
+
+/// Set or remove dotted `key` in `table`, creating intermediate tables.
+pub(super) fn apply_edit(
+    table: &mut toml::Table,
+    key: &str,
+    value: Option<toml::Value>,
+) -> Result<()> {
+    let invalid = || Error::InvalidConfigKey {
+        key: key.to_string(),
+    };
+    let mut parts: Vec<&str> = key.split('.').collect();
+    let leaf = parts.pop().filter(|p| !p.is_empty()).ok_or_else(invalid)?;
+
+    let mut current = table;
+    for part in parts {
+        if part.is_empty() {
+            return Err(invalid());
+        }
+        if value.is_none() && !current.contains_key(part) {
+            return Ok(());
+        }
+        current = current
+            .entry(part)
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+            .ok_or_else(invalid)?;
+    }
+    match value {
+        Some(value) => {
+            current.insert(leaf.to_string(), value);
+        }
+        None => {
+            current.remove(leaf);
+        }
+    }
+    Ok(())
+}
+
+/// Read `raw` as a TOML value, keeping it a string where one is expected.
+pub(super) fn parse_value(raw: &str, current: Option<&toml::Value>) -> toml::Value {
+    if matches!(current, Some(toml::Value::String(_))) {
+        return toml::Value::String(raw.to_string());
+    }
+    format!("value = {raw}")
+        .parse::<toml::Table>()
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(raw.to_string()))
+}
+
+/// Edit the line of `contents` holding `key`, leaving all other lines as they
+/// are.
+///
+/// Lines are matched by their text only, so the caller checks that the result
+/// means what was intended.
+fn edit_lines(contents: &str, key: &str, value: Option<&toml::Value>) -> String {
+    let (table, leaf) = key.rsplit_once('.').unwrap_or(("", key));
+    let mut lines: Vec<String> = contents.lines().map(str::to_string).collect();
+
+    let mut section = String::new();
+    let mut section_start = None;
+    let mut first_header = None;
+    let mut found = None;
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            first_header.get_or_insert(i);
+            // Keys of array tables are never edited in place
+            section = if trimmed.starts_with("[[") {
+                "[[".to_string()
+            } else {
+                trimmed
+                    .trim_start_matches('[')
+                    .split(']')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string()
+            };
+            if section == table {
+                section_start = Some(i + 1);
+            }
+            continue;
+        }
+        if section == table
+            && trimmed
+                .split_once('=')
+                .is_some_and(|(k, _)| k.trim() == leaf)
+        {
+            found = Some(i);
+            break;
+        }
+    }
+
+    match (found, value) {
+        (Some(i), Some(value)) => {
+            let line = &lines[i];
+            let indent = &line[..line.len() - line.trim_start().len()];
+            // A trailing comment is kept when the text before it is the whole
+            // key-value pair
+            let comment = line
+                .find(" #")
+                .filter(|&at| line[..at].parse::<toml::Table>().is_ok())
+                .map_or("", |at| &line[at..]);
+            lines[i] = format!("{indent}{leaf} = {value}{comment}");
+        }
+        (Some(i), None) => {
+            lines.remove(i);
+        }
+        (None, Some(value)) => {
+            let line = format!("{leaf} = {value}");
+            if table.is_empty() {
+                lines.insert(first_header.unwrap_or(lines.len()), line);
+            } else if let Some(start) = section_start {
+                lines.insert(start, line);
+            } else {
+                if lines.last().is_some_and(|l| !l.trim().is_empty()) {
+                    lines.push(String::new());
+                }
+                lines.push(format!("[{table}]"));
+                lines.push(line);
+            }
+        }
+        (None, None) => {}
+    }
+
+    let mut edited = lines.join("\n");
+    edited.push('\n');
+    edited
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::float_cmp)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_keeps_comments() {
+        let contents = "# Field station\n[defaults]\n# Quiet site\nmin_confidence = 0.1 # raised later\noverlap = 0.0\n";
+        let value = toml::Value::Float(0.3);
+        let edited = edit_lines(contents, "defaults.min_confidence", Some(&value));
+        assert_eq!(
+            edited,
+            "# Field station\n[defaults]\n# Quiet site\nmin_confidence = 0.3 # raised later\noverlap = 0.0\n"
+        );
+    }
+
+    #[test]
+    fn test_set_adds_missing_key_and_table() {
+        let contents = "[defaults]\nmin_confidence = 0.1\n";
+        let value = toml::Value::Float(1.5);
+        let edited = edit_lines(contents, "defaults.overlap", Some(&value));
+        assert_eq!(edited, "[defaults]\noverlap = 1.5\nmin_confidence = 0.1\n");
+
+        let value = toml::Value::String("cuda".to_string());
+        let edited = edit_lines(contents, "inference.device", Some(&value));
+        assert_eq!(
+            edited,
+            "[defaults]\nmin_confidence = 0.1\n\n[inference]\ndevice = \"cuda\"\n"
+        );
+    }
+
+    #[test]
+    fn test_unset_removes_line() {
+        let contents = "[defaults]\n# Site latitude\nlatitude = 60.1\nlongitude = 24.9\n";
+        let edited = edit_lines(contents, "defaults.latitude", None);
+        assert_eq!(edited, "[defaults]\n# Site latitude\nlongitude = 24.9\n");
+    }
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(parse_value("0.3", None), toml::Value::Float(0.3));
+        assert_eq!(parse_value("true", None), toml::Value::Boolean(true));
+        assert_eq!(
+            parse_value("birdnet-v24", None),
+            toml::Value::String("birdnet-v24".to_string())
+        );
+        // Keys holding strings keep the raw text
+        let current = toml::Value::String("birdnet".to_string());
+        assert_eq!(
+            parse_value("2024", Some(&current)),
+            toml::Value::String("2024".to_string())
+        );
+    }
+
+    #[test]
+    fn test_set_and_unset_config_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "# My settings\n[defaults]\nmin_confidence = 0.1\n").unwrap();
+
+        let config = set_config_value(&path, "defaults.min_confidence", "0.25").unwrap();
+        assert!((config.defaults.min_confidence - 0.25).abs() < f32::EPSILON);
+        let config = set_config_value(&path, "defaults.latitude", "60.17").unwrap();
+        assert_eq!(config.defaults.latitude, Some(60.17));
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.starts_with("# My settings\n"));
+
+        let config = unset_config_value(&path, "defaults.latitude").unwrap();
+        assert_eq!(config.defaults.latitude, None);
+        assert_eq!(
+            get_config_value(&config, "defaults.min_confidence"),
+            Some(toml::Value::Float(0.25))
+        );
+
+        // Typos are rejected and the file is left alone
+        assert!(set_config_value(&path, "defaults.min_confidnce", "0.5").is_err());
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            contents.replace("latitude = 60.17\n", "")
+        );
+    }
+}
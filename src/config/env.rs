@@ -0,0 +1,213 @@
+//! Config keys set from `BIRDA_*` environment variables.
+//!
+//! `BIRDA_<TABLE>__<KEY>` sets `<table>.<key>` on top of the config file, so
+//! containers can be configured without mounting one. Variables without the
+//! `__` separator are command-line options (`BIRDA_MIN_CONFIDENCE`) and are
+//! left to clap.
+//!
+//! Uppercase names are lowercased, except where they match an existing key
+//! in another case, such as a `[models.MyModel]` table. Names written in
+//! mixed case (`BIRDA_MODELS__MyModel__PATH`) are kept as written.
+
+use super::Config;
+use super::edit::{apply_edit, parse_value};
+use super::file::{resolve_paths, unknown_config_keys};
+use crate::constants::config_env::{PREFIX, SEPARATOR};
+use crate::error::{Error, Result};
+use std::path::Path;
+
+/// Apply config keys set in the environment to `config`.
+///
+/// Values are read like `config set` values. Paths may start with `~`;
+/// relative paths are relative to the working directory.
+///
+/// # Errors
+///
+/// Returns an error if a variable names an unknown key or its value does not
+/// fit the key.
+pub fn apply_env_overrides(config: &mut Config) -> Result<()> {
+    apply_overrides(config, std::env::vars())
+}
+
+/// Dotted config key set by environment variable `var`, if it sets one.
+fn override_key(var: &str) -> Option<String> {
+    let rest = var.strip_prefix(PREFIX)?;
+    rest.contains(SEPARATOR).then(|| {
+        rest.split(SEPARATOR)
+            .map(|part| {
+                if part.chars().any(char::is_lowercase) {
+                    part.to_string()
+                } else {
+                    part.to_lowercase()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(".")
+    })
+}
+
+/// `key` with each part spelled like the existing key in `root` it matches
+/// ignoring case, so variables can name tables such as `models.MyModel`.
+fn match_existing_key(root: &toml::Value, key: &str) -> String {
+    let mut current = Some(root);
+    key.split('.')
+        .map(|part| {
+            let existing = current
+                .and_then(toml::Value::as_table)
+                .and_then(|table| table.iter().find(|(k, _)| k.eq_ignore_ascii_case(part)));
+            current = existing.map(|(_, value)| value);
+            existing.map_or_else(|| part.to_string(), |(k, _)| k.clone())
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Apply the overrides among `vars` to `config`.
+fn apply_overrides(
+    config: &mut Config,
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> Result<()> {
+    let mut overrides: Vec<(String, String, String)> = vars
+        .into_iter()
+        .filter_map(|(var, value)| override_key(&var).map(|key| (var, key, value)))
+        .collect();
+    if overrides.is_empty() {
+        return Ok(());
+    }
+    overrides.sort();
+
+    let toml::Value::Table(mut table) =
+        toml::Value::try_from(&*config).map_err(|e| Error::ConfigSerialize { source: e })?
+    else {
+        return Ok(());
+    };
+    let mut updated = config.clone();
+    for (var, key, raw) in overrides {
+        let invalid = |reason: String| Error::ConfigEnvOverride {
+            var: var.clone(),
+            reason,
+        };
+        let root = toml::Value::Table(table.clone());
+        let key = match_existing_key(&root, &key);
+        let current = key
+            .split('.')
+            .try_fold(&root, |value, part| value.get(part));
+        apply_edit(&mut table, &key, Some(parse_value(&raw, current)))
+            .map_err(|e| invalid(e.to_string()))?;
+        updated = toml::Value::Table(table.clone())
+            .try_into()
+            .map_err(|e: toml::de::Error| invalid(e.message().to_string()))?;
+        if let Some((unknown, suggestion)) =
+            unknown_config_keys(&table, &updated).into_iter().next()
+        {
+            return Err(invalid(suggestion.map_or_else(
+                || format!("unknown config key '{unknown}'"),
+                |s| format!("unknown config key '{unknown}' (did you mean '{s}'?)"),
+            )));
+        }
+    }
+
+    // Relative paths resolve against the working directory
+    resolve_paths(&mut updated, Path::new(""))?;
+    *config = updated;
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use crate::config::InferenceDevice;
+    use std::path::PathBuf;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_override_key() {
+        assert_eq!(
+            override_key("BIRDA_DEFAULTS__MIN_CONFIDENCE").as_deref(),
+            Some("defaults.min_confidence")
+        );
+        assert_eq!(
+            override_key("BIRDA_INFERENCE__WATCHDOG__TIMEOUT_SECS").as_deref(),
+            Some("inference.watchdog.timeout_secs")
+        );
+        // Mixed-case names are kept as written
+        assert_eq!(
+            override_key("BIRDA_MODELS__MyModel__PATH").as_deref(),
+            Some("models.MyModel.path")
+        );
+        // Command-line options and other programs' variables are not overrides
+        assert_eq!(override_key("BIRDA_MIN_CONFIDENCE"), None);
+        assert_eq!(override_key("HOME"), None);
+    }
+
+    #[test]
+    fn test_apply_overrides() {
+        let mut config = Config::default();
+        apply_overrides(
+            &mut config,
+            vars(&[
+                ("BIRDA_DEFAULTS__MIN_CONFIDENCE", "0.4"),
+                ("BIRDA_INFERENCE__DEVICE", "cuda"),
+                ("BIRDA_DEFAULTS__SPECIES_LIST_FILE", "lists/finland.txt"),
+                ("BIRDA_MIN_CONFIDENCE", "0.9"),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(config.defaults.min_confidence, 0.4);
+        assert_eq!(config.inference.device, InferenceDevice::Cuda);
+        assert_eq!(
+            config.defaults.species_list_file,
+            Some(PathBuf::from("lists/finland.txt"))
+        );
+    }
+
+    #[test]
+    fn test_overrides_match_existing_keys_ignoring_case() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [models.MyModel]
+            path = "model.onnx"
+            labels = "labels.txt"
+            type = "birdnet-v24"
+            "#,
+        )
+        .unwrap();
+        apply_overrides(
+            &mut config,
+            vars(&[
+                ("BIRDA_MODELS__MYMODEL__PATH", "other.onnx"),
+                ("BIRDA_DEFAULTS__MODEL", "MyModel"),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(config.models.len(), 1);
+        assert!(config.models["MyModel"].path.ends_with("other.onnx"));
+        assert_eq!(config.defaults.model.as_deref(), Some("MyModel"));
+    }
+
+    #[test]
+    fn test_invalid_overrides_name_the_variable() {
+        let mut config = Config::default();
+        let err = apply_overrides(
+            &mut config,
+            vars(&[("BIRDA_DEFAULTS__MIN_CONFIDNCE", "0.4")]),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("BIRDA_DEFAULTS__MIN_CONFIDNCE"));
+        assert!(err.contains("did you mean 'defaults.min_confidence'"));
+
+        let err = apply_overrides(&mut config, vars(&[("BIRDA_INFERENCE__DEVICE", "tpu")]))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("BIRDA_INFERENCE__DEVICE"));
+        assert_eq!(config.inference.device, InferenceDevice::default());
+    }
+}
@@ -1,13 +1,20 @@
 //! Configuration file loading.
 
+use super::paths::resolve_config_path;
 use crate::config::Config;
 use crate::error::{Error, Result};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use tracing::warn;
 
 /// Load configuration from a TOML file.
 ///
-/// Returns default config if the file does not exist.
-pub fn load_config_file(path: &Path) -> Result<Config> {
+/// Paths may start with `~` for the home directory or be relative to the
+/// config file's directory; they are resolved to full paths here. Returns
+/// default config if the file does not exist.
+///
+/// Unknown keys, usually typos, are an error if `strict`; otherwise they are
+/// logged as warnings and ignored.
+pub fn load_config_file(path: &Path, strict: bool) -> Result<Config> {
     if !path.exists() {
         return Ok(Config::default());
     }
@@ -17,21 +24,219 @@ pub fn load_config_file(path: &Path) -> Result<Config> {
         source: e,
     })?;
 
-    toml::from_str(&contents).map_err(|e| Error::ConfigParse {
+    let mut config = parse_config(&contents, path, strict)?;
+    resolve_paths(&mut config, path)?;
+    Ok(config)
+}
+
+/// Parse config file `contents`, rejecting unknown keys if `strict`.
+pub(super) fn parse_config(contents: &str, path: &Path, strict: bool) -> Result<Config> {
+    let parse_error = |e| Error::ConfigParse {
         path: path.to_path_buf(),
         source: e,
-    })
+    };
+    let table: toml::Table = toml::from_str(contents).map_err(parse_error)?;
+    let config: Config = toml::from_str(contents).map_err(parse_error)?;
+
+    for (key, suggestion) in unknown_config_keys(&table, &config) {
+        if strict {
+            return Err(Error::ConfigUnknownKey {
+                path: path.to_path_buf(),
+                hint: suggestion.map_or_else(
+                    || " (remove it or use --lenient-config)".to_string(),
+                    |s| format!(" (did you mean '{s}'?)"),
+                ),
+                key,
+            });
+        }
+        match suggestion {
+            Some(s) => warn!("Ignoring unknown config key '{key}' (did you mean '{s}'?)"),
+            None => warn!("Ignoring unknown config key '{key}'"),
+        }
+    }
+    Ok(config)
+}
+
+/// Keys of the raw config `table` that were ignored while deserializing it
+/// into `config`, each with the closest known key if it looks like a typo.
+pub(super) fn unknown_config_keys(
+    table: &toml::Table,
+    config: &Config,
+) -> Vec<(String, Option<String>)> {
+    // Every key the config uses survives a round trip, so keys that do not
+    // were ignored while deserializing
+    let Ok(toml::Value::Table(known)) = toml::Value::try_from(config) else {
+        return Vec::new();
+    };
+    let known = known_keys(&known, "");
+    unknown_keys(table, &known, "")
+        .into_iter()
+        .map(|key| {
+            let suggestion = suggest_key(&key, &known);
+            (key, suggestion)
+        })
+        .collect()
+}
+
+/// Dotted paths of every key in `table` and its nested tables.
+fn known_keys(table: &toml::Table, prefix: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    for (key, value) in table {
+        let path = format!("{prefix}{key}");
+        if let toml::Value::Table(nested) = value {
+            keys.extend(known_keys(nested, &format!("{path}.")));
+        }
+        keys.push(path);
+    }
+    keys
+}
+
+/// Dotted paths of the keys in `table` missing from `known`.
+///
+/// Keys below an unknown key are not reported separately.
+fn unknown_keys(table: &toml::Table, known: &[String], prefix: &str) -> Vec<String> {
+    let mut unknown = Vec::new();
+    for (key, value) in table {
+        let path = format!("{prefix}{key}");
+        if !known.contains(&path) {
+            unknown.push(path);
+        } else if let toml::Value::Table(nested) = value {
+            unknown.extend(unknown_keys(nested, known, &format!("{path}.")));
+        }
+    }
+    unknown
+}
+
+/// Closest known key in the same table as `key`, if close enough to be a typo.
+fn suggest_key(key: &str, known: &[String]) -> Option<String> {
+    let (prefix, name) = key.rsplit_once('.').map_or(("", key), |(p, n)| (p, n));
+    known
+        .iter()
+        .filter_map(|candidate| {
+            let (candidate_prefix, candidate_name) = candidate
+                .rsplit_once('.')
+                .map_or(("", candidate.as_str()), |(p, n)| (p, n));
+            (candidate_prefix == prefix).then_some((candidate, edit_distance(name, candidate_name)))
+        })
+        .filter(|&(_, distance)| distance <= (name.len() / 3).max(1))
+        .min_by_key(|&(_, distance)| distance)
+        .map(|(candidate, _)| candidate.clone())
+}
+
+/// Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Expand `~` and resolve relative paths in `config` against the directory of
+/// the config file at `config_path`.
+///
+/// The paths as written are remembered, and [`save_config`] writes them back.
+pub(super) fn resolve_paths(config: &mut Config, config_path: &Path) -> Result<()> {
+    let base_dir = config_path.parent().unwrap_or_else(|| Path::new(""));
+    let mut written = std::collections::BTreeMap::new();
+    for path in path_fields(config) {
+        let resolved =
+            resolve_config_path(path, base_dir).map_err(|reason| Error::ConfigPathResolve {
+                path: path.clone(),
+                config: config_path.to_path_buf(),
+                reason,
+            })?;
+        if resolved != *path {
+            written.insert(resolved.clone(), std::mem::replace(path, resolved));
+        }
+    }
+    config.written_paths = written;
+    Ok(())
+}
+
+/// Every path setting of `config` that is resolved on load.
+fn path_fields(config: &mut Config) -> Vec<&mut PathBuf> {
+    let mut paths = Vec::new();
+    for model in config.models.values_mut() {
+        paths.push(&mut model.path);
+        paths.push(&mut model.labels);
+        paths.extend(
+            [
+                &mut model.meta_model,
+                &mut model.bsg_calibration,
+                &mut model.bsg_migration,
+                &mut model.bsg_distribution_maps,
+            ]
+            .into_iter()
+            .flatten(),
+        );
+        if let Some(head) = &mut model.custom_head {
+            paths.push(&mut head.path);
+            paths.push(&mut head.labels);
+        }
+    }
+    paths.extend(
+        [
+            &mut config.defaults.meta_model,
+            &mut config.defaults.species_list_file,
+            &mut config.output.lock_dir,
+            &mut config.output.dead_letter_dir,
+            &mut config.registry.models_dir,
+        ]
+        .into_iter()
+        .flatten(),
+    );
+    paths.extend(&mut config.sensitive.lists);
+    paths
 }
 
 /// Load configuration from the default platform-specific path.
 ///
-/// Returns default config if no config file exists.
-pub fn load_default_config() -> Result<Config> {
-    super::config_file_path().map_or_else(|_| Ok(Config::default()), |path| load_config_file(&path))
+/// Returns default config if no config file exists. Keys set in `BIRDA_*`
+/// environment variables and then `profile`, if given, are applied on top;
+/// commands that save the config load it with [`load_config_file`] instead,
+/// so neither ends up in the saved file. Unknown keys are rejected if
+/// `strict`; saving commands always load strictly, since a re-saved config
+/// would silently lose the keys that were ignored.
+pub fn load_default_config(profile: Option<&str>, strict: bool) -> Result<Config> {
+    let mut config = super::config_file_path().map_or_else(
+        |_| Ok(Config::default()),
+        |path| load_config_file(&path, strict),
+    )?;
+    super::apply_env_overrides(&mut config)?;
+    if let Some(profile) = profile {
+        super::apply_profile(&mut config, profile)?;
+    }
+    Ok(config)
 }
 
-/// Save configuration to a TOML file.
+/// Save configuration to a TOML file, replacing any existing file atomically.
+///
+/// Paths that were resolved on load are written as they were in the file
+/// (`~/models/...`, `models/...`); paths set since are written in full.
 pub fn save_config(config: &Config, path: &Path) -> Result<()> {
+    let mut config = config.clone();
+    let written = std::mem::take(&mut config.written_paths);
+    for field in path_fields(&mut config) {
+        if let Some(original) = written.get(&*field) {
+            field.clone_from(original);
+        }
+    }
+    let contents =
+        toml::to_string_pretty(&config).map_err(|e| Error::ConfigSerialize { source: e })?;
+    write_config_file(path, &contents)
+}
+
+/// Write config file `contents` to `path`, replacing any existing file
+/// atomically.
+pub(super) fn write_config_file(path: &Path, contents: &str) -> Result<()> {
     // Create parent directories if they don't exist
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent).map_err(|e| Error::ConfigWrite {
@@ -40,13 +245,18 @@ pub fn save_config(config: &Config, path: &Path) -> Result<()> {
         })?;
     }
 
-    let contents =
-        toml::to_string_pretty(config).map_err(|e| Error::ConfigSerialize { source: e })?;
-
-    std::fs::write(path, contents).map_err(|e| Error::ConfigWrite {
-        path: path.to_path_buf(),
-        source: e,
-    })
+    // Write a sibling file and rename it over the config, so readers and
+    // crashes never see a partially written file
+    let tmp_path = path.with_extension("toml.tmp");
+    std::fs::write(&tmp_path, contents)
+        .and_then(|()| std::fs::rename(&tmp_path, path))
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&tmp_path);
+            Error::ConfigWrite {
+                path: path.to_path_buf(),
+                source: e,
+            }
+        })
 }
 
 /// Save configuration to the default platform-specific path.
@@ -60,13 +270,14 @@ pub fn save_default_config(config: &Config) -> Result<std::path::PathBuf> {
 #[allow(clippy::unwrap_used, clippy::float_cmp)]
 mod tests {
     use super::*;
+    use crate::config::BatchSize;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
     #[test]
     fn test_load_nonexistent_file_returns_default() {
         let path = Path::new("/nonexistent/path/config.toml");
-        let config = load_config_file(path);
+        let config = load_config_file(path, true);
         assert!(config.is_ok());
         let config = config.ok().unwrap();
         assert!(config.models.is_empty());
@@ -89,19 +300,183 @@ min_confidence = 0.25
         )
         .unwrap();
 
-        let config = load_config_file(file.path());
+        let config = load_config_file(file.path(), true);
         assert!(config.is_ok());
         let config = config.ok().unwrap();
         assert!(config.models.contains_key("test-model"));
         assert_eq!(config.defaults.min_confidence, 0.25);
     }
 
+    #[test]
+    fn test_load_custom_head() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"
+[models.owls]
+path = "/path/to/model.onnx"
+labels = "/path/to/labels.txt"
+type = "birdnet-v24"
+
+[models.owls.custom_head]
+path = "/path/to/owls.onnx"
+labels = "/path/to/owls.txt"
+"#
+        )
+        .unwrap();
+
+        let config = load_config_file(file.path(), true).unwrap();
+        let head = config.models["owls"].custom_head.as_ref().unwrap();
+        assert_eq!(head.path, Path::new("/path/to/owls.onnx"));
+        assert_eq!(head.labels, Path::new("/path/to/owls.txt"));
+    }
+
     #[test]
     fn test_load_invalid_toml_returns_error() {
         let mut file = NamedTempFile::new().unwrap();
         writeln!(file, "this is not valid toml {{{{").unwrap();
 
-        let config = load_config_file(file.path());
+        let config = load_config_file(file.path(), true);
         assert!(config.is_err());
     }
+
+    #[test]
+    fn test_save_config_replaces_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("config.toml");
+        let mut config = Config::default();
+        save_config(&config, &path).unwrap();
+
+        config.defaults.min_confidence = 0.5;
+        save_config(&config, &path).unwrap();
+
+        assert_eq!(
+            load_config_file(&path, true)
+                .unwrap()
+                .defaults
+                .min_confidence,
+            0.5
+        );
+        assert_eq!(
+            std::fs::read_dir(path.parent().unwrap()).unwrap().count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_load_resolves_relative_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let labels = std::env::temp_dir().join("labels.txt");
+        std::fs::write(
+            &path,
+            format!(
+                r#"
+[models.birdnet]
+path = "models/model.onnx"
+labels = '{}'
+type = "birdnet-v24"
+
+[defaults]
+species_list_file = "lists/species.txt"
+"#,
+                labels.display()
+            ),
+        )
+        .unwrap();
+
+        let config = load_config_file(&path, true).unwrap();
+        let model = &config.models["birdnet"];
+        assert_eq!(model.path, dir.path().join("models/model.onnx"));
+        assert_eq!(model.labels, labels);
+        assert_eq!(
+            config.defaults.species_list_file,
+            Some(dir.path().join("lists/species.txt"))
+        );
+    }
+
+    #[test]
+    fn test_save_keeps_paths_as_written() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            r#"
+[models.birdnet]
+path = "models/model.onnx"
+labels = "~/labels.txt"
+type = "birdnet-v24"
+"#,
+        )
+        .unwrap();
+
+        let mut config = load_config_file(&path, true).unwrap();
+        config.defaults.species_list_file = Some(dir.path().join("species.txt"));
+        save_config(&config, &path).unwrap();
+
+        let saved: toml::Table = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let model = &saved["models"]["birdnet"];
+        assert_eq!(model["path"].as_str(), Some("models/model.onnx"));
+        assert_eq!(model["labels"].as_str(), Some("~/labels.txt"));
+        assert_eq!(
+            saved["defaults"]["species_list_file"].as_str(),
+            dir.path().join("species.txt").to_str()
+        );
+        assert_eq!(
+            load_config_file(&path, true).unwrap().models["birdnet"].path,
+            dir.path().join("models/model.onnx")
+        );
+    }
+
+    #[test]
+    fn test_load_rejects_other_users_home() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            "[models.birdnet]\npath = \"~bob/model.onnx\"\nlabels = \"labels.txt\"\ntype = \"birdnet-v24\"\n",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            load_config_file(&path, true),
+            Err(Error::ConfigPathResolve { .. })
+        ));
+    }
+
+    #[test]
+    fn test_unknown_key_suggests_known_key() {
+        let path = Path::new("config.toml");
+        let err = parse_config("[defaults]\nmin_confidnce = 0.3\n", path, true).unwrap_err();
+        match err {
+            Error::ConfigUnknownKey { key, hint, .. } => {
+                assert_eq!(key, "defaults.min_confidnce");
+                assert!(hint.contains("did you mean 'defaults.min_confidence'"));
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+
+        let err = parse_config("colour = true\n", path, true).unwrap_err();
+        assert!(matches!(err, Error::ConfigUnknownKey { key, .. } if key == "colour"));
+    }
+
+    #[test]
+    fn test_lenient_parse_ignores_unknown_keys() {
+        let contents = "[defaults]\nmin_confidnce = 0.3\nbatch_size = 16\n";
+        let config = parse_config(contents, Path::new("config.toml"), false).unwrap();
+        assert_eq!(config.defaults.batch_size, Some(BatchSize::Fixed(16)));
+    }
+
+    #[test]
+    fn test_strict_parse_accepts_saved_config() {
+        let contents = toml::to_string_pretty(&Config::default()).unwrap();
+        assert!(parse_config(&contents, Path::new("config.toml"), true).is_ok());
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("min_confidnce", "min_confidence"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("overlap", "overlap"), 0);
+    }
 }
@@ -1,17 +1,31 @@
 //! Configuration loading and management.
 
 pub mod bat;
+mod check;
+mod credentials;
+mod edit;
+mod env;
 mod file;
 mod paths;
+mod profile;
 pub mod range_filter;
+mod redact;
 mod types;
 mod validate;
 
 pub use bat::{BatConfig, BatRegion};
+pub use check::{ConfigDiagnostic, DiagnosticSeverity, check_config_file};
+pub use credentials::Credentials;
+pub use edit::{get_config_value, set_config_value, unset_config_value};
+pub use env::apply_env_overrides;
 pub use file::{load_config_file, load_default_config, save_config, save_default_config};
-pub use paths::{config_dir, config_file_path, tensorrt_cache_dir};
+pub use paths::{config_dir, config_file_path, credentials_file_path, tensorrt_cache_dir};
+pub use profile::{ProfileConfig, apply_profile};
+pub use redact::{redact_secrets, redact_secrets_at};
 pub use types::{
-    Config, CsvColumnsConfig, DefaultsConfig, InferenceConfig, InferenceDevice, ModelConfig,
-    ModelType, OutputConfig, OutputFormat, OutputMode,
+    ActivityFormat, ArenaExtendStrategy, BatchSize, Config, CsvColumnsConfig, CustomHeadConfig,
+    DeadLetterMode, DefaultsConfig, EmbeddingFormat, InferenceConfig, InferenceDevice,
+    LowGpuMemAction, ModelConfig, ModelType, OutputConfig, OutputFormat, OutputMode, ParquetLayout,
+    RegistryConfig, SensitiveAction, SensitiveConfig, SkipPolicy, StationConfig, WatchdogConfig,
 };
-pub use validate::{get_model, validate_config, validate_model_config};
+pub use validate::{get_model, validate_config, validate_custom_head, validate_model_config};
@@ -2,8 +2,8 @@
 
 use crate::constants::{APP_NAME, tensorrt};
 use crate::error::{Error, Result};
-use directories::ProjectDirs;
-use std::path::PathBuf;
+use directories::{BaseDirs, ProjectDirs};
+use std::path::{Component, Path, PathBuf};
 
 /// Get the configuration directory for the current platform.
 ///
@@ -32,6 +32,12 @@ pub fn config_file_path() -> Result<PathBuf> {
     Ok(config_dir()?.join("config.toml"))
 }
 
+/// Get the full path to the credentials file, kept apart from the config so
+/// `config show` and shared configs never contain secrets.
+pub fn credentials_file_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join(crate::constants::credentials::FILE_NAME))
+}
+
 /// Get the `TensorRT` cache directory for engine and timing caches.
 ///
 /// Uses the platform cache directory since `TensorRT` engines are:
@@ -46,6 +52,32 @@ pub fn tensorrt_cache_dir() -> Result<PathBuf> {
     Ok(cache_dir()?.join(tensorrt::CACHE_DIR))
 }
 
+/// Resolve a path from a config file.
+///
+/// A leading `~` is the user's home directory, relative paths are relative to
+/// `base_dir` (the config file's directory) and absolute paths are kept.
+///
+/// # Errors
+///
+/// Returns a description of the problem for `~user` paths or when the home
+/// directory cannot be determined.
+pub fn resolve_config_path(path: &Path, base_dir: &Path) -> std::result::Result<PathBuf, String> {
+    let mut components = path.components();
+    match components.next() {
+        Some(Component::Normal(first)) if first == "~" => {
+            let home = BaseDirs::new()
+                .map(|dirs| dirs.home_dir().to_path_buf())
+                .ok_or("the home directory could not be determined")?;
+            Ok(home.join(components.as_path()))
+        }
+        Some(Component::Normal(first)) if first.to_string_lossy().starts_with('~') => {
+            Err("only '~' (your own home directory) is supported, not '~user'".to_string())
+        }
+        _ if path.is_relative() => Ok(base_dir.join(path)),
+        _ => Ok(path.to_path_buf()),
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
@@ -93,4 +125,23 @@ mod tests {
         // TensorRT cache should be under the cache directory, not config
         assert!(tensorrt.starts_with(&cache));
     }
+
+    #[test]
+    fn test_resolve_config_path() {
+        let base = Path::new("/etc/birda");
+        assert_eq!(
+            resolve_config_path(Path::new("models/model.onnx"), base).unwrap(),
+            base.join("models/model.onnx")
+        );
+        let absolute = std::env::temp_dir().join("model.onnx");
+        assert_eq!(resolve_config_path(&absolute, base).unwrap(), absolute);
+
+        let home = BaseDirs::new().unwrap().home_dir().to_path_buf();
+        assert_eq!(
+            resolve_config_path(Path::new("~/birda/labels.txt"), base).unwrap(),
+            home.join("birda").join("labels.txt")
+        );
+        assert_eq!(resolve_config_path(Path::new("~"), base).unwrap(), home);
+        assert!(resolve_config_path(Path::new("~bob/labels.txt"), base).is_err());
+    }
 }
@@ -0,0 +1,123 @@
+//! Named configuration profiles.
+//!
+//! A `[profiles.<name>]` table overrides a few defaults at once, so switching
+//! between e.g. a quick scan and an archival run is one `--profile` flag.
+
+use super::{Config, InferenceDevice, OutputFormat};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// Settings a profile overrides; unset fields keep the configured defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileConfig {
+    /// Model name, overriding `defaults.model`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+
+    /// Minimum confidence threshold, overriding `defaults.min_confidence`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_confidence: Option<f32>,
+
+    /// Segment overlap in seconds, overriding `defaults.overlap`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overlap: Option<f32>,
+
+    /// Output formats, overriding `defaults.formats`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formats: Option<Vec<OutputFormat>>,
+
+    /// Inference device, overriding `inference.device`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device: Option<InferenceDevice>,
+}
+
+/// Apply the overrides of profile `name` to `config`.
+///
+/// # Errors
+///
+/// Returns an error if the config has no profile `name`.
+pub fn apply_profile(config: &mut Config, name: &str) -> Result<()> {
+    let profile = config
+        .profiles
+        .get(name)
+        .cloned()
+        .ok_or_else(|| Error::ProfileNotFound {
+            name: name.to_string(),
+            available: if config.profiles.is_empty() {
+                "none configured".to_string()
+            } else {
+                config
+                    .profiles
+                    .keys()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            },
+        })?;
+
+    if let Some(model) = profile.model {
+        config.defaults.model = Some(model);
+    }
+    if let Some(min_confidence) = profile.min_confidence {
+        config.defaults.min_confidence = min_confidence;
+    }
+    if let Some(overlap) = profile.overlap {
+        config.defaults.overlap = overlap;
+    }
+    if let Some(formats) = profile.formats {
+        config.defaults.formats = formats;
+    }
+    if let Some(device) = profile.device {
+        config.inference.device = device;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::float_cmp)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+[defaults]
+model = "birdnet"
+min_confidence = 0.1
+formats = ["csv"]
+
+[profiles.quick-scan]
+min_confidence = 0.5
+device = "cpu"
+
+[profiles.archival]
+model = "perch"
+overlap = 1.5
+formats = ["csv", "parquet"]
+"#;
+
+    #[test]
+    fn test_apply_profile_overrides_defaults() {
+        let mut config: Config = toml::from_str(CONFIG).unwrap();
+        apply_profile(&mut config, "quick-scan").unwrap();
+        assert_eq!(config.defaults.min_confidence, 0.5);
+        assert_eq!(config.defaults.model.as_deref(), Some("birdnet"));
+        assert_eq!(config.inference.device, InferenceDevice::Cpu);
+
+        let mut config: Config = toml::from_str(CONFIG).unwrap();
+        apply_profile(&mut config, "archival").unwrap();
+        assert_eq!(config.defaults.model.as_deref(), Some("perch"));
+        assert_eq!(config.defaults.min_confidence, 0.1);
+        assert_eq!(config.defaults.overlap, 1.5);
+        assert_eq!(
+            config.defaults.formats,
+            vec![OutputFormat::Csv, OutputFormat::Parquet]
+        );
+    }
+
+    #[test]
+    fn test_unknown_profile_lists_available() {
+        let mut config: Config = toml::from_str(CONFIG).unwrap();
+        let err = apply_profile(&mut config, "quick").unwrap_err();
+        assert!(err.to_string().contains("archival, quick-scan"));
+    }
+}
@@ -97,14 +97,37 @@ pub fn build_range_filter_config(
     model_name: &str,
 ) -> Result<Option<RangeFilterConfig>> {
     // Get coordinates (CLI overrides config)
-    let latitude = args.lat.or(config.defaults.latitude);
-    let longitude = args.lon.or(config.defaults.longitude);
-
-    // Range filtering requires both coordinates
-    let (Some(latitude), Some(longitude)) = (latitude, longitude) else {
+    let location = args
+        .lat
+        .or(config.defaults.latitude)
+        .zip(args.lon.or(config.defaults.longitude));
+    if location.is_none() {
         return Ok(None); // No coordinates - range filtering disabled
-    };
+    }
+    range_filter_config_at(args, config, model_config, model_name, location)
+}
 
+/// Build a `RangeFilterConfig` without a location, for runs whose recordings
+/// are each filtered at the location in their metadata.
+///
+/// The other conditions of [`build_range_filter_config`] apply.
+pub fn build_recording_range_filter_config(
+    args: &AnalyzeArgs,
+    config: &Config,
+    model_config: &ModelConfig,
+    model_name: &str,
+) -> Result<Option<RangeFilterConfig>> {
+    range_filter_config_at(args, config, model_config, model_name, None)
+}
+
+/// Build a `RangeFilterConfig` for `location`, if time and meta model allow.
+fn range_filter_config_at(
+    args: &AnalyzeArgs,
+    config: &Config,
+    model_config: &ModelConfig,
+    model_name: &str,
+    location: Option<(f64, f64)>,
+) -> Result<Option<RangeFilterConfig>> {
     // Get week number: either from CLI or convert from month/day
     let week = if let Some(week) = args.week {
         week
@@ -216,8 +239,7 @@ pub fn build_range_filter_config(
     Ok(Some(RangeFilterConfig {
         meta_model_path,
         threshold,
-        latitude,
-        longitude,
+        location,
         month,
         day,
         rerank: args.rerank,
@@ -251,14 +273,14 @@ mod tests {
             bsg_calibration: None,
             bsg_migration: None,
             bsg_distribution_maps: None,
+            custom_head: None,
         };
 
         let result = build_range_filter_config(&args, &config, &model_config, "test-model");
 
         assert!(result.is_ok());
         let rf_config = result.unwrap().unwrap();
-        assert_eq!(rf_config.latitude, 60.1699);
-        assert_eq!(rf_config.longitude, 24.9384);
+        assert_eq!(rf_config.location, Some((60.1699, 24.9384)));
         assert_eq!(rf_config.threshold, 0.01); // Default threshold
         // Week 24 = day 175 → June 24
         assert_eq!(rf_config.month, 6);
@@ -286,14 +308,14 @@ mod tests {
             bsg_calibration: None,
             bsg_migration: None,
             bsg_distribution_maps: None,
+            custom_head: None,
         };
 
         let result = build_range_filter_config(&args, &config, &model_config, "test-model");
 
         assert!(result.is_ok());
         let rf_config = result.unwrap().unwrap();
-        assert_eq!(rf_config.latitude, 60.1699);
-        assert_eq!(rf_config.longitude, 24.9384);
+        assert_eq!(rf_config.location, Some((60.1699, 24.9384)));
         // June 15 → week 22 → day 160 → June 9 (precision loss in round-trip)
         assert_eq!(rf_config.month, 6);
         assert_eq!(rf_config.day, 9);
@@ -319,6 +341,7 @@ mod tests {
             bsg_calibration: None,
             bsg_migration: None,
             bsg_distribution_maps: None,
+            custom_head: None,
         };
 
         let result = build_range_filter_config(&args, &config, &model_config, "test-model");
@@ -326,8 +349,7 @@ mod tests {
         assert!(result.is_ok());
         let rf_config = result.unwrap().unwrap();
         // Should use config defaults
-        assert_eq!(rf_config.latitude, 51.5074);
-        assert_eq!(rf_config.longitude, -0.1278);
+        assert_eq!(rf_config.location, Some((51.5074, -0.1278)));
     }
 
     #[test]
@@ -348,6 +370,7 @@ mod tests {
             bsg_calibration: None,
             bsg_migration: None,
             bsg_distribution_maps: None,
+            custom_head: None,
         };
 
         let result = build_range_filter_config(&args, &config, &model_config, "test-model");
@@ -356,6 +379,39 @@ mod tests {
         assert!(result.unwrap().is_none()); // Should be disabled
     }
 
+    #[test]
+    fn test_build_recording_range_filter_has_no_location() {
+        use crate::config::types::{Config, ModelConfig, ModelType};
+        use std::path::PathBuf;
+
+        let mut args = crate::cli::AnalyzeArgs::default();
+        args.lat = Some(60.1699);
+        args.lon = Some(24.9384);
+        args.week = Some(24);
+
+        let config = Config::default();
+
+        let model_config = ModelConfig {
+            path: PathBuf::from("test.onnx"),
+            labels: PathBuf::from("test.txt"),
+            model_type: ModelType::BirdnetV24,
+            meta_model: Some(PathBuf::from("meta.onnx")),
+            bsg_calibration: None,
+            bsg_migration: None,
+            bsg_distribution_maps: None,
+            custom_head: None,
+        };
+
+        let rf_config =
+            build_recording_range_filter_config(&args, &config, &model_config, "test-model")
+                .unwrap()
+                .unwrap();
+
+        // Each recording supplies its own location
+        assert!(rf_config.location.is_none());
+        assert_eq!(rf_config.month, 6);
+    }
+
     #[test]
     fn test_build_range_filter_disabled_without_time() {
         use crate::config::types::{Config, ModelConfig, ModelType};
@@ -375,6 +431,7 @@ mod tests {
             bsg_calibration: None,
             bsg_migration: None,
             bsg_distribution_maps: None,
+            custom_head: None,
         };
 
         let result = build_range_filter_config(&args, &config, &model_config, "test-model");
@@ -403,6 +460,7 @@ mod tests {
             bsg_calibration: None,
             bsg_migration: None,
             bsg_distribution_maps: None,
+            custom_head: None,
         };
 
         let result = build_range_filter_config(&args, &config, &model_config, "test-model");
@@ -434,6 +492,7 @@ mod tests {
                 bsg_calibration: None,
                 bsg_migration: None,
                 bsg_distribution_maps: None,
+                custom_head: None,
             },
         );
 
@@ -446,6 +505,7 @@ mod tests {
             bsg_calibration: None,
             bsg_migration: None,
             bsg_distribution_maps: None,
+            custom_head: None,
         };
 
         let result = build_range_filter_config(&args, &config, &model_config, "perch-v2");
@@ -482,6 +542,7 @@ mod tests {
                 bsg_calibration: Some(PathBuf::from("cal.csv")),
                 bsg_migration: Some(PathBuf::from("mig.csv")),
                 bsg_distribution_maps: Some(PathBuf::from("dist.bin")),
+                custom_head: None,
             },
         );
 
@@ -493,6 +554,7 @@ mod tests {
             bsg_calibration: None,
             bsg_migration: None,
             bsg_distribution_maps: None,
+            custom_head: None,
         };
 
         let result = build_range_filter_config(&args, &config, &model_config, "perch-v2");
@@ -525,6 +587,7 @@ mod tests {
                 bsg_calibration: None,
                 bsg_migration: None,
                 bsg_distribution_maps: None,
+                custom_head: None,
             },
         );
 
@@ -537,6 +600,7 @@ mod tests {
             bsg_calibration: None,
             bsg_migration: None,
             bsg_distribution_maps: None,
+            custom_head: None,
         };
 
         let result = build_range_filter_config(&args, &config, &model_config, "perch-v2");
@@ -574,6 +638,7 @@ mod tests {
                 bsg_calibration: None,
                 bsg_migration: None,
                 bsg_distribution_maps: None,
+                custom_head: None,
             },
         );
 
@@ -586,6 +651,7 @@ mod tests {
             bsg_calibration: None,
             bsg_migration: None,
             bsg_distribution_maps: None,
+            custom_head: None,
         };
 
         let result = build_range_filter_config(&args, &config, &model_config, "perch-v2");
@@ -622,6 +688,7 @@ mod tests {
             bsg_calibration: None,
             bsg_migration: None,
             bsg_distribution_maps: None,
+            custom_head: None,
         };
 
         let result = build_range_filter_config(&args, &config, &model_config, "birdnet-v24");
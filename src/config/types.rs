@@ -2,7 +2,7 @@
 
 use crate::constants::{DEFAULT_MIN_CONFIDENCE, DEFAULT_OVERLAP};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
 /// Complete application configuration.
@@ -24,6 +24,27 @@ pub struct Config {
     /// Output settings.
     #[serde(default)]
     pub output: OutputConfig,
+
+    /// Model registry settings.
+    #[serde(default)]
+    pub registry: RegistryConfig,
+
+    /// Sensitive species settings.
+    #[serde(default)]
+    pub sensitive: SensitiveConfig,
+
+    /// Identity of this birda instance.
+    #[serde(default)]
+    pub station: StationConfig,
+
+    /// Named profiles selected with `--profile`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, super::ProfileConfig>,
+
+    /// Paths as written in the config file, keyed by the full path they
+    /// were resolved to, so saving keeps `~` and relative paths.
+    #[serde(skip)]
+    pub(super) written_paths: BTreeMap<PathBuf, PathBuf>,
 }
 
 /// Configuration for a single model.
@@ -54,6 +75,24 @@ pub struct ModelConfig {
     /// BSG distribution maps binary file (required for BSG models).
     #[serde(default)]
     pub bsg_distribution_maps: Option<PathBuf>,
+
+    /// User-trained classifier applied to the model's embeddings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_head: Option<CustomHeadConfig>,
+}
+
+/// A user-trained classifier head run on top of a model's embeddings.
+///
+/// Configured as `[models.<name>.custom_head]`, e.g. a classifier trained
+/// with the `BirdNET`-Analyzer custom classifier workflow and exported to ONNX.
+/// Its predictions replace those of the model's own classification head.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomHeadConfig {
+    /// Path to the ONNX classifier, taking one embedding vector per segment.
+    pub path: PathBuf,
+
+    /// Path to the classifier's labels file, one label per output.
+    pub labels: PathBuf,
 }
 
 /// Default analysis settings.
@@ -69,12 +108,17 @@ pub struct DefaultsConfig {
     /// Segment overlap in seconds.
     pub overlap: f32,
 
+    /// Detection sensitivity (0.5-1.5), as in BirdNET-Analyzer; higher values
+    /// raise low confidences.
+    pub sensitivity: f32,
+
     /// Output formats.
     pub formats: Vec<OutputFormat>,
 
-    /// Batch size for inference. If None, a smart default based on model type
-    /// and execution provider will be used.
-    pub batch_size: Option<usize>,
+    /// Batch size for inference, or `"auto"` to benchmark sizes that fit in
+    /// free GPU memory. If None, a smart default based on model type and
+    /// execution provider will be used.
+    pub batch_size: Option<BatchSize>,
 
     /// Latitude for range filtering.
     pub latitude: Option<f64>,
@@ -103,6 +147,14 @@ pub struct DefaultsConfig {
     /// CSV column configuration.
     #[serde(default)]
     pub csv_columns: CsvColumnsConfig,
+
+    /// When to skip files that already have results.
+    #[serde(default)]
+    pub skip_policy: SkipPolicy,
+
+    /// `strftime` patterns for recording start times in file names, tried
+    /// before the built-in `YYYYMMDD_HHMMSS` convention.
+    pub timestamp_patterns: Vec<String>,
 }
 
 /// Default range filter threshold.
@@ -116,6 +168,7 @@ impl Default for DefaultsConfig {
             model: None,
             min_confidence: DEFAULT_MIN_CONFIDENCE,
             overlap: DEFAULT_OVERLAP,
+            sensitivity: crate::constants::sensitivity::DEFAULT,
             formats: vec![OutputFormat::Csv],
             batch_size: None, // Use smart defaults based on model/EP
             latitude: None,
@@ -125,6 +178,8 @@ impl Default for DefaultsConfig {
             species_list_file: None,
             day_of_year: None,
             csv_columns: CsvColumnsConfig::default(),
+            skip_policy: SkipPolicy::default(),
+            timestamp_patterns: Vec::new(),
         }
     }
 }
@@ -137,6 +192,80 @@ pub struct CsvColumnsConfig {
     pub include: Vec<String>,
 }
 
+/// Inference batch size setting.
+///
+/// Written as a number or `"auto"` in the config file and on the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "BatchSizeValue", into = "BatchSizeValue")]
+pub enum BatchSize {
+    /// Benchmark candidate sizes during warmup and keep the fastest that fits
+    /// in free GPU memory.
+    Auto,
+    /// Fixed number of segments per batch.
+    Fixed(usize),
+}
+
+impl BatchSize {
+    /// The fixed size, or `None` for auto.
+    pub const fn fixed(self) -> Option<usize> {
+        match self {
+            Self::Auto => None,
+            Self::Fixed(size) => Some(size),
+        }
+    }
+}
+
+impl std::fmt::Display for BatchSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Auto => write!(f, "auto"),
+            Self::Fixed(size) => write!(f, "{size}"),
+        }
+    }
+}
+
+impl std::str::FromStr for BatchSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("auto") {
+            return Ok(Self::Auto);
+        }
+        s.parse()
+            .map(Self::Fixed)
+            .map_err(|_| format!("invalid batch size '{s}', expected a number or 'auto'"))
+    }
+}
+
+/// Batch size as written in the config file.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum BatchSizeValue {
+    Number(usize),
+    Text(String),
+}
+
+impl TryFrom<BatchSizeValue> for BatchSize {
+    type Error = String;
+
+    fn try_from(value: BatchSizeValue) -> Result<Self, Self::Error> {
+        match value {
+            BatchSizeValue::Number(size) => Ok(Self::Fixed(size)),
+            BatchSizeValue::Text(text) => text.parse(),
+        }
+    }
+}
+
+impl From<BatchSize> for BatchSizeValue {
+    fn from(size: BatchSize) -> Self {
+        match size {
+            BatchSize::Auto => Self::Text(size.to_string()),
+            BatchSize::Fixed(size) => Self::Number(size),
+        }
+    }
+}
+
 /// Inference device configuration.
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -189,6 +318,93 @@ pub enum InferenceDevice {
 pub struct InferenceConfig {
     /// Device to use for inference.
     pub device: InferenceDevice,
+
+    /// Extra batch sizes to prewarm when `TensorRT` is active.
+    ///
+    /// Engines for these sizes are built (or loaded from cache) before
+    /// processing starts, so switching batch sizes between runs does not
+    /// trigger an engine rebuild.
+    pub tensorrt_warmup_batch_sizes: Vec<usize>,
+
+    /// Inference watchdog timeouts.
+    pub watchdog: WatchdogConfig,
+
+    /// Independent model sessions that split each batch when running on the
+    /// CPU (default: 1).
+    pub cpu_sessions: Option<usize>,
+
+    /// Most GPU memory the CUDA provider's arena may hold, e.g. `"4G"`.
+    pub gpu_mem_limit: Option<String>,
+
+    /// How the CUDA provider's memory arena grows (default: same-as-requested).
+    pub arena_extend_strategy: Option<ArenaExtendStrategy>,
+
+    /// Free GPU memory required before a run starts, e.g. `"2G"`, or `"auto"`
+    /// for the model's expected footprint (default: not checked).
+    pub min_free_gpu_mem: Option<String>,
+
+    /// What to do when the GPU fails the free memory check (default: fail).
+    pub low_gpu_mem: Option<LowGpuMemAction>,
+}
+
+/// Action taken when the GPU fails the pre-run memory check.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LowGpuMemAction {
+    /// Refuse to start.
+    #[default]
+    Fail,
+    /// Run on the CPU instead, with a warning.
+    Cpu,
+}
+
+impl std::fmt::Display for LowGpuMemAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fail => write!(f, "fail"),
+            Self::Cpu => write!(f, "cpu"),
+        }
+    }
+}
+
+/// Growth strategy of the GPU memory arena.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArenaExtendStrategy {
+    /// Grow by exactly what an allocation needs, keeping the footprint small.
+    #[default]
+    SameAsRequested,
+    /// Grow in power-of-two steps: fewer allocations, more memory held.
+    NextPowerOfTwo,
+}
+
+impl std::fmt::Display for ArenaExtendStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SameAsRequested => write!(f, "same-as-requested"),
+            Self::NextPowerOfTwo => write!(f, "next-power-of-two"),
+        }
+    }
+}
+
+/// Inference watchdog settings.
+///
+/// ```toml
+/// [inference.watchdog]
+/// timeout_secs = 15
+///
+/// [inference.watchdog.providers]
+/// tensorrt = 60
+/// cpu = 5
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchdogConfig {
+    /// Timeout in seconds for all providers (overrides built-in defaults).
+    pub timeout_secs: Option<u64>,
+
+    /// Per-provider timeouts in seconds, keyed by lowercase provider name.
+    pub providers: BTreeMap<String, u64>,
 }
 
 /// CLI output mode for structured output.
@@ -231,6 +447,50 @@ pub struct OutputConfig {
 
     /// Default CLI output format.
     pub default_format: OutputMode,
+
+    /// Never write anything (results or lock files) into input directories.
+    pub read_only_inputs: bool,
+
+    /// Central directory for lock files (hash-named) instead of beside outputs.
+    pub lock_dir: Option<PathBuf>,
+
+    /// How Parquet results are laid out on disk.
+    pub parquet_layout: ParquetLayout,
+
+    /// Decimal places for confidences in all result formats.
+    pub confidence_decimals: Option<usize>,
+
+    /// Decimal places for start and end times in all result formats.
+    pub time_decimals: Option<usize>,
+
+    /// What happens to recordings that fail to decode (default: nothing).
+    pub dead_letter: Option<DeadLetterMode>,
+
+    /// Directory failed recordings are moved or linked into, instead of
+    /// `_failed/` in each file's output directory.
+    pub dead_letter_dir: Option<PathBuf>,
+}
+
+/// Handling of recordings that fail to decode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum DeadLetterMode {
+    /// Move the recording into the dead-letter directory.
+    Move,
+    /// Link the recording from the dead-letter directory.
+    Symlink,
+    /// Only write an `.error.json` record beside the file's results.
+    Sidecar,
+}
+
+impl std::fmt::Display for DeadLetterMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Move => write!(f, "move"),
+            Self::Symlink => write!(f, "symlink"),
+            Self::Sidecar => write!(f, "sidecar"),
+        }
+    }
 }
 
 impl Default for OutputConfig {
@@ -238,6 +498,84 @@ impl Default for OutputConfig {
         Self {
             combined_prefix: "BirdNET".to_string(),
             default_format: OutputMode::Human,
+            read_only_inputs: false,
+            lock_dir: None,
+            parquet_layout: ParquetLayout::default(),
+            confidence_decimals: None,
+            time_decimals: None,
+            dead_letter: None,
+            dead_letter_dir: None,
+        }
+    }
+}
+
+/// Model registry settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RegistryConfig {
+    /// Directory models are installed into, instead of the platform data
+    /// directory (e.g. a shared lab location).
+    pub models_dir: Option<PathBuf>,
+    /// Additional registry JSON files (`http(s)://` URLs or local paths)
+    /// whose models are offered next to the bundled registry.
+    pub sources: Vec<String>,
+    /// Retries of a failed model file download (default: 3).
+    pub download_retries: Option<u32>,
+    /// Seconds before the first download retry, doubled per retry
+    /// (default: 2).
+    pub retry_delay_secs: Option<u64>,
+}
+
+/// Sensitive species settings.
+///
+/// Detections of listed species are flagged in the local results, which stay
+/// at full resolution, and `action` decides how they appear in combined
+/// outputs and `birda export` copies.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SensitiveConfig {
+    /// Species list files (e.g. national lists), one species per line as
+    /// `Genus species_Common Name` or `Genus species`.
+    pub lists: Vec<PathBuf>,
+    /// What shared outputs do with sensitive detections.
+    pub action: SensitiveAction,
+    /// Grid that `fuzz` snaps coordinates to, in degrees (default: 1.0).
+    pub grid: Option<f64>,
+}
+
+/// Identity of this birda instance.
+///
+/// Recorded in result files, run manifests, the runs log and JSON events, so
+/// results aggregated from several stations or cluster shards can be traced
+/// back to the instance that produced them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StationConfig {
+    /// Short stable identifier (letters, digits, `.`, `_` and `-`).
+    pub id: Option<String>,
+    /// Human-readable station name.
+    pub name: Option<String>,
+}
+
+/// Treatment of sensitive detections in shared outputs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SensitiveAction {
+    /// Only mark them in the `sensitive` column.
+    #[default]
+    Flag,
+    /// Leave them out.
+    Exclude,
+    /// Snap their coordinates to the coarse `grid`.
+    Fuzz,
+}
+
+impl std::fmt::Display for SensitiveAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Flag => write!(f, "flag"),
+            Self::Exclude => write!(f, "exclude"),
+            Self::Fuzz => write!(f, "fuzz"),
         }
     }
 }
@@ -248,6 +586,9 @@ impl Default for OutputConfig {
 pub enum OutputFormat {
     /// Generic CSV format.
     Csv,
+    /// BirdNET-Analyzer CSV, with its exact columns.
+    #[serde(rename = "birdnet-csv")]
+    BirdnetCsv,
     /// Raven selection table.
     Raven,
     /// Audacity labels.
@@ -258,17 +599,21 @@ pub enum OutputFormat {
     Json,
     /// Apache Parquet columnar format.
     Parquet,
+    /// SQLite database with runs, files and detections tables (one per run).
+    Sqlite,
 }
 
 impl std::fmt::Display for OutputFormat {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Csv => write!(f, "csv"),
+            Self::BirdnetCsv => write!(f, "birdnet-csv"),
             Self::Raven => write!(f, "raven"),
             Self::Audacity => write!(f, "audacity"),
             Self::Kaleidoscope => write!(f, "kaleidoscope"),
             Self::Json => write!(f, "json"),
             Self::Parquet => write!(f, "parquet"),
+            Self::Sqlite => write!(f, "sqlite"),
         }
     }
 }
@@ -279,11 +624,13 @@ impl std::str::FromStr for OutputFormat {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "csv" => Ok(Self::Csv),
+            "birdnet-csv" | "birdnet_csv" | "csv-legacy" => Ok(Self::BirdnetCsv),
             "raven" | "table" => Ok(Self::Raven),
             "audacity" => Ok(Self::Audacity),
             "kaleidoscope" => Ok(Self::Kaleidoscope),
             "json" => Ok(Self::Json),
             "parquet" => Ok(Self::Parquet),
+            "sqlite" => Ok(Self::Sqlite),
             other => Err(crate::error::Error::InvalidOutputFormat {
                 value: other.to_string(),
             }),
@@ -291,6 +638,92 @@ impl std::str::FromStr for OutputFormat {
     }
 }
 
+/// When to skip input files that already have results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum SkipPolicy {
+    /// Skip when all output files exist.
+    #[default]
+    Exists,
+    /// Skip when all outputs exist and are newer than the audio file.
+    Newer,
+    /// Skip when all outputs exist and the recorded run manifest matches the
+    /// current model and settings.
+    Hash,
+    /// Never skip; always reprocess.
+    Never,
+}
+
+impl std::fmt::Display for SkipPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Exists => write!(f, "exists"),
+            Self::Newer => write!(f, "newer"),
+            Self::Hash => write!(f, "hash"),
+            Self::Never => write!(f, "never"),
+        }
+    }
+}
+
+/// Layout of Parquet results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ParquetLayout {
+    /// One Parquet file beside each recording's other results.
+    #[default]
+    File,
+    /// One hive-style dataset (`species=.../date=.../part-N.parquet`) for the run.
+    Partitioned,
+}
+
+impl std::fmt::Display for ParquetLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::File => write!(f, "file"),
+            Self::Partitioned => write!(f, "partitioned"),
+        }
+    }
+}
+
+/// Output formats for the per-minute activity summary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ActivityFormat {
+    /// Generic CSV format.
+    Csv,
+    /// Apache Parquet columnar format.
+    Parquet,
+}
+
+impl std::fmt::Display for ActivityFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Csv => write!(f, "csv"),
+            Self::Parquet => write!(f, "parquet"),
+        }
+    }
+}
+
+/// Output formats for segment embeddings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingFormat {
+    /// Apache Parquet with segment times and one embedding per row.
+    #[default]
+    Parquet,
+    /// `NumPy` `.npy` float32 matrix (segments x dimensions).
+    Npy,
+}
+
+impl std::fmt::Display for EmbeddingFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parquet => write!(f, "parquet"),
+            Self::Npy => write!(f, "npy"),
+        }
+    }
+}
+
 /// Supported model types.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
 #[serde(rename_all = "kebab-case")]
@@ -337,13 +770,21 @@ impl std::str::FromStr for ModelType {
 }
 
 #[cfg(test)]
-#[allow(clippy::float_cmp)]
+#[allow(clippy::float_cmp, clippy::unwrap_used)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_output_format_from_str() {
         assert_eq!("csv".parse::<OutputFormat>().ok(), Some(OutputFormat::Csv));
+        assert_eq!(
+            "birdnet-csv".parse::<OutputFormat>().ok(),
+            Some(OutputFormat::BirdnetCsv)
+        );
+        assert_eq!(
+            "csv-legacy".parse::<OutputFormat>().ok(),
+            Some(OutputFormat::BirdnetCsv)
+        );
         assert_eq!(
             "raven".parse::<OutputFormat>().ok(),
             Some(OutputFormat::Raven)
@@ -364,12 +805,17 @@ mod tests {
             "json".parse::<OutputFormat>().ok(),
             Some(OutputFormat::Json)
         );
+        assert_eq!(
+            "sqlite".parse::<OutputFormat>().ok(),
+            Some(OutputFormat::Sqlite)
+        );
         assert!("unknown".parse::<OutputFormat>().is_err());
     }
 
     #[test]
     fn test_output_format_display() {
         assert_eq!(OutputFormat::Csv.to_string(), "csv");
+        assert_eq!(OutputFormat::BirdnetCsv.to_string(), "birdnet-csv");
         assert_eq!(OutputFormat::Raven.to_string(), "raven");
         assert_eq!(OutputFormat::Json.to_string(), "json");
     }
@@ -396,6 +842,24 @@ mod tests {
         assert_eq!(defaults.batch_size, None);
     }
 
+    #[test]
+    fn test_batch_size_from_config() {
+        #[derive(Deserialize, Serialize)]
+        struct Wrapper {
+            batch_size: BatchSize,
+        }
+
+        let fixed: Wrapper = toml::from_str("batch_size = 32").unwrap();
+        assert_eq!(fixed.batch_size, BatchSize::Fixed(32));
+        let auto: Wrapper = toml::from_str("batch_size = \"auto\"").unwrap();
+        assert_eq!(auto.batch_size, BatchSize::Auto);
+        assert!(toml::from_str::<Wrapper>("batch_size = \"big\"").is_err());
+        assert_eq!(
+            toml::to_string(&auto).unwrap().trim(),
+            "batch_size = \"auto\""
+        );
+    }
+
     #[test]
     fn test_defaults_with_species_list_file() {
         let defaults = DefaultsConfig {
@@ -1,13 +1,142 @@
 //! Configuration validation.
 
-use crate::config::{Config, ModelConfig};
-use crate::constants::confidence;
+use crate::config::{BatchSize, Config, CustomHeadConfig, ModelConfig, ModelType};
+use crate::constants::export::{MAX_GRID_DEGREES, MIN_GRID_DEGREES};
+use crate::constants::precision::MAX_DECIMAL_PLACES;
+use crate::constants::{MAX_BATCH_SIZE, MAX_CPU_SESSIONS, confidence, sensitivity, watchdog};
 use crate::error::{Error, Result};
 
 /// Validate the entire configuration.
 pub fn validate_config(config: &Config) -> Result<()> {
     validate_defaults(config)?;
+    validate_inference(config)?;
     validate_range_filter(config)?;
+    validate_sensitive(config)?;
+    validate_output(config)?;
+    validate_station(config)?;
+    Ok(())
+}
+
+/// Validate station identity settings.
+fn validate_station(config: &Config) -> Result<()> {
+    if let Some(id) = &config.station.id
+        && (id.is_empty()
+            || !id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-')))
+    {
+        return Err(Error::ConfigValidation {
+            message: format!("station.id must be letters, digits, '.', '_' or '-', got '{id}'"),
+        });
+    }
+    if config
+        .station
+        .name
+        .as_ref()
+        .is_some_and(|name| name.trim().is_empty())
+    {
+        return Err(Error::ConfigValidation {
+            message: "station.name must not be empty".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Validate output settings.
+fn validate_output(config: &Config) -> Result<()> {
+    let decimals = [
+        ("confidence_decimals", config.output.confidence_decimals),
+        ("time_decimals", config.output.time_decimals),
+    ];
+    for (name, value) in decimals {
+        if let Some(value) = value
+            && value > MAX_DECIMAL_PLACES
+        {
+            return Err(Error::ConfigValidation {
+                message: format!(
+                    "output.{name} must be between 0 and {MAX_DECIMAL_PLACES}, got {value}"
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Validate sensitive species settings.
+fn validate_sensitive(config: &Config) -> Result<()> {
+    if let Some(grid) = config.sensitive.grid
+        && !(MIN_GRID_DEGREES..=MAX_GRID_DEGREES).contains(&grid)
+    {
+        return Err(Error::ConfigValidation {
+            message: format!(
+                "sensitive.grid must be between {MIN_GRID_DEGREES} and {MAX_GRID_DEGREES}, got {grid}"
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Validate inference settings.
+fn validate_inference(config: &Config) -> Result<()> {
+    for &size in &config.inference.tensorrt_warmup_batch_sizes {
+        if !(1..=MAX_BATCH_SIZE).contains(&size) {
+            return Err(Error::ConfigValidation {
+                message: format!(
+                    "tensorrt_warmup_batch_sizes entries must be between 1 and {MAX_BATCH_SIZE}, got {size}"
+                ),
+            });
+        }
+    }
+
+    if let Some(sessions) = config.inference.cpu_sessions
+        && !(1..=MAX_CPU_SESSIONS).contains(&sessions)
+    {
+        return Err(Error::ConfigValidation {
+            message: format!(
+                "cpu_sessions must be between 1 and {MAX_CPU_SESSIONS}, got {sessions}"
+            ),
+        });
+    }
+
+    if let Some(limit) = &config.inference.gpu_mem_limit {
+        crate::cli::validators::parse_byte_size(limit).map_err(|e| Error::ConfigValidation {
+            message: format!("gpu_mem_limit: {e}"),
+        })?;
+    }
+
+    crate::utils::timestamp::TimestampParser::new(config.defaults.timestamp_patterns.clone())?;
+
+    if let Some(required) = &config.inference.min_free_gpu_mem {
+        crate::gpu::MemoryRequirement::parse(required).map_err(|e| Error::ConfigValidation {
+            message: format!("min_free_gpu_mem: {e}"),
+        })?;
+    }
+
+    let watchdog_cfg = &config.inference.watchdog;
+    let timeouts = watchdog_cfg
+        .timeout_secs
+        .iter()
+        .map(|&secs| ("timeout_secs", secs))
+        .chain(
+            watchdog_cfg
+                .providers
+                .iter()
+                .map(|(provider, &secs)| (provider.as_str(), secs)),
+        );
+    for (name, secs) in timeouts {
+        if !(watchdog::MIN_SECS..=watchdog::MAX_SECS).contains(&secs) {
+            return Err(Error::ConfigValidation {
+                message: format!(
+                    "watchdog timeout '{}' must be between {} and {} seconds, got {}",
+                    name,
+                    watchdog::MIN_SECS,
+                    watchdog::MAX_SECS,
+                    secs
+                ),
+            });
+        }
+    }
+
     Ok(())
 }
 
@@ -27,6 +156,17 @@ fn validate_defaults(config: &Config) -> Result<()> {
         });
     }
 
+    if !(sensitivity::MIN..=sensitivity::MAX).contains(&defaults.sensitivity) {
+        return Err(Error::ConfigValidation {
+            message: format!(
+                "sensitivity must be between {} and {}, got {}",
+                sensitivity::MIN,
+                sensitivity::MAX,
+                defaults.sensitivity
+            ),
+        });
+    }
+
     // Validate overlap is non-negative
     if defaults.overlap < 0.0 {
         return Err(Error::ConfigValidation {
@@ -35,7 +175,7 @@ fn validate_defaults(config: &Config) -> Result<()> {
     }
 
     // Validate batch_size is at least 1 (if explicitly set)
-    if defaults.batch_size == Some(0) {
+    if defaults.batch_size == Some(BatchSize::Fixed(0)) {
         return Err(Error::ConfigValidation {
             message: "batch_size must be at least 1".to_string(),
         });
@@ -68,10 +208,14 @@ pub fn validate_model_config(name: &str, model: &ModelConfig) -> Result<()> {
         });
     }
 
+    if let Some(head) = &model.custom_head {
+        validate_custom_head(model.model_type, head)?;
+    }
+
     // Model type validation is handled by the ModelType enum
 
     // BSG models require additional post-processing files
-    if model.model_type == crate::config::types::ModelType::BsgFinland {
+    if model.model_type == ModelType::BsgFinland {
         if model.bsg_calibration.is_none() {
             return Err(Error::BsgConfig {
                 message: format!(
@@ -98,6 +242,31 @@ pub fn validate_model_config(name: &str, model: &ModelConfig) -> Result<()> {
     Ok(())
 }
 
+/// Validate the custom classifier head of a model of `model_type` and check
+/// its files exist.
+///
+/// Used by config validation and before loading the head, so both reject the
+/// same configurations.
+pub fn validate_custom_head(model_type: ModelType, head: &CustomHeadConfig) -> Result<()> {
+    // The head replaces the model's predictions, which BSG post-processing expects
+    if model_type == ModelType::BsgFinland {
+        return Err(Error::ConfigValidation {
+            message: "custom_head is not supported for BSG models".to_string(),
+        });
+    }
+    if !head.path.exists() {
+        return Err(Error::ModelFileNotFound {
+            path: head.path.clone(),
+        });
+    }
+    if !head.labels.exists() {
+        return Err(Error::LabelsFileNotFound {
+            path: head.labels.clone(),
+        });
+    }
+    Ok(())
+}
+
 /// Get a model by name from the config.
 pub fn get_model<'a>(config: &'a Config, name: &str) -> Result<&'a ModelConfig> {
     config.models.get(name).ok_or_else(|| Error::ModelNotFound {
@@ -142,6 +311,7 @@ pub fn validate_range_filter(config: &Config) -> Result<()> {
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
 
@@ -158,6 +328,53 @@ mod tests {
         assert!(validate_config(&config).is_err());
     }
 
+    #[test]
+    fn test_validate_sensitivity_range() {
+        let mut config = Config::default();
+        config.defaults.sensitivity = 1.25;
+        assert!(validate_config(&config).is_ok());
+        config.defaults.sensitivity = 2.0;
+        assert!(validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_custom_head_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let touch = |name: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, b"").unwrap();
+            path
+        };
+        let mut model = ModelConfig {
+            path: touch("model.onnx"),
+            labels: touch("labels.txt"),
+            model_type: ModelType::BirdnetV24,
+            meta_model: None,
+            bsg_calibration: None,
+            bsg_migration: None,
+            bsg_distribution_maps: None,
+            custom_head: Some(CustomHeadConfig {
+                path: touch("head.onnx"),
+                labels: dir.path().join("missing.txt"),
+            }),
+        };
+        assert!(matches!(
+            validate_model_config("owls", &model),
+            Err(Error::LabelsFileNotFound { .. })
+        ));
+
+        if let Some(head) = model.custom_head.as_mut() {
+            head.labels = touch("head.txt");
+        }
+        assert!(validate_model_config("owls", &model).is_ok());
+
+        model.model_type = ModelType::BsgFinland;
+        model.bsg_calibration = Some(touch("calibration.csv"));
+        model.bsg_migration = Some(touch("migration.csv"));
+        model.bsg_distribution_maps = Some(touch("maps.bin"));
+        assert!(validate_model_config("owls", &model).is_err());
+    }
+
     #[test]
     fn test_validate_negative_overlap() {
         let mut config = Config::default();
@@ -168,8 +385,103 @@ mod tests {
     #[test]
     fn test_validate_zero_batch_size() {
         let mut config = Config::default();
-        config.defaults.batch_size = Some(0);
+        config.defaults.batch_size = Some(BatchSize::Fixed(0));
+        assert!(validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_sensitive_grid() {
+        let mut config = Config::default();
+        config.sensitive.grid = Some(0.0);
+        assert!(validate_config(&config).is_err());
+
+        config.sensitive.grid = Some(0.5);
+        assert!(validate_config(&config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_station() {
+        let mut config = Config::default();
+        config.station.id = Some("site-07.shard_2".to_string());
+        config.station.name = Some("Lake Pääjärvi north shore".to_string());
+        assert!(validate_config(&config).is_ok());
+
+        config.station.id = Some("site 07".to_string());
+        assert!(validate_config(&config).is_err());
+
+        config.station.id = None;
+        config.station.name = Some("  ".to_string());
+        assert!(validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_warmup_batch_sizes_out_of_range() {
+        let mut config = Config::default();
+        config.inference.tensorrt_warmup_batch_sizes = vec![8, 0];
         assert!(validate_config(&config).is_err());
+
+        config.inference.tensorrt_warmup_batch_sizes = vec![8, MAX_BATCH_SIZE + 1];
+        assert!(validate_config(&config).is_err());
+
+        config.inference.tensorrt_warmup_batch_sizes = vec![8, 32];
+        assert!(validate_config(&config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_cpu_sessions() {
+        let mut config = Config::default();
+        config.inference.cpu_sessions = Some(0);
+        assert!(validate_config(&config).is_err());
+
+        config.inference.cpu_sessions = Some(MAX_CPU_SESSIONS + 1);
+        assert!(validate_config(&config).is_err());
+
+        config.inference.cpu_sessions = Some(8);
+        assert!(validate_config(&config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_gpu_mem_limit() {
+        let mut config = Config::default();
+        config.inference.gpu_mem_limit = Some("lots".to_string());
+        assert!(validate_config(&config).is_err());
+
+        config.inference.gpu_mem_limit = Some("4G".to_string());
+        assert!(validate_config(&config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_min_free_gpu_mem() {
+        let mut config = Config::default();
+        config.inference.min_free_gpu_mem = Some("plenty".to_string());
+        assert!(validate_config(&config).is_err());
+
+        config.inference.min_free_gpu_mem = Some("auto".to_string());
+        assert!(validate_config(&config).is_ok());
+        config.inference.min_free_gpu_mem = Some("2G".to_string());
+        assert!(validate_config(&config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_watchdog_timeouts() {
+        let mut config = Config::default();
+        config.inference.watchdog.timeout_secs = Some(0);
+        assert!(validate_config(&config).is_err());
+
+        config.inference.watchdog.timeout_secs = Some(15);
+        config
+            .inference
+            .watchdog
+            .providers
+            .insert("tensorrt".to_string(), 7200);
+        assert!(validate_config(&config).is_err());
+
+        config
+            .inference
+            .watchdog
+            .providers
+            .insert("tensorrt".to_string(), 60);
+        assert!(validate_config(&config).is_ok());
     }
 
     #[test]
@@ -31,8 +31,16 @@ pub const DEFAULT_BATCH_SIZE: usize = 8;
 /// adjusted down at runtime to avoid unnecessary memory allocation and padding.
 pub const MAX_BATCH_SIZE: usize = 512;
 
+/// Maximum number of parallel CPU inference sessions.
+///
+/// The number actually used is further limited by the core count and
+/// available memory.
+pub const MAX_CPU_SESSIONS: usize = 256;
+
 /// Batch size defaults by execution provider and model type.
 pub mod batch_size {
+    use crate::config::ModelType;
+
     /// CPU batch size for all models.
     pub const CPU: usize = super::DEFAULT_BATCH_SIZE;
 
@@ -47,6 +55,123 @@ pub mod batch_size {
 
     /// Conservative default for unknown/other GPU providers.
     pub const OTHER_GPU: usize = 16;
+
+    /// Batch sizes benchmarked by `--batch-size auto`, smallest first.
+    pub const AUTO_CANDIDATES: &[usize] = &[8, 16, 32, 64, 128, 256];
+
+    /// Estimated GPU memory one segment of a batch needs, in bytes.
+    ///
+    /// Deliberately generous so that 4 GB cards skip the sizes that exhaust
+    /// them; candidates that still run out of memory end the benchmark.
+    pub const AUTO_VRAM_PER_SEGMENT_BYTES: u64 = 48 << 20;
+
+    /// Timed batches per candidate, after one untimed warmup batch.
+    pub const AUTO_BENCH_ITERATIONS: usize = 3;
+
+    /// A default batch size for models on one execution provider.
+    #[derive(Debug)]
+    pub struct BatchSizeDefault {
+        /// Provider that actually runs inference, as reported by the classifier.
+        pub provider: &'static str,
+        /// Model types the entry applies to; empty for all.
+        pub models: &'static [ModelType],
+        /// Batch size to use.
+        pub batch_size: usize,
+        /// Why this size was chosen, logged when it is used.
+        pub reason: &'static str,
+    }
+
+    /// Default batch sizes when none is configured, first match wins.
+    ///
+    /// Sizes are conservative choices guided by the example performance
+    /// tables in the README, which cover `BirdNET` v2.4 and Perch v2 on one
+    /// test system; they are not tuned per GPU. Providers not listed use
+    /// [`OTHER_GPU`].
+    pub const DEFAULTS: &[BatchSizeDefault] = &[
+        BatchSizeDefault {
+            provider: "CPU",
+            models: &[],
+            batch_size: CPU,
+            reason: "larger CPU batches add latency without raising throughput",
+        },
+        BatchSizeDefault {
+            provider: "CUDA",
+            models: &[ModelType::BirdnetV24, ModelType::BsgFinland],
+            batch_size: CUDA_BIRDNET_V24,
+            reason: "moderate CUDA batch that fits most cards; large cards gain from bigger batches",
+        },
+        BatchSizeDefault {
+            provider: "CUDA",
+            models: &[ModelType::BirdnetV30, ModelType::PerchV2],
+            batch_size: CUDA_BIRDNET_V30,
+            reason: "larger models need more VRAM per segment",
+        },
+        BatchSizeDefault {
+            provider: "TensorRT",
+            models: &[],
+            batch_size: TENSORRT,
+            reason: "TensorRT engines are fastest with small batches and build quicker",
+        },
+    ];
+}
+
+/// GPU memory handling constants.
+pub mod gpu {
+    /// Error message fragments that indicate GPU memory exhaustion (matched case-insensitively).
+    pub const OOM_ERROR_MARKERS: &[&str] = &[
+        "out of memory",
+        "CUDA_ERROR_OUT_OF_MEMORY",
+        "cudaErrorMemoryAllocation",
+        "Failed to allocate memory",
+        "E_OUTOFMEMORY",
+        "bad_alloc",
+    ];
+
+    /// Environment variable that selects the GPUs visible to CUDA.
+    pub const VISIBLE_DEVICES_VAR: &str = "CUDA_VISIBLE_DEVICES";
+
+    /// Minimum seconds between GPU memory samples for the run summary.
+    pub const MEMORY_SAMPLE_INTERVAL_SECS: u64 = 10;
+
+    /// Multiple of the model file size that `--min-free-gpu-mem auto` expects
+    /// the loaded model (weights plus workspace) to take on the GPU.
+    pub const MODEL_FOOTPRINT_FACTOR: u64 = 2;
+}
+
+/// Inference watchdog timeouts by execution provider.
+///
+/// Normal inference takes well under a second per batch, so these mainly
+/// catch GPU hangs. Providers that compile kernels lazily get more headroom.
+pub mod watchdog {
+    /// Environment variable that overrides all configured timeouts.
+    pub const TIMEOUT_ENV_VAR: &str = "BIRDA_INFERENCE_TIMEOUT";
+
+    /// Timeout for CPU inference in seconds.
+    pub const CPU_SECS: u64 = 5;
+
+    /// Timeout for `CUDA` inference in seconds.
+    pub const CUDA_SECS: u64 = 10;
+
+    /// Timeout for `TensorRT` inference in seconds.
+    pub const TENSORRT_SECS: u64 = 30;
+
+    /// Timeout for other GPU providers (`DirectML`, `CoreML`, `ROCm`, ...) in seconds.
+    pub const OTHER_GPU_SECS: u64 = 20;
+
+    /// Multiplier applied to the first batch of each file.
+    ///
+    /// The first batch with a new batch context may still allocate buffers
+    /// or finish deferred provider initialization.
+    pub const FIRST_BATCH_MULTIPLIER: u32 = 3;
+
+    /// Fraction of the timeout after which a warning event is emitted.
+    pub const WARNING_FRACTION: f64 = 0.75;
+
+    /// Minimum allowed timeout in seconds.
+    pub const MIN_SECS: u64 = 1;
+
+    /// Maximum allowed timeout in seconds.
+    pub const MAX_SECS: u64 = 3600;
 }
 
 /// Default number of top predictions to return per segment.
@@ -55,10 +180,15 @@ pub const DEFAULT_TOP_K: usize = 5;
 /// Lock file extension.
 pub const LOCK_FILE_EXTENSION: &str = ".birda.lock";
 
+/// Bytes of the input path hash used to name lock files in a central lock directory.
+pub const CENTRAL_LOCK_HASH_LEN: usize = 16;
+
 /// Output file extensions by format.
 pub mod output_extensions {
     /// CSV output extension.
     pub const CSV: &str = ".BirdNET.results.csv";
+    /// BirdNET-Analyzer CSV extension, the same as BirdNET-Analyzer's own.
+    pub const BIRDNET_CSV: &str = ".BirdNET.results.csv";
     /// Raven selection table extension.
     pub const RAVEN: &str = ".BirdNET.selection.table.txt";
     /// Audacity labels extension.
@@ -69,6 +199,47 @@ pub mod output_extensions {
     pub const JSON: &str = ".BirdNET.json";
     /// Parquet output extension.
     pub const PARQUET: &str = ".BirdNET.results.parquet";
+    /// Activity summary CSV extension.
+    pub const ACTIVITY_CSV: &str = ".BirdNET.activity.csv";
+    /// Activity summary Parquet extension.
+    pub const ACTIVITY_PARQUET: &str = ".BirdNET.activity.parquet";
+    /// Run manifest extension.
+    pub const MANIFEST: &str = ".BirdNET.run.json";
+    /// Embeddings Parquet extension.
+    pub const EMBEDDINGS_PARQUET: &str = ".BirdNET.embeddings.parquet";
+    /// Embeddings `NumPy` extension.
+    pub const EMBEDDINGS_NPY: &str = ".BirdNET.embeddings.npy";
+}
+
+/// Versions of the detection output formats.
+///
+/// A version is bumped whenever the columns of its format change.
+///
+/// Version 2 of CSV, JSON and Parquet adds the `sensitive`, `channel`,
+/// `datetime_start` and `datetime_end` fields.
+pub mod format_version {
+    /// Generic CSV.
+    pub const CSV: u32 = 2;
+    /// BirdNET-Analyzer CSV.
+    pub const BIRDNET_CSV: u32 = 1;
+    /// Raven selection table.
+    pub const RAVEN: u32 = 2;
+    /// Audacity labels.
+    pub const AUDACITY: u32 = 1;
+    /// Kaleidoscope CSV.
+    pub const KALEIDOSCOPE: u32 = 2;
+    /// JSON result files.
+    pub const JSON: u32 = 2;
+    /// Parquet tables.
+    pub const PARQUET: u32 = 2;
+    /// SQLite database.
+    pub const SQLITE: u32 = 2;
+
+    /// Optional CSV and Parquet column holding the format version.
+    pub const COLUMN: &str = "format_version";
+
+    /// Parquet key-value metadata key holding the format version.
+    pub const PARQUET_METADATA_KEY: &str = "birda.format_version";
 }
 
 /// Combined output file names.
@@ -81,6 +252,40 @@ pub mod combined_filenames {
     pub const KALEIDOSCOPE: &str = "BirdNET_Kaleidoscope.csv";
     /// Combined Parquet filename.
     pub const PARQUET: &str = "BirdNET_CombinedTable.parquet";
+    /// SQLite database filename.
+    pub const SQLITE: &str = "BirdNET_Detections.db";
+
+    /// Parquet column holding each row's full source path.
+    ///
+    /// The core `file` column has only the file name, which is ambiguous once
+    /// recordings from several directories share one table.
+    pub const SOURCE_COLUMN: &str = "path";
+
+    /// Partitioned Parquet dataset directory.
+    pub const PARQUET_DATASET: &str = "BirdNET_Dataset";
+
+    /// Prefix the filenames above carry, replaced by `output.combined_prefix`.
+    pub const DEFAULT_PREFIX: &str = "BirdNET";
+
+    /// Files' worth of detections queued for the combined writer before
+    /// senders block.
+    pub const CHANNEL_CAPACITY: usize = 8;
+}
+
+/// Hive-partitioned Parquet dataset output.
+pub mod parquet_dataset {
+    /// Partition value used when a value is unknown (Hive's null partition).
+    pub const NULL_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+    /// Buffered detections after which partitions are written out at the
+    /// next file boundary, bounding memory on large runs.
+    pub const MAX_BUFFERED_ROWS: usize = 500_000;
+
+    /// Schema-only summary file written at the dataset root.
+    pub const COMMON_METADATA: &str = "_common_metadata";
+
+    /// Row group summary file some tools write; stale once parts are added.
+    pub const METADATA: &str = "_metadata";
 }
 
 /// Confidence value bounds.
@@ -93,12 +298,33 @@ pub mod confidence {
     pub const DECIMAL_PLACES: usize = 4;
 }
 
+/// Numeric precision of result files.
+pub mod precision {
+    /// Decimal places for start, end and offset times in text result files.
+    pub const TIME_DECIMAL_PLACES: usize = 1;
+    /// Largest configurable number of decimal places.
+    pub const MAX_DECIMAL_PLACES: usize = 9;
+}
+
+/// Detection sensitivity, as in BirdNET-Analyzer's `--sensitivity`.
+pub mod sensitivity {
+    /// Default sensitivity, leaving model confidences unchanged.
+    pub const DEFAULT: f32 = 1.0;
+    /// Lowest accepted sensitivity.
+    pub const MIN: f32 = 0.5;
+    /// Highest accepted sensitivity.
+    pub const MAX: f32 = 1.5;
+    /// Logits are clipped to this magnitude before the sigmoid, as in
+    /// BirdNET-Analyzer.
+    pub const LOGIT_CLIP: f32 = 20.0;
+}
+
 /// Raven format constants.
 pub mod raven {
     /// View column value.
     pub const VIEW: &str = "Spectrogram 1";
-    /// Channel column value.
-    pub const CHANNEL: u8 = 1;
+    /// Channel column value for detections without a channel.
+    pub const CHANNEL: u32 = 1;
     /// Default low frequency bound in Hz.
     pub const DEFAULT_LOW_FREQ: u32 = 150;
     /// Default high frequency bound in Hz.
@@ -126,6 +352,263 @@ pub mod calendar {
     pub const DAYS_IN_MONTH: [u32; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
 }
 
+/// Acoustic activity summary constants.
+pub mod activity {
+    /// Width of each activity bin in seconds.
+    pub const BIN_DURATION_SECS: f64 = 60.0;
+
+    /// Default RMS level (dBFS) above which a segment counts as active.
+    pub const DEFAULT_ENERGY_THRESHOLD_DBFS: f32 = -50.0;
+
+    /// Level reported for silent segments (dBFS).
+    pub const SILENCE_FLOOR_DBFS: f32 = -120.0;
+}
+
+/// Allowed relative deviation of a resampled segment from its expected length.
+///
+/// Larger deviations are reported as warnings since the segment is padded or
+/// truncated to fit the model input.
+pub const RESAMPLE_LENGTH_TOLERANCE: f64 = 0.01;
+
+/// Detection audio fingerprint constants.
+pub mod fingerprint {
+    /// Number of bits in a segment fingerprint.
+    pub const BITS: usize = 64;
+
+    /// Output column name for fingerprints.
+    pub const COLUMN: &str = "fingerprint";
+}
+
+/// Detection provenance constants.
+pub mod provenance {
+    /// Output columns recording where and how each detection was produced.
+    pub const COLUMNS: [&str; 3] = ["device", "model_hash", "processed_at"];
+}
+
+/// Absolute (wall-clock) detection time constants.
+pub mod wall_clock {
+    /// Output columns holding the absolute start and end of each detection.
+    pub const COLUMNS: [&str; 2] = ["datetime_start", "datetime_end"];
+
+    /// Format of the absolute times: ISO 8601 local time with milliseconds.
+    pub const DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3f";
+
+    /// Formats of recorder metadata timestamps, before their UTC offset:
+    /// Song Meter WAMD and GUANO (ISO 8601).
+    pub const RECORDER_FORMATS: [&str; 2] = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"];
+}
+
+/// Recorder metadata constants.
+pub mod recorder {
+    /// Output columns holding Song Meter recorder settings.
+    pub const COLUMNS: [&str; 5] = [
+        "recorder_model",
+        "recorder_serial",
+        "recorder_gain",
+        "recorder_temperature",
+        "recorder_schedule",
+    ];
+
+    /// Largest metadata chunk read from a recording, in bytes.
+    pub const MAX_METADATA_CHUNK: u32 = 1024 * 1024;
+}
+
+/// Station identity constants.
+pub mod station {
+    /// Output columns identifying the station that produced a detection.
+    pub const COLUMNS: [&str; 2] = ["station_id", "station_name"];
+}
+
+/// Config environment overlay constants.
+pub mod config_env {
+    /// Prefix of environment variables that set config keys.
+    pub const PREFIX: &str = "BIRDA_";
+
+    /// Separator between table and key names in those variables
+    /// (`BIRDA_DEFAULTS__MIN_CONFIDENCE` sets `defaults.min_confidence`).
+    pub const SEPARATOR: &str = "__";
+}
+
+/// Channel selection constants.
+pub mod channel {
+    /// Output column holding the analyzed channel (1-based).
+    pub const COLUMN: &str = "channel";
+}
+
+/// Sensitive species constants.
+pub mod sensitive {
+    /// Output column flagging detections of sensitive species.
+    pub const COLUMN: &str = "sensitive";
+
+    /// Default grid that sensitive detections' coordinates are snapped to,
+    /// in degrees (about 110 km).
+    pub const DEFAULT_GRID_DEGREES: f64 = 1.0;
+}
+
+/// Integration credentials constants.
+pub mod credentials {
+    /// File name of the credentials file in the config directory.
+    pub const FILE_NAME: &str = "credentials.toml";
+
+    /// Permission bits of a written credentials file (owner read/write).
+    pub const FILE_MODE: u32 = 0o600;
+}
+
+/// Secret redaction constants.
+pub mod redact {
+    /// Placeholder shown instead of a secret.
+    pub const REDACTED: &str = "[redacted]";
+
+    /// Words in a config key name that mark its value as a secret.
+    pub const SECRET_KEY_WORDS: [&str; 6] = [
+        "password",
+        "passwd",
+        "secret",
+        "token",
+        "api_key",
+        "private_key",
+    ];
+}
+
+/// Runs log constants.
+pub mod runs {
+    /// File name of the runs log in the config directory.
+    pub const LOG_FILE: &str = "runs.jsonl";
+
+    /// Default number of runs shown by `birda runs list`.
+    pub const DEFAULT_LIST_LIMIT: usize = 20;
+}
+
+/// Example recordings constants.
+pub mod examples {
+    /// Default directory `birda examples fetch` downloads into.
+    pub const DEFAULT_DIR: &str = "birda-examples";
+
+    /// File name of the example set written next to the recordings.
+    pub const MANIFEST_NAME: &str = "examples.json";
+}
+
+/// Read buffer size for hashing model files.
+pub const HASH_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Directory watch constants.
+pub mod watch {
+    /// Default seconds between directory scans.
+    pub const DEFAULT_POLL_INTERVAL_SECS: u64 = 10;
+}
+
+/// Dead-letter handling of recordings that fail to decode.
+pub mod dead_letter {
+    /// Directory in the output directory that failed recordings go to.
+    ///
+    /// Input collection skips directories with this name, so set-aside files
+    /// are not picked up again by the next run or a watch.
+    pub const DIR_NAME: &str = "_failed";
+
+    /// Suffix added to a recording's file name for its error record.
+    pub const RECORD_EXTENSION: &str = ".error.json";
+}
+
+/// Live audio capture constants.
+pub mod listen {
+    /// Captured buffers queued for analysis before new ones are dropped.
+    pub const CAPTURE_QUEUE_BUFFERS: usize = 256;
+}
+
+/// Network audio stream constants.
+pub mod stream {
+    /// Downloaded chunks buffered ahead of the decoder.
+    pub const QUEUE_CHUNKS: usize = 64;
+
+    /// Connection timeout in seconds.
+    pub const CONNECT_TIMEOUT_SECS: u64 = 15;
+
+    /// Seconds without data after which a stream counts as dropped.
+    pub const READ_TIMEOUT_SECS: u64 = 30;
+
+    /// Seconds to wait before the first reconnection attempt.
+    pub const RECONNECT_DELAY_SECS: u64 = 1;
+
+    /// Longest wait in seconds between reconnection attempts.
+    pub const MAX_RECONNECT_DELAY_SECS: u64 = 60;
+
+    /// Consecutive failed reconnection attempts after which analysis stops.
+    pub const MAX_RECONNECT_ATTEMPTS: u32 = 10;
+}
+
+/// Split recording stitching constants.
+pub mod stitch {
+    /// Largest difference in seconds between the end of one recording and the
+    /// timestamp of the next for the two to count as one continuous stream.
+    ///
+    /// File name timestamps have one-second resolution.
+    pub const MAX_GAP_SECS: f64 = 2.0;
+}
+
+/// HTTP API server constants.
+pub mod serve {
+    /// Default address `birda serve` listens on (local connections only).
+    pub const DEFAULT_ADDRESS: &str = "127.0.0.1:8787";
+
+    /// Largest accepted upload in bytes.
+    pub const MAX_UPLOAD_BYTES: u64 = 256 * 1024 * 1024;
+
+    /// Largest accepted JSON request body in bytes.
+    pub const MAX_JSON_BODY_BYTES: u64 = 64 * 1024;
+
+    /// Worker threads answering requests.
+    pub const WORKERS: usize = 4;
+}
+
+/// Inference benchmark constants.
+pub mod bench {
+    /// Default number of segments classified per benchmark.
+    pub const DEFAULT_SEGMENTS: usize = 512;
+
+    /// Batches classified before timing starts, so one-time costs such as
+    /// `TensorRT` engine builds and memory arena growth are not measured.
+    pub const WARMUP_BATCHES: usize = 2;
+
+    /// Default allowed throughput drop below a baseline, in percent.
+    pub const DEFAULT_TOLERANCE_PERCENT: f64 = 10.0;
+}
+
+/// Model registry download constants.
+pub mod registry {
+    /// Default number of files downloaded concurrently during an install.
+    pub const DEFAULT_DOWNLOAD_CONNECTIONS: usize = 4;
+
+    /// Upper bound for `models install --connections`.
+    pub const MAX_DOWNLOAD_CONNECTIONS: usize = 16;
+
+    /// Default number of retries of a failed model file download.
+    pub const DEFAULT_DOWNLOAD_RETRIES: u32 = 3;
+
+    /// Default delay before the first download retry, doubled per retry.
+    pub const DEFAULT_RETRY_DELAY_SECS: u64 = 2;
+
+    /// Upper bound for the delay between download retries.
+    pub const MAX_RETRY_DELAY_SECS: u64 = 60;
+
+    /// Timeout for fetching a `registry.sources` URL.
+    pub const SOURCE_TIMEOUT_SECS: u64 = 30;
+
+    /// Directory in the config directory caching fetched registry sources.
+    pub const SOURCE_CACHE_DIR: &str = "registry-sources";
+
+    /// Default number of predictions shown by `models test`.
+    pub const DEFAULT_TEST_PREDICTIONS: usize = 5;
+
+    /// File name of the model lockfile in the config directory.
+    pub const LOCKFILE_NAME: &str = "birda.lock";
+
+    /// Environment variable pointing at a shared model lockfile.
+    pub const LOCKFILE_ENV: &str = "BIRDA_LOCKFILE";
+}
+
+/// Bytes per mebibyte, for human-readable file sizes.
+pub const BYTES_PER_MIB: f64 = 1_048_576.0;
+
 /// UTF-8 Byte Order Mark for Excel compatibility in CSV files.
 pub const UTF8_BOM: &[u8; 3] = b"\xEF\xBB\xBF";
 
@@ -133,6 +616,12 @@ pub const UTF8_BOM: &[u8; 3] = b"\xEF\xBB\xBF";
 pub mod tensorrt {
     /// Directory name for `TensorRT` engine and timing cache files.
     pub const CACHE_DIR: &str = "tensorrt_cache";
+
+    /// File extension of `TensorRT` engine cache files.
+    pub const ENGINE_EXTENSION: &str = "engine";
+
+    /// Manifest file mapping cached engines to the batch sizes they were built for.
+    pub const ENGINE_MANIFEST_FILE: &str = "birda_engines.json";
 }
 
 /// Clipper constants for clip extraction.
@@ -149,6 +638,9 @@ pub mod clipper {
     /// Default output directory for clips.
     pub const DEFAULT_OUTPUT_DIR: &str = "clips";
 
+    /// Program encoding FLAC, MP3 and Ogg Vorbis clips.
+    pub const FFMPEG: &str = "ffmpeg";
+
     /// Minimum start time (in seconds) before seeking is attempted.
     /// For clips starting before this threshold, we decode from the beginning.
     pub const SEEK_THRESHOLD_SECS: f64 = 10.0;
@@ -163,6 +655,80 @@ pub mod clipper {
     pub const AUDIO_EXTENSIONS: &[&str] = &["wav", "flac", "mp3", "m4a", "aac"];
 }
 
+/// Report generation constants.
+pub mod report {
+    /// Default output file for the phenology report.
+    pub const DEFAULT_PHENOLOGY_OUTPUT: &str = "phenology.csv";
+
+    /// Default output file for the activity calendar report.
+    pub const DEFAULT_CALENDAR_OUTPUT: &str = "calendar.csv";
+
+    /// Width of one day in calendar heatmaps, in pixels.
+    pub const CALENDAR_CELL_WIDTH: usize = 3;
+
+    /// Height of one hour in calendar heatmaps, in pixels.
+    pub const CALENDAR_CELL_HEIGHT: usize = 8;
+
+    /// Margin around calendar heatmaps for axis labels, in pixels.
+    pub const CALENDAR_MARGIN: usize = 24;
+
+    /// Default output file for the species review report.
+    pub const DEFAULT_REVIEW_OUTPUT: &str = "species_review.html";
+
+    /// Default number of top detections per species with audio snippets.
+    pub const DEFAULT_REVIEW_SNIPPETS: usize = 3;
+
+    /// Audio added before and after a detection in review snippets, in seconds.
+    pub const REVIEW_SNIPPET_PADDING: f64 = 1.0;
+
+    /// Suffix of the directory holding linked review snippets.
+    pub const REVIEW_CLIPS_DIR_SUFFIX: &str = "_clips";
+
+    /// Default output file for the species accumulation report.
+    pub const DEFAULT_ACCUMULATION_OUTPUT: &str = "accumulation.csv";
+
+    /// Width of the plot area of accumulation charts, in pixels.
+    pub const ACCUMULATION_CHART_WIDTH: usize = 600;
+
+    /// Height of the plot area of accumulation charts, in pixels.
+    pub const ACCUMULATION_CHART_HEIGHT: usize = 300;
+
+    /// Margin around accumulation charts for axis labels, in pixels.
+    pub const ACCUMULATION_CHART_MARGIN: usize = 40;
+
+    /// Share of the audio, at the end of an accumulation curve, in which newly
+    /// found species are reported as a sign the curve has not levelled off.
+    pub const ACCUMULATION_TAIL_FRACTION: f64 = 0.1;
+
+    /// Confidence histogram bins in the run report (5% each).
+    pub const RUN_HISTOGRAM_BINS: usize = 20;
+
+    /// Slices of each recording in the run report's detection timelines.
+    pub const RUN_TIMELINE_BINS: usize = 60;
+
+    /// Fewest detections of a species for a threshold suggestion.
+    pub const THRESHOLD_MIN_DETECTIONS: usize = 20;
+
+    /// Smallest drop below the straight line, as a share of detections,
+    /// that counts as an elbow in the kept-detections curve.
+    pub const THRESHOLD_MIN_ELBOW: f64 = 0.05;
+}
+
+/// Sanitized result export constants.
+pub mod export {
+    /// Default grid that coordinates are snapped to, in degrees (about 11 km).
+    pub const DEFAULT_GRID_DEGREES: f64 = 0.1;
+
+    /// Smallest accepted coordinate grid, in degrees.
+    pub const MIN_GRID_DEGREES: f64 = 0.001;
+
+    /// Largest accepted coordinate grid, in degrees.
+    pub const MAX_GRID_DEGREES: f64 = 10.0;
+
+    /// Suffix appended to the output directory name for the default mapping file.
+    pub const MAPPING_SUFFIX: &str = ".mapping.csv";
+}
+
 /// Bat detection constants.
 pub mod bat {
     /// Audio sample rate for bat recordings (256 kHz).
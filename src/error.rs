@@ -38,6 +38,96 @@ pub enum Error {
         source: toml::de::Error,
     },
 
+    /// Failed to read the credentials file.
+    #[error("failed to read credentials file '{path}'")]
+    CredentialsRead {
+        /// Path to the credentials file.
+        path: std::path::PathBuf,
+        /// Underlying I/O error.
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// Failed to parse the credentials file.
+    #[error("failed to parse credentials file '{path}'")]
+    CredentialsParse {
+        /// Path to the credentials file.
+        path: std::path::PathBuf,
+        /// Underlying parse error.
+        #[source]
+        source: toml::de::Error,
+    },
+
+    /// Failed to write the credentials file.
+    #[error("failed to write credentials file '{path}'")]
+    CredentialsWrite {
+        /// Path to the credentials file.
+        path: std::path::PathBuf,
+        /// Underlying I/O error.
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The credentials file can be read by other users.
+    #[error(
+        "credentials file '{path}' is accessible by other users (mode {mode:o}); restrict it with `chmod 600`"
+    )]
+    CredentialsPermissions {
+        /// Path to the credentials file.
+        path: std::path::PathBuf,
+        /// Permission bits of the file.
+        mode: u32,
+    },
+
+    /// A credential refers to an environment variable that is not set.
+    #[error("credential '{key}' refers to environment variable {var}, which is not set")]
+    CredentialEnvMissing {
+        /// Dotted credential key.
+        key: String,
+        /// Name of the environment variable.
+        var: String,
+    },
+
+    /// The config file contains a key birda does not know.
+    #[error("unknown key '{key}' in config file '{path}'{hint}")]
+    ConfigUnknownKey {
+        /// Path to the config file.
+        path: std::path::PathBuf,
+        /// Dotted path of the unknown key.
+        key: String,
+        /// Suggested fix, with a leading space.
+        hint: String,
+    },
+
+    /// An environment variable sets a config key to something invalid.
+    #[error("invalid config override {var}: {reason}")]
+    ConfigEnvOverride {
+        /// Name of the environment variable.
+        var: String,
+        /// What is wrong with it.
+        reason: String,
+    },
+
+    /// `config validate` found errors in the config file.
+    #[error("config file '{path}' has {errors} error(s)")]
+    ConfigInvalid {
+        /// Path to the config file.
+        path: std::path::PathBuf,
+        /// Number of errors found.
+        errors: usize,
+    },
+
+    /// A path in the config file could not be resolved.
+    #[error("cannot resolve path '{path}' in config file '{config}': {reason}")]
+    ConfigPathResolve {
+        /// Path as written in the config file.
+        path: std::path::PathBuf,
+        /// Config file containing the path.
+        config: std::path::PathBuf,
+        /// Why the path could not be resolved.
+        reason: String,
+    },
+
     /// Configuration validation failed.
     #[error("configuration validation failed: {message}")]
     ConfigValidation {
@@ -59,6 +149,15 @@ pub enum Error {
         name: String,
     },
 
+    /// Configuration profile not found.
+    #[error("profile '{name}' not found in configuration (available: {available})")]
+    ProfileNotFound {
+        /// Name of the missing profile.
+        name: String,
+        /// Configured profile names.
+        available: String,
+    },
+
     /// Model file does not exist.
     #[error("model file does not exist: {path}")]
     ModelFileNotFound {
@@ -74,7 +173,7 @@ pub enum Error {
     },
 
     /// Model already exists in configuration.
-    #[error("model '{name}' already exists in configuration")]
+    #[error("model '{name}' already exists in configuration (use --update to modify it)")]
     ModelAlreadyExists {
         /// Name of the existing model.
         name: String,
@@ -102,6 +201,16 @@ pub enum Error {
     #[error("no valid audio files found in the provided paths")]
     NoValidAudioFiles,
 
+    /// Failed to read a `--file-list` file.
+    #[error("failed to read file list '{path}'")]
+    FileListRead {
+        /// Path to the file list (`-` for stdin).
+        path: std::path::PathBuf,
+        /// Underlying I/O error.
+        #[source]
+        source: std::io::Error,
+    },
+
     /// Output path escapes the intended output directory (path traversal attempt).
     #[error("output path '{output_path}' escapes output directory '{output_dir}'")]
     PathTraversal {
@@ -138,6 +247,59 @@ pub enum Error {
         source: Box<dyn std::error::Error + Send + Sync>,
     },
 
+    /// Selected channel is not in the recording.
+    #[error("channel {channel} selected, but '{path}' has {channels} channel(s)")]
+    AudioChannel {
+        /// Path to the audio file.
+        path: std::path::PathBuf,
+        /// Selected channel (1-based).
+        channel: u32,
+        /// Channels in the recording.
+        channels: usize,
+    },
+
+    /// Recording continued across a boundary has another sample rate.
+    #[error(
+        "sample rate {sample_rate} Hz of '{path}' does not match the preceding recording's {expected} Hz"
+    )]
+    StitchSampleRate {
+        /// Path to the following recording.
+        path: std::path::PathBuf,
+        /// Its sample rate in Hz.
+        sample_rate: u32,
+        /// Sample rate of the preceding recording in Hz.
+        expected: u32,
+    },
+
+    /// Failure injected with `--fault-inject`.
+    #[error("injected fault: {reason} in '{path}'")]
+    InjectedFault {
+        /// File being processed.
+        path: std::path::PathBuf,
+        /// Failure the fault stands in for.
+        reason: String,
+    },
+
+    /// Sound device could not be opened or read.
+    #[error("audio device error: {reason}")]
+    AudioDevice {
+        /// Description of the failure.
+        reason: String,
+    },
+
+    /// Network audio stream could not be opened or read.
+    #[error("audio stream '{url}': {reason}")]
+    AudioStream {
+        /// Stream URL.
+        url: String,
+        /// Description of the failure.
+        reason: String,
+    },
+
+    /// Live audio capture requested from a build without it.
+    #[error("birda was built without live audio support; rebuild with `--features listen`")]
+    ListenUnavailable,
+
     /// No audio tracks found.
     #[error("no audio tracks found in '{path}'")]
     NoAudioTracks {
@@ -193,6 +355,13 @@ pub enum Error {
         reason: String,
     },
 
+    /// The GPU failed the pre-run memory and health check.
+    #[error("GPU not ready: {reason}")]
+    GpuNotReady {
+        /// Why the GPU cannot run the model.
+        reason: String,
+    },
+
     /// Inference failed.
     #[error("inference failed: {reason}")]
     Inference {
@@ -200,6 +369,71 @@ pub enum Error {
         reason: String,
     },
 
+    /// One or more self-test stages failed.
+    #[error("self-test failed: {stages}")]
+    SelftestFailed {
+        /// Names of the failed stages.
+        stages: String,
+    },
+
+    /// Failed to listen for API requests.
+    #[error("failed to listen on {address}")]
+    ServerBind {
+        /// Address that could not be bound.
+        address: std::net::SocketAddr,
+        /// Underlying I/O error.
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The `--allow-path` directory of `birda serve` cannot be resolved.
+    #[error("cannot resolve --allow-path directory '{path}'")]
+    ServeRoot {
+        /// Directory given on the command line.
+        path: std::path::PathBuf,
+        /// Underlying I/O error.
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// Failed to parse a benchmark baseline.
+    #[error("failed to parse benchmark baseline '{path}'")]
+    BaselineParse {
+        /// Path to the baseline file.
+        path: std::path::PathBuf,
+        /// Underlying JSON error.
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// Throughput fell further below the baseline than the tolerance allows.
+    #[error(
+        "throughput regression: {measured:.1} segments/s is {drop:.1}% below the baseline of {baseline:.1} (tolerance {tolerance}%)"
+    )]
+    ThroughputRegression {
+        /// Measured segments per second.
+        measured: f64,
+        /// Baseline segments per second.
+        baseline: f64,
+        /// Drop below the baseline in percent.
+        drop: f64,
+        /// Allowed drop in percent.
+        tolerance: f64,
+    },
+
+    /// The model does not output embeddings.
+    #[error("model does not output embeddings")]
+    EmbeddingsUnavailable,
+
+    /// Post-processing hook failed.
+    #[error("post-processing hook '{command}' failed: {reason}")]
+    PostprocessHook {
+        /// Hook command that failed.
+        command: String,
+        /// Description of the failure.
+        reason: String,
+    },
+
     /// Failed to read registry file.
     #[error("failed to read registry file '{path}'")]
     RegistryRead {
@@ -264,6 +498,74 @@ pub enum Error {
         source: Box<dyn std::error::Error + Send + Sync>,
     },
 
+    /// Downloaded file does not match the checksum declared in the registry.
+    #[error("checksum mismatch for downloaded '{file}': expected {expected}, got {actual}")]
+    DownloadChecksumMismatch {
+        /// File name that failed verification.
+        file: String,
+        /// SHA256 hash declared in the registry.
+        expected: String,
+        /// SHA256 hash of the downloaded file.
+        actual: String,
+    },
+
+    /// Installed model files failed verification.
+    #[error("model '{id}' failed verification: {files}")]
+    ModelVerifyFailed {
+        /// Model ID.
+        id: String,
+        /// Names of the files that are missing or do not match.
+        files: String,
+    },
+
+    /// Failed to parse the model lockfile.
+    #[error("failed to parse model lockfile '{path}'")]
+    LockfileParse {
+        /// Path to the lockfile.
+        path: std::path::PathBuf,
+        /// Underlying parse error.
+        #[source]
+        source: toml::de::Error,
+    },
+
+    /// Failed to write the model lockfile.
+    #[error("failed to write model lockfile '{path}'")]
+    LockfileWrite {
+        /// Path to the lockfile.
+        path: std::path::PathBuf,
+        /// Underlying I/O error.
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// Registry version differs from the version pinned in the lockfile.
+    #[error(
+        "model '{id}' is locked at version {locked}, but the registry provides {available} (use --upgrade to update the lock)"
+    )]
+    LockfileVersionMismatch {
+        /// Model ID.
+        id: String,
+        /// Version pinned in the lockfile.
+        locked: String,
+        /// Version available in the registry.
+        available: String,
+    },
+
+    /// Model file hash differs from the hash pinned in the lockfile.
+    #[error(
+        "model '{id}' file '{file}' does not match the lockfile: expected {expected}, got {actual}"
+    )]
+    LockfileHashMismatch {
+        /// Model ID.
+        id: String,
+        /// File name.
+        file: String,
+        /// Hash pinned in the lockfile.
+        expected: String,
+        /// Hash of the file on disk.
+        actual: String,
+    },
+
     /// Invalid model type string.
     #[error("invalid model type: {value}")]
     InvalidModelType {
@@ -364,6 +666,28 @@ pub enum Error {
         source: hound::Error,
     },
 
+    /// Failed to encode a clip in a compressed format.
+    #[error("failed to encode clip '{path}': {reason}")]
+    ClipEncodeFailed {
+        /// Path of the clip.
+        path: std::path::PathBuf,
+        /// Why encoding failed.
+        reason: String,
+    },
+
+    /// The encoder for a compressed clip format is not available.
+    #[error("{format} clips need {program} with the {encoder} encoder: {reason}")]
+    ClipEncoderUnavailable {
+        /// Clip format requested.
+        format: String,
+        /// Program that encodes the clips.
+        program: &'static str,
+        /// Encoder the format needs.
+        encoder: &'static str,
+        /// Why the encoder is unavailable.
+        reason: String,
+    },
+
     /// Failed to create output directory.
     #[error("failed to create output directory '{path}'")]
     OutputDirCreateFailed {
@@ -395,6 +719,67 @@ pub enum Error {
         source: serde_json::Error,
     },
 
+    // Report errors
+    /// No detection result files found for a report.
+    #[error("no detection result files found in '{path}'")]
+    NoResultFiles {
+        /// Directory that was searched.
+        path: std::path::PathBuf,
+    },
+
+    /// No run, or more than one run, in the runs log matches an ID.
+    #[error("no single run matching '{id}' in the runs log")]
+    RunNotFound {
+        /// Run ID or prefix that was looked up.
+        id: String,
+    },
+
+    /// Failed to write report file.
+    #[error("failed to write report file '{path}'")]
+    ReportWrite {
+        /// Path to the report file.
+        path: std::path::PathBuf,
+        /// Underlying error.
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    /// Failed to parse a run manifest.
+    #[error("failed to parse run manifest '{path}'")]
+    ManifestParse {
+        /// Path to the manifest file.
+        path: std::path::PathBuf,
+        /// Underlying JSON error.
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// DuckDB database or query failure.
+    #[error("DuckDB error ({context}): {reason}")]
+    Duckdb {
+        /// What was being done.
+        context: String,
+        /// Error reported by DuckDB.
+        reason: String,
+    },
+
+    /// DuckDB output or queries requested from a build without DuckDB.
+    #[error("birda was built without DuckDB support; rebuild with `--features duckdb`")]
+    DuckdbUnavailable,
+
+    /// SQLite database failure.
+    #[error("SQLite error ({context}): {reason}")]
+    Sqlite {
+        /// What was being done.
+        context: String,
+        /// Error reported by SQLite.
+        reason: String,
+    },
+
+    /// SQLite output requested from a build without SQLite.
+    #[error("birda was built without SQLite support; rebuild with `--features sqlite`")]
+    SqliteUnavailable,
+
     /// Invalid output format string.
     #[error("invalid output format: {value}")]
     InvalidOutputFormat {
@@ -440,6 +825,18 @@ pub enum Error {
         source: std::io::Error,
     },
 
+    /// Failed to move a model file while relocating a model.
+    #[error("failed to move '{from}' to '{to}'")]
+    ModelRelocateFailed {
+        /// File being moved.
+        from: std::path::PathBuf,
+        /// Destination of the file.
+        to: std::path::PathBuf,
+        /// Underlying I/O error.
+        #[source]
+        source: std::io::Error,
+    },
+
     /// Day of year auto-detection failed.
     #[error("could not auto-detect day of year from file {path}: {reason}")]
     DayOfYearAutoDetect {
@@ -490,6 +887,26 @@ pub enum Error {
     #[error("no input files were provided to combine")]
     NoInputFilesToCombine,
 
+    /// An existing results file has a different header than the rows to append.
+    #[error("cannot append to '{path}': its header does not match the current columns")]
+    AppendHeaderMismatch {
+        /// Path to the results file.
+        path: std::path::PathBuf,
+    },
+
+    /// The combined output thread stopped before all results were sent.
+    #[error("combined output writer stopped unexpectedly")]
+    CombinerStopped,
+
+    /// Failed to parse a GPS track file.
+    #[error("failed to read GPS track '{path}': {reason}")]
+    TrackParse {
+        /// Path to the track file.
+        path: std::path::PathBuf,
+        /// Description of the failure.
+        reason: String,
+    },
+
     /// Failed to load labels from file.
     #[error("failed to load labels from {path}: {reason}")]
     LabelLoad {
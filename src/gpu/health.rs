@@ -0,0 +1,152 @@
+//! GPU memory and health gate checked before a run starts.
+//!
+//! When another job holds most of the GPU's memory, the first batches can
+//! exhaust it and hang inference until the watchdog kills the process, hours
+//! into an archive. With a minimum free memory configured, the GPU is checked
+//! through NVML before the model is loaded, so a run that would not fit
+//! refuses to start or moves to the CPU instead.
+
+use super::memory::{FreeMemory, probe_free_memory};
+use crate::constants::batch_size::AUTO_VRAM_PER_SEGMENT_BYTES;
+use crate::constants::gpu::MODEL_FOOTPRINT_FACTOR;
+
+/// Free GPU memory a run needs before it starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryRequirement {
+    /// The model's expected footprint at the run's batch size.
+    Auto,
+    /// A fixed number of bytes.
+    Bytes(u64),
+}
+
+impl MemoryRequirement {
+    /// Parse `auto` or a byte size such as `2G`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        if s.trim().eq_ignore_ascii_case("auto") {
+            return Ok(Self::Auto);
+        }
+        crate::cli::validators::parse_byte_size(s).map(Self::Bytes)
+    }
+
+    /// Bytes required for a model file of `model_bytes` at `batch_size`.
+    pub fn required_bytes(self, model_bytes: u64, batch_size: usize) -> u64 {
+        match self {
+            Self::Auto => model_bytes
+                .saturating_mul(MODEL_FOOTPRINT_FACTOR)
+                .saturating_add((batch_size as u64).saturating_mul(AUTO_VRAM_PER_SEGMENT_BYTES)),
+            Self::Bytes(bytes) => bytes,
+        }
+    }
+}
+
+impl std::fmt::Display for MemoryRequirement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Auto => write!(f, "auto"),
+            Self::Bytes(bytes) => write!(f, "{} MiB", bytes >> 20),
+        }
+    }
+}
+
+/// Outcome of the pre-run GPU check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GpuCheck {
+    /// Enough memory is free.
+    Ready {
+        /// Free memory in bytes.
+        free: u64,
+    },
+    /// Less memory is free than the run needs.
+    Insufficient {
+        /// Free memory in bytes.
+        free: u64,
+        /// Required memory in bytes.
+        required: u64,
+    },
+    /// The GPU did not answer the query.
+    Unhealthy {
+        /// What went wrong.
+        reason: String,
+    },
+    /// NVML is not installed, so nothing could be checked.
+    Unknown,
+}
+
+impl GpuCheck {
+    /// Why the run should not start on the GPU, or `None` if it can.
+    pub fn problem(&self) -> Option<String> {
+        match self {
+            Self::Ready { .. } | Self::Unknown => None,
+            Self::Insufficient { free, required } => Some(format!(
+                "only {} MiB of GPU memory free, {} MiB required",
+                free >> 20,
+                required >> 20
+            )),
+            Self::Unhealthy { reason } => Some(format!("GPU health check failed: {reason}")),
+        }
+    }
+}
+
+/// Check that the NVIDIA GPU used by CUDA responds and has `required` bytes free.
+pub fn check_gpu(required: u64) -> GpuCheck {
+    classify(probe_free_memory(), required)
+}
+
+/// Judge a free memory reading against `required` bytes.
+fn classify(free: FreeMemory, required: u64) -> GpuCheck {
+    match free {
+        FreeMemory::Free(free) if free >= required => GpuCheck::Ready { free },
+        FreeMemory::Free(free) => GpuCheck::Insufficient { free, required },
+        FreeMemory::Failed(reason) => GpuCheck::Unhealthy { reason },
+        FreeMemory::Unavailable => GpuCheck::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_memory_requirement() {
+        assert_eq!(
+            MemoryRequirement::parse("auto"),
+            Ok(MemoryRequirement::Auto)
+        );
+        assert_eq!(
+            MemoryRequirement::parse("2G"),
+            Ok(MemoryRequirement::Bytes(2 << 30))
+        );
+        assert!(MemoryRequirement::parse("lots").is_err());
+    }
+
+    #[test]
+    fn test_required_bytes() {
+        assert_eq!(
+            MemoryRequirement::Bytes(1 << 30).required_bytes(50 << 20, 64),
+            1 << 30
+        );
+        assert_eq!(
+            MemoryRequirement::Auto.required_bytes(50 << 20, 16),
+            (50 << 20) * MODEL_FOOTPRINT_FACTOR + 16 * AUTO_VRAM_PER_SEGMENT_BYTES
+        );
+    }
+
+    #[test]
+    fn test_classify_gpu_check() {
+        assert_eq!(
+            classify(FreeMemory::Free(4 << 30), 1 << 30),
+            GpuCheck::Ready { free: 4 << 30 }
+        );
+        let check = classify(FreeMemory::Free(512 << 20), 1 << 30);
+        assert_eq!(
+            check.problem().as_deref(),
+            Some("only 512 MiB of GPU memory free, 1024 MiB required")
+        );
+        assert!(
+            classify(FreeMemory::Failed("GPU is lost".to_string()), 0)
+                .problem()
+                .is_some()
+        );
+        assert_eq!(classify(FreeMemory::Unavailable, 1 << 30).problem(), None);
+    }
+}
@@ -0,0 +1,220 @@
+//! GPU memory probing for batch size auto-tuning and run summaries.
+//!
+//! ONNX Runtime does not report device memory, so memory on the GPU used by
+//! the `CUDA` and `TensorRT` providers is read through NVML, the NVIDIA
+//! Management Library, loaded at runtime from the driver. That GPU is the
+//! first one `CUDA_VISIBLE_DEVICES` lists (an index or a UUID), or device 0
+//! when it is unset.
+
+use crate::constants::gpu::VISIBLE_DEVICES_VAR;
+use nvml_wrapper::enums::device::UsedGpuMemory;
+use nvml_wrapper::error::NvmlError;
+use nvml_wrapper::{Device, Nvml};
+use tracing::debug;
+
+/// Result of reading the selected NVIDIA GPU's free memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum FreeMemory {
+    /// Free memory in bytes.
+    Free(u64),
+    /// NVML loaded but could not query the GPU (driver error, lost GPU).
+    Failed(String),
+    /// NVML is not installed, so there is no NVIDIA GPU to check.
+    Unavailable,
+}
+
+/// GPU selected by `CUDA_VISIBLE_DEVICES`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DeviceId {
+    /// Device index.
+    Index(u32),
+    /// Device UUID (`GPU-...`).
+    Uuid(String),
+}
+
+/// The GPU that CUDA uses as device 0, given `CUDA_VISIBLE_DEVICES`.
+///
+/// Returns `None` when the variable hides every GPU.
+fn selected_device(visible: Option<&str>) -> Option<DeviceId> {
+    let Some(visible) = visible else {
+        return Some(DeviceId::Index(0));
+    };
+    let first = visible.split(',').next()?.trim();
+    if first.is_empty() || first.starts_with('-') {
+        return None;
+    }
+    Some(
+        first
+            .parse()
+            .map_or_else(|_| DeviceId::Uuid(first.to_string()), DeviceId::Index),
+    )
+}
+
+/// Run `query` on the GPU the `CUDA` providers use.
+fn query_device<T>(
+    query: impl FnOnce(&Device<'_>) -> Result<T, NvmlError>,
+) -> Result<T, NvmlError> {
+    let nvml = Nvml::init()?;
+    let visible = std::env::var(VISIBLE_DEVICES_VAR).ok();
+    let device = match selected_device(visible.as_deref()) {
+        Some(DeviceId::Index(index)) => nvml.device_by_index(index)?,
+        Some(DeviceId::Uuid(uuid)) => nvml.device_by_uuid(uuid.as_str())?,
+        None => return Err(NvmlError::NotFound),
+    };
+    query(&device)
+}
+
+/// Whether `error` means NVML itself is missing rather than the GPU failing.
+const fn is_missing_library(error: &NvmlError) -> bool {
+    matches!(
+        error,
+        NvmlError::LibloadingError(_) | NvmlError::LibraryNotFound
+    )
+}
+
+/// Query the selected NVIDIA GPU's free memory.
+pub(super) fn probe_free_memory() -> FreeMemory {
+    match query_device(|device| device.memory_info()) {
+        Ok(memory) => FreeMemory::Free(memory.free),
+        Err(e) if is_missing_library(&e) => {
+            debug!("Could not load NVML: {e}");
+            FreeMemory::Unavailable
+        }
+        Err(e) => FreeMemory::Failed(format!("NVML query failed: {e}")),
+    }
+}
+
+/// Free memory of the selected NVIDIA GPU in bytes, or `None` if it cannot
+/// be read.
+pub fn free_memory_bytes() -> Option<u64> {
+    match probe_free_memory() {
+        FreeMemory::Free(bytes) => Some(bytes),
+        FreeMemory::Failed(_) | FreeMemory::Unavailable => None,
+    }
+}
+
+/// Memory in use on the selected NVIDIA GPU in bytes, by every process, or
+/// `None` if it cannot be read.
+pub fn used_memory_bytes() -> Option<u64> {
+    query_device(|device| device.memory_info())
+        .inspect_err(|e| debug!("Could not read GPU memory: {e}"))
+        .ok()
+        .map(|memory| memory.used)
+}
+
+/// GPU memory used by this process in bytes, or `None` if it cannot be read.
+///
+/// NVML lists processes by their PID in the host's namespace, so inside a
+/// container this process is not found by its own PID. It is then credited
+/// with the memory used on the GPU beyond `baseline`, the use measured
+/// before the model was loaded.
+pub fn process_memory_bytes(baseline: Option<u64>) -> Option<u64> {
+    query_device(|device| {
+        let processes = device.running_compute_processes()?;
+        let own = own_memory(
+            processes.iter().map(|process| {
+                let used = match process.used_gpu_memory {
+                    UsedGpuMemory::Used(bytes) => Some(bytes),
+                    UsedGpuMemory::Unavailable => None,
+                };
+                (process.pid, used)
+            }),
+            std::process::id(),
+        );
+        if own.is_some() {
+            return Ok(own);
+        }
+        let Some(baseline) = baseline else {
+            return Ok(None);
+        };
+        Ok(Some(device.memory_info()?.used.saturating_sub(baseline)))
+    })
+    .inspect_err(|e| debug!("Could not read GPU memory use: {e}"))
+    .ok()
+    .flatten()
+}
+
+/// Bytes used by `pid` among `(pid, bytes)` compute process entries.
+fn own_memory(processes: impl IntoIterator<Item = (u32, Option<u64>)>, pid: u32) -> Option<u64> {
+    processes
+        .into_iter()
+        .filter(|&(process, _)| process == pid)
+        .filter_map(|(_, bytes)| bytes)
+        .reduce(|a, b| a + b)
+}
+
+/// Candidate batch sizes whose estimated footprint fits in `free_bytes`.
+///
+/// With unknown free memory every candidate is kept. The smallest candidate
+/// is always kept, so there is something to try.
+pub fn batch_size_candidates(
+    candidates: &[usize],
+    free_bytes: Option<u64>,
+    bytes_per_segment: u64,
+) -> Vec<usize> {
+    let fits = |size: usize| {
+        free_bytes.is_none_or(|free| (size as u64).saturating_mul(bytes_per_segment) <= free)
+    };
+    candidates
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|&(index, size)| index == 0 || fits(size))
+        .map(|(_, size)| size)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selected_device() {
+        assert_eq!(selected_device(None), Some(DeviceId::Index(0)));
+        assert_eq!(selected_device(Some("2,0")), Some(DeviceId::Index(2)));
+        assert_eq!(
+            selected_device(Some("GPU-8f6c1a2e-1b9d, 1")),
+            Some(DeviceId::Uuid("GPU-8f6c1a2e-1b9d".to_string()))
+        );
+        assert_eq!(selected_device(Some("")), None);
+        assert_eq!(selected_device(Some("-1")), None);
+    }
+
+    #[test]
+    fn test_own_memory() {
+        let processes = [
+            (4242, Some(1536 << 20)),
+            (977, Some(300 << 20)),
+            (4242, Some(512 << 20)),
+            (13, None),
+        ];
+        assert_eq!(own_memory(processes, 4242), Some(2048 << 20));
+        assert_eq!(own_memory(processes, 977), Some(300 << 20));
+        assert_eq!(own_memory(processes, 1), None);
+        assert_eq!(own_memory(processes, 13), None);
+    }
+
+    #[test]
+    fn test_batch_size_candidates() {
+        let candidates = [8, 16, 32, 64, 128];
+        let per_segment = 48 << 20;
+
+        // 4 GB free: 128 x 48 MiB no longer fits
+        assert_eq!(
+            batch_size_candidates(&candidates, Some(4 << 30), per_segment),
+            vec![8, 16, 32, 64]
+        );
+        assert_eq!(
+            batch_size_candidates(&candidates, Some(3 << 30), per_segment),
+            vec![8, 16, 32]
+        );
+        assert_eq!(
+            batch_size_candidates(&candidates, Some(1 << 20), per_segment),
+            vec![8]
+        );
+        assert_eq!(
+            batch_size_candidates(&candidates, None, per_segment),
+            candidates.to_vec()
+        );
+    }
+}
@@ -1,8 +1,19 @@
 //! GPU utilities for inference safety.
 //!
 //! This module provides the inference watchdog timer that kills the process
-//! if inference takes too long, indicating likely GPU memory exhaustion.
+//! if inference takes too long, indicating likely GPU memory exhaustion,
+//! helpers for backing off the batch size when the provider reports it,
+//! memory probing for batch size auto-tuning and run summaries, and the
+//! free memory check made before a run starts.
 
+mod health;
+mod memory;
+mod oom;
 mod watchdog;
 
-pub use watchdog::{WatchdogGuard, start_inference_watchdog};
+pub use health::{GpuCheck, MemoryRequirement, check_gpu};
+pub use memory::{
+    batch_size_candidates, free_memory_bytes, process_memory_bytes, used_memory_bytes,
+};
+pub use oom::{is_out_of_memory, reduced_batch_size};
+pub use watchdog::{WatchdogGuard, WatchdogPolicy, start_inference_watchdog};
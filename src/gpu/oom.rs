@@ -0,0 +1,65 @@
+//! GPU out-of-memory detection and batch size backoff.
+//!
+//! ONNX Runtime surfaces VRAM exhaustion as an ordinary inference error, so the
+//! error text is matched against known allocator messages from the CUDA,
+//! `TensorRT`, and `DirectML` providers.
+
+use crate::constants::gpu::OOM_ERROR_MARKERS;
+use crate::error::Error;
+
+/// Check whether an error indicates GPU memory exhaustion.
+pub fn is_out_of_memory(error: &Error) -> bool {
+    let Error::Inference { reason } = error else {
+        return false;
+    };
+
+    let reason = reason.to_lowercase();
+    OOM_ERROR_MARKERS
+        .iter()
+        .any(|marker| reason.contains(&marker.to_lowercase()))
+}
+
+/// Next batch size to try after an out-of-memory error.
+///
+/// Halves the batch size, returning `None` once it is already 1.
+pub fn reduced_batch_size(batch_size: usize) -> Option<usize> {
+    (batch_size > 1).then_some(batch_size / 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_out_of_memory_cuda() {
+        let err = Error::Inference {
+            reason: "CUDA failure 2: out of memory ; GPU=0".to_string(),
+        };
+        assert!(is_out_of_memory(&err));
+    }
+
+    #[test]
+    fn test_is_out_of_memory_allocator() {
+        let err = Error::Inference {
+            reason: "Failed to allocate memory for requested buffer of size 123".to_string(),
+        };
+        assert!(is_out_of_memory(&err));
+    }
+
+    #[test]
+    fn test_is_out_of_memory_other_error() {
+        let err = Error::Inference {
+            reason: "invalid input shape".to_string(),
+        };
+        assert!(!is_out_of_memory(&err));
+        assert!(!is_out_of_memory(&Error::DecodeChannelClosed));
+    }
+
+    #[test]
+    fn test_reduced_batch_size() {
+        assert_eq!(reduced_batch_size(64), Some(32));
+        assert_eq!(reduced_batch_size(3), Some(1));
+        assert_eq!(reduced_batch_size(1), None);
+        assert_eq!(reduced_batch_size(0), None);
+    }
+}
@@ -2,50 +2,180 @@
 //!
 //! Provides a watchdog that kills the process if inference takes too long,
 //! which typically indicates GPU memory exhaustion causing the system to hang.
+//! The timeout depends on the execution provider and can be overridden per
+//! provider in the `[inference.watchdog]` config section.
 
+use crate::config::WatchdogConfig;
+use crate::constants::watchdog::{
+    CPU_SECS, CUDA_SECS, FIRST_BATCH_MULTIPLIER, MAX_SECS, MIN_SECS, OTHER_GPU_SECS, TENSORRT_SECS,
+    TIMEOUT_ENV_VAR, WARNING_FRACTION,
+};
+use crate::output::{ErrorSeverity, ProgressReporter};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
 use std::time::Duration;
 
+/// Built-in timeout for an execution provider, in seconds.
+fn default_timeout_secs(provider_key: &str) -> u64 {
+    match provider_key {
+        "cpu" | "xnnpack" | "onednn" | "acl" | "armnn" => CPU_SECS,
+        "cuda" => CUDA_SECS,
+        "tensorrt" => TENSORRT_SECS,
+        _ => OTHER_GPU_SECS,
+    }
+}
+
+/// Read the timeout override from the environment, ignoring invalid values.
+fn env_timeout_secs() -> Option<u64> {
+    std::env::var(TIMEOUT_ENV_VAR)
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|&v| (MIN_SECS..=MAX_SECS).contains(&v))
+}
+
+/// Resolve the watchdog timeout for a provider.
+///
+/// Precedence: `BIRDA_INFERENCE_TIMEOUT` environment variable, per-provider
+/// config entry, global config `timeout_secs`, built-in provider default.
+fn resolve_timeout_secs(provider: &str, config: &WatchdogConfig, env: Option<u64>) -> u64 {
+    let key = provider.to_lowercase();
+    env.or_else(|| config.providers.get(&key).copied())
+        .or(config.timeout_secs)
+        .unwrap_or_else(|| default_timeout_secs(&key))
+}
+
+/// Watchdog timeout policy for the active execution provider.
+#[derive(Clone)]
+pub struct WatchdogPolicy {
+    /// Execution provider name (for messages).
+    provider: String,
+    /// Timeout for a regular batch.
+    timeout: Duration,
+    /// Reporter that receives warning and fatal events.
+    reporter: Option<Arc<dyn ProgressReporter>>,
+}
+
+impl WatchdogPolicy {
+    /// Build the policy for `provider` (e.g. "`TensorRT`", "CPU") from config.
+    #[must_use]
+    pub fn new(provider: &str, config: &WatchdogConfig) -> Self {
+        let secs = resolve_timeout_secs(provider, config, env_timeout_secs());
+        Self {
+            provider: provider.to_string(),
+            timeout: Duration::from_secs(secs),
+            reporter: None,
+        }
+    }
+
+    /// Send watchdog warning and timeout events to `reporter`.
+    #[must_use]
+    pub fn with_reporter(mut self, reporter: Arc<dyn ProgressReporter>) -> Self {
+        self.reporter = Some(reporter);
+        self
+    }
+
+    /// Timeout for a regular batch.
+    #[must_use]
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Timeout for a batch, with extra headroom for the first batch of a file.
+    #[must_use]
+    pub fn batch_timeout(&self, first_batch: bool) -> Duration {
+        if first_batch {
+            self.timeout * FIRST_BATCH_MULTIPLIER
+        } else {
+            self.timeout
+        }
+    }
+}
+
 /// Start a watchdog timer that kills the process if inference exceeds the deadline.
 ///
 /// Returns a guard that must be dropped when inference completes successfully.
-/// If the guard is not dropped before the timeout, the process exits with an error.
+/// Once a fraction of the deadline has passed, a warning event is emitted; if
+/// the guard is still alive at the deadline, a fatal error event is emitted and
+/// the process exits with an error.
 ///
 /// # Arguments
-/// * `timeout` - Maximum time allowed for the operation
+/// * `policy` - Timeout policy for the active provider
 /// * `batch_size` - Batch size being processed (for error message)
+/// * `first_batch` - Whether this is the first batch of a file
 ///
 /// # Returns
 /// A `WatchdogGuard` that cancels the watchdog when dropped.
-pub fn start_inference_watchdog(timeout: Duration, batch_size: usize) -> WatchdogGuard {
+pub fn start_inference_watchdog(
+    policy: &WatchdogPolicy,
+    batch_size: usize,
+    first_batch: bool,
+) -> WatchdogGuard {
     let cancelled = Arc::new(AtomicBool::new(false));
     let cancelled_clone = Arc::clone(&cancelled);
-    let timeout_secs = timeout.as_secs();
+    let timeout = policy.batch_timeout(first_batch);
+    let warn_after = timeout.mul_f64(WARNING_FRACTION);
+    let provider = policy.provider.clone();
+    let reporter = policy.reporter.clone();
 
     thread::spawn(move || {
-        thread::sleep(timeout);
-
-        if !cancelled_clone.load(Ordering::SeqCst) {
-            // Watchdog fired - inference didn't complete in time
-            let suggested_batch = (batch_size / 2).max(1);
-            eprintln!();
-            eprintln!("═══════════════════════════════════════════════════════════════");
-            eprintln!("FATAL: Inference timeout after {timeout_secs}s (batch size: {batch_size})");
-            eprintln!("═══════════════════════════════════════════════════════════════");
-            eprintln!();
-            eprintln!("The GPU inference operation did not complete within the expected time.");
-            eprintln!("This usually indicates GPU memory exhaustion causing the system to hang.");
-            eprintln!();
-            eprintln!("Recommendations:");
-            eprintln!("  1. Reduce batch size: birda -b {suggested_batch} <input>");
-            eprintln!("  2. Use CPU inference: birda --cpu <input>");
-            eprintln!("  3. Close other GPU applications and try again");
-            eprintln!();
-            eprintln!("Terminating process to prevent system lockup.");
-            std::process::exit(1);
+        thread::sleep(warn_after);
+        if cancelled_clone.load(Ordering::SeqCst) {
+            return;
         }
+
+        let timeout_secs = timeout.as_secs();
+        let message = format!(
+            "Inference on {provider} has been running for {}s (batch size: {batch_size}), \
+             process will be terminated after {timeout_secs}s",
+            warn_after.as_secs()
+        );
+        tracing::warn!("{}", message);
+        if let Some(reporter) = &reporter {
+            reporter.error(
+                "inference_slow",
+                ErrorSeverity::Warning,
+                &message,
+                Some("Raise the timeout in [inference.watchdog] if this hardware is slow"),
+            );
+        }
+
+        thread::sleep(timeout.saturating_sub(warn_after));
+        if cancelled_clone.load(Ordering::SeqCst) {
+            return;
+        }
+
+        // Watchdog fired - inference didn't complete in time
+        let suggested_batch = (batch_size / 2).max(1);
+        if let Some(reporter) = &reporter {
+            reporter.error(
+                "inference_timeout",
+                ErrorSeverity::Fatal,
+                &format!(
+                    "Inference on {provider} timed out after {timeout_secs}s (batch size: {batch_size})"
+                ),
+                Some(&format!("Reduce batch size: birda -b {suggested_batch} <input>")),
+            );
+        }
+        eprintln!();
+        eprintln!("═══════════════════════════════════════════════════════════════");
+        eprintln!("FATAL: Inference timeout after {timeout_secs}s (batch size: {batch_size})");
+        eprintln!("═══════════════════════════════════════════════════════════════");
+        eprintln!();
+        eprintln!("The GPU inference operation did not complete within the expected time.");
+        eprintln!("This usually indicates GPU memory exhaustion causing the system to hang.");
+        eprintln!();
+        eprintln!("Recommendations:");
+        eprintln!("  1. Reduce batch size: birda -b {suggested_batch} <input>");
+        eprintln!("  2. Use CPU inference: birda --cpu <input>");
+        eprintln!("  3. Close other GPU applications and try again");
+        eprintln!(
+            "  4. Raise the timeout: set {} = <seconds> under [inference.watchdog.providers]",
+            provider.to_lowercase()
+        );
+        eprintln!();
+        eprintln!("Terminating process to prevent system lockup.");
+        std::process::exit(1);
     });
 
     WatchdogGuard { cancelled }
@@ -66,13 +196,88 @@ impl Drop for WatchdogGuard {
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
+    use crate::output::{BatchProgress, FileProgress, FileStatus, PipelineSummary};
+    use std::path::Path;
+    use std::sync::Mutex;
+
+    /// Reporter that records error codes.
+    #[derive(Default)]
+    struct RecordingReporter {
+        codes: Mutex<Vec<String>>,
+    }
+
+    impl ProgressReporter for RecordingReporter {
+        fn pipeline_started(
+            &self,
+            _total_files: usize,
+            _model: &str,
+            _min_confidence: f32,
+            _execution_provider: &crate::output::ExecutionProviderInfo,
+            _range_filter: Option<&crate::output::RangeFilterInfo>,
+        ) {
+        }
+        fn file_started(
+            &self,
+            _file: &Path,
+            _index: usize,
+            _estimated_segments: usize,
+            _duration_seconds: Option<f64>,
+            _recorder: Option<&crate::audio::RecorderMetadata>,
+        ) {
+        }
+        fn progress(&self, _batch: Option<&BatchProgress>, _file: Option<&FileProgress>) {}
+        fn file_completed_success(
+            &self,
+            _file: &Path,
+            _detections: usize,
+            _species: &[crate::output::FileSpeciesSummary],
+            _duration_ms: u64,
+        ) {
+        }
+        fn file_completed_failure(&self, _file: &Path, _error_code: &str, _error_message: &str) {}
+        fn file_skipped(&self, _file: &Path, _reason: FileStatus) {}
+        fn pipeline_completed(&self, _summary: &PipelineSummary) {}
+        fn error(
+            &self,
+            code: &str,
+            _severity: ErrorSeverity,
+            _message: &str,
+            _suggestion: Option<&str>,
+        ) {
+            self.codes.lock().unwrap().push(code.to_string());
+        }
+        fn cancelled(
+            &self,
+            _reason: crate::output::CancelReason,
+            _files_completed: usize,
+            _files_total: usize,
+        ) {
+        }
+        fn detections(
+            &self,
+            _file: &Path,
+            _detections: &[crate::output::Detection],
+            _bsg_metadata: Option<&crate::output::BsgMetadata>,
+        ) {
+        }
+        fn flush(&self) {}
+    }
+
+    fn policy(secs: u64) -> WatchdogPolicy {
+        WatchdogPolicy {
+            provider: "CUDA".to_string(),
+            timeout: Duration::from_secs(secs),
+            reporter: None,
+        }
+    }
 
     #[test]
     fn test_watchdog_cancelled_when_dropped() {
         // Create watchdog with 1 second timeout
-        let guard = start_inference_watchdog(Duration::from_secs(1), 32);
+        let guard = start_inference_watchdog(&policy(1), 32, false);
 
         // Drop immediately - should cancel the watchdog
         drop(guard);
@@ -83,6 +288,58 @@ mod tests {
         // If we get here, the test passed (process didn't exit)
     }
 
+    #[test]
+    fn test_watchdog_warns_before_timeout() {
+        let reporter = Arc::new(RecordingReporter::default());
+        let policy = policy(2).with_reporter(reporter.clone());
+
+        let guard = start_inference_watchdog(&policy, 32, false);
+        // Warning fires at 1.5s; drop before the 2s deadline
+        thread::sleep(Duration::from_millis(1750));
+        drop(guard);
+        thread::sleep(Duration::from_millis(500));
+
+        assert_eq!(*reporter.codes.lock().unwrap(), vec!["inference_slow"]);
+    }
+
+    #[test]
+    fn test_resolve_timeout_defaults_by_provider() {
+        let config = WatchdogConfig::default();
+        assert_eq!(resolve_timeout_secs("CPU", &config, None), CPU_SECS);
+        assert_eq!(resolve_timeout_secs("CUDA", &config, None), CUDA_SECS);
+        assert_eq!(
+            resolve_timeout_secs("TensorRT", &config, None),
+            TENSORRT_SECS
+        );
+        assert_eq!(
+            resolve_timeout_secs("DirectML", &config, None),
+            OTHER_GPU_SECS
+        );
+    }
+
+    #[test]
+    fn test_resolve_timeout_precedence() {
+        let mut config = WatchdogConfig {
+            timeout_secs: Some(15),
+            ..WatchdogConfig::default()
+        };
+        config.providers.insert("tensorrt".to_string(), 90);
+
+        assert_eq!(resolve_timeout_secs("TensorRT", &config, None), 90);
+        assert_eq!(resolve_timeout_secs("CUDA", &config, None), 15);
+        assert_eq!(resolve_timeout_secs("TensorRT", &config, Some(7)), 7);
+    }
+
+    #[test]
+    fn test_first_batch_gets_extra_time() {
+        let policy = policy(10);
+        assert_eq!(policy.batch_timeout(false), Duration::from_secs(10));
+        assert_eq!(
+            policy.batch_timeout(true),
+            Duration::from_secs(10 * u64::from(FIRST_BATCH_MULTIPLIER))
+        );
+    }
+
     #[test]
     fn test_watchdog_guard_is_send() {
         // Ensure WatchdogGuard can be sent across threads
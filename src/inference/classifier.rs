@@ -1,12 +1,13 @@
 //! Inference classifier wrapper around birdnet-onnx.
 
 use crate::config::{
-    InferenceDevice, ModelConfig as BirdaModelConfig, ModelType, tensorrt_cache_dir,
+    ArenaExtendStrategy, CustomHeadConfig, InferenceDevice, ModelConfig as BirdaModelConfig,
+    ModelType, tensorrt_cache_dir, validate_custom_head,
 };
 use crate::error::{Error, Result};
 use birdnet_onnx::{
-    BatchInferenceContext, BsgPostProcessor, Classifier, ClassifierBuilder, ExecutionProviderInfo,
-    InferenceOptions, LocationScore, PredictionResult, TensorRTConfig,
+    BatchInferenceContext, BsgPostProcessor, Classifier, ClassifierBuilder, CustomClassifier,
+    ExecutionProviderInfo, InferenceOptions, LocationScore, PredictionResult, TensorRTConfig,
     available_execution_providers, ort_execution_providers,
 };
 use std::collections::{HashMap, HashSet};
@@ -28,16 +29,55 @@ pub struct ExecutionProviderStatus {
 
 /// Range filtering data that is always kept together.
 ///
-/// Invariant: All three fields are present together. This struct encapsulates
-/// the range filter, its configuration, and pre-computed location scores.
+/// This struct encapsulates the range filter, its configuration, and the
+/// location scores of the configured location.
 struct RangeFilterData {
     /// The range filter instance.
     filter: crate::inference::range_filter::RangeFilter,
     /// Range filter configuration parameters.
     config: crate::inference::RangeFilterConfig,
+    /// Meta model label → classifier label, when the meta model belongs to
+    /// another model.
+    mapping: Option<HashMap<String, String>>,
     /// Pre-computed location scores (computed once at initialization).
     /// Avoids recomputing scores on every batch (significant performance optimization).
-    scores: Vec<LocationScore>,
+    /// `None` when each recording is filtered at its own location.
+    scores: Option<Vec<LocationScore>>,
+}
+
+impl RangeFilterData {
+    /// Location scores at `latitude`/`longitude` on the configured date, in
+    /// the classifier's label format.
+    fn scores_at(&self, latitude: f64, longitude: f64) -> Result<Vec<LocationScore>> {
+        let scores =
+            self.filter
+                .predict(latitude, longitude, self.config.month, self.config.day)?;
+        let Some(mapping) = &self.mapping else {
+            return Ok(scores);
+        };
+
+        // Remap scores to the classifier's label format, then fill in
+        // zero-score entries for mapped species not in the remapped scores.
+        // predict() only returns species above the threshold, so species that
+        // ARE in the mapping but below the threshold have no entry. Without
+        // this, filter_predictions_impl treats missing entries as "keep
+        // unchanged", letting out-of-range species pass through unfiltered.
+        let mut remapped_scores = remap_location_scores(scores, mapping);
+        fill_missing_mapped_scores(&mut remapped_scores, mapping);
+        Ok(remapped_scores)
+    }
+}
+
+/// Number of CPU sessions to run: `requested`, but no more than `cores` and,
+/// when known, the `loaded` sessions plus all but one of the `spare` sessions
+/// that would fit in available memory.
+fn session_limit(requested: usize, cores: usize, loaded: usize, spare: Option<u64>) -> usize {
+    let limit = requested.min(cores);
+    let limit = spare.map_or(limit, |spare| {
+        let fits = usize::try_from(spare.saturating_sub(1)).unwrap_or(usize::MAX);
+        limit.min(loaded.saturating_add(fits))
+    });
+    limit.max(1)
 }
 
 /// Extract scientific name from a BirdNET-format label.
@@ -144,6 +184,27 @@ fn load_cross_model_labels(path: &std::path::Path) -> Result<Vec<String>> {
     Ok(labels)
 }
 
+/// Build a user-trained classifier head for a model of `model_type`.
+fn build_custom_head(model_type: ModelType, head: &CustomHeadConfig) -> Result<CustomClassifier> {
+    validate_custom_head(model_type, head)?;
+
+    let head = CustomClassifier::builder()
+        .model_path(&head.path)
+        .labels_path(&head.labels)
+        .build()
+        .map_err(|e| Error::ClassifierBuild {
+            reason: format!("failed to build custom classifier head: {e}"),
+        })?;
+
+    info!(
+        "Custom classifier head loaded: {} classes, {}-dim embeddings",
+        head.num_classes(),
+        head.input_dim()
+    );
+
+    Ok(head)
+}
+
 /// Wrapper around birdnet-onnx Classifier with birda configuration.
 pub struct BirdClassifier {
     inner: Classifier,
@@ -159,6 +220,12 @@ pub struct BirdClassifier {
     bsg_processor: Option<BsgPostProcessor>,
     /// Execution provider status (requested, actual, fallback reason).
     ep_status: ExecutionProviderStatus,
+    /// User-trained classifier head that replaces the model's predictions.
+    custom_head: Option<CustomClassifier>,
+    /// Extra CPU sessions of the model that share each batch with `inner`.
+    cpu_sessions: Vec<Classifier>,
+    /// Detection sensitivity that reshapes every confidence.
+    sensitivity: f32,
 }
 
 impl BirdClassifier {
@@ -170,6 +237,7 @@ impl BirdClassifier {
         top_k: usize,
         range_filter_config: Option<crate::inference::RangeFilterConfig>,
         species_list: Option<HashSet<String>>,
+        gpu_memory: Option<GpuMemoryOptions>,
     ) -> Result<Self> {
         // Check available execution providers at compile-time
         let available_providers = available_execution_providers();
@@ -192,7 +260,7 @@ impl BirdClassifier {
             builder,
             device_name: actual_device_msg,
             status: ep_status,
-        } = select_execution_provider(builder, device, &available_providers)?;
+        } = select_execution_provider(builder, device, gpu_memory, &available_providers)?;
 
         let inner = builder.build().map_err(|e| Error::ClassifierBuild {
             reason: e.to_string(),
@@ -221,10 +289,10 @@ impl BirdClassifier {
         } else if let Some(rf_config) = range_filter_config {
             use crate::inference::range_filter::RangeFilter;
 
-            let filter_and_scores = if let Some(ref cross_labels_path) =
+            let filter_and_mapping = if let Some(ref cross_labels_path) =
                 rf_config.cross_model_labels
             {
-                // Cross-model mode: load fallback model's labels, build filter, remap scores
+                // Cross-model mode: load fallback model's labels, build filter, map labels
                 let meta_labels = load_cross_model_labels(cross_labels_path)?;
 
                 let filter = RangeFilter::from_config(
@@ -233,23 +301,8 @@ impl BirdClassifier {
                     rf_config.threshold,
                 )?;
 
-                let raw_scores = filter.predict(
-                    rf_config.latitude,
-                    rf_config.longitude,
-                    rf_config.month,
-                    rf_config.day,
-                )?;
-
-                // Build mapping and remap scores to classifier's label format
+                // Build mapping to remap scores to classifier's label format
                 let mapping = build_cross_model_mapping(&meta_labels, inner.labels());
-                let mut remapped_scores = remap_location_scores(raw_scores, &mapping);
-
-                // Fill in zero-score entries for mapped species not in the remapped scores.
-                // predict() only returns species above the threshold, so species that ARE
-                // in the mapping but below the threshold have no entry. Without this,
-                // filter_predictions_impl treats missing entries as "keep unchanged",
-                // letting out-of-range species pass through unfiltered.
-                fill_missing_mapped_scores(&mut remapped_scores, &mapping);
 
                 let source = rf_config.meta_model_source.as_deref().unwrap_or("unknown");
                 info!(
@@ -259,11 +312,11 @@ impl BirdClassifier {
                     source
                 );
 
-                if remapped_scores.is_empty() {
+                if mapping.is_empty() {
                     warn!("Cross-model range filter produced zero matching species, disabling");
                     None
                 } else {
-                    Some((filter, remapped_scores))
+                    Some((filter, Some(mapping)))
                 }
             } else {
                 // Same-model mode: use classifier's own labels (existing behavior)
@@ -273,31 +326,29 @@ impl BirdClassifier {
                     rf_config.threshold,
                 )?;
 
-                let scores = filter.predict(
-                    rf_config.latitude,
-                    rf_config.longitude,
-                    rf_config.month,
-                    rf_config.day,
-                )?;
-
-                Some((filter, scores))
+                Some((filter, None))
             };
 
-            if let Some((filter, scores)) = filter_and_scores {
-                debug!(
-                    "Range filter: computed {} location scores for lat={:.4}, lon={:.4}, month={}, day={}",
-                    scores.len(),
-                    rf_config.latitude,
-                    rf_config.longitude,
-                    rf_config.month,
-                    rf_config.day
-                );
-
-                Some(RangeFilterData {
+            if let Some((filter, mapping)) = filter_and_mapping {
+                let mut data = RangeFilterData {
                     filter,
                     config: rf_config,
-                    scores,
-                })
+                    mapping,
+                    scores: None,
+                };
+                if let Some((latitude, longitude)) = data.config.location {
+                    let scores = data.scores_at(latitude, longitude)?;
+                    debug!(
+                        "Range filter: computed {} location scores for lat={:.4}, lon={:.4}, month={}, day={}",
+                        scores.len(),
+                        latitude,
+                        longitude,
+                        data.config.month,
+                        data.config.day
+                    );
+                    data.scores = Some(scores);
+                }
+                Some(data)
             } else {
                 None
             }
@@ -342,6 +393,12 @@ impl BirdClassifier {
             None
         };
 
+        let custom_head = model_config
+            .custom_head
+            .as_ref()
+            .map(|head| build_custom_head(model_config.model_type, head))
+            .transpose()?;
+
         Ok(Self {
             inner,
             range_filter_data,
@@ -349,9 +406,102 @@ impl BirdClassifier {
             uses_tensorrt,
             bsg_processor,
             ep_status,
+            custom_head,
+            cpu_sessions: Vec::new(),
+            sensitivity: crate::constants::sensitivity::DEFAULT,
         })
     }
 
+    /// Load up to `count` more sessions of the model on the CPU, so each batch
+    /// is split across sessions that run in parallel.
+    ///
+    /// A single CPU session rarely keeps every core of a large machine busy,
+    /// even with large batches. The total is capped at one session per core,
+    /// and at what fits in available memory going by the resident size of the
+    /// first added session. The cores are divided between the sessions, so
+    /// each runs `cores / sessions` intra-op threads instead of one per core,
+    /// and the original session is reloaded with its share. Only applies when
+    /// inference runs on the CPU.
+    ///
+    /// Returns the number of sessions in use, including the original one.
+    pub fn add_cpu_sessions(
+        &mut self,
+        model_config: &BirdaModelConfig,
+        count: usize,
+        min_confidence: f32,
+        top_k: usize,
+    ) -> Result<usize> {
+        use crate::utils::memory::{available_memory_bytes, resident_memory_bytes};
+
+        if count == 0 {
+            return Ok(1);
+        }
+        if self.ep_status.actual != "CPU" {
+            warn!(
+                "Ignoring extra CPU sessions: inference runs on {}",
+                self.ep_status.actual
+            );
+            return Ok(1);
+        }
+
+        let cores = std::thread::available_parallelism().map_or(1, std::num::NonZero::get);
+        let mut limit = session_limit(count + 1, cores, 1, None);
+        // Sessions running in parallel share the cores instead of
+        // oversubscribing them with a thread per core each
+        let mut threads = (cores / limit).max(1);
+        let build = |threads: usize| {
+            ClassifierBuilder::new()
+                .model_path(model_config.path.to_string_lossy().to_string())
+                .labels_path(model_config.labels.to_string_lossy().to_string())
+                .top_k(top_k)
+                .min_confidence(min_confidence)
+                .intra_threads(threads)
+                .build()
+                .map_err(|e| Error::ClassifierBuild {
+                    reason: e.to_string(),
+                })
+        };
+        while self.cpu_sessions.len() + 1 < limit {
+            let before = resident_memory_bytes();
+            self.cpu_sessions.push(build(threads)?);
+
+            if self.cpu_sessions.len() == 1 {
+                let spare = before
+                    .zip(resident_memory_bytes())
+                    .map(|(before, after)| after.saturating_sub(before))
+                    .filter(|&size| size > 0)
+                    .zip(available_memory_bytes())
+                    .map(|(size, available)| available / size);
+                limit = session_limit(count + 1, cores, 2, spare);
+
+                // Fewer sessions than planned get more of the cores each
+                let shared = (cores / limit).max(1);
+                if shared != threads {
+                    threads = shared;
+                    if let Some(first) = self.cpu_sessions.first_mut() {
+                        *first = build(threads)?;
+                    }
+                }
+            }
+        }
+
+        // The original session was loaded with a thread per core
+        if !self.cpu_sessions.is_empty() {
+            self.inner = build(threads)?;
+        }
+
+        let sessions = self.cpu_sessions.len() + 1;
+        if sessions < count + 1 {
+            warn!(
+                "Using {sessions} CPU sessions instead of {}: limited by {cores} cores and available memory",
+                count + 1
+            );
+        } else {
+            info!("Using {sessions} CPU sessions with {threads} threads each");
+        }
+        Ok(sessions)
+    }
+
     /// Get the model configuration.
     pub fn config(&self) -> &birdnet_onnx::ModelConfig {
         self.inner.config()
@@ -372,6 +522,20 @@ impl BirdClassifier {
         self.inner.config().sample_count
     }
 
+    /// Get the number of labels (classes) the model predicts.
+    ///
+    /// With a custom classifier head, this is the head's class count.
+    pub fn label_count(&self) -> usize {
+        self.custom_head
+            .as_ref()
+            .map_or_else(|| self.inner.labels().len(), CustomClassifier::num_classes)
+    }
+
+    /// Check if a custom classifier head replaces the model's predictions.
+    pub fn has_custom_head(&self) -> bool {
+        self.custom_head.is_some()
+    }
+
     /// Check if `TensorRT` is being used.
     pub fn uses_tensorrt(&self) -> bool {
         self.uses_tensorrt
@@ -389,7 +553,10 @@ impl BirdClassifier {
             .map(|data| crate::output::RangeFilterInfo {
                 cross_model: data.config.cross_model_labels.is_some(),
                 meta_model_source: data.config.meta_model_source.clone(),
-                species_in_range: data.scores.iter().filter(|s| s.score > 0.0).count(),
+                species_in_range: data
+                    .scores
+                    .as_ref()
+                    .map(|scores| scores.iter().filter(|s| s.score > 0.0).count()),
                 total_species: self.inner.labels().len(),
             })
     }
@@ -433,11 +600,15 @@ impl BirdClassifier {
 
     /// Run inference on a single audio segment.
     pub fn predict(&self, segment: &[f32], options: &InferenceOptions) -> Result<PredictionResult> {
-        self.inner
+        let mut result = self
+            .inner
             .predict(segment, options)
             .map_err(|e| Error::Inference {
                 reason: e.to_string(),
-            })
+            })?;
+        self.apply_custom_head(std::slice::from_mut(&mut result))?;
+        self.apply_sensitivity(std::slice::from_mut(&mut result));
+        Ok(result)
     }
 
     /// Run inference on a batch of audio segments.
@@ -446,11 +617,64 @@ impl BirdClassifier {
         segments: &[&[f32]],
         options: &InferenceOptions,
     ) -> Result<Vec<PredictionResult>> {
+        let mut results = if self.cpu_sessions.is_empty() || segments.len() < 2 {
+            self.inner
+                .predict_batch(segments, options)
+                .map_err(|e| Error::Inference {
+                    reason: e.to_string(),
+                })?
+        } else {
+            self.predict_batch_parallel(segments, options)?
+        };
+        self.apply_custom_head(&mut results)?;
+        self.apply_sensitivity(&mut results);
+        Ok(results)
+    }
+
+    /// Split a batch across the CPU sessions and run the parts in parallel,
+    /// returning results in segment order.
+    fn predict_batch_parallel(
+        &self,
+        segments: &[&[f32]],
+        options: &InferenceOptions,
+    ) -> Result<Vec<PredictionResult>> {
+        let sessions: Vec<&Classifier> = std::iter::once(&self.inner)
+            .chain(&self.cpu_sessions)
+            .collect();
+        let share = segments.len().div_ceil(sessions.len());
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = sessions
+                .into_iter()
+                .zip(segments.chunks(share))
+                .map(|(session, part)| scope.spawn(move || session.predict_batch(part, options)))
+                .collect();
+            let mut results = Vec::with_capacity(segments.len());
+            for handle in handles {
+                let part = handle
+                    .join()
+                    .map_err(|_| Error::Inference {
+                        reason: "inference thread panicked".to_string(),
+                    })?
+                    .map_err(|e| Error::Inference {
+                        reason: e.to_string(),
+                    })?;
+                results.extend(part);
+            }
+            Ok(results)
+        })
+    }
+
+    /// Extract backbone embeddings for a batch of audio segments, one vector
+    /// per segment.
+    pub fn predict_embeddings(&self, segments: &[&[f32]]) -> Result<Vec<Vec<f32>>> {
         self.inner
-            .predict_batch(segments, options)
+            .predict_batch(segments, &InferenceOptions::default())
             .map_err(|e| Error::Inference {
                 reason: e.to_string(),
-            })
+            })?
+            .into_iter()
+            .map(|r| r.embeddings.ok_or(Error::EmbeddingsUnavailable))
+            .collect()
     }
 
     /// Check if this classifier has BSG post-processing enabled.
@@ -524,18 +748,78 @@ impl BirdClassifier {
     /// Run inference on a batch of audio segments using a pre-allocated context.
     ///
     /// This method reuses GPU memory from the context, preventing memory growth
-    /// across repeated batch inference calls.
+    /// across repeated batch inference calls. With extra CPU sessions the
+    /// context is not used, since each session needs its own buffers.
     pub fn predict_batch_with_context(
         &self,
         context: &mut BatchInferenceContext,
         segments: &[&[f32]],
         options: &InferenceOptions,
     ) -> Result<Vec<PredictionResult>> {
-        self.inner
+        if !self.cpu_sessions.is_empty() {
+            return self.predict_batch(segments, options);
+        }
+        let mut results = self
+            .inner
             .predict_batch_with_context(context, segments, options)
             .map_err(|e| Error::Inference {
                 reason: e.to_string(),
-            })
+            })?;
+        self.apply_custom_head(&mut results)?;
+        self.apply_sensitivity(&mut results);
+        Ok(results)
+    }
+
+    /// Replace predictions with those of the custom classifier head, if any.
+    ///
+    /// Runs before range and species list filtering, so head labels in the
+    /// `Scientific name_Common name` form are filtered like model labels.
+    fn apply_custom_head(&self, results: &mut [PredictionResult]) -> Result<()> {
+        let Some(head) = &self.custom_head else {
+            return Ok(());
+        };
+        let embeddings = results
+            .iter()
+            .map(|r| r.embeddings.clone().ok_or(Error::EmbeddingsUnavailable))
+            .collect::<Result<Vec<_>>>()?;
+        let predictions = head
+            .predict_batch(&embeddings)
+            .map_err(|e| Error::Inference {
+                reason: format!("custom classifier head failed: {e}"),
+            })?;
+        if predictions.len() != results.len() {
+            return Err(Error::Inference {
+                reason: format!(
+                    "custom classifier head returned {} predictions for {} segments",
+                    predictions.len(),
+                    results.len()
+                ),
+            });
+        }
+        for (result, predictions) in results.iter_mut().zip(predictions) {
+            result.predictions = predictions;
+        }
+        Ok(())
+    }
+
+    /// Reshape confidences like BirdNET-Analyzer's `--sensitivity`.
+    ///
+    /// The model's own confidence threshold must come from
+    /// [`super::sensitivity_threshold`], so that predictions lifted above the
+    /// minimum confidence are not dropped before reshaping.
+    pub fn set_sensitivity(&mut self, sensitivity: f32) {
+        self.sensitivity = sensitivity;
+    }
+
+    /// Apply the detection sensitivity to every prediction.
+    fn apply_sensitivity(&self, results: &mut [PredictionResult]) {
+        if super::is_default_sensitivity(self.sensitivity) {
+            return;
+        }
+        for prediction in results.iter_mut().flat_map(|r| r.predictions.iter_mut()) {
+            prediction.confidence =
+                super::apply_sensitivity(prediction.confidence, self.sensitivity);
+        }
     }
 
     /// Get the optional range filter.
@@ -543,16 +827,60 @@ impl BirdClassifier {
         self.range_filter_data.as_ref().map(|data| &data.filter)
     }
 
+    /// Location scores for a recording made at `latitude`/`longitude`.
+    ///
+    /// Returns `Ok(None)` unless the range filter is located per recording;
+    /// a filter with a configured location uses its own scores.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the meta model fails to predict.
+    pub fn location_scores(
+        &self,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<Option<Vec<LocationScore>>> {
+        match &self.range_filter_data {
+            Some(data) if data.scores.is_none() => {
+                let scores = data.scores_at(latitude, longitude)?;
+                debug!(
+                    "Range filter: computed {} location scores for lat={:.4}, lon={:.4}",
+                    scores.len(),
+                    latitude,
+                    longitude
+                );
+                Ok(Some(scores))
+            }
+            _ => Ok(None),
+        }
+    }
+
     /// Apply range filtering to predictions if configured.
     ///
     /// Returns filtered predictions. If range filtering is not enabled, returns predictions unchanged.
     pub fn apply_range_filter(
+        &self,
+        predictions: Vec<PredictionResult>,
+    ) -> Result<Vec<PredictionResult>> {
+        self.apply_range_filter_at(predictions, None)
+    }
+
+    /// Apply range filtering with the `location_scores` of the recording,
+    /// when the range filter is located per recording.
+    ///
+    /// Without scores, such a filter leaves predictions unchanged.
+    pub fn apply_range_filter_at(
         &self,
         mut predictions: Vec<PredictionResult>,
+        location_scores: Option<&[LocationScore]>,
     ) -> Result<Vec<PredictionResult>> {
         if let Some(rf_data) = &self.range_filter_data {
             use tracing::debug;
 
+            let Some(scores) = rf_data.scores.as_deref().or(location_scores) else {
+                return Ok(predictions);
+            };
+
             debug!(
                 "Range filter: applying to {} prediction results",
                 predictions.len()
@@ -564,7 +892,7 @@ impl BirdClassifier {
 
                 result.predictions = rf_data.filter.filter_predictions(
                     &result.predictions,
-                    &rf_data.scores,
+                    scores,
                     rf_data.config.rerank,
                 );
 
@@ -614,6 +942,17 @@ impl BirdClassifier {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_session_limit() {
+        // Capped by cores
+        assert_eq!(session_limit(16, 8, 1, None), 8);
+        assert_eq!(session_limit(4, 8, 1, None), 4);
+        // Two sessions loaded and room for three more, one of which stays spare
+        assert_eq!(session_limit(8, 32, 2, Some(3)), 4);
+        // Never below one
+        assert_eq!(session_limit(4, 0, 1, Some(0)), 1);
+    }
+
     #[test]
     fn test_extract_scientific_name_birdnet_format() {
         assert_eq!(
@@ -949,6 +1288,7 @@ struct ProviderSelection {
 fn select_execution_provider(
     builder: ClassifierBuilder,
     device: InferenceDevice,
+    gpu_memory: Option<GpuMemoryOptions>,
     available_providers: &[ExecutionProviderInfo],
 ) -> Result<ProviderSelection> {
     // GPU provider priority order (shared by Auto and --gpu modes)
@@ -1019,7 +1359,7 @@ fn select_execution_provider(
                 .find(|(p, _)| available_providers.contains(p))
             {
                 info!("Auto mode: {} available, attempting GPU", name);
-                let builder = add_execution_provider(builder, provider_info);
+                let builder = add_execution_provider(builder, provider_info, gpu_memory);
                 (
                     builder,
                     name,
@@ -1090,7 +1430,7 @@ fn select_execution_provider(
                 .find(|(p, _)| available_providers.contains(p))
             {
                 info!("--gpu: Selected {} provider", name);
-                let builder = add_execution_provider(builder, provider_info);
+                let builder = add_execution_provider(builder, provider_info, gpu_memory);
 
                 // Combine fallback reasons
                 let fallback = match (tensorrt_fallback, cuda_fallback) {
@@ -1134,66 +1474,77 @@ fn select_execution_provider(
         // Explicit providers use the helper function
         InferenceDevice::Cuda => configure_explicit_provider(
             builder,
+            gpu_memory,
             available_providers,
             ExecutionProviderInfo::Cuda,
             "CUDA",
         )?,
         InferenceDevice::TensorRt => configure_explicit_provider(
             builder,
+            gpu_memory,
             available_providers,
             ExecutionProviderInfo::TensorRt,
             "TensorRT",
         )?,
         InferenceDevice::DirectMl => configure_explicit_provider(
             builder,
+            gpu_memory,
             available_providers,
             ExecutionProviderInfo::DirectMl,
             "DirectML",
         )?,
         InferenceDevice::CoreMl => configure_explicit_provider(
             builder,
+            gpu_memory,
             available_providers,
             ExecutionProviderInfo::CoreMl,
             "CoreML",
         )?,
         InferenceDevice::Rocm => configure_explicit_provider(
             builder,
+            gpu_memory,
             available_providers,
             ExecutionProviderInfo::Rocm,
             "ROCm",
         )?,
         InferenceDevice::OpenVino => configure_explicit_provider(
             builder,
+            gpu_memory,
             available_providers,
             ExecutionProviderInfo::OpenVino,
             "OpenVINO",
         )?,
         InferenceDevice::OneDnn => configure_explicit_provider(
             builder,
+            gpu_memory,
             available_providers,
             ExecutionProviderInfo::OneDnn,
             "oneDNN",
         )?,
         InferenceDevice::Qnn => configure_explicit_provider(
             builder,
+            gpu_memory,
             available_providers,
             ExecutionProviderInfo::Qnn,
             "QNN",
         )?,
         InferenceDevice::Acl => configure_explicit_provider(
             builder,
+            gpu_memory,
             available_providers,
             ExecutionProviderInfo::Acl,
             "ACL",
         )?,
         InferenceDevice::ArmNn => configure_explicit_provider(
             builder,
+            gpu_memory,
             available_providers,
             ExecutionProviderInfo::ArmNn,
             "ArmNN",
         )?,
         InferenceDevice::Xnnpack => configure_explicit_provider(
             builder,
+            gpu_memory,
             available_providers,
             ExecutionProviderInfo::Xnnpack,
             "XNNPACK",
@@ -1210,6 +1561,7 @@ fn select_execution_provider(
 /// Configure an explicit execution provider (fail if unavailable).
 fn configure_explicit_provider(
     builder: ClassifierBuilder,
+    gpu_memory: Option<GpuMemoryOptions>,
     available_providers: &[ExecutionProviderInfo],
     provider_info: ExecutionProviderInfo,
     provider_name: &'static str,
@@ -1261,7 +1613,7 @@ fn configure_explicit_provider(
     }
 
     info!("Requested device: {provider_name}");
-    let builder = add_execution_provider(builder, provider_info);
+    let builder = add_execution_provider(builder, provider_info, gpu_memory);
     let ep_status = ExecutionProviderStatus {
         requested: provider_name.to_lowercase(),
         actual: provider_name.to_string(),
@@ -1312,10 +1664,46 @@ fn setup_tensorrt_cache() -> Option<PathBuf> {
     Some(cache_dir)
 }
 
+/// GPU memory settings for the CUDA provider.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GpuMemoryOptions {
+    /// Most memory the arena may hold, in bytes.
+    pub limit_bytes: Option<u64>,
+    /// How the arena grows.
+    pub arena_extend_strategy: ArenaExtendStrategy,
+}
+
+/// CUDA provider configured with `options`.
+fn cuda_provider(options: GpuMemoryOptions) -> ort_execution_providers::CUDAExecutionProvider {
+    use ort_execution_providers::ArenaExtendStrategy as OrtStrategy;
+
+    let strategy = match options.arena_extend_strategy {
+        ArenaExtendStrategy::SameAsRequested => OrtStrategy::SameAsRequested,
+        ArenaExtendStrategy::NextPowerOfTwo => OrtStrategy::NextPowerOfTwo,
+    };
+    let provider = ort_execution_providers::CUDAExecutionProvider::default()
+        .with_arena_extend_strategy(strategy);
+    match options.limit_bytes {
+        Some(limit) => {
+            info!(
+                "Limiting CUDA memory arena to {} MiB ({} growth)",
+                limit >> 20,
+                options.arena_extend_strategy
+            );
+            provider.with_memory_limit(usize::try_from(limit).unwrap_or(usize::MAX))
+        }
+        None => provider,
+    }
+}
+
 /// Helper function to add execution provider to builder based on provider type.
+///
+/// `gpu_memory` configures the CUDA provider's arena; without it CUDA keeps
+/// its defaults.
 fn add_execution_provider(
     builder: ClassifierBuilder,
     provider_info: ExecutionProviderInfo,
+    gpu_memory: Option<GpuMemoryOptions>,
 ) -> ClassifierBuilder {
     #[allow(deprecated)]
     use ort_execution_providers::{
@@ -1325,11 +1713,17 @@ fn add_execution_provider(
     };
 
     match provider_info {
-        ExecutionProviderInfo::Cuda => {
+        ExecutionProviderInfo::Cuda => match gpu_memory {
+            Some(options) => builder.execution_provider(cuda_provider(options)),
             // Use with_cuda() for safe memory defaults (SameAsRequested arena strategy)
-            builder.with_cuda()
-        }
+            None => builder.with_cuda(),
+        },
         ExecutionProviderInfo::TensorRt => {
+            if gpu_memory.is_some() {
+                warn!(
+                    "--gpu-mem-limit and --arena-extend-strategy apply to the CUDA provider only; TensorRT manages its own memory"
+                );
+            }
             // Use optimized TensorRT configuration with app-specific cache directory
             let config = setup_tensorrt_cache().map_or_else(TensorRTConfig::new, |cache_dir| {
                 // UTF-8 validated in setup_tensorrt_cache; panic if invariant violated
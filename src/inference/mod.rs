@@ -6,13 +6,16 @@ mod library_detection;
 mod provider;
 pub mod range_filter;
 mod runtime;
+mod sensitivity;
+pub mod tensorrt_cache;
 mod tensorrt_detection;
 
 pub use birdnet_onnx::{BatchInferenceContext, InferenceOptions};
-pub use classifier::{BirdClassifier, ExecutionProviderStatus};
+pub use classifier::{BirdClassifier, ExecutionProviderStatus, GpuMemoryOptions};
 pub use cuda_detection::{get_cuda_library_patterns, is_cuda_available};
 pub use provider::{ProviderMetadata, provider_metadata};
 pub use runtime::ensure_runtime_available;
+pub use sensitivity::{apply_sensitivity, is_default_sensitivity, sensitivity_threshold};
 pub use tensorrt_detection::{get_tensorrt_library_name, is_tensorrt_available};
 
 use std::path::PathBuf;
@@ -24,10 +27,9 @@ pub struct RangeFilterConfig {
     pub meta_model_path: PathBuf,
     /// Filtering threshold.
     pub threshold: f32,
-    /// Latitude.
-    pub latitude: f64,
-    /// Longitude.
-    pub longitude: f64,
+    /// Location as (latitude, longitude); `None` when each recording
+    /// supplies its own.
+    pub location: Option<(f64, f64)>,
     /// Month (1-12).
     pub month: u32,
     /// Day (1-31).
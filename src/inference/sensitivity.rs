@@ -0,0 +1,85 @@
+//! Detection sensitivity matching BirdNET-Analyzer.
+//!
+//! BirdNET-Analyzer turns logits into confidences with a sigmoid whose slope
+//! is `2 - sensitivity`: higher sensitivity flattens the curve, raising low
+//! confidences and lowering high ones. The model's confidences are already
+//! sigmoid outputs, so they are mapped back to logits and reshaped here.
+
+use crate::constants::sensitivity::{DEFAULT, LOGIT_CLIP};
+
+/// Sigmoid slope for a sensitivity.
+fn slope(sensitivity: f32) -> f32 {
+    2.0 - sensitivity
+}
+
+/// Logit of a confidence, clipped like BirdNET-Analyzer's.
+fn logit(confidence: f32) -> f32 {
+    let p = confidence.clamp(f32::EPSILON, 1.0 - f32::EPSILON);
+    (p / (1.0 - p)).ln().clamp(-LOGIT_CLIP, LOGIT_CLIP)
+}
+
+/// Sigmoid of `x`.
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+/// Whether `sensitivity` leaves confidences unchanged.
+pub fn is_default_sensitivity(sensitivity: f32) -> bool {
+    (sensitivity - DEFAULT).abs() < f32::EPSILON
+}
+
+/// Reshape a model confidence for `sensitivity`.
+pub fn apply_sensitivity(confidence: f32, sensitivity: f32) -> f32 {
+    if is_default_sensitivity(sensitivity) {
+        return confidence;
+    }
+    sigmoid(slope(sensitivity) * logit(confidence))
+}
+
+/// Model confidence that becomes `min_confidence` after reshaping.
+///
+/// The model must keep predictions down to this threshold, since a higher
+/// sensitivity lifts some of them above `min_confidence`.
+pub fn sensitivity_threshold(min_confidence: f32, sensitivity: f32) -> f32 {
+    if is_default_sensitivity(sensitivity) || min_confidence <= 0.0 || min_confidence >= 1.0 {
+        return min_confidence;
+    }
+    sigmoid(logit(min_confidence) / slope(sensitivity))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_sensitivity_is_identity() {
+        assert!((apply_sensitivity(0.3, 1.0) - 0.3).abs() < f32::EPSILON);
+        assert!((sensitivity_threshold(0.3, 1.0) - 0.3).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_higher_sensitivity_raises_low_confidences() {
+        assert!(apply_sensitivity(0.2, 1.25) > 0.2);
+        assert!(apply_sensitivity(0.9, 1.25) < 0.9);
+        assert!(apply_sensitivity(0.2, 0.75) < 0.2);
+        assert!((apply_sensitivity(0.5, 1.5) - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_matches_birdnet_analyzer_sigmoid() {
+        // BirdNET-Analyzer: 1 / (1 + exp(-(2 - 1.25) * logit))
+        let logit = 2.0_f32;
+        let confidence = sigmoid(logit);
+        let expected = 1.0 / (1.0 + (-0.75 * logit).exp());
+        assert!((apply_sensitivity(confidence, 1.25) - expected).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_threshold_inverts_reshaping() {
+        for sensitivity in [0.5, 0.75, 1.25, 1.5] {
+            let raw = sensitivity_threshold(0.25, sensitivity);
+            assert!((apply_sensitivity(raw, sensitivity) - 0.25).abs() < 1e-5);
+        }
+        assert!((sensitivity_threshold(0.0, 1.25)).abs() < f32::EPSILON);
+    }
+}
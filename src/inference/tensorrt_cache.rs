@@ -0,0 +1,218 @@
+//! `TensorRT` engine cache bookkeeping.
+//!
+//! ONNX Runtime names cached engines by graph hash, so the files themselves do
+//! not reveal which batch sizes they were built for. Birda records that mapping
+//! in a small manifest next to the engines whenever a warmup builds or rebuilds
+//! an engine file.
+
+use crate::constants::tensorrt::{ENGINE_EXTENSION, ENGINE_MANIFEST_FILE};
+use crate::error::Result;
+use crate::output::EngineCacheEntry;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+use std::time::SystemTime;
+use tracing::{debug, warn};
+
+/// Snapshot of engine files (file name → modification time).
+pub type EngineSnapshot = HashMap<String, SystemTime>;
+
+/// Manifest record for a single engine file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ManifestRecord {
+    model: String,
+    batch_sizes: BTreeSet<usize>,
+}
+
+/// On-disk manifest mapping engine files to batch sizes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct EngineManifest {
+    engines: BTreeMap<String, ManifestRecord>,
+}
+
+/// Check whether a path looks like a `TensorRT` engine file.
+fn is_engine_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case(ENGINE_EXTENSION))
+}
+
+/// Take a snapshot of the engine files currently in the cache directory.
+///
+/// Returns an empty snapshot if the directory does not exist or cannot be read.
+pub fn snapshot_engines(cache_dir: &Path) -> EngineSnapshot {
+    let Ok(entries) = std::fs::read_dir(cache_dir) else {
+        return EngineSnapshot::new();
+    };
+
+    entries
+        .filter_map(std::result::Result::ok)
+        .filter(|entry| is_engine_file(&entry.path()))
+        .filter_map(|entry| {
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((entry.file_name().to_string_lossy().to_string(), modified))
+        })
+        .collect()
+}
+
+/// Load the engine manifest, treating a missing or corrupt manifest as empty.
+fn load_manifest(cache_dir: &Path) -> EngineManifest {
+    let path = cache_dir.join(ENGINE_MANIFEST_FILE);
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return EngineManifest::default();
+    };
+
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        warn!(
+            "Ignoring unreadable TensorRT engine manifest {}: {}",
+            path.display(),
+            e
+        );
+        EngineManifest::default()
+    })
+}
+
+/// Persist the engine manifest.
+fn save_manifest(cache_dir: &Path, manifest: &EngineManifest) -> Result<()> {
+    let path = cache_dir.join(ENGINE_MANIFEST_FILE);
+    let content = serde_json::to_string_pretty(manifest).map_err(std::io::Error::other)?;
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+/// Record a completed warmup in the manifest.
+///
+/// Engine files that are new or were rewritten since `before` are attributed to
+/// `model` and `batch_size`. Manifest entries for engine files that no longer
+/// exist are pruned.
+pub fn record_warmup(
+    cache_dir: &Path,
+    model: &str,
+    batch_size: usize,
+    before: &EngineSnapshot,
+) -> Result<()> {
+    let after = snapshot_engines(cache_dir);
+    let mut manifest = load_manifest(cache_dir);
+
+    manifest.engines.retain(|file, _| after.contains_key(file));
+
+    let mut changed = false;
+    for (file, modified) in &after {
+        let is_new_or_rebuilt = before.get(file).is_none_or(|prev| prev != modified);
+        if is_new_or_rebuilt {
+            debug!(
+                "TensorRT engine {} built for batch size {}",
+                file, batch_size
+            );
+            let record = manifest.engines.entry(file.clone()).or_default();
+            if record.model != model {
+                record.model = model.to_string();
+                record.batch_sizes.clear();
+            }
+            record.batch_sizes.insert(batch_size);
+            changed = true;
+        }
+    }
+
+    if changed || after.len() != before.len() {
+        save_manifest(cache_dir, &manifest)?;
+    }
+
+    Ok(())
+}
+
+/// Batch sizes with a cached engine for the given model, in ascending order.
+pub fn cached_batch_sizes(cache_dir: &Path, model: &str) -> Vec<usize> {
+    let present = snapshot_engines(cache_dir);
+    let manifest = load_manifest(cache_dir);
+
+    manifest
+        .engines
+        .iter()
+        .filter(|(file, record)| record.model == model && present.contains_key(*file))
+        .flat_map(|(_, record)| record.batch_sizes.iter().copied())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// List all engine files in the cache directory with their recorded batch sizes.
+///
+/// Engines built outside birda (or before the manifest existed) are listed with
+/// no model and an empty batch size list.
+pub fn list_engines(cache_dir: &Path) -> Vec<EngineCacheEntry> {
+    let manifest = load_manifest(cache_dir);
+    let mut files: Vec<String> = snapshot_engines(cache_dir).into_keys().collect();
+    files.sort();
+
+    files
+        .into_iter()
+        .map(|file| {
+            let size_bytes = std::fs::metadata(cache_dir.join(&file)).map_or(0, |m| m.len());
+            let record = manifest.engines.get(&file);
+            EngineCacheEntry {
+                size_bytes,
+                model: record.map(|r| r.model.clone()),
+                batch_sizes: record
+                    .map(|r| r.batch_sizes.iter().copied().collect())
+                    .unwrap_or_default(),
+                file,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_is_engine_file() {
+        assert!(is_engine_file(Path::new("TRTKernel_graph_123_sm86.engine")));
+        assert!(!is_engine_file(Path::new(
+            "TRTKernel_graph_123_sm86.profile"
+        )));
+        assert!(!is_engine_file(Path::new(ENGINE_MANIFEST_FILE)));
+    }
+
+    #[test]
+    fn test_record_warmup_maps_new_engines() {
+        let dir = TempDir::new().unwrap();
+        let before = snapshot_engines(dir.path());
+        std::fs::write(dir.path().join("graph_a.engine"), b"engine").unwrap();
+
+        record_warmup(dir.path(), "birdnet-v24", 32, &before).unwrap();
+
+        assert_eq!(cached_batch_sizes(dir.path(), "birdnet-v24"), vec![32]);
+        assert!(cached_batch_sizes(dir.path(), "perch-v2").is_empty());
+
+        let engines = list_engines(dir.path());
+        assert_eq!(engines.len(), 1);
+        assert_eq!(engines[0].file, "graph_a.engine");
+        assert_eq!(engines[0].model.as_deref(), Some("birdnet-v24"));
+        assert_eq!(engines[0].batch_sizes, vec![32]);
+        assert_eq!(engines[0].size_bytes, 6);
+    }
+
+    #[test]
+    fn test_record_warmup_ignores_unchanged_engines() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("graph_a.engine"), b"engine").unwrap();
+        let before = snapshot_engines(dir.path());
+
+        record_warmup(dir.path(), "birdnet-v24", 8, &before).unwrap();
+
+        assert!(cached_batch_sizes(dir.path(), "birdnet-v24").is_empty());
+        let engines = list_engines(dir.path());
+        assert_eq!(engines.len(), 1);
+        assert!(engines[0].model.is_none());
+    }
+
+    #[test]
+    fn test_list_engines_missing_dir() {
+        let dir = TempDir::new().unwrap();
+        assert!(list_engines(&dir.path().join("missing")).is_empty());
+    }
+}
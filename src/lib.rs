@@ -16,6 +16,9 @@ pub mod locking;
 pub mod output;
 pub mod pipeline;
 pub mod registry;
+pub mod report;
+pub mod runs;
+pub mod server;
 pub mod update;
 pub mod utils;
 
@@ -23,22 +26,24 @@ use clap::Parser;
 use cli::{AnalyzeArgs, Cli, Command};
 use config::{
     BatConfig, Config, InferenceDevice, ModelConfig, ModelType, OutputFormat, OutputMode,
-    config_file_path, load_default_config, range_filter::build_range_filter_config,
+    config_file_path, load_default_config,
+    range_filter::{build_range_filter_config, build_recording_range_filter_config},
     save_default_config,
 };
 use constants::DEFAULT_TOP_K;
 use inference::BirdClassifier;
 use output::{
-    ConfigPathPayload, ConfigPayload, FileStatus, ModelCheckEntry, ModelCheckPayload, ModelDetails,
-    ModelEntry, ModelInfoPayload, ModelInstalledPayload, ModelListPayload, ModelRemovedPayload,
-    PipelineSummary, ProgressReporter, ProviderInfo, ProvidersPayload, ResultType, create_reporter,
+    CacheListPayload, ConfigPathPayload, ConfigPayload, ConfigValuePayload, ErrorSeverity,
+    FileStatus, ModelCheckEntry, ModelCheckPayload, ModelDetails, ModelEntry, ModelInfoPayload,
+    ModelInstalledPayload, ModelListPayload, ModelRemovedPayload, PipelineSummary,
+    ProgressReporter, ProviderInfo, ProvidersPayload, ResultType, create_reporter,
     emit_json_result,
 };
 use pipeline::{
-    ProcessCheck, ProcessingConfig, collect_input_files, output_dir_for, process_file,
-    should_process,
+    ProcessCheck, ProcessingConfig, collect_input_files, combined_output_dir, output_dir_for,
+    process_file, should_process,
 };
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tracing::{error, info, warn};
@@ -93,6 +98,7 @@ fn resolve_model_config(args: &AnalyzeArgs, config: &Config) -> Result<(ModelCon
             bsg_calibration: None,
             bsg_migration: None,
             bsg_distribution_maps: None,
+            custom_head: None,
         };
 
         return Ok((model_config, ADHOC_MODEL_NAME.to_string()));
@@ -158,32 +164,232 @@ fn determine_default_batch_size(
     model_type: ModelType,
     ep_status: &inference::ExecutionProviderStatus,
 ) -> usize {
-    use ModelType::{BirdnetV24, BirdnetV30, BsgFinland, PerchV2};
     use constants::batch_size;
 
-    // Match on model type and actual provider (not requested)
-    match (model_type, ep_status.actual.as_str()) {
-        // CPU - all models (explicit CPU, auto fallback, or GPU fallback)
-        (_, "CPU") => batch_size::CPU,
+    // Match on model type and actual provider (not requested); CPU covers
+    // explicit CPU, auto fallback and GPU fallback alike
+    let entry = batch_size::DEFAULTS.iter().find(|entry| {
+        entry.provider == ep_status.actual
+            && (entry.models.is_empty() || entry.models.contains(&model_type))
+    });
+    let (size, reason) = entry.map_or(
+        (
+            batch_size::OTHER_GPU,
+            "conservative default for providers without a listed default",
+        ),
+        |entry| (entry.batch_size, entry.reason),
+    );
+    info!(
+        "Using default batch size {size} for {model_type} with {} provider ({reason}); set --batch-size to override",
+        ep_status.actual
+    );
+    size
+}
+
+/// Pick a batch size for `--batch-size auto` by benchmarking candidates.
+///
+/// Candidates come from [`constants::batch_size::AUTO_CANDIDATES`], limited to
+/// those whose estimated footprint fits in free GPU memory. Each is warmed up
+/// once and then timed; the first out-of-memory error ends the benchmark and
+/// the fastest size so far wins. `TensorRT` only benchmarks sizes with cached
+/// engines, since building an engine per candidate takes minutes. On the CPU
+/// the smart default is used.
+fn tune_batch_size(
+    classifier: &BirdClassifier,
+    model_type: ModelType,
+    model_name: &str,
+) -> Result<usize> {
+    use constants::batch_size::{
+        AUTO_BENCH_ITERATIONS, AUTO_CANDIDATES, AUTO_VRAM_PER_SEGMENT_BYTES,
+    };
+    use std::time::Instant;
+
+    let ep_status = classifier.execution_provider_status();
+    if ep_status.actual == "CPU" {
+        info!("Batch size auto-tuning applies to GPU providers only");
+        return Ok(determine_default_batch_size(model_type, ep_status));
+    }
+
+    let free_bytes = matches!(ep_status.actual.as_str(), "CUDA" | "TensorRT")
+        .then(gpu::free_memory_bytes)
+        .flatten();
+    match free_bytes {
+        Some(free) => info!("Auto batch size: {} MiB of GPU memory free", free >> 20),
+        None => info!("Auto batch size: free GPU memory unknown, probing until out of memory"),
+    }
+    let mut candidates =
+        gpu::batch_size_candidates(AUTO_CANDIDATES, free_bytes, AUTO_VRAM_PER_SEGMENT_BYTES);
+
+    if classifier.uses_tensorrt() {
+        let cached = config::tensorrt_cache_dir()
+            .map(|dir| inference::tensorrt_cache::cached_batch_sizes(&dir, model_name))
+            .unwrap_or_default();
+        candidates.retain(|size| cached.contains(size));
+        if candidates.is_empty() {
+            info!(
+                "Auto batch size: no cached TensorRT engines to benchmark; \
+                 prewarm sizes with [inference] tensorrt_warmup_batch_sizes"
+            );
+            return Ok(determine_default_batch_size(model_type, ep_status));
+        }
+    }
+
+    let benchmark = |size: usize| -> Result<f64> {
+        // The untimed run absorbs allocation and kernel selection
+        classifier.warmup(size)?;
+        let start = Instant::now();
+        for _ in 0..AUTO_BENCH_ITERATIONS {
+            classifier.warmup(size)?;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let segments = (size * AUTO_BENCH_ITERATIONS) as f64;
+        Ok(segments / start.elapsed().as_secs_f64().max(f64::EPSILON))
+    };
+
+    let mut best: Option<(usize, f64)> = None;
+    for &size in &candidates {
+        match benchmark(size) {
+            Ok(rate) => {
+                info!("Auto batch size: {size} runs {rate:.0} segments/s");
+                if best.is_none_or(|(_, best_rate)| rate > best_rate) {
+                    best = Some((size, rate));
+                }
+            }
+            Err(e) if gpu::is_out_of_memory(&e) => {
+                info!("Auto batch size: {size} does not fit in GPU memory");
+                break;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    // Nothing fit: start from the smallest candidate and let warmup back off
+    let size = best.map_or(candidates[0], |(size, _)| size);
+    info!(
+        "Using batch size {size} (auto-tuned for {model_type} with {} provider)",
+        ep_status.actual
+    );
+    Ok(size)
+}
 
-        // CUDA with BirdNET 2.4 or BSG Finland
-        (BirdnetV24 | BsgFinland, "CUDA") => batch_size::CUDA_BIRDNET_V24,
+/// Resolve GPU memory settings from CLI args (preferred) or config.
+///
+/// Returns `None` when neither is configured, so the CUDA provider keeps its
+/// defaults.
+fn gpu_memory_options(
+    args: &AnalyzeArgs,
+    config: &Config,
+) -> Result<Option<inference::GpuMemoryOptions>> {
+    let limit_bytes = match args.gpu_mem_limit {
+        Some(limit) => Some(limit),
+        None => config
+            .inference
+            .gpu_mem_limit
+            .as_deref()
+            .map(cli::validators::parse_byte_size)
+            .transpose()
+            .map_err(|e| Error::ConfigValidation {
+                message: format!("gpu_mem_limit: {e}"),
+            })?,
+    };
+    let strategy = args
+        .arena_extend_strategy
+        .or(config.inference.arena_extend_strategy);
+    if limit_bytes.is_none() && strategy.is_none() {
+        return Ok(None);
+    }
+    Ok(Some(inference::GpuMemoryOptions {
+        limit_bytes,
+        arena_extend_strategy: strategy.unwrap_or_default(),
+    }))
+}
 
-        // CUDA with BirdNET 3.0 or Perch v2
-        (BirdnetV30 | PerchV2, "CUDA") => batch_size::CUDA_BIRDNET_V30,
+/// Check the GPU's free memory and health before the model is loaded.
+///
+/// Only runs when `--min-free-gpu-mem` (or `min_free_gpu_mem`) is set and the
+/// device may use an NVIDIA GPU. A GPU that fails the check either stops the
+/// run or, with `--low-gpu-mem cpu` or the `auto` device, is swapped for the
+/// CPU with a warning.
+fn gate_gpu_memory(
+    args: &AnalyzeArgs,
+    config: &Config,
+    device: InferenceDevice,
+    model_config: &ModelConfig,
+    requested_batch_size: Option<config::BatchSize>,
+    reporter: &Arc<dyn ProgressReporter>,
+) -> Result<InferenceDevice> {
+    if !matches!(
+        device,
+        InferenceDevice::Auto
+            | InferenceDevice::Gpu
+            | InferenceDevice::Cuda
+            | InferenceDevice::TensorRt
+    ) {
+        return Ok(device);
+    }
+    let requirement = match args.min_free_gpu_mem {
+        Some(requirement) => requirement,
+        None => match config.inference.min_free_gpu_mem.as_deref() {
+            Some(value) => {
+                gpu::MemoryRequirement::parse(value).map_err(|e| Error::ConfigValidation {
+                    message: format!("min_free_gpu_mem: {e}"),
+                })?
+            }
+            None => return Ok(device),
+        },
+    };
 
-        // TensorRT with any model
-        (_, "TensorRT") => batch_size::TENSORRT,
+    let model_bytes = std::fs::metadata(&model_config.path).map_or(0, |m| m.len());
+    let batch_size = match requested_batch_size {
+        Some(config::BatchSize::Fixed(size)) => size,
+        _ => constants::batch_size::OTHER_GPU,
+    };
+    let required = requirement.required_bytes(model_bytes, batch_size);
+    let check = gpu::check_gpu(required);
+    let Some(problem) = check.problem() else {
+        if let gpu::GpuCheck::Ready { free } = check {
+            info!(
+                "GPU check passed: {} MiB free, {} MiB required",
+                free >> 20,
+                required >> 20
+            );
+        } else {
+            let message = "Could not check free GPU memory: NVML is not available";
+            warn!("{message}");
+            reporter.error(
+                "gpu_check_unavailable",
+                ErrorSeverity::Warning,
+                message,
+                Some("Install the NVIDIA driver to check the GPU before runs"),
+            );
+        }
+        return Ok(device);
+    };
 
-        // Other GPU providers (DirectML, ROCm, OpenVINO, CoreML, etc.)
-        _ => {
-            tracing::debug!(
-                "Using conservative batch size {} for model {:?} with provider {}",
-                batch_size::OTHER_GPU,
-                model_type,
-                ep_status.actual
+    // The auto device already allows the CPU, so it never refuses to start
+    let action = if device == InferenceDevice::Auto {
+        config::LowGpuMemAction::Cpu
+    } else {
+        args.low_gpu_mem
+            .or(config.inference.low_gpu_mem)
+            .unwrap_or_default()
+    };
+    match action {
+        config::LowGpuMemAction::Fail => Err(Error::GpuNotReady {
+            reason: format!(
+                "{problem}; free GPU memory, lower --min-free-gpu-mem or use --low-gpu-mem cpu"
+            ),
+        }),
+        config::LowGpuMemAction::Cpu => {
+            let message = format!("{problem}; running on the CPU instead");
+            warn!("{message}");
+            reporter.error(
+                "low_gpu_memory",
+                ErrorSeverity::Warning,
+                &message,
+                Some("Free GPU memory or lower --min-free-gpu-mem to run on the GPU"),
             );
-            batch_size::OTHER_GPU
+            Ok(InferenceDevice::Cpu)
         }
     }
 }
@@ -193,25 +399,94 @@ fn determine_default_batch_size(
 struct ProcessingParams<'a> {
     formats: &'a [OutputFormat],
     output_dir: Option<&'a Path>,
+    /// Central lock directory instead of lock files beside the outputs.
+    lock_dir: Option<&'a Path>,
     min_confidence: f32,
     overlap: f32,
+    /// Detection sensitivity applied by the classifier.
+    sensitivity: f32,
     batch_size: usize,
     csv_columns: &'a [String],
     csv_bom: bool,
     model_name: &'a str,
     range_filter_params: Option<(f64, f64, u8)>,
-    force: bool,
+    /// Range filter week, also when each file supplies the location.
+    range_filter_week: Option<u8>,
+    /// Whether each file's recorded location stands in for the missing
+    /// location of the run.
+    locate_files: bool,
+    /// When to skip files that already have results.
+    skip_policy: config::SkipPolicy,
+    /// Manifest recording the model and settings, written beside results.
+    run_manifest: output::RunManifest,
     fail_fast: bool,
     progress_enabled: bool,
     stdout_mode: bool,
+    /// Write results to stdout in this format instead of to files.
+    stdout_format: Option<OutputFormat>,
     /// Dual output mode: progress events to stdout, detections to files.
     /// When true, reporter receives progress events but not detection events.
     dual_output_mode: bool,
     /// BSG SDM parameters: (latitude, longitude, `day_of_year`)
     /// `day_of_year` is None for auto-detection from file timestamp
     bsg_params: Option<(f64, f64, Option<u32>)>,
+    /// BSG SDM day of year for files located from their metadata.
+    bsg_day_of_year: Option<u32>,
     /// Optional custom classifier for two-stage inference (bat detection).
     custom_classifier: Option<&'a birdnet_onnx::CustomClassifier>,
+    /// Optional per-minute activity summary format.
+    activity_format: Option<config::ActivityFormat>,
+    /// RMS level in dBFS above which a segment counts as active.
+    activity_threshold_dbfs: f32,
+    /// Inference watchdog policy for the active execution provider.
+    watchdog: gpu::WatchdogPolicy,
+    /// Optional post-processing hook applied to each file's detections.
+    postprocess_hook: Option<pipeline::PostprocessHook>,
+    /// Whether overlapping detections of a species are merged into events.
+    merge_overlapping: bool,
+    /// Overlap of the second pass around detections, if any.
+    zoom_overlap: Option<f32>,
+    /// Whether to compute an audio fingerprint for each detection.
+    fingerprint: bool,
+    /// Provenance stamped on each detection, if provenance columns are written.
+    provenance: Option<output::Provenance>,
+    /// Whether Song Meter recorder columns are written.
+    recorder_columns: bool,
+    /// Whether each file's recorder metadata is read, because columns,
+    /// locations, start times or `file_started` events use it.
+    recorder_metadata: bool,
+    /// Finds recording start times, if absolute time columns are written.
+    timestamps: Option<utils::timestamp::TimestampParser>,
+    /// Sensitive species flagged in results and masked in combined outputs.
+    sensitive: Option<output::SensitiveSpecies>,
+    /// GPS track that locates each detection.
+    track: Option<utils::track::GpsTrack>,
+    /// Combined output that receives every analyzed file's detections.
+    combined: Option<output::CombinedSender>,
+    /// Row and size limits after which CSV/Parquet outputs roll over into parts.
+    split_limits: output::SplitLimits,
+    /// Decimal places of confidences and times in the results.
+    number_format: output::NumberFormat,
+    /// Station stamped on each detection and recorded in the results.
+    station: Option<output::Station>,
+    /// Time range analyzed in files without a range of their own.
+    time_range: Option<audio::TimeRange>,
+    /// Time ranges given per input as `path@START-END`.
+    file_ranges: HashMap<PathBuf, audio::TimeRange>,
+    /// Channels analyzed in each file.
+    channel: audio::ChannelSelection,
+    /// Pre-filter applied to the audio of each file.
+    filter: Option<audio::FilterSpec>,
+    /// Size and duration limits that leave files out.
+    file_filter: pipeline::FileFilter,
+    /// Where recordings that fail to decode are set aside.
+    dead_letter: Option<pipeline::DeadLetter>,
+    /// How split recordings join their neighbours with `--stitch`.
+    stitches: HashMap<PathBuf, pipeline::Stitch>,
+    /// Run report collecting every analyzed file.
+    report: Option<&'a output::RunReport>,
+    /// Fault injected to exercise error paths.
+    fault: Option<pipeline::FaultInjector>,
 }
 
 /// Statistics from processing all files.
@@ -219,23 +494,78 @@ struct ProcessingParams<'a> {
 struct ProcessingStats {
     processed: usize,
     skipped: usize,
+    /// Skipped files left out by `--min-duration`, `--max-duration` or `--min-size`.
+    filtered: usize,
     errors: usize,
     total_detections: usize,
     total_segments: usize,
     total_audio_duration: f64,
+    /// Batch size in effect at the end of the run (after any GPU memory backoff).
+    effective_batch_size: Option<usize>,
+    /// Confidence distributions and suggested thresholds (`--suggest-thresholds`).
+    confidence: Option<output::ConfidenceSummary>,
+    /// Resolved settings that affect detections, for the runs log.
+    settings: Option<output::RunSettings>,
+    /// Random sample of the inputs that was analyzed, for the runs log.
+    sample: Option<output::InputSample>,
+    /// Highest GPU memory use of the process seen while sampling.
+    peak_gpu_memory: Option<u64>,
+    /// GPU memory in use before the model was loaded, for crediting memory
+    /// to this process when it cannot be found by PID (in containers).
+    gpu_memory_baseline: Option<u64>,
+    /// When GPU memory was last sampled.
+    gpu_memory_sampled: Option<std::time::Instant>,
+}
+
+impl ProcessingStats {
+    /// Sample this process's GPU memory, at most once per
+    /// [`constants::gpu::MEMORY_SAMPLE_INTERVAL_SECS`].
+    fn sample_gpu_memory(&mut self, classifier: &BirdClassifier) {
+        if !matches!(
+            classifier.execution_provider_status().actual.as_str(),
+            "CUDA" | "TensorRT"
+        ) || self
+            .gpu_memory_sampled
+            .is_some_and(|at| at.elapsed().as_secs() < constants::gpu::MEMORY_SAMPLE_INTERVAL_SECS)
+        {
+            return;
+        }
+        self.gpu_memory_sampled = Some(std::time::Instant::now());
+        if let Some(bytes) = gpu::process_memory_bytes(self.gpu_memory_baseline) {
+            self.peak_gpu_memory = self.peak_gpu_memory.max(Some(bytes));
+        }
+    }
+
+    /// Copy the settings and counts kept in the runs log to `record`.
+    fn fill_run_record(&self, record: &mut runs::RunRecord) {
+        record.settings.clone_from(&self.settings);
+        record.sample = self.sample;
+        record.files_processed = self.processed;
+        record.files_skipped = self.skipped;
+        record.files_failed = self.errors;
+        record.detections = self.total_detections;
+    }
 }
 
 /// Main entry point for birda CLI.
 pub fn run() -> Result<()> {
     let cli = Cli::parse();
 
-    validate_analyze_args_preflight(&cli.inputs, &cli.analyze)?;
+    // `analyze` and `watch` carry their own inputs and analysis options
+    let (inputs, analyze_args) = match &cli.command {
+        Some(Command::Analyze(analyze)) => (analyze.inputs.as_slice(), &analyze.analyze),
+        Some(Command::Watch(watch)) => (cli.inputs.as_slice(), &watch.analyze),
+        _ => (cli.inputs.as_slice(), &cli.analyze),
+    };
+    validate_analyze_args_preflight(inputs, analyze_args)?;
 
     // Initialize logging
-    init_logging(cli.analyze.verbose, cli.analyze.quiet);
+    init_logging(analyze_args.verbose, analyze_args.quiet, cli.log_events);
 
-    // Install Ctrl+C handler to clean up lock files on interrupt
+    // Install Ctrl+C handler to log the interrupted run and clean up lock
+    // files on interrupt
     if let Err(e) = ctrlc::set_handler(|| {
+        runs::record_interrupted();
         locking::cleanup_all_locks();
         std::process::exit(130); // 128 + SIGINT(2)
     }) {
@@ -243,39 +573,128 @@ pub fn run() -> Result<()> {
     }
 
     // Load configuration
-    let config = load_default_config()?;
+    let config = match load_default_config(cli.profile.as_deref(), !cli.lenient_config) {
+        // `config validate` reports the problems itself
+        Err(_)
+            if matches!(
+                cli.command,
+                Some(Command::Config {
+                    action: cli::ConfigAction::Validate
+                })
+            ) =>
+        {
+            Config::default()
+        }
+        result => result?,
+    };
 
     // Determine output mode (CLI flag takes precedence over config)
     // Auto-enable NDJSON mode for stdout
-    let output_mode = if cli.analyze.stdout {
+    let output_mode = if analyze_args.stdout {
         OutputMode::Ndjson
     } else {
         cli.output_mode.unwrap_or(config.output.default_format)
     };
 
     // Create reporter based on output mode
-    let reporter: Arc<dyn ProgressReporter> = Arc::from(create_reporter(output_mode));
+    let reporter: Arc<dyn ProgressReporter> = Arc::from(create_reporter(
+        output_mode,
+        output::Station::from_config(&config.station),
+    ));
+    if output_mode.is_structured() && cli.log_events != output::LogEventLevel::Off {
+        output::set_log_reporter(Arc::clone(&reporter));
+    }
+
+    // Report panics as structured events instead of dying silently
+    install_panic_hook(Arc::clone(&reporter));
 
     // Initialize ONNX Runtime only for commands that will touch it. This keeps
     // non-inference commands like `clip` working without a runtime install.
-    if command_requires_runtime(cli.command.as_ref(), cli.inputs.is_empty()) {
+    let has_no_inputs = cli.inputs.is_empty() && cli.analyze.file_list.is_none();
+    let gpu_memory = if command_requires_runtime(cli.command.as_ref(), has_no_inputs) {
         inference::ensure_runtime_available()?;
-    }
+        gpu_memory_options(analyze_args, &config)?
+    } else {
+        None
+    };
 
     // Handle subcommands
     if let Some(command) = cli.command {
-        return handle_command(command, &config, output_mode, &reporter);
+        let result = handle_command(command, &config, output_mode, &reporter, gpu_memory);
+        // Log events captured in json mode are buffered until here
+        reporter.flush();
+        return result;
     }
 
     // Default: analyze files
     // Show help if no inputs provided
-    if cli.inputs.is_empty() {
+    if has_no_inputs {
         cli::help::print_smart_help(&config);
         std::process::exit(0);
     }
 
     // Run analysis
-    analyze_files(&cli.inputs, &cli.analyze, &config, output_mode, &reporter)
+    analyze_files(
+        &cli.inputs,
+        &cli.analyze,
+        &config,
+        output_mode,
+        &reporter,
+        None,
+        gpu_memory,
+    )
+}
+
+/// Install a process-wide panic hook that reports panics through the reporter.
+///
+/// A panic on the main thread is fatal: it is reported as a fatal error event,
+/// buffered JSON output is flushed and all lock files are removed before the
+/// default hook prints the panic message. Panics on worker threads (e.g. the
+/// decode thread) are reported as warnings, since the pipeline recovers from them.
+fn install_panic_hook(reporter: Arc<dyn ProgressReporter>) {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let thread = std::thread::current();
+        let thread_name = thread.name().unwrap_or("unnamed");
+        let is_main = thread_name == "main";
+
+        let location = info
+            .location()
+            .map(|l| format!(" at {}:{}", l.file(), l.line()))
+            .unwrap_or_default();
+        let message = format!(
+            "panic in thread '{thread_name}'{location}: {}",
+            panic_payload_message(info.payload())
+        );
+
+        let severity = if is_main {
+            ErrorSeverity::Fatal
+        } else {
+            ErrorSeverity::Warning
+        };
+        reporter.error(
+            "panic",
+            severity,
+            &message,
+            Some("This is a bug in birda, please report it with the command that triggered it"),
+        );
+
+        if is_main {
+            reporter.flush();
+            locking::cleanup_all_locks();
+        }
+
+        default_hook(info);
+    }));
+}
+
+/// Extract a human-readable message from a panic payload.
+fn panic_payload_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic payload")
 }
 
 fn command_requires_runtime(command: Option<&Command>, has_no_inputs: bool) -> bool {
@@ -283,10 +702,30 @@ fn command_requires_runtime(command: Option<&Command>, has_no_inputs: bool) -> b
         Some(
             Command::Config { .. }
             | Command::Models { .. }
+            | Command::Selftest { .. }
             | Command::Clip(_)
-            | Command::Update { .. },
+            | Command::Report { .. }
+            | Command::Audit(_)
+            | Command::Query(_)
+            | Command::Export(_)
+            | Command::Update { .. }
+            | Command::Formats { .. }
+            | Command::Cache { .. }
+            | Command::Examples { .. }
+            | Command::Runs { .. },
         ) => false,
-        Some(Command::Providers | Command::Species { .. }) => true,
+        // Listing sound devices needs no model
+        Some(Command::Listen(args)) => !args.list_devices,
+        Some(
+            Command::Providers
+            | Command::Species { .. }
+            | Command::Analyze(_)
+            | Command::Watch(_)
+            | Command::Embed(_)
+            | Command::Calibrate(_)
+            | Command::Bench(_)
+            | Command::Serve(_),
+        ) => true,
         None => !has_no_inputs,
     }
 }
@@ -301,6 +740,109 @@ fn validate_analyze_args_preflight(inputs: &[PathBuf], args: &AnalyzeArgs) -> Re
         }
     }
 
+    if let Some(format) = args.stdout_format {
+        if inputs.len() != 1 {
+            return Err(Error::ConfigValidation {
+                message: "--stdout-format requires exactly one input file".to_string(),
+            });
+        }
+        if matches!(format, OutputFormat::Parquet | OutputFormat::Sqlite) {
+            return Err(Error::ConfigValidation {
+                message: format!("--stdout-format does not support {format} (binary format)"),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Apply `--sample`/`--sample-n` to the collected input files.
+///
+/// Returns the files to analyze and, when sampling, the sample to record.
+fn sample_inputs(
+    files: Vec<PathBuf>,
+    args: &AnalyzeArgs,
+) -> Result<(Vec<PathBuf>, Option<output::InputSample>)> {
+    let total = files.len();
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    let count = match (args.sample, args.sample_n) {
+        (Some(percent), _) => ((total as f64 * percent / 100.0).ceil() as usize).max(1),
+        (None, Some(n)) => usize::try_from(n).unwrap_or(usize::MAX),
+        (None, None) => return Ok((files, None)),
+    };
+    // Only a watch starts without files; it finds them as they appear
+    if total == 0 {
+        return Err(Error::ConfigValidation {
+            message: "--sample and --sample-n cannot be used with watch".to_string(),
+        });
+    }
+
+    let seed = args.sample_seed.unwrap_or_else(|| {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos());
+        #[allow(clippy::cast_possible_truncation)]
+        let seed = (nanos as u64) ^ u64::from(std::process::id()).rotate_left(32);
+        seed
+    });
+    let files = pipeline::random_sample(&files, count, seed);
+    info!(
+        "Analyzing a sample of {} of {total} file(s) (seed {seed}; repeat with --sample-seed {seed})",
+        files.len()
+    );
+    let sample = output::InputSample {
+        seed,
+        selected: files.len(),
+        total,
+    };
+    Ok((files, Some(sample)))
+}
+
+/// Ensure a read-only-inputs run writes nothing into its input directories.
+///
+/// Paths are compared lexically after making them absolute; symlinked
+/// output directories are not resolved.
+fn validate_read_only_inputs(
+    inputs: &[PathBuf],
+    output_dir: Option<&Path>,
+    writes_files: bool,
+) -> Result<()> {
+    if !writes_files {
+        return Ok(());
+    }
+
+    let Some(output_dir) = output_dir else {
+        return Err(Error::ConfigValidation {
+            message: "--read-only-inputs requires --output-dir".to_string(),
+        });
+    };
+
+    let output_dir = std::path::absolute(output_dir)?;
+    for input in inputs {
+        let input_dir = if input.is_dir() {
+            input.as_path()
+        } else {
+            input
+                .parent()
+                .filter(|p| !p.as_os_str().is_empty())
+                .unwrap_or_else(|| Path::new("."))
+        };
+        let input_dir = std::path::absolute(input_dir)?;
+        if output_dir.starts_with(&input_dir) {
+            return Err(Error::ConfigValidation {
+                message: format!(
+                    "--read-only-inputs: output directory '{}' is inside input directory '{}'",
+                    output_dir.display(),
+                    input_dir.display()
+                ),
+            });
+        }
+    }
+
     Ok(())
 }
 
@@ -360,7 +902,49 @@ fn resolve_species_filter(
     Ok(None)
 }
 
-/// Validate that model, labels, and optional meta-model files exist.
+/// Resolve the model of a subcommand from `-m`, else `defaults.model`, and
+/// check that its files exist.
+fn resolve_command_model<'a>(
+    model: Option<&'a String>,
+    config: &'a Config,
+) -> Result<(&'a String, &'a ModelConfig)> {
+    let name = model
+        .or(config.defaults.model.as_ref())
+        .ok_or_else(|| Error::ConfigValidation {
+            message: "no model specified (use -m or set defaults.model in config)".into(),
+        })?;
+    let model = config::get_model(config, name)?;
+    validate_model_files(model)?;
+    Ok((name, model))
+}
+
+/// Overlap from `--overlap`, else `defaults.overlap`, checked against the
+/// segment duration of `classifier`.
+fn resolve_overlap(
+    overlap: Option<f32>,
+    config: &Config,
+    classifier: &BirdClassifier,
+) -> Result<f32> {
+    let overlap = overlap.unwrap_or(config.defaults.overlap);
+    validate_overlap(overlap, classifier.segment_duration())?;
+    Ok(overlap)
+}
+
+/// Check that `overlap` leaves each segment advancing.
+fn validate_overlap(overlap: f32, segment_duration: f32) -> Result<()> {
+    if (0.0..segment_duration).contains(&overlap) {
+        Ok(())
+    } else {
+        Err(Error::ConfigValidation {
+            message: format!(
+                "overlap must be at least 0 and less than the {segment_duration}s segment duration, got {overlap}"
+            ),
+        })
+    }
+}
+
+/// Validate that model, labels, and optional meta-model and custom head
+/// files exist.
 fn validate_model_files(model_config: &ModelConfig) -> Result<()> {
     if !model_config.path.exists() {
         return Err(Error::ModelFileNotFound {
@@ -377,6 +961,9 @@ fn validate_model_files(model_config: &ModelConfig) -> Result<()> {
     {
         return Err(Error::MetaModelNotFound { path: meta.clone() });
     }
+    if let Some(ref head) = model_config.custom_head {
+        config::validate_custom_head(model_config.model_type, head)?;
+    }
     Ok(())
 }
 
@@ -385,13 +972,119 @@ fn validate_model_files(model_config: &ModelConfig) -> Result<()> {
 /// `TensorRT` compiles/loads its engine during the first inference, which can
 /// take several minutes on first run. This warmup triggers that initialization
 /// before the main processing loop starts.
-fn warmup_classifier(classifier: &BirdClassifier, batch_size: usize) -> Result<()> {
-    use indicatif::{ProgressBar, ProgressStyle};
-    use std::time::{Duration, Instant};
-
+///
+/// With `TensorRT`, any extra batch sizes from `[inference] tensorrt_warmup_batch_sizes`
+/// are prewarmed first, and the engine cache manifest is updated so that
+/// `birda cache list` can show which batch sizes each engine covers.
+fn warmup_classifier(
+    classifier: &BirdClassifier,
+    batch_size: usize,
+    model_name: &str,
+    prewarm_batch_sizes: &[usize],
+) -> Result<usize> {
     if !classifier.uses_tensorrt() {
-        return classifier.warmup(batch_size);
+        return warmup_with_backoff(batch_size, |size| classifier.warmup(size));
+    }
+
+    let cache_dir = config::tensorrt_cache_dir().ok();
+
+    if let Some(ref dir) = cache_dir {
+        let cached = inference::tensorrt_cache::cached_batch_sizes(dir, model_name);
+        if cached.is_empty() {
+            info!("TensorRT: No cached engines recorded for {}", model_name);
+        } else {
+            info!(
+                "TensorRT: Cached engines for {} cover batch sizes {}",
+                model_name,
+                cached
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+    }
+
+    let warmup_and_record = |size: usize| -> Result<()> {
+        let before = cache_dir
+            .as_deref()
+            .map(inference::tensorrt_cache::snapshot_engines)
+            .unwrap_or_default();
+
+        warmup_tensorrt_batch(classifier, size)?;
+
+        if let Some(ref dir) = cache_dir
+            && let Err(e) = inference::tensorrt_cache::record_warmup(dir, model_name, size, &before)
+        {
+            warn!("Failed to update TensorRT engine manifest: {}", e);
+        }
+        Ok(())
+    };
+
+    // Prewarm configured sizes first; the active batch size runs last so its
+    // engine is the one resident when processing starts.
+    let mut extra_sizes: Vec<usize> = prewarm_batch_sizes
+        .iter()
+        .copied()
+        .filter(|&size| size != batch_size)
+        .collect();
+    extra_sizes.sort_unstable();
+    extra_sizes.dedup();
+
+    for size in extra_sizes {
+        match warmup_and_record(size) {
+            Ok(()) => {}
+            Err(e) if gpu::is_out_of_memory(&e) => {
+                warn!(
+                    "TensorRT: Skipping prewarm of batch size {} (GPU out of memory)",
+                    size
+                );
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    warmup_with_backoff(batch_size, warmup_and_record)
+}
+
+/// Run a warmup, halving the batch size on GPU out-of-memory errors.
+///
+/// Returns the batch size that warmed up successfully.
+fn warmup_with_backoff(
+    batch_size: usize,
+    mut warmup: impl FnMut(usize) -> Result<()>,
+) -> Result<usize> {
+    let mut size = batch_size;
+    loop {
+        match warmup(size) {
+            Ok(()) => {
+                if size < batch_size {
+                    warn!(
+                        "Reduced batch size from {} to {} after GPU out-of-memory errors",
+                        batch_size, size
+                    );
+                }
+                return Ok(size);
+            }
+            Err(e) if gpu::is_out_of_memory(&e) => {
+                let Some(reduced) = gpu::reduced_batch_size(size) else {
+                    return Err(e);
+                };
+                warn!(
+                    "GPU out of memory during warmup with batch size {}, retrying with {}",
+                    size, reduced
+                );
+                size = reduced;
+            }
+            Err(e) => return Err(e),
+        }
     }
+}
+
+/// Run a single `TensorRT` warmup for one batch size with a progress spinner.
+fn warmup_tensorrt_batch(classifier: &BirdClassifier, batch_size: usize) -> Result<()> {
+    use indicatif::{ProgressBar, ProgressStyle};
+    use std::time::{Duration, Instant};
 
     let spinner = ProgressBar::new_spinner();
     spinner.set_style(
@@ -413,12 +1106,14 @@ fn warmup_classifier(classifier: &BirdClassifier, batch_size: usize) -> Result<(
 
     if warmup_duration.as_secs() >= WARMUP_BUILD_THRESHOLD_SECS {
         info!(
-            "TensorRT: Engine built in {:.1}s (cached for future runs)",
+            "TensorRT: Engine for batch size {} built in {:.1}s (cached for future runs)",
+            batch_size,
             warmup_duration.as_secs_f64()
         );
     } else {
         info!(
-            "TensorRT: Engine loaded from cache ({:.0}ms)",
+            "TensorRT: Engine for batch size {} loaded from cache ({:.0}ms)",
+            batch_size,
             warmup_duration.as_secs_f64() * 1000.0
         );
     }
@@ -469,10 +1164,23 @@ fn report_summary(
         );
     }
 
+    let peak_rss_bytes = utils::memory::peak_resident_memory_bytes();
+    if let Some(rss) = peak_rss_bytes {
+        let gpu = stats
+            .peak_gpu_memory
+            .map(|bytes| format!(", {} MiB GPU", bytes >> 20))
+            .unwrap_or_default();
+        info!("Memory: peak {} MiB RSS{gpu}", rss >> 20);
+    }
+
     if stats.errors > 0 && !fail_fast {
         warn!("{} file(s) had errors", stats.errors);
     }
 
+    if let Some(confidence) = &stats.confidence {
+        log_threshold_suggestions(confidence);
+    }
+
     reporter.pipeline_completed(&PipelineSummary {
         files_processed: stats.processed,
         files_failed: stats.errors,
@@ -481,9 +1189,32 @@ fn report_summary(
         total_segments: stats.total_segments,
         duration_ms,
         realtime_factor,
+        effective_batch_size: stats.effective_batch_size,
+        confidence: stats.confidence.clone(),
+        peak_rss_bytes,
+        peak_gpu_memory_bytes: stats.peak_gpu_memory,
     });
 }
 
+/// Log suggested confidence thresholds for the run.
+fn log_threshold_suggestions(confidence: &output::ConfidenceSummary) {
+    match confidence.suggested_min_confidence {
+        Some(threshold) => info!("Suggested --min-confidence for this run: {threshold:.2}"),
+        None => info!("Too few detections to suggest a minimum confidence"),
+    }
+    for species in &confidence.species {
+        if let Some(threshold) = species.suggested_threshold {
+            info!(
+                "  {} ({}): {} detections, median confidence {:.2}, suggested threshold {threshold:.2}",
+                species.common_name,
+                species.scientific_name,
+                species.detections,
+                species.median_confidence
+            );
+        }
+    }
+}
+
 /// Process all files, updating stats in place.
 ///
 /// On fail-fast error, returns `Err` immediately but `stats` contains partial results.
@@ -499,16 +1230,40 @@ fn process_all_files(
 
     let file_progress = progress::create_file_progress(files.len(), params.progress_enabled);
 
+    // May shrink if a file hits GPU memory exhaustion; later files reuse the reduced size
+    let mut batch_size = params.batch_size;
+    stats.effective_batch_size = Some(batch_size);
+
     for (index, file) in files.iter().enumerate() {
+        // An interrupted run is logged with the files finished so far
+        runs::update_active(|record| stats.fill_run_record(record));
         let file_output_dir = output_dir_for(file, params.output_dir);
 
+        if let Some(reason) = params.file_filter.rejection(file) {
+            info!("Skipping (filtered, {reason}): {}", file.display());
+            reporter.file_skipped(file, FileStatus::Filtered);
+            stats.skipped += 1;
+            stats.filtered += 1;
+            progress::inc_progress(file_progress.as_ref());
+            continue;
+        }
+
+        // Inputs with their own range record it in their manifest
+        let file_manifest = params
+            .file_ranges
+            .get(file)
+            .map(|range| params.run_manifest.with_time_range(range.to_string()));
+        let run_manifest = file_manifest.as_ref().unwrap_or(&params.run_manifest);
+
         // Check if should process
         match should_process(
             file,
             &file_output_dir,
+            params.lock_dir,
             params.formats,
-            params.force,
-            params.stdout_mode,
+            params.skip_policy,
+            &run_manifest.settings_hash,
+            params.stdout_mode || params.stdout_format.is_some(),
         ) {
             ProcessCheck::SkipExists => {
                 info!("Skipping (output exists): {}", file.display());
@@ -527,8 +1282,12 @@ fn process_all_files(
             ProcessCheck::Process => {}
         }
 
-        // Get audio duration for progress estimation
-        let audio_duration = crate::audio::get_audio_duration(file).ok().flatten();
+        // Get audio duration for progress estimation, limited to the analyzed range
+        let time_range = params.file_ranges.get(file).copied().or(params.time_range);
+        let audio_duration = crate::audio::get_audio_duration(file)
+            .ok()
+            .flatten()
+            .map(|duration| time_range.map_or(duration, |r| r.duration_within(duration)));
 
         // Estimate segments for reporter; bat mode uses shorter segments
         let segment_duration = if params.custom_classifier.is_some() {
@@ -549,8 +1308,55 @@ fn process_all_files(
         )
         .unwrap_or(0) as usize;
 
+        // Song Meter recordings carry their recorder settings
+        let recorder = if params.recorder_metadata {
+            crate::audio::read_recorder_metadata(file).ok().flatten()
+        } else {
+            None
+        };
+
+        // Without a location for the run, the recording's metadata or a GPX
+        // sidecar places it for the range filter and BSG
+        let location = if params.locate_files {
+            recorder
+                .as_ref()
+                .and_then(crate::audio::RecorderMetadata::location)
+                .or_else(|| utils::track::sidecar_position(file))
+        } else {
+            None
+        };
+        let range_filter_params = params.range_filter_params.or_else(|| {
+            let ((lat, lon), week) = location.zip(params.range_filter_week)?;
+            Some((lat, lon, week))
+        });
+        if range_filter_params.is_none() && params.range_filter_week.is_some() {
+            warn!(
+                "{}: no recorded location, range filter not applied",
+                file.display()
+            );
+        }
+        let bsg_params = params
+            .bsg_params
+            .or_else(|| location.map(|(lat, lon)| (lat, lon, params.bsg_day_of_year)));
+        let recording_start = params.timestamps.as_ref().and_then(|timestamps| {
+            let start = timestamps.recording_start(file, recorder.as_ref());
+            if start.is_none() {
+                warn!(
+                    "{}: no recording start time, absolute times left empty",
+                    file.display()
+                );
+            }
+            start
+        });
+
         // Report file start
-        reporter.file_started(file, index, estimated_segments, audio_duration);
+        reporter.file_started(
+            file,
+            index,
+            estimated_segments,
+            audio_duration,
+            recorder.as_ref(),
+        );
 
         // Process the file
         let file_start = std::time::Instant::now();
@@ -563,35 +1369,85 @@ fn process_all_files(
         let proc_config = ProcessingConfig {
             input_path: file,
             output_dir: &file_output_dir,
+            lock_dir: params.lock_dir,
             formats: params.formats,
             min_confidence: params.min_confidence,
             overlap: params.overlap,
-            batch_size: params.batch_size,
+            sensitivity: params.sensitivity,
+            batch_size,
             csv_columns: params.csv_columns,
             progress_enabled: params.progress_enabled,
             csv_bom_enabled: params.csv_bom,
             model_name: params.model_name,
-            range_filter_params: params.range_filter_params,
-            bsg_params: params.bsg_params,
+            range_filter_params,
+            bsg_params,
             reporter: reporter_ref,
             dual_output_mode: params.dual_output_mode,
+            stdout_format: params.stdout_format,
             custom_classifier: params.custom_classifier,
             bat_mode: params.custom_classifier.is_some(),
+            activity_format: params.activity_format,
+            activity_threshold_dbfs: params.activity_threshold_dbfs,
+            watchdog: &params.watchdog,
+            warning_reporter: reporter.as_ref(),
+            postprocess_hook: params.postprocess_hook.as_ref(),
+            merge_overlapping: params.merge_overlapping,
+            zoom_overlap: params.zoom_overlap,
+            fingerprint: params.fingerprint,
+            provenance: params.provenance.as_ref(),
+            recorder: recorder.as_ref().filter(|_| params.recorder_columns),
+            recording_start,
+            station: params.station.as_ref(),
+            sensitive: params.sensitive.as_ref(),
+            track: params.track.as_ref(),
+            run_manifest: Some(run_manifest),
+            combined: params.combined.as_ref(),
+            split_limits: params.split_limits,
+            number_format: params.number_format,
+            time_range,
+            channel: params.channel,
+            filter: params.filter,
+            stitch: params.stitches.get(file),
+            report: params.report,
+            fault: params.fault.as_ref(),
         };
         match process_file(&proc_config, classifier) {
             Ok(result) => {
                 #[allow(clippy::cast_possible_truncation)]
                 let duration_ms = file_start.elapsed().as_millis() as u64;
-                reporter.file_completed_success(file, result.detections, duration_ms);
+                reporter.file_completed_success(
+                    file,
+                    result.detections,
+                    &result.species,
+                    duration_ms,
+                );
                 stats.processed += 1;
                 stats.total_detections += result.detections;
                 stats.total_segments += result.segments;
                 stats.total_audio_duration += result.audio_duration_secs;
+                stats.sample_gpu_memory(classifier);
+                if let Some(reduced) = result.reduced_batch_size {
+                    warn!(
+                        "Using batch size {} for remaining files after GPU memory backoff",
+                        reduced
+                    );
+                    batch_size = reduced;
+                    stats.effective_batch_size = Some(reduced);
+                }
             }
             Err(e) => {
                 error!("Failed to process {}: {}", file.display(), e);
                 reporter.file_completed_failure(file, "processing_error", &e.to_string());
                 stats.errors += 1;
+                if let Some(dead_letter) = &params.dead_letter {
+                    match dead_letter.handle(file, &file_output_dir, &e) {
+                        Ok(Some(record)) => {
+                            info!("Recorded failed file in {}", record.display());
+                        }
+                        Ok(None) => {}
+                        Err(err) => warn!("Failed to set aside {}: {err}", file.display()),
+                    }
+                }
                 if params.fail_fast {
                     progress::finish_progress(file_progress, "Failed");
                     return Err(e);
@@ -601,22 +1457,213 @@ fn process_all_files(
         progress::inc_progress(file_progress.as_ref());
     }
 
+    runs::update_active(|record| stats.fill_run_record(record));
     progress::finish_progress(file_progress, "Complete");
     Ok(())
 }
 
-/// Analyze input files with the given options.
+/// Analyze new files in the `dirs` as they appear, until interrupted.
+///
+/// The classifier stays loaded between files. Only a fail-fast error ends the
+/// watch; Ctrl+C exits through the interrupt handler, which logs the run as
+/// interrupted.
+fn watch_files(
+    dirs: &[PathBuf],
+    exclude: &[utils::glob::Glob],
+    interval: std::time::Duration,
+    classifier: &BirdClassifier,
+    params: &ProcessingParams<'_>,
+    reporter: &Arc<dyn ProgressReporter>,
+    stats: &mut ProcessingStats,
+) -> Result<()> {
+    let mut watcher = pipeline::DirectoryWatcher::new(dirs.to_vec(), exclude.to_vec());
+    info!(
+        "Watching {} for new audio files every {}s (Ctrl+C to stop)",
+        dirs.iter()
+            .map(|d| d.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+        interval.as_secs()
+    );
+
+    loop {
+        // Network shares can be briefly unavailable; retry on the next scan
+        match watcher.poll() {
+            Ok(files) if !files.is_empty() => {
+                info!("Found {} new audio file(s)", files.len());
+                process_all_files(&files, classifier, params, reporter, stats)?;
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to scan watched directories: {e}"),
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+/// Analyze `inputs` with the given options and record the run in the runs
+/// log.
+///
+/// With `watch`, the inputs are directories that are scanned at that interval
+/// and new files are analyzed until the process is interrupted. The run is
+/// registered as the active run while it lasts, so the interrupt handler can
+/// log it. Failing to write the log only logs a warning, so a read-only
+/// config directory never fails an analysis.
 fn analyze_files(
     inputs: &[PathBuf],
     args: &AnalyzeArgs,
     config: &Config,
     output_mode: OutputMode,
     reporter: &Arc<dyn ProgressReporter>,
+    watch: Option<std::time::Duration>,
+    gpu_memory: Option<inference::GpuMemoryOptions>,
 ) -> Result<()> {
-    use std::time::Instant;
+    let started = chrono::Utc::now();
+    let start = std::time::Instant::now();
+    let mut record = runs::RunRecord {
+        run_id: runs::RunRecord::new_id(started),
+        started,
+        duration_secs: 0.0,
+        birda_version: env!("CARGO_PKG_VERSION").to_string(),
+        hostname: hostname::get().map_or_else(
+            |_| "unknown".to_string(),
+            |h| h.to_string_lossy().to_string(),
+        ),
+        station: output::Station::from_config(&config.station),
+        command_line: std::env::args().collect(),
+        inputs: inputs.to_vec(),
+        settings: None,
+        sample: None,
+        files_processed: 0,
+        files_skipped: 0,
+        files_failed: 0,
+        detections: 0,
+        outcome: runs::RunOutcome::Interrupted,
+        error: None,
+    };
+    runs::set_active(Some(record.clone()));
+    let mut stats = ProcessingStats::default();
+    let result = run_analysis(
+        inputs,
+        args,
+        config,
+        output_mode,
+        reporter,
+        watch,
+        gpu_memory,
+        &record.run_id,
+        &mut stats,
+    );
+    runs::set_active(None);
+
+    stats.fill_run_record(&mut record);
+    record.duration_secs = start.elapsed().as_secs_f64();
+    record.outcome = match &result {
+        Err(_) => runs::RunOutcome::Failed,
+        Ok(()) if stats.errors > 0 => runs::RunOutcome::CompletedWithErrors,
+        Ok(()) => runs::RunOutcome::Success,
+    };
+    record.error = result.as_ref().err().map(ToString::to_string);
+    if let Err(e) = runs::log_path().and_then(|path| runs::append(&path, &record)) {
+        warn!("Failed to record run in the runs log: {e}");
+    } else {
+        tracing::debug!("Recorded run {}", record.run_id);
+    }
+
+    result
+}
+
+/// Analyze `inputs`, accumulating counts in `stats` even when the run fails.
+///
+/// `run_id` is the run's ID in the runs log, recorded in each file's run
+/// manifest.
+#[allow(clippy::too_many_arguments)]
+fn run_analysis(
+    inputs: &[PathBuf],
+    args: &AnalyzeArgs,
+    config: &Config,
+    output_mode: OutputMode,
+    reporter: &Arc<dyn ProgressReporter>,
+    watch: Option<std::time::Duration>,
+    gpu_memory: Option<inference::GpuMemoryOptions>,
+    run_id: &str,
+    stats: &mut ProcessingStats,
+) -> Result<()> {
+    use std::time::Instant;
 
     let total_start = Instant::now();
 
+    let fault = args.fault_inject.map(|mut fault| {
+        if let Some(after) = args.fail_after {
+            fault.after = after;
+        }
+        warn!("Injecting faults at {fault}; analysis will fail on purpose");
+        pipeline::FaultInjector::new(fault)
+    });
+
+    // A network stream is analyzed continuously rather than file by file
+    if let Some(url) = inputs
+        .iter()
+        .filter_map(|input| input.to_str())
+        .find(|input| audio::is_stream_url(input))
+    {
+        if inputs.len() > 1 || watch.is_some() {
+            return Err(Error::ConfigValidation {
+                message: "a stream URL must be the only input".to_string(),
+            });
+        }
+        return analyze_stream_input(url, args, config, output_mode, reporter, gpu_memory);
+    }
+
+    // A file list adds inputs that did not fit on the command line
+    let listed = match &args.file_list {
+        Some(_) if watch.is_some() => {
+            return Err(Error::ConfigValidation {
+                message: "--file-list cannot be used with watch".to_string(),
+            });
+        }
+        Some(list) => pipeline::read_file_list(list)?,
+        None => Vec::new(),
+    };
+
+    // Inputs may carry their own range as `path@START-END`
+    let mut input_paths = Vec::with_capacity(inputs.len() + listed.len());
+    let mut input_ranges = Vec::new();
+    for input in inputs.iter().chain(&listed) {
+        let (path, range) = audio::split_input_range(input)
+            .map_err(|message| Error::ConfigValidation { message })?;
+        if let Some(range) = range {
+            input_ranges.push((path.clone(), range));
+        }
+        input_paths.push(path);
+    }
+    let inputs = input_paths.as_slice();
+    let time_range = if args.from.is_some() || args.to.is_some() {
+        Some(
+            audio::TimeRange::new(args.from, args.to).map_err(|e| Error::ConfigValidation {
+                message: format!("--from/--to: {e}"),
+            })?,
+        )
+    } else {
+        None
+    };
+    if let (Some(min), Some(max)) = (args.min_duration, args.max_duration)
+        && min > max
+    {
+        return Err(Error::ConfigValidation {
+            message: format!("--min-duration ({min}s) is longer than --max-duration ({max}s)"),
+        });
+    }
+    if watch.is_some() && !input_ranges.is_empty() {
+        return Err(Error::ConfigValidation {
+            message: "watched directories cannot have a time range; use --from/--to".to_string(),
+        });
+    }
+    if args.stitch && (watch.is_some() || !input_ranges.is_empty()) {
+        return Err(Error::ConfigValidation {
+            message: "--stitch cannot be used with watch or per-input time ranges".to_string(),
+        });
+    }
+
     // Fail fast on configuration errors before scanning filesystem
     // Resolve model configuration using priority-based resolution
     let (model_config, model_name) = resolve_model_config(args, config)?;
@@ -634,8 +1681,17 @@ fn analyze_files(
             });
         }
 
-        // Resolve bat models directory: <data_dir>/models/bat/
-        let bat_models_dir = registry::models_dir()?.join("bat");
+        // The bat classifier already replaces the backbone's predictions
+        if model_config.custom_head.is_some() {
+            return Err(Error::ConfigValidation {
+                message: format!(
+                    "--bat cannot be used with model '{model_name}', which has a custom_head"
+                ),
+            });
+        }
+
+        // Resolve bat models directory: <models_dir>/bat/
+        let bat_models_dir = registry::configured_models_dir(config)?.join("bat");
         let bat_config = BatConfig::resolve(region, &bat_models_dir)?;
 
         info!(
@@ -663,19 +1719,101 @@ fn analyze_files(
         None
     };
 
-    // Collect input files only after config is validated
-    let files = collect_input_files(inputs)?;
-    if files.is_empty() {
-        return Err(Error::NoValidAudioFiles);
+    // Read-only inputs: results must go elsewhere and locks to a private directory
+    let read_only_inputs = args.read_only_inputs || config.output.read_only_inputs;
+    if read_only_inputs {
+        let writes_files = !args.stdout && args.stdout_format.is_none();
+        validate_read_only_inputs(inputs, args.output_dir.as_deref(), writes_files)?;
+    }
+
+    let dead_letter =
+        args.dead_letter
+            .or(config.output.dead_letter)
+            .map(|mode| pipeline::DeadLetter {
+                mode,
+                dir: args
+                    .dead_letter_dir
+                    .clone()
+                    .or_else(|| config.output.dead_letter_dir.clone()),
+            });
+    if read_only_inputs
+        && dead_letter
+            .as_ref()
+            .is_some_and(|d| d.mode == config::DeadLetterMode::Move)
+    {
+        return Err(Error::ConfigValidation {
+            message: "--dead-letter move cannot be used with --read-only-inputs".to_string(),
+        });
+    }
+
+    // A configured central lock directory wins; read-only runs without one
+    // fall back to a run-specific directory
+    let central_lock_dir = args
+        .lock_dir
+        .clone()
+        .or_else(|| config.output.lock_dir.clone());
+    let run_lock_dir = if central_lock_dir.is_none() && read_only_inputs {
+        Some(locking::RunLockDir::create()?)
+    } else {
+        None
+    };
+    let lock_dir = central_lock_dir
+        .as_deref()
+        .or_else(|| run_lock_dir.as_ref().map(locking::RunLockDir::path));
+    if let Some(lock_dir) = lock_dir {
+        locking::set_central_lock_dir(lock_dir);
+        info!("Lock files in {}", lock_dir.display());
     }
 
-    info!("Found {} audio file(s) to process", files.len());
+    let number_format = output::NumberFormat {
+        confidence_decimals: args
+            .confidence_decimals
+            .or(config.output.confidence_decimals),
+        time_decimals: args.time_decimals.or(config.output.time_decimals),
+    };
+
+    // Collect input files only after config is validated; a watch finds
+    // its files as they appear
+    let files = if watch.is_some() {
+        if let Some(dir) = inputs.iter().find(|d| !d.is_dir()) {
+            return Err(Error::ConfigValidation {
+                message: format!("watched path is not a directory: {}", dir.display()),
+            });
+        }
+        Vec::new()
+    } else {
+        let files = collect_input_files(inputs, &args.exclude)?;
+        if files.is_empty() {
+            return Err(Error::NoValidAudioFiles);
+        }
+        info!("Found {} audio file(s) to process", files.len());
+        files
+    };
+    let (files, sample) = sample_inputs(files, args)?;
+
+    let mut file_ranges = HashMap::new();
+    for (input, range) in &input_ranges {
+        for file in collect_input_files(std::slice::from_ref(input), &args.exclude)? {
+            file_ranges.insert(file, *range);
+        }
+    }
 
     // Resolve other settings
     let min_confidence = args
         .min_confidence
         .unwrap_or(config.defaults.min_confidence);
 
+    // BSG models calibrate their own confidences, which reshaping would undo
+    let mut sensitivity = args.sensitivity.unwrap_or(config.defaults.sensitivity);
+    if model_config.model_type == ModelType::BsgFinland
+        && !inference::is_default_sensitivity(sensitivity)
+    {
+        warn!("Ignoring sensitivity {sensitivity}: BSG models calibrate their own confidences");
+        sensitivity = constants::sensitivity::DEFAULT;
+    }
+    // The model must keep predictions that the sensitivity lifts above the threshold
+    let model_min_confidence = inference::sensitivity_threshold(min_confidence, sensitivity);
+
     // In bat mode, override overlap to the bat-specific value unless the user
     // explicitly provided one via CLI
     let overlap = if bat_classifier.is_some() && args.overlap.is_none() {
@@ -697,21 +1835,79 @@ fn analyze_files(
         .clone()
         .unwrap_or_else(|| config.defaults.formats.clone());
     let output_dir = args.output_dir.clone();
-    let force = args.force;
+    // --force is shorthand for never skipping
+    let skip_policy = if args.force {
+        config::SkipPolicy::Never
+    } else {
+        args.skip_policy.unwrap_or(config.defaults.skip_policy)
+    };
     let fail_fast = args.fail_fast;
 
+    // Run-wide outputs are finalized when the run ends, which a watch never does
+    let partitioned_parquet = formats.contains(&OutputFormat::Parquet)
+        && args.parquet_layout.unwrap_or(config.output.parquet_layout)
+            == config::ParquetLayout::Partitioned;
+    let sqlite = formats.contains(&OutputFormat::Sqlite);
+    if watch.is_some()
+        && (args.combine
+            || args.duckdb.is_some()
+            || args.report.is_some()
+            || args.suggest_thresholds
+            || partitioned_parquet
+            || sqlite)
+    {
+        return Err(Error::ConfigValidation {
+            message: "watch does not support --combine, --duckdb, --report, --suggest-thresholds, \
+                      sqlite output or the partitioned Parquet layout"
+                .to_string(),
+        });
+    }
+    if formats.contains(&OutputFormat::Csv) && formats.contains(&OutputFormat::BirdnetCsv) {
+        return Err(Error::ConfigValidation {
+            message: "csv and birdnet-csv both write .BirdNET.results.csv files; choose one"
+                .to_string(),
+        });
+    }
+
     // Resolve device from command-line flags or config
     let device = resolve_device(args, config);
+    let device = gate_gpu_memory(
+        args,
+        config,
+        device,
+        &model_config,
+        requested_batch_size,
+        reporter,
+    )?;
+
+    // Without a location for the run, each recording supplies its own
+    let locate_files = args
+        .lat
+        .or(config.defaults.latitude)
+        .zip(args.lon.or(config.defaults.longitude))
+        .is_none();
 
     // Build range filter config
-    let range_filter_config = build_range_filter_config(args, config, &model_config, &model_name)?;
+    let range_filter_config =
+        match build_range_filter_config(args, config, &model_config, &model_name)? {
+            // A species list takes the place of a filter without a location
+            None if locate_files
+                && args.slist.is_none()
+                && config.defaults.species_list_file.is_none() =>
+            {
+                build_recording_range_filter_config(args, config, &model_config, &model_name)?
+            }
+            range_filter_config => range_filter_config,
+        };
 
     // Log if range filtering is enabled
     if let Some(ref rf_config) = range_filter_config {
         info!(
-            "Range filter enabled: lat={:.4}, lon={:.4}, month={}, day={}, threshold={:.3}{}",
-            rf_config.latitude,
-            rf_config.longitude,
+            "Range filter enabled: {}, month={}, day={}, threshold={:.3}{}",
+            rf_config.location.map_or_else(
+                || "location from each recording".to_string(),
+                |(lat, lon)| format!("lat={lat:.4}, lon={lon:.4}")
+            ),
             rf_config.month,
             rf_config.day,
             rf_config.threshold,
@@ -728,57 +1924,101 @@ fn analyze_files(
 
     // Extract range filter params and BSG params before moving range_filter_config
     #[allow(clippy::cast_possible_truncation)]
-    let range_filter_params = range_filter_config.as_ref().map(|rf| {
-        let week = crate::utils::date::date_to_week(rf.month, rf.day) as u8;
-        (rf.latitude, rf.longitude, week)
-    });
+    let range_filter_week = range_filter_config
+        .as_ref()
+        .map(|rf| crate::utils::date::date_to_week(rf.month, rf.day) as u8);
+    let range_filter_params = range_filter_config
+        .as_ref()
+        .and_then(|rf| rf.location)
+        .zip(range_filter_week)
+        .map(|((lat, lon), week)| (lat, lon, week));
+    let range_filter_settings =
+        range_filter_config
+            .as_ref()
+            .zip(range_filter_week)
+            .map(|(rf, week)| output::RangeFilterSettings {
+                latitude: rf.location.map(|(lat, _)| lat),
+                longitude: rf.location.map(|(_, lon)| lon),
+                week,
+                threshold: rf.threshold,
+                rerank: rf.rerank,
+                meta_model_source: rf.meta_model_source.clone(),
+            });
 
     // Build BSG SDM parameters (latitude, longitude, day_of_year)
     // day_of_year is None for auto-detection from file timestamp
     // Use same latitude/longitude as range filter if available
-    let bsg_params = range_filter_config.as_ref().map_or_else(
-        || {
-            if let (Some(lat), Some(lon)) = (args.lat, args.lon) {
-                let day_of_year = args.day_of_year.or(config.defaults.day_of_year);
-                Some((lat, lon, day_of_year))
-            } else {
-                None
-            }
-        },
-        |rf| {
-            let day_of_year = args.day_of_year.or(config.defaults.day_of_year);
-            Some((rf.latitude, rf.longitude, day_of_year))
-        },
-    );
+    let day_of_year = args.day_of_year.or(config.defaults.day_of_year);
+    let bsg_params = range_filter_config
+        .as_ref()
+        .and_then(|rf| rf.location)
+        .or_else(|| args.lat.zip(args.lon))
+        .map(|(lat, lon)| (lat, lon, day_of_year));
+
+    if device != InferenceDevice::Cpu {
+        stats.gpu_memory_baseline = gpu::used_memory_bytes();
+    }
 
     // Build classifier
     info!("Loading model: {}", model_name);
-    let classifier = BirdClassifier::from_config(
+    let mut classifier = BirdClassifier::from_config(
         &model_config,
         device,
-        min_confidence,
+        model_min_confidence,
         DEFAULT_TOP_K,
         range_filter_config,
         species_list,
+        gpu_memory,
     )?;
+    if let Some(sessions) = args.cpu_sessions.or(config.inference.cpu_sessions) {
+        classifier.add_cpu_sessions(
+            &model_config,
+            sessions.saturating_sub(1),
+            model_min_confidence,
+            DEFAULT_TOP_K,
+        )?;
+    }
+    if !inference::is_default_sensitivity(sensitivity) {
+        info!("Detection sensitivity: {sensitivity}");
+        classifier.set_sensitivity(sensitivity);
+    }
+
+    // The zoom pass needs a finer grid than the first pass
+    let zoom_overlap = match args.zoom_overlap {
+        Some(_) if bat_classifier.is_some() => {
+            warn!("Ignoring --zoom-overlap: bat mode uses a fixed overlap");
+            None
+        }
+        Some(zoom) if zoom <= overlap || zoom >= classifier.segment_duration() => {
+            return Err(Error::ConfigValidation {
+                message: format!(
+                    "--zoom-overlap must be greater than the {overlap}s overlap and less than the {}s segment duration, got {zoom}",
+                    classifier.segment_duration()
+                ),
+            });
+        }
+        zoom => zoom,
+    };
 
-    // Determine final batch size: user choice > smart default based on actual EP
-    let batch_size = requested_batch_size.unwrap_or_else(|| {
-        let default = determine_default_batch_size(
+    // Determine final batch size: user choice > auto-tuned > smart default based on actual EP
+    let batch_size = match requested_batch_size {
+        Some(config::BatchSize::Fixed(size)) => size,
+        Some(config::BatchSize::Auto) => {
+            tune_batch_size(&classifier, model_config.model_type, &model_name)?
+        }
+        None => determine_default_batch_size(
             model_config.model_type,
             classifier.execution_provider_status(),
-        );
-        info!(
-            "Using default batch size {} for {} with {} provider",
-            default,
-            model_config.model_type,
-            classifier.execution_provider_status().actual
-        );
-        default
-    });
+        ),
+    };
 
     // Warm up the classifier (handles TensorRT spinner internally)
-    warmup_classifier(&classifier, batch_size)?;
+    let batch_size = warmup_classifier(
+        &classifier,
+        batch_size,
+        &model_name,
+        &config.inference.tensorrt_warmup_batch_sizes,
+    )?;
 
     // Report pipeline start with execution provider info
     let ep_info = classifier.execution_provider_status().clone().into();
@@ -800,38 +2040,407 @@ fn analyze_files(
     let dual_output_mode =
         output_dir.is_some() && matches!(output_mode, OutputMode::Ndjson) && !args.stdout;
 
+    let watchdog = gpu::WatchdogPolicy::new(
+        &classifier.execution_provider_status().actual,
+        &config.inference.watchdog,
+    )
+    .with_reporter(Arc::clone(reporter));
+    info!(
+        "Inference watchdog timeout: {}s",
+        watchdog.timeout().as_secs()
+    );
+
+    // Fingerprints are written as an extra metadata column
+    let mut csv_columns = config.defaults.csv_columns.include.clone();
+    if args.fingerprint
+        && !csv_columns
+            .iter()
+            .any(|c| c == constants::fingerprint::COLUMN)
+    {
+        csv_columns.push(constants::fingerprint::COLUMN.to_string());
+    }
+    if args.provenance {
+        for column in constants::provenance::COLUMNS {
+            if !csv_columns.iter().any(|c| c == column) {
+                csv_columns.push(column.to_string());
+            }
+        }
+    }
+    if args.recorder_metadata {
+        for column in constants::recorder::COLUMNS {
+            if !csv_columns.iter().any(|c| c == column) {
+                csv_columns.push(column.to_string());
+            }
+        }
+    }
+    if args.wall_clock {
+        for column in constants::wall_clock::COLUMNS {
+            if !csv_columns.iter().any(|c| c == column) {
+                csv_columns.push(column.to_string());
+            }
+        }
+    }
+    // A configured station is recorded on every detection
+    let station = output::Station::from_config(&config.station);
+    if let Some(station) = &station {
+        for column in station.columns() {
+            if !csv_columns.iter().any(|c| c == column) {
+                csv_columns.push(column.to_string());
+            }
+        }
+    }
+    // Selected channels are recorded on each detection
+    let channel = args.channel.unwrap_or_default();
+    let filter = args.highpass.or(args.bandpass);
+    if let Some(filter) = filter {
+        info!("Pre-filtering audio: {filter}");
+    }
+    if channel != audio::ChannelSelection::Mix
+        && !csv_columns.iter().any(|c| c == constants::channel::COLUMN)
+    {
+        csv_columns.push(constants::channel::COLUMN.to_string());
+    }
+    // Sensitive species are flagged in their own column
+    let sensitive = output::SensitiveSpecies::load(&config.sensitive)?;
+    if let Some(sensitive) = &sensitive {
+        info!(
+            "Sensitive species lists loaded; shared outputs will {} them",
+            sensitive.action()
+        );
+        if !csv_columns
+            .iter()
+            .any(|c| c == constants::sensitive::COLUMN)
+        {
+            csv_columns.push(constants::sensitive::COLUMN.to_string());
+        }
+    }
+    // A GPS track gives every detection its own location
+    let track = match &args.track {
+        Some(path) => {
+            let track = utils::track::GpsTrack::load(path)?
+                .with_utc_offset(args.track_utc_offset.unwrap_or(0.0));
+            let (first, last) = track.span();
+            info!(
+                "Loaded GPS track with {} points ({} to {})",
+                track.len(),
+                first,
+                last
+            );
+            for column in ["lat", "lon"] {
+                if !csv_columns.iter().any(|c| c == column) {
+                    csv_columns.push(column.to_string());
+                }
+            }
+            Some(track)
+        }
+        None => None,
+    };
+    // The model hash identifies the model in manifests and provenance columns
+    let model_hash = utils::hash::sha256_file(&model_config.path)?;
+    // Provenance is also recorded when the columns are configured explicitly
+    let provenance = if csv_columns
+        .iter()
+        .any(|c| constants::provenance::COLUMNS.contains(&c.as_str()))
+    {
+        Some(output::Provenance::new(
+            classifier.execution_provider_status().actual.to_string(),
+            model_hash.clone(),
+        ))
+    } else {
+        None
+    };
+    // Absolute times are also written when the columns are configured explicitly
+    let timestamps = if csv_columns
+        .iter()
+        .any(|c| constants::wall_clock::COLUMNS.contains(&c.as_str()))
+    {
+        let patterns = if args.timestamp_pattern.is_empty() {
+            config.defaults.timestamp_patterns.clone()
+        } else {
+            args.timestamp_pattern.clone()
+        };
+        Some(utils::timestamp::TimestampParser::new(patterns)?)
+    } else {
+        None
+    };
+
+    // Record everything that affects detections so stale results can be found
+    let mut run_manifest = output::RunManifest::new(output::RunSettings {
+        model: model_name.clone(),
+        model_type: model_config.model_type.to_string(),
+        model_sha256: Some(model_hash),
+        min_confidence,
+        overlap,
+        sensitivity: (!inference::is_default_sensitivity(sensitivity)).then_some(sensitivity),
+        latitude: range_filter_params.map(|(lat, _, _)| lat),
+        longitude: range_filter_params.map(|(_, lon, _)| lon),
+        week: range_filter_params.map(|(_, _, week)| week),
+        species_list: args
+            .slist
+            .clone()
+            .or_else(|| config.defaults.species_list_file.clone()),
+        bat: bat_classifier.is_some(),
+        postprocess_hook: args.postprocess_hook.clone(),
+        merge_overlapping: args.merge_overlapping,
+        zoom_overlap,
+        channel: (channel != audio::ChannelSelection::Mix).then(|| channel.to_string()),
+        filter: filter.map(|filter| filter.to_string()),
+        custom_head: model_config
+            .custom_head
+            .as_ref()
+            .map(|head| head.path.clone()),
+        time_range: time_range.map(|range| range.to_string()),
+        stitch: args.stitch,
+        confidence_decimals: number_format.confidence_decimals,
+        time_decimals: number_format.time_decimals,
+    });
+    tracing::debug!("Run settings hash: {}", run_manifest.settings_hash);
+    stats.settings = Some(run_manifest.settings.clone());
+    run_manifest.run_id = Some(run_id.to_string());
+    run_manifest.station.clone_from(&station);
+    run_manifest.sample = sample;
+    stats.sample = sample;
+
+    let split_limits = output::SplitLimits {
+        max_rows: args.max_rows_per_file,
+        max_bytes: args.max_file_size,
+    };
+
+    // A partitioned dataset replaces the per-recording Parquet files, and the
+    // SQLite database holds the whole run
+    let writes_files = !args.stdout && args.stdout_format.is_none();
+    let partitioned = writes_files && partitioned_parquet;
+    let file_formats: Vec<OutputFormat> = formats
+        .iter()
+        .copied()
+        .filter(|f| !(partitioned && *f == OutputFormat::Parquet) && *f != OutputFormat::Sqlite)
+        .collect();
+
+    // Combined results and the partitioned dataset span all files; a dedicated
+    // writer thread streams them as files finish
+    let run_dir = combined_output_dir(&files, output_dir.as_deref());
+    let prefix = &config.output.combined_prefix;
+    let mut run_outputs = Vec::new();
+    if args.combine {
+        run_outputs.extend(output::combined_outputs(
+            &run_dir,
+            prefix,
+            &formats,
+            &csv_columns,
+            !args.no_csv_bom,
+            number_format,
+            split_limits,
+            args.append,
+        )?);
+    }
+    if partitioned {
+        let root = run_dir.join(output::dataset_dirname(prefix));
+        info!("Writing Parquet dataset to {}", root.display());
+        let dataset = output::ParquetDatasetWriter::new(&root, &csv_columns)?;
+        run_outputs.push((
+            root,
+            Box::new(dataset) as Box<dyn output::OutputWriter + Send>,
+        ));
+    }
+    if writes_files && sqlite {
+        let db = run_dir.join(output::sqlite_filename(prefix));
+        info!("Writing detections to SQLite database {}", db.display());
+        let writer = output::open_sqlite_output(&db, &run_manifest)?;
+        run_outputs.push((db, writer));
+    }
+    if let Some(db) = &args.duckdb {
+        info!("Writing detections to DuckDB database {}", db.display());
+        run_outputs.push((db.clone(), output::open_database_output(db)?));
+    }
+    let combined = if run_outputs.is_empty() {
+        None
+    } else {
+        Some(output::CombinedWriter::start(run_outputs)?)
+    };
+
+    // Recordings that follow on from one another are analyzed as one stream
+    let stitches = if args.stitch {
+        let stitches = pipeline::plan_stitching(
+            &files,
+            classifier.segment_duration(),
+            overlap,
+            classifier.sample_rate(),
+        );
+        info!(
+            "Stitching {} recording(s) across file boundaries",
+            stitches.len()
+        );
+        stitches
+    } else {
+        HashMap::new()
+    };
+
+    let run_report =
+        (args.report.is_some() || args.suggest_thresholds).then(output::RunReport::default);
+
+    // Record what the run resolved to, after all precedence rules
+    reporter.run_config(&output::RunConfigPayload {
+        result_type: output::ResultType::RunConfig,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        model: model_name.clone(),
+        model_type: model_config.model_type.to_string(),
+        model_path: model_config.path.clone(),
+        execution_provider: ep_info.clone(),
+        batch_size,
+        min_confidence,
+        sensitivity,
+        overlap,
+        formats: formats.clone(),
+        columns: csv_columns.clone(),
+        output_dir: output_dir.clone(),
+        range_filter: range_filter_settings,
+        species_list: run_manifest.settings.species_list.clone(),
+        settings_hash: run_manifest.settings_hash.clone(),
+    });
+
+    let recorder_columns = csv_columns
+        .iter()
+        .any(|c| constants::recorder::COLUMNS.contains(&c.as_str()));
+    let recorder_metadata =
+        recorder_columns || locate_files || timestamps.is_some() || output_mode.is_structured();
     let params = ProcessingParams {
-        formats: &formats,
+        formats: &file_formats,
         output_dir: output_dir.as_deref(),
+        lock_dir,
         min_confidence,
         overlap,
+        sensitivity,
         batch_size,
-        csv_columns: &config.defaults.csv_columns.include,
+        csv_columns: &csv_columns,
         csv_bom: !args.no_csv_bom,
         model_name: &model_name,
         range_filter_params,
-        force,
+        range_filter_week,
+        locate_files,
+        skip_policy,
+        run_manifest,
         fail_fast,
         progress_enabled,
         stdout_mode: args.stdout,
+        stdout_format: args.stdout_format,
         dual_output_mode,
         bsg_params,
+        bsg_day_of_year: day_of_year,
         custom_classifier: bat_classifier.as_ref(),
+        activity_format: args.activity,
+        activity_threshold_dbfs: args
+            .activity_threshold
+            .unwrap_or(constants::activity::DEFAULT_ENERGY_THRESHOLD_DBFS),
+        watchdog,
+        postprocess_hook: args
+            .postprocess_hook
+            .as_ref()
+            .map(pipeline::PostprocessHook::new),
+        merge_overlapping: args.merge_overlapping,
+        zoom_overlap,
+        fingerprint: args.fingerprint,
+        provenance,
+        recorder_columns,
+        recorder_metadata,
+        timestamps,
+        sensitive,
+        track,
+        combined: combined.as_ref().map(output::CombinedWriter::sender),
+        split_limits,
+        number_format,
+        station,
+        time_range,
+        file_ranges,
+        channel,
+        filter,
+        file_filter: pipeline::FileFilter {
+            min_duration: args.min_duration,
+            max_duration: args.max_duration,
+            min_size: args.min_size,
+        },
+        dead_letter,
+        stitches,
+        report: run_report.as_ref(),
+        fault,
     };
 
-    // Process all files - stats owned here so partial results available on fail-fast
-    let mut stats = ProcessingStats::default();
-    let result = process_all_files(&files, &classifier, &params, reporter, &mut stats);
+    // Process all files - stats owned by the caller so partial results are
+    // available on fail-fast
+    let result = match watch {
+        Some(interval) => watch_files(
+            inputs,
+            &args.exclude,
+            interval,
+            &classifier,
+            &params,
+            reporter,
+            stats,
+        ),
+        None => process_all_files(&files, &classifier, &params, reporter, stats),
+    };
+
+    // Release the params sender so the combiner sees the channel close, and
+    // finalize even after a fail-fast error so the combined files stay readable
+    drop(params);
+    let combined_result = combined.map_or(Ok(()), |writer| {
+        finish_combined(writer, stats, args.combine)
+    });
+
+    // Like combined files, the report also covers a run cut short by fail-fast
+    let report_result = match (&args.report, &run_report) {
+        (Some(path), Some(report)) => {
+            let totals = output::RunTotals {
+                model: model_name.clone(),
+                files_skipped: stats.skipped,
+                files_failed: stats.errors,
+                elapsed_secs: total_start.elapsed().as_secs_f64(),
+                min_confidence,
+            };
+            report.write(path, &totals).inspect(|()| {
+                info!("Run report: {}", path.display());
+            })
+        }
+        _ => Ok(()),
+    };
+
+    if args.suggest_thresholds {
+        stats.confidence = run_report
+            .as_ref()
+            .map(|report| report.confidence_summary(min_confidence));
+    }
 
     // analyze_files is sole authority for all reporting (success or failure)
-    report_summary(&stats, total_start, fail_fast, reporter);
+    report_summary(stats, total_start, fail_fast, reporter);
 
     // Propagate any error after reporting
-    result
+    result.and(combined_result).and(report_result)
+}
+
+/// Finalize run-wide output files and log where they were written.
+fn finish_combined(
+    writer: output::CombinedWriter,
+    stats: &ProcessingStats,
+    combine: bool,
+) -> Result<()> {
+    for path in writer.finish()? {
+        if path.is_dir() {
+            info!("Parquet dataset: {}", path.display());
+        } else {
+            info!("Combined results: {}", path.display());
+        }
+    }
+    // Filtered files have no results to include
+    let skipped = stats.skipped - stats.filtered;
+    if combine && skipped > 0 {
+        warn!(
+            "{skipped} skipped file(s) are not in the combined results; use --force to include them"
+        );
+    }
+    Ok(())
 }
 
-fn init_logging(verbose: u8, quiet: bool) {
-    use tracing_subscriber::{EnvFilter, fmt};
+fn init_logging(verbose: u8, quiet: bool, log_events: output::LogEventLevel) {
+    use tracing_subscriber::{EnvFilter, Layer, filter, fmt, prelude::*};
 
     // Build filter string based on verbosity level.
     // ORT logging is suppressed by default because CUDA fallback is expected in auto mode.
@@ -849,11 +2458,21 @@ fn init_logging(verbose: u8, quiet: bool) {
 
     let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&filter_str));
 
+    // Events captured as `log` events (json/ndjson modes) stay off stderr
+    let events_level = log_events.level_filter();
+    let stderr = fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_filter(filter)
+        .with_filter(filter::filter_fn(move |metadata| {
+            !(output::log_events_active() && events_level >= *metadata.level())
+        }));
+    let events = output::LogEventLayer.with_filter(events_level);
+
     // Write logs to stderr to keep stdout clean for JSON output
     // Use try_init() to avoid panic if subscriber is already set (e.g., in tests)
-    let _ = fmt()
-        .with_env_filter(filter)
-        .with_writer(std::io::stderr)
+    let _ = tracing_subscriber::registry()
+        .with(stderr)
+        .with(events)
         .try_init();
 }
 
@@ -861,15 +2480,25 @@ fn handle_command(
     command: Command,
     config: &config::Config,
     output_mode: OutputMode,
-    _reporter: &Arc<dyn ProgressReporter>,
+    reporter: &Arc<dyn ProgressReporter>,
+    gpu_memory: Option<inference::GpuMemoryOptions>,
 ) -> Result<()> {
     match command {
-        Command::Config { action } => handle_config_command(action, output_mode),
-        Command::Models { action } => handle_models_command(action, config, output_mode),
+        Command::Config { action } => handle_config_command(action, config, output_mode),
+        Command::Models { action } => handle_models_command(action, config, output_mode, reporter),
         Command::Providers => {
             handle_providers_command(output_mode);
             Ok(())
         }
+        Command::Formats { action } => {
+            handle_formats_command(&action, output_mode);
+            Ok(())
+        }
+        Command::Cache { action } => handle_cache_command(&action, output_mode),
+        Command::Runs { action } => handle_runs_command(&action, output_mode),
+        Command::Examples { action, catalog } => {
+            handle_examples_command(&action, catalog.as_deref(), config, output_mode, reporter)
+        }
         Command::Species {
             output,
             lat,
@@ -891,30 +2520,347 @@ fn handle_command(
             sort,
             model,
             output_mode,
+            config,
+        ),
+        Command::Analyze(args) => analyze_files(
+            &args.inputs,
+            &args.analyze,
+            config,
+            output_mode,
+            reporter,
+            None,
+            gpu_memory,
+        ),
+        Command::Watch(args) => analyze_files(
+            &args.dirs,
+            &args.analyze,
+            config,
+            output_mode,
+            reporter,
+            Some(std::time::Duration::from_secs(args.poll_interval)),
+            gpu_memory,
         ),
+        Command::Selftest { model } => {
+            handle_selftest_command(model.as_deref(), config, output_mode)
+        }
+        Command::Embed(args) => handle_embed_command(&args, config, output_mode, gpu_memory),
+        Command::Listen(args) => {
+            handle_listen_command(&args, config, output_mode, reporter, gpu_memory)
+        }
+        Command::Calibrate(args) => {
+            handle_calibrate_command(&args, config, output_mode, gpu_memory)
+        }
+        Command::Bench(args) => handle_bench_command(&args, config, output_mode, gpu_memory),
+        Command::Serve(args) => handle_serve_command(&args, config, gpu_memory),
         Command::Clip(args) => clipper::command::execute(&args, output_mode),
+        Command::Report { action } => report::command::execute(&action, output_mode),
+        Command::Audit(args) => report::command::audit(&args, config, output_mode),
+        Command::Query(args) => report::command::query(&args, output_mode),
+        Command::Export(args) => report::command::export(&args, config, output_mode),
         Command::Update { check } => handle_update_command(check, output_mode),
     }
 }
 
-fn handle_providers_command(output_mode: OutputMode) {
-    use crate::inference::provider_metadata;
-    use birdnet_onnx::available_execution_providers;
+/// Handle the `formats` command.
+fn handle_formats_command(action: &cli::FormatsAction, output_mode: OutputMode) {
+    use clap::ValueEnum;
 
-    let providers = available_execution_providers();
+    match action {
+        cli::FormatsAction::Describe { format } => {
+            let formats: Vec<output::FormatSchema> = format
+                .map_or_else(|| OutputFormat::value_variants().to_vec(), |f| vec![f])
+                .into_iter()
+                .map(output::format_schema)
+                .collect();
 
-    // Build provider info list
-    let provider_infos: Vec<ProviderInfo> = providers
-        .iter()
-        .map(|provider| {
-            let meta = provider_metadata(*provider);
-            ProviderInfo {
-                id: meta.id.to_string(),
+            if output_mode.is_structured() {
+                emit_json_result(&output::FormatsPayload {
+                    result_type: ResultType::Formats,
+                    formats,
+                });
+                return;
+            }
+
+            for schema in &formats {
+                println!("{} (version {})", schema.format, schema.format_version);
+                if let Some(location) = &schema.version_location {
+                    println!("  Version recorded in: {location}");
+                }
+                for column in &schema.columns {
+                    print_column_schema(column);
+                }
+                if !schema.optional_columns.is_empty() {
+                    println!("  Optional columns (defaults.csv_columns.include):");
+                    for column in &schema.optional_columns {
+                        print_column_schema(column);
+                    }
+                }
+                println!();
+            }
+        }
+    }
+}
+
+/// Print one column of a format schema.
+fn print_column_schema(column: &output::ColumnSchema) {
+    let column_type = match column.column_type {
+        output::ColumnType::Integer => "integer",
+        output::ColumnType::Float => "float",
+        output::ColumnType::String => "string",
+        output::ColumnType::Timestamp => "timestamp",
+        output::ColumnType::Boolean => "boolean",
+    };
+    let unit = column
+        .unit
+        .as_ref()
+        .map_or_else(String::new, |unit| format!(" [{unit}]"));
+    let nullable = if column.nullable { ", nullable" } else { "" };
+    println!(
+        "    {:<18} {column_type}{unit}{nullable} - {}",
+        column.name, column.description
+    );
+}
+
+/// Handle the `examples` subcommand.
+fn handle_examples_command(
+    action: &cli::ExamplesAction,
+    catalog: Option<&Path>,
+    config: &Config,
+    output_mode: OutputMode,
+    reporter: &Arc<dyn ProgressReporter>,
+) -> Result<()> {
+    let set = registry::load_examples(catalog)?;
+    if set.examples.is_empty() && !output_mode.is_structured() {
+        println!(
+            "No example recordings are bundled with this version; use --catalog <FILE> to use a published example set."
+        );
+        return Ok(());
+    }
+
+    match action {
+        cli::ExamplesAction::List => {
+            if output_mode.is_structured() {
+                emit_json_result(&output::ExampleListPayload {
+                    result_type: ResultType::ExampleList,
+                    version: set.version,
+                    examples: set.examples,
+                });
+                return Ok(());
+            }
+            for example in &set.examples {
+                println!(
+                    "{:<20} {:<12} {}",
+                    example.id, example.license, example.attribution
+                );
+                for expected in &example.expected {
+                    println!(
+                        "    {} ({}) {:.1}-{:.1}s",
+                        expected.common_name,
+                        expected.scientific_name,
+                        expected.start_time,
+                        expected.end_time
+                    );
+                }
+            }
+        }
+        cli::ExamplesAction::Fetch {
+            dir,
+            force,
+            connections,
+        } => {
+            let runtime = tokio::runtime::Runtime::new().map_err(|e| Error::Internal {
+                message: format!("Failed to create async runtime: {e}"),
+            })?;
+            let dir = std::path::absolute(dir)?;
+            let fetched = runtime.block_on(registry::fetch_examples(
+                &set,
+                &dir,
+                *force,
+                *connections,
+                output_mode.is_structured().then_some(reporter.as_ref()),
+                registry::RetryPolicy::from_config(config),
+            ))?;
+            let manifest = dir.join(constants::examples::MANIFEST_NAME);
+
+            if output_mode.is_structured() {
+                emit_json_result(&output::ExamplesFetchedPayload {
+                    result_type: ResultType::ExamplesFetched,
+                    dir,
+                    manifest,
+                    examples: fetched,
+                });
+                return Ok(());
+            }
+            let downloaded = fetched.iter().filter(|e| e.downloaded).count();
+            println!(
+                "{downloaded} of {} example recordings downloaded to {}",
+                fetched.len(),
+                dir.display()
+            );
+            println!("Expected detections: {}", manifest.display());
+            println!("\nAnalyze them with: birda {}", dir.display());
+        }
+    }
+    Ok(())
+}
+
+fn handle_runs_command(action: &cli::RunsAction, output_mode: OutputMode) -> Result<()> {
+    let log_path = runs::log_path()?;
+    let mut records = runs::load(&log_path)?;
+
+    match action {
+        cli::RunsAction::List { limit } => {
+            records.reverse();
+            records.truncate(*limit);
+
+            if output_mode.is_structured() {
+                emit_json_result(&output::RunListPayload {
+                    result_type: ResultType::RunList,
+                    log_path,
+                    runs: records,
+                });
+                return Ok(());
+            }
+
+            if records.is_empty() {
+                println!("No runs recorded in {}", log_path.display());
+                return Ok(());
+            }
+            println!(
+                "{:<26} {:<20} {:>6} {:>8} {:>10}  OUTCOME",
+                "RUN", "STARTED", "FILES", "FAILED", "DURATION"
+            );
+            for run in &records {
+                println!(
+                    "{:<26} {:<20} {:>6} {:>8} {:>9.1}s  {}",
+                    run.run_id,
+                    run.started.format("%Y-%m-%d %H:%M:%S"),
+                    run.files_processed,
+                    run.files_failed,
+                    run.duration_secs,
+                    run.outcome
+                );
+            }
+        }
+        cli::RunsAction::Show { run_id } => {
+            let run = runs::find(&records, run_id)?;
+
+            if output_mode.is_structured() {
+                emit_json_result(&output::RunDetailsPayload {
+                    result_type: ResultType::RunDetails,
+                    run: run.clone(),
+                });
+                return Ok(());
+            }
+
+            println!("Run: {}", run.run_id);
+            println!("  Started: {}", run.started.to_rfc3339());
+            println!("  Duration: {:.1}s", run.duration_secs);
+            println!("  birda version: {}", run.birda_version);
+            println!("  Host: {}", run.hostname);
+            if let Some(station) = &run.station {
+                println!("  Station: {station}");
+            }
+            println!("  Command: {}", run.command_line.join(" "));
+            for input in &run.inputs {
+                println!("  Input: {}", input.display());
+            }
+            if let Some(settings) = &run.settings {
+                println!("  Model: {} ({})", settings.model, settings.model_type);
+                println!("  Min confidence: {}", settings.min_confidence);
+                println!("  Overlap: {}s", settings.overlap);
+                if let (Some(lat), Some(lon)) = (settings.latitude, settings.longitude) {
+                    println!("  Location: {lat}, {lon}");
+                }
+            }
+            println!(
+                "  Files: {} processed, {} skipped, {} failed",
+                run.files_processed, run.files_skipped, run.files_failed
+            );
+            println!("  Detections: {}", run.detections);
+            println!("  Outcome: {}", run.outcome);
+            if let Some(error) = &run.error {
+                println!("  Error: {error}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_cache_command(action: &cli::CacheAction, output_mode: OutputMode) -> Result<()> {
+    use crate::inference::tensorrt_cache::list_engines;
+
+    match action {
+        cli::CacheAction::List => {
+            let cache_dir = config::tensorrt_cache_dir()?;
+            let engines = list_engines(&cache_dir);
+
+            if output_mode.is_structured() {
+                let payload = CacheListPayload {
+                    result_type: ResultType::CacheList,
+                    cache_dir,
+                    engines,
+                };
+                emit_json_result(&payload);
+                return Ok(());
+            }
+
+            println!("TensorRT engine cache: {}", cache_dir.display());
+            println!();
+
+            if engines.is_empty() {
+                println!("No cached engines.");
+                return Ok(());
+            }
+
+            for engine in &engines {
+                let batch_sizes = if engine.batch_sizes.is_empty() {
+                    "unknown".to_string()
+                } else {
+                    engine
+                        .batch_sizes
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                #[allow(clippy::cast_precision_loss)]
+                let size_mb = engine.size_bytes as f64 / constants::BYTES_PER_MIB;
+                println!("  {}", engine.file);
+                println!(
+                    "    Model: {}",
+                    engine.model.as_deref().unwrap_or("unknown")
+                );
+                println!("    Batch sizes: {batch_sizes}");
+                println!("    Size: {size_mb:.1} MB");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Execution providers compiled into this build.
+fn provider_infos() -> Vec<ProviderInfo> {
+    use crate::inference::provider_metadata;
+    use birdnet_onnx::available_execution_providers;
+
+    available_execution_providers()
+        .iter()
+        .map(|provider| {
+            let meta = provider_metadata(*provider);
+            ProviderInfo {
+                id: meta.id.to_string(),
                 name: meta.name.to_string(),
                 description: meta.description.to_string(),
             }
         })
-        .collect();
+        .collect()
+}
+
+fn handle_providers_command(output_mode: OutputMode) {
+    let provider_infos = provider_infos();
 
     // JSON/NDJSON output
     if output_mode.is_structured() {
@@ -1057,7 +3003,11 @@ fn handle_update_command(check_only: bool, output_mode: OutputMode) -> Result<()
     }
 }
 
-fn handle_config_command(action: cli::ConfigAction, output_mode: OutputMode) -> Result<()> {
+fn handle_config_command(
+    action: cli::ConfigAction,
+    config: &Config,
+    output_mode: OutputMode,
+) -> Result<()> {
     use cli::ConfigAction;
 
     match action {
@@ -1077,30 +3027,54 @@ fn handle_config_command(action: cli::ConfigAction, output_mode: OutputMode) ->
             }
             Ok(())
         }
-        ConfigAction::Show => {
-            let config = load_default_config()?;
+        ConfigAction::Show { show_secrets } => {
             let config_path = config_file_path()?;
+            let mut config_json =
+                serde_json::to_value(config).map_err(|e| Error::ConfigValidation {
+                    message: format!("failed to serialize config to JSON: {e}"),
+                })?;
+            let redacted = if show_secrets {
+                Vec::new()
+            } else {
+                config::redact_secrets(&mut config_json)
+            };
 
             // JSON/NDJSON output
             if output_mode.is_structured() {
-                let config_json =
-                    serde_json::to_value(&config).map_err(|e| Error::ConfigValidation {
-                        message: format!("failed to serialize config to JSON: {e}"),
-                    })?;
                 let payload = ConfigPayload {
                     result_type: ResultType::Config,
                     config_path,
                     config: config_json,
+                    redacted,
                 };
                 emit_json_result(&payload);
                 return Ok(());
             }
 
             // Human-readable output
-            println!("{config:#?}");
+            if redacted.is_empty() {
+                println!("{config:#?}");
+            } else {
+                let config: Config =
+                    serde_json::from_value(config_json).map_err(|e| Error::ConfigValidation {
+                        message: format!("failed to redact config: {e}"),
+                    })?;
+                println!("{config:#?}");
+                println!();
+                println!(
+                    "Redacted {}; use --show-secrets to reveal.",
+                    redacted.join(", ")
+                );
+            }
             Ok(())
         }
-        ConfigAction::Set { key, value } => handle_config_set(&key, &value, output_mode),
+        ConfigAction::Set { key, value } => handle_config_set(&key, Some(&value), output_mode),
+        ConfigAction::Get { key, show_secrets } => {
+            handle_config_get(&key, show_secrets, config, output_mode)
+        }
+        ConfigAction::Unset { key } => handle_config_set(&key, None, output_mode),
+        ConfigAction::Validate => handle_config_validate(output_mode),
+        ConfigAction::Credentials { action } => handle_credentials_command(action, output_mode),
         ConfigAction::Path => {
             let path = config_file_path()?;
 
@@ -1122,132 +3096,222 @@ fn handle_config_command(action: cli::ConfigAction, output_mode: OutputMode) ->
     }
 }
 
-fn handle_config_set(key: &str, value: &str, output_mode: OutputMode) -> Result<()> {
-    let mut config = load_default_config()?;
+/// Set (or with `None`, remove) one config key and report the saved config.
+fn handle_config_set(key: &str, value: Option<&str>, output_mode: OutputMode) -> Result<()> {
     let config_path = config_file_path()?;
+    let config = match value {
+        Some(value) if !value.is_empty() => config::set_config_value(&config_path, key, value)?,
+        _ => config::unset_config_value(&config_path, key)?,
+    };
 
-    match key {
-        "defaults.model" => {
-            config.defaults.model = if value.is_empty() {
-                None
-            } else {
-                Some(value.to_string())
-            };
+    if output_mode.is_structured() {
+        let mut config_json =
+            serde_json::to_value(&config).map_err(|e| Error::ConfigValidation {
+                message: format!("failed to serialize config to JSON: {e}"),
+            })?;
+        let redacted = config::redact_secrets(&mut config_json);
+        let payload = ConfigPayload {
+            result_type: ResultType::Config,
+            config_path,
+            config: config_json,
+            redacted,
+        };
+        emit_json_result(&payload);
+    } else {
+        match value {
+            Some(value) if !value.is_empty() => println!("Set '{key}' = '{value}'"),
+            _ => println!("Unset '{key}'"),
         }
-        "defaults.min_confidence" => {
-            config.defaults.min_confidence = if value.is_empty() {
-                config::DefaultsConfig::default().min_confidence
+        println!("Configuration saved to: {}", config_path.display());
+    }
+
+    Ok(())
+}
+
+fn handle_config_get(
+    key: &str,
+    show_secrets: bool,
+    config: &Config,
+    output_mode: OutputMode,
+) -> Result<()> {
+    let mut value = config::get_config_value(config, key)
+        .map(|v| serde_json::to_value(&v))
+        .transpose()
+        .map_err(|e| Error::ConfigValidation {
+            message: format!("failed to serialize config value to JSON: {e}"),
+        })?;
+    let redacted = match value.as_mut() {
+        Some(value) if !show_secrets => config::redact_secrets_at(value, key),
+        _ => Vec::new(),
+    };
+
+    if output_mode.is_structured() {
+        let payload = ConfigValuePayload {
+            result_type: ResultType::ConfigValue,
+            config_path: config_file_path()?,
+            key: key.to_string(),
+            value,
+            redacted,
+        };
+        emit_json_result(&payload);
+        return Ok(());
+    }
+
+    let value = value
+        .map(toml::Value::try_from)
+        .transpose()
+        .map_err(|e| Error::ConfigSerialize { source: e })?;
+
+    // Strings print bare so scripts can use them directly
+    match value {
+        Some(toml::Value::String(text)) => println!("{text}"),
+        Some(toml::Value::Table(table)) => print!(
+            "{}",
+            toml::to_string_pretty(&table).map_err(|e| Error::ConfigSerialize { source: e })?
+        ),
+        Some(value) => println!("{value}"),
+        None => eprintln!("'{key}' is not set"),
+    }
+    Ok(())
+}
+
+/// Handle `config credentials`; credential values are never printed.
+fn handle_credentials_command(
+    action: cli::CredentialsAction,
+    output_mode: OutputMode,
+) -> Result<()> {
+    use cli::CredentialsAction;
+    use std::io::IsTerminal;
+
+    let path = config::credentials_file_path()?;
+    let mut credentials = config::Credentials::load(&path)?;
+    match &action {
+        CredentialsAction::List => {}
+        CredentialsAction::Set { key } => {
+            // Typed secrets are not echoed; piped ones are read as a line
+            let value = if std::io::stdin().is_terminal() {
+                rpassword::prompt_password(format!("Value for '{key}': "))?
             } else {
-                value.parse::<f32>().map_err(|_| Error::ConfigValidation {
-                    message: format!("invalid float value for '{key}': {value}"),
-                })?
+                let mut value = String::new();
+                std::io::stdin().read_line(&mut value)?;
+                value
             };
+            let value = value.trim_end_matches(['\r', '\n']);
+            if value.is_empty() {
+                return Err(Error::ConfigValidation {
+                    message: format!("no value given for credential '{key}'"),
+                });
+            }
+            credentials.set(key, Some(value))?;
+            credentials.save(&path)?;
         }
-        "defaults.overlap" => {
-            config.defaults.overlap = if value.is_empty() {
-                config::DefaultsConfig::default().overlap
-            } else {
-                value.parse::<f32>().map_err(|_| Error::ConfigValidation {
-                    message: format!("invalid float value for '{key}': {value}"),
-                })?
-            };
+        CredentialsAction::Unset { key } => {
+            credentials.set(key, None)?;
+            credentials.save(&path)?;
         }
-        "defaults.latitude" => {
-            config.defaults.latitude = if value.is_empty() {
-                None
-            } else {
-                Some(value.parse::<f64>().map_err(|_| Error::ConfigValidation {
-                    message: format!("invalid float value for '{key}': {value}"),
-                })?)
-            };
+    }
+
+    if output_mode.is_structured() {
+        emit_json_result(&output::CredentialListPayload {
+            result_type: ResultType::CredentialList,
+            credentials_path: path,
+            keys: credentials.keys(),
+        });
+        return Ok(());
+    }
+
+    match action {
+        CredentialsAction::List => {
+            println!("Credentials file: {}", path.display());
+            for key in credentials.keys() {
+                println!("  {key}");
+            }
         }
-        "defaults.longitude" => {
-            config.defaults.longitude = if value.is_empty() {
-                None
-            } else {
-                Some(value.parse::<f64>().map_err(|_| Error::ConfigValidation {
-                    message: format!("invalid float value for '{key}': {value}"),
-                })?)
-            };
+        CredentialsAction::Set { key } => {
+            println!("Stored credential '{key}' in {}", path.display());
         }
-        "defaults.batch_size" => {
-            config.defaults.batch_size = if value.is_empty() {
-                None
-            } else {
-                Some(
-                    value
-                        .parse::<usize>()
-                        .map_err(|_| Error::ConfigValidation {
-                            message: format!("invalid integer value for '{key}': {value}"),
-                        })?,
-                )
-            };
+        CredentialsAction::Unset { key } => {
+            println!("Removed credential '{key}' from {}", path.display());
         }
-        "defaults.range_threshold" => {
-            config.defaults.range_threshold = if value.is_empty() {
-                config::DefaultsConfig::default().range_threshold
-            } else {
-                value.parse::<f32>().map_err(|_| Error::ConfigValidation {
-                    message: format!("invalid float value for '{key}': {value}"),
-                })?
+    }
+    Ok(())
+}
+
+/// Check the config file and report every problem found.
+fn handle_config_validate(output_mode: OutputMode) -> Result<()> {
+    let config_path = config_file_path()?;
+    let diagnostics = config::check_config_file(&config_path)?;
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == config::DiagnosticSeverity::Error)
+        .count();
+
+    if output_mode.is_structured() {
+        emit_json_result(&output::ConfigValidationPayload {
+            result_type: ResultType::ConfigValidation,
+            config_path: config_path.clone(),
+            valid: errors == 0,
+            diagnostics,
+        });
+    } else {
+        println!("Checking {}", config_path.display());
+        for diagnostic in &diagnostics {
+            let severity = match diagnostic.severity {
+                config::DiagnosticSeverity::Error => "ERROR",
+                config::DiagnosticSeverity::Warning => "WARNING",
             };
+            match &diagnostic.key {
+                Some(key) => println!("  {severity:<8} {key}: {}", diagnostic.message),
+                None => println!("  {severity:<8} {}", diagnostic.message),
+            }
         }
-        _ => {
-            return Err(Error::InvalidConfigKey {
-                key: key.to_string(),
-            });
+        if errors == 0 {
+            println!("Configuration is valid");
         }
     }
 
-    config::validate_config(&config)?;
-    save_default_config(&config)?;
-
-    if output_mode.is_structured() {
-        let config_json = serde_json::to_value(&config).map_err(|e| Error::ConfigValidation {
-            message: format!("failed to serialize config to JSON: {e}"),
-        })?;
-        let payload = ConfigPayload {
-            result_type: ResultType::Config,
-            config_path,
-            config: config_json,
-        };
-        emit_json_result(&payload);
+    if errors == 0 {
+        Ok(())
     } else {
-        println!("Set '{key}' = '{value}'");
-        println!("Configuration saved to: {}", config_path.display());
+        Err(Error::ConfigInvalid {
+            path: config_path,
+            errors,
+        })
     }
+}
 
-    Ok(())
+/// Configured models, sorted by ID for deterministic output.
+fn model_entries(config: &config::Config) -> Vec<ModelEntry> {
+    let mut models: Vec<ModelEntry> = config
+        .models
+        .iter()
+        .map(|(name, model)| {
+            let is_default = config.defaults.model.as_ref().is_some_and(|d| d == name);
+            ModelEntry {
+                id: name.clone(),
+                model_type: model.model_type.to_string(),
+                is_default,
+                path: Some(model.path.clone()),
+                labels_path: Some(model.labels.clone()),
+                has_meta_model: model.meta_model.is_some(),
+            }
+        })
+        .collect();
+    models.sort_unstable_by(|a, b| a.id.cmp(&b.id));
+    models
 }
 
 fn handle_models_command(
     action: cli::ModelsAction,
     config: &config::Config,
     output_mode: OutputMode,
+    reporter: &Arc<dyn ProgressReporter>,
 ) -> Result<()> {
     use cli::ModelsAction;
 
     match action {
         ModelsAction::List => {
-            // Build model entries
-            let mut models: Vec<ModelEntry> = config
-                .models
-                .iter()
-                .map(|(name, model)| {
-                    let is_default = config.defaults.model.as_ref().is_some_and(|d| d == name);
-                    ModelEntry {
-                        id: name.clone(),
-                        model_type: model.model_type.to_string(),
-                        is_default,
-                        path: Some(model.path.clone()),
-                        labels_path: Some(model.labels.clone()),
-                        has_meta_model: model.meta_model.is_some(),
-                    }
-                })
-                .collect();
-
-            // Sort by ID for deterministic output
-            models.sort_unstable_by(|a, b| a.id.cmp(&b.id));
+            let models = model_entries(config);
 
             // JSON/NDJSON output
             if output_mode.is_structured() {
@@ -1276,7 +3340,7 @@ fn handle_models_command(
             Ok(())
         }
         ModelsAction::ListAvailable => {
-            let registry = registry::load_registry()?;
+            let registry = registry::load_configured_registry(config)?;
             registry::list_available(&registry, output_mode);
             Ok(())
         }
@@ -1286,22 +3350,55 @@ fn handle_models_command(
             labels,
             r#type,
             default,
-        } => handle_models_add(name, path, labels, r#type, default),
+            update,
+        } => handle_models_add(name, path, labels, r#type, default, update),
         ModelsAction::Check => {
+            let lockfile = registry::Lockfile::load(&registry::Lockfile::path()?)?;
+
+            // Validate a configured model, including its pinned files
+            let check = |name: &str, model: &config::ModelConfig| -> Result<()> {
+                config::validate_model_config(name, model)?;
+                if let Some(locked) = lockfile.models.get(name) {
+                    let dir = model.path.parent().unwrap_or_else(|| Path::new("."));
+                    locked.verify(name, dir)?;
+                }
+                Ok(())
+            };
+            // Locked models that are no longer configured
+            let missing: Vec<&String> = lockfile
+                .models
+                .keys()
+                .filter(|id| !config.models.contains_key(*id))
+                .collect();
+
             // JSON/NDJSON output — collect all results then emit
             if output_mode.is_structured() {
-                let models: Vec<ModelCheckEntry> = config
+                let mut models: Vec<ModelCheckEntry> = config
                     .models
                     .iter()
                     .map(|(name, model)| {
-                        let result = config::validate_model_config(name, model);
+                        let result = check(name, model);
                         ModelCheckEntry {
                             id: name.clone(),
                             valid: result.is_ok(),
                             error: result.err().map(|e| e.to_string()),
+                            locked_version: lockfile.models.get(name).map(|l| l.version.clone()),
                         }
                     })
                     .collect();
+                models.extend(missing.iter().map(|id| {
+                    ModelCheckEntry {
+                        id: (*id).clone(),
+                        valid: false,
+                        error: Some(
+                            Error::ModelNotFound {
+                                name: (*id).clone(),
+                            }
+                            .to_string(),
+                        ),
+                        locked_version: Some(lockfile.models[*id].version.clone()),
+                    }
+                }));
                 let payload = ModelCheckPayload {
                     result_type: ResultType::ModelCheck,
                     models,
@@ -1312,75 +3409,1018 @@ fn handle_models_command(
 
             // Human-readable output (fail on first invalid model)
             for (name, model) in &config.models {
-                config::validate_model_config(name, model)?;
-                println!("  {name}: OK");
+                check(name, model)?;
+                match lockfile.models.get(name) {
+                    Some(locked) => println!("  {name}: OK (locked at {})", locked.version),
+                    None => println!("  {name}: OK"),
+                }
+            }
+            if let Some(id) = missing.first() {
+                return Err(Error::ModelNotFound {
+                    name: (*id).clone(),
+                });
             }
             Ok(())
         }
-        ModelsAction::Info { id, languages } => {
-            // Try registry first
-            let registry = registry::load_registry()?;
-            if let Some(reg_model) = registry::find_model(&registry, &id) {
-                // JSON/NDJSON output for registry model
-                if output_mode.is_structured() {
-                    // Note: --languages flag doesn't apply to JSON output - we include all info
-                    let payload = ModelInfoPayload {
-                        result_type: ResultType::ModelInfo,
-                        model: ModelDetails {
-                            id: reg_model.id.clone(),
-                            model_type: reg_model.model_type.clone(),
-                            path: None,
-                            labels_path: None,
-                            meta_model_path: None,
-                            source: "registry".to_string(),
-                        },
-                    };
-                    emit_json_result(&payload);
-                    return Ok(());
-                }
+        ModelsAction::Info {
+            id,
+            languages,
+            footprint,
+        } => {
+            // Try registry first; measuring needs the configured model
+            let registry = registry::load_configured_registry(config)?;
+            let reg_model = if footprint {
+                None
+            } else {
+                registry::find_model(&registry, &id)
+            };
+            if let Some(reg_model) = reg_model {
+                // JSON/NDJSON output for registry model
+                if output_mode.is_structured() {
+                    // Note: --languages flag doesn't apply to JSON output - we include all info
+                    let payload = ModelInfoPayload {
+                        result_type: ResultType::ModelInfo,
+                        model: ModelDetails {
+                            id: reg_model.id.clone(),
+                            model_type: reg_model.model_type.clone(),
+                            path: None,
+                            labels_path: None,
+                            meta_model_path: None,
+                            source: "registry".to_string(),
+                            footprint: None,
+                        },
+                    };
+                    emit_json_result(&payload);
+                    return Ok(());
+                }
+
+                // Human-readable output
+                if languages {
+                    registry::show_languages(&registry, &id)?;
+                } else {
+                    registry::show_info(&registry, &id)?;
+                }
+            } else {
+                // Fall back to configured model
+                let model = config::get_model(config, &id)?;
+                let footprint = if footprint {
+                    Some(measure_model_footprint(model, config.inference.device)?)
+                } else {
+                    None
+                };
+
+                // JSON/NDJSON output
+                if output_mode.is_structured() {
+                    let payload = ModelInfoPayload {
+                        result_type: ResultType::ModelInfo,
+                        model: ModelDetails {
+                            id: id.clone(),
+                            model_type: model.model_type.to_string(),
+                            path: Some(model.path.clone()),
+                            labels_path: Some(model.labels.clone()),
+                            meta_model_path: model.meta_model.clone(),
+                            source: "configured".to_string(),
+                            footprint,
+                        },
+                    };
+                    emit_json_result(&payload);
+                    return Ok(());
+                }
+
+                // Human-readable output
+                println!("Model: {id}");
+                println!("  Type: {}", model.model_type);
+                println!("  Path: {}", model.path.display());
+                println!("  Labels: {}", model.labels.display());
+                if let Some(footprint) = footprint {
+                    print_model_footprint(&footprint);
+                }
+            }
+            Ok(())
+        }
+        ModelsAction::Verify { id } => handle_models_verify(&id, config, output_mode),
+        ModelsAction::Test { id, top } => handle_models_test(&id, top, config, output_mode),
+        ModelsAction::Remove { name, purge } => handle_models_remove(&name, purge, output_mode),
+        ModelsAction::Relocate { id, new_dir } => {
+            handle_models_relocate(&id, &new_dir, output_mode)
+        }
+        ModelsAction::Install {
+            id,
+            language,
+            default,
+            dest,
+            upgrade,
+            connections,
+            retries,
+            retry_delay,
+        } => {
+            let defaults = registry::RetryPolicy::from_config(config);
+            let retry = registry::RetryPolicy {
+                retries: retries.unwrap_or(defaults.retries),
+                backoff: retry_delay.map_or(defaults.backoff, std::time::Duration::from_secs),
+            };
+            handle_models_install(
+                config,
+                &id,
+                language.as_deref(),
+                default,
+                dest.as_deref(),
+                upgrade,
+                connections,
+                retry,
+                output_mode,
+                reporter,
+            )
+        }
+        ModelsAction::Update {
+            id,
+            yes,
+            check,
+            connections,
+        } => handle_models_update(
+            id.as_deref(),
+            yes,
+            check,
+            connections,
+            config,
+            output_mode,
+            reporter,
+        ),
+    }
+}
+
+/// Handle `models update`: reinstall models whose registry version is newer
+/// than the version pinned in the lockfile.
+///
+/// Models are reinstalled into their current directory with their current
+/// labels language.
+fn handle_models_update(
+    id: Option<&str>,
+    yes: bool,
+    check: bool,
+    connections: usize,
+    config: &Config,
+    output_mode: OutputMode,
+    reporter: &Arc<dyn ProgressReporter>,
+) -> Result<()> {
+    use std::io::{IsTerminal, Write};
+
+    let registry = registry::load_configured_registry(config)?;
+    let lockfile = registry::Lockfile::load(&registry::Lockfile::path()?)?;
+    if let Some(id) = id
+        && !lockfile.models.contains_key(id)
+        && !config.models.contains_key(id)
+    {
+        return Err(Error::ModelNotFound {
+            name: id.to_string(),
+        });
+    }
+    let outdated: Vec<(&str, &registry::ModelEntry)> = lockfile
+        .outdated(&registry)
+        .into_iter()
+        .filter(|(_, model)| id.is_none_or(|id| model.id == id))
+        .collect();
+    // Installed before the lockfile existed, or by hand: the version is
+    // unknown, so they are neither up to date nor outdated
+    let untracked: Vec<&str> = config
+        .models
+        .keys()
+        .map(String::as_str)
+        .filter(|name| id.is_none_or(|id| *name == id))
+        .filter(|name| {
+            !lockfile.models.contains_key(*name) && registry::find_model(&registry, name).is_some()
+        })
+        .collect();
+
+    if !output_mode.is_structured() {
+        if !untracked.is_empty() {
+            println!(
+                "Version unknown (not in the lockfile): {}. Reinstall with `birda models install <id> --upgrade` to track them.",
+                untracked.join(", ")
+            );
+        }
+        if outdated.is_empty() {
+            if untracked.is_empty() {
+                println!("All installed models are up to date.");
+            } else {
+                println!("All tracked models are up to date.");
+            }
+        } else {
+            println!("Outdated models:");
+            for (installed, model) in &outdated {
+                println!("  {}: {installed} -> {}", model.id, model.version);
+            }
+        }
+    }
+
+    let interactive = std::io::stdin().is_terminal() && !output_mode.is_structured();
+    let confirmed = if check || outdated.is_empty() {
+        false
+    } else if yes {
+        true
+    } else if interactive {
+        print!("Update {} model(s)? [y/N]: ", outdated.len());
+        std::io::stdout().flush()?;
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        input.trim().eq_ignore_ascii_case("y")
+    } else {
+        if !output_mode.is_structured() {
+            println!("Run with --yes to update them.");
+        }
+        false
+    };
+
+    if confirmed {
+        let retry = registry::RetryPolicy::from_config(config);
+        for (_, model) in &outdated {
+            let configured = config.models.get(&model.id);
+            let dest = configured.and_then(|m| m.path.parent());
+            let language = configured.and_then(|m| {
+                model
+                    .files
+                    .labels
+                    .languages
+                    .iter()
+                    .find(|l| m.labels.file_name() == Some(std::ffi::OsStr::new(&l.filename)))
+                    .map(|l| l.code.as_str())
+            });
+            handle_models_install(
+                config,
+                &model.id,
+                language,
+                false,
+                dest,
+                true,
+                connections,
+                retry,
+                output_mode,
+                reporter,
+            )?;
+        }
+    }
+
+    if output_mode.is_structured() {
+        emit_json_result(&output::ModelUpdatePayload {
+            result_type: ResultType::ModelUpdate,
+            models: outdated
+                .iter()
+                .map(|(installed, model)| output::ModelUpdateEntry {
+                    id: model.id.clone(),
+                    installed_version: (*installed).to_string(),
+                    available_version: model.version.clone(),
+                    updated: confirmed,
+                })
+                .collect(),
+            untracked: untracked.iter().map(ToString::to_string).collect(),
+        });
+    }
+    Ok(())
+}
+
+/// Handle `models verify`: re-hash the installed files of a registry model.
+///
+/// Files are looked up next to the configured model of the same name, or in
+/// the configured models directory if the model is not configured.
+fn handle_models_verify(id: &str, config: &Config, output_mode: OutputMode) -> Result<()> {
+    let registry = registry::load_configured_registry(config)?;
+    let model = registry::find_model(&registry, id)
+        .ok_or_else(|| Error::ModelNotFoundInRegistry { id: id.to_string() })?;
+    let lockfile = registry::Lockfile::load(&registry::Lockfile::path()?)?;
+
+    let dir = match config.models.get(id) {
+        Some(configured) => configured
+            .path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .to_path_buf(),
+        None => registry::configured_models_dir(config)?,
+    };
+    let files = registry::verify_installed(model, &dir, lockfile.models.get(id))?;
+
+    // Missing label languages are fine as long as one labels file exists
+    let failed: Vec<&str> = files
+        .iter()
+        .filter(|f| {
+            f.status == output::FileCheckStatus::Mismatch
+                || (f.status == output::FileCheckStatus::Missing
+                    && !model
+                        .files
+                        .labels
+                        .languages
+                        .iter()
+                        .any(|l| l.filename == f.file))
+        })
+        .map(|f| f.file.as_str())
+        .collect();
+
+    if output_mode.is_structured() {
+        emit_json_result(&output::ModelVerifyPayload {
+            result_type: ResultType::ModelVerify,
+            id: id.to_string(),
+            dir: dir.clone(),
+            passed: failed.is_empty(),
+            files: files.clone(),
+        });
+    } else {
+        println!("Verifying {id} in {}", dir.display());
+        for file in &files {
+            let status = match file.status {
+                output::FileCheckStatus::Ok => "OK",
+                output::FileCheckStatus::Mismatch => "MISMATCH",
+                output::FileCheckStatus::Missing => "MISSING",
+                output::FileCheckStatus::Unverified => "NO CHECKSUM",
+            };
+            println!("  {status:<12} {}", file.file);
+        }
+    }
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::ModelVerifyFailed {
+            id: id.to_string(),
+            files: failed.join(", "),
+        })
+    }
+}
+
+/// Load a configured model and measure what loading it costs.
+fn measure_model_footprint(
+    model: &ModelConfig,
+    device: InferenceDevice,
+) -> Result<output::ModelFootprint> {
+    inference::ensure_runtime_available()?;
+    validate_model_files(model)?;
+    let file_size_bytes = std::fs::metadata(&model.path)?.len();
+
+    let memory_before = utils::memory::resident_memory_bytes();
+    let start = std::time::Instant::now();
+    let classifier = BirdClassifier::from_config(
+        model,
+        device,
+        constants::DEFAULT_MIN_CONFIDENCE,
+        DEFAULT_TOP_K,
+        None,
+        None,
+        None,
+    )?;
+    #[allow(clippy::cast_possible_truncation)]
+    let load_time_ms = start.elapsed().as_millis() as u64;
+    let memory_after = utils::memory::resident_memory_bytes();
+
+    Ok(output::ModelFootprint {
+        file_size_bytes,
+        device: classifier.execution_provider_status().actual.to_string(),
+        load_time_ms,
+        resident_memory_bytes: memory_before
+            .zip(memory_after)
+            .map(|(before, after)| after.saturating_sub(before)),
+        label_count: classifier.label_count(),
+        batch_context: classifier.create_batch_context(1).is_ok(),
+    })
+}
+
+/// Print a measured model footprint.
+#[allow(clippy::cast_precision_loss)]
+fn print_model_footprint(footprint: &output::ModelFootprint) {
+    println!("  Footprint ({}):", footprint.device);
+    println!(
+        "    File size: {:.1} MiB",
+        footprint.file_size_bytes as f64 / constants::BYTES_PER_MIB
+    );
+    println!("    Load time: {} ms", footprint.load_time_ms);
+    match footprint.resident_memory_bytes {
+        Some(bytes) => println!(
+            "    Resident memory: +{:.1} MiB",
+            bytes as f64 / constants::BYTES_PER_MIB
+        ),
+        None => println!("    Resident memory: not measurable on this platform"),
+    }
+    println!("    Labels: {}", footprint.label_count);
+    println!(
+        "    Batch context: {}",
+        if footprint.batch_context {
+            "supported"
+        } else {
+            "not supported"
+        }
+    );
+}
+
+/// Handle the `selftest` command.
+///
+/// The ONNX Runtime check runs as a stage, so a missing runtime is reported
+/// alongside the stages that still work.
+fn handle_selftest_command(
+    model: Option<&str>,
+    config: &Config,
+    output_mode: OutputMode,
+) -> Result<()> {
+    let model_name = model.or(config.defaults.model.as_deref());
+    let model = model_name
+        .map(|name| config::get_model(config, name).map(|m| (name, m)))
+        .transpose()?;
+
+    let work_dir = std::env::temp_dir().join(format!("birda-selftest-{}", std::process::id()));
+    std::fs::create_dir_all(&work_dir)?;
+    let stages = pipeline::run_selftest(model, config.inference.device, &work_dir);
+    if let Err(e) = std::fs::remove_dir_all(&work_dir) {
+        warn!("Failed to remove {}: {e}", work_dir.display());
+    }
+
+    let failed: Vec<&str> = stages
+        .iter()
+        .filter(|s| s.status == output::StageStatus::Failed)
+        .map(|s| s.name.as_str())
+        .collect();
+
+    if output_mode.is_structured() {
+        emit_json_result(&output::SelftestPayload {
+            result_type: ResultType::Selftest,
+            passed: failed.is_empty(),
+            stages: stages.clone(),
+        });
+    } else {
+        for stage in &stages {
+            let status = match stage.status {
+                output::StageStatus::Passed => "PASS",
+                output::StageStatus::Failed => "FAIL",
+                output::StageStatus::Skipped => "SKIP",
+            };
+            println!(
+                "{status}  {:<20} {}",
+                stage.name,
+                stage.message.as_deref().unwrap_or_default()
+            );
+        }
+    }
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::SelftestFailed {
+            stages: failed.join(", "),
+        })
+    }
+}
+
+/// Handle the `listen` command: classify live audio from an input device.
+fn handle_listen_command(
+    args: &cli::ListenArgs,
+    config: &Config,
+    output_mode: OutputMode,
+    reporter: &Arc<dyn ProgressReporter>,
+    gpu_memory: Option<inference::GpuMemoryOptions>,
+) -> Result<()> {
+    if args.list_devices {
+        let devices = pipeline::input_devices()?;
+        if output_mode.is_structured() {
+            emit_json_result(&output::InputDeviceListPayload {
+                result_type: ResultType::InputDeviceList,
+                devices,
+            });
+        } else if devices.is_empty() {
+            println!("No input devices found.");
+        } else {
+            for device in devices {
+                println!("{device}");
+            }
+        }
+        return Ok(());
+    }
+
+    // Buffered JSON would only be written once listening stops
+    if output_mode == OutputMode::Json {
+        return Err(Error::ConfigValidation {
+            message: "listen streams detections; use --output-mode ndjson".to_string(),
+        });
+    }
+
+    let (_, model) = resolve_command_model(args.model.as_ref(), config)?;
+
+    let min_confidence = args
+        .min_confidence
+        .unwrap_or(config.defaults.min_confidence);
+    let classifier = BirdClassifier::from_config(
+        model,
+        config.inference.device,
+        min_confidence,
+        DEFAULT_TOP_K,
+        None,
+        None,
+        gpu_memory,
+    )?;
+    let overlap = resolve_overlap(args.overlap, config, &classifier)?;
+
+    let structured = output_mode.is_structured();
+    pipeline::listen(
+        &classifier,
+        args.device.as_deref(),
+        overlap,
+        min_confidence,
+        |device, sample_rate| {
+            if structured {
+                info!("Listening on '{device}' at {sample_rate} Hz");
+            } else {
+                println!("Listening on '{device}' at {sample_rate} Hz (Ctrl+C to stop)");
+            }
+        },
+        |detections| report_live_detections(detections, structured, reporter.as_ref()),
+    )
+}
+
+/// Emit detections from live analysis as they happen: as detection events in
+/// structured output, otherwise as one line per detection with the local time.
+fn report_live_detections(
+    detections: &[output::Detection],
+    structured: bool,
+    reporter: &dyn ProgressReporter,
+) {
+    if structured {
+        if let Some(first) = detections.first() {
+            reporter.detections(&first.file_path, detections, None);
+        }
+    } else {
+        let now = chrono::Local::now().format("%H:%M:%S");
+        for d in detections {
+            println!(
+                "{now}  {} ({}) {:.1}%",
+                d.common_name,
+                d.scientific_name,
+                d.confidence * 100.0
+            );
+        }
+    }
+}
+
+/// Analyze a network audio stream continuously, reporting detections as they
+/// are found.
+fn analyze_stream_input(
+    url: &str,
+    args: &AnalyzeArgs,
+    config: &Config,
+    output_mode: OutputMode,
+    reporter: &Arc<dyn ProgressReporter>,
+    gpu_memory: Option<inference::GpuMemoryOptions>,
+) -> Result<()> {
+    // Buffered JSON would only be written once the stream ends
+    if output_mode == OutputMode::Json {
+        return Err(Error::ConfigValidation {
+            message: "stream inputs report detections as they happen; use --output-mode ndjson"
+                .to_string(),
+        });
+    }
+    if args.bat.is_some() || args.stitch || args.from.is_some() || args.to.is_some() {
+        return Err(Error::ConfigValidation {
+            message: "--bat, --stitch, --from and --to are not supported for stream inputs"
+                .to_string(),
+        });
+    }
+
+    let (model_config, model_name) = resolve_model_config(args, config)?;
+    validate_model_files(&model_config)?;
+    let min_confidence = args
+        .min_confidence
+        .unwrap_or(config.defaults.min_confidence);
+    // Streams have no recording metadata, so only coordinates for the run
+    // enable the range filter
+    let range_filter_config = build_range_filter_config(args, config, &model_config, &model_name)?;
+    let species_list = resolve_species_filter(args, config, range_filter_config.is_some())?;
+
+    info!("Loading model: {}", model_name);
+    let classifier = BirdClassifier::from_config(
+        &model_config,
+        resolve_device(args, config),
+        min_confidence,
+        DEFAULT_TOP_K,
+        range_filter_config,
+        species_list,
+        gpu_memory,
+    )?;
+    let overlap = resolve_overlap(args.overlap, config, &classifier)?;
+
+    let structured = output_mode.is_structured();
+    if !structured {
+        println!("Analyzing {url} (Ctrl+C to stop)");
+    }
+    pipeline::analyze_stream(&classifier, url, overlap, min_confidence, |detections| {
+        report_live_detections(detections, structured, reporter.as_ref());
+    })
+}
+
+/// Handle the `calibrate` command: compare overlaps on a sample of the inputs.
+fn handle_calibrate_command(
+    args: &cli::CalibrateArgs,
+    config: &Config,
+    output_mode: OutputMode,
+    gpu_memory: Option<inference::GpuMemoryOptions>,
+) -> Result<()> {
+    let (_, model) = resolve_command_model(args.model.as_ref(), config)?;
+
+    let files = pipeline::collect_input_files(&args.inputs, &[])?;
+    if files.is_empty() {
+        return Err(Error::NoValidAudioFiles);
+    }
+    let files = pipeline::sample_files(&files, args.sample as usize);
+
+    let min_confidence = args
+        .min_confidence
+        .unwrap_or(config.defaults.min_confidence);
+    let batch_size = args
+        .batch_size
+        .or(config
+            .defaults
+            .batch_size
+            .and_then(config::BatchSize::fixed))
+        .unwrap_or(constants::DEFAULT_BATCH_SIZE);
+    let classifier = BirdClassifier::from_config(
+        model,
+        config.inference.device,
+        min_confidence,
+        DEFAULT_TOP_K,
+        None,
+        None,
+        gpu_memory,
+    )?;
+    for &overlap in &args.overlaps {
+        validate_overlap(overlap, classifier.segment_duration())?;
+    }
+
+    info!(
+        "Calibrating overlaps {:?} on {} recording(s)",
+        args.overlaps,
+        files.len()
+    );
+    let (events, overlaps) = pipeline::calibrate_overlaps(
+        &classifier,
+        &files,
+        &args.overlaps,
+        min_confidence,
+        batch_size,
+    )?;
+
+    if output_mode.is_structured() {
+        emit_json_result(&output::OverlapCalibrationPayload {
+            result_type: ResultType::OverlapCalibration,
+            files,
+            events,
+            overlaps,
+        });
+        return Ok(());
+    }
+
+    println!(
+        "{} recording(s), {events} detection event(s) found at any overlap\n",
+        files.len()
+    );
+    println!(
+        "{:>8}  {:>9}  {:>10}  {:>6}  {:>7}  {:>8}  {:>9}  {:>6}",
+        "Overlap", "Segments", "Detections", "Events", "Recall", "Change", "Runtime", "Cost"
+    );
+    let percent = |value: Option<f64>, signed: bool| {
+        value.map_or_else(
+            || "-".to_string(),
+            |v| {
+                if signed {
+                    format!("{:+.1}%", v * 100.0)
+                } else {
+                    format!("{:.1}%", v * 100.0)
+                }
+            },
+        )
+    };
+    for row in &overlaps {
+        println!(
+            "{:>7.1}s  {:>9}  {:>10}  {:>6}  {:>7}  {:>8}  {:>8.1}s  {:>6}",
+            row.overlap,
+            row.segments,
+            row.detections,
+            row.events_found,
+            percent(row.recall, false),
+            percent(row.recall_delta, true),
+            row.runtime_secs,
+            row.relative_runtime
+                .map_or_else(|| "-".to_string(), |r| format!("{r:.2}x")),
+        );
+    }
+    Ok(())
+}
+
+/// Handle the `bench` command: measure inference throughput.
+///
+/// With `--baseline`, a drop in throughput beyond the tolerance is an error,
+/// so scripts and release checks fail on a performance regression.
+fn handle_bench_command(
+    args: &cli::BenchArgs,
+    config: &Config,
+    output_mode: OutputMode,
+    gpu_memory: Option<inference::GpuMemoryOptions>,
+) -> Result<()> {
+    let (model_name, model) = resolve_command_model(args.model.as_ref(), config)?;
+    // Read the baseline first, so a bad path fails before the benchmark runs
+    let baseline = args
+        .baseline
+        .as_deref()
+        .map(pipeline::load_baseline)
+        .transpose()?;
+
+    let batch_size = args
+        .batch_size
+        .or(config
+            .defaults
+            .batch_size
+            .and_then(config::BatchSize::fixed))
+        .unwrap_or(constants::DEFAULT_BATCH_SIZE);
+    let classifier = BirdClassifier::from_config(
+        model,
+        config.inference.device,
+        0.0,
+        1,
+        None,
+        None,
+        gpu_memory,
+    )?;
+
+    info!(
+        "Benchmarking {model_name}: {} segment(s) in batches of {batch_size}",
+        args.segments
+    );
+    let result = pipeline::run_bench(&classifier, model_name, batch_size, args.segments)?;
+    let comparison = baseline
+        .as_ref()
+        .zip(args.baseline.as_deref())
+        .map(|(baseline, path)| {
+            pipeline::compare_to_baseline(&result, baseline, path, args.tolerance)
+        });
+    if let Some(path) = &args.save_baseline {
+        pipeline::save_baseline(path, &result)?;
+        info!("Saved baseline to {}", path.display());
+    }
+
+    if output_mode.is_structured() {
+        emit_json_result(&output::BenchPayload {
+            result_type: ResultType::Bench,
+            result: result.clone(),
+            baseline: comparison.clone(),
+        });
+    } else {
+        println!("Model: {}", result.model);
+        println!("  Device: {}", result.device);
+        println!("  Batch size: {}", result.batch_size);
+        println!(
+            "  Throughput: {:.1} segments/s ({} segments in {:.2} s)",
+            result.segments_per_sec, result.segments, result.elapsed_secs
+        );
+        if let Some(comparison) = &comparison {
+            println!(
+                "  Baseline: {:.1} segments/s ({:+.1}%, tolerance {}%)",
+                comparison.segments_per_sec,
+                comparison.change_percent,
+                comparison.tolerance_percent
+            );
+        }
+    }
+
+    match comparison {
+        Some(comparison) if comparison.regressed => Err(Error::ThroughputRegression {
+            measured: result.segments_per_sec,
+            baseline: comparison.segments_per_sec,
+            drop: -comparison.change_percent,
+            tolerance: comparison.tolerance_percent,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Handle the `serve` command: answer API requests until stopped.
+fn handle_serve_command(
+    args: &cli::ServeArgs,
+    config: &Config,
+    gpu_memory: Option<inference::GpuMemoryOptions>,
+) -> Result<()> {
+    let (model_name, model) = resolve_command_model(args.model.as_ref(), config)?;
+
+    let min_confidence = args
+        .min_confidence
+        .unwrap_or(config.defaults.min_confidence);
+    let classifier = BirdClassifier::from_config(
+        model,
+        config.inference.device,
+        min_confidence,
+        DEFAULT_TOP_K,
+        None,
+        None,
+        gpu_memory,
+    )?;
+    let overlap = resolve_overlap(args.overlap, config, &classifier)?;
+
+    // Warm up now, so the first request does not pay for engine builds
+    let batch_size = args
+        .batch_size
+        .or(config
+            .defaults
+            .batch_size
+            .and_then(config::BatchSize::fixed))
+        .unwrap_or_else(|| {
+            determine_default_batch_size(model.model_type, classifier.execution_provider_status())
+        });
+    let batch_size = warmup_classifier(
+        &classifier,
+        batch_size,
+        model_name,
+        &config.inference.tensorrt_warmup_batch_sizes,
+    )?;
+
+    let allowed_root = args
+        .allow_path
+        .as_ref()
+        .map(|dir| {
+            dir.canonicalize().map_err(|source| Error::ServeRoot {
+                path: dir.clone(),
+                source,
+            })
+        })
+        .transpose()?;
+    let listener = std::net::TcpListener::bind(args.bind).map_err(|source| Error::ServerBind {
+        address: args.bind,
+        source,
+    })?;
+    let address = listener.local_addr()?;
+    let http = tiny_http::Server::from_listener(listener, None).map_err(|e| Error::ServerBind {
+        address: args.bind,
+        source: std::io::Error::other(e),
+    })?;
+    let server = server::Server::new(
+        classifier,
+        server::ServerSettings {
+            model: model_name.clone(),
+            min_confidence,
+            overlap,
+            batch_size,
+            max_upload_bytes: constants::serve::MAX_UPLOAD_BYTES,
+            allowed_root,
+            station: output::Station::from_config(&config.station),
+        },
+        output::ModelListPayload {
+            result_type: ResultType::ModelList,
+            models: model_entries(config),
+        },
+        ProvidersPayload {
+            result_type: ResultType::Providers,
+            providers: provider_infos(),
+        },
+    )?;
+    info!("Listening on http://{address}");
+    server.run(&http);
+    Ok(())
+}
+
+/// Handle the `embed` command: write per-segment embeddings for each input.
+fn handle_embed_command(
+    args: &cli::EmbedArgs,
+    config: &Config,
+    output_mode: OutputMode,
+    gpu_memory: Option<inference::GpuMemoryOptions>,
+) -> Result<()> {
+    let (model_name, model) = resolve_command_model(args.model.as_ref(), config)?;
+
+    let files = pipeline::collect_input_files(&args.inputs, &[])?;
+    if files.is_empty() {
+        return Err(Error::NoValidAudioFiles);
+    }
+
+    let batch_size = args
+        .batch_size
+        .or(config
+            .defaults
+            .batch_size
+            .and_then(config::BatchSize::fixed))
+        .unwrap_or(constants::DEFAULT_BATCH_SIZE);
+    let classifier = BirdClassifier::from_config(
+        model,
+        config.inference.device,
+        constants::DEFAULT_MIN_CONFIDENCE,
+        DEFAULT_TOP_K,
+        None,
+        None,
+        gpu_memory,
+    )?;
+    let overlap = resolve_overlap(args.overlap, config, &classifier)?;
+
+    let mut entries = Vec::with_capacity(files.len());
+    for file in &files {
+        info!("Extracting embeddings from {}", file.display());
+        let output_dir = pipeline::output_dir_for(file, args.output_dir.as_deref());
+        std::fs::create_dir_all(&output_dir)?;
+        let path = pipeline::embeddings_path_for(file, &output_dir, args.format)?;
+        let mut writer = output::EmbeddingWriter::new(&path, args.format, file);
+        let written =
+            pipeline::extract_embeddings(&classifier, file, overlap, batch_size, |batch| {
+                writer.write(batch)
+            });
+        let (segments, dimensions) = (writer.rows(), writer.dimensions());
+        if let Err(e) = written.and_then(|()| writer.finish()) {
+            // Don't leave a truncated file that looks complete
+            let _ = std::fs::remove_file(&path);
+            return Err(e);
+        }
+
+        let entry = output::EmbeddingFileEntry {
+            input: file.clone(),
+            output: path,
+            segments,
+            dimensions,
+        };
+        if !output_mode.is_structured() {
+            println!(
+                "{} ({} segments, {} dimensions)",
+                entry.output.display(),
+                entry.segments,
+                entry.dimensions
+            );
+        }
+        entries.push(entry);
+    }
 
-                // Human-readable output
-                if languages {
-                    registry::show_languages(&registry, &id)?;
-                } else {
-                    registry::show_info(&registry, &id)?;
-                }
-            } else {
-                // Fall back to configured model
-                let model = config::get_model(config, &id)?;
+    if output_mode.is_structured() {
+        emit_json_result(&output::EmbeddingsPayload {
+            result_type: ResultType::Embeddings,
+            model: model_name.clone(),
+            files: entries,
+        });
+    }
+    Ok(())
+}
 
-                // JSON/NDJSON output
-                if output_mode.is_structured() {
-                    let payload = ModelInfoPayload {
-                        result_type: ResultType::ModelInfo,
-                        model: ModelDetails {
-                            id: id.clone(),
-                            model_type: model.model_type.to_string(),
-                            path: Some(model.path.clone()),
-                            labels_path: Some(model.labels.clone()),
-                            meta_model_path: model.meta_model.clone(),
-                            source: "configured".to_string(),
-                        },
-                    };
-                    emit_json_result(&payload);
-                    return Ok(());
-                }
+/// Handle the `models test` command: classify a synthetic call end to end.
+fn handle_models_test(
+    id: &str,
+    top: usize,
+    config: &Config,
+    output_mode: OutputMode,
+) -> Result<()> {
+    inference::ensure_runtime_available()?;
+    let model = config::get_model(config, id)?;
+    validate_model_files(model)?;
+
+    // No threshold, so the top predictions are shown however weak they are
+    let start = std::time::Instant::now();
+    let classifier =
+        BirdClassifier::from_config(model, config.inference.device, 0.0, top, None, None, None)?;
+    #[allow(clippy::cast_possible_truncation)]
+    let load_time_ms = start.elapsed().as_millis() as u64;
 
-                // Human-readable output
-                println!("Model: {id}");
-                println!("  Type: {}", model.model_type);
-                println!("  Path: {}", model.path.display());
-                println!("  Labels: {}", model.labels.display());
+    let signal = audio::test_signal(classifier.sample_rate(), classifier.sample_count());
+    let start = std::time::Instant::now();
+    let result = classifier.predict(&signal, &birdnet_onnx::InferenceOptions::default())?;
+    #[allow(clippy::cast_possible_truncation)]
+    let inference_time_ms = start.elapsed().as_millis() as u64;
+
+    let predictions: Vec<output::TestPrediction> = result
+        .predictions
+        .iter()
+        .take(top)
+        .map(|p| {
+            let detection =
+                output::Detection::from_label(&p.species, p.confidence, 0.0, 0.0, PathBuf::new());
+            output::TestPrediction {
+                scientific_name: detection.scientific_name,
+                common_name: detection.common_name,
+                confidence: detection.confidence,
             }
-            Ok(())
-        }
-        ModelsAction::Remove { name, purge } => handle_models_remove(&name, purge, output_mode),
-        ModelsAction::Install {
-            id,
-            language,
-            default,
-        } => handle_models_install(&id, language.as_deref(), default, output_mode),
+        })
+        .collect();
+    let device = classifier.execution_provider_status().actual.to_string();
+
+    if output_mode.is_structured() {
+        emit_json_result(&output::ModelTestPayload {
+            result_type: ResultType::ModelTest,
+            model: id.to_string(),
+            device,
+            load_time_ms,
+            inference_time_ms,
+            predictions,
+        });
+        return Ok(());
+    }
+
+    println!("Model: {id}");
+    println!("  Device: {device}");
+    println!("  Load time: {load_time_ms} ms");
+    println!("  Inference time: {inference_time_ms} ms");
+    println!("  Top predictions for the synthetic test call:");
+    for (i, p) in predictions.iter().enumerate() {
+        println!(
+            "    {}. {} ({}) {:.1}%",
+            i + 1,
+            p.common_name,
+            p.scientific_name,
+            p.confidence * 100.0
+        );
     }
+    println!("Inference OK");
+    Ok(())
 }
 
 /// Handle the `models add` command.
@@ -1390,6 +4430,7 @@ fn handle_models_add(
     labels: PathBuf,
     model_type: ModelType,
     set_default: bool,
+    update: bool,
 ) -> Result<()> {
     // Validate files exist
     if !path.exists() {
@@ -1400,26 +4441,16 @@ fn handle_models_add(
     }
 
     // Load existing config
-    let mut config = load_default_config()?;
-
-    // Check if model already exists
-    if config.models.contains_key(&name) {
-        return Err(Error::ModelAlreadyExists { name });
-    }
-
-    // Add the model
-    config.models.insert(
-        name.clone(),
-        ModelConfig {
-            path: path.clone(),
-            labels: labels.clone(),
-            model_type,
-            meta_model: None,
-            bsg_calibration: None,
-            bsg_migration: None,
-            bsg_distribution_maps: None,
-        },
-    );
+    let mut config = config::load_config_file(&config_file_path()?, true)?;
+
+    let updated = add_model_to_config(
+        &mut config,
+        &name,
+        path.clone(),
+        labels.clone(),
+        model_type,
+        update,
+    )?;
 
     // Set as default if requested
     if set_default {
@@ -1430,15 +4461,58 @@ fn handle_models_add(
     let config_path = save_default_config(&config)?;
 
     // Print success message
-    println!("Added model '{name}' ({model_type})");
+    let is_default = config.defaults.model.as_deref() == Some(name.as_str());
+    let action = if updated { "Updated" } else { "Added" };
+    println!("{action} model '{name}' ({model_type})");
     println!("  Model: {}", path.display());
     println!("  Labels: {}", labels.display());
-    println!("  Default: {}", if set_default { "yes" } else { "no" });
+    println!("  Default: {}", if is_default { "yes" } else { "no" });
     println!("\nConfiguration saved to: {}", config_path.display());
 
     Ok(())
 }
 
+/// Add a model to config, or with `update` modify an existing entry.
+///
+/// Updating replaces the model and labels paths and the type, keeping any
+/// meta model, BSG files and custom head. Returns whether an existing entry
+/// was updated.
+fn add_model_to_config(
+    config: &mut Config,
+    name: &str,
+    path: PathBuf,
+    labels: PathBuf,
+    model_type: ModelType,
+    update: bool,
+) -> Result<bool> {
+    if let Some(existing) = config.models.get_mut(name) {
+        if !update {
+            return Err(Error::ModelAlreadyExists {
+                name: name.to_string(),
+            });
+        }
+        existing.path = path;
+        existing.labels = labels;
+        existing.model_type = model_type;
+        return Ok(true);
+    }
+
+    config.models.insert(
+        name.to_string(),
+        ModelConfig {
+            path,
+            labels,
+            model_type,
+            meta_model: None,
+            bsg_calibration: None,
+            bsg_migration: None,
+            bsg_distribution_maps: None,
+            custom_head: None,
+        },
+    );
+    Ok(false)
+}
+
 /// Remove a model from config and handle default promotion.
 ///
 /// Returns the removed `ModelConfig` and the new default name (if promoted).
@@ -1496,7 +4570,7 @@ fn handle_models_remove(name: &str, purge: bool, output_mode: OutputMode) -> Res
     use std::io::Write;
 
     // Load config and verify model exists
-    let mut config = load_default_config()?;
+    let mut config = config::load_config_file(&config_file_path()?, true)?;
 
     // If purge, confirm before deleting files (skip in structured mode)
     if purge && !output_mode.is_structured() {
@@ -1594,22 +4668,204 @@ fn handle_models_remove(name: &str, purge: bool, output_mode: OutputMode) -> Res
     Ok(())
 }
 
+/// Files of `model`, each once.
+fn model_files(model: &ModelConfig) -> Vec<PathBuf> {
+    let mut files = vec![model.path.clone(), model.labels.clone()];
+    files.extend(
+        [
+            &model.meta_model,
+            &model.bsg_calibration,
+            &model.bsg_migration,
+            &model.bsg_distribution_maps,
+        ]
+        .into_iter()
+        .flatten()
+        .cloned(),
+    );
+    if let Some(ref head) = model.custom_head {
+        files.push(head.path.clone());
+        files.push(head.labels.clone());
+    }
+    let mut seen = HashSet::new();
+    files.retain(|file| seen.insert(file.clone()));
+    files
+}
+
+/// Plan the moves that relocate model `name` into `new_dir`.
+///
+/// Files already in `new_dir` stay where they are. Fails if two files of the
+/// model share a file name or a destination already exists.
+fn relocation_plan(config: &Config, name: &str, new_dir: &Path) -> Result<Vec<output::MovedFile>> {
+    let model = config::get_model(config, name)?;
+    let mut targets = HashSet::new();
+    let mut plan = Vec::new();
+    for from in model_files(model) {
+        let Some(file_name) = from.file_name() else {
+            continue;
+        };
+        let to = new_dir.join(file_name);
+        if !targets.insert(to.clone()) {
+            return Err(Error::ConfigValidation {
+                message: format!(
+                    "model '{name}' has several files named '{}'",
+                    file_name.to_string_lossy()
+                ),
+            });
+        }
+        if from == to {
+            continue;
+        }
+        if to.exists() {
+            return Err(Error::ConfigValidation {
+                message: format!("'{}' already exists", to.display()),
+            });
+        }
+        plan.push(output::MovedFile { from, to });
+    }
+    Ok(plan)
+}
+
+/// Point every configured path that was moved to its new location.
+///
+/// Other models sharing a moved file follow it.
+fn apply_relocation(config: &mut Config, moves: &[output::MovedFile]) {
+    let relocate = |path: &mut PathBuf| {
+        if let Some(moved) = moves.iter().find(|moved| moved.from == *path) {
+            path.clone_from(&moved.to);
+        }
+    };
+    for model in config.models.values_mut() {
+        relocate(&mut model.path);
+        relocate(&mut model.labels);
+        for path in [
+            &mut model.meta_model,
+            &mut model.bsg_calibration,
+            &mut model.bsg_migration,
+            &mut model.bsg_distribution_maps,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            relocate(path);
+        }
+        if let Some(ref mut head) = model.custom_head {
+            relocate(&mut head.path);
+            relocate(&mut head.labels);
+        }
+    }
+}
+
+/// Move a file, copying it when `to` is on another filesystem.
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    std::fs::copy(from, to)?;
+    std::fs::remove_file(from).inspect_err(|_| {
+        let _ = std::fs::remove_file(to);
+    })
+}
+
+/// Handle the `models relocate` command.
+///
+/// Files are moved one at a time and moved back if a later move or the
+/// config save fails, so the config never points at files that are gone.
+fn handle_models_relocate(name: &str, new_dir: &Path, output_mode: OutputMode) -> Result<()> {
+    let mut config = config::load_config_file(&config_file_path()?, true)?;
+    config::get_model(&config, name)?;
+
+    std::fs::create_dir_all(new_dir).map_err(|e| Error::OutputDirCreateFailed {
+        path: new_dir.to_path_buf(),
+        source: e,
+    })?;
+    // Config paths must not depend on the working directory
+    let new_dir = std::path::absolute(new_dir)?;
+    let moves = relocation_plan(&config, name, &new_dir)?;
+
+    let mut moved: Vec<&output::MovedFile> = Vec::new();
+    let mut result = Ok(None);
+    for file in &moves {
+        if let Err(source) = move_file(&file.from, &file.to) {
+            result = Err(Error::ModelRelocateFailed {
+                from: file.from.clone(),
+                to: file.to.clone(),
+                source,
+            });
+            break;
+        }
+        moved.push(file);
+    }
+    if matches!(result, Ok(None)) && !moves.is_empty() {
+        apply_relocation(&mut config, &moves);
+        result = save_default_config(&config).map(Some);
+    }
+    let config_path = match result {
+        Ok(path) => path,
+        Err(e) => {
+            for file in moved.iter().rev() {
+                if let Err(undo) = move_file(&file.to, &file.from) {
+                    error!(
+                        "Could not move '{}' back to '{}': {undo}",
+                        file.to.display(),
+                        file.from.display()
+                    );
+                }
+            }
+            return Err(e);
+        }
+    };
+
+    if output_mode.is_structured() {
+        emit_json_result(&output::ModelRelocatedPayload {
+            result_type: ResultType::ModelRelocated,
+            id: name.to_string(),
+            dir: new_dir,
+            moved: moves,
+        });
+    } else if let Some(config_path) = config_path {
+        for file in &moves {
+            println!("  Moved: {} -> {}", file.from.display(), file.to.display());
+        }
+        println!("Model '{name}' relocated to {}.", new_dir.display());
+        println!("Configuration saved to: {}", config_path.display());
+    } else {
+        println!("Model '{name}' is already in {}.", new_dir.display());
+    }
+
+    Ok(())
+}
+
 /// Handle the `models install` command.
+///
+/// Registry settings are read from `config`, with environment overrides and
+/// the profile applied; the config file is only loaded to add the model.
+#[allow(clippy::too_many_arguments)]
 fn handle_models_install(
+    config: &Config,
     id: &str,
     language: Option<&str>,
     set_default: bool,
+    dest: Option<&Path>,
+    upgrade: bool,
+    connections: usize,
+    retry: registry::RetryPolicy,
     output_mode: OutputMode,
+    reporter: &Arc<dyn ProgressReporter>,
 ) -> Result<()> {
     use std::io::{IsTerminal, Write};
 
     let interactive = std::io::stdin().is_terminal() && !output_mode.is_structured();
 
-    // Load registry
-    let registry = registry::load_registry()?;
+    // Load the registry, including the configured sources
+    let registry = registry::load_configured_registry(config)?;
     let model = registry::find_model(&registry, id)
         .ok_or_else(|| Error::ModelNotFoundInRegistry { id: id.to_string() })?;
 
+    // Refuse to change a pinned version before downloading anything
+    let lockfile_path = registry::Lockfile::path()?;
+    let mut lockfile = registry::Lockfile::load(&lockfile_path)?;
+    lockfile.check_install(model, upgrade)?;
+
     // Prompt for license acceptance
     if !registry::prompt_license_acceptance(model, interactive)? {
         if !output_mode.is_structured() {
@@ -1623,7 +4879,32 @@ fn handle_models_install(
         message: format!("Failed to create async runtime: {e}"),
     })?;
 
-    let installed = runtime.block_on(async { registry::install_model(model, language).await })?;
+    // Report download progress as events in structured modes, replacing the
+    // progress bars. Json mode buffers them until the command returns
+    let reporter = output_mode.is_structured().then_some(reporter.as_ref());
+    // Install into --dest, else the configured or default models directory.
+    // Configured model paths must not depend on the working directory
+    let models_dir = match dest {
+        Some(dir) => std::path::absolute(dir)?,
+        None => std::path::absolute(registry::configured_models_dir(config)?)?,
+    };
+    let installed = runtime.block_on(async {
+        registry::install_model(
+            model,
+            language,
+            &models_dir,
+            connections,
+            reporter,
+            retry,
+            // Downloads must match the pinned hashes unless upgrading
+            lockfile.models.get(&model.id).filter(|_| !upgrade),
+        )
+        .await
+    })?;
+
+    // Pin the installed version and file hashes
+    lockfile.lock(model, &installed.files, upgrade)?;
+    lockfile.save(&lockfile_path)?;
 
     if !output_mode.is_structured() {
         println!();
@@ -1645,12 +4926,19 @@ fn handle_models_install(
             println!("  {}", maps_path.display());
         }
         println!();
+        println!(
+            "Locked {} at version {} in {}",
+            model.id,
+            model.version,
+            lockfile_path.display()
+        );
+        println!();
     }
 
-    // Prompt to set as default
+    // Prompt to set as default, unless reinstalling a configured model
     let should_set_default = if set_default {
         true
-    } else if interactive {
+    } else if interactive && !config.models.contains_key(id) {
         print!("Set as default model? [Y/n]: ");
         std::io::stdout().flush()?;
         let mut input = String::new();
@@ -1661,7 +4949,6 @@ fn handle_models_install(
     };
 
     // Add to config
-    let mut config = load_default_config()?;
 
     // Parse model_type from string
     let model_type: ModelType = model
@@ -1674,6 +4961,8 @@ fn handle_models_install(
     let model_path = installed.model.clone();
     let labels_path = installed.labels.clone();
 
+    // Environment overrides and the profile must not end up in the file
+    let mut config = config::load_config_file(&config_file_path()?, true)?;
     config.models.insert(
         id.to_string(),
         ModelConfig {
@@ -1684,6 +4973,7 @@ fn handle_models_install(
             bsg_calibration: installed.bsg_calibration,
             bsg_migration: installed.bsg_migration,
             bsg_distribution_maps: installed.bsg_distribution_maps,
+            custom_head: None,
         },
     );
 
@@ -1719,7 +5009,6 @@ fn handle_models_install(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashMap;
 
     /// Create a minimal Config with a named model.
     fn config_with_model(name: &str) -> Config {
@@ -1734,6 +5023,7 @@ mod tests {
                 bsg_calibration: None,
                 bsg_migration: None,
                 bsg_distribution_maps: None,
+                custom_head: None,
             },
         );
         Config {
@@ -1967,6 +5257,7 @@ mod tests {
             bsg_calibration: None,
             bsg_migration: None,
             bsg_distribution_maps: None,
+            custom_head: None,
         };
 
         assert!(validate_model_files(&config).is_ok());
@@ -1987,6 +5278,7 @@ mod tests {
             bsg_calibration: None,
             bsg_migration: None,
             bsg_distribution_maps: None,
+            custom_head: None,
         };
 
         let err = validate_model_files(&config).unwrap_err();
@@ -2008,6 +5300,7 @@ mod tests {
             bsg_calibration: None,
             bsg_migration: None,
             bsg_distribution_maps: None,
+            custom_head: None,
         };
 
         let err = validate_model_files(&config).unwrap_err();
@@ -2031,6 +5324,7 @@ mod tests {
             bsg_calibration: None,
             bsg_migration: None,
             bsg_distribution_maps: None,
+            custom_head: None,
         };
 
         let err = validate_model_files(&config).unwrap_err();
@@ -2055,6 +5349,7 @@ mod tests {
             bsg_calibration: None,
             bsg_migration: None,
             bsg_distribution_maps: None,
+            custom_head: None,
         };
 
         assert!(validate_model_files(&config).is_ok());
@@ -2318,6 +5613,108 @@ mod tests {
         );
     }
 
+    // ── models add / relocate tests ──────────────────────────────
+
+    #[test]
+    fn test_add_existing_model_requires_update() {
+        let mut config = config_with_model("birdnet");
+        config.models.get_mut("birdnet").unwrap().meta_model = Some(PathBuf::from("/m/meta.onnx"));
+
+        let result = add_model_to_config(
+            &mut config,
+            "birdnet",
+            PathBuf::from("/new/model.onnx"),
+            PathBuf::from("/new/labels.txt"),
+            ModelType::BirdnetV30,
+            false,
+        );
+        assert!(matches!(result, Err(Error::ModelAlreadyExists { .. })));
+
+        let updated = add_model_to_config(
+            &mut config,
+            "birdnet",
+            PathBuf::from("/new/model.onnx"),
+            PathBuf::from("/new/labels.txt"),
+            ModelType::BirdnetV30,
+            true,
+        )
+        .unwrap();
+        assert!(updated);
+        let model = &config.models["birdnet"];
+        assert_eq!(model.path, PathBuf::from("/new/model.onnx"));
+        assert_eq!(model.model_type, ModelType::BirdnetV30);
+        assert_eq!(model.meta_model, Some(PathBuf::from("/m/meta.onnx")));
+    }
+
+    #[test]
+    fn test_relocate_moves_files_and_rewrites_shared_paths() {
+        let old_dir = tempfile::tempdir().unwrap();
+        let new_dir = tempfile::tempdir().unwrap();
+        let model_path = old_dir.path().join("model.onnx");
+        let labels_path = old_dir.path().join("labels.txt");
+        std::fs::write(&model_path, b"onnx").unwrap();
+        std::fs::write(&labels_path, b"labels").unwrap();
+
+        let mut config = Config::default();
+        add_model_to_config(
+            &mut config,
+            "a",
+            model_path.clone(),
+            labels_path.clone(),
+            ModelType::BirdnetV24,
+            false,
+        )
+        .unwrap();
+        config
+            .models
+            .insert("b".to_string(), config.models["a"].clone());
+
+        let moves = relocation_plan(&config, "a", new_dir.path()).unwrap();
+        assert_eq!(moves.len(), 2);
+        for file in &moves {
+            move_file(&file.from, &file.to).unwrap();
+        }
+        apply_relocation(&mut config, &moves);
+
+        assert!(!model_path.exists());
+        assert_eq!(config.models["a"].path, new_dir.path().join("model.onnx"));
+        assert_eq!(config.models["b"].labels, new_dir.path().join("labels.txt"));
+        assert_eq!(
+            std::fs::read(new_dir.path().join("model.onnx")).unwrap(),
+            b"onnx"
+        );
+
+        // Already in place: nothing to move
+        assert!(
+            relocation_plan(&config, "a", new_dir.path())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_relocate_refuses_to_overwrite() {
+        let old_dir = tempfile::tempdir().unwrap();
+        let new_dir = tempfile::tempdir().unwrap();
+        std::fs::write(new_dir.path().join("model.onnx"), b"other").unwrap();
+
+        let mut config = Config::default();
+        add_model_to_config(
+            &mut config,
+            "a",
+            old_dir.path().join("model.onnx"),
+            old_dir.path().join("labels.txt"),
+            ModelType::BirdnetV24,
+            false,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            relocation_plan(&config, "a", new_dir.path()),
+            Err(Error::ConfigValidation { .. })
+        ));
+    }
+
     // ── models remove tests ──────────────────────────────────────
 
     #[test]
@@ -2382,4 +5779,83 @@ mod tests {
         assert_eq!(model.labels, PathBuf::from("/path/to/labels.txt"));
         assert_eq!(model.model_type, ModelType::BirdnetV24);
     }
+
+    #[test]
+    fn test_warmup_with_backoff_halves_on_oom() {
+        let mut attempts = Vec::new();
+        let result = warmup_with_backoff(32, |size| {
+            attempts.push(size);
+            if size > 8 {
+                Err(Error::Inference {
+                    reason: "CUDA failure 2: out of memory".to_string(),
+                })
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(result.unwrap(), 8);
+        assert_eq!(attempts, vec![32, 16, 8]);
+    }
+
+    #[test]
+    fn test_warmup_with_backoff_gives_up_at_one() {
+        let result = warmup_with_backoff(2, |_| {
+            Err(Error::Inference {
+                reason: "out of memory".to_string(),
+            })
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_warmup_with_backoff_propagates_other_errors() {
+        let mut attempts = 0;
+        let result = warmup_with_backoff(16, |_| {
+            attempts += 1;
+            Err(Error::Inference {
+                reason: "invalid input shape".to_string(),
+            })
+        });
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn test_panic_payload_message() {
+        let static_payload: Box<dyn std::any::Any + Send> = Box::new("decode failed");
+        assert_eq!(
+            panic_payload_message(static_payload.as_ref()),
+            "decode failed"
+        );
+
+        let owned_payload: Box<dyn std::any::Any + Send> = Box::new(String::from("writer failed"));
+        assert_eq!(
+            panic_payload_message(owned_payload.as_ref()),
+            "writer failed"
+        );
+
+        let other_payload: Box<dyn std::any::Any + Send> = Box::new(42_u32);
+        assert_eq!(
+            panic_payload_message(other_payload.as_ref()),
+            "unknown panic payload"
+        );
+    }
+
+    #[test]
+    fn test_validate_read_only_inputs() {
+        let inputs = vec![PathBuf::from("/archive/site1/rec.wav")];
+
+        // Output elsewhere is fine
+        assert!(validate_read_only_inputs(&inputs, Some(Path::new("/results")), true).is_ok());
+
+        // Output dir required when files are written
+        assert!(validate_read_only_inputs(&inputs, None, true).is_err());
+        assert!(validate_read_only_inputs(&inputs, None, false).is_ok());
+
+        // Output inside an input directory is rejected
+        assert!(
+            validate_read_only_inputs(&inputs, Some(Path::new("/archive/site1/out")), true)
+                .is_err()
+        );
+    }
 }
@@ -1,9 +1,11 @@
 //! File locking for distributed processing.
 
-use crate::constants::LOCK_FILE_EXTENSION;
+use crate::constants::{CENTRAL_LOCK_HASH_LEN, LOCK_FILE_EXTENSION};
 use crate::error::{Error, Result};
+use crate::utils::hash::to_hex;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fs::{self, OpenOptions};
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -32,13 +34,22 @@ impl FileLock {
     ///
     /// The lock file is created in the output directory.
     pub fn acquire(input_path: &Path, output_dir: &Path) -> Result<Self> {
-        let lock_path = Self::lock_path_for(input_path, output_dir);
+        Self::acquire_at(&Self::lock_path_for(input_path, output_dir), input_path)
+    }
 
-        // Ensure output directory exists before creating lock file
-        fs::create_dir_all(output_dir).map_err(|e| Error::OutputDirCreateFailed {
-            path: output_dir.to_path_buf(),
-            source: e,
-        })?;
+    /// Attempt to acquire a lock at an explicit lock file path.
+    ///
+    /// The parent directory is created if needed.
+    pub fn acquire_at(lock_path: &Path, input_path: &Path) -> Result<Self> {
+        let lock_path = lock_path.to_path_buf();
+
+        // Ensure lock directory exists before creating lock file
+        if let Some(lock_dir) = lock_path.parent() {
+            fs::create_dir_all(lock_dir).map_err(|e| Error::OutputDirCreateFailed {
+                path: lock_dir.to_path_buf(),
+                source: e,
+            })?;
+        }
 
         // Register for cleanup BEFORE file creation to avoid race condition
         // if Ctrl+C occurs between creation and registration
@@ -92,6 +103,32 @@ impl FileLock {
         output_dir.join(format!("{stem}{LOCK_FILE_EXTENSION}"))
     }
 
+    /// Get the lock file path for an input file in a central lock directory.
+    ///
+    /// The name is a hash of the absolute input path, so inputs with the same
+    /// file name in different directories never share a lock.
+    pub fn central_lock_path_for(input_path: &Path, lock_dir: &Path) -> PathBuf {
+        let absolute = std::path::absolute(input_path).unwrap_or_else(|_| input_path.to_path_buf());
+        let hash = Sha256::digest(absolute.as_os_str().as_encoded_bytes());
+        let name = to_hex(&hash[..CENTRAL_LOCK_HASH_LEN]);
+        lock_dir.join(format!("{name}{LOCK_FILE_EXTENSION}"))
+    }
+
+    /// Resolve where the lock for an input file lives.
+    ///
+    /// Uses a hash-named file in `lock_dir` when a central lock directory is
+    /// configured, otherwise a file named after the input beside the outputs.
+    pub fn resolve_lock_path(
+        input_path: &Path,
+        output_dir: &Path,
+        lock_dir: Option<&Path>,
+    ) -> PathBuf {
+        lock_dir.map_or_else(
+            || Self::lock_path_for(input_path, output_dir),
+            |dir| Self::central_lock_path_for(input_path, dir),
+        )
+    }
+
     /// Check if a lock file exists.
     pub fn is_locked(input_path: &Path, output_dir: &Path) -> bool {
         Self::lock_path_for(input_path, output_dir).exists()
@@ -132,6 +169,50 @@ impl Drop for FileLock {
     }
 }
 
+/// Central lock directory in use, scanned on cleanup.
+static CENTRAL_LOCK_DIR: std::sync::OnceLock<PathBuf> = std::sync::OnceLock::new();
+
+/// Register the central lock directory for this process.
+///
+/// [`cleanup_all_locks`] then also scans that directory and removes every
+/// lock held by this process, so cleanup does not rely on the in-memory
+/// registry alone.
+pub fn set_central_lock_dir(dir: &Path) {
+    let _ = CENTRAL_LOCK_DIR.set(dir.to_path_buf());
+}
+
+/// List the lock files in a central lock directory with their contents.
+///
+/// Lock info is `None` for files that could not be read or parsed.
+pub fn list_locks(lock_dir: &Path) -> Vec<(PathBuf, Option<LockInfo>)> {
+    let Ok(entries) = fs::read_dir(lock_dir) else {
+        return Vec::new();
+    };
+
+    let mut locks: Vec<(PathBuf, Option<LockInfo>)> = entries
+        .filter_map(std::result::Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| name.to_string_lossy().ends_with(LOCK_FILE_EXTENSION))
+        })
+        .map(|path| {
+            let info = fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok());
+            (path, info)
+        })
+        .collect();
+    locks.sort_by(|a, b| a.0.cmp(&b.0));
+    locks
+}
+
+/// Whether a lock was written by this process on this host.
+fn is_own_lock(info: &LockInfo) -> bool {
+    info.pid == std::process::id()
+        && hostname::get().is_ok_and(|h| h.to_string_lossy() == info.hostname)
+}
+
 /// Global registry of active lock paths for cleanup on signal.
 static ACTIVE_LOCKS: std::sync::LazyLock<std::sync::Mutex<Vec<PathBuf>>> =
     std::sync::LazyLock::new(|| std::sync::Mutex::new(Vec::new()));
@@ -166,6 +247,14 @@ pub fn cleanup_all_locks() {
     for lock_path in paths {
         let _ = fs::remove_file(&lock_path);
     }
+
+    if let Some(lock_dir) = CENTRAL_LOCK_DIR.get() {
+        for (lock_path, info) in list_locks(lock_dir) {
+            if info.as_ref().is_some_and(is_own_lock) {
+                let _ = fs::remove_file(&lock_path);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -215,6 +304,40 @@ mod tests {
         assert_eq!(path.to_string_lossy(), "/output/audio.wav.birda.lock");
     }
 
+    #[test]
+    fn test_central_lock_path_is_hashed() {
+        let a = FileLock::central_lock_path_for(Path::new("/data/a/rec.wav"), Path::new("/locks"));
+        let b = FileLock::central_lock_path_for(Path::new("/data/b/rec.wav"), Path::new("/locks"));
+        assert_ne!(a, b);
+        assert_eq!(a.parent(), Some(Path::new("/locks")));
+
+        let name = a.file_name().unwrap().to_string_lossy().into_owned();
+        let stem = name.strip_suffix(LOCK_FILE_EXTENSION).unwrap();
+        assert_eq!(stem.len(), CENTRAL_LOCK_HASH_LEN * 2);
+        assert!(stem.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn test_central_lock_listed_and_released() {
+        let _guard = TEST_LOCK.lock().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let lock_dir = temp_dir.path().join("locks");
+        let input = temp_dir.path().join("test.wav");
+        File::create(&input).unwrap();
+
+        let lock_path = FileLock::resolve_lock_path(&input, temp_dir.path(), Some(&lock_dir));
+        let lock = FileLock::acquire_at(&lock_path, &input).unwrap();
+
+        let locks = list_locks(&lock_dir);
+        assert_eq!(locks.len(), 1);
+        let info = locks[0].1.as_ref().unwrap();
+        assert_eq!(info.input, input);
+        assert!(is_own_lock(info));
+
+        drop(lock);
+        assert!(list_locks(&lock_dir).is_empty());
+    }
+
     #[test]
     fn test_cleanup_all_locks_removes_registered_files() {
         let _guard = TEST_LOCK.lock().unwrap();
@@ -0,0 +1,52 @@
+//! Lock directories kept away from input and output directories.
+
+use crate::error::{Error, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Run-specific lock directory under the system temp directory.
+///
+/// Used when lock files must not be written beside inputs or outputs. The
+/// directory is removed when the guard is dropped.
+pub struct RunLockDir {
+    path: PathBuf,
+}
+
+impl RunLockDir {
+    /// Create the lock directory for this process.
+    pub fn create() -> Result<Self> {
+        let path = std::env::temp_dir().join(format!("birda-locks-{}", std::process::id()));
+        fs::create_dir_all(&path).map_err(|e| Error::LockCreate {
+            path: path.clone(),
+            source: e,
+        })?;
+        Ok(Self { path })
+    }
+
+    /// Path of the lock directory.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for RunLockDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_lock_dir_removed_on_drop() {
+        let dir = RunLockDir::create().unwrap();
+        let path = dir.path().to_path_buf();
+        assert!(path.is_dir());
+
+        drop(dir);
+        assert!(!path.exists());
+    }
+}
@@ -1,5 +1,7 @@
 //! File locking for distributed processing.
 
 mod file_lock;
+mod lock_dir;
 
-pub use file_lock::{FileLock, LockInfo, cleanup_all_locks};
+pub use file_lock::{FileLock, LockInfo, cleanup_all_locks, list_locks, set_central_lock_dir};
+pub use lock_dir::RunLockDir;
@@ -0,0 +1,353 @@
+//! Per-minute acoustic activity summary.
+//!
+//! Aggregates segment energy and detections into fixed-width time bins, giving a
+//! compact overview of when a recording is interesting without reading the full
+//! detections table.
+
+use arrow::array::{ArrayRef, Float64Array, UInt32Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::config::ActivityFormat;
+use crate::constants::UTF8_BOM;
+use crate::constants::activity::{BIN_DURATION_SECS, SILENCE_FLOOR_DBFS};
+use crate::error::{Error, Result};
+use crate::output::Detection;
+
+/// Activity statistics for a single time bin.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActivityBin {
+    /// Zero-based bin index (minute of the recording).
+    pub minute: u32,
+    /// Number of analyzed segments starting in this bin.
+    pub segments: u32,
+    /// Number of segments whose level exceeded the energy threshold.
+    pub active_segments: u32,
+    /// Number of detections starting in this bin.
+    pub detections: u32,
+    /// Number of distinct species detected in this bin.
+    pub species: u32,
+}
+
+impl ActivityBin {
+    /// Start time of this bin in seconds.
+    #[must_use]
+    pub fn start_seconds(&self) -> f64 {
+        f64::from(self.minute) * BIN_DURATION_SECS
+    }
+}
+
+/// Accumulates segment energy while a file is being analyzed.
+#[derive(Debug, Clone)]
+pub struct ActivityTracker {
+    threshold_dbfs: f32,
+    /// Per-bin (segments, active segments) counts.
+    segments: BTreeMap<u32, (u32, u32)>,
+}
+
+impl ActivityTracker {
+    /// Create a tracker that counts segments louder than `threshold_dbfs` as active.
+    #[must_use]
+    pub fn new(threshold_dbfs: f32) -> Self {
+        Self {
+            threshold_dbfs,
+            segments: BTreeMap::new(),
+        }
+    }
+
+    /// Record one analyzed segment.
+    pub fn record_segment(&mut self, start_time: f32, samples: &[f32]) {
+        let entry = self.segments.entry(bin_index(start_time)).or_default();
+        entry.0 += 1;
+        if segment_level_dbfs(samples) >= self.threshold_dbfs {
+            entry.1 += 1;
+        }
+    }
+
+    /// Combine recorded segment statistics with detections into per-minute bins.
+    ///
+    /// Bins with no segments are emitted with zero counts so the output has no
+    /// gaps in its time axis.
+    #[must_use]
+    pub fn summarize(&self, detections: &[Detection]) -> Vec<ActivityBin> {
+        let mut detection_counts: BTreeMap<u32, (u32, HashSet<&str>)> = BTreeMap::new();
+        for detection in detections {
+            let entry = detection_counts
+                .entry(bin_index(detection.start_time))
+                .or_default();
+            entry.0 += 1;
+            entry.1.insert(detection.scientific_name.as_str());
+        }
+
+        let last_bin = self
+            .segments
+            .keys()
+            .chain(detection_counts.keys())
+            .max()
+            .copied();
+
+        let Some(last_bin) = last_bin else {
+            return Vec::new();
+        };
+
+        (0..=last_bin)
+            .map(|minute| {
+                let (segments, active_segments) =
+                    self.segments.get(&minute).copied().unwrap_or_default();
+                let (detections, species) =
+                    detection_counts
+                        .get(&minute)
+                        .map_or((0, 0), |(count, species)| {
+                            #[allow(clippy::cast_possible_truncation)]
+                            (*count, species.len() as u32)
+                        });
+                ActivityBin {
+                    minute,
+                    segments,
+                    active_segments,
+                    detections,
+                    species,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Map a time offset in seconds to its activity bin index.
+fn bin_index(start_time: f32) -> u32 {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let index = (f64::from(start_time.max(0.0)) / BIN_DURATION_SECS).floor() as u32;
+    index
+}
+
+/// Compute the RMS level of a segment in dBFS.
+///
+/// Silent or empty segments return [`SILENCE_FLOOR_DBFS`].
+#[must_use]
+pub fn segment_level_dbfs(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return SILENCE_FLOOR_DBFS;
+    }
+
+    let sum_squares: f64 = samples.iter().map(|&s| f64::from(s) * f64::from(s)).sum();
+    #[allow(clippy::cast_precision_loss)]
+    let rms = (sum_squares / samples.len() as f64).sqrt();
+
+    if rms <= 0.0 {
+        return SILENCE_FLOOR_DBFS;
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    let dbfs = (20.0 * rms.log10()) as f32;
+    dbfs.max(SILENCE_FLOOR_DBFS)
+}
+
+/// Write an activity summary in the requested format.
+///
+/// # Errors
+///
+/// Returns an error if the output file cannot be created or written.
+pub fn write_activity(
+    path: &Path,
+    format: ActivityFormat,
+    bins: &[ActivityBin],
+    include_bom: bool,
+) -> Result<()> {
+    match format {
+        ActivityFormat::Csv => write_activity_csv(path, bins, include_bom),
+        ActivityFormat::Parquet => write_activity_parquet(path, bins),
+    }
+}
+
+/// Write an activity summary as CSV.
+fn write_activity_csv(path: &Path, bins: &[ActivityBin], include_bom: bool) -> Result<()> {
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(file);
+
+    if include_bom {
+        writer.write_all(UTF8_BOM)?;
+    }
+
+    writeln!(
+        writer,
+        "Minute,Start (s),Segments,Active segments,Detections,Species"
+    )?;
+    for bin in bins {
+        writeln!(
+            writer,
+            "{},{:.1},{},{},{},{}",
+            bin.minute,
+            bin.start_seconds(),
+            bin.segments,
+            bin.active_segments,
+            bin.detections,
+            bin.species
+        )?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Build the Arrow schema for activity summaries.
+fn activity_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("minute", DataType::UInt32, false),
+        Field::new("start_s", DataType::Float64, false),
+        Field::new("segments", DataType::UInt32, false),
+        Field::new("active_segments", DataType::UInt32, false),
+        Field::new("detections", DataType::UInt32, false),
+        Field::new("species", DataType::UInt32, false),
+    ]))
+}
+
+/// Write an activity summary as Parquet.
+fn write_activity_parquet(path: &Path, bins: &[ActivityBin]) -> Result<()> {
+    let schema = activity_schema();
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(bins.iter().map(|b| b.minute).collect::<UInt32Array>()),
+        Arc::new(
+            bins.iter()
+                .map(ActivityBin::start_seconds)
+                .collect::<Float64Array>(),
+        ),
+        Arc::new(bins.iter().map(|b| b.segments).collect::<UInt32Array>()),
+        Arc::new(
+            bins.iter()
+                .map(|b| b.active_segments)
+                .collect::<UInt32Array>(),
+        ),
+        Arc::new(bins.iter().map(|b| b.detections).collect::<UInt32Array>()),
+        Arc::new(bins.iter().map(|b| b.species).collect::<UInt32Array>()),
+    ];
+
+    let batch = RecordBatch::try_new(schema.clone(), columns).map_err(|e| Error::ParquetWrite {
+        context: "Failed to build activity record batch".to_string(),
+        source: e.into(),
+    })?;
+
+    let file = File::create(path).map_err(|e| Error::ParquetFileCreate {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+
+    let props = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+
+    let mut writer =
+        ArrowWriter::try_new(file, schema, Some(props)).map_err(|e| Error::ParquetWrite {
+            context: "Failed to initialize activity Parquet writer".to_string(),
+            source: e,
+        })?;
+
+    writer.write(&batch).map_err(|e| Error::ParquetWrite {
+        context: "Failed to write activity record batch".to_string(),
+        source: e,
+    })?;
+
+    writer.close().map_err(|e| Error::ParquetWrite {
+        context: "Failed to close activity Parquet writer".to_string(),
+        source: e,
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_segment_level_silence() {
+        assert!((segment_level_dbfs(&[0.0; 100]) - SILENCE_FLOOR_DBFS).abs() < f32::EPSILON);
+        assert!((segment_level_dbfs(&[]) - SILENCE_FLOOR_DBFS).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_segment_level_full_scale() {
+        let level = segment_level_dbfs(&[1.0, -1.0, 1.0, -1.0]);
+        assert!(level.abs() < 0.01);
+    }
+
+    #[test]
+    fn test_summarize_bins_by_minute() {
+        let mut tracker = ActivityTracker::new(-20.0);
+        tracker.record_segment(0.0, &[0.5; 10]);
+        tracker.record_segment(3.0, &[0.0; 10]);
+        tracker.record_segment(120.0, &[0.5; 10]);
+
+        let detections = vec![
+            Detection::test("Parus major", 0.0, "test.wav"),
+            Detection::test("Parus major", 3.0, "test.wav"),
+            Detection::test("Turdus merula", 3.0, "test.wav"),
+        ];
+
+        let bins = tracker.summarize(&detections);
+        assert_eq!(bins.len(), 3);
+        assert_eq!(bins[0].segments, 2);
+        assert_eq!(bins[0].active_segments, 1);
+        assert_eq!(bins[0].detections, 3);
+        assert_eq!(bins[0].species, 2);
+        // Gap minute is emitted with zero counts
+        assert_eq!(
+            bins[1],
+            ActivityBin {
+                minute: 1,
+                ..ActivityBin::default()
+            }
+        );
+        assert_eq!(bins[2].active_segments, 1);
+        assert_eq!(bins[2].detections, 0);
+    }
+
+    #[test]
+    fn test_summarize_empty() {
+        let tracker = ActivityTracker::new(-50.0);
+        assert!(tracker.summarize(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_write_activity_csv() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("activity.csv");
+        let bins = vec![ActivityBin {
+            minute: 0,
+            segments: 20,
+            active_segments: 5,
+            detections: 3,
+            species: 2,
+        }];
+
+        write_activity(&path, ActivityFormat::Csv, &bins, false).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let mut lines = content.lines();
+        assert_eq!(
+            lines.next(),
+            Some("Minute,Start (s),Segments,Active segments,Detections,Species")
+        );
+        assert_eq!(lines.next(), Some("0,0.0,20,5,3,2"));
+    }
+
+    #[test]
+    fn test_write_activity_parquet() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("activity.parquet");
+        let bins = vec![ActivityBin::default()];
+
+        write_activity(&path, ActivityFormat::Parquet, &bins, false).unwrap();
+
+        assert!(path.exists());
+    }
+}
@@ -1,24 +1,37 @@
 //! Audacity labels output format writer.
 
-use crate::constants::confidence::DECIMAL_PLACES;
 use crate::error::Result;
-use crate::output::{Detection, OutputWriter};
+use crate::output::{Detection, NumberFormat, OutputWriter};
 use std::fs::File;
 use std::io::{BufWriter, Write};
 use std::path::Path;
 
 /// Audacity labels output writer.
 pub struct AudacityWriter {
-    writer: BufWriter<File>,
+    writer: BufWriter<Box<dyn Write + Send>>,
+    /// Decimal places of confidences and times.
+    number_format: NumberFormat,
 }
 
 impl AudacityWriter {
     /// Create a new Audacity writer.
     pub fn new(path: &Path) -> Result<Self> {
-        let file = File::create(path)?;
-        Ok(Self {
-            writer: BufWriter::new(file),
-        })
+        Ok(Self::from_writer(Box::new(File::create(path)?)))
+    }
+
+    /// Create an Audacity writer over any sink (e.g. stdout).
+    pub fn from_writer(sink: Box<dyn Write + Send>) -> Self {
+        Self {
+            writer: BufWriter::new(sink),
+            number_format: NumberFormat::default(),
+        }
+    }
+
+    /// Write confidences and times with the decimal places of `format`.
+    #[must_use]
+    pub const fn with_number_format(mut self, format: NumberFormat) -> Self {
+        self.number_format = format;
+        self
     }
 }
 
@@ -27,18 +40,25 @@ impl OutputWriter for AudacityWriter {
         // Replace underscores with commas for Audacity format
         let species_name = detection.common_name.replace('_', ", ");
 
+        let format = self.number_format;
         writeln!(
             self.writer,
-            "{:.1}\t{:.1}\t{}\t{:.decimal$}",
+            "{:.time$}\t{:.time$}\t{}\t{:.decimal$}",
             detection.start_time,
             detection.end_time,
             species_name,
             detection.confidence,
-            decimal = DECIMAL_PLACES,
+            time = format.time_places(),
+            decimal = format.confidence_places(),
         )?;
         Ok(())
     }
 
+    fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
     fn finalize(&mut self) -> Result<()> {
         self.writer.flush()?;
         Ok(())
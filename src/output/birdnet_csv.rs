@@ -0,0 +1,128 @@
+//! BirdNET-Analyzer CSV output format writer.
+//!
+//! Writes the exact columns of BirdNET-Analyzer's `csv` result type, without
+//! the `File` column, BOM or optional columns of birda's own CSV, so scripts
+//! built for BirdNET-Analyzer output can read the files unchanged.
+
+use crate::error::Result;
+use crate::output::csv::escape_csv;
+use crate::output::{Detection, NumberFormat, OutputWriter};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Header row of BirdNET-Analyzer CSV results.
+const HEADER: &str = "Start (s),End (s),Scientific name,Common name,Confidence";
+
+/// BirdNET-Analyzer CSV output writer.
+pub struct BirdnetCsvWriter {
+    writer: BufWriter<Box<dyn Write + Send>>,
+    /// Decimal places of confidences and times.
+    number_format: NumberFormat,
+}
+
+impl BirdnetCsvWriter {
+    /// Create a new BirdNET-Analyzer CSV writer.
+    pub fn new(path: &Path) -> Result<Self> {
+        Ok(Self::from_writer(Box::new(File::create(path)?)))
+    }
+
+    /// Create a BirdNET-Analyzer CSV writer over any sink (e.g. stdout).
+    pub fn from_writer(sink: Box<dyn Write + Send>) -> Self {
+        Self {
+            writer: BufWriter::new(sink),
+            number_format: NumberFormat::default(),
+        }
+    }
+
+    /// Write confidences and times with the decimal places of `format`.
+    #[must_use]
+    pub const fn with_number_format(mut self, format: NumberFormat) -> Self {
+        self.number_format = format;
+        self
+    }
+}
+
+impl OutputWriter for BirdnetCsvWriter {
+    fn write_header(&mut self) -> Result<()> {
+        writeln!(self.writer, "{HEADER}")?;
+        Ok(())
+    }
+
+    fn write_detection(&mut self, detection: &Detection) -> Result<()> {
+        let format = self.number_format;
+        writeln!(
+            self.writer,
+            "{:.time$},{:.time$},{},{},{:.decimal$}",
+            detection.start_time,
+            detection.end_time,
+            escape_csv(&detection.scientific_name),
+            escape_csv(&detection.common_name),
+            detection.confidence,
+            time = format.time_places(),
+            decimal = format.confidence_places(),
+        )?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    fn finalize(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_birdnet_csv_writer_basic() {
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = BirdnetCsvWriter::new(file.path()).unwrap();
+
+        writer.write_header().unwrap();
+        let detection = Detection::from_label(
+            "Glaucidium passerinum_Eurasian Pygmy Owl",
+            0.9237,
+            0.0,
+            3.0,
+            PathBuf::from("/path/to/recording.wav"),
+        );
+        writer.write_detection(&detection).unwrap();
+        writer.finalize().unwrap();
+
+        let contents = std::fs::read_to_string(file.path()).unwrap();
+        assert_eq!(
+            contents,
+            "Start (s),End (s),Scientific name,Common name,Confidence\n\
+             0.0,3.0,Glaucidium passerinum,Eurasian Pygmy Owl,0.9237\n"
+        );
+    }
+
+    #[test]
+    fn test_birdnet_csv_quotes_commas() {
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = BirdnetCsvWriter::new(file.path()).unwrap();
+
+        let detection = Detection::from_label(
+            "Homo sapiens_Human, vocal",
+            0.5,
+            3.0,
+            6.0,
+            PathBuf::from("a.wav"),
+        );
+        writer.write_detection(&detection).unwrap();
+        writer.finalize().unwrap();
+
+        let contents = std::fs::read_to_string(file.path()).unwrap();
+        assert_eq!(contents, "3.0,6.0,Homo sapiens,\"Human, vocal\",0.5000\n");
+    }
+}
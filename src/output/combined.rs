@@ -0,0 +1,383 @@
+//! Streaming combined results across all analyzed files.
+//!
+//! A dedicated combiner thread owns the combined writers and receives each
+//! file's detections over a bounded channel, appending them as they arrive.
+//! CSV-style formats are appended and flushed per file, and Parquet gets one
+//! row group per file, so results are never re-read to build the combined
+//! table. Workers only hold a cloneable [`CombinedSender`], so any number of
+//! them can feed the same files without sharing a writer.
+
+use crate::config::OutputFormat;
+use crate::constants::combined_filenames;
+use crate::error::{Error, Result};
+use crate::output::{
+    CsvWriter, Detection, KaleidoscopeWriter, NumberFormat, OutputWriter, ParquetWriter,
+    RavenWriter, RollingWriter, SplitLimits, next_free_part,
+};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, SyncSender, sync_channel};
+use std::thread::{self, JoinHandle};
+use tracing::{debug, warn};
+
+/// Combined output filename for `format`, or `None` if it has no combined form.
+pub fn combined_filename(format: OutputFormat, prefix: &str) -> Option<String> {
+    let name = match format {
+        OutputFormat::Csv => combined_filenames::CSV,
+        OutputFormat::Raven => combined_filenames::RAVEN,
+        OutputFormat::Kaleidoscope => combined_filenames::KALEIDOSCOPE,
+        OutputFormat::Parquet => combined_filenames::PARQUET,
+        OutputFormat::BirdnetCsv
+        | OutputFormat::Audacity
+        | OutputFormat::Json
+        | OutputFormat::Sqlite => return None,
+    };
+    Some(with_prefix(name, prefix))
+}
+
+/// Whether `name` is a combined output file, whatever its prefix.
+pub fn is_combined_filename(name: &str) -> bool {
+    use clap::ValueEnum;
+    OutputFormat::value_variants()
+        .iter()
+        .filter_map(|&format| combined_filename(format, ""))
+        .any(|suffix| name.ends_with(&suffix))
+}
+
+/// Replace the default `BirdNET` prefix of a run-wide output name.
+pub(crate) fn with_prefix(name: &str, prefix: &str) -> String {
+    let suffix = name
+        .strip_prefix(combined_filenames::DEFAULT_PREFIX)
+        .unwrap_or(name);
+    format!("{prefix}{suffix}")
+}
+
+/// One analyzed recording: its path, detections and audio duration in
+/// seconds.
+type FileBatch = (PathBuf, Vec<Detection>, f64);
+
+/// Handle for sending a file's detections to the combiner thread.
+#[derive(Debug, Clone)]
+pub struct CombinedSender {
+    tx: SyncSender<FileBatch>,
+}
+
+impl CombinedSender {
+    /// Queue the detections of recording `file` and its audio duration in
+    /// seconds, blocking while the channel is full. Recordings without
+    /// detections are sent too, so run-wide outputs can clear stale results.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::CombinerStopped`] if the combiner thread has exited,
+    /// usually after a write error that [`CombinedWriter::finish`] reports.
+    pub fn send(
+        &self,
+        file: &Path,
+        detections: Vec<Detection>,
+        audio_duration_secs: f64,
+    ) -> Result<()> {
+        self.tx
+            .send((file.to_path_buf(), detections, audio_duration_secs))
+            .map_err(|_| Error::CombinerStopped)
+    }
+}
+
+/// Run-wide output files written incrementally by a background thread.
+///
+/// Besides the combined tables this also drives other outputs spanning all
+/// files, such as a partitioned Parquet dataset.
+pub struct CombinedWriter {
+    sender: CombinedSender,
+    handle: JoinHandle<Result<()>>,
+    paths: Vec<PathBuf>,
+}
+
+impl CombinedWriter {
+    /// Create the combined files in `output_dir` and start the combiner thread.
+    ///
+    /// See [`combined_outputs`] for which files are created.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the output directory or any combined file cannot be
+    /// created.
+    pub fn spawn(
+        output_dir: &Path,
+        prefix: &str,
+        formats: &[OutputFormat],
+        csv_columns: &[String],
+        csv_bom_enabled: bool,
+        number_format: NumberFormat,
+        split_limits: SplitLimits,
+    ) -> Result<Self> {
+        Self::start(combined_outputs(
+            output_dir,
+            prefix,
+            formats,
+            csv_columns,
+            csv_bom_enabled,
+            number_format,
+            split_limits,
+            false,
+        )?)
+    }
+
+    /// Start the combiner thread over already opened run-wide outputs.
+    ///
+    /// Headers are written before returning, so errors surface immediately
+    /// rather than after the first analyzed file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a header cannot be written.
+    pub fn start(outputs: Vec<(PathBuf, Box<dyn OutputWriter + Send>)>) -> Result<Self> {
+        let mut writers = Vec::with_capacity(outputs.len());
+        let mut paths = Vec::with_capacity(outputs.len());
+        for (path, mut writer) in outputs {
+            writer.write_header()?;
+            writers.push(writer);
+            paths.push(path);
+        }
+
+        let (tx, rx) = sync_channel(combined_filenames::CHANNEL_CAPACITY);
+        let handle = thread::spawn(move || run_combiner(writers, &rx));
+
+        Ok(Self {
+            sender: CombinedSender { tx },
+            handle,
+            paths,
+        })
+    }
+
+    /// Handle for queueing detections; clone it for each worker.
+    pub fn sender(&self) -> CombinedSender {
+        self.sender.clone()
+    }
+
+    /// Paths of the combined files being written.
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Close the channel, wait for queued detections, and finalize the files.
+    ///
+    /// Senders cloned from this writer must be dropped first, otherwise this
+    /// waits for them.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first write error hit by the combiner thread.
+    pub fn finish(self) -> Result<Vec<PathBuf>> {
+        let Self {
+            sender,
+            handle,
+            paths,
+        } = self;
+        drop(sender);
+
+        handle.join().map_err(|_| Error::Internal {
+            message: "combined output thread panicked".to_string(),
+        })??;
+        Ok(paths)
+    }
+}
+
+/// Open the combined result files for `formats` in `output_dir`.
+///
+/// Formats without a combined form (Audacity, JSON) are skipped, and CSV and
+/// Parquet roll over into numbered parts under `split_limits`. With `append`,
+/// CSV rows are added to an existing table and Parquet goes to the next free
+/// part file, so results accumulate across runs; Raven and Kaleidoscope tables
+/// are still replaced. Combined Parquet always has the `path` column, so rows
+/// from same-named recordings in different directories stay distinguishable.
+///
+/// # Errors
+///
+/// Returns an error if the output directory or any combined file cannot be
+/// created.
+#[allow(clippy::too_many_arguments)]
+pub fn combined_outputs(
+    output_dir: &Path,
+    prefix: &str,
+    formats: &[OutputFormat],
+    csv_columns: &[String],
+    csv_bom_enabled: bool,
+    number_format: NumberFormat,
+    split_limits: SplitLimits,
+    append: bool,
+) -> Result<Vec<(PathBuf, Box<dyn OutputWriter + Send>)>> {
+    std::fs::create_dir_all(output_dir)?;
+
+    // The core Parquet `file` column is only the file name
+    let mut parquet_columns = csv_columns.to_vec();
+    if !parquet_columns
+        .iter()
+        .any(|c| c == combined_filenames::SOURCE_COLUMN)
+    {
+        parquet_columns.push(combined_filenames::SOURCE_COLUMN.to_string());
+    }
+
+    let mut outputs = Vec::new();
+    for format in formats {
+        let Some(filename) = combined_filename(*format, prefix) else {
+            debug!("No combined output for {} format", format);
+            continue;
+        };
+        let mut path = output_dir.join(filename);
+        let writer: Box<dyn OutputWriter + Send> = match format {
+            // Split outputs continue after the earlier run's parts when appending
+            _ if split_limits.applies_to(*format) => {
+                let columns = if *format == OutputFormat::Parquet {
+                    &parquet_columns
+                } else {
+                    csv_columns
+                };
+                let open = if append {
+                    RollingWriter::append
+                } else {
+                    RollingWriter::new
+                };
+                Box::new(open(
+                    &path,
+                    *format,
+                    columns,
+                    csv_bom_enabled,
+                    number_format,
+                    split_limits,
+                )?)
+            }
+            OutputFormat::Csv if append => Box::new(
+                CsvWriter::append(&path, csv_columns.to_vec(), csv_bom_enabled)?
+                    .with_number_format(number_format),
+            ),
+            OutputFormat::Parquet if append => {
+                path = next_free_part(&path);
+                Box::new(ParquetWriter::new(&path, &parquet_columns)?)
+            }
+            OutputFormat::Raven | OutputFormat::Kaleidoscope if append => {
+                warn!(
+                    "Combined {} output cannot be appended to; replacing {}",
+                    format,
+                    path.display()
+                );
+                if *format == OutputFormat::Raven {
+                    Box::new(
+                        RavenWriter::new(&path)?
+                            .multi_file()
+                            .with_number_format(number_format),
+                    )
+                } else {
+                    Box::new(KaleidoscopeWriter::new(&path)?.with_number_format(number_format))
+                }
+            }
+            OutputFormat::Csv => Box::new(
+                CsvWriter::new(&path, csv_columns.to_vec(), csv_bom_enabled)?
+                    .with_number_format(number_format),
+            ),
+            OutputFormat::Raven => Box::new(
+                RavenWriter::new(&path)?
+                    .multi_file()
+                    .with_number_format(number_format),
+            ),
+            OutputFormat::Kaleidoscope => {
+                Box::new(KaleidoscopeWriter::new(&path)?.with_number_format(number_format))
+            }
+            OutputFormat::Parquet => Box::new(ParquetWriter::new(&path, &parquet_columns)?),
+            OutputFormat::BirdnetCsv
+            | OutputFormat::Audacity
+            | OutputFormat::Json
+            | OutputFormat::Sqlite => continue,
+        };
+        debug!("Writing combined {} output: {}", format, path.display());
+        outputs.push((path, writer));
+    }
+    Ok(outputs)
+}
+
+/// Append each received batch to every writer, then finalize them.
+fn run_combiner(
+    mut writers: Vec<Box<dyn OutputWriter + Send>>,
+    rx: &Receiver<FileBatch>,
+) -> Result<()> {
+    // Returning early drops the receiver, so senders see CombinerStopped
+    while let Ok((file, detections, audio_duration_secs)) = rx.recv() {
+        for writer in &mut writers {
+            for detection in &detections {
+                writer.write_detection(detection)?;
+            }
+            writer.end_file(&file, audio_duration_secs)?;
+            writer.flush()?;
+        }
+    }
+
+    for writer in &mut writers {
+        writer.finalize()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_combined_filename_uses_prefix() {
+        assert_eq!(
+            combined_filename(OutputFormat::Csv, "BirdNET").as_deref(),
+            Some(combined_filenames::CSV)
+        );
+        assert_eq!(
+            combined_filename(OutputFormat::Parquet, "Site1").as_deref(),
+            Some("Site1_CombinedTable.parquet")
+        );
+        assert!(combined_filename(OutputFormat::Json, "BirdNET").is_none());
+    }
+
+    #[test]
+    fn test_combined_writer_streams_from_multiple_senders() {
+        let dir = TempDir::new().unwrap();
+        let writer = CombinedWriter::spawn(
+            dir.path(),
+            "BirdNET",
+            &[OutputFormat::Csv, OutputFormat::Json],
+            &[],
+            false,
+            NumberFormat::default(),
+            SplitLimits::default(),
+        )
+        .unwrap();
+        assert_eq!(writer.paths().len(), 1);
+
+        let workers: Vec<_> = ["a.wav", "b.wav", "c.wav"]
+            .into_iter()
+            .map(|file| {
+                let sender = writer.sender();
+                thread::spawn(move || {
+                    sender
+                        .send(
+                            Path::new(file),
+                            vec![
+                                Detection::test("Parus major_Great Tit", 0.0, file),
+                                Detection::test("Parus major_Great Tit", 3.0, file),
+                            ],
+                            60.0,
+                        )
+                        .unwrap();
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        let paths = writer.finish().unwrap();
+        let content = std::fs::read_to_string(&paths[0]).unwrap();
+        let lines: Vec<_> = content.lines().collect();
+        assert_eq!(lines.len(), 7);
+        for file in ["a.wav", "b.wav", "c.wav"] {
+            assert_eq!(lines.iter().filter(|l| l.contains(file)).count(), 2);
+        }
+    }
+}
@@ -1,17 +1,22 @@
 //! CSV output format writer.
 
 use crate::constants::UTF8_BOM;
-use crate::constants::confidence::DECIMAL_PLACES;
+use crate::constants::format_version;
 use crate::error::Result;
-use crate::output::{Detection, OutputWriter};
+use crate::output::writer::{CountingWriter, open_append};
+use crate::output::{Detection, NumberFormat, OutputWriter};
 use std::fs::File;
 use std::io::{BufWriter, Write};
 use std::path::Path;
 
 /// CSV format output writer.
 pub struct CsvWriter {
-    writer: BufWriter<File>,
+    writer: BufWriter<CountingWriter>,
     include_columns: Vec<String>,
+    /// Whether the header is already present (appending to an existing file).
+    header_written: bool,
+    /// Decimal places of confidences and times.
+    number_format: NumberFormat,
 }
 
 impl CsvWriter {
@@ -23,8 +28,16 @@ impl CsvWriter {
     /// * `include_columns` - Additional columns to include in output
     /// * `include_bom` - Whether to write UTF-8 BOM for Excel compatibility
     pub fn new(path: &Path, include_columns: Vec<String>, include_bom: bool) -> Result<Self> {
-        let file = File::create(path)?;
-        let mut writer = BufWriter::new(file);
+        Self::from_writer(Box::new(File::create(path)?), include_columns, include_bom)
+    }
+
+    /// Create a CSV writer over any sink (e.g. stdout).
+    pub fn from_writer(
+        sink: Box<dyn Write + Send>,
+        include_columns: Vec<String>,
+        include_bom: bool,
+    ) -> Result<Self> {
+        let mut writer = BufWriter::new(CountingWriter::new(sink));
 
         // Write UTF-8 BOM for Excel compatibility (unless disabled)
         if include_bom {
@@ -34,35 +47,70 @@ impl CsvWriter {
         Ok(Self {
             writer,
             include_columns,
+            header_written: false,
+            number_format: NumberFormat::default(),
         })
     }
+
+    /// Open a CSV writer that appends to `path`, creating it if missing.
+    ///
+    /// An existing file must have the same header; its header is not written
+    /// again. The BOM is only written to a new file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or its header differs.
+    pub fn append(path: &Path, include_columns: Vec<String>, include_bom: bool) -> Result<Self> {
+        let header = header_line(&include_columns);
+        let (file, has_header) = open_append(path, &header)?;
+        let mut writer =
+            Self::from_writer(Box::new(file), include_columns, include_bom && !has_header)?;
+        writer.header_written = has_header;
+        Ok(writer)
+    }
+
+    /// Write confidences and times with the decimal places of `format`.
+    #[must_use]
+    pub const fn with_number_format(mut self, format: NumberFormat) -> Self {
+        self.number_format = format;
+        self
+    }
+}
+
+/// Header row for the core columns plus `include_columns`.
+fn header_line(include_columns: &[String]) -> String {
+    let mut header = "Start (s),End (s),Scientific name,Common name,Confidence,File".to_string();
+
+    for col in include_columns {
+        header.push(',');
+        header.push_str(col);
+    }
+    header
 }
 
 impl OutputWriter for CsvWriter {
     fn write_header(&mut self) -> Result<()> {
-        let mut header =
-            "Start (s),End (s),Scientific name,Common name,Confidence,File".to_string();
-
-        for col in &self.include_columns {
-            header.push(',');
-            header.push_str(col);
+        if self.header_written {
+            return Ok(());
         }
-
-        writeln!(self.writer, "{header}")?;
+        writeln!(self.writer, "{}", header_line(&self.include_columns))?;
+        self.header_written = true;
         Ok(())
     }
 
     fn write_detection(&mut self, detection: &Detection) -> Result<()> {
+        let format = self.number_format;
         write!(
             self.writer,
-            "{:.1},{:.1},{},{},{:.decimal$},{}",
+            "{:.time$},{:.time$},{},{},{:.decimal$},{}",
             detection.start_time,
             detection.end_time,
             escape_csv(&detection.scientific_name),
             escape_csv(&detection.common_name),
             detection.confidence,
             escape_csv(&detection.file_path.display().to_string()),
-            decimal = DECIMAL_PLACES,
+            time = format.time_places(),
+            decimal = format.confidence_places(),
         )?;
 
         for col in &self.include_columns {
@@ -108,6 +156,87 @@ impl OutputWriter for CsvWriter {
                         write!(self.writer, "{}", escape_csv(list))?;
                     }
                 }
+                "annotation" => {
+                    if let Some(ref annotation) = detection.metadata.annotation {
+                        write!(self.writer, "{}", escape_csv(annotation))?;
+                    }
+                }
+                "fingerprint" => {
+                    if let Some(ref fingerprint) = detection.metadata.fingerprint {
+                        write!(self.writer, "{fingerprint}")?;
+                    }
+                }
+                "device" => {
+                    if let Some(ref device) = detection.metadata.device {
+                        write!(self.writer, "{}", escape_csv(device))?;
+                    }
+                }
+                "model_hash" => {
+                    if let Some(ref hash) = detection.metadata.model_hash {
+                        write!(self.writer, "{hash}")?;
+                    }
+                }
+                "processed_at" => {
+                    if let Some(ref processed_at) = detection.metadata.processed_at {
+                        write!(self.writer, "{processed_at}")?;
+                    }
+                }
+                "recorder_model" => {
+                    if let Some(ref model) = detection.metadata.recorder_model {
+                        write!(self.writer, "{}", escape_csv(model))?;
+                    }
+                }
+                "recorder_serial" => {
+                    if let Some(ref serial) = detection.metadata.recorder_serial {
+                        write!(self.writer, "{}", escape_csv(serial))?;
+                    }
+                }
+                "recorder_gain" => {
+                    if let Some(ref gain) = detection.metadata.recorder_gain {
+                        write!(self.writer, "{}", escape_csv(gain))?;
+                    }
+                }
+                "recorder_temperature" => {
+                    if let Some(temperature) = detection.metadata.recorder_temperature {
+                        write!(self.writer, "{temperature}")?;
+                    }
+                }
+                "recorder_schedule" => {
+                    if let Some(ref schedule) = detection.metadata.recorder_schedule {
+                        write!(self.writer, "{}", escape_csv(schedule))?;
+                    }
+                }
+                "station_id" => {
+                    if let Some(ref id) = detection.metadata.station_id {
+                        write!(self.writer, "{}", escape_csv(id))?;
+                    }
+                }
+                "station_name" => {
+                    if let Some(ref name) = detection.metadata.station_name {
+                        write!(self.writer, "{}", escape_csv(name))?;
+                    }
+                }
+                "channel" => {
+                    if let Some(channel) = detection.metadata.channel {
+                        write!(self.writer, "{channel}")?;
+                    }
+                }
+                "datetime_start" => {
+                    if let Some(ref datetime) = detection.metadata.datetime_start {
+                        write!(self.writer, "{datetime}")?;
+                    }
+                }
+                "datetime_end" => {
+                    if let Some(ref datetime) = detection.metadata.datetime_end {
+                        write!(self.writer, "{datetime}")?;
+                    }
+                }
+                "sensitive" => {
+                    if let Some(sensitive) = detection.metadata.sensitive {
+                        write!(self.writer, "{sensitive}")?;
+                    }
+                }
+                format_version::COLUMN => write!(self.writer, "{}", format_version::CSV)?,
                 _ => {}
             }
         }
@@ -116,14 +245,23 @@ impl OutputWriter for CsvWriter {
         Ok(())
     }
 
+    fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
     fn finalize(&mut self) -> Result<()> {
         self.writer.flush()?;
         Ok(())
     }
+
+    fn bytes_written(&self) -> u64 {
+        self.writer.get_ref().count() + self.writer.buffer().len() as u64
+    }
 }
 
 /// Escape a value for CSV output.
-fn escape_csv(value: &str) -> String {
+pub(super) fn escape_csv(value: &str) -> String {
     if value.contains(',') || value.contains('"') || value.contains('\n') {
         format!("\"{}\"", value.replace('"', "\"\""))
     } else {
@@ -161,6 +299,33 @@ mod tests {
         assert!(contents.contains("0.8542"));
     }
 
+    #[test]
+    fn test_csv_writer_format_version_column() {
+        let file = NamedTempFile::new().unwrap();
+        let mut writer =
+            CsvWriter::new(file.path(), vec![format_version::COLUMN.to_string()], false).unwrap();
+        writer.write_header().unwrap();
+        let detection = Detection::from_label(
+            "Passer domesticus_House Sparrow",
+            0.8542,
+            0.0,
+            3.0,
+            PathBuf::from("/path/to/audio.wav"),
+        );
+        writer.write_detection(&detection).unwrap();
+        writer.finalize().unwrap();
+
+        let contents = std::fs::read_to_string(file.path()).unwrap();
+        let mut lines = contents.lines();
+        assert!(lines.next().unwrap().ends_with(",File,format_version"));
+        assert!(
+            lines
+                .next()
+                .unwrap()
+                .ends_with(&format!(",{}", format_version::CSV))
+        );
+    }
+
     #[test]
     fn test_escape_csv() {
         assert_eq!(escape_csv("simple"), "simple");
@@ -199,4 +364,30 @@ mod tests {
         let content = String::from_utf8_lossy(&bytes);
         assert!(content.starts_with("Start (s),End (s)"));
     }
+
+    #[test]
+    fn test_csv_writer_append_keeps_single_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("combined.csv");
+        let detection = Detection::from_label(
+            "Parus major_Great Tit",
+            0.9,
+            0.0,
+            3.0,
+            PathBuf::from("a.wav"),
+        );
+
+        for _ in 0..2 {
+            let mut writer = CsvWriter::append(&path, vec![], true).unwrap();
+            writer.write_header().unwrap();
+            writer.write_detection(&detection).unwrap();
+            writer.finalize().unwrap();
+        }
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(&bytes[0..3], UTF8_BOM);
+        let content = String::from_utf8_lossy(&bytes[3..]);
+        assert_eq!(content.lines().count(), 3);
+        assert_eq!(content.matches("Start (s)").count(), 1);
+    }
 }
@@ -0,0 +1,317 @@
+//! DuckDB database output and SQL queries over detection results.
+//!
+//! Requires the `duckdb` cargo feature; without it, opening a database or
+//! running a query returns [`Error::DuckdbUnavailable`](crate::error::Error::DuckdbUnavailable).
+//!
+//! All detections of a run go into one `detections` table. Reprocessing a
+//! recording replaces its rows instead of duplicating them, and removes them
+//! if it no longer has detections.
+
+use crate::error::Result;
+use crate::output::OutputWriter;
+use std::path::Path;
+
+/// Table that receives detections.
+pub const DETECTIONS_TABLE: &str = "detections";
+
+/// Result of a SQL query.
+#[derive(Debug, Clone, Default)]
+pub struct QueryResult {
+    /// Column names.
+    pub columns: Vec<String>,
+    /// Rows as JSON values, one per column.
+    pub rows: Vec<Vec<serde_json::Value>>,
+}
+
+/// Open a DuckDB database as a run-wide output.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be opened or the table created,
+/// or `Error::DuckdbUnavailable` in builds without the `duckdb` feature.
+#[cfg(feature = "duckdb")]
+pub fn open_database_output(path: &Path) -> Result<Box<dyn OutputWriter + Send>> {
+    Ok(Box::new(imp::DuckdbWriter::new(path)?))
+}
+
+/// Open a DuckDB database as a run-wide output.
+///
+/// # Errors
+///
+/// Always returns `Error::DuckdbUnavailable` in builds without the `duckdb`
+/// feature.
+#[cfg(not(feature = "duckdb"))]
+pub fn open_database_output(_path: &Path) -> Result<Box<dyn OutputWriter + Send>> {
+    Err(crate::error::Error::DuckdbUnavailable)
+}
+
+/// Run `sql` against birda results at `source`.
+///
+/// `source` is a DuckDB database written by birda (opened read-only), a
+/// Parquet file, or a directory searched recursively for Parquet files (hive
+/// partitions such as `species=`/`date=` become columns). Parquet sources are
+/// exposed as a `detections` view.
+///
+/// # Errors
+///
+/// Returns an error if the source cannot be opened or the query fails, or
+/// `Error::DuckdbUnavailable` in builds without the `duckdb` feature.
+#[cfg(feature = "duckdb")]
+pub fn run_query(sql: &str, source: &Path) -> Result<QueryResult> {
+    imp::run_query(sql, source)
+}
+
+/// Run `sql` against birda results at `source`.
+///
+/// # Errors
+///
+/// Always returns `Error::DuckdbUnavailable` in builds without the `duckdb`
+/// feature.
+#[cfg(not(feature = "duckdb"))]
+pub fn run_query(_sql: &str, _source: &Path) -> Result<QueryResult> {
+    Err(crate::error::Error::DuckdbUnavailable)
+}
+
+/// Whether `path` names a DuckDB database rather than Parquet results.
+#[cfg_attr(not(feature = "duckdb"), allow(dead_code))]
+fn is_database_path(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("duckdb") || ext.eq_ignore_ascii_case("db"))
+}
+
+/// Quote a path as a SQL string literal.
+#[cfg_attr(not(feature = "duckdb"), allow(dead_code))]
+fn sql_string(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', "''"))
+}
+
+/// SQL defining the `detections` view over Parquet results at `source`.
+#[cfg_attr(not(feature = "duckdb"), allow(dead_code))]
+fn parquet_view_sql(source: &Path) -> String {
+    let pattern = if source.is_dir() {
+        source.join("**").join("*.parquet")
+    } else {
+        source.to_path_buf()
+    };
+    format!(
+        "CREATE VIEW {DETECTIONS_TABLE} AS SELECT * FROM read_parquet({}, \
+         hive_partitioning = true, union_by_name = true)",
+        sql_string(&pattern)
+    )
+}
+
+#[cfg(feature = "duckdb")]
+mod imp {
+    use super::{DETECTIONS_TABLE, QueryResult, is_database_path, parquet_view_sql};
+    use crate::error::{Error, Result};
+    use crate::output::{Detection, OutputWriter};
+    use duckdb::types::Value;
+    use duckdb::{AccessMode, Config, Connection, params};
+    use std::collections::BTreeSet;
+    use std::path::{Path, PathBuf};
+
+    fn duckdb_error(context: impl Into<String>) -> impl FnOnce(duckdb::Error) -> Error {
+        let context = context.into();
+        move |e| Error::Duckdb {
+            context,
+            reason: e.to_string(),
+        }
+    }
+
+    /// Writer that appends detections to the `detections` table.
+    pub struct DuckdbWriter {
+        conn: Connection,
+        path: PathBuf,
+        pending: Vec<Detection>,
+        /// Recordings ended since the last commit.
+        ended: Vec<PathBuf>,
+    }
+
+    impl DuckdbWriter {
+        pub fn new(path: &Path) -> Result<Self> {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)?;
+            }
+            let conn = Connection::open(path)
+                .map_err(duckdb_error(format!("opening {}", path.display())))?;
+            conn.execute_batch(&format!(
+                "CREATE TABLE IF NOT EXISTS {DETECTIONS_TABLE} (
+                    file VARCHAR NOT NULL,
+                    start_s FLOAT NOT NULL,
+                    end_s FLOAT NOT NULL,
+                    scientific_name VARCHAR NOT NULL,
+                    common_name VARCHAR NOT NULL,
+                    confidence FLOAT NOT NULL,
+                    lat DOUBLE,
+                    lon DOUBLE,
+                    week UTINYINT,
+                    model VARCHAR,
+                    annotation VARCHAR,
+                    fingerprint VARCHAR
+                )"
+            ))
+            .map_err(duckdb_error("creating detections table"))?;
+
+            Ok(Self {
+                conn,
+                path: path.to_path_buf(),
+                pending: Vec::new(),
+                ended: Vec::new(),
+            })
+        }
+
+        /// Replace the rows of every ended or pending file in one
+        /// transaction.
+        fn commit_pending(&mut self) -> Result<()> {
+            if self.pending.is_empty() && self.ended.is_empty() {
+                return Ok(());
+            }
+            let context = format!("writing to {}", self.path.display());
+            let tx = self
+                .conn
+                .transaction()
+                .map_err(duckdb_error(context.clone()))?;
+
+            let files: BTreeSet<String> = self
+                .ended
+                .iter()
+                .chain(self.pending.iter().map(|d| &d.file_path))
+                .map(|file| file.display().to_string())
+                .collect();
+            for file in &files {
+                tx.execute(
+                    &format!("DELETE FROM {DETECTIONS_TABLE} WHERE file = ?"),
+                    params![file],
+                )
+                .map_err(duckdb_error(context.clone()))?;
+            }
+
+            {
+                let mut appender = tx
+                    .appender(DETECTIONS_TABLE)
+                    .map_err(duckdb_error(context.clone()))?;
+                for d in &self.pending {
+                    appender
+                        .append_row(params![
+                            d.file_path.display().to_string(),
+                            d.start_time,
+                            d.end_time,
+                            d.scientific_name,
+                            d.common_name,
+                            d.confidence,
+                            d.metadata.lat,
+                            d.metadata.lon,
+                            d.metadata.week,
+                            d.metadata.model,
+                            d.metadata.annotation,
+                            d.metadata.fingerprint,
+                        ])
+                        .map_err(duckdb_error(context.clone()))?;
+                }
+                appender.flush().map_err(duckdb_error(context.clone()))?;
+            }
+
+            tx.commit().map_err(duckdb_error(context))?;
+            self.pending.clear();
+            self.ended.clear();
+            Ok(())
+        }
+    }
+
+    impl OutputWriter for DuckdbWriter {
+        fn write_detection(&mut self, detection: &Detection) -> Result<()> {
+            self.pending.push(detection.clone());
+            Ok(())
+        }
+
+        fn end_file(&mut self, file: &Path, _audio_duration_secs: f64) -> Result<()> {
+            self.ended.push(file.to_path_buf());
+            Ok(())
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            self.commit_pending()
+        }
+
+        fn finalize(&mut self) -> Result<()> {
+            self.commit_pending()
+        }
+    }
+
+    pub fn run_query(sql: &str, source: &Path) -> Result<QueryResult> {
+        let conn = if is_database_path(source) {
+            let config = Config::default()
+                .access_mode(AccessMode::ReadOnly)
+                .map_err(duckdb_error("configuring connection"))?;
+            Connection::open_with_flags(source, config)
+                .map_err(duckdb_error(format!("opening {}", source.display())))?
+        } else {
+            let conn = Connection::open_in_memory().map_err(duckdb_error("opening DuckDB"))?;
+            conn.execute_batch(&parquet_view_sql(source))
+                .map_err(duckdb_error(format!("reading {}", source.display())))?;
+            conn
+        };
+
+        let mut stmt = conn.prepare(sql).map_err(duckdb_error("preparing query"))?;
+        let mut rows = stmt.query([]).map_err(duckdb_error("running query"))?;
+        let columns = rows
+            .as_ref()
+            .map(|stmt| stmt.column_names())
+            .unwrap_or_default();
+
+        let mut result = QueryResult {
+            columns,
+            rows: Vec::new(),
+        };
+        while let Some(row) = rows.next().map_err(duckdb_error("reading results"))? {
+            let values = (0..result.columns.len())
+                .map(|i| row.get::<_, Value>(i).map(to_json))
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(duckdb_error("reading results"))?;
+            result.rows.push(values);
+        }
+        Ok(result)
+    }
+
+    /// Convert a DuckDB value to JSON; types without a JSON equivalent are
+    /// rendered as text.
+    fn to_json(value: Value) -> serde_json::Value {
+        use serde_json::json;
+        match value {
+            Value::Null => serde_json::Value::Null,
+            Value::Boolean(b) => json!(b),
+            Value::TinyInt(n) => json!(n),
+            Value::SmallInt(n) => json!(n),
+            Value::Int(n) => json!(n),
+            Value::BigInt(n) => json!(n),
+            Value::UTinyInt(n) => json!(n),
+            Value::USmallInt(n) => json!(n),
+            Value::UInt(n) => json!(n),
+            Value::UBigInt(n) => json!(n),
+            Value::Float(n) => json!(n),
+            Value::Double(n) => json!(n),
+            Value::Text(s) => json!(s),
+            other => json!(format!("{other:?}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_database_path() {
+        assert!(is_database_path(Path::new("results.duckdb")));
+        assert!(is_database_path(Path::new("results.DB")));
+        assert!(!is_database_path(Path::new("results/")));
+        assert!(!is_database_path(Path::new("rec.BirdNET.results.parquet")));
+    }
+
+    #[test]
+    fn test_parquet_view_sql_escapes_quotes() {
+        let sql = parquet_view_sql(Path::new("/data/o'brien.parquet"));
+        assert!(sql.contains("read_parquet('/data/o''brien.parquet'"));
+        assert!(sql.starts_with("CREATE VIEW detections"));
+    }
+}
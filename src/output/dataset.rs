@@ -0,0 +1,243 @@
+//! Hive-partitioned Parquet dataset output.
+//!
+//! Instead of one Parquet file per recording, detections of a whole run are
+//! written to a single dataset directory partitioned by species and date:
+//!
+//! ```text
+//! BirdNET_Dataset/
+//!   _common_metadata
+//!   species=Parus_major/date=2024-05-01/part-0.parquet
+//!   species=Turdus_merula/date=2024-05-01/part-0.parquet
+//! ```
+//!
+//! DuckDB (`read_parquet('BirdNET_Dataset/**/*.parquet', hive_partitioning = true)`)
+//! and Spark read this directly and prune partitions on species and date
+//! filters. Detections are buffered per partition and written when the run
+//! finishes, or earlier at a file boundary once the buffer grows large, so
+//! each partition gets few files. New parts never overwrite existing ones,
+//! which lets several runs add to one dataset.
+
+use crate::constants::{combined_filenames, parquet_dataset};
+use crate::error::Result;
+use crate::output::{Detection, OutputWriter, ParquetWriter};
+use crate::utils::date::recording_timestamp;
+use chrono::{NaiveDateTime, TimeDelta};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// Dataset directory name for the combined output `prefix`.
+pub fn dataset_dirname(prefix: &str) -> String {
+    super::combined::with_prefix(combined_filenames::PARQUET_DATASET, prefix)
+}
+
+/// Partition of a detection: sanitized species and detection date.
+type PartitionKey = (String, String);
+
+/// Writer for a hive-partitioned Parquet dataset.
+pub struct ParquetDatasetWriter {
+    root: PathBuf,
+    columns: Vec<String>,
+    partitions: BTreeMap<PartitionKey, Vec<Detection>>,
+    buffered: usize,
+    /// Recording start per input file, looked up once per file.
+    recording_starts: HashMap<PathBuf, Option<NaiveDateTime>>,
+}
+
+impl ParquetDatasetWriter {
+    /// Create a dataset writer rooted at `root`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the dataset directory cannot be created.
+    pub fn new(root: &Path, columns: &[String]) -> Result<Self> {
+        std::fs::create_dir_all(root)?;
+        Ok(Self {
+            root: root.to_path_buf(),
+            columns: columns.to_vec(),
+            partitions: BTreeMap::new(),
+            buffered: 0,
+            recording_starts: HashMap::new(),
+        })
+    }
+
+    /// Partition key for a detection.
+    fn partition_for(&mut self, detection: &Detection) -> PartitionKey {
+        let start = *self
+            .recording_starts
+            .entry(detection.file_path.clone())
+            .or_insert_with(|| recording_timestamp(&detection.file_path));
+
+        #[allow(clippy::cast_possible_truncation)]
+        let offset =
+            TimeDelta::milliseconds((f64::from(detection.start_time) * 1000.0).round() as i64);
+        let date = start.map_or_else(
+            || parquet_dataset::NULL_PARTITION.to_string(),
+            |start| (start + offset).format("%Y-%m-%d").to_string(),
+        );
+
+        (partition_value(&detection.scientific_name), date)
+    }
+
+    /// Write every buffered partition to a new part file.
+    fn write_partitions(&mut self) -> Result<()> {
+        for ((species, date), detections) in std::mem::take(&mut self.partitions) {
+            let dir = self
+                .root
+                .join(format!("species={species}"))
+                .join(format!("date={date}"));
+            std::fs::create_dir_all(&dir)?;
+            let path = next_part_path(&dir);
+            debug!(
+                "Writing {} detections to {}",
+                detections.len(),
+                path.display()
+            );
+
+            let mut writer = ParquetWriter::new(&path, &self.columns)?;
+            for detection in detections {
+                writer.write_detection(detection)?;
+            }
+            writer.finalize()?;
+        }
+        self.buffered = 0;
+        Ok(())
+    }
+
+    /// Write the schema summary and drop a stale row group summary.
+    fn write_dataset_metadata(&self) -> Result<()> {
+        // A Parquet file without row groups carries just the schema. It is
+        // written beside the old one and renamed over it, so readers never
+        // see a partial file
+        let common_metadata = self.root.join(parquet_dataset::COMMON_METADATA);
+        let partial = self
+            .root
+            .join(format!("{}.tmp", parquet_dataset::COMMON_METADATA));
+        let mut summary = ParquetWriter::new(&partial, &self.columns)?;
+        summary.finalize()?;
+        std::fs::rename(&partial, &common_metadata)?;
+
+        // `_metadata` lists row groups of specific files, so readers that trust
+        // it would silently miss the parts just added
+        let metadata = self.root.join(parquet_dataset::METADATA);
+        if metadata.exists() {
+            warn!(
+                "Removing stale {} (it does not list the new parts)",
+                metadata.display()
+            );
+            std::fs::remove_file(&metadata)?;
+        }
+        Ok(())
+    }
+}
+
+impl OutputWriter for ParquetDatasetWriter {
+    fn write_detection(&mut self, detection: &Detection) -> Result<()> {
+        let key = self.partition_for(detection);
+        self.partitions
+            .entry(key)
+            .or_default()
+            .push(detection.clone());
+        self.buffered += 1;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        // Called at file boundaries; only spill when the buffer is large so
+        // partitions are not fragmented into one file per recording
+        if self.buffered >= parquet_dataset::MAX_BUFFERED_ROWS {
+            self.write_partitions()?;
+        }
+        Ok(())
+    }
+
+    fn finalize(&mut self) -> Result<()> {
+        self.write_partitions()?;
+        self.write_dataset_metadata()
+    }
+}
+
+/// Sanitize a value for use in a `key=value` partition directory name.
+///
+/// Spaces and characters that are unsafe in paths become `_`; the exact value
+/// is still available in the data columns.
+fn partition_value(value: &str) -> String {
+    let sanitized: String = value
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    match sanitized.replace("..", "__").as_str() {
+        "" => parquet_dataset::NULL_PARTITION.to_string(),
+        value => value.to_string(),
+    }
+}
+
+/// First unused `part-N.parquet` path in a partition directory.
+fn next_part_path(dir: &Path) -> PathBuf {
+    (0..)
+        .map(|n| dir.join(format!("part-{n}.parquet")))
+        .find(|path| !path.exists())
+        .unwrap_or_else(|| dir.join("part-0.parquet"))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_partition_value() {
+        assert_eq!(partition_value("Parus major"), "Parus_major");
+        assert_eq!(partition_value("../etc"), "___etc");
+        assert_eq!(partition_value(""), parquet_dataset::NULL_PARTITION);
+    }
+
+    #[test]
+    fn test_dataset_partitions_by_species_and_date() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().join("dataset");
+        let existing = root.join("species=Parus_major/date=2024-05-01");
+        std::fs::create_dir_all(&existing).unwrap();
+        std::fs::write(existing.join("part-0.parquet"), b"earlier run").unwrap();
+        std::fs::write(root.join(parquet_dataset::METADATA), b"stale").unwrap();
+
+        let mut writer = ParquetDatasetWriter::new(&root, &[]).unwrap();
+        writer.write_header().unwrap();
+        // Recording starts at 23:59:00, so the second detection is on the next day
+        let file = "/data/site/20240501_235900.wav";
+        writer
+            .write_detection(&Detection::test("Parus major_Great Tit", 0.0, file))
+            .unwrap();
+        writer
+            .write_detection(&Detection::test("Parus major_Great Tit", 90.0, file))
+            .unwrap();
+        writer
+            .write_detection(&Detection::test(
+                "Turdus merula_Eurasian Blackbird",
+                3.0,
+                file,
+            ))
+            .unwrap();
+        writer.flush().unwrap();
+        writer.finalize().unwrap();
+
+        assert!(existing.join("part-1.parquet").exists());
+        assert!(
+            root.join("species=Parus_major/date=2024-05-02/part-0.parquet")
+                .exists()
+        );
+        assert!(
+            root.join("species=Turdus_merula/date=2024-05-01/part-0.parquet")
+                .exists()
+        );
+        assert!(root.join(parquet_dataset::COMMON_METADATA).exists());
+        assert!(!root.join(parquet_dataset::METADATA).exists());
+    }
+}
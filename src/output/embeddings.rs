@@ -0,0 +1,356 @@
+//! Segment embedding output.
+//!
+//! Embeddings are the backbone's feature vectors before the classification
+//! head, used for clustering recordings or training custom classifiers.
+//! Parquet keeps the segment times next to each vector; `.npy` holds only the
+//! float32 matrix, with rows in segment order.
+
+use arrow::array::{ArrayRef, FixedSizeListArray, Float32Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::config::EmbeddingFormat;
+use crate::error::{Error, Result};
+
+/// Embedding of one analyzed segment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentEmbedding {
+    /// Segment start in seconds.
+    pub start_time: f32,
+    /// Segment end in seconds.
+    pub end_time: f32,
+    /// Embedding vector.
+    pub values: Vec<f32>,
+}
+
+/// Rows buffered per Parquet row group, bounding memory for long recordings.
+const ROW_GROUP_ROWS: usize = 4096;
+
+/// Writes the embeddings of one recording to a file, batch by batch.
+///
+/// The file is created with the first batch, once the vector length is known.
+/// Call [`finish`](Self::finish) to complete it; a writer dropped before that
+/// leaves an incomplete file behind.
+pub struct EmbeddingWriter {
+    path: PathBuf,
+    format: EmbeddingFormat,
+    source: String,
+    dimensions: Option<usize>,
+    rows: usize,
+    sink: Option<Sink>,
+}
+
+/// Open output file of an [`EmbeddingWriter`].
+enum Sink {
+    Parquet(ArrowWriter<File>, Arc<Schema>),
+    Npy(BufWriter<File>),
+}
+
+impl EmbeddingWriter {
+    /// Create a writer for the embeddings of `source`, written to `path` in
+    /// `format`.
+    #[must_use]
+    pub fn new(path: &Path, format: EmbeddingFormat, source: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            format,
+            source: source.display().to_string(),
+            dimensions: None,
+            rows: 0,
+            sink: None,
+        }
+    }
+
+    /// Number of embeddings written so far.
+    #[must_use]
+    pub const fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Length of the embedding vectors, or 0 before any were written.
+    #[must_use]
+    pub fn dimensions(&self) -> usize {
+        self.dimensions.unwrap_or(0)
+    }
+
+    /// Append `embeddings` to the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the vectors differ in length from the ones already
+    /// written or the file cannot be written.
+    pub fn write(&mut self, embeddings: &[SegmentEmbedding]) -> Result<()> {
+        let Some(first) = embeddings.first() else {
+            return Ok(());
+        };
+        let dimensions = *self.dimensions.get_or_insert(first.values.len());
+        if embeddings.iter().any(|e| e.values.len() != dimensions) {
+            return Err(Error::Internal {
+                message: "embedding vectors differ in length".to_string(),
+            });
+        }
+
+        let sink = self.take_sink()?;
+        match self.sink.insert(sink) {
+            Sink::Parquet(writer, schema) => {
+                let batch = embeddings_batch(schema, &self.source, embeddings, dimensions)?;
+                writer.write(&batch).map_err(|e| Error::ParquetWrite {
+                    context: "Failed to write embeddings record batch".to_string(),
+                    source: e,
+                })?;
+            }
+            Sink::Npy(writer) => {
+                for value in embeddings.iter().flat_map(|e| &e.values) {
+                    writer.write_all(&value.to_le_bytes())?;
+                }
+            }
+        }
+        self.rows += embeddings.len();
+        Ok(())
+    }
+
+    /// Complete the file, creating an empty one if nothing was written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn finish(mut self) -> Result<()> {
+        let (rows, dimensions) = (self.rows, self.dimensions());
+        match self.take_sink()? {
+            Sink::Parquet(writer, _) => {
+                writer.close().map_err(|e| Error::ParquetWrite {
+                    context: "Failed to close embeddings Parquet writer".to_string(),
+                    source: e,
+                })?;
+            }
+            Sink::Npy(writer) => {
+                // The header was written before the rows were counted
+                let mut file = writer.into_inner().map_err(|e| e.into_error())?;
+                file.seek(SeekFrom::Start(0))?;
+                file.write_all(&npy_header(rows, dimensions))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Take the output file, creating it on first use.
+    fn take_sink(&mut self) -> Result<Sink> {
+        self.sink.take().map_or_else(|| self.create(), Ok)
+    }
+
+    /// Create the output file and write its header.
+    fn create(&self) -> Result<Sink> {
+        let dimensions = self.dimensions();
+        match self.format {
+            EmbeddingFormat::Parquet => {
+                let file = File::create(&self.path).map_err(|e| Error::ParquetFileCreate {
+                    path: self.path.clone(),
+                    source: e,
+                })?;
+                let schema = embeddings_schema(dimensions);
+                let props = WriterProperties::builder()
+                    .set_compression(Compression::SNAPPY)
+                    .set_max_row_group_size(ROW_GROUP_ROWS)
+                    .build();
+                let writer =
+                    ArrowWriter::try_new(file, schema.clone(), Some(props)).map_err(|e| {
+                        Error::ParquetWrite {
+                            context: "Failed to initialize embeddings Parquet writer".to_string(),
+                            source: e,
+                        }
+                    })?;
+                Ok(Sink::Parquet(writer, schema))
+            }
+            EmbeddingFormat::Npy => {
+                let mut writer = BufWriter::new(File::create(&self.path)?);
+                // Rewritten with the row count once all rows are written
+                writer.write_all(&npy_header(0, dimensions))?;
+                Ok(Sink::Npy(writer))
+            }
+        }
+    }
+}
+
+/// Schema with `file`, `start_s`, `end_s` and a fixed-size `embedding` list
+/// column.
+fn embeddings_schema(dimensions: usize) -> Arc<Schema> {
+    let item = Arc::new(Field::new("item", DataType::Float32, false));
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    let size = dimensions as i32;
+    Arc::new(Schema::new(vec![
+        Field::new("file", DataType::Utf8, false),
+        Field::new("start_s", DataType::Float32, false),
+        Field::new("end_s", DataType::Float32, false),
+        Field::new("embedding", DataType::FixedSizeList(item, size), false),
+    ]))
+}
+
+/// Record batch of `embeddings` from `source` in `schema`.
+fn embeddings_batch(
+    schema: &Arc<Schema>,
+    source: &str,
+    embeddings: &[SegmentEmbedding],
+    dimensions: usize,
+) -> Result<RecordBatch> {
+    let item = Arc::new(Field::new("item", DataType::Float32, false));
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    let size = dimensions as i32;
+    let values: Float32Array = embeddings
+        .iter()
+        .flat_map(|e| e.values.iter().copied())
+        .collect();
+    let vectors = FixedSizeListArray::try_new(item, size, Arc::new(values), None).map_err(|e| {
+        Error::ParquetWrite {
+            context: "Failed to build embedding column".to_string(),
+            source: e.into(),
+        }
+    })?;
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from(vec![source; embeddings.len()])),
+        Arc::new(
+            embeddings
+                .iter()
+                .map(|e| e.start_time)
+                .collect::<Float32Array>(),
+        ),
+        Arc::new(
+            embeddings
+                .iter()
+                .map(|e| e.end_time)
+                .collect::<Float32Array>(),
+        ),
+        Arc::new(vectors),
+    ];
+
+    RecordBatch::try_new(schema.clone(), columns).map_err(|e| Error::ParquetWrite {
+        context: "Failed to build embeddings record batch".to_string(),
+        source: e.into(),
+    })
+}
+
+/// `.npy` version 1.0 header for a float32 matrix of `rows` x `columns`.
+///
+/// The header is padded with spaces so the data starts 64-byte aligned, to
+/// the same length for any row count, so it can be rewritten in place once
+/// the rows are counted.
+fn npy_header(rows: usize, columns: usize) -> Vec<u8> {
+    const MAGIC: &[u8] = b"\x93NUMPY\x01\x00";
+    // Magic, version and the 2-byte header length precede the dictionary
+    const PREFIX_LEN: usize = 10;
+
+    let dict_for = |rows: usize| {
+        format!("{{'descr': '<f4', 'fortran_order': False, 'shape': ({rows}, {columns}), }}")
+    };
+    let padded = (PREFIX_LEN + dict_for(usize::MAX).len() + 1).next_multiple_of(64);
+    let mut dict = dict_for(rows);
+    dict.push_str(&" ".repeat(padded - PREFIX_LEN - dict.len() - 1));
+    dict.push('\n');
+
+    let mut header = MAGIC.to_vec();
+    #[allow(clippy::cast_possible_truncation)]
+    header.extend_from_slice(&(dict.len() as u16).to_le_bytes());
+    header.extend_from_slice(dict.as_bytes());
+    header
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn embeddings() -> Vec<SegmentEmbedding> {
+        vec![
+            SegmentEmbedding {
+                start_time: 0.0,
+                end_time: 3.0,
+                values: vec![1.0, 2.0],
+            },
+            SegmentEmbedding {
+                start_time: 3.0,
+                end_time: 6.0,
+                values: vec![3.0, 4.0],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_npy_header_is_aligned() {
+        let header = npy_header(2, 1024);
+        assert_eq!(header.len() % 64, 0);
+        assert!(header.starts_with(b"\x93NUMPY\x01\x00"));
+        assert_eq!(header.last(), Some(&b'\n'));
+        let dict = String::from_utf8_lossy(&header[10..]);
+        assert!(dict.contains("'shape': (2, 1024)"));
+    }
+
+    /// Write `embeddings` to `path` one segment per batch.
+    fn write_all(
+        path: &Path,
+        format: EmbeddingFormat,
+        embeddings: &[SegmentEmbedding],
+    ) -> Result<()> {
+        let mut writer = EmbeddingWriter::new(path, format, Path::new("a.wav"));
+        for embedding in embeddings {
+            writer.write(std::slice::from_ref(embedding))?;
+        }
+        writer.finish()
+    }
+
+    #[test]
+    fn test_write_embeddings_npy() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.npy");
+        write_all(&path, EmbeddingFormat::Npy, &embeddings()).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        let header = npy_header(2, 2);
+        assert_eq!(&bytes[..header.len()], header.as_slice());
+        let data = &bytes[header.len()..];
+        assert_eq!(data.len(), 4 * 4);
+        assert_eq!(&data[12..], &4.0f32.to_le_bytes());
+    }
+
+    #[test]
+    fn test_npy_header_length_ignores_rows() {
+        assert_eq!(
+            npy_header(0, 1024).len(),
+            npy_header(usize::MAX, 1024).len()
+        );
+    }
+
+    #[test]
+    fn test_writer_counts_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = EmbeddingWriter::new(
+            &dir.path().join("a.parquet"),
+            EmbeddingFormat::Parquet,
+            Path::new("a.wav"),
+        );
+        writer.write(&embeddings()).unwrap();
+        writer.write(&embeddings()).unwrap();
+        assert_eq!((writer.rows(), writer.dimensions()), (4, 2));
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn test_write_embeddings_rejects_ragged_vectors() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut ragged = embeddings();
+        ragged[1].values.push(5.0);
+        assert!(
+            write_all(
+                &dir.path().join("a.parquet"),
+                EmbeddingFormat::Parquet,
+                &ragged
+            )
+            .is_err()
+        );
+    }
+}
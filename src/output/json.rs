@@ -1,23 +1,30 @@
 //! JSON output format writer.
 
 use crate::error::Result;
-use crate::output::{Detection, OutputWriter};
+use crate::output::{Detection, NumberFormat, OutputWriter, Station};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::fs::File;
-use std::io::BufWriter;
+use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
 
 /// JSON result file structure.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct JsonResultFile {
+    /// Version of the JSON result format (0 in files written before it was
+    /// recorded).
+    #[serde(default)]
+    pub format_version: u32,
     /// Source audio file name.
     pub source_file: String,
     /// Analysis timestamp.
     pub analysis_date: DateTime<Utc>,
     /// Model used for analysis.
     pub model: String,
+    /// Station that produced the results.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub station: Option<Station>,
     /// Analysis settings.
     pub settings: JsonSettings,
     /// Detection results.
@@ -57,6 +64,30 @@ pub struct JsonDetection {
     pub common_name: String,
     /// Confidence score.
     pub confidence: f32,
+    /// Perceptual fingerprint of the detection's audio segment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    /// Execution provider that ran inference.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device: Option<String>,
+    /// Hex SHA-256 hash of the model file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_hash: Option<String>,
+    /// When the recording finished processing (RFC 3339, UTC).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub processed_at: Option<String>,
+    /// Whether the species is on a configured sensitive species list.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sensitive: Option<bool>,
+    /// Audio channel analyzed (1-based), with `--channel`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<u32>,
+    /// Absolute start time (ISO 8601 local time), with `--wall-clock`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datetime_start: Option<String>,
+    /// Absolute end time (ISO 8601 local time), with `--wall-clock`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datetime_end: Option<String>,
 }
 
 /// Summary statistics.
@@ -92,6 +123,12 @@ pub struct JsonResultWriter {
     week: Option<u8>,
     /// Audio file duration in seconds (actual, not derived from detections).
     audio_duration: f32,
+    /// Sink to write to instead of `output_path` (e.g. stdout).
+    sink: Option<Box<dyn Write + Send>>,
+    /// Decimal places of confidences and times.
+    number_format: NumberFormat,
+    /// Station recorded in the result file, if one is configured.
+    station: Option<Station>,
 }
 
 impl JsonResultWriter {
@@ -131,9 +168,33 @@ impl JsonResultWriter {
             lon,
             week,
             audio_duration,
+            sink: None,
+            number_format: NumberFormat::default(),
+            station: None,
         })
     }
 
+    /// Write the result to `sink` instead of the output path.
+    #[must_use]
+    pub fn with_sink(mut self, sink: Box<dyn Write + Send>) -> Self {
+        self.sink = Some(sink);
+        self
+    }
+
+    /// Round confidences and times to the decimal places of `format`, if set.
+    #[must_use]
+    pub const fn with_number_format(mut self, format: NumberFormat) -> Self {
+        self.number_format = format;
+        self
+    }
+
+    /// Record `station` in the result file.
+    #[must_use]
+    pub fn with_station(mut self, station: Option<Station>) -> Self {
+        self.station = station;
+        self
+    }
+
     /// Compute summary from detections.
     fn compute_summary(&self) -> JsonSummary {
         let unique_species: HashSet<&str> = self
@@ -157,22 +218,33 @@ impl OutputWriter for JsonResultWriter {
     }
 
     fn finalize(&mut self) -> Result<()> {
+        let format = self.number_format;
         let json_detections: Vec<JsonDetection> = self
             .detections
             .iter()
             .map(|d| JsonDetection {
-                start_time: d.start_time,
-                end_time: d.end_time,
+                start_time: format.json_time(d.start_time),
+                end_time: format.json_time(d.end_time),
                 scientific_name: d.scientific_name.clone(),
                 common_name: d.common_name.clone(),
-                confidence: d.confidence,
+                confidence: format.json_confidence(d.confidence),
+                fingerprint: d.metadata.fingerprint.clone(),
+                device: d.metadata.device.clone(),
+                model_hash: d.metadata.model_hash.clone(),
+                processed_at: d.metadata.processed_at.clone(),
+                sensitive: d.metadata.sensitive,
+                channel: d.metadata.channel,
+                datetime_start: d.metadata.datetime_start.clone(),
+                datetime_end: d.metadata.datetime_end.clone(),
             })
             .collect();
 
         let result = JsonResultFile {
+            format_version: crate::constants::format_version::JSON,
             source_file: self.source_file.clone(),
             analysis_date: Utc::now(),
             model: self.model.clone(),
+            station: self.station.clone(),
             settings: JsonSettings {
                 min_confidence: self.min_confidence,
                 overlap: self.overlap,
@@ -184,14 +256,19 @@ impl OutputWriter for JsonResultWriter {
             summary: self.compute_summary(),
         };
 
-        let file = File::create(&self.output_path)?;
-        let writer = BufWriter::new(file);
-        serde_json::to_writer_pretty(writer, &result).map_err(|e| {
-            crate::error::Error::JsonWrite {
-                path: self.output_path.clone(),
-                source: e,
-            }
-        })?;
+        let json_error = |e| crate::error::Error::JsonWrite {
+            path: self.output_path.clone(),
+            source: e,
+        };
+        if let Some(mut sink) = self.sink.take() {
+            serde_json::to_writer_pretty(&mut sink, &result).map_err(json_error)?;
+            writeln!(sink)?;
+            sink.flush()?;
+        } else {
+            let file = File::create(&self.output_path)?;
+            let writer = BufWriter::new(file);
+            serde_json::to_writer_pretty(writer, &result).map_err(json_error)?;
+        }
 
         Ok(())
     }
@@ -238,6 +315,10 @@ mod tests {
         let content = std::fs::read_to_string(&output_path).expect("read file");
         let result: JsonResultFile = serde_json::from_str(&content).expect("parse JSON");
 
+        assert_eq!(
+            result.format_version,
+            crate::constants::format_version::JSON
+        );
         assert_eq!(result.source_file, "test.wav");
         assert_eq!(result.model, "birdnet-v24");
         assert_eq!(result.detections.len(), 1);
@@ -20,6 +20,9 @@ pub struct JsonEnvelope<T> {
     pub timestamp: DateTime<Utc>,
     /// Event type.
     pub event: EventType,
+    /// Station that emitted the event, if one is configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub station: Option<super::Station>,
     /// Event-specific payload.
     pub payload: T,
 }
@@ -31,9 +34,17 @@ impl<T: Serialize> JsonEnvelope<T> {
             spec_version: SPEC_VERSION.to_string(),
             timestamp: Utc::now(),
             event,
+            station: None,
             payload,
         }
     }
+
+    /// Record `station` as the station that emitted the event.
+    #[must_use]
+    pub fn with_station(mut self, station: Option<super::Station>) -> Self {
+        self.station = station;
+        self
+    }
 }
 
 /// Event types for JSON output.
@@ -58,6 +69,8 @@ pub enum EventType {
     Cancelled,
     /// Detection results for a file.
     Detections,
+    /// Diagnostic message captured from logging.
+    Log,
 }
 
 /// Result type discriminator for result payloads.
@@ -86,10 +99,62 @@ pub enum ResultType {
     ModelCheck,
     /// Configuration file path.
     ConfigPath,
+    /// One configuration value.
+    ConfigValue,
+    /// Configuration file diagnostics.
+    ConfigValidation,
+    /// Names of the stored integration credentials.
+    CredentialList,
     /// Model removed from configuration.
     ModelRemoved,
     /// Model installed.
     ModelInstalled,
+    /// Cached `TensorRT` engines.
+    CacheList,
+    /// Phenology report.
+    Phenology,
+    /// Activity calendar report.
+    Calendar,
+    /// Species review report.
+    Review,
+    /// Species accumulation report.
+    Accumulation,
+    /// Result staleness audit.
+    Audit,
+    /// SQL query over detection results.
+    Query,
+    /// Model smoke test.
+    ModelTest,
+    /// Extracted segment embeddings.
+    Embeddings,
+    /// Installation self-test.
+    Selftest,
+    /// Sound input devices.
+    InputDeviceList,
+    /// Detections and runtime compared across overlaps.
+    OverlapCalibration,
+    /// Inference throughput benchmark.
+    Bench,
+    /// Installed model files verified against their checksums.
+    ModelVerify,
+    /// Column schemas of the output formats.
+    Formats,
+    /// Sanitized copy of detection results.
+    Export,
+    /// Installed models compared with the registry.
+    ModelUpdate,
+    /// Recent analysis runs from the runs log.
+    RunList,
+    /// One analysis run from the runs log.
+    RunDetails,
+    /// Model files moved to another directory.
+    ModelRelocated,
+    /// Example recordings available for download.
+    ExampleList,
+    /// Example recordings downloaded.
+    ExamplesFetched,
+    /// Resolved settings of an analysis run.
+    RunConfig,
 }
 
 /// Error severity level.
@@ -140,6 +205,17 @@ pub struct ErrorPayload {
     pub suggestion: Option<String>,
 }
 
+/// Payload for log events.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogPayload {
+    /// Severity (`error`, `warn`, `info`, `debug`, `trace`).
+    pub level: String,
+    /// Module that logged the message (`ort` for ONNX Runtime).
+    pub target: String,
+    /// Message text, followed by any structured fields as `key=value`.
+    pub message: String,
+}
+
 /// File processing status.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -152,6 +228,20 @@ pub enum FileStatus {
     Skipped,
     /// File was skipped due to being locked.
     Locked,
+    /// File was left out by a size or duration filter.
+    Filtered,
+}
+
+/// Staleness of existing results relative to the current configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditStatus {
+    /// Results match the current model and settings.
+    UpToDate,
+    /// Results were produced with a different model, version, or settings.
+    Stale,
+    /// Results have no run manifest to compare against.
+    Unknown,
 }
 
 // ============================================================================
@@ -184,8 +274,10 @@ pub struct RangeFilterInfo {
     pub meta_model_source: Option<String>,
     /// Number of species predicted present at the query location/date.
     /// In cross-model mode, this is further limited to species that overlap
-    /// between the meta model and classifier label sets.
-    pub species_in_range: usize,
+    /// between the meta model and classifier label sets. Absent when each
+    /// recording is filtered at its own location.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub species_in_range: Option<usize>,
     /// Total classifier species.
     pub total_species: usize,
 }
@@ -212,6 +304,71 @@ impl From<crate::inference::ExecutionProviderStatus> for ExecutionProviderInfo {
     }
 }
 
+/// Payload for the `run_config` result emitted when an analysis starts.
+///
+/// Holds the settings after CLI, environment, config file and default values
+/// have been combined, so a run can be reproduced or audited as it ran.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunConfigPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// birda version.
+    pub version: String,
+    /// Model name.
+    pub model: String,
+    /// Model type (e.g. `birdnet-v24`).
+    pub model_type: String,
+    /// Path of the model file.
+    pub model_path: PathBuf,
+    /// Requested and actual execution provider.
+    pub execution_provider: ExecutionProviderInfo,
+    /// Inference batch size.
+    pub batch_size: usize,
+    /// Minimum confidence threshold.
+    pub min_confidence: f32,
+    /// Sigmoid sensitivity.
+    pub sensitivity: f32,
+    /// Segment overlap in seconds.
+    pub overlap: f32,
+    /// Output formats.
+    pub formats: Vec<crate::config::OutputFormat>,
+    /// Optional columns added to CSV and Parquet rows.
+    pub columns: Vec<String>,
+    /// Output directory; absent when results are written next to the inputs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_dir: Option<PathBuf>,
+    /// Range filter settings, if active.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range_filter: Option<RangeFilterSettings>,
+    /// Species list file, if one filters the detections.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub species_list: Option<PathBuf>,
+    /// Hash of the settings that affect detections, as in the run manifest.
+    pub settings_hash: String,
+}
+
+/// Resolved range filter settings of a run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RangeFilterSettings {
+    /// Latitude of the query location; absent when each recording supplies
+    /// its own.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latitude: Option<f64>,
+    /// Longitude of the query location; absent when each recording supplies
+    /// its own.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longitude: Option<f64>,
+    /// BirdNET week of year (1-48).
+    pub week: u8,
+    /// Minimum location score of a species.
+    pub threshold: f32,
+    /// Whether detections are re-ranked by location score.
+    pub rerank: bool,
+    /// Model that provided the meta model, when it is not the classifier's.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta_model_source: Option<String>,
+}
+
 /// Payload for `file_started` event.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileStartedPayload {
@@ -224,6 +381,9 @@ pub struct FileStartedPayload {
     /// Estimated duration in seconds.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub duration_seconds: Option<f64>,
+    /// Recorder settings from Song Meter (WAMD) metadata.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recorder: Option<crate::audio::RecorderMetadata>,
 }
 
 /// Payload for progress event.
@@ -255,6 +415,8 @@ pub struct DownloadProgress {
     pub total_bytes: u64,
     /// Progress percentage.
     pub percent: f32,
+    /// Download attempt, starting at 1; later attempts resume the file.
+    pub attempt: u32,
 }
 
 /// Payload for `file_completed` event.
@@ -273,6 +435,53 @@ pub struct FileCompletedPayload {
     /// Error details (if failed).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<FileErrorInfo>,
+    /// Species found in the file, most confident first (if processed).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub species: Option<Vec<FileSpeciesSummary>>,
+}
+
+/// Detections of one species in a file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileSpeciesSummary {
+    /// Scientific name.
+    pub scientific_name: String,
+    /// Common name.
+    pub common_name: String,
+    /// Number of detections.
+    pub detections: usize,
+    /// Highest confidence of the species' detections.
+    pub max_confidence: f32,
+}
+
+impl FileSpeciesSummary {
+    /// Summarize `detections` per species, ordered by highest confidence
+    /// (descending), then by name.
+    pub fn from_detections(detections: &[crate::output::Detection]) -> Vec<Self> {
+        let mut species: Vec<Self> = Vec::new();
+        for detection in detections {
+            match species
+                .iter_mut()
+                .find(|s| s.scientific_name == detection.scientific_name)
+            {
+                Some(summary) => {
+                    summary.detections += 1;
+                    summary.max_confidence = summary.max_confidence.max(detection.confidence);
+                }
+                None => species.push(Self {
+                    scientific_name: detection.scientific_name.clone(),
+                    common_name: detection.common_name.clone(),
+                    detections: 1,
+                    max_confidence: detection.confidence,
+                }),
+            }
+        }
+        species.sort_by(|a, b| {
+            b.max_confidence
+                .total_cmp(&a.max_confidence)
+                .then_with(|| a.scientific_name.cmp(&b.scientific_name))
+        });
+        species
+    }
 }
 
 /// Error information for a file.
@@ -303,6 +512,44 @@ pub struct PipelineCompletedPayload {
     pub duration_ms: u64,
     /// Realtime processing factor.
     pub realtime_factor: f64,
+    /// Batch size in effect at the end of the run (after any GPU memory backoff).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_batch_size: Option<usize>,
+    /// Confidence distributions and suggested thresholds (`--suggest-thresholds`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<ConfidenceSummary>,
+    /// Peak resident memory of the process in bytes (Linux).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak_rss_bytes: Option<u64>,
+    /// Peak GPU memory used by the process in bytes (NVIDIA GPUs).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak_gpu_memory_bytes: Option<u64>,
+}
+
+/// Confidence distributions of a run with suggested thresholds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfidenceSummary {
+    /// Suggested minimum confidence for all species together.
+    pub suggested_min_confidence: Option<f32>,
+    /// Per-species distributions, most detected first.
+    pub species: Vec<SpeciesConfidence>,
+}
+
+/// Confidence distribution of one species.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeciesConfidence {
+    /// Scientific name.
+    pub scientific_name: String,
+    /// Common name.
+    pub common_name: String,
+    /// Number of detections.
+    pub detections: usize,
+    /// Median confidence.
+    pub median_confidence: f32,
+    /// Suggested minimum confidence, if there were enough detections.
+    pub suggested_threshold: Option<f32>,
+    /// Detections per 5% confidence bin, from 0-5% up to 95-100%.
+    pub histogram: Vec<u32>,
 }
 
 /// Pipeline completion status.
@@ -389,6 +636,23 @@ pub struct DetectionInfo {
     pub start_time: f32,
     /// End time in seconds.
     pub end_time: f32,
+    /// Perceptual fingerprint of the detection's audio segment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+}
+
+impl From<&crate::output::Detection> for DetectionInfo {
+    fn from(d: &crate::output::Detection) -> Self {
+        Self {
+            species: format!("{}_{}", d.scientific_name, d.common_name),
+            common_name: d.common_name.clone(),
+            scientific_name: d.scientific_name.clone(),
+            confidence: d.confidence,
+            start_time: d.start_time,
+            end_time: d.end_time,
+            fingerprint: d.metadata.fingerprint.clone(),
+        }
+    }
 }
 
 // ============================================================================
@@ -450,6 +714,223 @@ pub struct ModelDetails {
     pub meta_model_path: Option<PathBuf>,
     /// Source (configured or registry).
     pub source: String,
+    /// Measured load footprint (with `--footprint`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub footprint: Option<ModelFootprint>,
+}
+
+/// Measured cost of loading a model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelFootprint {
+    /// Model file size in bytes.
+    pub file_size_bytes: u64,
+    /// Execution provider the model was loaded on.
+    pub device: String,
+    /// Time to create the inference session in milliseconds.
+    pub load_time_ms: u64,
+    /// Growth of resident memory from creating the session, in bytes.
+    /// Absent where resident memory cannot be measured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resident_memory_bytes: Option<u64>,
+    /// Number of labels (classes) the model predicts.
+    pub label_count: usize,
+    /// Whether the model supports pre-allocated batch contexts.
+    pub batch_context: bool,
+}
+
+/// Payload for model smoke test result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelTestPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Model name from configuration.
+    pub model: String,
+    /// Execution provider inference ran on.
+    pub device: String,
+    /// Time to load the model in milliseconds.
+    pub load_time_ms: u64,
+    /// Time of the test inference in milliseconds.
+    pub inference_time_ms: u64,
+    /// Top predictions for the test signal, best first.
+    pub predictions: Vec<TestPrediction>,
+}
+
+/// A prediction for the test signal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestPrediction {
+    /// Scientific name.
+    pub scientific_name: String,
+    /// Common name.
+    pub common_name: String,
+    /// Confidence score (0.0-1.0).
+    pub confidence: f32,
+}
+
+/// Payload for embedding extraction result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingsPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Model the embeddings were extracted with.
+    pub model: String,
+    /// Embedding files written.
+    pub files: Vec<EmbeddingFileEntry>,
+}
+
+/// Embeddings written for one input file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingFileEntry {
+    /// Input audio file.
+    pub input: PathBuf,
+    /// Embedding file written.
+    pub output: PathBuf,
+    /// Number of segments (rows).
+    pub segments: usize,
+    /// Embedding dimensions.
+    pub dimensions: usize,
+}
+
+/// Payload for self-test result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelftestPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Whether every stage passed.
+    pub passed: bool,
+    /// Stages in execution order.
+    pub stages: Vec<SelftestStage>,
+}
+
+/// Outcome of one self-test stage.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelftestStage {
+    /// Stage name (e.g. `decode`, `inference`, `writer:csv`).
+    pub name: String,
+    /// Stage outcome.
+    pub status: StageStatus,
+    /// Stage duration in milliseconds.
+    pub duration_ms: u64,
+    /// What the stage produced, or why it failed or was skipped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Self-test stage outcome.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StageStatus {
+    /// Stage completed.
+    Passed,
+    /// Stage failed.
+    Failed,
+    /// Stage not run because a stage it depends on failed.
+    Skipped,
+}
+
+/// Payload for input device list result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputDeviceListPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Input device names, as accepted by `birda listen --device`.
+    pub devices: Vec<String>,
+}
+
+/// Payload for a recording analyzed by `birda serve`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Analyzed file, or the upload's name.
+    pub file: PathBuf,
+    /// Model name from configuration.
+    pub model: String,
+    /// Audio duration in seconds.
+    pub duration_secs: f32,
+    /// Detections, by start time and then confidence.
+    pub detections: Vec<DetectionInfo>,
+}
+
+/// Payload for benchmark result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Measured throughput.
+    pub result: BenchResult,
+    /// Comparison with the baseline, when one was given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<BaselineComparison>,
+}
+
+/// Inference throughput of one benchmark run.
+///
+/// Also the format of baseline files written by `birda bench --save-baseline`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchResult {
+    /// birda version that ran the benchmark.
+    pub version: String,
+    /// Model name from configuration.
+    pub model: String,
+    /// Execution provider inference ran on.
+    pub device: String,
+    /// Segments per inference batch.
+    pub batch_size: usize,
+    /// Segments classified, excluding warm-up.
+    pub segments: usize,
+    /// Time spent classifying in seconds.
+    pub elapsed_secs: f64,
+    /// Segments classified per second.
+    pub segments_per_sec: f64,
+}
+
+/// Throughput compared with a baseline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaselineComparison {
+    /// Baseline file.
+    pub path: PathBuf,
+    /// Baseline segments per second.
+    pub segments_per_sec: f64,
+    /// Change from the baseline in percent (negative when slower).
+    pub change_percent: f64,
+    /// Allowed drop below the baseline in percent.
+    pub tolerance_percent: f64,
+    /// Whether throughput dropped by more than the tolerance.
+    pub regressed: bool,
+}
+
+/// Payload for overlap calibration result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverlapCalibrationPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Recordings analyzed.
+    pub files: Vec<PathBuf>,
+    /// Detection events found at any overlap.
+    pub events: usize,
+    /// Results per overlap, in the order given; deltas are relative to the first.
+    pub overlaps: Vec<OverlapCalibration>,
+}
+
+/// Detections and runtime at one overlap.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverlapCalibration {
+    /// Segment overlap in seconds.
+    pub overlap: f32,
+    /// Segments classified.
+    pub segments: usize,
+    /// Detections at or above the confidence threshold.
+    pub detections: usize,
+    /// Detection events this overlap found.
+    pub events_found: usize,
+    /// Share of all events found (absent without any events).
+    pub recall: Option<f64>,
+    /// Recall minus the first overlap's recall.
+    pub recall_delta: Option<f64>,
+    /// Inference time in seconds.
+    pub runtime_secs: f64,
+    /// Inference time relative to the first overlap.
+    pub relative_runtime: Option<f64>,
 }
 
 /// Payload for providers result.
@@ -481,6 +962,9 @@ pub struct ConfigPayload {
     pub config_path: PathBuf,
     /// The configuration contents (as JSON value for flexibility).
     pub config: serde_json::Value,
+    /// Dotted paths of values replaced with a placeholder.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redacted: Vec<String>,
 }
 
 /// Payload for available models list result.
@@ -534,6 +1018,220 @@ pub struct ModelCheckEntry {
     /// Error message if validation failed.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Version pinned in the model lockfile, if locked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locked_version: Option<String>,
+}
+
+/// Payload for formats describe result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormatsPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Schema of each described format.
+    pub formats: Vec<FormatSchema>,
+}
+
+/// Column schema of one output format version.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormatSchema {
+    /// Output format.
+    pub format: crate::config::OutputFormat,
+    /// Version of the format, bumped whenever its columns change.
+    pub format_version: u32,
+    /// Field delimiter of delimited text formats.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delimiter: Option<String>,
+    /// Whether the file starts with a header row.
+    pub header: bool,
+    /// Where the format version is recorded in the files, if anywhere.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_location: Option<String>,
+    /// Columns of every file, in order.
+    pub columns: Vec<ColumnSchema>,
+    /// Columns appended in the given order when listed in
+    /// `defaults.csv_columns.include`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub optional_columns: Vec<ColumnSchema>,
+}
+
+/// One column of an output format.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnSchema {
+    /// Column name as written in the file.
+    pub name: String,
+    /// Value type.
+    #[serde(rename = "type")]
+    pub column_type: ColumnType,
+    /// Unit of the values, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    /// Whether values may be empty.
+    pub nullable: bool,
+    /// What the column holds.
+    pub description: String,
+}
+
+/// Value type of an output column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnType {
+    /// Integer number.
+    Integer,
+    /// Floating point number.
+    Float,
+    /// Text.
+    String,
+    /// RFC 3339 timestamp.
+    Timestamp,
+    /// `true` or `false`.
+    Boolean,
+}
+
+/// Payload for `runs list`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunListPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Path of the runs log.
+    pub log_path: PathBuf,
+    /// Runs, newest first.
+    pub runs: Vec<crate::runs::RunRecord>,
+}
+
+/// Payload for `runs show`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunDetailsPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// The run.
+    pub run: crate::runs::RunRecord,
+}
+
+/// Payload for `models update`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelUpdatePayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Installed models with a newer registry version.
+    pub models: Vec<ModelUpdateEntry>,
+    /// Configured registry models missing from the lockfile, whose installed
+    /// version is unknown.
+    pub untracked: Vec<String>,
+}
+
+/// Installed model with a newer registry version.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelUpdateEntry {
+    /// Registry model ID.
+    pub id: String,
+    /// Version pinned in the lockfile.
+    pub installed_version: String,
+    /// Version offered by the registry.
+    pub available_version: String,
+    /// Whether the model was reinstalled.
+    pub updated: bool,
+}
+
+/// Payload for `models relocate`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelRelocatedPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Name of the relocated model.
+    pub id: String,
+    /// Directory the files were moved into.
+    pub dir: PathBuf,
+    /// Files that were moved.
+    pub moved: Vec<MovedFile>,
+}
+
+/// Model file moved by `models relocate`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MovedFile {
+    /// Previous path.
+    pub from: PathBuf,
+    /// New path.
+    pub to: PathBuf,
+}
+
+/// Payload for `examples list`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExampleListPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Version of the example set.
+    pub version: u32,
+    /// Example recordings with their expected detections.
+    pub examples: Vec<crate::registry::ExampleRecording>,
+}
+
+/// Payload for `examples fetch`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExamplesFetchedPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Directory holding the recordings.
+    pub dir: PathBuf,
+    /// Example set file with the expected detections.
+    pub manifest: PathBuf,
+    /// Example recordings.
+    pub examples: Vec<FetchedExample>,
+}
+
+/// Example recording fetched by `examples fetch`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FetchedExample {
+    /// Example ID.
+    pub id: String,
+    /// Path of the recording.
+    pub path: PathBuf,
+    /// Whether it was downloaded now (false if it was already present).
+    pub downloaded: bool,
+}
+
+/// Payload for model verify result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelVerifyPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Registry model ID.
+    pub id: String,
+    /// Directory holding the installed files.
+    pub dir: PathBuf,
+    /// Whether every file with a known checksum matched.
+    pub passed: bool,
+    /// Verification result per file.
+    pub files: Vec<ModelFileCheck>,
+}
+
+/// Verification result for one installed model file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelFileCheck {
+    /// File name.
+    pub file: String,
+    /// Outcome of the check.
+    pub status: FileCheckStatus,
+    /// Expected SHA256 hash, from the registry or else the lockfile.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+    /// SHA256 hash of the file on disk.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual: Option<String>,
+}
+
+/// Outcome of verifying one model file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileCheckStatus {
+    /// File hash matches the expected hash.
+    Ok,
+    /// File hash differs from the expected hash.
+    Mismatch,
+    /// File is not installed.
+    Missing,
+    /// No checksum is known for the file.
+    Unverified,
 }
 
 /// Payload for config path result.
@@ -547,6 +1245,46 @@ pub struct ConfigPathPayload {
     pub exists: bool,
 }
 
+/// Payload for credentials list result; values are never included.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialListPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Path to the credentials file.
+    pub credentials_path: PathBuf,
+    /// Dotted keys of the stored credentials.
+    pub keys: Vec<String>,
+}
+
+/// Payload for config get result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigValuePayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Path to the configuration file.
+    pub config_path: PathBuf,
+    /// Dotted configuration key.
+    pub key: String,
+    /// Value of the key, including defaults; `null` if not set.
+    pub value: Option<serde_json::Value>,
+    /// Dotted paths of values replaced with a placeholder.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redacted: Vec<String>,
+}
+
+/// Payload for config validate result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigValidationPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Path to the configuration file.
+    pub config_path: PathBuf,
+    /// Whether no errors were found.
+    pub valid: bool,
+    /// Problems found, errors and warnings.
+    pub diagnostics: Vec<crate::config::ConfigDiagnostic>,
+}
+
 /// Payload for model removed result.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelRemovedPayload {
@@ -575,6 +1313,30 @@ pub struct ModelInstalledPayload {
     pub labels_path: PathBuf,
 }
 
+/// Payload for cache list result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheListPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// `TensorRT` engine cache directory.
+    pub cache_dir: PathBuf,
+    /// Cached engines.
+    pub engines: Vec<EngineCacheEntry>,
+}
+
+/// A cached `TensorRT` engine and the batch sizes it was built for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EngineCacheEntry {
+    /// Engine file name inside the cache directory.
+    pub file: String,
+    /// Engine file size in bytes.
+    pub size_bytes: u64,
+    /// Model the engine was built for, if recorded by birda.
+    pub model: Option<String>,
+    /// Batch sizes warmed against this engine (empty if unknown).
+    pub batch_sizes: Vec<usize>,
+}
+
 /// Payload for species list result.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpeciesListPayload {
@@ -649,10 +1411,193 @@ pub struct ClipExtractionEntry {
     pub output_file: PathBuf,
 }
 
+/// Payload for the phenology report result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhenologyPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Output CSV file.
+    pub output_file: PathBuf,
+    /// Total result files read.
+    pub total_files: usize,
+    /// Total detections aggregated.
+    pub total_detections: usize,
+    /// Phenology rows, one per species, site, and year.
+    pub species: Vec<crate::report::PhenologyRow>,
+}
+
+/// Payload for the activity calendar report result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Output CSV file.
+    pub output_file: PathBuf,
+    /// Directory containing per-species SVG heatmaps, if written.
+    pub charts_dir: Option<PathBuf>,
+    /// Total result files read.
+    pub total_files: usize,
+    /// Total detections aggregated.
+    pub total_detections: usize,
+    /// Per-species calendar summaries.
+    pub species: Vec<CalendarEntry>,
+}
+
+/// Calendar summary for one species.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarEntry {
+    /// Scientific name of the species.
+    pub scientific_name: String,
+    /// Common name of the species.
+    pub common_name: String,
+    /// Number of days with detections.
+    pub active_days: usize,
+    /// Detections per hour of day (index 0-23), summed over all days.
+    pub hourly_totals: Vec<u32>,
+    /// SVG heatmap file, if charts were written.
+    pub chart_file: Option<PathBuf>,
+}
+
+/// Payload for the species accumulation report result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccumulationPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Output CSV file.
+    pub output_file: PathBuf,
+    /// HTML page with the accumulation chart, if written.
+    pub html_file: Option<PathBuf>,
+    /// Total result files read.
+    pub total_files: usize,
+    /// Total audio analyzed, in hours.
+    pub audio_hours: f64,
+    /// Distinct species found.
+    pub total_species: usize,
+    /// Species first found at the end of the curve (last 10% of the audio).
+    pub tail_new_species: usize,
+    /// Cumulative totals, one per recording in recording order.
+    pub points: Vec<crate::report::AccumulationPoint>,
+}
+
+/// Payload for the species review report result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Output HTML file.
+    pub output_file: PathBuf,
+    /// Directory containing linked snippets, if not embedded.
+    pub clips_dir: Option<PathBuf>,
+    /// Total result files read.
+    pub total_files: usize,
+    /// Total detections aggregated.
+    pub total_detections: usize,
+    /// Per-species review summaries.
+    pub species: Vec<ReviewEntry>,
+}
+
+/// Review summary for one species.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewEntry {
+    /// Scientific name of the species.
+    pub scientific_name: String,
+    /// Common name of the species.
+    pub common_name: String,
+    /// Number of detections.
+    pub detections: usize,
+    /// Highest detection confidence.
+    pub max_confidence: f32,
+    /// Number of top detections with an audio snippet.
+    pub snippets: usize,
+}
+
+/// Payload for the result audit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Directory that was audited.
+    pub directory: PathBuf,
+    /// Results matching the current configuration.
+    pub up_to_date: usize,
+    /// Results that should be re-run.
+    pub stale: usize,
+    /// Results without a run manifest.
+    pub unknown: usize,
+    /// Per-input findings.
+    pub results: Vec<AuditEntry>,
+}
+
+/// Audit finding for one input's results.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Result file.
+    pub result: PathBuf,
+    /// Staleness status.
+    pub status: AuditStatus,
+    /// Differences from the current configuration.
+    pub reasons: Vec<String>,
+    /// Recorded birda version.
+    pub birda_version: Option<String>,
+    /// Recorded model name.
+    pub model: Option<String>,
+}
+
+/// Payload for a SQL query over detection results.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Database or Parquet path the query ran against.
+    pub source: PathBuf,
+    /// Result column names.
+    pub columns: Vec<String>,
+    /// Result rows, one value per column.
+    pub rows: Vec<Vec<serde_json::Value>>,
+}
+
+/// Payload for a sanitized export of detection results.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportPayload {
+    /// Result type discriminator.
+    pub result_type: ResultType,
+    /// Directory holding the sanitized copy.
+    pub output_dir: PathBuf,
+    /// File mapping original to exported values.
+    pub mapping: PathBuf,
+    /// Files written to the output directory.
+    pub files_exported: usize,
+    /// Files left out because their format cannot be sanitized.
+    pub files_skipped: Vec<PathBuf>,
+    /// Paths reduced to file names and directories removed.
+    pub paths_replaced: usize,
+    /// Coordinates snapped to the grid.
+    pub coordinates_fuzzed: usize,
+    /// Detections of sensitive species left out.
+    pub detections_withheld: usize,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_file_species_summary() {
+        use crate::output::Detection;
+        use std::path::PathBuf;
+        let species = FileSpeciesSummary::from_detections(&[
+            Detection::test("Parus major_Great Tit", 0.0, "a.wav").with_confidence(0.4),
+            Detection::test("Turdus merula_Eurasian Blackbird", 0.0, "a.wav").with_confidence(0.7),
+            Detection::test("Parus major_Great Tit", 0.0, "a.wav").with_confidence(0.9),
+            Detection::test("Parus major_Great Tit", 0.0, "a.wav").with_confidence(0.5),
+        ]);
+        assert_eq!(species.len(), 2);
+        assert_eq!(species[0].common_name, "Great Tit");
+        assert_eq!(species[0].detections, 3);
+        assert!((species[0].max_confidence - 0.9).abs() < f32::EPSILON);
+        assert_eq!(species[1].detections, 1);
+    }
+
     #[test]
     fn test_envelope_serialization() {
         let payload = PipelineStartedPayload {
@@ -737,6 +1682,46 @@ mod tests {
         assert!(json.contains("\"actual\":\"TensorRT\""));
     }
 
+    #[test]
+    fn test_run_config_serialization() {
+        let payload = RunConfigPayload {
+            result_type: ResultType::RunConfig,
+            version: "1.0.0".to_string(),
+            model: "birdnet-v24".to_string(),
+            model_type: "birdnet-v24".to_string(),
+            model_path: PathBuf::from("/models/birdnet.onnx"),
+            execution_provider: ExecutionProviderInfo {
+                requested: "auto".to_string(),
+                actual: "CPU".to_string(),
+                fallback_reason: None,
+            },
+            batch_size: 8,
+            min_confidence: 0.1,
+            sensitivity: 1.0,
+            overlap: 0.0,
+            formats: vec![crate::config::OutputFormat::Csv],
+            columns: Vec::new(),
+            output_dir: None,
+            range_filter: Some(RangeFilterSettings {
+                latitude: Some(60.17),
+                longitude: Some(24.94),
+                week: 20,
+                threshold: 0.03,
+                rerank: false,
+                meta_model_source: None,
+            }),
+            species_list: None,
+            settings_hash: "abc123".to_string(),
+        };
+
+        let json = serde_json::to_value(&payload).expect("serialize");
+        assert_eq!(json["result_type"], "run_config");
+        assert_eq!(json["formats"], serde_json::json!(["csv"]));
+        assert_eq!(json["range_filter"]["week"], 20);
+        assert!(json.get("output_dir").is_none());
+        assert!(json["range_filter"].get("meta_model_source").is_none());
+    }
+
     #[test]
     fn test_event_type_serialization() {
         assert_eq!(
@@ -859,11 +1844,13 @@ mod tests {
                     id: "birdnet".to_string(),
                     valid: true,
                     error: None,
+                    locked_version: None,
                 },
                 ModelCheckEntry {
                     id: "broken".to_string(),
                     valid: false,
                     error: Some("model file not found".to_string()),
+                    locked_version: None,
                 },
             ],
         };
@@ -920,6 +1907,7 @@ mod tests {
             confidence: 0.95,
             start_time: 0.0,
             end_time: 3.0,
+            fingerprint: None,
         };
         let json = serde_json::to_string(&info).expect("serialize");
         let actual: serde_json::Value = serde_json::from_str(&json).expect("deserialize");
@@ -945,6 +1933,7 @@ mod tests {
                 confidence: 0.95,
                 start_time: 0.0,
                 end_time: 3.0,
+                fingerprint: None,
             }],
             bsg: None,
         };
@@ -965,6 +1954,7 @@ mod tests {
                 confidence: 0.95,
                 start_time: 0.0,
                 end_time: 3.0,
+                fingerprint: None,
             }],
             bsg: Some(BsgMetadata {
                 calibration_applied: true,
@@ -1023,7 +2013,7 @@ mod tests {
         let info = RangeFilterInfo {
             cross_model: false,
             meta_model_source: None,
-            species_in_range: 350,
+            species_in_range: Some(350),
             total_species: 6522,
         };
 
@@ -1046,7 +2036,7 @@ mod tests {
         let info = RangeFilterInfo {
             cross_model: true,
             meta_model_source: Some("birdnet-v24".to_string()),
-            species_in_range: 280,
+            species_in_range: Some(280),
             total_species: 900,
         };
 
@@ -1083,4 +2073,31 @@ mod tests {
         });
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_cache_list_payload() {
+        let payload = CacheListPayload {
+            result_type: ResultType::CacheList,
+            cache_dir: PathBuf::from("/cache/tensorrt_cache"),
+            engines: vec![EngineCacheEntry {
+                file: "graph_a.engine".to_string(),
+                size_bytes: 1024,
+                model: Some("birdnet-v24".to_string()),
+                batch_sizes: vec![8, 32],
+            }],
+        };
+        let json = serde_json::to_string(&payload).expect("serialize");
+        let actual: serde_json::Value = serde_json::from_str(&json).expect("deserialize");
+        let expected = serde_json::json!({
+            "result_type": "cache_list",
+            "cache_dir": "/cache/tensorrt_cache",
+            "engines": [{
+                "file": "graph_a.engine",
+                "size_bytes": 1024,
+                "model": "birdnet-v24",
+                "batch_sizes": [8, 32]
+            }]
+        });
+        assert_eq!(actual, expected);
+    }
 }
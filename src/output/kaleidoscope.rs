@@ -1,33 +1,108 @@
 //! Kaleidoscope CSV output format writer.
+//!
+//! Rows follow the column layout of Kaleidoscope Pro `id.csv` files, so the
+//! results can be opened as a review project without mapping columns. `DATE`,
+//! `TIME` and `HOUR` give the recording start, from the absolute detection
+//! times when stamped or else from a timestamp in the file name; the `-12`
+//! columns are shifted back 12 hours so a whole night shares one date.
+//! `MANUAL ID` is left empty for reviewers.
 
-use crate::constants::confidence::DECIMAL_PLACES;
+use crate::constants::wall_clock::DATETIME_FORMAT;
 use crate::error::Result;
-use crate::output::{Detection, OutputWriter};
+use crate::output::csv::escape_csv;
+use crate::output::{Detection, NumberFormat, OutputWriter};
+use crate::utils::date::timestamp_from_filename;
+use chrono::{NaiveDateTime, TimeDelta, Timelike};
 use std::fs::File;
 use std::io::{BufWriter, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Header row of Kaleidoscope CSV results.
+const HEADER: &str = "INDIR,OUTDIR,FOLDER,IN FILE,CHANNEL,OFFSET,DURATION,DATE,TIME,HOUR,\
+                      DATE-12,TIME-12,HOUR-12,AUTO ID*,TOP1MATCH,TOP1DIST,MANUAL ID";
+
+/// Format of the `DATE` columns.
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Format of the `TIME` columns.
+const TIME_FORMAT: &str = "%H:%M:%S";
 
 /// Kaleidoscope CSV output writer.
 pub struct KaleidoscopeWriter {
-    writer: BufWriter<File>,
+    writer: BufWriter<Box<dyn Write + Send>>,
+    /// Directory written to the `OUTDIR` column.
+    output_dir: PathBuf,
+    /// Decimal places of confidences and times.
+    number_format: NumberFormat,
 }
 
 impl KaleidoscopeWriter {
     /// Create a new Kaleidoscope writer.
     pub fn new(path: &Path) -> Result<Self> {
-        let file = File::create(path)?;
-        Ok(Self {
-            writer: BufWriter::new(file),
+        let writer = Self::from_writer(Box::new(File::create(path)?));
+        Ok(match path.parent() {
+            Some(dir) => writer.output_dir(dir),
+            None => writer,
         })
     }
+
+    /// Create a Kaleidoscope writer over any sink (e.g. stdout).
+    pub fn from_writer(sink: Box<dyn Write + Send>) -> Self {
+        Self {
+            writer: BufWriter::new(sink),
+            output_dir: PathBuf::new(),
+            number_format: NumberFormat::default(),
+        }
+    }
+
+    /// Record `dir` as the `OUTDIR` of every row.
+    #[must_use]
+    pub fn output_dir(mut self, dir: &Path) -> Self {
+        self.output_dir = dir.to_path_buf();
+        self
+    }
+
+    /// Write confidences and times with the decimal places of `format`.
+    #[must_use]
+    pub const fn with_number_format(mut self, format: NumberFormat) -> Self {
+        self.number_format = format;
+        self
+    }
+}
+
+/// Start of the recording holding `detection`, if known.
+fn recording_start(detection: &Detection) -> Option<NaiveDateTime> {
+    detection
+        .metadata
+        .datetime_start
+        .as_deref()
+        .and_then(|datetime| NaiveDateTime::parse_from_str(datetime, DATETIME_FORMAT).ok())
+        .map(|datetime| {
+            #[allow(clippy::cast_possible_truncation)]
+            let millis = (f64::from(detection.start_time) * 1000.0).round() as i64;
+            datetime - TimeDelta::milliseconds(millis)
+        })
+        .or_else(|| timestamp_from_filename(&detection.file_path))
+}
+
+/// `DATE`, `TIME` and `HOUR` values of `datetime`, empty when unknown.
+fn date_columns(datetime: Option<NaiveDateTime>) -> String {
+    datetime.map_or_else(
+        || ",,".to_string(),
+        |datetime| {
+            format!(
+                "{},{},{}",
+                datetime.format(DATE_FORMAT),
+                datetime.format(TIME_FORMAT),
+                datetime.hour()
+            )
+        },
+    )
 }
 
 impl OutputWriter for KaleidoscopeWriter {
     fn write_header(&mut self) -> Result<()> {
-        writeln!(
-            self.writer,
-            "INDIR,FOLDER,IN FILE,OFFSET,DURATION,TOP1MATCH,TOP1DIST"
-        )?;
+        writeln!(self.writer, "{HEADER}")?;
         Ok(())
     }
 
@@ -51,23 +126,44 @@ impl OutputWriter for KaleidoscopeWriter {
             .file_name()
             .map_or_else(|| std::borrow::Cow::Borrowed(""), |n| n.to_string_lossy());
 
+        // Kaleidoscope numbers channels from 0
+        let channel = detection
+            .metadata
+            .channel
+            .map_or(0, |c| c.saturating_sub(1));
+
         let duration = detection.end_time - detection.start_time;
 
+        let start = recording_start(detection);
+        let species = escape_csv(&detection.common_name.replace(' ', "_"));
+
+        let format = self.number_format;
         writeln!(
             self.writer,
-            "{},{},{},{:.1},{:.1},{},{:.decimal$}",
-            indir,
-            folder,
-            filename,
+            "{},{},{},{},{},{:.time$},{:.time$},{},{},{},{},{:.decimal$},",
+            escape_csv(&indir),
+            escape_csv(&self.output_dir.display().to_string()),
+            escape_csv(&folder),
+            escape_csv(&filename),
+            channel,
             detection.start_time,
             duration,
-            detection.common_name.replace(' ', "_"),
+            date_columns(start),
+            date_columns(start.map(|start| start - TimeDelta::hours(12))),
+            species,
+            species,
             detection.confidence,
-            decimal = DECIMAL_PLACES,
+            time = format.time_places(),
+            decimal = format.confidence_places(),
         )?;
         Ok(())
     }
 
+    fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
     fn finalize(&mut self) -> Result<()> {
         self.writer.flush()?;
         Ok(())
@@ -99,9 +195,66 @@ mod tests {
         writer.finalize().unwrap();
 
         let contents = std::fs::read_to_string(file.path()).unwrap();
-        assert!(contents.contains("INDIR,FOLDER,IN FILE"));
+        assert!(contents.starts_with("INDIR,OUTDIR,FOLDER,IN FILE,CHANNEL,"));
         assert!(contents.contains("morning"));
         assert!(contents.contains("audio.wav"));
         assert!(contents.contains("House_Sparrow"));
     }
+
+    #[test]
+    fn test_kaleidoscope_date_columns() {
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = KaleidoscopeWriter::new(file.path())
+            .unwrap()
+            .output_dir(Path::new("/out"));
+
+        let mut detection = Detection::from_label(
+            "Strix aluco_Tawny Owl",
+            0.75,
+            61.5,
+            64.5,
+            PathBuf::from("/data/site1/20240315_063000.wav"),
+        );
+        writer.write_detection(&detection).unwrap();
+        detection.file_path = PathBuf::from("/data/site1/dawn.wav");
+        writer.write_detection(&detection).unwrap();
+        writer.finalize().unwrap();
+
+        let contents = std::fs::read_to_string(file.path()).unwrap();
+        let rows: Vec<_> = contents.lines().collect();
+        assert_eq!(
+            rows[0],
+            "/data,/out,site1,20240315_063000.wav,0,61.5,3.0,2024-03-15,06:30:00,6,\
+             2024-03-14,18:30:00,18,Tawny_Owl,Tawny_Owl,0.7500,"
+        );
+        assert_eq!(
+            rows[1],
+            "/data,/out,site1,dawn.wav,0,61.5,3.0,,,,,,,Tawny_Owl,Tawny_Owl,0.7500,"
+        );
+    }
+
+    #[test]
+    fn test_kaleidoscope_escapes_columns() {
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = KaleidoscopeWriter::new(file.path())
+            .unwrap()
+            .output_dir(Path::new("/out"));
+
+        let detection = Detection::from_label(
+            "Anas platyrhynchos_Mallard, \"wild\"",
+            0.5,
+            0.0,
+            3.0,
+            PathBuf::from("/data/site 1, north/dawn,1.wav"),
+        );
+        writer.write_detection(&detection).unwrap();
+        writer.finalize().unwrap();
+
+        let contents = std::fs::read_to_string(file.path()).unwrap();
+        assert_eq!(
+            contents.trim_end(),
+            "/data,/out,\"site 1, north\",\"dawn,1.wav\",0,0.0,3.0,,,,,,,\
+             \"Mallard,_\"\"wild\"\"\",\"Mallard,_\"\"wild\"\"\",0.5000,"
+        );
+    }
 }
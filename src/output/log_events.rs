@@ -0,0 +1,130 @@
+//! Diagnostics captured as structured `log` events.
+//!
+//! In JSON and NDJSON modes, tracing events (including ONNX Runtime's, which
+//! arrive under the `ort` target) are reported through the progress reporter
+//! instead of as free text on stderr, so GUIs can show them in a log pane.
+
+use crate::output::ProgressReporter;
+use std::fmt::{Debug, Write as _};
+use std::sync::{Arc, OnceLock};
+use tracing::field::{Field, Visit};
+use tracing::level_filters::LevelFilter;
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// Reporter that receives captured log events, once output mode is known.
+static LOG_REPORTER: OnceLock<Arc<dyn ProgressReporter>> = OnceLock::new();
+
+/// Lowest severity captured as `log` events (`--log-events`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogEventLevel {
+    /// Keep all diagnostics on stderr.
+    Off,
+    /// Errors only.
+    Error,
+    /// Warnings and errors.
+    #[default]
+    Warn,
+    /// Informational messages and above.
+    Info,
+    /// Debug messages and above.
+    Debug,
+    /// Everything.
+    Trace,
+}
+
+impl LogEventLevel {
+    /// Equivalent tracing level filter.
+    pub const fn level_filter(self) -> LevelFilter {
+        match self {
+            Self::Off => LevelFilter::OFF,
+            Self::Error => LevelFilter::ERROR,
+            Self::Warn => LevelFilter::WARN,
+            Self::Info => LevelFilter::INFO,
+            Self::Debug => LevelFilter::DEBUG,
+            Self::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+/// Route captured log events to `reporter` from now on.
+///
+/// Called once the output mode is known; until then diagnostics go to stderr.
+pub fn set_log_reporter(reporter: Arc<dyn ProgressReporter>) {
+    let _ = LOG_REPORTER.set(reporter);
+}
+
+/// Whether log events are being captured (and kept off stderr).
+pub fn log_events_active() -> bool {
+    LOG_REPORTER.get().is_some()
+}
+
+/// Tracing layer that forwards events to the log reporter.
+///
+/// Filter it to the `--log-events` level; events are dropped until
+/// [`set_log_reporter`] is called.
+pub struct LogEventLayer;
+
+impl<S: Subscriber> Layer<S> for LogEventLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let Some(reporter) = LOG_REPORTER.get() else {
+            return;
+        };
+        let metadata = event.metadata();
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        reporter.log(
+            &metadata.level().as_str().to_lowercase(),
+            metadata.target(),
+            &visitor.into_message(),
+        );
+    }
+}
+
+/// Collects an event's message and other fields into one line.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl MessageVisitor {
+    fn into_message(mut self) -> String {
+        if self.message.is_empty() {
+            self.fields.trim_start().to_string()
+        } else {
+            self.message.push_str(&self.fields);
+            self.message
+        }
+    }
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={value}", field.name());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_filter_order() {
+        assert!(LogEventLevel::Warn.level_filter() >= tracing::Level::ERROR);
+        assert!(LogEventLevel::Warn.level_filter() < tracing::Level::INFO);
+        assert_eq!(LogEventLevel::Off.level_filter(), LevelFilter::OFF);
+    }
+}
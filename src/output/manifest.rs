@@ -0,0 +1,231 @@
+//! Per-file run manifests recording how results were produced.
+//!
+//! A manifest is written beside each file's results and records the birda
+//! version, model, and every setting that affects detections, plus a hash of
+//! those settings. Comparing the hash tells whether existing results are
+//! stale after a model upgrade or settings change.
+
+use crate::error::{Error, Result};
+use crate::utils::hash::to_hex;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// Settings that affect which detections are produced.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunSettings {
+    /// Model name from configuration.
+    pub model: String,
+    /// Model type.
+    pub model_type: String,
+    /// Hex SHA-256 hash of the model file (changes when the model is
+    /// upgraded).
+    #[serde(default)]
+    pub model_sha256: Option<String>,
+    /// Minimum confidence threshold.
+    pub min_confidence: f32,
+    /// Segment overlap in seconds.
+    pub overlap: f32,
+    /// Detection sensitivity, if not the default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sensitivity: Option<f32>,
+    /// Range filter latitude.
+    pub latitude: Option<f64>,
+    /// Range filter longitude.
+    pub longitude: Option<f64>,
+    /// Range filter week.
+    pub week: Option<u8>,
+    /// Species list file.
+    pub species_list: Option<PathBuf>,
+    /// Whether a bat classifier was used.
+    pub bat: bool,
+    /// Post-processing hook program.
+    pub postprocess_hook: Option<PathBuf>,
+    /// Whether overlapping detections were merged into events.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub merge_overlapping: bool,
+    /// Overlap of the second pass around detections.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zoom_overlap: Option<f32>,
+    /// Channels analyzed (`all` or a channel number), unless mixed to mono.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    /// Pre-filter applied to the audio (`high-pass 250 Hz`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    /// Custom classifier head applied to the model's embeddings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_head: Option<PathBuf>,
+    /// Part of the recording analyzed (`START-END`), unless the whole file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_range: Option<String>,
+    /// Whether consecutive recordings were analyzed as one stream.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stitch: bool,
+    /// Decimal places of confidences in the results, if set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence_decimals: Option<usize>,
+    /// Decimal places of times in the results, if set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_decimals: Option<usize>,
+}
+
+impl RunSettings {
+    /// Hex SHA-256 hash of the settings.
+    pub fn hash(&self) -> String {
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        to_hex(&Sha256::digest(&json))
+    }
+}
+
+/// Random sample of the input files analyzed by a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputSample {
+    /// Seed that selected the sample; reuse it with `--sample-seed`.
+    pub seed: u64,
+    /// Files in the sample.
+    pub selected: usize,
+    /// Files found in the inputs.
+    pub total: usize,
+}
+
+/// Manifest written beside each file's results.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunManifest {
+    /// Version of birda that produced the results.
+    pub birda_version: String,
+    /// Hash of `settings`, for quick comparison.
+    pub settings_hash: String,
+    /// Settings used for the run.
+    pub settings: RunSettings,
+    /// Random sample of the inputs the file was part of.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sample: Option<InputSample>,
+    /// Station that produced the results.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub station: Option<super::Station>,
+    /// ID of the run in the runs log (`birda runs show <run_id>`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    /// When the results were written.
+    pub created: DateTime<Utc>,
+}
+
+impl RunManifest {
+    /// Create a manifest for the current birda version.
+    pub fn new(settings: RunSettings) -> Self {
+        Self {
+            birda_version: env!("CARGO_PKG_VERSION").to_string(),
+            settings_hash: settings.hash(),
+            settings,
+            sample: None,
+            station: None,
+            run_id: None,
+            created: Utc::now(),
+        }
+    }
+
+    /// This manifest for a file analyzed over its own `time_range` (from
+    /// `path@START-END`) instead of the run's.
+    #[must_use]
+    pub fn with_time_range(&self, time_range: String) -> Self {
+        let mut manifest = self.clone();
+        manifest.settings.time_range = Some(time_range);
+        manifest.settings_hash = manifest.settings.hash();
+        manifest
+    }
+
+    /// Read a manifest from disk.
+    pub fn read(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(|e| Error::ManifestParse {
+            path: path.to_path_buf(),
+            source: e,
+        })
+    }
+
+    /// Write the manifest to disk, stamped with the current time.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let manifest = Self {
+            created: Utc::now(),
+            ..self.clone()
+        };
+        let file = std::fs::File::create(path)?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(file), &manifest).map_err(|e| {
+            Error::JsonWrite {
+                path: path.to_path_buf(),
+                source: e,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn settings() -> RunSettings {
+        RunSettings {
+            model: "birdnet".to_string(),
+            model_type: "birdnet-v24".to_string(),
+            model_sha256: Some("aa".to_string()),
+            min_confidence: 0.1,
+            overlap: 0.0,
+            sensitivity: None,
+            latitude: None,
+            longitude: None,
+            week: None,
+            species_list: None,
+            bat: false,
+            postprocess_hook: None,
+            merge_overlapping: false,
+            zoom_overlap: None,
+            channel: None,
+            filter: None,
+            custom_head: None,
+            time_range: None,
+            stitch: false,
+            confidence_decimals: None,
+            time_decimals: None,
+        }
+    }
+
+    #[test]
+    fn test_settings_hash_changes_with_settings() {
+        let a = settings();
+        let mut b = settings();
+        assert_eq!(a.hash(), b.hash());
+
+        b.model_sha256 = Some("bb".to_string());
+        assert_ne!(a.hash(), b.hash());
+    }
+
+    #[test]
+    fn test_with_time_range_rehashes_settings() {
+        let manifest = RunManifest::new(settings());
+        let ranged = manifest.with_time_range("00:00:10-00:01:00".to_string());
+        assert_eq!(
+            ranged.settings.time_range.as_deref(),
+            Some("00:00:10-00:01:00")
+        );
+        assert_eq!(ranged.settings_hash, ranged.settings.hash());
+        assert_ne!(ranged.settings_hash, manifest.settings_hash);
+    }
+
+    #[test]
+    fn test_manifest_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rec.BirdNET.run.json");
+
+        let mut manifest = RunManifest::new(settings());
+        manifest.run_id = Some("20250611T081201Z-1f3a".to_string());
+        manifest.write(&path).unwrap();
+
+        let read = RunManifest::read(&path).unwrap();
+        assert_eq!(read.settings, manifest.settings);
+        assert_eq!(read.settings_hash, manifest.settings_hash);
+        assert_eq!(read.run_id, manifest.run_id);
+    }
+}
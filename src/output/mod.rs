@@ -1,37 +1,82 @@
 //! Output format writers.
 
+mod activity;
 mod audacity;
+mod birdnet_csv;
+mod combined;
 mod csv;
+mod database;
+mod dataset;
+mod embeddings;
 mod json;
 pub mod json_envelope;
 mod kaleidoscope;
+mod log_events;
+mod manifest;
+mod number_format;
 mod parquet;
 pub mod progress;
+mod provenance;
 mod raven;
+mod report;
 mod reporter;
+mod rolling;
+mod schema;
+mod sensitive;
+mod sqlite;
+mod station;
 mod types;
 mod writer;
 
+pub use activity::{ActivityBin, ActivityTracker, segment_level_dbfs, write_activity};
 pub use audacity::AudacityWriter;
+pub use birdnet_csv::BirdnetCsvWriter;
+pub use combined::{
+    CombinedSender, CombinedWriter, combined_filename, combined_outputs, is_combined_filename,
+};
 pub use csv::CsvWriter;
+pub use database::{DETECTIONS_TABLE, QueryResult, open_database_output, run_query};
+pub use dataset::{ParquetDatasetWriter, dataset_dirname};
+pub use embeddings::{EmbeddingWriter, SegmentEmbedding};
 pub use json::JsonResultWriter;
 pub use json_envelope::{
-    AvailableModelEntry, AvailableModelsPayload, BatchProgress, BsgMetadata, CancelReason,
-    CancelledPayload, ClipExtractionEntry, ClipExtractionPayload, ConfigPathPayload, ConfigPayload,
-    DetectionInfo, DetectionsPayload, DownloadProgress, ErrorPayload, ErrorSeverity, EventType,
-    ExecutionProviderInfo, FileCompletedPayload, FileErrorInfo, FileProgress, FileStartedPayload,
-    FileStatus, JsonEnvelope, ModelCheckEntry, ModelCheckPayload, ModelDetails, ModelEntry,
-    ModelInfoPayload, ModelInstalledPayload, ModelListPayload, ModelRemovedPayload,
-    PipelineCompletedPayload, PipelineStartedPayload, PipelineStatus, ProgressPayload,
-    ProviderInfo, ProvidersPayload, RangeFilterInfo, ResultType, SPEC_VERSION, SpeciesEntry,
-    SpeciesListPayload, VersionPayload,
+    AccumulationPayload, AnalysisPayload, AuditEntry, AuditPayload, AuditStatus,
+    AvailableModelEntry, AvailableModelsPayload, BaselineComparison, BatchProgress, BenchPayload,
+    BenchResult, BsgMetadata, CacheListPayload, CalendarEntry, CalendarPayload, CancelReason,
+    CancelledPayload, ClipExtractionEntry, ClipExtractionPayload, ConfidenceSummary,
+    ConfigPathPayload, ConfigPayload, ConfigValidationPayload, ConfigValuePayload,
+    CredentialListPayload, DetectionInfo, DetectionsPayload, DownloadProgress, EmbeddingFileEntry,
+    EmbeddingsPayload, EngineCacheEntry, ErrorPayload, ErrorSeverity, EventType,
+    ExampleListPayload, ExamplesFetchedPayload, ExecutionProviderInfo, ExportPayload,
+    FetchedExample, FileCheckStatus, FileCompletedPayload, FileErrorInfo, FileProgress,
+    FileSpeciesSummary, FileStartedPayload, FileStatus, InputDeviceListPayload, JsonEnvelope,
+    LogPayload, ModelCheckEntry, ModelCheckPayload, ModelDetails, ModelEntry, ModelFileCheck,
+    ModelFootprint, ModelInfoPayload, ModelInstalledPayload, ModelListPayload,
+    ModelRelocatedPayload, ModelRemovedPayload, ModelTestPayload, ModelUpdateEntry,
+    ModelUpdatePayload, ModelVerifyPayload, MovedFile, OverlapCalibration,
+    OverlapCalibrationPayload, PhenologyPayload, PipelineCompletedPayload, PipelineStartedPayload,
+    PipelineStatus, ProgressPayload, ProviderInfo, ProvidersPayload, QueryPayload, RangeFilterInfo,
+    RangeFilterSettings, ResultType, ReviewEntry, ReviewPayload, RunConfigPayload,
+    RunDetailsPayload, RunListPayload, SPEC_VERSION, SelftestPayload, SelftestStage,
+    SpeciesConfidence, SpeciesEntry, SpeciesListPayload, StageStatus, TestPrediction,
+    VersionPayload,
 };
 pub use kaleidoscope::KaleidoscopeWriter;
+pub use log_events::{LogEventLayer, LogEventLevel, log_events_active, set_log_reporter};
+pub use manifest::{InputSample, RunManifest, RunSettings};
+pub use number_format::NumberFormat;
 pub use parquet::{ParquetWriter, combine_parquet_files};
+pub use provenance::Provenance;
 pub use raven::RavenWriter;
+pub use report::{FileSummary, RunReport, RunTotals, SpeciesSummary};
 pub use reporter::{
     JsonProgressReporter, NullReporter, PipelineSummary, ProgressReporter, ProgressThrottler,
     create_reporter, emit_json_result,
 };
+pub use rolling::{RollingWriter, SplitLimits, next_free_part, part_path};
+pub use schema::{format_schema, format_version_of};
+pub use sensitive::SensitiveSpecies;
+pub use sqlite::{open_sqlite_output, sqlite_filename};
+pub use station::Station;
 pub use types::{Detection, DetectionMetadata};
 pub use writer::OutputWriter;
@@ -0,0 +1,121 @@
+//! Numeric precision of result files.
+//!
+//! Text formats always round confidences and times; JSON keeps full
+//! precision unless a precision is configured, so all formats of a run can be
+//! made to agree digit for digit. Writers take the format of their run through
+//! `with_number_format`.
+
+use std::borrow::Cow;
+
+use crate::constants::confidence::DECIMAL_PLACES;
+use crate::constants::precision::TIME_DECIMAL_PLACES;
+use crate::output::Detection;
+
+/// Configured decimal places for confidences and times.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NumberFormat {
+    /// Decimal places for confidences (default: 4 in text formats).
+    pub confidence_decimals: Option<usize>,
+    /// Decimal places for start, end and offset times (default: 1 in text
+    /// formats).
+    pub time_decimals: Option<usize>,
+}
+
+impl NumberFormat {
+    /// Decimal places for confidences in text formats.
+    pub fn confidence_places(self) -> usize {
+        self.confidence_decimals.unwrap_or(DECIMAL_PLACES)
+    }
+
+    /// Decimal places for times in text formats.
+    pub fn time_places(self) -> usize {
+        self.time_decimals.unwrap_or(TIME_DECIMAL_PLACES)
+    }
+
+    /// Confidence for JSON output, rounded only if a precision is set.
+    pub fn json_confidence(self, confidence: f32) -> f32 {
+        self.confidence_decimals
+            .map_or(confidence, |places| round_to(confidence, places))
+    }
+
+    /// Time for JSON output, rounded only if a precision is set.
+    pub fn json_time(self, time: f32) -> f32 {
+        self.time_decimals
+            .map_or(time, |places| round_to(time, places))
+    }
+
+    /// `detections` with confidences and times rounded as in JSON output,
+    /// e.g. for the event stream; borrowed unchanged if no precision is set.
+    pub fn json_detections(self, detections: &[Detection]) -> Cow<'_, [Detection]> {
+        if self.confidence_decimals.is_none() && self.time_decimals.is_none() {
+            return Cow::Borrowed(detections);
+        }
+        Cow::Owned(
+            detections
+                .iter()
+                .map(|d| Detection {
+                    confidence: self.json_confidence(d.confidence),
+                    start_time: self.json_time(d.start_time),
+                    end_time: self.json_time(d.end_time),
+                    ..d.clone()
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Round `value` to `places` decimal places.
+fn round_to(value: f32, places: usize) -> f32 {
+    let scale = 10f64.powi(i32::try_from(places).unwrap_or(i32::MAX));
+    #[allow(clippy::cast_possible_truncation)]
+    let rounded = ((f64::from(value) * scale).round() / scale) as f32;
+    rounded
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_places_default() {
+        let format = NumberFormat::default();
+        assert_eq!(format.confidence_places(), DECIMAL_PLACES);
+        assert_eq!(format.time_places(), TIME_DECIMAL_PLACES);
+    }
+
+    #[test]
+    fn test_json_rounding_only_when_set() {
+        let format = NumberFormat::default();
+        assert!((format.json_confidence(0.854_26) - 0.854_26).abs() < f32::EPSILON);
+
+        let format = NumberFormat {
+            confidence_decimals: Some(2),
+            time_decimals: Some(0),
+        };
+        assert_eq!(format.json_confidence(0.854_26).to_string(), "0.85");
+        assert_eq!(format.json_time(4.5).to_string(), "5");
+        assert_eq!(
+            serde_json::to_string(&format.json_confidence(0.1)).unwrap(),
+            "0.1"
+        );
+    }
+
+    #[test]
+    fn test_json_detections_borrowed_without_precision() {
+        let detections =
+            [Detection::test("Parus major_Great Tit", 1.25, "a.wav").with_confidence(0.854_26)];
+        assert!(matches!(
+            NumberFormat::default().json_detections(&detections),
+            Cow::Borrowed(_)
+        ));
+
+        let format = NumberFormat {
+            confidence_decimals: Some(2),
+            time_decimals: None,
+        };
+        let rounded = format.json_detections(&detections);
+        assert_eq!(rounded[0].confidence.to_string(), "0.85");
+        assert!((rounded[0].start_time - 1.25).abs() < f32::EPSILON);
+    }
+}
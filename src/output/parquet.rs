@@ -3,16 +3,20 @@
 //! This module provides functionality to write bird detection results in Apache Parquet format,
 //! offering better compression, type safety, and integration with data science tooling compared to CSV.
 
-use arrow::array::{ArrayRef, Float32Array, Float64Array, StringArray, UInt8Array};
+use arrow::array::{
+    ArrayRef, BooleanArray, Float32Array, Float64Array, StringArray, UInt8Array, UInt32Array,
+};
 use arrow::datatypes::{DataType, Field, Schema};
 use arrow::record_batch::RecordBatch;
 use parquet::arrow::ArrowWriter;
 use parquet::basic::Compression;
+use parquet::file::metadata::KeyValue;
 use parquet::file::properties::WriterProperties;
 use std::fs::File;
 use std::path::Path;
 use std::sync::Arc;
 
+use crate::constants::format_version;
 use crate::error::Result;
 use crate::output::OutputWriter;
 use crate::output::types::Detection;
@@ -41,10 +45,7 @@ impl ParquetWriter {
     /// Returns error if file creation fails or Parquet writer initialization fails.
     pub fn new(output_path: &Path, include_additional_columns: &[String]) -> Result<Self> {
         let schema = build_schema(include_additional_columns);
-        let props = WriterProperties::builder()
-            .set_compression(Compression::SNAPPY)
-            .set_writer_version(parquet::file::properties::WriterVersion::PARQUET_2_0)
-            .build();
+        let props = writer_properties();
 
         let file =
             File::create(output_path).map_err(|e| crate::error::Error::ParquetFileCreate {
@@ -123,6 +124,22 @@ impl OutputWriter for ParquetWriter {
         self.write_detection(detection.clone())
     }
 
+    fn flush(&mut self) -> Result<()> {
+        self.flush_batch()?;
+
+        // Close the current row group so each flush becomes its own row group
+        if let Some(writer) = self.writer.as_mut() {
+            writer
+                .flush()
+                .map_err(|e| crate::error::Error::ParquetWrite {
+                    context: "Failed to flush Parquet row group".to_string(),
+                    source: e,
+                })?;
+        }
+
+        Ok(())
+    }
+
     fn finalize(&mut self) -> Result<()> {
         // Flush any remaining buffered detections
         self.flush_batch()?;
@@ -139,6 +156,26 @@ impl OutputWriter for ParquetWriter {
 
         Ok(())
     }
+
+    fn bytes_written(&self) -> u64 {
+        // Encoded row groups plus the row group still being built in memory
+        self.writer
+            .as_ref()
+            .map_or(0, |w| (w.bytes_written() + w.in_progress_size()) as u64)
+    }
+}
+
+/// Writer properties of every Parquet file, recording the format version as
+/// key-value metadata.
+fn writer_properties() -> WriterProperties {
+    WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .set_writer_version(parquet::file::properties::WriterVersion::PARQUET_2_0)
+        .set_key_value_metadata(Some(vec![KeyValue::new(
+            format_version::PARQUET_METADATA_KEY.to_string(),
+            format_version::PARQUET.to_string(),
+        )]))
+        .build()
 }
 
 /// Build Arrow schema based on included columns.
@@ -169,6 +206,24 @@ fn build_schema(include_additional_columns: &[String]) -> Arc<Schema> {
             "sensitivity" => Field::new("sensitivity", DataType::Float32, true),
             "min_conf" => Field::new("min_conf", DataType::Float32, true),
             "species_list" => Field::new("species_list", DataType::Utf8, true),
+            "annotation" => Field::new("annotation", DataType::Utf8, true),
+            "fingerprint" => Field::new("fingerprint", DataType::Utf8, true),
+            "device" => Field::new("device", DataType::Utf8, true),
+            "model_hash" => Field::new("model_hash", DataType::Utf8, true),
+            "processed_at" => Field::new("processed_at", DataType::Utf8, true),
+            "recorder_model" => Field::new("recorder_model", DataType::Utf8, true),
+            "recorder_serial" => Field::new("recorder_serial", DataType::Utf8, true),
+            "recorder_gain" => Field::new("recorder_gain", DataType::Utf8, true),
+            "recorder_temperature" => Field::new("recorder_temperature", DataType::Float32, true),
+            "recorder_schedule" => Field::new("recorder_schedule", DataType::Utf8, true),
+            "station_id" => Field::new("station_id", DataType::Utf8, true),
+            "station_name" => Field::new("station_name", DataType::Utf8, true),
+            "channel" => Field::new("channel", DataType::UInt32, true),
+            "datetime_start" => Field::new("datetime_start", DataType::Utf8, true),
+            "datetime_end" => Field::new("datetime_end", DataType::Utf8, true),
+            "sensitive" => Field::new("sensitive", DataType::Boolean, true),
+            "path" => Field::new("path", DataType::Utf8, true),
+            format_version::COLUMN => Field::new(format_version::COLUMN, DataType::UInt32, false),
             _ => continue, // Skip unknown columns
         };
         fields.push(field);
@@ -291,6 +346,124 @@ fn build_metadata_column(field: &Field, detections: &[Detection]) -> Result<Arra
                 .collect();
             Ok(Arc::new(StringArray::from(values)))
         }
+        "annotation" => {
+            let values: Vec<Option<&str>> = detections
+                .iter()
+                .map(|d| d.metadata.annotation.as_deref())
+                .collect();
+            Ok(Arc::new(StringArray::from(values)))
+        }
+        "fingerprint" => {
+            let values: Vec<Option<&str>> = detections
+                .iter()
+                .map(|d| d.metadata.fingerprint.as_deref())
+                .collect();
+            Ok(Arc::new(StringArray::from(values)))
+        }
+        "device" => {
+            let values: Vec<Option<&str>> = detections
+                .iter()
+                .map(|d| d.metadata.device.as_deref())
+                .collect();
+            Ok(Arc::new(StringArray::from(values)))
+        }
+        "model_hash" => {
+            let values: Vec<Option<&str>> = detections
+                .iter()
+                .map(|d| d.metadata.model_hash.as_deref())
+                .collect();
+            Ok(Arc::new(StringArray::from(values)))
+        }
+        "processed_at" => {
+            let values: Vec<Option<&str>> = detections
+                .iter()
+                .map(|d| d.metadata.processed_at.as_deref())
+                .collect();
+            Ok(Arc::new(StringArray::from(values)))
+        }
+        "recorder_model" => {
+            let values: Vec<Option<&str>> = detections
+                .iter()
+                .map(|d| d.metadata.recorder_model.as_deref())
+                .collect();
+            Ok(Arc::new(StringArray::from(values)))
+        }
+        "recorder_serial" => {
+            let values: Vec<Option<&str>> = detections
+                .iter()
+                .map(|d| d.metadata.recorder_serial.as_deref())
+                .collect();
+            Ok(Arc::new(StringArray::from(values)))
+        }
+        "recorder_gain" => {
+            let values: Vec<Option<&str>> = detections
+                .iter()
+                .map(|d| d.metadata.recorder_gain.as_deref())
+                .collect();
+            Ok(Arc::new(StringArray::from(values)))
+        }
+        "recorder_temperature" => {
+            let values: Vec<Option<f32>> = detections
+                .iter()
+                .map(|d| d.metadata.recorder_temperature)
+                .collect();
+            Ok(Arc::new(Float32Array::from(values)))
+        }
+        "recorder_schedule" => {
+            let values: Vec<Option<&str>> = detections
+                .iter()
+                .map(|d| d.metadata.recorder_schedule.as_deref())
+                .collect();
+            Ok(Arc::new(StringArray::from(values)))
+        }
+        "station_id" => {
+            let values: Vec<Option<&str>> = detections
+                .iter()
+                .map(|d| d.metadata.station_id.as_deref())
+                .collect();
+            Ok(Arc::new(StringArray::from(values)))
+        }
+        "station_name" => {
+            let values: Vec<Option<&str>> = detections
+                .iter()
+                .map(|d| d.metadata.station_name.as_deref())
+                .collect();
+            Ok(Arc::new(StringArray::from(values)))
+        }
+        "channel" => {
+            let values: Vec<Option<u32>> = detections.iter().map(|d| d.metadata.channel).collect();
+            Ok(Arc::new(UInt32Array::from(values)))
+        }
+        "datetime_start" => {
+            let values: Vec<Option<&str>> = detections
+                .iter()
+                .map(|d| d.metadata.datetime_start.as_deref())
+                .collect();
+            Ok(Arc::new(StringArray::from(values)))
+        }
+        "datetime_end" => {
+            let values: Vec<Option<&str>> = detections
+                .iter()
+                .map(|d| d.metadata.datetime_end.as_deref())
+                .collect();
+            Ok(Arc::new(StringArray::from(values)))
+        }
+        "sensitive" => {
+            let values: Vec<Option<bool>> =
+                detections.iter().map(|d| d.metadata.sensitive).collect();
+            Ok(Arc::new(BooleanArray::from(values)))
+        }
+        "path" => {
+            let values: StringArray = detections
+                .iter()
+                .map(|d| Some(d.file_path.to_string_lossy()))
+                .collect();
+            Ok(Arc::new(values))
+        }
+        format_version::COLUMN => Ok(Arc::new(UInt32Array::from(vec![
+            format_version::PARQUET;
+            detections.len()
+        ]))),
         name => Err(crate::error::Error::InvalidColumnName {
             name: name.to_string(),
         }),
@@ -344,10 +517,7 @@ pub fn combine_parquet_files(input_files: &[std::path::PathBuf], output_path: &P
             source: e,
         })?;
 
-    let props = WriterProperties::builder()
-        .set_compression(Compression::SNAPPY)
-        .set_writer_version(parquet::file::properties::WriterVersion::PARQUET_2_0)
-        .build();
+    let props = writer_properties();
 
     let mut writer =
         ArrowWriter::try_new(output_file, schema.clone(), Some(props)).map_err(|e| {
@@ -453,6 +623,7 @@ pub fn combine_parquet_files(input_files: &[std::path::PathBuf], output_path: &P
 #[cfg(test)]
 mod tests {
     use super::*;
+    use arrow::array::Array;
     use std::path::PathBuf;
 
     #[test]
@@ -494,6 +665,68 @@ mod tests {
         assert_eq!(batch.num_columns(), 6);
     }
 
+    #[test]
+    fn test_path_column_keeps_full_path() {
+        let detections = vec![Detection {
+            file_path: PathBuf::from("/data/site1/test.wav"),
+            start_time: 0.0,
+            end_time: 3.0,
+            scientific_name: "Poecile atricapillus".to_string(),
+            common_name: "Black-capped Chickadee".to_string(),
+            confidence: 0.95,
+            metadata: Default::default(),
+        }];
+
+        let schema = build_schema(&["path".to_string()]);
+        let batch = build_record_batch(&detections, &schema).ok().unwrap();
+        let paths = batch
+            .column_by_name("path")
+            .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+            .unwrap();
+        assert_eq!(paths.value(0), "/data/site1/test.wav");
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_format_version_column_and_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.parquet");
+        let mut writer = ParquetWriter::new(&path, &[format_version::COLUMN.to_string()]).unwrap();
+        writer
+            .write_detection(Detection {
+                file_path: PathBuf::from("/data/test.wav"),
+                start_time: 0.0,
+                end_time: 3.0,
+                scientific_name: "Poecile atricapillus".to_string(),
+                common_name: "Black-capped Chickadee".to_string(),
+                confidence: 0.95,
+                metadata: Default::default(),
+            })
+            .unwrap();
+        writer.finalize().unwrap();
+
+        let reader = parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(
+            File::open(&path).unwrap(),
+        )
+        .unwrap();
+        let key_value = reader
+            .metadata()
+            .file_metadata()
+            .key_value_metadata()
+            .unwrap()
+            .iter()
+            .find(|kv| kv.key == format_version::PARQUET_METADATA_KEY)
+            .and_then(|kv| kv.value.clone());
+        assert_eq!(key_value, Some(format_version::PARQUET.to_string()));
+
+        let batch = reader.build().unwrap().next().unwrap().unwrap();
+        let versions = batch
+            .column_by_name(format_version::COLUMN)
+            .and_then(|c| c.as_any().downcast_ref::<UInt32Array>())
+            .unwrap();
+        assert_eq!(versions.value(0), format_version::PARQUET);
+    }
+
     #[test]
     fn test_empty_detections() {
         let schema = build_schema(&[]);
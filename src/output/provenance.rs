@@ -0,0 +1,95 @@
+//! Row-level provenance for detections.
+//!
+//! Datasets merged from several machines, or from CPU and GPU runs, lose track
+//! of how each row was produced once results are combined. [`Provenance`]
+//! stamps every detection with the execution provider, the model file hash and
+//! the processing time, written as the `device`, `model_hash` and
+//! `processed_at` columns.
+
+use crate::output::Detection;
+use chrono::{SecondsFormat, Utc};
+
+/// Provenance shared by every detection of a run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provenance {
+    /// Execution provider that runs inference.
+    pub device: String,
+    /// Hex SHA-256 hash of the model file.
+    pub model_hash: String,
+}
+
+impl Provenance {
+    /// Record provenance for `device` and the model with hex SHA-256 hash
+    /// `model_hash`.
+    pub fn new(device: impl Into<String>, model_hash: impl Into<String>) -> Self {
+        Self {
+            device: device.into(),
+            model_hash: model_hash.into(),
+        }
+    }
+
+    /// Stamp `detections` of a just-processed file with this provenance.
+    ///
+    /// All detections of a file share one `processed_at` timestamp.
+    pub fn apply(&self, detections: &mut [Detection]) {
+        let processed_at = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+        for detection in detections {
+            detection.metadata.device = Some(self.device.clone());
+            detection.metadata.model_hash = Some(self.model_hash.clone());
+            detection.metadata.processed_at = Some(processed_at.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::utils::hash::sha256_file;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_provenance_stamps_detections() {
+        let dir = tempfile::tempdir().unwrap();
+        let model = dir.path().join("model.onnx");
+        std::fs::write(&model, b"").unwrap();
+
+        let provenance = Provenance::new("CUDA", sha256_file(&model).unwrap());
+        assert_eq!(
+            provenance.model_hash,
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+
+        let mut detections = vec![
+            Detection::from_label(
+                "Parus major_Great Tit",
+                0.9,
+                0.0,
+                3.0,
+                PathBuf::from("a.wav"),
+            ),
+            Detection::from_label(
+                "Parus major_Great Tit",
+                0.8,
+                3.0,
+                6.0,
+                PathBuf::from("a.wav"),
+            ),
+        ];
+        provenance.apply(&mut detections);
+
+        assert_eq!(detections[0].metadata.device.as_deref(), Some("CUDA"));
+        assert!(
+            detections[0]
+                .metadata
+                .processed_at
+                .as_deref()
+                .unwrap()
+                .ends_with('Z')
+        );
+        assert_eq!(
+            detections[0].metadata.processed_at,
+            detections[1].metadata.processed_at
+        );
+    }
+}
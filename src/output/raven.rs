@@ -1,26 +1,62 @@
 //! Raven selection table output format writer.
 
-use crate::constants::{confidence::DECIMAL_PLACES, raven};
+use crate::constants::raven;
 use crate::error::Result;
-use crate::output::{Detection, OutputWriter};
+use crate::output::{Detection, NumberFormat, OutputWriter};
 use std::fs::File;
 use std::io::{BufWriter, Write};
 use std::path::Path;
 
 /// Raven selection table output writer.
 pub struct RavenWriter {
-    writer: BufWriter<File>,
+    writer: BufWriter<Box<dyn Write + Send>>,
     selection_id: u32,
+    /// Whether begin/end times run on across recordings.
+    multi_file: bool,
+    /// Start of the current recording on the table's timeline, in seconds.
+    time_offset: f64,
+    /// Whether the current recording has selections in the table.
+    file_has_selections: bool,
+    /// Decimal places of confidences and times.
+    number_format: NumberFormat,
 }
 
 impl RavenWriter {
     /// Create a new Raven writer.
     pub fn new(path: &Path) -> Result<Self> {
-        let file = File::create(path)?;
-        Ok(Self {
-            writer: BufWriter::new(file),
+        Ok(Self::from_writer(Box::new(File::create(path)?)))
+    }
+
+    /// Create a Raven writer over any sink (e.g. stdout).
+    pub fn from_writer(sink: Box<dyn Write + Send>) -> Self {
+        Self {
+            writer: BufWriter::new(sink),
             selection_id: 0,
-        })
+            multi_file: false,
+            time_offset: 0.0,
+            file_has_selections: false,
+            number_format: NumberFormat::default(),
+        }
+    }
+
+    /// Write a table spanning several recordings, as Raven's multi-file
+    /// review expects.
+    ///
+    /// Begin and end times run on from one recording to the next in table
+    /// order, while `File Offset (s)` stays relative to the recording. Only
+    /// recordings with selections advance the timeline, because Raven only
+    /// opens the files listed in the table.
+    #[must_use]
+    pub const fn multi_file(mut self) -> Self {
+        self.multi_file = true;
+        self
+    }
+
+    /// Write confidences and times with the decimal places of `format`.
+    #[must_use]
+    pub const fn with_number_format(mut self, format: NumberFormat) -> Self {
+        self.number_format = format;
+        self
     }
 }
 
@@ -28,7 +64,7 @@ impl OutputWriter for RavenWriter {
     fn write_header(&mut self) -> Result<()> {
         writeln!(
             self.writer,
-            "Selection\tView\tChannel\tBegin Time (s)\tEnd Time (s)\tLow Freq (Hz)\tHigh Freq (Hz)\tCommon Name\tSpecies Code\tConfidence\tBegin Path\tFile Offset (s)"
+            "Selection\tView\tChannel\tBegin Time (s)\tEnd Time (s)\tLow Freq (Hz)\tHigh Freq (Hz)\tCommon Name\tSpecies Code\tConfidence\tBegin File\tBegin Path\tFile Offset (s)"
         )?;
         Ok(())
     }
@@ -43,26 +79,53 @@ impl OutputWriter for RavenWriter {
         // For now, use a placeholder based on common name
         let species_code = generate_species_code(&detection.common_name);
 
+        let begin_file = detection
+            .file_path
+            .file_name()
+            .map_or_else(|| std::borrow::Cow::Borrowed(""), |n| n.to_string_lossy());
+        let offset = if self.multi_file {
+            self.time_offset
+        } else {
+            0.0
+        };
+        self.file_has_selections = true;
+
+        let format = self.number_format;
         writeln!(
             self.writer,
-            "{}\t{}\t{}\t{:.1}\t{:.1}\t{}\t{}\t{}\t{}\t{:.decimal$}\t{}\t{:.1}",
+            "{}\t{}\t{}\t{:.time$}\t{:.time$}\t{}\t{}\t{}\t{}\t{:.decimal$}\t{}\t{}\t{:.time$}",
             self.selection_id,
             raven::VIEW,
-            raven::CHANNEL,
-            detection.start_time,
-            detection.end_time,
+            detection.metadata.channel.unwrap_or(raven::CHANNEL),
+            offset + f64::from(detection.start_time),
+            offset + f64::from(detection.end_time),
             raven::DEFAULT_LOW_FREQ,
             raven::DEFAULT_HIGH_FREQ,
             common_name,
             species_code,
             detection.confidence,
+            begin_file,
             detection.file_path.display(),
             detection.start_time,
-            decimal = DECIMAL_PLACES,
+            time = format.time_places(),
+            decimal = format.confidence_places(),
         )?;
         Ok(())
     }
 
+    fn end_file(&mut self, _file: &Path, audio_duration_secs: f64) -> Result<()> {
+        if self.multi_file && self.file_has_selections {
+            self.time_offset += audio_duration_secs;
+        }
+        self.file_has_selections = false;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
     fn finalize(&mut self) -> Result<()> {
         self.writer.flush()?;
         Ok(())
@@ -115,6 +178,64 @@ mod tests {
         assert!(contents.contains("Spectrogram 1"));
     }
 
+    #[test]
+    fn test_raven_writer_channel_and_begin_file() {
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = RavenWriter::new(file.path()).unwrap();
+        writer.write_header().unwrap();
+
+        let mut detection = Detection::from_label(
+            "Passer domesticus_House Sparrow",
+            0.8542,
+            6.0,
+            9.0,
+            PathBuf::from("/path/to/audio.wav"),
+        );
+        detection.metadata.channel = Some(2);
+        writer.write_detection(&detection).unwrap();
+        writer.finalize().unwrap();
+
+        let contents = std::fs::read_to_string(file.path()).unwrap();
+        let mut lines = contents.lines();
+        let header: Vec<_> = lines.next().unwrap().split('\t').collect();
+        let row: Vec<_> = lines.next().unwrap().split('\t').collect();
+        assert_eq!(header.len(), row.len());
+        let value = |name: &str| row[header.iter().position(|h| *h == name).unwrap()];
+        assert_eq!(value("Channel"), "2");
+        assert_eq!(value("Begin File"), "audio.wav");
+        assert_eq!(value("Begin Path"), "/path/to/audio.wav");
+        assert_eq!(value("Begin Time (s)"), "6.0");
+    }
+
+    #[test]
+    fn test_raven_writer_multi_file_timeline() {
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = RavenWriter::new(file.path()).unwrap().multi_file();
+        writer.write_header().unwrap();
+        writer
+            .write_detection(&Detection::test("Parus major_Great Tit", 3.0, "a.wav"))
+            .unwrap();
+        writer.end_file(Path::new("a.wav"), 60.0).unwrap();
+        // Recordings without selections are not part of Raven's file sequence
+        writer.end_file(Path::new("b.wav"), 30.0).unwrap();
+        writer
+            .write_detection(&Detection::test("Parus major_Great Tit", 3.0, "c.wav"))
+            .unwrap();
+        writer.end_file(Path::new("c.wav"), 60.0).unwrap();
+        writer.finalize().unwrap();
+
+        let contents = std::fs::read_to_string(file.path()).unwrap();
+        let rows: Vec<Vec<&str>> = contents
+            .lines()
+            .skip(1)
+            .map(|l| l.split('\t').collect())
+            .collect();
+        assert_eq!(rows[0][3], "3.0");
+        assert_eq!(rows[1][3], "63.0");
+        assert_eq!(rows[1][4], "66.0");
+        assert_eq!(rows[1][12], "3.0");
+    }
+
     #[test]
     fn test_generate_species_code() {
         assert_eq!(generate_species_code("House Sparrow"), "houspa");
@@ -0,0 +1,519 @@
+//! Self-contained HTML summary of an analysis run (`--report`).
+//!
+//! Each file's detections are folded into per-species counts and confidence
+//! histograms and a per-file timeline as soon as the file finishes, so long
+//! runs do not keep every detection in memory for the report.
+
+use crate::constants::report::{
+    RUN_HISTOGRAM_BINS, RUN_TIMELINE_BINS, THRESHOLD_MIN_DETECTIONS, THRESHOLD_MIN_ELBOW,
+};
+use crate::error::{Error, Result};
+use crate::output::progress::format_duration;
+use crate::output::{ConfidenceSummary, Detection, SpeciesConfidence};
+use crate::report::escape_xml;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Detections of one species over the run.
+#[derive(Debug, Clone)]
+pub struct SpeciesSummary {
+    /// Scientific name.
+    pub scientific_name: String,
+    /// Common name.
+    pub common_name: String,
+    /// Number of detections.
+    pub detections: usize,
+    /// Number of recordings with at least one detection.
+    pub files: usize,
+    /// Highest confidence.
+    pub max_confidence: f32,
+    /// Sum of confidences, for the mean.
+    confidence_sum: f64,
+    /// Detections per 5% confidence bin, from 0-5% up to 95-100%.
+    pub histogram: [u32; RUN_HISTOGRAM_BINS],
+}
+
+impl SpeciesSummary {
+    /// Mean confidence of the species' detections.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    pub fn mean_confidence(&self) -> f32 {
+        if self.detections == 0 {
+            0.0
+        } else {
+            (self.confidence_sum / self.detections as f64) as f32
+        }
+    }
+}
+
+/// One analyzed recording.
+#[derive(Debug, Clone)]
+pub struct FileSummary {
+    /// Recording path.
+    pub path: PathBuf,
+    /// Audio duration in seconds.
+    pub audio_duration_secs: f64,
+    /// Processing time in seconds.
+    pub processing_secs: f64,
+    /// Segments analyzed.
+    pub segments: usize,
+    /// Detections found.
+    pub detections: usize,
+    /// Distinct species detected.
+    pub species: usize,
+    /// Detections in each equal slice of the recording.
+    pub timeline: [u32; RUN_TIMELINE_BINS],
+}
+
+/// Run-level figures shown at the top of the report.
+#[derive(Debug, Clone, Default)]
+pub struct RunTotals {
+    /// Model name.
+    pub model: String,
+    /// Files skipped because results existed or were locked.
+    pub files_skipped: usize,
+    /// Files that failed.
+    pub files_failed: usize,
+    /// Wall-clock duration of the run in seconds.
+    pub elapsed_secs: f64,
+    /// Minimum confidence the run used.
+    pub min_confidence: f32,
+}
+
+#[derive(Debug, Default)]
+struct ReportData {
+    species: BTreeMap<String, SpeciesSummary>,
+    files: Vec<FileSummary>,
+}
+
+/// Statistics collected for the run report as files finish.
+#[derive(Debug, Default)]
+pub struct RunReport {
+    data: Mutex<ReportData>,
+}
+
+/// Bin of `fraction` (0.0-1.0) among `bins` equal bins.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+)]
+fn bin_index(fraction: f64, bins: usize) -> usize {
+    ((fraction.max(0.0) * bins as f64) as usize).min(bins - 1)
+}
+
+/// First bin at which `share` of the histogram's detections is reached.
+fn quantile_bin(histogram: &[u32], share: f64) -> usize {
+    let total: u64 = histogram.iter().map(|&c| u64::from(c)).sum();
+    let mut cumulative = 0u64;
+    for (bin, &count) in histogram.iter().enumerate() {
+        cumulative += u64::from(count);
+        #[allow(clippy::cast_precision_loss)]
+        if cumulative as f64 >= share * total as f64 {
+            return bin;
+        }
+    }
+    histogram.len().saturating_sub(1)
+}
+
+/// Lower edge of `bin`, rounded to whole percent.
+#[allow(clippy::cast_precision_loss)]
+fn bin_edge(bin: usize, bins: usize) -> f32 {
+    (bin as f32 * 100.0 / bins as f32).round() / 100.0
+}
+
+/// Median confidence of a histogram, as the middle of its median bin.
+#[allow(clippy::cast_precision_loss)]
+pub fn median_confidence(histogram: &[u32]) -> f32 {
+    if histogram.is_empty() {
+        return 0.0;
+    }
+    (quantile_bin(histogram, 0.5) as f32 + 0.5) / histogram.len() as f32
+}
+
+/// Suggest a minimum confidence from a confidence histogram.
+///
+/// Low-confidence detections, mostly false positives, usually fall off
+/// steeply before the count levels out. The suggestion is the elbow of the
+/// curve of detections kept at each threshold: the point furthest below the
+/// straight line from `min_confidence` to the top bin. Without a clear elbow
+/// it is the lower quartile. It is never below `min_confidence`, and there is
+/// none for fewer than [`THRESHOLD_MIN_DETECTIONS`] detections.
+pub fn suggest_threshold(histogram: &[u32], min_confidence: f32) -> Option<f32> {
+    let total: u64 = histogram.iter().map(|&c| u64::from(c)).sum();
+    if total < THRESHOLD_MIN_DETECTIONS as u64 {
+        return None;
+    }
+    let bins = histogram.len();
+    let start = bin_index(f64::from(min_confidence), bins);
+
+    // Share of detections kept with a threshold at each bin's lower edge
+    let mut remaining: u64 = histogram[start..].iter().map(|&c| u64::from(c)).sum();
+    let mut kept = Vec::with_capacity(bins - start);
+    for &count in &histogram[start..] {
+        #[allow(clippy::cast_precision_loss)]
+        kept.push(remaining as f64 / total as f64);
+        remaining -= u64::from(count);
+    }
+
+    let last = kept.len() - 1;
+    let elbow = kept
+        .iter()
+        .enumerate()
+        .filter(|_| last > 0)
+        .map(|(i, &share)| {
+            #[allow(clippy::cast_precision_loss)]
+            let line = kept[0] + (kept[last] - kept[0]) * (i as f64 / last as f64);
+            (start + i, line - share)
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .filter(|&(_, drop)| drop >= THRESHOLD_MIN_ELBOW);
+    let bin = elbow.map_or_else(|| quantile_bin(histogram, 0.25), |(bin, _)| bin);
+    Some(bin_edge(bin, bins).max(min_confidence))
+}
+
+impl RunReport {
+    /// Confidence distributions and suggested thresholds of the run so far.
+    pub fn confidence_summary(&self, min_confidence: f32) -> ConfidenceSummary {
+        let species = self.species();
+        let overall = overall_histogram(&species);
+        ConfidenceSummary {
+            suggested_min_confidence: suggest_threshold(&overall, min_confidence),
+            species: species
+                .into_iter()
+                .map(|s| SpeciesConfidence {
+                    median_confidence: median_confidence(&s.histogram),
+                    suggested_threshold: suggest_threshold(&s.histogram, min_confidence),
+                    histogram: s.histogram.to_vec(),
+                    scientific_name: s.scientific_name,
+                    common_name: s.common_name,
+                    detections: s.detections,
+                })
+                .collect(),
+        }
+    }
+
+    /// Fold one analyzed file into the report.
+    pub fn add_file(
+        &self,
+        path: &Path,
+        detections: &[Detection],
+        audio_duration_secs: f64,
+        processing_secs: f64,
+        segments: usize,
+    ) {
+        let Ok(mut data) = self.data.lock() else {
+            return;
+        };
+
+        let mut timeline = [0u32; RUN_TIMELINE_BINS];
+        let mut seen = HashSet::new();
+        for detection in detections {
+            let species = data
+                .species
+                .entry(detection.scientific_name.clone())
+                .or_insert_with(|| SpeciesSummary {
+                    scientific_name: detection.scientific_name.clone(),
+                    common_name: detection.common_name.clone(),
+                    detections: 0,
+                    files: 0,
+                    max_confidence: 0.0,
+                    confidence_sum: 0.0,
+                    histogram: [0; RUN_HISTOGRAM_BINS],
+                });
+            species.detections += 1;
+            species.max_confidence = species.max_confidence.max(detection.confidence);
+            species.confidence_sum += f64::from(detection.confidence);
+            species.histogram[bin_index(f64::from(detection.confidence), RUN_HISTOGRAM_BINS)] += 1;
+            if seen.insert(detection.scientific_name.as_str()) {
+                species.files += 1;
+            }
+
+            if audio_duration_secs > 0.0 {
+                let position = f64::from(detection.start_time) / audio_duration_secs;
+                timeline[bin_index(position, RUN_TIMELINE_BINS)] += 1;
+            }
+        }
+
+        let species = seen.len();
+        data.files.push(FileSummary {
+            path: path.to_path_buf(),
+            audio_duration_secs,
+            processing_secs,
+            segments,
+            detections: detections.len(),
+            species,
+            timeline,
+        });
+    }
+
+    /// Species detected so far, most frequent first.
+    pub fn species(&self) -> Vec<SpeciesSummary> {
+        let mut species: Vec<_> = self
+            .data
+            .lock()
+            .map(|data| data.species.values().cloned().collect())
+            .unwrap_or_default();
+        species.sort_by(|a, b| {
+            b.detections
+                .cmp(&a.detections)
+                .then_with(|| a.scientific_name.cmp(&b.scientific_name))
+        });
+        species
+    }
+
+    /// Files analyzed so far, in processing order.
+    pub fn files(&self) -> Vec<FileSummary> {
+        self.data
+            .lock()
+            .map(|data| data.files.clone())
+            .unwrap_or_default()
+    }
+
+    /// Write the report as HTML to `path`.
+    pub fn write(&self, path: &Path, totals: &RunTotals) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.render_html(totals)).map_err(|e| Error::ReportWrite {
+            path: path.to_path_buf(),
+            source: Box::new(e),
+        })
+    }
+
+    /// Render the report as a self-contained HTML page.
+    pub fn render_html(&self, totals: &RunTotals) -> String {
+        let species = self.species();
+        let files = self.files();
+        let audio_secs: f64 = files.iter().map(|f| f.audio_duration_secs).sum();
+        let detections: usize = files.iter().map(|f| f.detections).sum();
+
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str("<title>birda run report</title>\n");
+        html.push_str(
+            "<style>\
+             body{font-family:sans-serif;margin:2em}\
+             table{border-collapse:collapse;margin-bottom:2em}\
+             th,td{border-bottom:1px solid #ddd;padding:.3em .8em;text-align:left;vertical-align:middle}\
+             td.n{text-align:right}\
+             dl{display:grid;grid-template-columns:max-content auto;gap:.2em 1em}\
+             dt{color:#555}dd{margin:0}\
+             svg rect.bar{fill:#2c7fb8}\
+             </style>\n</head>\n<body>\n<h1>birda run report</h1>\n<dl>\n",
+        );
+        let summary = [
+            ("Model", escape_xml(&totals.model)),
+            ("Files analyzed", files.len().to_string()),
+            ("Files skipped", totals.files_skipped.to_string()),
+            ("Files failed", totals.files_failed.to_string()),
+            ("Audio", format_duration(audio_secs)),
+            ("Processing time", format_duration(totals.elapsed_secs)),
+            ("Detections", detections.to_string()),
+            ("Species", species.len().to_string()),
+            (
+                "Suggested minimum confidence",
+                percent_or_dash(suggest_threshold(
+                    &overall_histogram(&species),
+                    totals.min_confidence,
+                )),
+            ),
+        ];
+        for (label, value) in summary {
+            let _ = writeln!(html, "<dt>{label}</dt><dd>{value}</dd>");
+        }
+        html.push_str("</dl>\n");
+
+        html.push_str(
+            "<h2>Species</h2>\n<table>\n<thead><tr><th>Species</th><th>Detections</th>\
+             <th>Files</th><th>Mean confidence</th><th>Max confidence</th>\
+             <th>Confidence (0&ndash;100%)</th><th>Suggested threshold</th></tr></thead>\n<tbody>\n",
+        );
+        for s in &species {
+            let _ = writeln!(
+                html,
+                "<tr><td><b>{}</b><br><i>{}</i></td><td class=\"n\">{}</td><td class=\"n\">{}</td>\
+                 <td class=\"n\">{:.0}%</td><td class=\"n\">{:.0}%</td><td>{}</td><td class=\"n\">{}</td></tr>",
+                escape_xml(&s.common_name),
+                escape_xml(&s.scientific_name),
+                s.detections,
+                s.files,
+                s.mean_confidence() * 100.0,
+                s.max_confidence * 100.0,
+                bar_chart(&s.histogram, 5, 24),
+                percent_or_dash(suggest_threshold(&s.histogram, totals.min_confidence))
+            );
+        }
+        html.push_str("</tbody>\n</table>\n");
+
+        html.push_str(
+            "<h2>Files</h2>\n<table>\n<thead><tr><th>File</th><th>Audio</th><th>Processing</th>\
+             <th>Realtime</th><th>Segments</th><th>Detections</th><th>Species</th>\
+             <th>Detections over the recording</th></tr></thead>\n<tbody>\n",
+        );
+        for f in &files {
+            let realtime = if f.processing_secs > 0.0 {
+                f.audio_duration_secs / f.processing_secs
+            } else {
+                0.0
+            };
+            let _ = writeln!(
+                html,
+                "<tr><td title=\"{}\">{}</td><td class=\"n\">{}</td><td class=\"n\">{:.1}s</td>\
+                 <td class=\"n\">{realtime:.1}x</td><td class=\"n\">{}</td><td class=\"n\">{}</td>\
+                 <td class=\"n\">{}</td><td>{}</td></tr>",
+                escape_xml(&f.path.display().to_string()),
+                escape_xml(&f.path.file_name().unwrap_or_default().to_string_lossy()),
+                format_duration(f.audio_duration_secs),
+                f.processing_secs,
+                f.segments,
+                f.detections,
+                f.species,
+                bar_chart(&f.timeline, 4, 16)
+            );
+        }
+        html.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+        html
+    }
+}
+
+/// Histogram of all species together.
+fn overall_histogram(species: &[SpeciesSummary]) -> [u32; RUN_HISTOGRAM_BINS] {
+    let mut overall = [0u32; RUN_HISTOGRAM_BINS];
+    for s in species {
+        for (total, count) in overall.iter_mut().zip(s.histogram) {
+            *total += count;
+        }
+    }
+    overall
+}
+
+/// A confidence as a whole percentage, or a dash when there is none.
+fn percent_or_dash(confidence: Option<f32>) -> String {
+    confidence.map_or_else(|| "&ndash;".to_string(), |c| format!("{:.0}%", c * 100.0))
+}
+
+/// Inline SVG bar chart of `counts`, each bar `bar_width` pixels wide.
+fn bar_chart(counts: &[u32], bar_width: usize, height: usize) -> String {
+    let max = counts.iter().copied().max().unwrap_or(0).max(1);
+    let mut svg = format!(
+        "<svg width=\"{}\" height=\"{height}\" role=\"img\">",
+        counts.len() * bar_width
+    );
+    for (i, &count) in counts.iter().enumerate().filter(|(_, c)| **c > 0) {
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss
+        )]
+        let bar = ((count as f64 / f64::from(max)) * height as f64).ceil() as usize;
+        let _ = write!(
+            svg,
+            "<rect class=\"bar\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{bar}\"><title>{count}</title></rect>",
+            i * bar_width,
+            height - bar,
+            bar_width.saturating_sub(1).max(1)
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_file_aggregates_species() {
+        let report = RunReport::default();
+        report.add_file(
+            Path::new("a.wav"),
+            &[
+                Detection::test("Parus major_Great Tit", 0.0, "a.wav").with_confidence(0.92),
+                Detection::test("Parus major_Great Tit", 30.0, "a.wav").with_confidence(0.41),
+                Detection::test("Turdus merula_Eurasian Blackbird", 57.0, "a.wav")
+                    .with_confidence(0.6),
+            ],
+            60.0,
+            1.5,
+            20,
+        );
+        report.add_file(
+            Path::new("b.wav"),
+            &[Detection::test("Parus major_Great Tit", 3.0, "a.wav").with_confidence(0.5)],
+            60.0,
+            1.0,
+            20,
+        );
+
+        let species = report.species();
+        assert_eq!(species[0].scientific_name, "Parus major");
+        assert_eq!(species[0].detections, 3);
+        assert_eq!(species[0].files, 2);
+        assert_eq!(species[0].histogram[18], 1);
+        assert_eq!(species[0].histogram[8], 1);
+        assert_eq!(species[0].histogram[10], 1);
+
+        let files = report.files();
+        assert_eq!(files[0].species, 2);
+        assert_eq!(files[0].timeline[0], 1);
+        assert_eq!(files[0].timeline[30], 1);
+        assert_eq!(files[0].timeline[RUN_TIMELINE_BINS - 3], 1);
+    }
+
+    #[test]
+    fn test_render_html_escapes_names() {
+        let report = RunReport::default();
+        report.add_file(
+            Path::new("<site>.wav"),
+            &[Detection::test("A b_A <b>", 0.0, "a.wav").with_confidence(0.9)],
+            3.0,
+            0.1,
+            1,
+        );
+        let html = report.render_html(&RunTotals {
+            model: "birdnet".to_string(),
+            ..RunTotals::default()
+        });
+        assert!(html.contains("A &lt;b&gt;"));
+        assert!(html.contains("&lt;site&gt;.wav"));
+        assert!(html.contains("<svg"));
+    }
+
+    #[test]
+    fn test_suggest_threshold_finds_elbow() {
+        // Many weak detections falling off quickly, then a long flat tail
+        let mut histogram = [0u32; RUN_HISTOGRAM_BINS];
+        histogram[2..8].copy_from_slice(&[120, 60, 25, 10, 5, 4]);
+        for count in &mut histogram[8..] {
+            *count = 4;
+        }
+        let threshold = suggest_threshold(&histogram, 0.1).unwrap();
+        assert!((0.15..=0.4).contains(&threshold), "got {threshold}");
+        assert!(suggest_threshold(&histogram, 0.5).unwrap() >= 0.5);
+    }
+
+    #[test]
+    fn test_suggest_threshold_needs_detections() {
+        let mut histogram = [0u32; RUN_HISTOGRAM_BINS];
+        histogram[10] = 5;
+        assert!(suggest_threshold(&histogram, 0.1).is_none());
+    }
+
+    #[test]
+    fn test_median_confidence() {
+        let mut histogram = [0u32; RUN_HISTOGRAM_BINS];
+        histogram[4] = 1;
+        histogram[16] = 3;
+        assert!((median_confidence(&histogram) - 0.825).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_bin_index_clamps() {
+        assert_eq!(bin_index(1.0, 20), 19);
+        assert_eq!(bin_index(-0.5, 20), 0);
+        assert_eq!(bin_index(0.5, 20), 10);
+    }
+}
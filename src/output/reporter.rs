@@ -5,10 +5,10 @@
 
 use crate::config::OutputMode;
 use crate::output::json_envelope::{
-    BatchProgress, BsgMetadata, CancelReason, CancelledPayload, ErrorPayload, ErrorSeverity,
-    EventType, FileCompletedPayload, FileErrorInfo, FileProgress, FileStartedPayload, FileStatus,
-    JsonEnvelope, PipelineCompletedPayload, PipelineStartedPayload, PipelineStatus,
-    ProgressPayload,
+    BatchProgress, BsgMetadata, CancelReason, CancelledPayload, DownloadProgress, ErrorPayload,
+    ErrorSeverity, EventType, FileCompletedPayload, FileErrorInfo, FileProgress,
+    FileSpeciesSummary, FileStartedPayload, FileStatus, JsonEnvelope, LogPayload,
+    PipelineCompletedPayload, PipelineStartedPayload, PipelineStatus, ProgressPayload,
 };
 use std::io::{self, Write};
 use std::path::Path;
@@ -30,6 +30,11 @@ pub trait ProgressReporter: Send + Sync {
         range_filter: Option<&crate::output::RangeFilterInfo>,
     );
 
+    /// Report the resolved settings of the run, before any file starts.
+    ///
+    /// The default implementation ignores them.
+    fn run_config(&self, _config: &crate::output::RunConfigPayload) {}
+
     /// Report file processing start.
     fn file_started(
         &self,
@@ -37,13 +42,20 @@ pub trait ProgressReporter: Send + Sync {
         index: usize,
         estimated_segments: usize,
         duration_seconds: Option<f64>,
+        recorder: Option<&crate::audio::RecorderMetadata>,
     );
 
     /// Report progress update.
     fn progress(&self, batch: Option<&BatchProgress>, file: Option<&FileProgress>);
 
-    /// Report file completion (success).
-    fn file_completed_success(&self, file: &Path, detections: usize, duration_ms: u64);
+    /// Report file completion (success) with a summary of the species found.
+    fn file_completed_success(
+        &self,
+        file: &Path,
+        detections: usize,
+        species: &[FileSpeciesSummary],
+        duration_ms: u64,
+    );
 
     /// Report file completion (failure).
     fn file_completed_failure(&self, file: &Path, error_code: &str, error_message: &str);
@@ -67,6 +79,19 @@ pub trait ProgressReporter: Send + Sync {
         detections: &[crate::output::Detection],
         bsg_metadata: Option<&BsgMetadata>,
     );
+
+    /// Write out any buffered events (e.g. before the process terminates).
+    fn flush(&self);
+
+    /// Report model download progress.
+    ///
+    /// Callers throttle these updates; the default implementation ignores them.
+    fn download_progress(&self, _progress: &DownloadProgress) {}
+
+    /// Report a diagnostic message captured from logging (`--log-events`).
+    ///
+    /// The default implementation ignores it.
+    fn log(&self, _level: &str, _target: &str, _message: &str) {}
 }
 
 /// Summary of pipeline execution.
@@ -86,6 +111,14 @@ pub struct PipelineSummary {
     pub duration_ms: u64,
     /// Realtime processing factor.
     pub realtime_factor: f64,
+    /// Batch size in effect at the end of the run (after any GPU memory backoff).
+    pub effective_batch_size: Option<usize>,
+    /// Confidence distributions and suggested thresholds, if requested.
+    pub confidence: Option<crate::output::ConfidenceSummary>,
+    /// Peak resident memory of the process in bytes, where available.
+    pub peak_rss_bytes: Option<u64>,
+    /// Peak GPU memory used by the process in bytes, where available.
+    pub peak_gpu_memory_bytes: Option<u64>,
 }
 
 /// Progress throttler to limit update frequency.
@@ -173,6 +206,8 @@ pub struct JsonProgressReporter {
     writer: Mutex<Box<dyn Write + Send>>,
     /// Buffer for JSON mode (collect all events).
     json_buffer: Mutex<Vec<String>>,
+    /// Station recorded on every event, if one is configured.
+    station: Option<crate::output::Station>,
 }
 
 impl JsonProgressReporter {
@@ -183,9 +218,17 @@ impl JsonProgressReporter {
             throttler: ProgressThrottler::new(),
             writer: Mutex::new(Box::new(io::stdout())),
             json_buffer: Mutex::new(Vec::new()),
+            station: None,
         }
     }
 
+    /// Record `station` on every event.
+    #[must_use]
+    pub fn with_station(mut self, station: Option<crate::output::Station>) -> Self {
+        self.station = station;
+        self
+    }
+
     /// Create a reporter with a custom writer (for testing).
     #[cfg(test)]
     pub fn with_writer<W: Write + Send + 'static>(mode: OutputMode, writer: W) -> Self {
@@ -194,12 +237,13 @@ impl JsonProgressReporter {
             throttler: ProgressThrottler::new(),
             writer: Mutex::new(Box::new(writer)),
             json_buffer: Mutex::new(Vec::new()),
+            station: None,
         }
     }
 
     /// Emit an event as JSON.
     fn emit<T: serde::Serialize>(&self, event: EventType, payload: T) {
-        let envelope = JsonEnvelope::new(event, payload);
+        let envelope = JsonEnvelope::new(event, payload).with_station(self.station.clone());
         if let Ok(json) = serde_json::to_string(&envelope) {
             match self.mode {
                 OutputMode::Ndjson => {
@@ -231,26 +275,6 @@ impl JsonProgressReporter {
             }
         }
     }
-
-    /// Flush buffered JSON output (for Json mode).
-    pub fn flush(&self) {
-        if self.mode == OutputMode::Json
-            && let Ok(buffer) = self.json_buffer.lock()
-            && let Ok(mut writer) = self.writer.lock()
-        {
-            // Output as JSON array
-            let _ = writeln!(writer, "[");
-            for (i, json) in buffer.iter().enumerate() {
-                if i > 0 {
-                    let _ = writeln!(writer, ",");
-                }
-                let _ = write!(writer, "  {json}");
-            }
-            let _ = writeln!(writer);
-            let _ = writeln!(writer, "]");
-            let _ = writer.flush();
-        }
-    }
 }
 
 impl ProgressReporter for JsonProgressReporter {
@@ -274,12 +298,17 @@ impl ProgressReporter for JsonProgressReporter {
         );
     }
 
+    fn run_config(&self, config: &crate::output::RunConfigPayload) {
+        self.emit(EventType::Result, config);
+    }
+
     fn file_started(
         &self,
         file: &Path,
         index: usize,
         estimated_segments: usize,
         duration_seconds: Option<f64>,
+        recorder: Option<&crate::audio::RecorderMetadata>,
     ) {
         self.throttler.reset();
         self.emit(
@@ -289,6 +318,7 @@ impl ProgressReporter for JsonProgressReporter {
                 index,
                 estimated_segments,
                 duration_seconds,
+                recorder: recorder.cloned(),
             },
         );
     }
@@ -309,7 +339,35 @@ impl ProgressReporter for JsonProgressReporter {
         }
     }
 
-    fn file_completed_success(&self, file: &Path, detections: usize, duration_ms: u64) {
+    fn download_progress(&self, progress: &DownloadProgress) {
+        self.emit(
+            EventType::Progress,
+            ProgressPayload {
+                batch: None,
+                file: None,
+                download: Some(progress.clone()),
+            },
+        );
+    }
+
+    fn log(&self, level: &str, target: &str, message: &str) {
+        self.emit(
+            EventType::Log,
+            LogPayload {
+                level: level.to_string(),
+                target: target.to_string(),
+                message: message.to_string(),
+            },
+        );
+    }
+
+    fn file_completed_success(
+        &self,
+        file: &Path,
+        detections: usize,
+        species: &[FileSpeciesSummary],
+        duration_ms: u64,
+    ) {
         self.emit(
             EventType::FileCompleted,
             FileCompletedPayload {
@@ -318,6 +376,7 @@ impl ProgressReporter for JsonProgressReporter {
                 detections: Some(detections),
                 duration_ms: Some(duration_ms),
                 error: None,
+                species: Some(species.to_vec()),
             },
         );
     }
@@ -334,6 +393,7 @@ impl ProgressReporter for JsonProgressReporter {
                     code: error_code.to_string(),
                     message: error_message.to_string(),
                 }),
+                species: None,
             },
         );
     }
@@ -347,6 +407,7 @@ impl ProgressReporter for JsonProgressReporter {
                 detections: None,
                 duration_ms: None,
                 error: None,
+                species: None,
             },
         );
     }
@@ -371,6 +432,10 @@ impl ProgressReporter for JsonProgressReporter {
                 total_segments: summary.total_segments,
                 duration_ms: summary.duration_ms,
                 realtime_factor: summary.realtime_factor,
+                effective_batch_size: summary.effective_batch_size,
+                confidence: summary.confidence.clone(),
+                peak_rss_bytes: summary.peak_rss_bytes,
+                peak_gpu_memory_bytes: summary.peak_gpu_memory_bytes,
             },
         );
 
@@ -412,17 +477,7 @@ impl ProgressReporter for JsonProgressReporter {
     ) {
         use crate::output::{DetectionInfo, DetectionsPayload};
 
-        let detection_infos: Vec<DetectionInfo> = detections
-            .iter()
-            .map(|d| DetectionInfo {
-                species: format!("{}_{}", d.scientific_name, d.common_name),
-                common_name: d.common_name.clone(),
-                scientific_name: d.scientific_name.clone(),
-                confidence: d.confidence,
-                start_time: d.start_time,
-                end_time: d.end_time,
-            })
-            .collect();
+        let detection_infos: Vec<DetectionInfo> = detections.iter().map(Into::into).collect();
 
         self.emit(
             EventType::Detections,
@@ -433,6 +488,31 @@ impl ProgressReporter for JsonProgressReporter {
             },
         );
     }
+
+    /// Flush buffered JSON output (for Json mode).
+    ///
+    /// The buffer is drained, so events are written at most once even if
+    /// flush is called again (e.g. from the panic hook after completion).
+    fn flush(&self) {
+        if self.mode == OutputMode::Json
+            && let Ok(mut buffer) = self.json_buffer.lock()
+            && !buffer.is_empty()
+            && let Ok(mut writer) = self.writer.lock()
+        {
+            let events = std::mem::take(&mut *buffer);
+            // Output as JSON array
+            let _ = writeln!(writer, "[");
+            for (i, json) in events.iter().enumerate() {
+                if i > 0 {
+                    let _ = writeln!(writer, ",");
+                }
+                let _ = write!(writer, "  {json}");
+            }
+            let _ = writeln!(writer);
+            let _ = writeln!(writer, "]");
+            let _ = writer.flush();
+        }
+    }
 }
 
 /// Null reporter that does nothing (for human mode or disabled progress).
@@ -457,10 +537,18 @@ impl ProgressReporter for NullReporter {
         _index: usize,
         _estimated_segments: usize,
         _duration_seconds: Option<f64>,
+        _recorder: Option<&crate::audio::RecorderMetadata>,
     ) {
     }
     fn progress(&self, _batch: Option<&BatchProgress>, _file: Option<&FileProgress>) {}
-    fn file_completed_success(&self, _file: &Path, _detections: usize, _duration_ms: u64) {}
+    fn file_completed_success(
+        &self,
+        _file: &Path,
+        _detections: usize,
+        _species: &[FileSpeciesSummary],
+        _duration_ms: u64,
+    ) {
+    }
     fn file_completed_failure(&self, _file: &Path, _error_code: &str, _error_message: &str) {}
     fn file_skipped(&self, _file: &Path, _reason: FileStatus) {}
     fn pipeline_completed(&self, _summary: &PipelineSummary) {}
@@ -480,13 +568,19 @@ impl ProgressReporter for NullReporter {
         _bsg_metadata: Option<&BsgMetadata>,
     ) {
     }
+    fn flush(&self) {}
 }
 
-/// Create a reporter based on output mode.
-pub fn create_reporter(mode: OutputMode) -> Box<dyn ProgressReporter> {
+/// Create a reporter based on output mode, recording `station` on JSON events.
+pub fn create_reporter(
+    mode: OutputMode,
+    station: Option<crate::output::Station>,
+) -> Box<dyn ProgressReporter> {
     match mode {
         OutputMode::Human => Box::new(NullReporter),
-        OutputMode::Json | OutputMode::Ndjson => Box::new(JsonProgressReporter::new(mode)),
+        OutputMode::Json | OutputMode::Ndjson => {
+            Box::new(JsonProgressReporter::new(mode).with_station(station))
+        }
     }
 }
 
@@ -561,6 +655,31 @@ mod tests {
         assert!(output_str.contains("\"total_files\":5"));
     }
 
+    #[test]
+    fn test_json_reporter_emits_download_progress() {
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let writer = TestWriter {
+            buffer: buffer.clone(),
+        };
+
+        let reporter = JsonProgressReporter::with_writer(OutputMode::Ndjson, writer);
+        reporter.download_progress(&DownloadProgress {
+            operation: "download".to_string(),
+            model: "birdnet-v24".to_string(),
+            file: "model.onnx".to_string(),
+            downloaded_bytes: 512,
+            total_bytes: 1024,
+            percent: 50.0,
+            attempt: 1,
+        });
+
+        let output = buffer.lock().expect("lock");
+        let output_str = String::from_utf8_lossy(&output);
+        assert!(output_str.contains("\"event\":\"progress\""));
+        assert!(output_str.contains("\"downloaded_bytes\":512"));
+        assert!(!output_str.contains("\"file\":{"));
+    }
+
     #[test]
     fn test_null_reporter_does_nothing() {
         let reporter = NullReporter;
@@ -570,8 +689,8 @@ mod tests {
             fallback_reason: None,
         };
         reporter.pipeline_started(10, "model", 0.1, &dummy_ep, None);
-        reporter.file_started(Path::new("test.wav"), 0, 100, Some(60.0));
-        reporter.file_completed_success(Path::new("test.wav"), 5, 1000);
+        reporter.file_started(Path::new("test.wav"), 0, 100, Some(60.0), None);
+        reporter.file_completed_success(Path::new("test.wav"), 5, &[], 1000);
         // No assertions - just verifying it doesn't panic
     }
 
@@ -632,6 +751,24 @@ mod tests {
         // Test passes if no panic occurs
     }
 
+    #[test]
+    fn test_json_mode_flush_writes_once() {
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let writer = TestWriter {
+            buffer: buffer.clone(),
+        };
+
+        let reporter = JsonProgressReporter::with_writer(OutputMode::Json, writer);
+        reporter.error("panic", ErrorSeverity::Fatal, "boom", None);
+        reporter.flush();
+        reporter.flush();
+
+        let output = buffer.lock().expect("lock");
+        let output_str = String::from_utf8_lossy(&output);
+        assert_eq!(output_str.matches("\"event\":\"error\"").count(), 1);
+        assert_eq!(output_str.matches('[').count(), 1);
+    }
+
     /// Test writer that captures output.
     struct TestWriter {
         buffer: Arc<Mutex<Vec<u8>>>,
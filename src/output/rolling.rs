@@ -0,0 +1,447 @@
+//! Splitting large result files into numbered parts.
+//!
+//! Some downstream tools (Excel, several GIS importers) cannot open
+//! multi-million-row files. A [`RollingWriter`] finalizes the current part
+//! once a row or size limit is reached and continues in the next one. The
+//! first part keeps the normal output name; later parts insert `.partN`
+//! before the extension (`rec.BirdNET.results.part2.csv`). When appending,
+//! the writer continues after the parts of the earlier run.
+
+use crate::config::OutputFormat;
+use crate::error::{Error, Result};
+use crate::output::{CsvWriter, Detection, NumberFormat, OutputWriter, ParquetWriter};
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// Limits after which an output file rolls over into a new part.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SplitLimits {
+    /// Maximum detections per part.
+    pub max_rows: Option<u64>,
+    /// Approximate maximum size of a part in bytes.
+    pub max_bytes: Option<u64>,
+}
+
+impl SplitLimits {
+    /// Whether outputs in `format` are split under these limits.
+    ///
+    /// Only CSV and Parquet are split; other formats are always one file.
+    pub const fn applies_to(self, format: OutputFormat) -> bool {
+        (self.max_rows.is_some() || self.max_bytes.is_some())
+            && matches!(format, OutputFormat::Csv | OutputFormat::Parquet)
+    }
+
+    /// Whether a part with `rows` detections and `bytes` bytes is full.
+    fn reached(self, rows: u64, bytes: u64) -> bool {
+        self.max_rows.is_some_and(|max| rows >= max)
+            || self.max_bytes.is_some_and(|max| bytes >= max)
+    }
+}
+
+/// Path of part `part` (1-based) of the output at `path`.
+///
+/// Part 1 is `path` itself so existing-output checks keep working.
+pub fn part_path(path: &Path, part: usize) -> PathBuf {
+    if part <= 1 {
+        return path.to_path_buf();
+    }
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name = match name.rsplit_once('.') {
+        Some((stem, ext)) => format!("{stem}.part{part}.{ext}"),
+        None => format!("{name}.part{part}"),
+    };
+    path.with_file_name(name)
+}
+
+/// First part of `path` that does not exist yet.
+///
+/// Used to add Parquet files next to earlier ones when appending, since a
+/// finished Parquet file cannot be extended. Existing parts without a Parquet
+/// footer (from an interrupted run) are reported, as readers will reject them.
+pub fn next_free_part(path: &Path) -> PathBuf {
+    let mut part = 1;
+    loop {
+        let candidate = part_path(path, part);
+        if !candidate.exists() {
+            return candidate;
+        }
+        if !has_parquet_footer(&candidate) {
+            warn!(
+                "{} is incomplete (interrupted run?) and cannot be read",
+                candidate.display()
+            );
+        }
+        part += 1;
+    }
+}
+
+/// Number of the last existing part of `path`, or 0 if there is none.
+fn last_part(path: &Path) -> usize {
+    let mut part = 0;
+    while part_path(path, part + 1).exists() {
+        part += 1;
+    }
+    part
+}
+
+/// Number of detection rows in the CSV file at `path`.
+fn csv_rows(path: &Path) -> Result<u64> {
+    let parse_error = |e: csv::Error| Error::DetectionParseFailed {
+        path: path.to_path_buf(),
+        source: Box::new(e),
+    };
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .from_path(path)
+        .map_err(parse_error)?;
+    let mut record = csv::ByteRecord::new();
+    let mut rows = 0;
+    while reader.read_byte_record(&mut record).map_err(parse_error)? {
+        rows += 1;
+    }
+    Ok(rows)
+}
+
+/// Whether a file ends with the Parquet magic bytes.
+fn has_parquet_footer(path: &Path) -> bool {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut magic = [0u8; 4];
+    std::fs::File::open(path)
+        .and_then(|mut file| {
+            file.seek(SeekFrom::End(-4))?;
+            file.read_exact(&mut magic)
+        })
+        .is_ok()
+        && &magic == b"PAR1"
+}
+
+/// Writer that rolls CSV or Parquet output over into numbered parts.
+pub struct RollingWriter {
+    path: PathBuf,
+    format: OutputFormat,
+    csv_columns: Vec<String>,
+    csv_bom_enabled: bool,
+    number_format: NumberFormat,
+    limits: SplitLimits,
+    current: Box<dyn OutputWriter + Send>,
+    part: usize,
+    rows: u64,
+    /// Bytes the current part held before it was opened for appending.
+    existing_bytes: u64,
+}
+
+impl RollingWriter {
+    /// Create a rolling writer whose first part is `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the first part cannot be created.
+    pub fn new(
+        path: &Path,
+        format: OutputFormat,
+        csv_columns: &[String],
+        csv_bom_enabled: bool,
+        number_format: NumberFormat,
+        limits: SplitLimits,
+    ) -> Result<Self> {
+        let current = open_part(path, format, csv_columns, csv_bom_enabled, number_format)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            format,
+            csv_columns: csv_columns.to_vec(),
+            csv_bom_enabled,
+            number_format,
+            limits,
+            current,
+            part: 1,
+            rows: 0,
+            existing_bytes: 0,
+        })
+    }
+
+    /// Create a rolling writer that continues the parts of an earlier run.
+    ///
+    /// CSV detections are appended to the last existing part, whose rows and
+    /// size count towards the limits. Parquet starts a new part after the
+    /// last one, since a finished Parquet file cannot be extended.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the part cannot be opened, or the last CSV part
+    /// cannot be read or has a different header.
+    pub fn append(
+        path: &Path,
+        format: OutputFormat,
+        csv_columns: &[String],
+        csv_bom_enabled: bool,
+        number_format: NumberFormat,
+        limits: SplitLimits,
+    ) -> Result<Self> {
+        let last = last_part(path);
+        let (part, current, rows, existing_bytes) = if format == OutputFormat::Parquet {
+            let part = last + 1;
+            let current = open_part(
+                &part_path(path, part),
+                format,
+                csv_columns,
+                csv_bom_enabled,
+                number_format,
+            )?;
+            (part, current, 0, 0)
+        } else {
+            let part = last.max(1);
+            let part_file = part_path(path, part);
+            // Opening first drops a partial last line left by an interrupted run
+            let current: Box<dyn OutputWriter + Send> = Box::new(
+                CsvWriter::append(&part_file, csv_columns.to_vec(), csv_bom_enabled)?
+                    .with_number_format(number_format),
+            );
+            let rows = csv_rows(&part_file)?;
+            let existing_bytes = std::fs::metadata(&part_file)?.len();
+            (part, current, rows, existing_bytes)
+        };
+        debug!(
+            "Appending {} output to {}",
+            format,
+            part_path(path, part).display()
+        );
+        Ok(Self {
+            path: path.to_path_buf(),
+            format,
+            csv_columns: csv_columns.to_vec(),
+            csv_bom_enabled,
+            number_format,
+            limits,
+            current,
+            part,
+            rows,
+            existing_bytes,
+        })
+    }
+
+    /// Finalize the current part and start the next one with a fresh header.
+    fn roll_over(&mut self) -> Result<()> {
+        self.current.finalize()?;
+        self.part += 1;
+        let path = part_path(&self.path, self.part);
+        debug!("Continuing {} output in {}", self.format, path.display());
+        self.current = open_part(
+            &path,
+            self.format,
+            &self.csv_columns,
+            self.csv_bom_enabled,
+            self.number_format,
+        )?;
+        self.current.write_header()?;
+        self.rows = 0;
+        self.existing_bytes = 0;
+        Ok(())
+    }
+}
+
+impl OutputWriter for RollingWriter {
+    fn write_header(&mut self) -> Result<()> {
+        self.current.write_header()
+    }
+
+    fn write_detection(&mut self, detection: &Detection) -> Result<()> {
+        if self.rows > 0
+            && self.limits.reached(
+                self.rows,
+                self.existing_bytes + self.current.bytes_written(),
+            )
+        {
+            self.roll_over()?;
+        }
+        self.current.write_detection(detection)?;
+        self.rows += 1;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.current.flush()
+    }
+
+    fn finalize(&mut self) -> Result<()> {
+        self.current.finalize()?;
+
+        // Remove parts left over from an earlier, larger run
+        let mut stale = self.part + 1;
+        while std::fs::remove_file(part_path(&self.path, stale)).is_ok() {
+            stale += 1;
+        }
+        Ok(())
+    }
+
+    fn bytes_written(&self) -> u64 {
+        self.current.bytes_written()
+    }
+}
+
+/// Create the writer for one part.
+fn open_part(
+    path: &Path,
+    format: OutputFormat,
+    csv_columns: &[String],
+    csv_bom_enabled: bool,
+    number_format: NumberFormat,
+) -> Result<Box<dyn OutputWriter + Send>> {
+    match format {
+        OutputFormat::Parquet => Ok(Box::new(ParquetWriter::new(path, csv_columns)?)),
+        _ => Ok(Box::new(
+            CsvWriter::new(path, csv_columns.to_vec(), csv_bom_enabled)?
+                .with_number_format(number_format),
+        )),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_part_path() {
+        let path = Path::new("/out/rec.BirdNET.results.csv");
+        assert_eq!(part_path(path, 1), path);
+        assert_eq!(
+            part_path(path, 2),
+            PathBuf::from("/out/rec.BirdNET.results.part2.csv")
+        );
+    }
+
+    #[test]
+    fn test_next_free_part() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("combined.parquet");
+        assert_eq!(next_free_part(&path), path);
+
+        std::fs::write(&path, b"PAR1...PAR1").unwrap();
+        assert_eq!(next_free_part(&path), part_path(&path, 2));
+    }
+
+    #[test]
+    fn test_applies_only_to_csv_and_parquet() {
+        let limits = SplitLimits {
+            max_rows: Some(10),
+            max_bytes: None,
+        };
+        assert!(limits.applies_to(OutputFormat::Csv));
+        assert!(limits.applies_to(OutputFormat::Parquet));
+        assert!(!limits.applies_to(OutputFormat::Raven));
+        assert!(!SplitLimits::default().applies_to(OutputFormat::Csv));
+    }
+
+    #[test]
+    fn test_rolls_over_by_row_count() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("rec.BirdNET.results.csv");
+        // Leftover from a previous run that produced more parts
+        std::fs::write(part_path(&path, 4), "stale").unwrap();
+
+        let limits = SplitLimits {
+            max_rows: Some(2),
+            max_bytes: None,
+        };
+        let mut writer = RollingWriter::new(
+            &path,
+            OutputFormat::Csv,
+            &[],
+            false,
+            NumberFormat::default(),
+            limits,
+        )
+        .unwrap();
+        writer.write_header().unwrap();
+        for i in 0..5 {
+            #[allow(clippy::cast_precision_loss)]
+            writer
+                .write_detection(&Detection::test(
+                    "Parus major_Great Tit",
+                    i as f32 * 3.0,
+                    "rec.wav",
+                ))
+                .unwrap();
+        }
+        writer.finalize().unwrap();
+
+        for (part, rows) in [(1, 2), (2, 2), (3, 1)] {
+            let content = std::fs::read_to_string(part_path(&path, part)).unwrap();
+            assert!(content.starts_with("Start (s)"));
+            assert_eq!(content.lines().count(), rows + 1);
+        }
+        assert!(!part_path(&path, 4).exists());
+    }
+
+    #[test]
+    fn test_rolls_over_by_size() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("rec.BirdNET.results.csv");
+        let limits = SplitLimits {
+            max_rows: None,
+            max_bytes: Some(100),
+        };
+        let mut writer = RollingWriter::new(
+            &path,
+            OutputFormat::Csv,
+            &[],
+            false,
+            NumberFormat::default(),
+            limits,
+        )
+        .unwrap();
+        writer.write_header().unwrap();
+        for i in 0..10 {
+            #[allow(clippy::cast_precision_loss)]
+            writer
+                .write_detection(&Detection::test(
+                    "Parus major_Great Tit",
+                    i as f32 * 3.0,
+                    "rec.wav",
+                ))
+                .unwrap();
+        }
+        writer.finalize().unwrap();
+
+        assert!(part_path(&path, 2).exists());
+    }
+
+    #[test]
+    fn test_append_continues_last_part() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("BirdNET_CombinedTable.csv");
+        let limits = SplitLimits {
+            max_rows: Some(2),
+            max_bytes: None,
+        };
+        let detection = Detection::test("Parus major_Great Tit", 0.0, "rec.wav");
+        for _ in 0..2 {
+            let mut writer = RollingWriter::append(
+                &path,
+                OutputFormat::Csv,
+                &[],
+                false,
+                NumberFormat::default(),
+                limits,
+            )
+            .unwrap();
+            writer.write_header().unwrap();
+            for _ in 0..3 {
+                writer.write_detection(&detection).unwrap();
+            }
+            writer.finalize().unwrap();
+        }
+
+        // 6 rows: the second run fills part 2 and continues in part 3
+        for (part, rows) in [(1, 2), (2, 2), (3, 2)] {
+            let content = std::fs::read_to_string(part_path(&path, part)).unwrap();
+            assert_eq!(content.lines().count(), rows + 1);
+        }
+        assert!(!part_path(&path, 4).exists());
+    }
+}
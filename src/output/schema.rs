@@ -0,0 +1,401 @@
+//! Machine-readable column schemas of the detection output formats.
+//!
+//! `birda formats describe` emits these so downstream pipelines can check
+//! that the files they read have the layout they were built for. Each format
+//! has a version that is bumped whenever its columns change. JSON files carry
+//! it as `format_version`, Parquet files as `birda.format_version` key-value
+//! metadata and SQLite databases as `PRAGMA user_version`; CSV and Parquet
+//! files can add it as a `format_version` column.
+
+use crate::config::OutputFormat;
+use crate::constants::format_version;
+use crate::output::{ColumnSchema, ColumnType, FormatSchema};
+
+/// Version of the columns written for `format`.
+pub const fn format_version_of(format: OutputFormat) -> u32 {
+    match format {
+        OutputFormat::Csv => format_version::CSV,
+        OutputFormat::BirdnetCsv => format_version::BIRDNET_CSV,
+        OutputFormat::Raven => format_version::RAVEN,
+        OutputFormat::Audacity => format_version::AUDACITY,
+        OutputFormat::Kaleidoscope => format_version::KALEIDOSCOPE,
+        OutputFormat::Json => format_version::JSON,
+        OutputFormat::Parquet => format_version::PARQUET,
+        OutputFormat::Sqlite => format_version::SQLITE,
+    }
+}
+
+/// Column with values in every row.
+fn column(
+    name: &str,
+    column_type: ColumnType,
+    unit: Option<&str>,
+    description: &str,
+) -> ColumnSchema {
+    ColumnSchema {
+        name: name.to_string(),
+        column_type,
+        unit: unit.map(str::to_string),
+        nullable: false,
+        description: description.to_string(),
+    }
+}
+
+/// Column whose values may be empty.
+fn nullable(
+    name: &str,
+    column_type: ColumnType,
+    unit: Option<&str>,
+    description: &str,
+) -> ColumnSchema {
+    ColumnSchema {
+        nullable: true,
+        ..column(name, column_type, unit, description)
+    }
+}
+
+/// Columns selectable with `defaults.csv_columns.include`, shared by CSV and
+/// Parquet.
+fn optional_columns(format: OutputFormat) -> Vec<ColumnSchema> {
+    use ColumnType::{Boolean, Float, Integer, String, Timestamp};
+
+    let mut columns = vec![
+        nullable("lat", Float, Some("degrees"), "Recording latitude"),
+        nullable("lon", Float, Some("degrees"), "Recording longitude"),
+        nullable("week", Integer, None, "BirdNET week of year (1-48)"),
+        nullable("model", String, None, "Configured model name"),
+        nullable("overlap", Float, Some("seconds"), "Segment overlap"),
+        nullable("sensitivity", Float, None, "Sigmoid sensitivity"),
+        nullable("min_conf", Float, None, "Minimum confidence threshold"),
+        nullable("species_list", String, None, "Species list file"),
+        nullable("annotation", String, None, "Annotation of the detection"),
+        nullable("fingerprint", String, None, "Stable detection identifier"),
+        nullable("device", String, None, "Execution provider"),
+        nullable("model_hash", String, None, "SHA-256 hash of the model file"),
+        nullable("processed_at", Timestamp, None, "Processing time"),
+        nullable("recorder_model", String, None, "Recorder model"),
+        nullable("recorder_serial", String, None, "Recorder serial number"),
+        nullable("recorder_gain", String, None, "Recorder microphone gain"),
+        nullable(
+            "recorder_temperature",
+            Float,
+            Some("degrees Celsius"),
+            "Recorder temperature",
+        ),
+        nullable("recorder_schedule", String, None, "Recorder schedule"),
+        nullable("station_id", String, None, "Station identifier"),
+        nullable("station_name", String, None, "Station name"),
+        nullable(
+            "channel",
+            Integer,
+            None,
+            "Analyzed audio channel (1-based), with --channel",
+        ),
+        nullable(
+            "datetime_start",
+            Timestamp,
+            None,
+            "Absolute segment start (local time), with --wall-clock",
+        ),
+        nullable(
+            "datetime_end",
+            Timestamp,
+            None,
+            "Absolute segment end (local time), with --wall-clock",
+        ),
+        nullable(
+            "sensitive",
+            Boolean,
+            None,
+            "Species is on a sensitive species list",
+        ),
+        column(
+            format_version::COLUMN,
+            Integer,
+            None,
+            "Format version of the file",
+        ),
+    ];
+    if format == OutputFormat::Parquet {
+        columns.push(nullable(
+            "path",
+            String,
+            None,
+            "Full path of the analyzed recording",
+        ));
+    }
+    columns
+}
+
+/// Column schema of `format` at its current version.
+pub fn format_schema(format: OutputFormat) -> FormatSchema {
+    use ColumnType::{Boolean, Float, Integer, String, Timestamp};
+
+    let confidence = "Model confidence (0-1)";
+    let (delimiter, header, version_location, columns) = match format {
+        OutputFormat::Csv => (
+            Some(","),
+            true,
+            Some("optional format_version column"),
+            vec![
+                column("Start (s)", Float, Some("seconds"), "Segment start"),
+                column("End (s)", Float, Some("seconds"), "Segment end"),
+                column("Scientific name", String, None, "Species scientific name"),
+                column("Common name", String, None, "Species common name"),
+                column("Confidence", Float, None, confidence),
+                column("File", String, None, "Path of the analyzed recording"),
+            ],
+        ),
+        OutputFormat::BirdnetCsv => (
+            Some(","),
+            true,
+            None,
+            vec![
+                column("Start (s)", Float, Some("seconds"), "Segment start"),
+                column("End (s)", Float, Some("seconds"), "Segment end"),
+                column("Scientific name", String, None, "Species scientific name"),
+                column("Common name", String, None, "Species common name"),
+                column("Confidence", Float, None, confidence),
+            ],
+        ),
+        OutputFormat::Raven => (
+            Some("\t"),
+            true,
+            None,
+            vec![
+                column("Selection", Integer, None, "Selection number"),
+                column("View", String, None, "Raven view"),
+                column("Channel", Integer, None, "Audio channel (1-based)"),
+                column(
+                    "Begin Time (s)",
+                    Float,
+                    Some("seconds"),
+                    "Segment start; in combined tables, on a timeline running across the listed recordings",
+                ),
+                column(
+                    "End Time (s)",
+                    Float,
+                    Some("seconds"),
+                    "Segment end, on the same timeline as Begin Time",
+                ),
+                column(
+                    "Low Freq (Hz)",
+                    Integer,
+                    Some("Hz"),
+                    "Lower frequency bound",
+                ),
+                column(
+                    "High Freq (Hz)",
+                    Integer,
+                    Some("Hz"),
+                    "Upper frequency bound",
+                ),
+                column("Common Name", String, None, "Species common name"),
+                column("Species Code", String, None, "Species code"),
+                column("Confidence", Float, None, confidence),
+                column(
+                    "Begin File",
+                    String,
+                    None,
+                    "File name of the analyzed recording",
+                ),
+                column("Begin Path", String, None, "Path of the analyzed recording"),
+                column(
+                    "File Offset (s)",
+                    Float,
+                    Some("seconds"),
+                    "Segment start in the file",
+                ),
+            ],
+        ),
+        OutputFormat::Audacity => (
+            Some("\t"),
+            false,
+            None,
+            vec![
+                column("start", Float, Some("seconds"), "Label start"),
+                column("end", Float, Some("seconds"), "Label end"),
+                column("label", String, None, "Species common name"),
+                column("confidence", Float, None, confidence),
+            ],
+        ),
+        OutputFormat::Kaleidoscope => (
+            Some(","),
+            true,
+            None,
+            vec![
+                column(
+                    "INDIR",
+                    String,
+                    None,
+                    "Grandparent directory of the recording",
+                ),
+                column("OUTDIR", String, None, "Directory of the results file"),
+                column(
+                    "FOLDER",
+                    String,
+                    None,
+                    "Parent directory name of the recording",
+                ),
+                column("IN FILE", String, None, "File name of the recording"),
+                column("CHANNEL", Integer, None, "Audio channel (0-based)"),
+                column("OFFSET", Float, Some("seconds"), "Segment start"),
+                column("DURATION", Float, Some("seconds"), "Segment length"),
+                nullable("DATE", String, None, "Recording start date (YYYY-MM-DD)"),
+                nullable("TIME", String, None, "Recording start time (HH:MM:SS)"),
+                nullable("HOUR", Integer, None, "Recording start hour"),
+                nullable(
+                    "DATE-12",
+                    String,
+                    None,
+                    "Date of the recording start 12 hours earlier",
+                ),
+                nullable(
+                    "TIME-12",
+                    String,
+                    None,
+                    "Time of the recording start 12 hours earlier",
+                ),
+                nullable(
+                    "HOUR-12",
+                    Integer,
+                    None,
+                    "Hour of the recording start 12 hours earlier",
+                ),
+                column("AUTO ID*", String, None, "Species common name"),
+                column("TOP1MATCH", String, None, "Species common name"),
+                column("TOP1DIST", Float, None, confidence),
+                nullable("MANUAL ID", String, None, "Reviewer identification"),
+            ],
+        ),
+        OutputFormat::Json => (
+            None,
+            false,
+            Some("format_version field"),
+            vec![
+                column("start_time", Float, Some("seconds"), "Segment start"),
+                column("end_time", Float, Some("seconds"), "Segment end"),
+                column("scientific_name", String, None, "Species scientific name"),
+                column("common_name", String, None, "Species common name"),
+                column("confidence", Float, None, confidence),
+                nullable("fingerprint", String, None, "Stable detection identifier"),
+                nullable("device", String, None, "Execution provider"),
+                nullable("model_hash", String, None, "SHA-256 hash of the model file"),
+                nullable("processed_at", Timestamp, None, "Processing time"),
+                nullable(
+                    "sensitive",
+                    Boolean,
+                    None,
+                    "Species is on a sensitive species list",
+                ),
+                nullable("channel", Integer, None, "Analyzed audio channel (1-based)"),
+                nullable(
+                    "datetime_start",
+                    Timestamp,
+                    None,
+                    "Absolute segment start (local time)",
+                ),
+                nullable(
+                    "datetime_end",
+                    Timestamp,
+                    None,
+                    "Absolute segment end (local time)",
+                ),
+            ],
+        ),
+        OutputFormat::Parquet => (
+            None,
+            false,
+            Some(format_version::PARQUET_METADATA_KEY),
+            vec![
+                column("start_s", Float, Some("seconds"), "Segment start"),
+                column("end_s", Float, Some("seconds"), "Segment end"),
+                column("scientific_name", String, None, "Species scientific name"),
+                column("common_name", String, None, "Species common name"),
+                column("confidence", Float, None, confidence),
+                column("file", String, None, "File name of the analyzed recording"),
+            ],
+        ),
+        OutputFormat::Sqlite => (
+            None,
+            false,
+            Some("PRAGMA user_version"),
+            vec![
+                column("id", Integer, None, "Detection row ID"),
+                column("file_id", Integer, None, "Row ID in the files table"),
+                column("run_id", Integer, None, "Row ID in the runs table"),
+                column("start_s", Float, Some("seconds"), "Segment start"),
+                column("end_s", Float, Some("seconds"), "Segment end"),
+                column("scientific_name", String, None, "Species scientific name"),
+                column("common_name", String, None, "Species common name"),
+                column("confidence", Float, None, confidence),
+                nullable("lat", Float, Some("degrees"), "Recording latitude"),
+                nullable("lon", Float, Some("degrees"), "Recording longitude"),
+                nullable("week", Integer, None, "BirdNET week of year (1-48)"),
+                nullable("model", String, None, "Configured model name"),
+                nullable("annotation", String, None, "Annotation of the detection"),
+                nullable("fingerprint", String, None, "Stable detection identifier"),
+            ],
+        ),
+    };
+
+    let optional_columns = match format {
+        OutputFormat::Csv | OutputFormat::Parquet => optional_columns(format),
+        _ => Vec::new(),
+    };
+
+    FormatSchema {
+        format,
+        format_version: format_version_of(format),
+        delimiter: delimiter.map(str::to_string),
+        header,
+        version_location: version_location.map(str::to_string),
+        columns,
+        optional_columns,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::ValueEnum;
+
+    #[test]
+    fn test_every_format_has_columns() {
+        for format in OutputFormat::value_variants() {
+            let schema = format_schema(*format);
+            assert!(!schema.columns.is_empty(), "{format:?}");
+            assert_eq!(schema.format_version, format_version_of(*format));
+        }
+    }
+
+    #[test]
+    fn test_csv_schema_matches_header() {
+        let names: Vec<_> = format_schema(OutputFormat::Csv)
+            .columns
+            .into_iter()
+            .map(|c| c.name)
+            .collect();
+        assert_eq!(
+            names.join(","),
+            "Start (s),End (s),Scientific name,Common name,Confidence,File"
+        );
+    }
+
+    #[test]
+    fn test_format_version_column_is_optional_for_csv_and_parquet() {
+        for format in [OutputFormat::Csv, OutputFormat::Parquet] {
+            assert!(
+                format_schema(format)
+                    .optional_columns
+                    .iter()
+                    .any(|c| c.name == format_version::COLUMN)
+            );
+        }
+        assert!(
+            format_schema(OutputFormat::Raven)
+                .optional_columns
+                .is_empty()
+        );
+    }
+}
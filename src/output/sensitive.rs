@@ -0,0 +1,170 @@
+//! Sensitive species masking.
+//!
+//! Locations of rare or persecuted species are often protected (national
+//! sensitive species lists). Detections of listed species keep their full
+//! resolution in the per-file results, written as `sensitive = true`, while
+//! run-wide outputs and `birda export` copies flag, drop or coarsen them
+//! according to the configured [`SensitiveAction`].
+
+use crate::config::{SensitiveAction, SensitiveConfig};
+use crate::constants::sensitive::DEFAULT_GRID_DEGREES;
+use crate::error::Result;
+use crate::output::Detection;
+use crate::utils::geo::snap_to_grid;
+use crate::utils::species_list::read_species_list;
+use std::collections::HashSet;
+
+/// Species from the configured sensitive lists.
+#[derive(Debug, Clone)]
+pub struct SensitiveSpecies {
+    names: HashSet<String>,
+    action: SensitiveAction,
+    grid: f64,
+}
+
+impl SensitiveSpecies {
+    /// Load the lists of `config`, or `None` if no lists are configured.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a list file cannot be read.
+    pub fn load(config: &SensitiveConfig) -> Result<Option<Self>> {
+        if config.lists.is_empty() {
+            return Ok(None);
+        }
+        let mut entries = Vec::new();
+        for path in &config.lists {
+            entries.extend(read_species_list(path)?);
+        }
+        Ok(Some(Self::new(
+            &entries,
+            config.action,
+            config.grid.unwrap_or(DEFAULT_GRID_DEGREES),
+        )))
+    }
+
+    /// Build from species list entries (`Genus species_Common Name` or
+    /// `Genus species`).
+    pub fn new(entries: &[String], action: SensitiveAction, grid: f64) -> Self {
+        let names = entries
+            .iter()
+            .flat_map(|entry| entry.split('_'))
+            .map(normalize)
+            .filter(|name| !name.is_empty())
+            .collect();
+        Self {
+            names,
+            action,
+            grid,
+        }
+    }
+
+    /// What shared outputs do with sensitive detections.
+    pub const fn action(&self) -> SensitiveAction {
+        self.action
+    }
+
+    /// Grid that coordinates of sensitive detections are snapped to.
+    pub const fn grid(&self) -> f64 {
+        self.grid
+    }
+
+    /// Whether a scientific or common name is on a sensitive list.
+    ///
+    /// Case and `_` in place of spaces (Raven, Kaleidoscope) are ignored.
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains(&normalize(name))
+    }
+
+    /// Whether `detection` is of a sensitive species.
+    pub fn matches(&self, detection: &Detection) -> bool {
+        self.contains(&detection.scientific_name) || self.contains(&detection.common_name)
+    }
+
+    /// Set the `sensitive` flag of every detection of a just-processed file.
+    pub fn flag(&self, detections: &mut [Detection]) {
+        for detection in detections {
+            detection.metadata.sensitive = Some(self.matches(detection));
+        }
+    }
+
+    /// Copy of `detections` for shared outputs, with sensitive detections
+    /// dropped or coarsened as configured.
+    pub fn mask(&self, detections: &[Detection]) -> Vec<Detection> {
+        detections
+            .iter()
+            .filter(|d| self.action != SensitiveAction::Exclude || !self.matches(d))
+            .cloned()
+            .map(|mut d| {
+                if self.action == SensitiveAction::Fuzz && self.matches(&d) {
+                    d.metadata.lat = d.metadata.lat.map(|lat| snap_to_grid(lat, self.grid));
+                    d.metadata.lon = d.metadata.lon.map(|lon| snap_to_grid(lon, self.grid));
+                }
+                d
+            })
+            .collect()
+    }
+}
+
+/// Lowercase name with `_` read as a space.
+fn normalize(name: &str) -> String {
+    name.trim().replace('_', " ").to_lowercase()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn detection(label: &str) -> Detection {
+        let mut d = Detection::test(label, 0.0, "rec.wav");
+        d.metadata.lat = Some(60.1699);
+        d.metadata.lon = Some(24.9384);
+        d
+    }
+
+    fn species(action: SensitiveAction) -> SensitiveSpecies {
+        SensitiveSpecies::new(&["Aquila chrysaetos_Golden Eagle".to_string()], action, 1.0)
+    }
+
+    #[test]
+    fn test_contains_matches_either_name() {
+        let sensitive = species(SensitiveAction::Flag);
+        assert!(sensitive.contains("Aquila chrysaetos"));
+        assert!(sensitive.contains("golden eagle"));
+        assert!(sensitive.contains("Golden_Eagle"));
+        assert!(!sensitive.contains("Parus major"));
+    }
+
+    #[test]
+    fn test_flag_marks_every_detection() {
+        let mut detections = vec![
+            detection("Aquila chrysaetos_Golden Eagle"),
+            detection("Parus major_Great Tit"),
+        ];
+        species(SensitiveAction::Flag).flag(&mut detections);
+        assert_eq!(detections[0].metadata.sensitive, Some(true));
+        assert_eq!(detections[1].metadata.sensitive, Some(false));
+    }
+
+    #[test]
+    fn test_mask_excludes_or_fuzzes() {
+        let detections = vec![
+            detection("Aquila chrysaetos_Golden Eagle"),
+            detection("Parus major_Great Tit"),
+        ];
+
+        let excluded = species(SensitiveAction::Exclude).mask(&detections);
+        assert_eq!(excluded.len(), 1);
+        assert_eq!(excluded[0].common_name, "Great Tit");
+
+        let fuzzed = species(SensitiveAction::Fuzz).mask(&detections);
+        assert_eq!(fuzzed[0].metadata.lat, Some(60.5));
+        assert_eq!(fuzzed[0].metadata.lon, Some(24.5));
+        assert_eq!(fuzzed[1].metadata.lat, Some(60.1699));
+
+        let flagged = species(SensitiveAction::Flag).mask(&detections);
+        assert_eq!(flagged.len(), 2);
+        assert_eq!(flagged[0].metadata.lat, Some(60.1699));
+    }
+}
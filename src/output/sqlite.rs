@@ -0,0 +1,417 @@
+//! SQLite database output.
+//!
+//! Requires the `sqlite` cargo feature; without it, opening a database returns
+//! [`Error::SqliteUnavailable`](crate::error::Error::SqliteUnavailable).
+//!
+//! Each run appends a row to the `runs` table, every analyzed recording gets
+//! a row in `files`, and detections reference both. Reprocessing a recording
+//! moves its `files` row to the new run and replaces its detections instead
+//! of duplicating them, also when it no longer has any. The format version is recorded
+//! as `PRAGMA user_version`.
+
+use crate::constants::combined_filenames;
+use crate::error::Result;
+use crate::output::{OutputWriter, RunManifest};
+use std::path::Path;
+
+/// Schema of the database, created if missing.
+#[cfg_attr(not(feature = "sqlite"), allow(dead_code))]
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY,
+    started_at TEXT NOT NULL,
+    birda_version TEXT NOT NULL,
+    settings_hash TEXT NOT NULL,
+    model TEXT NOT NULL,
+    min_confidence REAL NOT NULL,
+    overlap REAL NOT NULL,
+    station_id TEXT,
+    station_name TEXT
+);
+CREATE TABLE IF NOT EXISTS files (
+    id INTEGER PRIMARY KEY,
+    path TEXT NOT NULL UNIQUE,
+    run_id INTEGER NOT NULL REFERENCES runs(id),
+    analyzed_at TEXT NOT NULL,
+    detections INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS detections (
+    id INTEGER PRIMARY KEY,
+    file_id INTEGER NOT NULL REFERENCES files(id),
+    run_id INTEGER NOT NULL REFERENCES runs(id),
+    start_s REAL NOT NULL,
+    end_s REAL NOT NULL,
+    scientific_name TEXT NOT NULL,
+    common_name TEXT NOT NULL,
+    confidence REAL NOT NULL,
+    lat REAL,
+    lon REAL,
+    week INTEGER,
+    model TEXT,
+    annotation TEXT,
+    fingerprint TEXT
+);
+CREATE INDEX IF NOT EXISTS detections_file_id ON detections(file_id);
+";
+
+/// Columns added to the `runs` table of version 1 databases.
+#[cfg_attr(not(feature = "sqlite"), allow(dead_code))]
+const MIGRATE_V1: &str = "
+ALTER TABLE runs ADD COLUMN station_id TEXT;
+ALTER TABLE runs ADD COLUMN station_name TEXT;
+";
+
+/// Name of the run's SQLite database for the combined output `prefix`.
+pub fn sqlite_filename(prefix: &str) -> String {
+    super::combined::with_prefix(combined_filenames::SQLITE, prefix)
+}
+
+/// Open a SQLite database as a run-wide output, recording `manifest` as a
+/// new run.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be opened or the tables created,
+/// or `Error::SqliteUnavailable` in builds without the `sqlite` feature.
+#[cfg(feature = "sqlite")]
+pub fn open_sqlite_output(
+    path: &Path,
+    manifest: &RunManifest,
+) -> Result<Box<dyn OutputWriter + Send>> {
+    Ok(Box::new(imp::SqliteWriter::new(path, manifest)?))
+}
+
+/// Open a SQLite database as a run-wide output.
+///
+/// # Errors
+///
+/// Always returns `Error::SqliteUnavailable` in builds without the `sqlite`
+/// feature.
+#[cfg(not(feature = "sqlite"))]
+pub fn open_sqlite_output(
+    _path: &Path,
+    _manifest: &RunManifest,
+) -> Result<Box<dyn OutputWriter + Send>> {
+    Err(crate::error::Error::SqliteUnavailable)
+}
+
+#[cfg(feature = "sqlite")]
+mod imp {
+    use super::{MIGRATE_V1, SCHEMA};
+    use crate::constants::format_version;
+    use crate::error::{Error, Result};
+    use crate::output::{Detection, OutputWriter, RunManifest};
+    use chrono::{SecondsFormat, Utc};
+    use rusqlite::{Connection, params};
+    use std::collections::BTreeMap;
+    use std::path::{Path, PathBuf};
+
+    fn sqlite_error(context: impl Into<String>) -> impl FnOnce(rusqlite::Error) -> Error {
+        let context = context.into();
+        move |e| Error::Sqlite {
+            context,
+            reason: e.to_string(),
+        }
+    }
+
+    /// Writer that records files and detections under one run.
+    pub struct SqliteWriter {
+        conn: Connection,
+        path: PathBuf,
+        run_id: i64,
+        pending: Vec<Detection>,
+        /// Recordings ended since the last commit.
+        ended: Vec<PathBuf>,
+    }
+
+    impl SqliteWriter {
+        pub fn new(path: &Path, manifest: &RunManifest) -> Result<Self> {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)?;
+            }
+            let conn = Connection::open(path)
+                .map_err(sqlite_error(format!("opening {}", path.display())))?;
+            let station = manifest.station.as_ref();
+            let version: u32 = conn
+                .pragma_query_value(None, "user_version", |row| row.get(0))
+                .map_err(sqlite_error("reading format version"))?;
+            if version == 1 {
+                conn.execute_batch(MIGRATE_V1)
+                    .map_err(sqlite_error("upgrading tables"))?;
+            }
+            conn.execute_batch(SCHEMA)
+                .map_err(sqlite_error("creating tables"))?;
+            conn.pragma_update(None, "user_version", format_version::SQLITE)
+                .map_err(sqlite_error("recording format version"))?;
+            conn.execute(
+                "INSERT INTO runs (started_at, birda_version, settings_hash, model, \
+                 min_confidence, overlap, station_id, station_name) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    manifest.created.to_rfc3339_opts(SecondsFormat::Secs, true),
+                    manifest.birda_version,
+                    manifest.settings_hash,
+                    manifest.settings.model,
+                    manifest.settings.min_confidence,
+                    manifest.settings.overlap,
+                    station.and_then(|s| s.id.as_deref()),
+                    station.and_then(|s| s.name.as_deref()),
+                ],
+            )
+            .map_err(sqlite_error("recording run"))?;
+            let run_id = conn.last_insert_rowid();
+
+            Ok(Self {
+                conn,
+                path: path.to_path_buf(),
+                run_id,
+                pending: Vec::new(),
+                ended: Vec::new(),
+            })
+        }
+
+        /// Record every ended or pending file and replace its detections in
+        /// one transaction.
+        fn commit_pending(&mut self) -> Result<()> {
+            if self.pending.is_empty() && self.ended.is_empty() {
+                return Ok(());
+            }
+            let context = format!("writing to {}", self.path.display());
+            let analyzed_at = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+            let tx = self
+                .conn
+                .transaction()
+                .map_err(sqlite_error(context.clone()))?;
+
+            let mut files: BTreeMap<String, Vec<&Detection>> = self
+                .ended
+                .iter()
+                .map(|file| (file.display().to_string(), Vec::new()))
+                .collect();
+            for d in &self.pending {
+                files
+                    .entry(d.file_path.display().to_string())
+                    .or_default()
+                    .push(d);
+            }
+
+            {
+                let mut upsert_file = tx
+                    .prepare(
+                        "INSERT INTO files (path, run_id, analyzed_at, detections) \
+                         VALUES (?1, ?2, ?3, ?4) \
+                         ON CONFLICT (path) DO UPDATE SET run_id = excluded.run_id, \
+                         analyzed_at = excluded.analyzed_at, detections = excluded.detections \
+                         RETURNING id",
+                    )
+                    .map_err(sqlite_error(context.clone()))?;
+                let mut delete_detections = tx
+                    .prepare("DELETE FROM detections WHERE file_id = ?1")
+                    .map_err(sqlite_error(context.clone()))?;
+                let mut insert_detection = tx
+                    .prepare(
+                        "INSERT INTO detections (file_id, run_id, start_s, end_s, \
+                         scientific_name, common_name, confidence, lat, lon, week, model, \
+                         annotation, fingerprint) \
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                    )
+                    .map_err(sqlite_error(context.clone()))?;
+
+                for (file, detections) in &files {
+                    let file_id: i64 = upsert_file
+                        .query_row(
+                            params![file, self.run_id, analyzed_at, detections.len()],
+                            |row| row.get(0),
+                        )
+                        .map_err(sqlite_error(context.clone()))?;
+                    delete_detections
+                        .execute(params![file_id])
+                        .map_err(sqlite_error(context.clone()))?;
+                    for d in detections {
+                        insert_detection
+                            .execute(params![
+                                file_id,
+                                self.run_id,
+                                d.start_time,
+                                d.end_time,
+                                d.scientific_name,
+                                d.common_name,
+                                d.confidence,
+                                d.metadata.lat,
+                                d.metadata.lon,
+                                d.metadata.week,
+                                d.metadata.model,
+                                d.metadata.annotation,
+                                d.metadata.fingerprint,
+                            ])
+                            .map_err(sqlite_error(context.clone()))?;
+                    }
+                }
+            }
+
+            tx.commit().map_err(sqlite_error(context))?;
+            self.pending.clear();
+            self.ended.clear();
+            Ok(())
+        }
+    }
+
+    impl OutputWriter for SqliteWriter {
+        fn write_detection(&mut self, detection: &Detection) -> Result<()> {
+            self.pending.push(detection.clone());
+            Ok(())
+        }
+
+        fn end_file(&mut self, file: &Path, _audio_duration_secs: f64) -> Result<()> {
+            self.ended.push(file.to_path_buf());
+            Ok(())
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            self.commit_pending()
+        }
+
+        fn finalize(&mut self) -> Result<()> {
+            self.commit_pending()
+        }
+    }
+
+    #[cfg(test)]
+    #[allow(clippy::unwrap_used)]
+    mod tests {
+        use super::*;
+        use crate::output::RunSettings;
+
+        fn manifest() -> RunManifest {
+            RunManifest::new(RunSettings {
+                model: "birdnet".to_string(),
+                model_type: "birdnet-v24".to_string(),
+                model_sha256: None,
+                min_confidence: 0.1,
+                overlap: 0.0,
+                sensitivity: None,
+                latitude: None,
+                longitude: None,
+                week: None,
+                species_list: None,
+                bat: false,
+                postprocess_hook: None,
+                merge_overlapping: false,
+                zoom_overlap: None,
+                channel: None,
+                filter: None,
+                custom_head: None,
+                time_range: None,
+                stitch: false,
+                confidence_decimals: None,
+                time_decimals: None,
+            })
+        }
+
+        fn count(conn: &Connection, sql: &str) -> i64 {
+            conn.query_row(sql, [], |row| row.get(0)).unwrap()
+        }
+
+        #[test]
+        fn test_sqlite_writer_replaces_reprocessed_files() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("detections.db");
+
+            let mut writer = SqliteWriter::new(&path, &manifest()).unwrap();
+            writer
+                .write_detection(&Detection::test("Parus major_Great Tit", 0.0, "a.wav"))
+                .unwrap();
+            writer
+                .write_detection(&Detection::test("Parus major_Great Tit", 3.0, "a.wav"))
+                .unwrap();
+            writer.flush().unwrap();
+            writer
+                .write_detection(&Detection::test("Parus major_Great Tit", 0.0, "b.wav"))
+                .unwrap();
+            writer.finalize().unwrap();
+            drop(writer);
+
+            // A second run reprocessing a.wav replaces its detections
+            let mut writer = SqliteWriter::new(&path, &manifest()).unwrap();
+            writer
+                .write_detection(&Detection::test("Parus major_Great Tit", 6.0, "a.wav"))
+                .unwrap();
+            writer.finalize().unwrap();
+
+            let conn = Connection::open(&path).unwrap();
+            assert_eq!(
+                count(&conn, "PRAGMA user_version"),
+                i64::from(format_version::SQLITE)
+            );
+            assert_eq!(count(&conn, "SELECT COUNT(*) FROM runs"), 2);
+            assert_eq!(count(&conn, "SELECT COUNT(*) FROM files"), 2);
+            assert_eq!(count(&conn, "SELECT COUNT(*) FROM detections"), 2);
+            assert_eq!(
+                count(
+                    &conn,
+                    "SELECT run_id FROM files WHERE path = 'a.wav' AND detections = 1"
+                ),
+                2
+            );
+        }
+
+        #[test]
+        fn test_sqlite_writer_clears_files_without_detections() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("detections.db");
+
+            let mut writer = SqliteWriter::new(&path, &manifest()).unwrap();
+            writer
+                .write_detection(&Detection::test("Parus major_Great Tit", 0.0, "a.wav"))
+                .unwrap();
+            writer.end_file(Path::new("a.wav"), 60.0).unwrap();
+            writer.end_file(Path::new("b.wav"), 60.0).unwrap();
+            writer.finalize().unwrap();
+            drop(writer);
+
+            // Reprocessing a.wav without detections removes its old ones
+            let mut writer = SqliteWriter::new(&path, &manifest()).unwrap();
+            writer.end_file(Path::new("a.wav"), 60.0).unwrap();
+            writer.finalize().unwrap();
+
+            let conn = Connection::open(&path).unwrap();
+            assert_eq!(count(&conn, "SELECT COUNT(*) FROM files"), 2);
+            assert_eq!(count(&conn, "SELECT COUNT(*) FROM detections"), 0);
+            assert_eq!(
+                count(
+                    &conn,
+                    "SELECT run_id FROM files WHERE path = 'a.wav' AND detections = 0"
+                ),
+                2
+            );
+        }
+
+        #[test]
+        fn test_sqlite_writer_upgrades_version_1() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("detections.db");
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE runs (id INTEGER PRIMARY KEY, started_at TEXT NOT NULL, \
+                 birda_version TEXT NOT NULL, settings_hash TEXT NOT NULL, \
+                 model TEXT NOT NULL, min_confidence REAL NOT NULL, overlap REAL NOT NULL); \
+                 PRAGMA user_version = 1;",
+            )
+            .unwrap();
+            drop(conn);
+
+            let mut writer = SqliteWriter::new(&path, &manifest()).unwrap();
+            writer.finalize().unwrap();
+
+            let conn = Connection::open(&path).unwrap();
+            assert_eq!(
+                count(&conn, "PRAGMA user_version"),
+                i64::from(format_version::SQLITE)
+            );
+            assert_eq!(
+                count(&conn, "SELECT COUNT(*) FROM runs WHERE station_id IS NULL"),
+                1
+            );
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Station identity recorded in outputs and events.
+//!
+//! Results merged from several birda instances (field stations, cluster
+//! shards) cannot rely on hostnames or directory layout to tell where they
+//! came from. The configured [`Station`] is stamped on every detection as the
+//! `station_id` and `station_name` columns, and recorded in run manifests,
+//! JSON result files, the runs log and every JSON event of an analysis.
+
+use crate::config::StationConfig;
+use crate::output::Detection;
+use serde::{Deserialize, Serialize};
+
+/// Identity of the birda instance that produced results.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Station {
+    /// Short stable identifier.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Human-readable station name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl Station {
+    /// Station from the `[station]` config table, or `None` if neither an id
+    /// nor a name is configured.
+    pub fn from_config(config: &StationConfig) -> Option<Self> {
+        (config.id.is_some() || config.name.is_some()).then(|| Self {
+            id: config.id.clone(),
+            name: config.name.clone(),
+        })
+    }
+
+    /// Output columns this station fills in.
+    pub fn columns(&self) -> impl Iterator<Item = &'static str> {
+        let [id_column, name_column] = crate::constants::station::COLUMNS;
+        self.id
+            .as_ref()
+            .map(|_| id_column)
+            .into_iter()
+            .chain(self.name.as_ref().map(|_| name_column))
+    }
+
+    /// Stamp `detections` with this station.
+    pub fn apply(&self, detections: &mut [Detection]) {
+        for detection in detections {
+            detection.metadata.station_id.clone_from(&self.id);
+            detection.metadata.station_name.clone_from(&self.name);
+        }
+    }
+}
+
+impl std::fmt::Display for Station {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.id, &self.name) {
+            (Some(id), Some(name)) => write!(f, "{id} ({name})"),
+            (Some(value), None) | (None, Some(value)) => write!(f, "{value}"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_station_from_config() {
+        assert!(Station::from_config(&StationConfig::default()).is_none());
+
+        let station = Station::from_config(&StationConfig {
+            id: Some("site-07".to_string()),
+            name: None,
+        })
+        .unwrap();
+        assert_eq!(station.columns().collect::<Vec<_>>(), ["station_id"]);
+        assert_eq!(
+            serde_json::to_string(&station).unwrap(),
+            r#"{"id":"site-07"}"#
+        );
+    }
+
+    #[test]
+    fn test_station_stamps_detections() {
+        let station = Station {
+            id: Some("site-07".to_string()),
+            name: Some("North shore".to_string()),
+        };
+        let mut detections = vec![Detection::from_label(
+            "Parus major_Great Tit",
+            0.9,
+            0.0,
+            3.0,
+            PathBuf::from("a.wav"),
+        )];
+        station.apply(&mut detections);
+
+        assert_eq!(
+            detections[0].metadata.station_id.as_deref(),
+            Some("site-07")
+        );
+        assert_eq!(
+            detections[0].metadata.station_name.as_deref(),
+            Some("North shore")
+        );
+        assert_eq!(
+            station.columns().collect::<Vec<_>>(),
+            ["station_id", "station_name"]
+        );
+        assert_eq!(station.to_string(), "site-07 (North shore)");
+    }
+}
@@ -49,6 +49,57 @@ pub struct DetectionMetadata {
     /// Species list file path.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub species_list: Option<String>,
+    /// Free-form annotation set by a post-processing hook.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotation: Option<String>,
+    /// Perceptual fingerprint of the detection's audio segment (16 hex digits).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    /// Execution provider that ran inference (e.g. `CUDA`, `CPU`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device: Option<String>,
+    /// Hex SHA-256 hash of the model file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_hash: Option<String>,
+    /// When the recording finished processing (RFC 3339, UTC).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub processed_at: Option<String>,
+    /// Recorder model from Song Meter metadata.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recorder_model: Option<String>,
+    /// Recorder serial number from Song Meter metadata.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recorder_serial: Option<String>,
+    /// Microphone gain setting from Song Meter metadata.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recorder_gain: Option<String>,
+    /// Recorder temperature in degrees Celsius from Song Meter metadata.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recorder_temperature: Option<f32>,
+    /// Recording schedule from Song Meter metadata.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recorder_schedule: Option<String>,
+    /// Identifier of the station that produced the detection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub station_id: Option<String>,
+    /// Name of the station that produced the detection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub station_name: Option<String>,
+    /// Whether the species is on a configured sensitive species list.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sensitive: Option<bool>,
+    /// Audio channel the detection was found in (1-based), when channels
+    /// are analyzed separately.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<u32>,
+    /// Absolute start time (ISO 8601 local time), when the recording start
+    /// is known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datetime_start: Option<String>,
+    /// Absolute end time (ISO 8601 local time), when the recording start is
+    /// known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datetime_end: Option<String>,
 }
 
 impl Detection {
@@ -79,6 +130,21 @@ impl Detection {
     }
 }
 
+#[cfg(test)]
+impl Detection {
+    /// Three-second test detection of `label` (`Scientific_Common`) starting
+    /// at `start` in `file`, with confidence 0.9.
+    pub fn test(label: &str, start: f32, file: &str) -> Self {
+        Self::from_label(label, 0.9, start, start + 3.0, PathBuf::from(file))
+    }
+
+    /// The detection with `confidence` instead.
+    #[must_use]
+    pub fn with_confidence(self, confidence: f32) -> Self {
+        Self { confidence, ..self }
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::float_cmp)]
 mod tests {
@@ -1,7 +1,15 @@
 //! Output writer trait definition.
 
-use crate::error::Result;
+use crate::constants::UTF8_BOM;
+use crate::error::{Error, Result};
 use crate::output::Detection;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use tracing::warn;
+
+/// Bytes read from the end of a file when looking for the last complete line.
+const APPEND_TAIL_BYTES: u64 = 64 * 1024;
 
 /// Trait for writing detection results.
 pub trait OutputWriter {
@@ -13,6 +21,144 @@ pub trait OutputWriter {
     /// Write a single detection.
     fn write_detection(&mut self, detection: &Detection) -> Result<()>;
 
+    /// Mark the end of recording `file`'s detections in a run-wide output.
+    ///
+    /// Called for every analyzed recording, including those without
+    /// detections.
+    fn end_file(&mut self, _file: &Path, _audio_duration_secs: f64) -> Result<()> {
+        Ok(())
+    }
+
+    /// Flush buffered detections so the output is complete up to this point.
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+
     /// Finalize the output (flush, close, etc.).
     fn finalize(&mut self) -> Result<()>;
+
+    /// Approximate size of the output so far, including buffered data.
+    ///
+    /// Writers that cannot report a size return 0.
+    fn bytes_written(&self) -> u64 {
+        0
+    }
+}
+
+/// Sink wrapper that counts the bytes passed through it.
+pub(crate) struct CountingWriter {
+    inner: Box<dyn Write + Send>,
+    count: u64,
+}
+
+impl CountingWriter {
+    /// Wrap `inner`, starting the count at zero.
+    pub(crate) fn new(inner: Box<dyn Write + Send>) -> Self {
+        Self { inner, count: 0 }
+    }
+
+    /// Bytes written to the inner sink so far.
+    pub(crate) const fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+impl Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Open a text results file for appending rows under `header`.
+///
+/// Returns the file positioned at its end and whether it already has the
+/// header. A partial last line left by an interrupted run is truncated so
+/// appended rows start on a fresh line.
+///
+/// # Errors
+///
+/// Returns [`Error::AppendHeaderMismatch`] if an existing file has a different
+/// header, since appended columns would not line up.
+pub(crate) fn open_append(path: &Path, header: &str) -> Result<(File, bool)> {
+    let mut file = OpenOptions::new()
+        .read(true)
+        .append(true)
+        .create(true)
+        .open(path)?;
+    let len = file.metadata()?.len();
+    if len == 0 {
+        return Ok((file, false));
+    }
+
+    let mut first_line = Vec::new();
+    BufReader::new(&mut file).read_until(b'\n', &mut first_line)?;
+    let header_complete = first_line.ends_with(b"\n");
+    let first_line = first_line.strip_prefix(UTF8_BOM).unwrap_or(&first_line);
+    let existing = String::from_utf8_lossy(first_line);
+    if existing.trim_end_matches(['\r', '\n']) != header {
+        return Err(Error::AppendHeaderMismatch {
+            path: path.to_path_buf(),
+        });
+    }
+
+    if !header_complete {
+        // Header only, cut off before its line ending
+        file.write_all(b"\n")?;
+        return Ok((file, true));
+    }
+
+    // Drop a partial row from an interrupted run
+    let tail_start = len.saturating_sub(APPEND_TAIL_BYTES);
+    file.seek(SeekFrom::Start(tail_start))?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail)?;
+    if tail.last() != Some(&b'\n') {
+        let keep = tail
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(tail_start, |i| tail_start + i as u64 + 1);
+        warn!(
+            "Truncating incomplete last line of {} before appending",
+            path.display()
+        );
+        file.set_len(keep)?;
+    }
+
+    Ok((file, true))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_append_checks_header_and_truncates_partial_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("results.csv");
+
+        let (_, has_header) = open_append(&path, "a,b").unwrap();
+        assert!(!has_header);
+
+        std::fs::write(&path, "a,b\n1,2\n3,").unwrap();
+        let (mut file, has_header) = open_append(&path, "a,b").unwrap();
+        assert!(has_header);
+        file.write_all(b"5,6\n").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a,b\n1,2\n5,6\n");
+
+        std::fs::write(&path, "a,b").unwrap();
+        open_append(&path, "a,b").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a,b\n");
+
+        assert!(matches!(
+            open_append(&path, "a,b,c"),
+            Err(Error::AppendHeaderMismatch { .. })
+        ));
+    }
 }
@@ -0,0 +1,179 @@
+//! Inference throughput benchmark for `birda bench`.
+//!
+//! Classifies batches of the synthetic test call and reports segments per
+//! second. A result can be saved as a baseline and later runs compared with
+//! it, so a build or configuration change that slows inference down (such as
+//! a provider falling back to CPU) is caught before release.
+
+use crate::audio::test_signal;
+use crate::constants::bench::WARMUP_BATCHES;
+use crate::error::{Error, Result};
+use crate::inference::{BirdClassifier, InferenceOptions};
+use crate::output::{BaselineComparison, BenchResult};
+use std::path::Path;
+use std::time::Instant;
+use tracing::{info, warn};
+
+/// Classify `segments` test segments `batch_size` at a time with `classifier`.
+pub fn run_bench(
+    classifier: &BirdClassifier,
+    model: &str,
+    batch_size: usize,
+    segments: usize,
+) -> Result<BenchResult> {
+    let batch_size = batch_size.max(1);
+    let signal = test_signal(classifier.sample_rate(), classifier.sample_count());
+    let batch: Vec<&[f32]> = vec![signal.as_slice(); batch_size];
+    let options = InferenceOptions::default();
+
+    info!("Warming up with {WARMUP_BATCHES} batch(es) of {batch_size}");
+    for _ in 0..WARMUP_BATCHES {
+        classifier.predict_batch(&batch, &options)?;
+    }
+
+    let start = Instant::now();
+    let mut remaining = segments;
+    while remaining > 0 {
+        let len = remaining.min(batch_size);
+        classifier.predict_batch(&batch[..len], &options)?;
+        remaining -= len;
+    }
+    let elapsed_secs = start.elapsed().as_secs_f64();
+
+    #[allow(clippy::cast_precision_loss)]
+    let segments_per_sec = if elapsed_secs > 0.0 {
+        segments as f64 / elapsed_secs
+    } else {
+        0.0
+    };
+    Ok(BenchResult {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        model: model.to_string(),
+        device: classifier.execution_provider_status().actual.to_string(),
+        batch_size,
+        segments,
+        elapsed_secs,
+        segments_per_sec,
+    })
+}
+
+/// Read a baseline written by [`save_baseline`].
+pub fn load_baseline(path: &Path) -> Result<BenchResult> {
+    let content = std::fs::read_to_string(path)?;
+    serde_json::from_str(&content).map_err(|source| Error::BaselineParse {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Write `result` to `path` as a baseline for later runs.
+pub fn save_baseline(path: &Path, result: &BenchResult) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(result).map_err(|e| Error::Internal {
+        message: format!("failed to serialize benchmark result: {e}"),
+    })?;
+    std::fs::write(path, json + "\n")?;
+    Ok(())
+}
+
+/// Compare `result` with `baseline`, read from `path`.
+///
+/// Throughput regressed when it is more than `tolerance_percent` below the
+/// baseline; being faster never counts as a regression. Runs that differ from
+/// the baseline in model, device or batch size are compared anyway, with a
+/// warning, since a changed device is often the regression being looked for.
+pub fn compare_to_baseline(
+    result: &BenchResult,
+    baseline: &BenchResult,
+    path: &Path,
+    tolerance_percent: f64,
+) -> BaselineComparison {
+    for (what, measured, expected) in [
+        ("model", result.model.as_str(), baseline.model.as_str()),
+        ("device", result.device.as_str(), baseline.device.as_str()),
+    ] {
+        if measured != expected {
+            warn!("Baseline {what} is {expected}, this run used {measured}");
+        }
+    }
+    if result.batch_size != baseline.batch_size {
+        warn!(
+            "Baseline batch size is {}, this run used {}",
+            baseline.batch_size, result.batch_size
+        );
+    }
+
+    let change_percent = if baseline.segments_per_sec > 0.0 {
+        (result.segments_per_sec / baseline.segments_per_sec - 1.0) * 100.0
+    } else {
+        0.0
+    };
+    BaselineComparison {
+        path: path.to_path_buf(),
+        segments_per_sec: baseline.segments_per_sec,
+        change_percent,
+        tolerance_percent,
+        regressed: -change_percent > tolerance_percent,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn result(segments_per_sec: f64) -> BenchResult {
+        BenchResult {
+            version: "1.0.0".to_string(),
+            model: "birdnet".to_string(),
+            device: "CUDA".to_string(),
+            batch_size: 32,
+            segments: 512,
+            elapsed_secs: 512.0 / segments_per_sec,
+            segments_per_sec,
+        }
+    }
+
+    #[test]
+    fn test_compare_within_tolerance() {
+        let path = Path::new("baseline.json");
+        let comparison = compare_to_baseline(&result(95.0), &result(100.0), path, 10.0);
+        assert!((comparison.change_percent + 5.0).abs() < 1e-9);
+        assert!(!comparison.regressed);
+
+        // Faster is never a regression
+        assert!(!compare_to_baseline(&result(300.0), &result(100.0), path, 0.0).regressed);
+    }
+
+    #[test]
+    fn test_compare_detects_regression() {
+        let comparison =
+            compare_to_baseline(&result(40.0), &result(100.0), Path::new("b.json"), 10.0);
+        assert!((comparison.change_percent + 60.0).abs() < 1e-9);
+        assert!(comparison.regressed);
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bench/baseline.json");
+        save_baseline(&path, &result(120.0)).unwrap();
+
+        let loaded = load_baseline(&path).unwrap();
+        assert_eq!(loaded.model, "birdnet");
+        assert!((loaded.segments_per_sec - 120.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_load_baseline_rejects_invalid_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("baseline.json");
+        std::fs::write(&path, "not json").unwrap();
+        assert!(matches!(
+            load_baseline(&path),
+            Err(Error::BaselineParse { .. })
+        ));
+    }
+}
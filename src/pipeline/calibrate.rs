@@ -0,0 +1,202 @@
+//! Overlap calibration for `birda calibrate`.
+//!
+//! Each sampled recording is streamed through the model once per overlap, a
+//! batch at a time, so long recordings need no more memory than short ones.
+//! Detections of a species in a file whose times overlap, at any overlap
+//! setting, form one detection event; an overlap's recall is the share of all
+//! events it found. Inference time is measured per overlap, so the extra
+//! recall of a denser overlap can be weighed against what it costs.
+
+use super::classify::{classify_chunks, for_each_batch};
+use crate::error::Result;
+use crate::inference::BirdClassifier;
+use crate::output::{Detection, OverlapCalibration};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// Detections and effort of one overlap across all sampled files.
+#[derive(Default)]
+struct Run {
+    segments: usize,
+    detections: Vec<Detection>,
+    elapsed: Duration,
+}
+
+/// Pick up to `count` files spread evenly over `files` in path order.
+pub fn sample_files(files: &[PathBuf], count: usize) -> Vec<PathBuf> {
+    let mut files = files.to_vec();
+    files.sort();
+    if files.len() <= count {
+        return files;
+    }
+    (0..count)
+        .map(|i| files[i * files.len() / count].clone())
+        .collect()
+}
+
+/// Classify `files` at each of `overlaps` and compare the results.
+///
+/// Returns the total number of detection events and one entry per overlap, in
+/// the given order; recall and runtime deltas are relative to the first.
+pub fn calibrate_overlaps(
+    classifier: &BirdClassifier,
+    files: &[PathBuf],
+    overlaps: &[f32],
+    min_confidence: f32,
+    batch_size: usize,
+) -> Result<(usize, Vec<OverlapCalibration>)> {
+    let mut runs: Vec<Run> = overlaps.iter().map(|_| Run::default()).collect();
+
+    for file in files {
+        info!("Calibrating on {}", file.display());
+        for (run, &overlap) in runs.iter_mut().zip(overlaps) {
+            for_each_batch(classifier, file, overlap, batch_size, |batch| {
+                // Only inference is timed; decoding costs the same at any overlap
+                let start = Instant::now();
+                let detections =
+                    classify_chunks(classifier, batch, min_confidence, batch_size, file)?;
+                run.elapsed += start.elapsed();
+                run.segments += batch.len();
+                run.detections.extend(detections);
+                Ok(())
+            })?;
+        }
+    }
+
+    let detections: Vec<&[Detection]> = runs.iter().map(|r| r.detections.as_slice()).collect();
+    let (events, found) = count_events(&detections);
+    let baseline = runs
+        .first()
+        .zip(found.first())
+        .map(|(run, &found)| (run.elapsed.as_secs_f64(), recall(found, events)));
+
+    let results = runs
+        .iter()
+        .zip(overlaps)
+        .zip(found)
+        .map(|((run, &overlap), found)| {
+            let runtime_secs = run.elapsed.as_secs_f64();
+            let share = recall(found, events);
+            OverlapCalibration {
+                overlap,
+                segments: run.segments,
+                detections: run.detections.len(),
+                events_found: found,
+                recall: share,
+                recall_delta: baseline.and_then(|(_, base)| Some(share? - base?)),
+                runtime_secs,
+                relative_runtime: baseline
+                    .map(|(base, _)| base)
+                    .filter(|base| *base > 0.0)
+                    .map(|base| runtime_secs / base),
+            }
+        })
+        .collect();
+    Ok((events, results))
+}
+
+/// Count detection events across `runs` and how many of them each run found.
+///
+/// Detections of the same species in the same file are merged into one event
+/// while their times overlap or touch, whichever run they come from.
+fn count_events(runs: &[&[Detection]]) -> (usize, Vec<usize>) {
+    let mut by_species: HashMap<(&Path, &str), Vec<(f32, f32, usize)>> = HashMap::new();
+    for (run, detections) in runs.iter().enumerate() {
+        for d in *detections {
+            by_species
+                .entry((d.file_path.as_path(), d.scientific_name.as_str()))
+                .or_default()
+                .push((d.start_time, d.end_time, run));
+        }
+    }
+
+    let mut events = 0;
+    let mut found = vec![0; runs.len()];
+    for mut spans in by_species.into_values() {
+        spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut end = f32::NEG_INFINITY;
+        let mut seen = vec![false; runs.len()];
+        for (start, stop, run) in spans {
+            if start > end {
+                // A new event starts; close the previous one
+                if end.is_finite() {
+                    events += 1;
+                    credit(&mut found, &seen);
+                }
+                seen.fill(false);
+                end = stop;
+            } else {
+                end = end.max(stop);
+            }
+            seen[run] = true;
+        }
+        if end.is_finite() {
+            events += 1;
+            credit(&mut found, &seen);
+        }
+    }
+    (events, found)
+}
+
+/// Count an event for every run that found it.
+fn credit(found: &mut [usize], seen: &[bool]) {
+    for (count, _) in found.iter_mut().zip(seen).filter(|(_, seen)| **seen) {
+        *count += 1;
+    }
+}
+
+/// Share of `events` that were found; `None` without any events.
+#[allow(clippy::cast_precision_loss)]
+fn recall(found: usize, events: usize) -> Option<f64> {
+    (events > 0).then(|| found as f64 / events as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_files_spreads_evenly() {
+        let files: Vec<PathBuf> = (0..10).map(|i| PathBuf::from(format!("{i}.wav"))).collect();
+        let sample = sample_files(&files, 3);
+        assert_eq!(
+            sample,
+            vec![
+                PathBuf::from("0.wav"),
+                PathBuf::from("3.wav"),
+                PathBuf::from("6.wav")
+            ]
+        );
+        assert_eq!(sample_files(&files, 20).len(), 10);
+    }
+
+    #[test]
+    fn test_count_events_merges_overlapping_detections() {
+        // No overlap: segments at 0 and 3 s; 1.5 s overlap adds 1.5 and 4.5 s
+        let sparse = vec![
+            Detection::test("Parus major_Common", 0.0, "a.wav"),
+            Detection::test("Parus major_Common", 30.0, "a.wav"),
+        ];
+        let dense = vec![
+            Detection::test("Parus major_Common", 1.5, "a.wav"),
+            // Only found with overlap
+            Detection::test("Turdus merula_Common", 10.5, "a.wav"),
+            // Same time, another file
+            Detection::test("Parus major_Common", 1.5, "b.wav"),
+        ];
+        let (events, found) = count_events(&[&sparse, &dense]);
+
+        assert_eq!(events, 4);
+        assert_eq!(found, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_count_events_without_detections() {
+        let (events, found) = count_events(&[&[], &[]]);
+        assert_eq!(events, 0);
+        assert_eq!(found, vec![0, 0]);
+        assert_eq!(recall(0, 0), None);
+    }
+}
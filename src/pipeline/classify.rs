@@ -0,0 +1,95 @@
+//! Classification of audio chunks outside the file processor.
+//!
+//! Used by commands that need plain segments and predictions without the
+//! processor's outputs and bookkeeping, such as overlap calibration and the
+//! HTTP server. Recordings are streamed through the decoder a batch at a
+//! time, so their length does not bound memory.
+
+use super::processor::{segment_lengths, source_samples};
+use crate::audio::{AudioChunk, StreamingDecoder, resample_chunk};
+use crate::error::Result;
+use crate::inference::{BirdClassifier, InferenceOptions};
+use crate::output::Detection;
+use std::path::Path;
+
+/// Decode `path` and pass its segments to `each`, `batch_size` at a time.
+///
+/// Segments are resampled to the model's rate one at a time and advance by
+/// the segment duration minus `overlap` seconds; the last is zero-padded.
+/// Returns the duration of the recording in seconds.
+pub fn for_each_batch(
+    classifier: &BirdClassifier,
+    path: &Path,
+    overlap: f32,
+    batch_size: usize,
+    mut each: impl FnMut(&[AudioChunk]) -> Result<()>,
+) -> Result<f32> {
+    let mut decoder = StreamingDecoder::open(path)?;
+    let source_rate = decoder.sample_rate();
+    let target_rate = classifier.sample_rate();
+    let segment_duration = classifier.segment_duration();
+    let (segment_samples, overlap_samples) =
+        segment_lengths(segment_duration, overlap, target_rate);
+    let source_segment_samples = source_samples(segment_samples, source_rate, target_rate);
+    let source_overlap_samples = source_samples(overlap_samples, source_rate, target_rate);
+
+    let batch_size = batch_size.max(1);
+    let mut batch = Vec::with_capacity(batch_size);
+    while let Some(raw) = decoder.next_segment(source_segment_samples, source_overlap_samples)? {
+        let mut samples = resample_chunk(raw.samples, source_rate, target_rate)?;
+        samples.resize(segment_samples, 0.0);
+        #[allow(clippy::cast_precision_loss)]
+        let start_time = raw.start_sample as f32 / source_rate as f32;
+        batch.push(AudioChunk {
+            samples: samples.into(),
+            start_time,
+            end_time: start_time + segment_duration,
+        });
+        if batch.len() == batch_size {
+            each(&batch)?;
+            batch.clear();
+        }
+    }
+    if !batch.is_empty() {
+        each(&batch)?;
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    let duration_secs = decoder.position_secs() as f32;
+    Ok(duration_secs)
+}
+
+/// Classify chunks `batch_size` at a time, keeping predictions at or above
+/// `min_confidence`.
+pub fn classify_chunks(
+    classifier: &BirdClassifier,
+    chunks: &[AudioChunk],
+    min_confidence: f32,
+    batch_size: usize,
+    file: &Path,
+) -> Result<Vec<Detection>> {
+    let mut detections = Vec::new();
+    for batch in chunks.chunks(batch_size.max(1)) {
+        let segments: Vec<&[f32]> = batch.iter().map(|c| c.samples.as_slice()).collect();
+        let results = classifier.predict_batch(&segments, &InferenceOptions::default())?;
+        let results = classifier.apply_range_filter(results)?;
+        for (chunk, result) in batch.iter().zip(&results) {
+            detections.extend(
+                result
+                    .predictions
+                    .iter()
+                    .filter(|p| p.confidence >= min_confidence)
+                    .map(|p| {
+                        Detection::from_label(
+                            &p.species,
+                            p.confidence,
+                            chunk.start_time,
+                            chunk.end_time,
+                            file.to_path_buf(),
+                        )
+                    }),
+            );
+        }
+    }
+    Ok(detections)
+}
@@ -1,6 +1,7 @@
 //! Configuration types for the processing pipeline.
 
-use crate::config::OutputFormat;
+use crate::config::{ActivityFormat, OutputFormat};
+use crate::gpu::WatchdogPolicy;
 use birdnet_onnx::CustomClassifier;
 use std::path::Path;
 
@@ -12,9 +13,11 @@ use std::path::Path;
 /// let config = ProcessingConfig {
 ///     input_path: Path::new("recording.wav"),
 ///     output_dir: Path::new("output/"),
+///     lock_dir: None,
 ///     formats: &[OutputFormat::Csv],
 ///     min_confidence: 0.5,
 ///     overlap: 0.0,
+///     sensitivity: 1.0,
 ///     batch_size: 32,
 ///     csv_columns: &[],
 ///     progress_enabled: true,
@@ -24,8 +27,33 @@ use std::path::Path;
 ///     bsg_params: None,
 ///     reporter: None,
 ///     dual_output_mode: false,
+///     stdout_format: None,
 ///     custom_classifier: None,
 ///     bat_mode: false,
+///     activity_format: None,
+///     activity_threshold_dbfs: -50.0,
+///     watchdog: &watchdog,
+///     warning_reporter: &NullReporter,
+///     postprocess_hook: None,
+///     merge_overlapping: false,
+///     zoom_overlap: None,
+///     fingerprint: false,
+///     provenance: None,
+///     recorder: None,
+///     recording_start: None,
+///     station: None,
+///     sensitive: None,
+///     track: None,
+///     run_manifest: None,
+///     combined: None,
+///     split_limits: SplitLimits::default(),
+///     number_format: NumberFormat::default(),
+///     time_range: None,
+///     channel: ChannelSelection::Mix,
+///     filter: None,
+///     stitch: None,
+///     report: None,
+///     fault: None,
 /// };
 /// ```
 #[allow(clippy::struct_excessive_bools)]
@@ -34,12 +62,16 @@ pub struct ProcessingConfig<'a> {
     pub input_path: &'a Path,
     /// Directory for output files.
     pub output_dir: &'a Path,
+    /// Central lock directory (hash-named lock files) instead of the output directory.
+    pub lock_dir: Option<&'a Path>,
     /// Output formats to generate.
     pub formats: &'a [OutputFormat],
     /// Minimum confidence threshold (0.0-1.0).
     pub min_confidence: f32,
     /// Overlap between chunks in seconds.
     pub overlap: f32,
+    /// Detection sensitivity the classifier applied, recorded on each detection.
+    pub sensitivity: f32,
     /// Number of chunks to process in parallel.
     pub batch_size: usize,
     /// Additional columns to include in CSV output.
@@ -58,9 +90,59 @@ pub struct ProcessingConfig<'a> {
     pub reporter: Option<&'a dyn crate::output::ProgressReporter>,
     /// Whether to write both files and stdout.
     pub dual_output_mode: bool,
+    /// Write results to stdout in this format instead of to files.
+    pub stdout_format: Option<OutputFormat>,
     /// Optional custom classifier for two-stage inference (e.g., bat detection).
     /// When present, backbone embeddings are fed to this classifier for final predictions.
     pub custom_classifier: Option<&'a CustomClassifier>,
     /// Whether bat audio mode is active (skip resampling, use bat chunk params).
     pub bat_mode: bool,
+    /// Optional format for the per-minute activity summary.
+    pub activity_format: Option<ActivityFormat>,
+    /// RMS level in dBFS above which a segment counts as active.
+    pub activity_threshold_dbfs: f32,
+    /// Inference watchdog policy for the active execution provider.
+    pub watchdog: &'a WatchdogPolicy,
+    /// Reporter that receives pipeline warnings (in every output mode).
+    pub warning_reporter: &'a dyn crate::output::ProgressReporter,
+    /// Optional external hook that rescores, drops, or annotates detections.
+    pub postprocess_hook: Option<&'a super::PostprocessHook>,
+    /// Whether to merge overlapping detections of a species into events.
+    pub merge_overlapping: bool,
+    /// Overlap in seconds for a second pass around detections, if any.
+    pub zoom_overlap: Option<f32>,
+    /// Whether to compute an audio fingerprint for each detection.
+    pub fingerprint: bool,
+    /// Provenance to stamp on each detection (device, model hash, time).
+    pub provenance: Option<&'a crate::output::Provenance>,
+    /// Song Meter recorder settings to stamp on each detection.
+    pub recorder: Option<&'a crate::audio::RecorderMetadata>,
+    /// Recording start for absolute detection times, if they are written.
+    pub recording_start: Option<chrono::NaiveDateTime>,
+    /// Station identity to stamp on each detection.
+    pub station: Option<&'a crate::output::Station>,
+    /// Sensitive species flagged here and masked in combined outputs.
+    pub sensitive: Option<&'a crate::output::SensitiveSpecies>,
+    /// GPS track that sets each detection's location (mobile surveys).
+    pub track: Option<&'a crate::utils::track::GpsTrack>,
+    /// Run manifest to write beside the result files.
+    pub run_manifest: Option<&'a crate::output::RunManifest>,
+    /// Combined output that also receives this file's detections.
+    pub combined: Option<&'a crate::output::CombinedSender>,
+    /// Row and size limits after which CSV/Parquet outputs roll over into parts.
+    pub split_limits: crate::output::SplitLimits,
+    /// Decimal places of confidences and times in the results.
+    pub number_format: crate::output::NumberFormat,
+    /// Part of the recording to analyze; timestamps stay relative to the file start.
+    pub time_range: Option<crate::audio::TimeRange>,
+    /// Channels analyzed: the mono mix, one channel, or each channel separately.
+    pub channel: crate::audio::ChannelSelection,
+    /// High-pass or band-pass filter applied before segmenting, if any.
+    pub filter: Option<crate::audio::FilterSpec>,
+    /// How this recording continues from and into its neighbours with `--stitch`.
+    pub stitch: Option<&'a super::Stitch>,
+    /// Run report that also summarizes this file's detections.
+    pub report: Option<&'a crate::output::RunReport>,
+    /// Fault injected to exercise error paths (`--fault-inject`).
+    pub fault: Option<&'a super::FaultInjector>,
 }
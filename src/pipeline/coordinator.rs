@@ -1,9 +1,13 @@
 //! Pipeline coordination for file processing.
 
-use crate::config::OutputFormat;
+use crate::config::{ActivityFormat, EmbeddingFormat, OutputFormat, SkipPolicy};
+use crate::constants::dead_letter::DIR_NAME as DEAD_LETTER_DIR;
 use crate::constants::output_extensions;
 use crate::error::{Error, Result};
 use crate::locking::FileLock;
+use crate::output::RunManifest;
+use crate::utils::glob::{self, Glob};
+use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
 use tracing::warn;
 
@@ -37,6 +41,52 @@ pub enum ProcessCheck {
     SkipLocked,
 }
 
+/// Size and duration limits that leave input files out before analysis.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FileFilter {
+    /// Shortest recording to analyze, in seconds.
+    pub min_duration: Option<f64>,
+    /// Longest recording to analyze, in seconds.
+    pub max_duration: Option<f64>,
+    /// Smallest file to analyze, in bytes.
+    pub min_size: Option<u64>,
+}
+
+impl FileFilter {
+    /// Why `path` is left out, or `None` if it should be analyzed.
+    ///
+    /// Files whose duration cannot be read from their headers are not
+    /// filtered by duration; decoding reports them instead.
+    pub fn rejection(&self, path: &Path) -> Option<String> {
+        if let Some(min_size) = self.min_size {
+            let size = std::fs::metadata(path).map_or(0, |m| m.len());
+            if size < min_size {
+                return Some(format!("{size} bytes is below --min-size {min_size}"));
+            }
+        }
+        if self.min_duration.is_none() && self.max_duration.is_none() {
+            return None;
+        }
+        let duration = crate::audio::get_audio_duration(path).ok().flatten()?;
+        self.duration_rejection(duration)
+    }
+
+    /// Why a recording of `duration` seconds is left out, if it is.
+    fn duration_rejection(&self, duration: f64) -> Option<String> {
+        if let Some(min) = self.min_duration.filter(|&min| duration < min) {
+            return Some(format!(
+                "{duration:.1}s is shorter than --min-duration {min}s"
+            ));
+        }
+        if let Some(max) = self.max_duration.filter(|&max| duration > max) {
+            return Some(format!(
+                "{duration:.1}s is longer than --max-duration {max}s"
+            ));
+        }
+        None
+    }
+}
+
 /// Determine the output directory for a file.
 pub fn output_dir_for(input: &Path, explicit_output_dir: Option<&Path>) -> PathBuf {
     explicit_output_dir.map_or_else(
@@ -49,6 +99,29 @@ pub fn output_dir_for(input: &Path, explicit_output_dir: Option<&Path>) -> PathB
     )
 }
 
+/// Determine the directory for combined results across `files`.
+///
+/// Uses the explicit output directory, otherwise the deepest directory that
+/// contains every input file.
+pub fn combined_output_dir(files: &[PathBuf], explicit_output_dir: Option<&Path>) -> PathBuf {
+    if let Some(dir) = explicit_output_dir {
+        return dir.to_path_buf();
+    }
+
+    let mut parents = files.iter().map(|file| output_dir_for(file, None));
+    let Some(mut common) = parents.next() else {
+        return PathBuf::from(".");
+    };
+    for parent in parents {
+        while !parent.starts_with(&common) {
+            if !common.pop() {
+                return PathBuf::from(".");
+            }
+        }
+    }
+    common
+}
+
 /// Sanitize a filename to prevent path traversal attacks.
 ///
 /// Replaces path separators with underscores.
@@ -61,6 +134,63 @@ fn sanitize_filename(filename: &str) -> String {
 /// The filename is sanitized to prevent path traversal attacks.
 /// Returns an error if the output path would escape the output directory.
 pub fn output_path_for(input: &Path, output_dir: &Path, format: OutputFormat) -> Result<PathBuf> {
+    let extension = match format {
+        OutputFormat::Csv => output_extensions::CSV,
+        OutputFormat::BirdnetCsv => output_extensions::BIRDNET_CSV,
+        OutputFormat::Raven => output_extensions::RAVEN,
+        OutputFormat::Audacity => output_extensions::AUDACITY,
+        OutputFormat::Kaleidoscope => output_extensions::KALEIDOSCOPE,
+        OutputFormat::Json => output_extensions::JSON,
+        OutputFormat::Parquet => output_extensions::PARQUET,
+        OutputFormat::Sqlite => {
+            return Err(Error::InvalidOutputFormat {
+                value: "sqlite is written once per run, not per file".to_string(),
+            });
+        }
+    };
+
+    output_path_with_extension(input, output_dir, extension)
+}
+
+/// Get the activity summary path for a given format.
+///
+/// Uses the same sanitization and traversal checks as [`output_path_for`].
+pub fn activity_path_for(
+    input: &Path,
+    output_dir: &Path,
+    format: ActivityFormat,
+) -> Result<PathBuf> {
+    let extension = match format {
+        ActivityFormat::Csv => output_extensions::ACTIVITY_CSV,
+        ActivityFormat::Parquet => output_extensions::ACTIVITY_PARQUET,
+    };
+
+    output_path_with_extension(input, output_dir, extension)
+}
+
+/// Get the embeddings output path for an input file.
+pub fn embeddings_path_for(
+    input: &Path,
+    output_dir: &Path,
+    format: EmbeddingFormat,
+) -> Result<PathBuf> {
+    let extension = match format {
+        EmbeddingFormat::Parquet => output_extensions::EMBEDDINGS_PARQUET,
+        EmbeddingFormat::Npy => output_extensions::EMBEDDINGS_NPY,
+    };
+
+    output_path_with_extension(input, output_dir, extension)
+}
+
+/// Get the run manifest path for an input file.
+///
+/// Uses the same sanitization and traversal checks as [`output_path_for`].
+pub fn manifest_path_for(input: &Path, output_dir: &Path) -> Result<PathBuf> {
+    output_path_with_extension(input, output_dir, output_extensions::MANIFEST)
+}
+
+/// Build an output path from the input file stem and an extension suffix.
+fn output_path_with_extension(input: &Path, output_dir: &Path, extension: &str) -> Result<PathBuf> {
     // Use to_string_lossy() to handle non-UTF-8 filenames gracefully
     // Invalid UTF-8 sequences will be replaced with the Unicode replacement character
     let stem = input.file_stem().map_or_else(
@@ -71,15 +201,6 @@ pub fn output_path_for(input: &Path, output_dir: &Path, format: OutputFormat) ->
     // Sanitize filename to prevent path traversal
     let safe_stem = sanitize_filename(&stem);
 
-    let extension = match format {
-        OutputFormat::Csv => output_extensions::CSV,
-        OutputFormat::Raven => output_extensions::RAVEN,
-        OutputFormat::Audacity => output_extensions::AUDACITY,
-        OutputFormat::Kaleidoscope => output_extensions::KALEIDOSCOPE,
-        OutputFormat::Json => output_extensions::JSON,
-        OutputFormat::Parquet => output_extensions::PARQUET,
-    };
-
     let output_path = output_dir.join(format!("{safe_stem}{extension}"));
 
     // Runtime verification: output path must stay within output directory
@@ -94,53 +215,108 @@ pub fn output_path_for(input: &Path, output_dir: &Path, format: OutputFormat) ->
 }
 
 /// Check if a file should be processed.
+///
+/// `lock_dir` is the central lock directory, if one is used instead of
+/// lock files beside the outputs. `settings_hash` is the hash of the current
+/// run settings, compared against the recorded manifest by
+/// [`SkipPolicy::Hash`].
 pub fn should_process(
     input: &Path,
     output_dir: &Path,
+    lock_dir: Option<&Path>,
     formats: &[OutputFormat],
-    force: bool,
+    policy: SkipPolicy,
+    settings_hash: &str,
     stdout_mode: bool,
 ) -> ProcessCheck {
     // Check if locked
-    if FileLock::is_locked(input, output_dir) {
+    if FileLock::resolve_lock_path(input, output_dir, lock_dir).exists() {
         return ProcessCheck::SkipLocked;
     }
 
     // Skip file existence check in stdout mode (no files written)
-    if stdout_mode {
+    if stdout_mode || policy == SkipPolicy::Never {
         return ProcessCheck::Process;
     }
 
-    // Check if all outputs exist (unless force)
-    if !force {
-        let all_exist = formats.iter().all(|fmt| {
-            output_path_for(input, output_dir, *fmt).map_or_else(
-                |e| {
-                    warn!("Failed to generate output path: {}", e);
-                    false
-                },
-                |p| p.exists(),
-            )
-        });
-        if all_exist {
-            return ProcessCheck::SkipExists;
-        }
+    // Without per-file outputs (e.g. only a partitioned Parquet dataset), the
+    // run manifest written after each file marks it as done
+    let expected: Vec<Result<PathBuf>> = if formats.is_empty() {
+        vec![manifest_path_for(input, output_dir)]
+    } else {
+        formats
+            .iter()
+            .map(|fmt| output_path_for(input, output_dir, *fmt))
+            .collect()
+    };
+    let outputs: Option<Vec<PathBuf>> = expected
+        .into_iter()
+        .map(|path| {
+            path.inspect_err(|e| warn!("Failed to generate output path: {}", e))
+                .ok()
+                .filter(|p| p.exists())
+        })
+        .collect();
+    let Some(outputs) = outputs else {
+        return ProcessCheck::Process;
+    };
+
+    let up_to_date = match policy {
+        SkipPolicy::Exists => true,
+        SkipPolicy::Newer => outputs_newer_than_input(input, &outputs),
+        SkipPolicy::Hash => manifest_path_for(input, output_dir)
+            .and_then(|path| RunManifest::read(&path))
+            .is_ok_and(|manifest| manifest.settings_hash == settings_hash),
+        SkipPolicy::Never => false,
+    };
+
+    if up_to_date {
+        ProcessCheck::SkipExists
+    } else {
+        ProcessCheck::Process
     }
+}
 
-    ProcessCheck::Process
+/// Whether every output was modified after the input audio file.
+fn outputs_newer_than_input(input: &Path, outputs: &[PathBuf]) -> bool {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    let Some(input_modified) = modified(input) else {
+        return false;
+    };
+    outputs
+        .iter()
+        .all(|output| modified(output).is_some_and(|m| m >= input_modified))
 }
 
-/// Collect input files from paths (files and directories).
-pub fn collect_input_files(paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+/// Collect input files from paths (files, directories and glob patterns).
+///
+/// A glob such as `recordings/**/*.wav` is expanded below its leading
+/// directory. Files and directories matching an `exclude` pattern are left
+/// out: a pattern without `/` matches any file or directory name, one with `/`
+/// the path below the input directory.
+pub fn collect_input_files(paths: &[PathBuf], exclude: &[Glob]) -> Result<Vec<PathBuf>> {
     let mut files = Vec::new();
 
     for path in paths {
         if path.is_file() {
-            if is_audio_file(path) {
+            if is_audio_file(path) && !is_excluded(path, Path::new(""), exclude) {
                 files.push(path.clone());
             }
         } else if path.is_dir() {
-            collect_audio_files_recursive(path, &mut files)?;
+            collect_audio_files_recursive(path, path, None, exclude, &mut files)?;
+        } else if glob::is_glob(path) {
+            let (base, pattern) = glob::split_glob(path);
+            let pattern = Glob::new(&pattern).map_err(|e| Error::ConfigValidation {
+                message: format!("invalid input pattern '{}': {e}", path.display()),
+            })?;
+            if base.is_dir() {
+                collect_audio_files_recursive(&base, &base, Some(&pattern), exclude, &mut files)?;
+            } else {
+                warn!(
+                    "Skipping pattern below non-existent directory: {}",
+                    path.display()
+                );
+            }
         } else {
             warn!("Skipping non-existent path: {}", path.display());
         }
@@ -149,15 +325,84 @@ pub fn collect_input_files(paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
     Ok(files)
 }
 
-/// Recursively collect audio files from a directory.
-fn collect_audio_files_recursive(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+/// Read input paths from a file list, one per line (`-` reads stdin).
+///
+/// Blank lines and lines starting with `#` are skipped. Relative paths are
+/// taken as given on the command line, relative to the working directory.
+pub fn read_file_list(path: &Path) -> Result<Vec<PathBuf>> {
+    let list_error = |source| Error::FileListRead {
+        path: path.to_path_buf(),
+        source,
+    };
+    if path == Path::new("-") {
+        parse_file_list(std::io::stdin().lock()).map_err(list_error)
+    } else {
+        let file = std::fs::File::open(path).map_err(list_error)?;
+        parse_file_list(std::io::BufReader::new(file)).map_err(list_error)
+    }
+}
+
+/// Parse the lines of a file list.
+fn parse_file_list(reader: impl std::io::BufRead) -> std::io::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if !line.is_empty() && !line.starts_with('#') {
+            paths.push(PathBuf::from(line));
+        }
+    }
+    Ok(paths)
+}
+
+/// Pick a random sample of `count` of `files`, in path order.
+///
+/// Each file is ranked by a hash of `seed` and its path, so the same seed
+/// selects the same files from the same archive whatever the input order.
+pub fn random_sample(files: &[PathBuf], count: usize, seed: u64) -> Vec<PathBuf> {
+    let rank = |path: &PathBuf| {
+        let digest = Sha256::new()
+            .chain_update(seed.to_le_bytes())
+            .chain_update(path.as_os_str().as_encoded_bytes())
+            .finalize();
+        let mut key = [0u8; 8];
+        key.copy_from_slice(&digest[..8]);
+        u64::from_le_bytes(key)
+    };
+    let mut ranked: Vec<(u64, &PathBuf)> = files.iter().map(|path| (rank(path), path)).collect();
+    ranked.sort_unstable();
+    let mut sample: Vec<PathBuf> = ranked
+        .into_iter()
+        .take(count)
+        .map(|(_, path)| path.clone())
+        .collect();
+    sample.sort();
+    sample
+}
+
+/// Recursively collect audio files from `dir` below the input directory
+/// `root`, keeping only files whose path below `root` matches `pattern`.
+fn collect_audio_files_recursive(
+    root: &Path,
+    dir: &Path,
+    pattern: Option<&Glob>,
+    exclude: &[Glob],
+    files: &mut Vec<PathBuf>,
+) -> Result<()> {
     for entry in std::fs::read_dir(dir)? {
         let entry = entry?;
         let path = entry.path();
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        if is_excluded(&path, relative, exclude) {
+            continue;
+        }
 
         if path.is_dir() {
-            collect_audio_files_recursive(&path, files)?;
-        } else if is_audio_file(&path) {
+            // Recordings set aside by --dead-letter are not analyzed again
+            if path.file_name() != Some(std::ffi::OsStr::new(DEAD_LETTER_DIR)) {
+                collect_audio_files_recursive(root, &path, pattern, exclude, files)?;
+            }
+        } else if is_audio_file(&path) && pattern.is_none_or(|p| p.matches_path(relative)) {
             files.push(path);
         }
     }
@@ -165,6 +410,19 @@ fn collect_audio_files_recursive(dir: &Path, files: &mut Vec<PathBuf>) -> Result
     Ok(())
 }
 
+/// Whether `path`, at `relative` below its input directory, matches an
+/// exclude pattern.
+fn is_excluded(path: &Path, relative: &Path, exclude: &[Glob]) -> bool {
+    let name = path.file_name().map(|n| n.to_string_lossy());
+    exclude.iter().any(|glob| {
+        if glob.is_name_pattern() {
+            name.as_ref().is_some_and(|name| glob.matches_name(name))
+        } else {
+            glob.matches_path(relative)
+        }
+    })
+}
+
 /// Check if a file is a supported audio format.
 fn is_audio_file(path: &Path) -> bool {
     const AUDIO_EXTENSIONS: &[&str] = &["wav", "flac", "mp3", "m4a", "aac"];
@@ -182,6 +440,23 @@ fn is_audio_file(path: &Path) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_combined_output_dir() {
+        let files = vec![
+            PathBuf::from("/data/site1/a.wav"),
+            PathBuf::from("/data/site2/night/b.wav"),
+        ];
+        assert_eq!(combined_output_dir(&files, None), PathBuf::from("/data"));
+        assert_eq!(
+            combined_output_dir(&files, Some(Path::new("/results"))),
+            PathBuf::from("/results")
+        );
+        assert_eq!(
+            combined_output_dir(&files[..1], None),
+            PathBuf::from("/data/site1")
+        );
+    }
+
     #[test]
     fn test_output_dir_for_with_explicit() {
         let input = Path::new("/data/audio.wav");
@@ -207,6 +482,17 @@ mod tests {
         assert!(path.to_string_lossy().ends_with(".BirdNET.results.csv"));
     }
 
+    #[test]
+    fn test_activity_path_for_parquet() {
+        let path = activity_path_for(
+            Path::new("test.wav"),
+            Path::new("/output"),
+            ActivityFormat::Parquet,
+        )
+        .unwrap();
+        assert_eq!(path, PathBuf::from("/output/test.BirdNET.activity.parquet"));
+    }
+
     #[test]
     fn test_is_audio_file() {
         assert!(is_audio_file(Path::new("test.wav")));
@@ -288,4 +574,185 @@ mod tests {
         let path_str = path1.to_string_lossy();
         assert!(!path_str.contains("../"));
     }
+
+    #[test]
+    fn test_should_process_skip_policies() {
+        use crate::output::RunSettings;
+
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("rec.wav");
+        std::fs::write(&input, b"audio").unwrap();
+        let formats = [OutputFormat::Csv];
+        let check = |policy, hash: &str| {
+            matches!(
+                should_process(&input, dir.path(), None, &formats, policy, hash, false),
+                ProcessCheck::SkipExists
+            )
+        };
+
+        // No outputs yet: always process
+        assert!(!check(SkipPolicy::Exists, ""));
+
+        let output = output_path_for(&input, dir.path(), OutputFormat::Csv).unwrap();
+        std::fs::write(&output, b"results").unwrap();
+        assert!(check(SkipPolicy::Exists, ""));
+        assert!(check(SkipPolicy::Newer, ""));
+        assert!(!check(SkipPolicy::Never, ""));
+
+        // Hash policy needs a manifest with a matching settings hash
+        let manifest = RunManifest::new(RunSettings {
+            model: "birdnet".to_string(),
+            model_type: "birdnet-v24".to_string(),
+            model_sha256: None,
+            min_confidence: 0.1,
+            overlap: 0.0,
+            sensitivity: None,
+            latitude: None,
+            longitude: None,
+            week: None,
+            species_list: None,
+            bat: false,
+            postprocess_hook: None,
+            merge_overlapping: false,
+            zoom_overlap: None,
+            channel: None,
+            filter: None,
+            custom_head: None,
+            time_range: None,
+            stitch: false,
+            confidence_decimals: None,
+            time_decimals: None,
+        });
+        assert!(!check(SkipPolicy::Hash, &manifest.settings_hash));
+        manifest
+            .write(&manifest_path_for(&input, dir.path()).unwrap())
+            .unwrap();
+        assert!(check(SkipPolicy::Hash, &manifest.settings_hash));
+        assert!(!check(SkipPolicy::Hash, "different"));
+    }
+
+    #[test]
+    fn test_should_process_without_file_formats_uses_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("rec.wav");
+        std::fs::write(&input, b"audio").unwrap();
+        let check = || {
+            matches!(
+                should_process(&input, dir.path(), None, &[], SkipPolicy::Exists, "", false),
+                ProcessCheck::SkipExists
+            )
+        };
+
+        assert!(!check());
+        std::fs::write(manifest_path_for(&input, dir.path()).unwrap(), b"{}").unwrap();
+        assert!(check());
+    }
+
+    #[test]
+    fn test_random_sample_is_seeded() {
+        let files: Vec<PathBuf> = (0..100)
+            .map(|i| PathBuf::from(format!("rec_{i:03}.wav")))
+            .collect();
+        let sample = random_sample(&files, 10, 42);
+        assert_eq!(sample.len(), 10);
+        assert!(sample.is_sorted());
+
+        // Input order does not matter, the seed does
+        let reversed: Vec<PathBuf> = files.iter().rev().cloned().collect();
+        assert_eq!(random_sample(&reversed, 10, 42), sample);
+        assert_ne!(random_sample(&files, 10, 43), sample);
+
+        // A larger sample contains the smaller one
+        let larger = random_sample(&files, 20, 42);
+        assert!(sample.iter().all(|file| larger.contains(file)));
+        assert_eq!(random_sample(&files, 500, 42).len(), 100);
+    }
+
+    #[test]
+    fn test_file_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        let empty = dir.path().join("empty.wav");
+        std::fs::write(&empty, b"").unwrap();
+
+        assert!(FileFilter::default().rejection(&empty).is_none());
+        let filter = FileFilter {
+            min_size: Some(1024),
+            ..FileFilter::default()
+        };
+        assert!(filter.rejection(&empty).is_some());
+
+        let filter = FileFilter {
+            min_duration: Some(10.0),
+            max_duration: Some(86_400.0),
+            min_size: None,
+        };
+        assert!(filter.duration_rejection(3.0).is_some());
+        assert!(filter.duration_rejection(3600.0).is_none());
+        assert!(filter.duration_rejection(3.0 * 86_400.0).is_some());
+        // Unreadable durations are left to the decoder
+        assert!(filter.rejection(&empty).is_none());
+    }
+
+    #[test]
+    fn test_parse_file_list() {
+        let list = "# generated by survey-export\r\n/data/site1/a.wav\r\n\n  site2/b.flac  \n";
+        assert_eq!(
+            parse_file_list(list.as_bytes()).unwrap(),
+            [
+                PathBuf::from("/data/site1/a.wav"),
+                PathBuf::from("site2/b.flac")
+            ]
+        );
+        assert!(matches!(
+            read_file_list(Path::new("does-not-exist.txt")),
+            Err(Error::FileListRead { .. })
+        ));
+    }
+
+    #[test]
+    fn test_collect_input_files_glob_and_exclude() {
+        let dir = tempfile::tempdir().unwrap();
+        for file in [
+            "site1/dawn.wav",
+            "site1/calibration/tone.wav",
+            "site1/night/owl.flac",
+            "site2/dusk.wav",
+            "site2/noise/ref.wav",
+            "site2/_failed/corrupt.wav",
+        ] {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"").unwrap();
+        }
+        let collect = |paths: &[PathBuf], exclude: &[&str]| {
+            let exclude: Vec<Glob> = exclude.iter().map(|p| Glob::new(p).unwrap()).collect();
+            let mut files: Vec<String> = collect_input_files(paths, &exclude)
+                .unwrap()
+                .iter()
+                .map(|f| {
+                    let relative = f.strip_prefix(dir.path()).unwrap();
+                    relative.to_string_lossy().replace('\\', "/")
+                })
+                .collect();
+            files.sort();
+            files
+        };
+
+        let root = vec![dir.path().to_path_buf()];
+        assert_eq!(collect(&root, &[]).len(), 5);
+        assert_eq!(
+            collect(&root, &["calibration", "site*/noise"]),
+            ["site1/dawn.wav", "site1/night/owl.flac", "site2/dusk.wav"]
+        );
+
+        let pattern = vec![dir.path().join("**/*.wav")];
+        assert_eq!(
+            collect(&pattern, &["calibration"]),
+            ["site1/dawn.wav", "site2/dusk.wav", "site2/noise/ref.wav"]
+        );
+        assert_eq!(
+            collect(&[dir.path().join("site2/*.wav")], &[]),
+            ["site2/dusk.wav"]
+        );
+    }
 }
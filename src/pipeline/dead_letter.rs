@@ -0,0 +1,254 @@
+//! Dead-letter handling of recordings that cannot be decoded.
+//!
+//! Unattended runs over large archives meet the odd corrupt or truncated
+//! recording. With a dead-letter mode set, each such file is moved or
+//! symlinked into a `_failed/` directory together with an `.error.json`
+//! record, or only gets the record beside its results, so operators can
+//! collect the problem files instead of searching the logs for them.
+
+use crate::config::DeadLetterMode;
+use crate::constants::dead_letter::{DIR_NAME, RECORD_EXTENSION};
+use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Where and how recordings that fail to decode are set aside.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeadLetter {
+    /// What happens to the failed recording.
+    pub mode: DeadLetterMode,
+    /// Directory for moved or linked recordings; `_failed/` in the file's
+    /// output directory when `None`.
+    pub dir: Option<PathBuf>,
+}
+
+/// Contents of an `.error.json` record.
+#[derive(Debug, Serialize)]
+struct FailureRecord<'a> {
+    /// Original path of the recording.
+    file: &'a Path,
+    /// Error message.
+    error: String,
+    /// Messages of the underlying errors, outermost first.
+    causes: Vec<String>,
+    /// When the file failed.
+    failed_at: DateTime<Utc>,
+    /// Version of birda that failed to read the file.
+    birda_version: &'static str,
+}
+
+impl DeadLetter {
+    /// Set `file` aside after it failed with `error`, if the error means the
+    /// recording itself is unreadable.
+    ///
+    /// Returns the path of the `.error.json` record, or `None` when the error
+    /// is not the recording's fault (a full disk or a failed inference must
+    /// not move files away).
+    pub fn handle(&self, file: &Path, output_dir: &Path, error: &Error) -> Result<Option<PathBuf>> {
+        let Some(name) = file.file_name().filter(|_| is_unreadable_audio(error)) else {
+            return Ok(None);
+        };
+
+        let record_dir = match self.mode {
+            DeadLetterMode::Sidecar => output_dir.to_path_buf(),
+            DeadLetterMode::Move | DeadLetterMode::Symlink => {
+                let dir = self
+                    .dir
+                    .clone()
+                    .unwrap_or_else(|| output_dir.join(DIR_NAME));
+                std::fs::create_dir_all(&dir)?;
+                let target = unique_path(&dir.join(name));
+                if self.mode == DeadLetterMode::Move {
+                    move_file(file, &target)?;
+                } else {
+                    symlink_file(&std::path::absolute(file)?, &target)?;
+                }
+                return write_record(file, &target, error).map(Some);
+            }
+        };
+        std::fs::create_dir_all(&record_dir)?;
+        write_record(file, &record_dir.join(name), error).map(Some)
+    }
+}
+
+/// Whether `error` means the recording itself cannot be read: it fails to
+/// open, probe or decode. A missing `--channel`, a stitch mismatch or an
+/// injected fault is not the recording's fault.
+const fn is_unreadable_audio(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::AudioOpen { .. }
+            | Error::UnsupportedAudioFormat { .. }
+            | Error::AudioDecode { .. }
+            | Error::NoAudioTracks { .. }
+    )
+}
+
+/// Write the `.error.json` record of `file` beside `placed`.
+fn write_record(file: &Path, placed: &Path, error: &Error) -> Result<PathBuf> {
+    let mut causes = Vec::new();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        causes.push(cause.to_string());
+        source = cause.source();
+    }
+    let record = FailureRecord {
+        file,
+        error: error.to_string(),
+        causes,
+        failed_at: Utc::now(),
+        birda_version: env!("CARGO_PKG_VERSION"),
+    };
+
+    let mut path = placed.as_os_str().to_owned();
+    path.push(RECORD_EXTENSION);
+    let path = PathBuf::from(path);
+    let json = serde_json::to_string_pretty(&record).map_err(std::io::Error::other)?;
+    std::fs::write(&path, json)?;
+    Ok(path)
+}
+
+/// `path`, or `path` with `-1`, `-2`, ... added to its stem when taken.
+fn unique_path(path: &Path) -> PathBuf {
+    if path.symlink_metadata().is_err() {
+        return path.to_path_buf();
+    }
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    (1..)
+        .map(|n| path.with_file_name(format!("{stem}-{n}{extension}")))
+        .find(|candidate| candidate.symlink_metadata().is_err())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Move `from` to `to`, copying when they are on different filesystems.
+fn move_file(from: &Path, to: &Path) -> Result<()> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    std::fs::copy(from, to)?;
+    std::fs::remove_file(from)?;
+    Ok(())
+}
+
+/// Create a symbolic link at `link` pointing to `target`.
+#[cfg(unix)]
+fn symlink_file(target: &Path, link: &Path) -> Result<()> {
+    Ok(std::os::unix::fs::symlink(target, link)?)
+}
+
+/// Create a symbolic link at `link` pointing to `target`.
+#[cfg(windows)]
+fn symlink_file(target: &Path, link: &Path) -> Result<()> {
+    Ok(std::os::windows::fs::symlink_file(target, link)?)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn decode_error(path: &Path) -> Error {
+        Error::AudioDecode {
+            path: path.to_path_buf(),
+            source: "invalid packet".into(),
+        }
+    }
+
+    #[test]
+    fn test_move_writes_record() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("corrupt.wav");
+        std::fs::write(&file, b"not audio").unwrap();
+
+        let dead_letter = DeadLetter {
+            mode: DeadLetterMode::Move,
+            dir: None,
+        };
+        let record = dead_letter
+            .handle(&file, tmp.path(), &decode_error(&file))
+            .unwrap()
+            .unwrap();
+
+        assert!(!file.exists());
+        assert!(tmp.path().join(DIR_NAME).join("corrupt.wav").exists());
+        assert_eq!(
+            record,
+            tmp.path().join(DIR_NAME).join("corrupt.wav.error.json")
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(record).unwrap()).unwrap();
+        assert_eq!(json["causes"][0], "invalid packet");
+    }
+
+    #[test]
+    fn test_sidecar_keeps_file() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("corrupt.wav");
+        std::fs::write(&file, b"not audio").unwrap();
+        let output_dir = tmp.path().join("results");
+
+        let dead_letter = DeadLetter {
+            mode: DeadLetterMode::Sidecar,
+            dir: None,
+        };
+        let record = dead_letter
+            .handle(&file, &output_dir, &decode_error(&file))
+            .unwrap();
+
+        assert!(file.exists());
+        assert_eq!(record, Some(output_dir.join("corrupt.wav.error.json")));
+    }
+
+    #[test]
+    fn test_other_errors_are_not_dead_lettered() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("fine.wav");
+        std::fs::write(&file, b"audio").unwrap();
+
+        let dead_letter = DeadLetter {
+            mode: DeadLetterMode::Move,
+            dir: None,
+        };
+        let errors = [
+            Error::Inference {
+                reason: "timeout".to_string(),
+            },
+            Error::AudioChannel {
+                path: file.clone(),
+                channel: 2,
+                channels: 1,
+            },
+            Error::StitchSampleRate {
+                path: file.clone(),
+                sample_rate: 48_000,
+                expected: 24_000,
+            },
+            Error::InjectedFault {
+                path: file.clone(),
+                reason: "corrupt audio".to_string(),
+            },
+        ];
+        for error in &errors {
+            assert_eq!(dead_letter.handle(&file, tmp.path(), error).unwrap(), None);
+        }
+        assert!(file.exists());
+    }
+
+    #[test]
+    fn test_unique_path() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("a.wav");
+        assert_eq!(unique_path(&path), path);
+        std::fs::write(&path, b"").unwrap();
+        assert_eq!(unique_path(&path), tmp.path().join("a-1.wav"));
+    }
+}
@@ -0,0 +1,37 @@
+//! Segment embedding extraction for `birda embed`.
+
+use super::classify::for_each_batch;
+use crate::error::Result;
+use crate::inference::BirdClassifier;
+use crate::output::SegmentEmbedding;
+use std::path::Path;
+
+/// Decode `path` and pass the embeddings of its segments to `emit`, one batch
+/// at a time.
+///
+/// Segments advance by the model's segment duration minus `overlap` seconds
+/// and are run through the model `batch_size` at a time, so memory stays
+/// bounded however long the recording is.
+pub fn extract_embeddings(
+    classifier: &BirdClassifier,
+    path: &Path,
+    overlap: f32,
+    batch_size: usize,
+    mut emit: impl FnMut(&[SegmentEmbedding]) -> Result<()>,
+) -> Result<()> {
+    for_each_batch(classifier, path, overlap, batch_size, |batch| {
+        let segments: Vec<&[f32]> = batch.iter().map(|c| c.samples.as_slice()).collect();
+        let vectors = classifier.predict_embeddings(&segments)?;
+        let embeddings: Vec<SegmentEmbedding> = batch
+            .iter()
+            .zip(vectors)
+            .map(|(chunk, values)| SegmentEmbedding {
+                start_time: chunk.start_time,
+                end_time: chunk.end_time,
+                values,
+            })
+            .collect();
+        emit(&embeddings)
+    })?;
+    Ok(())
+}
@@ -0,0 +1,189 @@
+//! Failure injection for exercising error paths.
+//!
+//! The hidden `--fault-inject POINT[:N]` option (also read from
+//! `BIRDA_FAULT_INJECT`) makes analysis fail at a chosen point once N
+//! operations there have succeeded, so integration tests and GUI front ends
+//! can trigger decode errors, inference timeouts, out-of-memory retries and
+//! write failures, and the error events they produce, deterministically.
+//! Every operation at the point after the first N fails.
+
+use crate::error::Error;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Point in the pipeline where a fault can be injected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultPoint {
+    /// Decoding an audio segment fails.
+    Decode,
+    /// An inference batch times out.
+    Timeout,
+    /// An inference batch runs out of GPU memory, triggering batch size backoff.
+    OutOfMemory,
+    /// Writing a result file fails.
+    Write,
+}
+
+impl FaultPoint {
+    /// Name used on the command line.
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Decode => "decode",
+            Self::Timeout => "timeout",
+            Self::OutOfMemory => "oom",
+            Self::Write => "write",
+        }
+    }
+}
+
+/// Where to inject a fault and after how many successful operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FaultSpec {
+    /// Point at which operations fail.
+    pub point: FaultPoint,
+    /// Operations at `point` that succeed before the first fault.
+    pub after: usize,
+}
+
+impl FaultSpec {
+    /// Parse `POINT[:N]`, where `POINT` is `decode`, `timeout`, `oom` or `write`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let (point, after) = s.split_once(':').unwrap_or((s, "0"));
+        let point = [
+            FaultPoint::Decode,
+            FaultPoint::Timeout,
+            FaultPoint::OutOfMemory,
+            FaultPoint::Write,
+        ]
+        .into_iter()
+        .find(|p| p.name() == point.trim())
+        .ok_or_else(|| {
+            format!("unknown fault point '{point}' (expected decode, timeout, oom or write)")
+        })?;
+        let after = after
+            .trim()
+            .parse()
+            .map_err(|_| format!("'{after}' is not a number of operations"))?;
+        Ok(Self { point, after })
+    }
+}
+
+impl std::fmt::Display for FaultSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.point.name(), self.after)
+    }
+}
+
+/// Injected fault and the operations seen at its point so far.
+#[derive(Debug)]
+struct FaultState {
+    spec: FaultSpec,
+    seen: AtomicUsize,
+}
+
+impl FaultState {
+    /// Count an operation at `point`, returning whether it should fail.
+    fn trips(&self, point: FaultPoint) -> bool {
+        point == self.spec.point && self.seen.fetch_add(1, Ordering::Relaxed) >= self.spec.after
+    }
+}
+
+/// Fault injected into an analysis.
+///
+/// Clones share the operation count, so the decode threads and the inference
+/// of a run trip the same fault.
+#[derive(Debug, Clone)]
+pub struct FaultInjector(Arc<FaultState>);
+
+impl FaultInjector {
+    /// Inject `spec`, counting operations from zero.
+    pub fn new(spec: FaultSpec) -> Self {
+        Self(Arc::new(FaultState {
+            spec,
+            seen: AtomicUsize::new(0),
+        }))
+    }
+}
+
+/// Count an operation at `point` on `path`, failing it if `fault` is due.
+///
+/// # Errors
+/// Returns the error the real failure would produce, marked as injected.
+pub(super) fn inject(
+    fault: Option<&FaultInjector>,
+    point: FaultPoint,
+    path: &Path,
+) -> crate::error::Result<()> {
+    match fault {
+        Some(fault) if fault.0.trips(point) => Err(fault_error(point, path)),
+        _ => Ok(()),
+    }
+}
+
+/// Error standing in for a real failure at `point`.
+fn fault_error(point: FaultPoint, path: &Path) -> Error {
+    match point {
+        FaultPoint::Decode => Error::InjectedFault {
+            path: path.to_path_buf(),
+            reason: "corrupt audio".to_string(),
+        },
+        FaultPoint::Timeout => Error::Inference {
+            reason: "injected fault: inference timed out".to_string(),
+        },
+        FaultPoint::OutOfMemory => Error::Inference {
+            reason: "injected fault: out of memory".to_string(),
+        },
+        FaultPoint::Write => Error::Io(std::io::Error::other(format!(
+            "injected fault: failed to write results for '{}'",
+            path.display()
+        ))),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fault_spec() {
+        assert_eq!(
+            FaultSpec::parse("decode"),
+            Ok(FaultSpec {
+                point: FaultPoint::Decode,
+                after: 0
+            })
+        );
+        assert_eq!(
+            FaultSpec::parse("oom:3"),
+            Ok(FaultSpec {
+                point: FaultPoint::OutOfMemory,
+                after: 3
+            })
+        );
+        assert!(FaultSpec::parse("disk").is_err());
+        assert!(FaultSpec::parse("write:x").is_err());
+    }
+
+    #[test]
+    fn test_fault_trips_after_n_operations() {
+        let state = FaultState {
+            spec: FaultSpec::parse("timeout:2").unwrap(),
+            seen: AtomicUsize::new(0),
+        };
+        assert!(!state.trips(FaultPoint::Write));
+        assert!(!state.trips(FaultPoint::Timeout));
+        assert!(!state.trips(FaultPoint::Timeout));
+        assert!(state.trips(FaultPoint::Timeout));
+        assert!(state.trips(FaultPoint::Timeout));
+    }
+
+    #[test]
+    fn test_injected_oom_is_retried() {
+        let error = fault_error(FaultPoint::OutOfMemory, Path::new("a.wav"));
+        assert!(crate::gpu::is_out_of_memory(&error));
+        let error = fault_error(FaultPoint::Timeout, Path::new("a.wav"));
+        assert!(!crate::gpu::is_out_of_memory(&error));
+    }
+}
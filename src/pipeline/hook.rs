@@ -0,0 +1,368 @@
+//! External post-processing hook for per-segment predictions.
+//!
+//! A hook is any executable that reads one JSON document from stdin and writes
+//! the (possibly modified) document to stdout. It receives the detections of a
+//! file grouped by segment and can rescore, drop, or annotate predictions:
+//!
+//! ```json
+//! {
+//!   "file": "/data/rec.wav",
+//!   "segments": [
+//!     {
+//!       "start_time": 0.0,
+//!       "end_time": 3.0,
+//!       "predictions": [
+//!         { "scientific_name": "Parus major", "common_name": "Great Tit", "confidence": 0.91 }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Predictions omitted from the output are dropped, and an optional
+//! `annotation` string is carried into the `annotation` output column. When
+//! channels are analyzed separately, each segment also carries its `channel`
+//! and the channels' segments are kept apart. Returned predictions keep the
+//! other fields of the detection they came from.
+
+use crate::error::{Error, Result};
+use crate::output::Detection;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// A single prediction exchanged with the hook.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct HookPrediction {
+    scientific_name: String,
+    common_name: String,
+    confidence: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    annotation: Option<String>,
+}
+
+/// Predictions for one analyzed segment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct HookSegment {
+    start_time: f32,
+    end_time: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    channel: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    fingerprint: Option<String>,
+    predictions: Vec<HookPrediction>,
+}
+
+/// Document sent to the hook on stdin.
+#[derive(Debug, Serialize)]
+struct HookInput<'a> {
+    file: &'a Path,
+    segments: Vec<HookSegment>,
+}
+
+/// Document read back from the hook's stdout.
+#[derive(Debug, Deserialize)]
+struct HookOutput {
+    segments: Vec<HookSegment>,
+}
+
+/// Post-processing hook that runs an external program once per file.
+#[derive(Debug, Clone)]
+pub struct PostprocessHook {
+    program: PathBuf,
+}
+
+impl PostprocessHook {
+    /// Create a hook that runs `program`.
+    pub fn new(program: impl Into<PathBuf>) -> Self {
+        Self {
+            program: program.into(),
+        }
+    }
+
+    /// Pass a file's detections through the hook.
+    ///
+    /// Detections whose returned confidence falls below `min_confidence` are
+    /// dropped. The result is sorted like the pipeline output (start time,
+    /// then confidence descending).
+    pub fn apply(
+        &self,
+        file: &Path,
+        detections: Vec<Detection>,
+        min_confidence: f32,
+    ) -> Result<Vec<Detection>> {
+        let input = HookInput {
+            file,
+            segments: group_by_segment(&detections),
+        };
+        let payload = serde_json::to_vec(&input).map_err(|e| self.error(e.to_string()))?;
+
+        let mut child = Command::new(&self.program)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| self.error(format!("failed to start: {e}")))?;
+
+        // Write stdin on a separate thread so a hook that streams output
+        // before reading all input cannot deadlock on full pipes
+        let stdin = child.stdin.take();
+        let writer = std::thread::spawn(move || -> std::io::Result<()> {
+            if let Some(mut stdin) = stdin {
+                stdin.write_all(&payload)?;
+            }
+            Ok(())
+        });
+
+        let output = child
+            .wait_with_output()
+            .map_err(|e| self.error(e.to_string()))?;
+        match writer.join() {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => return Err(self.error(format!("failed to write input: {e}"))),
+            Err(_) => return Err(self.error("input writer thread panicked".to_string())),
+        }
+
+        if !output.status.success() {
+            return Err(self.error(format!("exited with {}", output.status)));
+        }
+
+        let parsed: HookOutput = serde_json::from_slice(&output.stdout)
+            .map_err(|e| self.error(format!("invalid output: {e}")))?;
+
+        ungroup_segments(file, &detections, parsed.segments, min_confidence)
+            .map_err(|reason| self.error(reason))
+    }
+
+    fn error(&self, reason: String) -> Error {
+        Error::PostprocessHook {
+            command: self.program.display().to_string(),
+            reason,
+        }
+    }
+}
+
+/// Group detections into segments keyed by (start, end, channel), preserving
+/// order.
+#[allow(clippy::float_cmp)]
+fn group_by_segment(detections: &[Detection]) -> Vec<HookSegment> {
+    let mut segments: Vec<HookSegment> = Vec::new();
+    for detection in detections {
+        let prediction = HookPrediction {
+            scientific_name: detection.scientific_name.clone(),
+            common_name: detection.common_name.clone(),
+            confidence: detection.confidence,
+            annotation: detection.metadata.annotation.clone(),
+        };
+        match segments.iter_mut().find(|s| {
+            s.start_time == detection.start_time
+                && s.end_time == detection.end_time
+                && s.channel == detection.metadata.channel
+        }) {
+            Some(segment) => segment.predictions.push(prediction),
+            None => segments.push(HookSegment {
+                start_time: detection.start_time,
+                end_time: detection.end_time,
+                channel: detection.metadata.channel,
+                fingerprint: detection.metadata.fingerprint.clone(),
+                predictions: vec![prediction],
+            }),
+        }
+    }
+    segments
+}
+
+/// Convert hook segments back into detections.
+///
+/// Each prediction starts from the original detection of its segment and
+/// species (or else any detection of the segment), so metadata such as the
+/// channel survives the hook.
+#[allow(clippy::float_cmp)]
+fn ungroup_segments(
+    file: &Path,
+    originals: &[Detection],
+    segments: Vec<HookSegment>,
+    min_confidence: f32,
+) -> std::result::Result<Vec<Detection>, String> {
+    let mut detections = Vec::new();
+    for segment in segments {
+        let in_segment = || {
+            originals.iter().filter(|d| {
+                d.start_time == segment.start_time
+                    && d.end_time == segment.end_time
+                    && d.metadata.channel == segment.channel
+            })
+        };
+        for prediction in segment.predictions {
+            if !(0.0..=1.0).contains(&prediction.confidence) {
+                return Err(format!(
+                    "confidence {} for {} at {}s is outside 0.0-1.0",
+                    prediction.confidence, prediction.scientific_name, segment.start_time
+                ));
+            }
+            if prediction.confidence < min_confidence {
+                continue;
+            }
+            let mut detection = in_segment()
+                .find(|d| d.scientific_name == prediction.scientific_name)
+                .or_else(|| in_segment().next())
+                .map_or_else(
+                    || {
+                        let mut detection = Detection::from_label(
+                            &prediction.scientific_name,
+                            prediction.confidence,
+                            segment.start_time,
+                            segment.end_time,
+                            file.to_path_buf(),
+                        );
+                        detection.metadata.channel = segment.channel;
+                        detection
+                    },
+                    Clone::clone,
+                );
+            detection.scientific_name = prediction.scientific_name;
+            detection.common_name = prediction.common_name;
+            detection.confidence = prediction.confidence;
+            detection.metadata.annotation = prediction.annotation;
+            detection
+                .metadata
+                .fingerprint
+                .clone_from(&segment.fingerprint);
+            detections.push(detection);
+        }
+    }
+
+    detections.sort_unstable_by(|a, b| {
+        a.start_time
+            .partial_cmp(&b.start_time)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| {
+                b.confidence
+                    .partial_cmp(&a.confidence)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+    });
+    Ok(detections)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_by_segment() {
+        let detections = vec![
+            Detection::test("Parus major_Great Tit", 0.0, "test.wav").with_confidence(0.9),
+            Detection::test("Turdus merula_Eurasian Blackbird", 0.0, "test.wav")
+                .with_confidence(0.4),
+            Detection::test("Parus major_Great Tit", 3.0, "test.wav").with_confidence(0.8),
+        ];
+
+        let segments = group_by_segment(&detections);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].predictions.len(), 2);
+        assert_eq!(segments[1].predictions[0].scientific_name, "Parus major");
+    }
+
+    #[test]
+    fn test_segments_keep_their_channel() {
+        let mut left = Detection::test("Parus major_Great Tit", 0.0, "test.wav");
+        left.metadata.channel = Some(1);
+        left.metadata.model = Some("birdnet".to_string());
+        let mut right = Detection::test("Turdus merula_Eurasian Blackbird", 0.0, "test.wav");
+        right.metadata.channel = Some(2);
+        let detections = vec![left, right];
+
+        let segments = group_by_segment(&detections);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].channel, Some(1));
+        assert_eq!(segments[1].channel, Some(2));
+
+        let result = ungroup_segments(Path::new("test.wav"), &detections, segments, 0.1).unwrap();
+        let great_tit = result
+            .iter()
+            .find(|d| d.scientific_name == "Parus major")
+            .unwrap();
+        assert_eq!(great_tit.metadata.channel, Some(1));
+        assert_eq!(great_tit.metadata.model.as_deref(), Some("birdnet"));
+        let blackbird = result
+            .iter()
+            .find(|d| d.scientific_name == "Turdus merula")
+            .unwrap();
+        assert_eq!(blackbird.metadata.channel, Some(2));
+    }
+
+    #[test]
+    fn test_ungroup_applies_threshold_and_annotation() {
+        let segments = vec![HookSegment {
+            start_time: 0.0,
+            end_time: 3.0,
+            channel: None,
+            fingerprint: None,
+            predictions: vec![
+                HookPrediction {
+                    scientific_name: "Parus major".to_string(),
+                    common_name: "Great Tit".to_string(),
+                    confidence: 0.95,
+                    annotation: Some("verified".to_string()),
+                },
+                HookPrediction {
+                    scientific_name: "Turdus merula".to_string(),
+                    common_name: "Eurasian Blackbird".to_string(),
+                    confidence: 0.05,
+                    annotation: None,
+                },
+            ],
+        }];
+
+        let detections = ungroup_segments(Path::new("test.wav"), &[], segments, 0.1).unwrap();
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].common_name, "Great Tit");
+        assert_eq!(
+            detections[0].metadata.annotation.as_deref(),
+            Some("verified")
+        );
+    }
+
+    #[test]
+    fn test_ungroup_rejects_invalid_confidence() {
+        let segments = vec![HookSegment {
+            start_time: 0.0,
+            end_time: 3.0,
+            channel: None,
+            fingerprint: None,
+            predictions: vec![HookPrediction {
+                scientific_name: "Parus major".to_string(),
+                common_name: "Great Tit".to_string(),
+                confidence: 1.5,
+                annotation: None,
+            }],
+        }];
+
+        assert!(ungroup_segments(Path::new("test.wav"), &[], segments, 0.1).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_identity_hook_roundtrip() {
+        let hook = PostprocessHook::new("cat");
+        let detections = vec![
+            Detection::test("Parus major_Great Tit", 0.0, "test.wav").with_confidence(0.9),
+            Detection::test("Turdus merula_Eurasian Blackbird", 3.0, "test.wav")
+                .with_confidence(0.4),
+        ];
+
+        let result = hook.apply(Path::new("test.wav"), detections, 0.1).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[1].scientific_name, "Turdus merula");
+    }
+
+    #[test]
+    fn test_missing_hook_program_fails() {
+        let hook = PostprocessHook::new("/nonexistent/birda-hook");
+        let result = hook.apply(Path::new("test.wav"), Vec::new(), 0.1);
+        assert!(matches!(result, Err(Error::PostprocessHook { .. })));
+    }
+}
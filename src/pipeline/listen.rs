@@ -0,0 +1,145 @@
+//! Live analysis of a sound device for `birda listen`.
+//!
+//! Captured audio is cut into rolling windows of the model's segment length,
+//! resampled to the model's rate and classified as soon as each window is
+//! complete. Detection times are seconds since listening started.
+
+use crate::error::Result;
+use crate::inference::BirdClassifier;
+use crate::output::Detection;
+use std::path::Path;
+
+/// Names of the available input devices.
+#[cfg(feature = "listen")]
+pub fn input_devices() -> Result<Vec<String>> {
+    crate::audio::list_input_devices()
+}
+
+/// Names of the available input devices.
+#[cfg(not(feature = "listen"))]
+pub fn input_devices() -> Result<Vec<String>> {
+    Err(crate::error::Error::ListenUnavailable)
+}
+
+/// Classify live audio from `device` (or the default input device) until the
+/// stream ends or the process is interrupted.
+///
+/// `on_start` receives the device name and capture rate once the stream is
+/// open; `on_detections` receives the detections of every window that has any.
+#[cfg(feature = "listen")]
+pub fn listen(
+    classifier: &BirdClassifier,
+    device: Option<&str>,
+    overlap: f32,
+    min_confidence: f32,
+    on_start: impl FnOnce(&str, u32),
+    mut on_detections: impl FnMut(&[Detection]),
+) -> Result<()> {
+    use crate::audio::{LiveInput, RollingWindow};
+    use std::path::PathBuf;
+    use tracing::warn;
+
+    let input = LiveInput::open(device)?;
+    let source_rate = input.sample_rate();
+    let (window, hop) = window_samples(classifier.segment_duration(), overlap, source_rate);
+    let mut rolling = RollingWindow::new(window, hop);
+    let source = PathBuf::from(input.name());
+    on_start(input.name(), source_rate);
+
+    while let Some(buffer) = input.recv() {
+        let dropped = input.take_dropped();
+        if dropped > 0 {
+            warn!("Analysis fell behind; dropped {dropped} audio buffer(s)");
+        }
+
+        for (start_sample, samples) in rolling.push(&buffer) {
+            #[allow(clippy::cast_precision_loss)]
+            let start_time = start_sample as f32 / source_rate as f32;
+            let detections = classify_segment(
+                classifier,
+                samples,
+                source_rate,
+                start_time,
+                min_confidence,
+                &source,
+            )?;
+            if !detections.is_empty() {
+                on_detections(&detections);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Classify live audio; unavailable in this build.
+#[cfg(not(feature = "listen"))]
+pub fn listen(
+    _classifier: &BirdClassifier,
+    _device: Option<&str>,
+    _overlap: f32,
+    _min_confidence: f32,
+    _on_start: impl FnOnce(&str, u32),
+    _on_detections: impl FnMut(&[Detection]),
+) -> Result<()> {
+    Err(crate::error::Error::ListenUnavailable)
+}
+
+/// Classify one segment captured at `source_rate` that starts `start_time`
+/// seconds into the stream.
+pub(super) fn classify_segment(
+    classifier: &BirdClassifier,
+    samples: Vec<f32>,
+    source_rate: u32,
+    start_time: f32,
+    min_confidence: f32,
+    source: &Path,
+) -> Result<Vec<Detection>> {
+    use crate::audio::resample_chunk;
+    use birdnet_onnx::InferenceOptions;
+
+    let mut samples = resample_chunk(samples, source_rate, classifier.sample_rate())?;
+    samples.resize(classifier.sample_count(), 0.0);
+    let result = classifier.predict(&samples, &InferenceOptions::default())?;
+    let results = classifier.apply_range_filter(vec![result])?;
+
+    let end_time = start_time + classifier.segment_duration();
+    Ok(results
+        .iter()
+        .flat_map(|r| &r.predictions)
+        .filter(|p| p.confidence >= min_confidence)
+        .map(|p| {
+            Detection::from_label(
+                &p.species,
+                p.confidence,
+                start_time,
+                end_time,
+                source.to_path_buf(),
+            )
+        })
+        .collect())
+}
+
+/// Window and hop length in samples at `sample_rate` for segments of
+/// `segment_duration` seconds overlapping by `overlap` seconds.
+#[cfg_attr(not(feature = "listen"), allow(dead_code))]
+fn window_samples(segment_duration: f32, overlap: f32, sample_rate: u32) -> (usize, usize) {
+    let rate = f64::from(sample_rate);
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let window = (f64::from(segment_duration) * rate).round() as usize;
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let hop = (f64::from(segment_duration - overlap) * rate).round() as usize;
+    (window, hop.clamp(1, window.max(1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_samples() {
+        assert_eq!(window_samples(3.0, 0.0, 48_000), (144_000, 144_000));
+        assert_eq!(window_samples(3.0, 1.5, 44_100), (132_300, 66_150));
+        // An overlap of the whole segment still advances
+        assert_eq!(window_samples(3.0, 3.0, 16_000), (48_000, 1));
+    }
+}
@@ -0,0 +1,103 @@
+//! Merging of overlapping detections into events.
+//!
+//! With segment overlap the same call is detected in several overlapping
+//! segments, one row each. Merging joins consecutive detections of a species
+//! whose time ranges overlap or touch into one event spanning all of them,
+//! with the highest confidence. Detections from different channels are
+//! never merged. The event keeps the other fields (metadata,
+//! fingerprint) of its most confident detection.
+
+use crate::output::Detection;
+use std::collections::HashMap;
+
+/// Merge overlapping or adjacent detections of the same species.
+///
+/// Returns events sorted by start time, then by confidence (descending).
+pub fn merge_overlapping(detections: Vec<Detection>) -> Vec<Detection> {
+    let mut by_species: HashMap<(String, Option<u32>), Vec<Detection>> = HashMap::new();
+    for detection in detections {
+        by_species
+            .entry((
+                detection.scientific_name.clone(),
+                detection.metadata.channel,
+            ))
+            .or_default()
+            .push(detection);
+    }
+
+    let mut events = Vec::new();
+    for mut species in by_species.into_values() {
+        species.sort_unstable_by(|a, b| a.start_time.total_cmp(&b.start_time));
+        let mut species = species.into_iter();
+        let Some(mut event) = species.next() else {
+            continue;
+        };
+        for detection in species {
+            if detection.start_time <= event.end_time {
+                let (start, end) = (event.start_time, event.end_time.max(detection.end_time));
+                if detection.confidence > event.confidence {
+                    event = detection;
+                }
+                event.start_time = start;
+                event.end_time = end;
+            } else {
+                events.push(std::mem::replace(&mut event, detection));
+            }
+        }
+        events.push(event);
+    }
+
+    events.sort_unstable_by(|a, b| {
+        a.start_time
+            .total_cmp(&b.start_time)
+            .then_with(|| b.confidence.total_cmp(&a.confidence))
+    });
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merges_overlapping_detections_of_a_species() {
+        let events = merge_overlapping(vec![
+            Detection::test("Parus major_Great Tit", 0.0, "a.wav").with_confidence(0.4),
+            Detection::test("Parus major_Great Tit", 1.5, "a.wav").with_confidence(0.9),
+            Detection::test("Turdus merula_Eurasian Blackbird", 1.5, "a.wav").with_confidence(0.6),
+            Detection::test("Parus major_Great Tit", 3.0, "a.wav").with_confidence(0.5),
+            Detection::test("Parus major_Great Tit", 7.5, "a.wav").with_confidence(0.3),
+        ]);
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].common_name, "Great Tit");
+        assert!((events[0].start_time - 0.0).abs() < f32::EPSILON);
+        assert!((events[0].end_time - 6.0).abs() < f32::EPSILON);
+        assert!((events[0].confidence - 0.9).abs() < f32::EPSILON);
+        assert_eq!(events[1].common_name, "Eurasian Blackbird");
+        assert!((events[2].start_time - 7.5).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_keeps_separate_calls_apart() {
+        let events = merge_overlapping(vec![
+            Detection::test("Parus major_Great Tit", 0.0, "a.wav").with_confidence(0.4),
+            Detection::test("Parus major_Great Tit", 3.5, "a.wav").with_confidence(0.5),
+        ]);
+        assert_eq!(events.len(), 2);
+        assert!(merge_overlapping(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn test_keeps_channels_apart() {
+        let mut right = Detection::test("Parus major_Great Tit", 1.5, "a.wav").with_confidence(0.9);
+        right.metadata.channel = Some(2);
+        let mut left = Detection::test("Parus major_Great Tit", 0.0, "a.wav").with_confidence(0.4);
+        left.metadata.channel = Some(1);
+
+        let events = merge_overlapping(vec![left, right]);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].metadata.channel, Some(1));
+        assert_eq!(events[1].metadata.channel, Some(2));
+    }
+}
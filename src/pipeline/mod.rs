@@ -1,12 +1,40 @@
 //! Processing pipeline components.
 
+mod bench;
+mod calibrate;
+mod classify;
 mod config;
 mod coordinator;
+mod dead_letter;
+mod embed;
+mod fault;
+mod hook;
+mod listen;
+mod merge;
 mod processor;
+mod selftest;
+mod stitch;
+mod stream;
+mod watch;
+mod zoom;
 
+pub use bench::{compare_to_baseline, load_baseline, run_bench, save_baseline};
+pub use calibrate::{calibrate_overlaps, sample_files};
+pub use classify::{classify_chunks, for_each_batch};
 pub use config::ProcessingConfig;
 pub use coordinator::{
-    ProcessCheck, ProcessOptions, collect_input_files, output_dir_for, output_path_for,
-    should_process,
+    FileFilter, ProcessCheck, ProcessOptions, activity_path_for, collect_input_files,
+    combined_output_dir, embeddings_path_for, manifest_path_for, output_dir_for, output_path_for,
+    random_sample, read_file_list, should_process,
 };
+pub use dead_letter::DeadLetter;
+pub use embed::extract_embeddings;
+pub use fault::{FaultInjector, FaultPoint, FaultSpec};
+pub use hook::PostprocessHook;
+pub use listen::{input_devices, listen};
+pub use merge::merge_overlapping;
 pub use processor::{ProcessResult, process_file};
+pub use selftest::run_selftest;
+pub use stitch::{Stitch, plan_stitching};
+pub use stream::analyze_stream;
+pub use watch::DirectoryWatcher;
@@ -1,16 +1,19 @@
 //! Single file processing pipeline.
 
-use crate::audio::AudioChunk;
+use crate::audio::{AudioChunk, FilterSpec, TimeRange};
 use crate::config::OutputFormat;
 use crate::error::Result;
+use crate::gpu::WatchdogPolicy;
 use crate::inference::{BatchInferenceContext, BirdClassifier, InferenceOptions};
 use crate::locking::FileLock;
 use crate::output::{
-    AudacityWriter, CsvWriter, Detection, JsonResultWriter, KaleidoscopeWriter, OutputWriter,
-    ParquetWriter, RavenWriter,
+    ActivityTracker, AudacityWriter, BirdnetCsvWriter, CsvWriter, Detection, ErrorSeverity,
+    JsonResultWriter, KaleidoscopeWriter, NumberFormat, OutputWriter, ParquetWriter, RavenWriter,
+    RollingWriter, SplitLimits,
 };
-use crate::pipeline::output_path_for;
-use birdnet_onnx::CustomClassifier;
+use crate::pipeline::fault::{self, FaultInjector, FaultPoint};
+use crate::pipeline::{Stitch, activity_path_for, manifest_path_for, output_path_for};
+use birdnet_onnx::{CustomClassifier, LocationScore};
 use std::path::Path;
 use std::sync::mpsc::{Receiver, SyncSender, sync_channel};
 use std::thread::{self, JoinHandle};
@@ -19,15 +22,97 @@ use tracing::{debug, info};
 /// Result type for chunks sent through the decode channel.
 type ChunkResult = std::result::Result<AudioChunk, crate::error::Error>;
 
+/// Issues encountered by the decode thread that do not abort processing.
+#[derive(Debug, Clone, Copy, Default)]
+struct DecodeStats {
+    /// Corrupt packets skipped by the decoder.
+    skipped_packets: usize,
+    /// Segments whose resampled length was off by more than the tolerance.
+    resample_mismatches: usize,
+}
+
+/// Outcome of streaming inference over one file.
+struct InferenceOutcome {
+    /// Detections sorted by start time, then confidence.
+    detections: Vec<Detection>,
+    /// Total segments processed.
+    segment_count: usize,
+    /// Batch size in effect at the end (lower if GPU memory ran out).
+    batch_size: usize,
+    /// Silent segments added to fill partial batches.
+    padded_segments: usize,
+}
+
+/// Buffers reused by every batch of a file.
+///
+/// Keeps the silence used to pad partial batches, the slice list handed to
+/// the classifier and the bat mode embeddings list alive between batches,
+/// so long recordings do not allocate them again for every batch.
+#[derive(Default)]
+struct BatchScratch {
+    /// One segment of silence, allocated on the first partial batch.
+    padding: Vec<f32>,
+    /// Allocation for the per-batch list of segment slices.
+    segments: Vec<&'static [f32]>,
+    /// Backbone embeddings passed to the custom classifier.
+    embeddings: Vec<Vec<f32>>,
+}
+
+/// Empty `segments` and reuse its allocation for slices with another lifetime.
+fn recycle_segments<'a>(mut segments: Vec<&[f32]>) -> Vec<&'a [f32]> {
+    segments.clear();
+    // Collecting into an element type of the same layout reuses the buffer;
+    // the vector is empty, so no element is kept
+    segments.into_iter().filter_map(|_| None).collect()
+}
+
+/// Segment and overlap length in samples at `rate`.
+pub(super) fn segment_lengths(segment_duration: f32, overlap: f32, rate: u32) -> (usize, usize) {
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    let seg = (segment_duration * rate as f32) as usize;
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    let ovl = (overlap * rate as f32) as usize;
+    (seg, ovl)
+}
+
+/// Convert a length in samples at `target_rate` to the source rate, rounding up.
+pub(super) fn source_samples(samples: usize, source_rate: u32, target_rate: u32) -> usize {
+    if source_rate == target_rate {
+        return samples;
+    }
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    let source =
+        ((samples as f64) * f64::from(source_rate) / f64::from(target_rate)).ceil() as usize;
+    source
+}
+
 /// Spawn a thread that decodes audio and sends chunks through the channel.
+#[allow(clippy::too_many_arguments)]
 fn spawn_decode_thread(
     path: std::path::PathBuf,
     source_rate: u32,
     target_rate: u32,
     segment_samples: usize,
     overlap_samples: usize,
+    time_range: Option<TimeRange>,
+    stitch: Option<Stitch>,
+    channel: Option<u32>,
+    filter: Option<FilterSpec>,
+    fault: Option<FaultInjector>,
     tx: SyncSender<ChunkResult>,
-) -> JoinHandle<()> {
+) -> JoinHandle<DecodeStats> {
     thread::spawn(move || {
         let result = decode_and_stream(
             &path,
@@ -35,55 +120,100 @@ fn spawn_decode_thread(
             target_rate,
             segment_samples,
             overlap_samples,
+            time_range,
+            stitch.as_ref(),
+            channel,
+            filter,
+            fault.as_ref(),
             &tx,
         );
-        if let Err(e) = result {
-            // Send error through channel, ignore if receiver dropped
-            let _ = tx.send(Err(e));
+        match result {
+            Ok(stats) => stats,
+            Err(e) => {
+                // Send error through channel, ignore if receiver dropped
+                let _ = tx.send(Err(e));
+                DecodeStats::default()
+            }
         }
         // tx drops here, closing channel
     })
 }
 
 /// Decode audio file and stream chunks through the channel.
+///
+/// With a time range, decoding starts at the range start and stops at the
+/// first segment starting at or after its end; the last segment may extend
+/// past the end.
+///
+/// A stitched recording starts where the previous recording's last segment
+/// left off, and its own last segment is completed with audio from the next
+/// recording; segments starting in the next recording are left to it.
+///
+/// With a channel (1-based), only that channel is decoded instead of the
+/// mono mix. A pre-filter is applied to the decoded audio before it is
+/// split into segments.
+#[allow(clippy::too_many_arguments)]
 fn decode_and_stream(
     path: &Path,
     source_rate: u32,
     target_rate: u32,
     segment_samples: usize,
     overlap_samples: usize,
+    time_range: Option<TimeRange>,
+    stitch: Option<&Stitch>,
+    channel: Option<u32>,
+    filter: Option<FilterSpec>,
+    fault: Option<&FaultInjector>,
     tx: &SyncSender<ChunkResult>,
-) -> Result<()> {
+) -> Result<DecodeStats> {
     use crate::audio::{StreamingDecoder, resample_chunk};
+    use crate::constants::RESAMPLE_LENGTH_TOLERANCE;
 
     let mut decoder = StreamingDecoder::open(path)?;
+    if let Some(channel) = channel {
+        decoder.select_channel(channel)?;
+    }
+    if let Some(filter) = filter {
+        decoder.set_filter(filter);
+    }
+    let end_sample = time_range.and_then(|range| range.end_sample(source_rate));
+    if let Some(range) = time_range {
+        decoder.seek(range.start)?;
+    }
+    if let Some(stitch) = stitch {
+        decoder.skip_to(stitch.start_sample)?;
+        if let Some(next) = &stitch.next {
+            decoder.continue_with(StreamingDecoder::open(next)?)?;
+        }
+    }
 
     // Calculate source segment size based on rate ratio
-    #[allow(
-        clippy::cast_possible_truncation,
-        clippy::cast_sign_loss,
-        clippy::cast_precision_loss
-    )]
-    let source_segment_samples = if source_rate == target_rate {
-        segment_samples
-    } else {
-        ((segment_samples as f64) * f64::from(source_rate) / f64::from(target_rate)).ceil() as usize
-    };
+    let source_segment_samples = source_samples(segment_samples, source_rate, target_rate);
+    let source_overlap_samples = source_samples(overlap_samples, source_rate, target_rate);
 
     #[allow(
         clippy::cast_possible_truncation,
         clippy::cast_sign_loss,
         clippy::cast_precision_loss
     )]
-    let source_overlap_samples = if source_rate == target_rate {
-        overlap_samples
-    } else {
-        ((overlap_samples as f64) * f64::from(source_rate) / f64::from(target_rate)).ceil() as usize
-    };
+    let length_tolerance = ((segment_samples as f64) * RESAMPLE_LENGTH_TOLERANCE).ceil() as usize;
+    let mut stats = DecodeStats::default();
 
     while let Some(raw) = decoder.next_segment(source_segment_samples, source_overlap_samples)? {
+        if end_sample.is_some_and(|end| raw.start_sample >= end)
+            || decoder
+                .continuation_start()
+                .is_some_and(|boundary| raw.start_sample >= boundary)
+        {
+            break;
+        }
+        fault::inject(fault, FaultPoint::Decode, path)?;
+
         // Resample to target rate and ensure exact segment length
         let mut samples = resample_chunk(raw.samples, source_rate, target_rate)?;
+        if samples.len().abs_diff(segment_samples) > length_tolerance {
+            stats.resample_mismatches += 1;
+        }
         samples.resize(segment_samples, 0.0);
 
         // Calculate time offsets from decoder position (more accurate than index-based)
@@ -93,8 +223,10 @@ fn decode_and_stream(
         let segment_duration = segment_samples as f32 / target_rate as f32;
         let end_time = start_time + segment_duration;
 
+        // Each streamed segment is resampled on its own, so it keeps its own
+        // buffer; wrapping it moves the buffer without copying
         let chunk = AudioChunk {
-            samples,
+            samples: samples.into(),
             start_time,
             end_time,
         };
@@ -104,12 +236,25 @@ fn decode_and_stream(
             .map_err(|_| crate::error::Error::DecodeChannelClosed)?;
     }
 
-    Ok(())
+    stats.skipped_packets = decoder.skipped_packets();
+    Ok(stats)
+}
+
+/// Record the analyzed channel on each detection.
+fn tag_channel(detections: Vec<Detection>, channel: Option<u32>) -> Vec<Detection> {
+    detections
+        .into_iter()
+        .map(|mut detection| {
+            detection.metadata.channel = channel;
+            detection
+        })
+        .collect()
 }
 
 /// Run inference on chunks received from the decode channel.
 ///
-/// Returns detections and the total segment count processed.
+/// When an activity tracker is provided, every segment's energy is recorded
+/// before inference.
 #[allow(clippy::too_many_arguments)]
 fn run_streaming_inference(
     rx: Receiver<ChunkResult>,
@@ -122,20 +267,31 @@ fn run_streaming_inference(
     reporter: Option<&dyn crate::output::ProgressReporter>,
     estimated_segments: usize,
     bsg_params: Option<(f64, f64, Option<u32>)>,
+    location_scores: Option<&[LocationScore]>,
     custom_classifier: Option<&CustomClassifier>,
-) -> Result<(Vec<Detection>, usize)> {
+    mut activity: Option<&mut ActivityTracker>,
+    watchdog: &WatchdogPolicy,
+    fingerprint: bool,
+    fault: Option<&FaultInjector>,
+) -> Result<InferenceOutcome> {
     let mut detections = Vec::new();
     let mut batch: Vec<AudioChunk> = Vec::with_capacity(batch_size);
     let mut segment_count = 0usize;
     let mut segments_done = 0usize;
+    let mut padded_segments = 0usize;
+    let mut batch_size = batch_size;
+    let mut scratch = BatchScratch::default();
 
     for item in rx {
         let chunk = item?; // Propagate decode errors
+        if let Some(tracker) = activity.as_deref_mut() {
+            tracker.record_segment(chunk.start_time, &chunk.samples);
+        }
         batch.push(chunk);
         segment_count += 1;
 
         if batch.len() >= batch_size {
-            process_batch(
+            padded_segments += process_batch_with_backoff(
                 &batch,
                 classifier,
                 file_path,
@@ -143,12 +299,17 @@ fn run_streaming_inference(
                 &mut detections,
                 progress,
                 batch_context,
-                batch_size,
+                &mut batch_size,
                 reporter,
                 &mut segments_done,
                 estimated_segments,
                 bsg_params,
+                location_scores,
                 custom_classifier,
+                watchdog,
+                fingerprint,
+                fault,
+                &mut scratch,
             )?;
             batch.clear();
         }
@@ -156,7 +317,7 @@ fn run_streaming_inference(
 
     // Process remaining partial batch (padding handled inside process_batch)
     if !batch.is_empty() {
-        process_batch(
+        padded_segments += process_batch_with_backoff(
             &batch,
             classifier,
             file_path,
@@ -164,12 +325,17 @@ fn run_streaming_inference(
             &mut detections,
             progress,
             batch_context,
-            batch_size, // Target size for TensorRT alignment
+            &mut batch_size, // Target size for TensorRT alignment
             reporter,
             &mut segments_done,
             estimated_segments,
             bsg_params,
+            location_scores,
             custom_classifier,
+            watchdog,
+            fingerprint,
+            fault,
+            &mut scratch,
         )?;
     }
 
@@ -186,28 +352,105 @@ fn run_streaming_inference(
             })
     });
 
-    Ok((detections, segment_count))
+    Ok(InferenceOutcome {
+        detections,
+        segment_count,
+        batch_size,
+        padded_segments,
+    })
 }
 
-/// Default watchdog timeout for inference operations (in seconds).
-/// Can be overridden via `BIRDA_INFERENCE_TIMEOUT` environment variable.
-const DEFAULT_INFERENCE_WATCHDOG_SECS: u64 = 10;
+/// Process chunks in batches, halving the batch size on GPU out-of-memory errors.
+///
+/// Inference runs before any detections are recorded, so a failed batch can be
+/// retried in smaller pieces without producing duplicates. The reduced size is
+/// written back to `batch_size` and the batch context is rebuilt to match.
+///
+/// Returns the number of padding segments added to partial batches.
+#[allow(clippy::too_many_arguments)]
+fn process_batch_with_backoff(
+    chunks: &[AudioChunk],
+    classifier: &BirdClassifier,
+    file_path: &Path,
+    min_confidence: f32,
+    detections: &mut Vec<Detection>,
+    progress: Option<&indicatif::ProgressBar>,
+    batch_context: &mut Option<BatchInferenceContext>,
+    batch_size: &mut usize,
+    reporter: Option<&dyn crate::output::ProgressReporter>,
+    segments_done: &mut usize,
+    estimated_segments: usize,
+    bsg_params: Option<(f64, f64, Option<u32>)>,
+    location_scores: Option<&[LocationScore]>,
+    custom_classifier: Option<&CustomClassifier>,
+    watchdog: &WatchdogPolicy,
+    fingerprint: bool,
+    fault: Option<&FaultInjector>,
+    scratch: &mut BatchScratch,
+) -> Result<usize> {
+    use crate::gpu::{is_out_of_memory, reduced_batch_size};
+
+    let mut remaining = chunks;
+    let mut padded = 0;
+    while !remaining.is_empty() {
+        let (head, tail) = remaining.split_at(remaining.len().min(*batch_size));
+
+        match process_batch(
+            head,
+            classifier,
+            file_path,
+            min_confidence,
+            detections,
+            progress,
+            batch_context,
+            *batch_size,
+            reporter,
+            segments_done,
+            estimated_segments,
+            bsg_params,
+            location_scores,
+            custom_classifier,
+            watchdog,
+            fingerprint,
+            fault,
+            scratch,
+        ) {
+            Ok(padding) => {
+                padded += padding;
+                remaining = tail;
+            }
+            Err(e) if is_out_of_memory(&e) => {
+                let Some(reduced) = reduced_batch_size(*batch_size) else {
+                    return Err(e);
+                };
+                tracing::warn!(
+                    "GPU out of memory with batch size {}, retrying with batch size {}",
+                    *batch_size,
+                    reduced
+                );
+                *batch_size = reduced;
+                // Release the old context before allocating a smaller one
+                *batch_context = None;
+                if reduced > 1 {
+                    *batch_context = classifier.create_batch_context(reduced).ok();
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    }
 
-/// Minimum and maximum allowed watchdog timeout values.
-const MIN_WATCHDOG_SECS: u64 = 1;
-const MAX_WATCHDOG_SECS: u64 = 3600;
+    Ok(padded)
+}
 
-/// Get the inference watchdog timeout from environment or use default.
-///
-/// Override with `BIRDA_INFERENCE_TIMEOUT=<seconds>` for different hardware.
-/// Normal inference is ~74ms per batch, so 10s default is generous while catching hangs.
-/// Valid range: 1-3600 seconds. Invalid values use default.
-fn inference_watchdog_timeout() -> u64 {
-    std::env::var("BIRDA_INFERENCE_TIMEOUT")
-        .ok()
-        .and_then(|v| v.parse::<u64>().ok())
-        .filter(|&v| (MIN_WATCHDOG_SECS..=MAX_WATCHDOG_SECS).contains(&v))
-        .unwrap_or(DEFAULT_INFERENCE_WATCHDOG_SECS)
+/// Log a pipeline warning and forward it to the reporter as a warning event.
+fn report_warning(
+    reporter: &dyn crate::output::ProgressReporter,
+    code: &str,
+    message: &str,
+    suggestion: Option<&str>,
+) {
+    tracing::warn!("{}", message);
+    reporter.error(code, ErrorSeverity::Warning, message, suggestion);
 }
 
 /// Process a batch of chunks through the classifier.
@@ -216,6 +459,9 @@ fn inference_watchdog_timeout() -> u64 {
 ///
 /// * `target_batch_size` - Target batch size for `TensorRT` alignment (pads with silence if needed)
 /// * `bsg_params` - Optional (lat, lon, `day_of_year`) for BSG SDM, `day_of_year=None` for auto-detect
+/// * `location_scores` - Range filter scores of the recording's location, when the filter is located per file
+///
+/// Returns the number of padding segments added to reach `target_batch_size`.
 #[allow(clippy::too_many_arguments)]
 fn process_batch(
     batch: &[AudioChunk],
@@ -230,20 +476,26 @@ fn process_batch(
     segments_done: &mut usize,
     estimated_segments: usize,
     bsg_params: Option<(f64, f64, Option<u32>)>,
+    location_scores: Option<&[LocationScore]>,
     custom_classifier: Option<&CustomClassifier>,
-) -> Result<()> {
+    watchdog: &WatchdogPolicy,
+    fingerprint: bool,
+    fault: Option<&FaultInjector>,
+    scratch: &mut BatchScratch,
+) -> Result<usize> {
     use crate::gpu::start_inference_watchdog;
     use crate::output::progress::inc_progress;
-    use std::time::Duration;
 
     let valid_count = batch.len();
-    let mut segments: Vec<&[f32]> = batch.iter().map(|c| c.samples.as_slice()).collect();
+    let mut segments = recycle_segments(std::mem::take(&mut scratch.segments));
+    segments.extend(batch.iter().map(|c| c.samples.as_slice()));
 
-    // Pad segments with silence for TensorRT batch size alignment (single allocation, no cloning)
-    let padding_buffer: Vec<f32>;
+    // Pad segments with silence for TensorRT batch size alignment (one buffer per file, no cloning)
     if valid_count < target_batch_size {
         let sample_count = classifier.sample_count();
-        padding_buffer = vec![0.0f32; sample_count];
+        if scratch.padding.len() != sample_count {
+            scratch.padding = vec![0.0f32; sample_count];
+        }
         let padding_needed = target_batch_size - valid_count;
         tracing::debug!(
             "Padding partial batch: {} → {} segments ({} padding)",
@@ -252,18 +504,17 @@ fn process_batch(
             padding_needed
         );
         segments.extend(std::iter::repeat_n(
-            padding_buffer.as_slice(),
+            scratch.padding.as_slice(),
             padding_needed,
         ));
     }
 
     let batch_size = segments.len();
+    fault::inject(fault, FaultPoint::Timeout, file_path)?;
+    fault::inject(fault, FaultPoint::OutOfMemory, file_path)?;
 
     // Start watchdog timer - kills process if inference hangs
-    let _watchdog = start_inference_watchdog(
-        Duration::from_secs(inference_watchdog_timeout()),
-        batch_size,
-    );
+    let _watchdog = start_inference_watchdog(watchdog, batch_size, *segments_done == 0);
 
     let options = InferenceOptions::default();
     let mut results = if batch_size == 1 {
@@ -275,6 +526,7 @@ fn process_batch(
         // Fallback for PerchV2 or when context not available
         classifier.predict_batch(&segments, &options)?
     };
+    scratch.segments = recycle_segments(segments);
 
     // Watchdog is automatically cancelled when _watchdog drops here
 
@@ -314,7 +566,7 @@ fn process_batch(
     }
 
     // Apply range filtering if configured (skipped for BSG models)
-    let mut results = classifier.apply_range_filter(results)?;
+    let mut results = classifier.apply_range_filter_at(results, location_scores)?;
 
     // Two-stage inference: if a custom classifier is present (bat mode),
     // extract embeddings from the backbone and classify them with the
@@ -322,23 +574,25 @@ fn process_batch(
     // backbone predictions for detection extraction.
     let bat_predictions: Option<Vec<Vec<birdnet_onnx::Prediction>>> =
         if let Some(cc) = custom_classifier {
-            let embeddings_batch: Vec<Vec<f32>> = results
-                .iter_mut()
-                .take(valid_count)
-                .filter_map(|r| r.embeddings.take())
-                .collect();
+            scratch.embeddings.clear();
+            scratch.embeddings.extend(
+                results
+                    .iter_mut()
+                    .take(valid_count)
+                    .filter_map(|r| r.embeddings.take()),
+            );
 
-            if embeddings_batch.len() != valid_count {
+            if scratch.embeddings.len() != valid_count {
                 return Err(crate::error::Error::Inference {
                     reason: format!(
                         "bat mode requires embeddings from backbone, but got {} of {} segments",
-                        embeddings_batch.len(),
+                        scratch.embeddings.len(),
                         valid_count
                     ),
                 });
             }
 
-            let batch_preds = cc.predict_batch(&embeddings_batch).map_err(|e| {
+            let batch_preds = cc.predict_batch(&scratch.embeddings).map_err(|e| {
                 crate::error::Error::Inference {
                     reason: format!("bat custom classifier failed: {e}"),
                 }
@@ -371,15 +625,26 @@ fn process_batch(
             .as_ref()
             .map_or(&result.predictions, |bp| &bp[i]);
 
+        // Fingerprint is computed once per segment, only if something was detected
+        let mut segment_fingerprint: Option<String> = None;
         for pred in preds {
             if pred.confidence >= min_confidence {
-                let detection = Detection::from_label(
+                let mut detection = Detection::from_label(
                     &pred.species,
                     pred.confidence,
                     chunk.start_time,
                     chunk.end_time,
                     file_path.to_path_buf(),
                 );
+                if fingerprint {
+                    detection.metadata.fingerprint = Some(
+                        segment_fingerprint
+                            .get_or_insert_with(|| {
+                                crate::audio::segment_fingerprint(&chunk.samples)
+                            })
+                            .clone(),
+                    );
+                }
                 detections.push(detection);
             }
         }
@@ -406,7 +671,7 @@ fn process_batch(
         }
     }
 
-    Ok(())
+    Ok(target_batch_size.saturating_sub(valid_count))
 }
 
 /// Process a single audio file and write detection results.
@@ -437,17 +702,26 @@ pub fn process_file(
     let bsg_params = config.bsg_params;
     let reporter = config.reporter;
     let dual_output_mode = config.dual_output_mode;
+    let stdout_format = config.stdout_format;
     let custom_classifier = config.custom_classifier;
     let bat_mode = config.bat_mode;
+    let activity_format = config.activity_format;
+    let warnings = config.warning_reporter;
 
     let start_time = Instant::now();
 
     info!("Processing: {}", input_path.display());
 
+    // Determine if we should write files (dual output or pure file mode)
+    let should_write_files = stdout_format.is_none() && (dual_output_mode || reporter.is_none());
+
     // Acquire lock when writing files (file mode or dual output mode)
-    let _lock = if reporter.is_none() || dual_output_mode {
+    let _lock = if should_write_files {
         // File mode or dual output mode - need lock to prevent concurrent writes
-        Some(FileLock::acquire(input_path, output_dir)?)
+        Some(FileLock::acquire_at(
+            &FileLock::resolve_lock_path(input_path, output_dir, config.lock_dir),
+            input_path,
+        )?)
     } else {
         // Pure stdout mode - no files written, no lock needed
         None
@@ -456,17 +730,44 @@ pub fn process_file(
     // Open decoder to get metadata
     let decoder = StreamingDecoder::open(input_path)?;
     let source_rate = decoder.sample_rate();
-    let duration_hint = decoder.duration_hint();
+    // Within a time range, only the range's share of the file is analyzed
+    let duration_hint = decoder.duration_hint().map(|duration| {
+        config
+            .time_range
+            .map_or(duration, |r| r.duration_within(duration))
+    });
+    if let Some(range) = config.time_range {
+        info!("Analyzing range {range}");
+    }
+    if let Some(filter) = config.filter {
+        debug!("Pre-filtering: {filter}");
+    }
+    if let Some(next) = config.stitch.and_then(|stitch| stitch.next.as_ref()) {
+        info!("Continuing across the boundary into {}", next.display());
+    }
+    // One pass per analyzed channel, or a single pass over the mono mix
+    let passes = config.channel.passes(decoder.channel_count());
+    match passes.as_slice() {
+        [None] => {}
+        [Some(channel)] => info!("Analyzing channel {channel}"),
+        _ => info!("Analyzing {} channels separately", passes.len()),
+    }
 
     // In bat mode, skip resampling: feed raw samples directly to the model.
     // BirdNET v2.4 expects 144,000 samples; at 256kHz this is 0.5625s of audio,
     // but the model treats them as 48kHz (the "slow-down trick").
     let (target_rate, segment_duration) = if bat_mode {
         if source_rate != crate::constants::bat::SAMPLE_RATE {
-            tracing::warn!(
-                "Bat mode expects {}kHz audio, source is {}kHz. Results may be unreliable.",
-                crate::constants::bat::SAMPLE_RATE / 1000,
-                source_rate / 1000,
+            report_warning(
+                warnings,
+                "sample_rate_mismatch",
+                &format!(
+                    "{}: bat mode expects {}kHz audio, source is {}kHz. Results may be unreliable.",
+                    input_path.display(),
+                    crate::constants::bat::SAMPLE_RATE / 1000,
+                    source_rate / 1000,
+                ),
+                None,
             );
         }
         (source_rate, crate::constants::bat::SEGMENT_DURATION)
@@ -486,7 +787,12 @@ pub fn process_file(
                         Some(d)
                     }
                     Err(e) => {
-                        tracing::warn!("{}, SDM will not be applied", e);
+                        report_warning(
+                            warnings,
+                            "day_of_year_unavailable",
+                            &format!("{e}, SDM will not be applied"),
+                            Some("Pass --day-of-year to enable the species distribution model"),
+                        );
                         None
                     }
                 }
@@ -498,6 +804,12 @@ pub fn process_file(
         None
     };
 
+    // A range filter located per file scores this recording's location once
+    let location_scores = match range_filter_params {
+        Some((lat, lon, _)) => classifier.location_scores(lat, lon)?,
+        None => None,
+    };
+
     // Calculate segment parameters (needed for batch size adjustment and progress bar)
     let (segment_samples, overlap_samples) = if bat_mode {
         // Bat mode: use fixed 144,000 samples and bat-specific overlap.
@@ -506,19 +818,7 @@ pub fn process_file(
         let overlap_samps = crate::constants::bat::CHUNK_SAMPLES / 4;
         (crate::constants::bat::CHUNK_SAMPLES, overlap_samps)
     } else {
-        #[allow(
-            clippy::cast_possible_truncation,
-            clippy::cast_sign_loss,
-            clippy::cast_precision_loss
-        )]
-        let seg = (segment_duration * target_rate as f32) as usize;
-        #[allow(
-            clippy::cast_possible_truncation,
-            clippy::cast_sign_loss,
-            clippy::cast_precision_loss
-        )]
-        let ovl = (overlap * target_rate as f32) as usize;
-        (seg, ovl)
+        segment_lengths(segment_duration, overlap, target_rate)
     };
 
     // Estimate segment count for batch size adjustment and progress bar
@@ -590,6 +890,7 @@ pub fn process_file(
     // Sanitize filename to prevent template injection (curly braces are special in indicatif)
     let safe_name = file_name.replace(['{', '}'], "");
 
+    // Each channel pass is its own run through the estimated segments
     #[allow(clippy::cast_possible_truncation)]
     let segment_progress = estimated_segments.map_or_else(
         || {
@@ -617,48 +918,255 @@ pub fn process_file(
     // Create channel with capacity for 2 batches (backpressure)
     // Use effective_batch_size to match adjusted memory allocation
     let channel_capacity = effective_batch_size.saturating_mul(2).max(4);
-    let (tx, rx) = sync_channel::<ChunkResult>(channel_capacity);
-
-    // Spawn decode thread
-    // Note: We re-open the decoder in the thread since StreamingDecoder
-    // has consumed state. This is a minor overhead but keeps ownership clean.
-    let path_for_thread = input_path.to_path_buf();
-    let decode_handle = spawn_decode_thread(
-        path_for_thread,
-        source_rate,
-        target_rate,
-        segment_samples,
-        overlap_samples,
-        tx,
-    );
 
-    // Run inference on main thread
+    // Activity summary is only written alongside result files
+    let mut activity_tracker = activity_format
+        .filter(|_| should_write_files)
+        .map(|_| ActivityTracker::new(config.activity_threshold_dbfs));
+
     #[allow(clippy::cast_possible_truncation)]
     let estimated_segments_usize = estimated_segments.unwrap_or(0) as usize;
-    let (detections, actual_segments) = run_streaming_inference(
-        rx,
-        classifier,
-        input_path,
-        min_confidence,
-        effective_batch_size,
-        progress_guard.get(),
-        &mut batch_context,
-        reporter,
-        estimated_segments_usize,
-        resolved_bsg_params,
-        custom_classifier,
-    )?;
+    let mut detections = Vec::new();
+    let mut actual_segments = 0;
+    let mut final_batch_size = effective_batch_size;
+    let mut padded_segments = 0;
+    let mut decode_stats = DecodeStats::default();
+    for (pass, &channel) in passes.iter().enumerate() {
+        let (tx, rx) = sync_channel::<ChunkResult>(channel_capacity);
+        if pass > 0
+            && let Some(pb) = progress_guard.get()
+        {
+            pb.reset();
+        }
 
-    // Wait for decode thread to finish
-    // Errors are sent through the channel, so we just wait for cleanup
-    // If the thread panicked, log a warning (panics indicate bugs, but shouldn't crash batch jobs)
-    if let Err(panic_payload) = decode_handle.join() {
-        tracing::warn!("Decode thread panicked: {:?}", panic_payload);
+        // Spawn decode thread
+        // Note: We re-open the decoder in the thread since StreamingDecoder
+        // has consumed state. This is a minor overhead but keeps ownership clean.
+        let decode_handle = spawn_decode_thread(
+            input_path.to_path_buf(),
+            source_rate,
+            target_rate,
+            segment_samples,
+            overlap_samples,
+            config.time_range,
+            config.stitch.cloned(),
+            channel,
+            config.filter,
+            config.fault.cloned(),
+            tx,
+        );
+
+        // Run inference on main thread; activity is measured on the first pass only
+        let outcome = run_streaming_inference(
+            rx,
+            classifier,
+            input_path,
+            min_confidence,
+            final_batch_size,
+            progress_guard.get(),
+            &mut batch_context,
+            reporter,
+            estimated_segments_usize,
+            resolved_bsg_params,
+            location_scores.as_deref(),
+            custom_classifier,
+            activity_tracker.as_mut().filter(|_| pass == 0),
+            config.watchdog,
+            config.fingerprint,
+            config.fault,
+        )?;
+
+        // Wait for decode thread to finish
+        // Errors are sent through the channel, so we just wait for cleanup
+        // If the thread panicked, log a warning (panics indicate bugs, but shouldn't crash batch jobs)
+        match decode_handle.join() {
+            Ok(stats) => {
+                decode_stats.skipped_packets += stats.skipped_packets;
+                decode_stats.resample_mismatches += stats.resample_mismatches;
+            }
+            Err(panic_payload) => {
+                tracing::warn!("Decode thread panicked: {:?}", panic_payload);
+            }
+        }
+
+        actual_segments += outcome.segment_count;
+        final_batch_size = outcome.batch_size;
+        padded_segments += outcome.padded_segments;
+        detections.extend(tag_channel(outcome.detections, channel));
+    }
+    if passes.len() > 1 {
+        detections.sort_unstable_by(|a, b| {
+            a.start_time
+                .total_cmp(&b.start_time)
+                .then_with(|| b.confidence.total_cmp(&a.confidence))
+        });
+    }
+
+    if decode_stats.skipped_packets > 0 {
+        report_warning(
+            warnings,
+            "corrupt_packets_skipped",
+            &format!(
+                "{}: skipped {} corrupt audio packet(s), affected segments contain gaps",
+                input_path.display(),
+                decode_stats.skipped_packets
+            ),
+            None,
+        );
+    }
+    if decode_stats.resample_mismatches > 0 {
+        report_warning(
+            warnings,
+            "resample_length_mismatch",
+            &format!(
+                "{}: {} segment(s) had unexpected length after resampling from {} Hz to {} Hz",
+                input_path.display(),
+                decode_stats.resample_mismatches,
+                source_rate,
+                target_rate
+            ),
+            None,
+        );
+    }
+    // Some padding of the final batch is normal; warn when most of the work was padding
+    if padded_segments > actual_segments {
+        report_warning(
+            warnings,
+            "padded_batch",
+            &format!(
+                "{}: {} silent segment(s) added to fill partial batches for {} audio segment(s)",
+                input_path.display(),
+                padded_segments,
+                actual_segments
+            ),
+            Some("Use a smaller --batch-size for short recordings"),
+        );
     }
 
     // Finish progress bar
     drop(progress_guard);
 
+    // Analyze again with a finer overlap around what the first pass found
+    let first_pass_segments = actual_segments;
+    if let Some(zoom_overlap) = config.zoom_overlap.filter(|_| !bat_mode)
+        && !detections.is_empty()
+    {
+        let (_, zoom_overlap_samples) =
+            segment_lengths(segment_duration, zoom_overlap, target_rate);
+        let mut zoomed = Vec::new();
+        let mut window_count = 0;
+        // Windows are found per channel; merging below sorts them together again
+        let mut by_pass: Vec<Vec<Detection>> = vec![Vec::new(); passes.len()];
+        for detection in std::mem::take(&mut detections) {
+            let pass = passes
+                .iter()
+                .position(|&channel| channel == detection.metadata.channel)
+                .unwrap_or(0);
+            by_pass[pass].push(detection);
+        }
+        for (channel_detections, &channel) in by_pass.iter().zip(&passes) {
+            let windows = super::zoom::zoom_windows(
+                channel_detections,
+                segment_duration,
+                segment_duration - zoom_overlap,
+                config.time_range,
+            );
+            window_count += windows.len();
+            for window in &windows {
+                let (tx, rx) = sync_channel::<ChunkResult>(channel_capacity);
+                let decode_handle = spawn_decode_thread(
+                    input_path.to_path_buf(),
+                    source_rate,
+                    target_rate,
+                    segment_samples,
+                    zoom_overlap_samples,
+                    Some(*window),
+                    None,
+                    channel,
+                    config.filter,
+                    config.fault.cloned(),
+                    tx,
+                );
+                let outcome = run_streaming_inference(
+                    rx,
+                    classifier,
+                    input_path,
+                    min_confidence,
+                    final_batch_size,
+                    None,
+                    &mut batch_context,
+                    None,
+                    0,
+                    resolved_bsg_params,
+                    location_scores.as_deref(),
+                    custom_classifier,
+                    None,
+                    config.watchdog,
+                    config.fingerprint,
+                    config.fault,
+                )?;
+                if decode_handle.join().is_err() {
+                    tracing::warn!("Decode thread panicked during the zoom pass");
+                }
+                actual_segments += outcome.segment_count;
+                final_batch_size = outcome.batch_size;
+                zoomed.extend(tag_channel(outcome.detections, channel));
+            }
+        }
+        detections = by_pass.into_iter().flatten().collect();
+        let before = detections.len();
+        detections = super::zoom::merge_zoomed(detections, zoomed);
+        debug!(
+            "Zoom pass over {} range(s) added {} detections",
+            window_count,
+            detections.len() - before
+        );
+    }
+
+    if let Some(hook) = config.postprocess_hook {
+        let before = detections.len();
+        detections = hook.apply(input_path, detections, min_confidence)?;
+        debug!(
+            "Post-processing hook kept {} of {} detections",
+            detections.len(),
+            before
+        );
+    }
+
+    if config.merge_overlapping {
+        let before = detections.len();
+        detections = super::merge_overlapping(detections);
+        debug!(
+            "Merged {} detections into {} events",
+            before,
+            detections.len()
+        );
+    }
+
+    if let Some(track) = config.track {
+        track.locate(input_path, &mut detections);
+    }
+
+    for detection in &mut detections {
+        detection.metadata.sensitivity = Some(config.sensitivity);
+    }
+    if let Some(provenance) = config.provenance {
+        provenance.apply(&mut detections);
+    }
+    if let Some(recorder) = config.recorder {
+        recorder.apply(&mut detections);
+    }
+    if let Some(start) = config.recording_start {
+        crate::utils::timestamp::TimestampParser::stamp(start, &mut detections);
+    }
+    if let Some(station) = config.station {
+        station.apply(&mut detections);
+    }
+
+    if let Some(sensitive) = config.sensitive {
+        sensitive.flag(&mut detections);
+    }
+
     info!(
         "Found {} detections above {:.1}% confidence",
         detections.len(),
@@ -669,39 +1177,39 @@ pub fn process_file(
     // Use decoder hint if available, otherwise estimate from processed segments
     let audio_duration_secs = duration_hint.unwrap_or_else(|| {
         // Estimate: segment_duration + (n-1) * (segment_duration - overlap)
-        if actual_segments > 0 {
+        if first_pass_segments > 0 {
             let seg_dur = f64::from(segment_duration);
             let ovr = f64::from(overlap);
             let non_overlap = seg_dur - ovr;
             #[allow(clippy::cast_precision_loss)]
-            let estimated = (actual_segments as f64 - 1.0).mul_add(non_overlap, seg_dur);
+            let estimated = (first_pass_segments as f64 - 1.0).mul_add(non_overlap, seg_dur);
             estimated
         } else {
             0.0
         }
     });
-    let json_config = if formats.contains(&OutputFormat::Json) {
-        #[allow(clippy::cast_possible_truncation)]
-        let audio_duration_f32 = audio_duration_secs as f32;
-        Some(JsonOutputConfig {
-            model: model_name.to_string(),
-            min_confidence,
-            overlap,
-            audio_duration: audio_duration_f32,
-            lat: range_filter_params.map(|(lat, _, _)| lat),
-            lon: range_filter_params.map(|(_, lon, _)| lon),
-            week: range_filter_params.map(|(_, _, week)| week),
-        })
-    } else {
-        None
-    };
-
-    // Determine if we should write files (dual output or pure file mode)
-    let should_write_files = dual_output_mode || reporter.is_none();
+    let json_config =
+        if formats.contains(&OutputFormat::Json) || stdout_format == Some(OutputFormat::Json) {
+            #[allow(clippy::cast_possible_truncation)]
+            let audio_duration_f32 = audio_duration_secs as f32;
+            Some(JsonOutputConfig {
+                model: model_name.to_string(),
+                min_confidence,
+                overlap,
+                audio_duration: audio_duration_f32,
+                lat: range_filter_params.map(|(lat, _, _)| lat),
+                lon: range_filter_params.map(|(_, lon, _)| lon),
+                week: range_filter_params.map(|(_, _, week)| week),
+                station: config.station.cloned(),
+            })
+        } else {
+            None
+        };
 
     // Write output files if needed
     if should_write_files {
         for format in formats {
+            fault::inject(config.fault, FaultPoint::Write, input_path)?;
             write_output(
                 input_path,
                 output_dir,
@@ -709,11 +1217,45 @@ pub fn process_file(
                 &detections,
                 csv_columns,
                 csv_bom_enabled,
+                config.number_format,
                 json_config.as_ref(),
+                config.split_limits,
+            )?;
+        }
+
+        if let (Some(format), Some(tracker)) = (activity_format, activity_tracker.as_ref()) {
+            let activity_path = activity_path_for(input_path, output_dir, format)?;
+            debug!("Writing activity summary: {}", activity_path.display());
+            let bins = tracker.summarize(&detections);
+            crate::output::write_activity(&activity_path, format, &bins, csv_bom_enabled)?;
+        }
+
+        if let Some(manifest) = config.run_manifest {
+            manifest.write(&manifest_path_for(input_path, output_dir)?)?;
+        }
+
+        if let Some(combined) = config.combined {
+            combined.send(
+                input_path,
+                config
+                    .sensitive
+                    .map_or_else(|| detections.clone(), |s| s.mask(&detections)),
+                audio_duration_secs,
             )?;
         }
     }
 
+    if let Some(format) = stdout_format {
+        write_stdout_output(
+            input_path,
+            format,
+            &detections,
+            csv_columns,
+            config.number_format,
+            json_config.as_ref(),
+        )?;
+    }
+
     // Emit detections to stdout only in pure stdout mode (not dual output mode)
     if !dual_output_mode && let Some(reporter) = reporter {
         // Construct BSG metadata if BSG model is used
@@ -744,7 +1286,11 @@ pub fn process_file(
             None
         };
 
-        reporter.detections(input_path, &detections, bsg_metadata.as_ref());
+        reporter.detections(
+            input_path,
+            &config.number_format.json_detections(&detections),
+            bsg_metadata.as_ref(),
+        );
     }
 
     let duration_secs = start_time.elapsed().as_secs_f64();
@@ -766,11 +1312,25 @@ pub fn process_file(
         actual_segments, duration_secs, segments_per_sec, realtime_factor
     );
 
+    if let Some(report) = config.report {
+        report.add_file(
+            input_path,
+            &detections,
+            audio_duration_secs,
+            duration_secs,
+            actual_segments,
+        );
+    }
+
     Ok(ProcessResult {
         detections: detections.len(),
+        species: crate::output::FileSpeciesSummary::from_detections(
+            &config.number_format.json_detections(&detections),
+        ),
         segments: actual_segments,
         duration_secs,
         audio_duration_secs,
+        reduced_batch_size: (final_batch_size < effective_batch_size).then_some(final_batch_size),
     })
 }
 
@@ -791,31 +1351,132 @@ pub struct JsonOutputConfig {
     pub lon: Option<f64>,
     /// Week for range filtering.
     pub week: Option<u8>,
+    /// Station recorded in the result file, if one is configured.
+    pub station: Option<crate::output::Station>,
 }
 
 /// Write detections to an output file.
 #[allow(clippy::too_many_arguments)]
-fn write_output(
+pub(super) fn write_output(
     input_path: &Path,
     output_dir: &Path,
     format: OutputFormat,
     detections: &[Detection],
     csv_columns: &[String],
     csv_bom_enabled: bool,
+    number_format: NumberFormat,
     json_config: Option<&JsonOutputConfig>,
+    split_limits: SplitLimits,
 ) -> Result<()> {
     let output_path = output_path_for(input_path, output_dir, format)?;
     debug!("Writing {} output: {}", format, output_path.display());
 
-    let mut writer: Box<dyn OutputWriter> = match format {
-        OutputFormat::Csv => Box::new(CsvWriter::new(
-            &output_path,
-            csv_columns.to_vec(),
+    let writer = create_writer(
+        input_path,
+        OutputTarget::File(&output_path),
+        format,
+        csv_columns,
+        csv_bom_enabled,
+        number_format,
+        json_config,
+        split_limits,
+    )?;
+    write_detections(writer, detections)
+}
+
+/// Write detections to stdout in a single output format.
+///
+/// CSV is written without a BOM since the stream is meant for piping.
+fn write_stdout_output(
+    input_path: &Path,
+    format: OutputFormat,
+    detections: &[Detection],
+    csv_columns: &[String],
+    number_format: NumberFormat,
+    json_config: Option<&JsonOutputConfig>,
+) -> Result<()> {
+    debug!("Writing {} output to stdout", format);
+
+    let writer = create_writer(
+        input_path,
+        OutputTarget::Stdout,
+        format,
+        csv_columns,
+        false,
+        number_format,
+        json_config,
+        SplitLimits::default(),
+    )?;
+    write_detections(writer, detections)
+}
+
+/// Destination of an output writer.
+#[derive(Clone, Copy)]
+enum OutputTarget<'a> {
+    /// Write to a file.
+    File(&'a Path),
+    /// Stream to stdout.
+    Stdout,
+}
+
+/// Create the writer for `format` over an output target.
+///
+/// File outputs covered by `split_limits` roll over into numbered parts.
+#[allow(clippy::too_many_arguments)]
+fn create_writer(
+    input_path: &Path,
+    target: OutputTarget<'_>,
+    format: OutputFormat,
+    csv_columns: &[String],
+    csv_bom_enabled: bool,
+    number_format: NumberFormat,
+    json_config: Option<&JsonOutputConfig>,
+    split_limits: SplitLimits,
+) -> Result<Box<dyn OutputWriter>> {
+    if let OutputTarget::File(path) = target
+        && split_limits.applies_to(format)
+    {
+        return Ok(Box::new(RollingWriter::new(
+            path,
+            format,
+            csv_columns,
             csv_bom_enabled,
-        )?),
-        OutputFormat::Raven => Box::new(RavenWriter::new(&output_path)?),
-        OutputFormat::Audacity => Box::new(AudacityWriter::new(&output_path)?),
-        OutputFormat::Kaleidoscope => Box::new(KaleidoscopeWriter::new(&output_path)?),
+            number_format,
+            split_limits,
+        )?));
+    }
+
+    let open_sink = || -> Result<Box<dyn std::io::Write + Send>> {
+        match target {
+            OutputTarget::File(path) => Ok(Box::new(std::fs::File::create(path)?)),
+            OutputTarget::Stdout => Ok(Box::new(std::io::stdout())),
+        }
+    };
+
+    let writer: Box<dyn OutputWriter> = match format {
+        OutputFormat::Csv => Box::new(
+            CsvWriter::from_writer(open_sink()?, csv_columns.to_vec(), csv_bom_enabled)?
+                .with_number_format(number_format),
+        ),
+        OutputFormat::BirdnetCsv => {
+            Box::new(BirdnetCsvWriter::from_writer(open_sink()?).with_number_format(number_format))
+        }
+        OutputFormat::Raven => {
+            Box::new(RavenWriter::from_writer(open_sink()?).with_number_format(number_format))
+        }
+        OutputFormat::Audacity => {
+            Box::new(AudacityWriter::from_writer(open_sink()?).with_number_format(number_format))
+        }
+        OutputFormat::Kaleidoscope => {
+            let writer =
+                KaleidoscopeWriter::from_writer(open_sink()?).with_number_format(number_format);
+            match target {
+                OutputTarget::File(path) => {
+                    Box::new(writer.output_dir(path.parent().unwrap_or_else(|| Path::new(""))))
+                }
+                OutputTarget::Stdout => Box::new(writer),
+            }
+        }
         OutputFormat::Json => {
             let source_file = input_path.file_name().map_or_else(
                 || "unknown".to_string(),
@@ -827,8 +1488,12 @@ fn write_output(
                 message: "JsonOutputConfig required for JSON format".to_string(),
             })?;
 
-            Box::new(JsonResultWriter::new(
-                &output_path,
+            let output_path = match target {
+                OutputTarget::File(path) => path,
+                OutputTarget::Stdout => Path::new("<stdout>"),
+            };
+            let writer = JsonResultWriter::new(
+                output_path,
                 &source_file,
                 config.audio_duration,
                 &config.model,
@@ -837,11 +1502,34 @@ fn write_output(
                 config.lat,
                 config.lon,
                 config.week,
-            )?)
+            )?
+            .with_number_format(number_format)
+            .with_station(config.station.clone());
+            match target {
+                OutputTarget::File(_) => Box::new(writer),
+                OutputTarget::Stdout => Box::new(writer.with_sink(open_sink()?)),
+            }
+        }
+        OutputFormat::Parquet => match target {
+            OutputTarget::File(path) => Box::new(ParquetWriter::new(path, csv_columns)?),
+            OutputTarget::Stdout => {
+                return Err(crate::error::Error::InvalidOutputFormat {
+                    value: "parquet cannot be written to stdout".to_string(),
+                });
+            }
+        },
+        OutputFormat::Sqlite => {
+            return Err(crate::error::Error::InvalidOutputFormat {
+                value: "sqlite is written once per run, not per file".to_string(),
+            });
         }
-        OutputFormat::Parquet => Box::new(ParquetWriter::new(&output_path, csv_columns)?),
     };
 
+    Ok(writer)
+}
+
+/// Write all detections through a writer and finalize it.
+fn write_detections(mut writer: Box<dyn OutputWriter>, detections: &[Detection]) -> Result<()> {
     writer.write_header()?;
     for detection in detections {
         writer.write_detection(detection)?;
@@ -856,10 +1544,14 @@ fn write_output(
 pub struct ProcessResult {
     /// Number of detections found.
     pub detections: usize,
+    /// Detections per species, most confident first.
+    pub species: Vec<crate::output::FileSpeciesSummary>,
     /// Number of segments processed.
     pub segments: usize,
     /// Processing duration in seconds.
     pub duration_secs: f64,
     /// Audio duration in seconds.
     pub audio_duration_secs: f64,
+    /// Reduced batch size if GPU out-of-memory errors forced a backoff.
+    pub reduced_batch_size: Option<usize>,
 }
@@ -0,0 +1,296 @@
+//! Installation self-test for `birda selftest`.
+//!
+//! Runs a generated recording through every pipeline stage (decode, resample,
+//! chunk, inference and each output writer) and records which stages pass, so
+//! a broken installation can be narrowed down with one command. A failed
+//! stage skips the stages that depend on it instead of aborting the test.
+
+use super::output_path_for;
+use super::processor::{JsonOutputConfig, write_output};
+use crate::audio::{
+    AudioChunk, DecodedAudio, chunk_audio, decode_audio_file, resample, test_signal,
+};
+use crate::clipper::encode_wav;
+use crate::config::{InferenceDevice, ModelConfig, OutputFormat};
+use crate::error::{Error, Result};
+use crate::inference::{self, BirdClassifier};
+use crate::output::{
+    Detection, NumberFormat, RunManifest, RunSettings, SelftestStage, SplitLimits, StageStatus,
+    open_sqlite_output,
+};
+use birdnet_onnx::InferenceOptions;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Sample rate of the generated fixture.
+const FIXTURE_SAMPLE_RATE: u32 = 16_000;
+
+/// Length of the generated fixture in samples (3 seconds).
+const FIXTURE_SAMPLES: usize = 48_000;
+
+/// File name the fixture is written to before decoding.
+const FIXTURE_NAME: &str = "selftest.wav";
+
+/// File name of the database written by the SQLite writer stage.
+const SQLITE_NAME: &str = "selftest.db";
+
+/// Formats written by the writer stages.
+const WRITER_FORMATS: [OutputFormat; 8] = [
+    OutputFormat::Csv,
+    OutputFormat::BirdnetCsv,
+    OutputFormat::Raven,
+    OutputFormat::Audacity,
+    OutputFormat::Kaleidoscope,
+    OutputFormat::Json,
+    OutputFormat::Parquet,
+    OutputFormat::Sqlite,
+];
+
+/// Records stage outcomes in order.
+struct Stages(Vec<SelftestStage>);
+
+impl Stages {
+    /// Run a stage, recording its outcome and returning its output on success.
+    fn run<T>(&mut self, name: &str, f: impl FnOnce() -> Result<(T, String)>) -> Option<T> {
+        let start = Instant::now();
+        let result = f();
+        #[allow(clippy::cast_possible_truncation)]
+        let duration_ms = start.elapsed().as_millis() as u64;
+        let (status, message, value) = match result {
+            Ok((value, message)) => (StageStatus::Passed, message, Some(value)),
+            Err(e) => (StageStatus::Failed, e.to_string(), None),
+        };
+        self.0.push(SelftestStage {
+            name: name.to_string(),
+            status,
+            duration_ms,
+            message: Some(message),
+        });
+        value
+    }
+
+    /// Record a stage that could not run because `cause` failed.
+    fn skip(&mut self, name: &str, cause: &str) {
+        self.0.push(SelftestStage {
+            name: name.to_string(),
+            status: StageStatus::Skipped,
+            duration_ms: 0,
+            message: Some(format!("requires {cause}")),
+        });
+    }
+}
+
+/// Run the self-test in `work_dir` with `model` (name and configuration).
+///
+/// Without a model, the runtime and inference stages fail and the writers
+/// are tested with a placeholder detection.
+pub fn run_selftest(
+    model: Option<(&str, &ModelConfig)>,
+    device: InferenceDevice,
+    work_dir: &Path,
+) -> Vec<SelftestStage> {
+    let mut stages = Stages(Vec::new());
+    let fixture = work_dir.join(FIXTURE_NAME);
+
+    let decoded = stages.run("decode", || {
+        let samples = test_signal(FIXTURE_SAMPLE_RATE, FIXTURE_SAMPLES);
+        let wav =
+            encode_wav(&samples, FIXTURE_SAMPLE_RATE).map_err(|source| Error::WavWriteFailed {
+                path: fixture.clone(),
+                source,
+            })?;
+        std::fs::write(&fixture, wav)?;
+        let audio = decode_audio_file(&fixture)?;
+        let message = format!("{:.1} s at {} Hz", audio.duration_secs, audio.sample_rate);
+        Ok((audio, message))
+    });
+
+    let classifier = stages.run("model", || {
+        let (name, config) = model.ok_or_else(|| Error::ConfigValidation {
+            message: "no model specified (use -m or set defaults.model in config)".into(),
+        })?;
+        inference::ensure_runtime_available()?;
+        let classifier = BirdClassifier::from_config(config, device, 0.0, 1, None, None, None)?;
+        let message = format!(
+            "{name} on {}",
+            classifier.execution_provider_status().actual
+        );
+        Ok((classifier, message))
+    });
+
+    let detections = match (decoded, &classifier) {
+        (Some(audio), Some(classifier)) => infer(&mut stages, audio, classifier),
+        (decoded, _) => {
+            let cause = if decoded.is_none() { "decode" } else { "model" };
+            for name in ["resample", "chunk", "inference"] {
+                stages.skip(name, cause);
+            }
+            None
+        }
+    };
+
+    // Writers are tested even when inference is unavailable
+    let detections = detections.unwrap_or_else(|| {
+        vec![Detection::from_label(
+            "Parus major_Great Tit",
+            0.9,
+            0.0,
+            3.0,
+            PathBuf::from(FIXTURE_NAME),
+        )]
+    });
+    let json_config = JsonOutputConfig {
+        model: model.map_or_else(|| "selftest".to_string(), |(name, _)| name.to_string()),
+        min_confidence: 0.0,
+        overlap: 0.0,
+        audio_duration: 3.0,
+        lat: None,
+        lon: None,
+        week: None,
+        station: None,
+    };
+    for format in WRITER_FORMATS {
+        let name = format!("writer:{format}");
+        if format == OutputFormat::Sqlite && !cfg!(feature = "sqlite") {
+            stages.skip(&name, "a build with the sqlite feature");
+            continue;
+        }
+        stages.run(&name, || {
+            let path = if format == OutputFormat::Sqlite {
+                write_sqlite(&fixture, work_dir, &detections, &json_config)?
+            } else {
+                write_output(
+                    &fixture,
+                    work_dir,
+                    format,
+                    &detections,
+                    &[],
+                    true,
+                    NumberFormat::default(),
+                    Some(&json_config),
+                    SplitLimits::default(),
+                )?;
+                output_path_for(&fixture, work_dir, format)?
+            };
+            let size = std::fs::metadata(&path)?.len();
+            Ok(((), format!("{size} bytes")))
+        });
+    }
+
+    stages.0
+}
+
+/// Write `detections` of the fixture to a SQLite database in `work_dir`,
+/// returning its path. SQLite is written once per run rather than per file.
+fn write_sqlite(
+    fixture: &Path,
+    work_dir: &Path,
+    detections: &[Detection],
+    json_config: &JsonOutputConfig,
+) -> Result<PathBuf> {
+    let path = work_dir.join(SQLITE_NAME);
+    let manifest = RunManifest::new(RunSettings {
+        model: json_config.model.clone(),
+        ..RunSettings::default()
+    });
+    let mut writer = open_sqlite_output(&path, &manifest)?;
+    for detection in detections {
+        writer.write_detection(detection)?;
+    }
+    writer.end_file(fixture, f64::from(json_config.audio_duration))?;
+    writer.finalize()?;
+    Ok(path)
+}
+
+/// Resample, chunk and classify the decoded fixture.
+fn infer(
+    stages: &mut Stages,
+    audio: DecodedAudio,
+    classifier: &BirdClassifier,
+) -> Option<Vec<Detection>> {
+    let sample_rate = classifier.sample_rate();
+    let Some(samples) = stages.run("resample", || {
+        let from = audio.sample_rate;
+        let samples = resample(audio.samples, from, sample_rate)?;
+        Ok((samples, format!("{from} Hz to {sample_rate} Hz")))
+    }) else {
+        stages.skip("chunk", "resample");
+        stages.skip("inference", "resample");
+        return None;
+    };
+
+    let Some(chunks) = stages.run("chunk", || {
+        let chunks = chunk_audio(&samples, sample_rate, classifier.segment_duration(), 0.0);
+        if chunks.is_empty() {
+            return Err(Error::Internal {
+                message: "fixture produced no segments".to_string(),
+            });
+        }
+        let message = format!("{} segment(s)", chunks.len());
+        Ok((chunks, message))
+    }) else {
+        stages.skip("inference", "chunk");
+        return None;
+    };
+
+    stages.run("inference", || {
+        let detections = classify(classifier, &chunks)?;
+        let message = detections.first().map_or_else(
+            || "no predictions".to_string(),
+            |d| format!("top: {} ({:.1}%)", d.common_name, d.confidence * 100.0),
+        );
+        Ok((detections, message))
+    })
+}
+
+/// Classify every chunk, keeping the top prediction of each.
+fn classify(classifier: &BirdClassifier, chunks: &[AudioChunk]) -> Result<Vec<Detection>> {
+    let segments: Vec<&[f32]> = chunks.iter().map(|c| c.samples.as_slice()).collect();
+    let results = classifier.predict_batch(&segments, &InferenceOptions::default())?;
+    Ok(chunks
+        .iter()
+        .zip(results)
+        .filter_map(|(chunk, result)| {
+            result.predictions.first().map(|p| {
+                Detection::from_label(
+                    &p.species,
+                    p.confidence,
+                    chunk.start_time,
+                    chunk.end_time,
+                    PathBuf::from(FIXTURE_NAME),
+                )
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selftest_without_model_still_checks_writers() {
+        let dir = tempfile::tempdir().unwrap();
+        let stages = run_selftest(None, InferenceDevice::Cpu, dir.path());
+
+        let status = |name: &str| {
+            stages
+                .iter()
+                .find(|s| s.name == name)
+                .map(|s| s.status)
+                .unwrap()
+        };
+        assert_eq!(status("decode"), StageStatus::Passed);
+        assert_eq!(status("model"), StageStatus::Failed);
+        assert_eq!(status("inference"), StageStatus::Skipped);
+        for format in WRITER_FORMATS {
+            let expected = if format == OutputFormat::Sqlite && !cfg!(feature = "sqlite") {
+                StageStatus::Skipped
+            } else {
+                StageStatus::Passed
+            };
+            assert_eq!(status(&format!("writer:{format}")), expected);
+        }
+    }
+}
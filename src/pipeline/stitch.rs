@@ -0,0 +1,243 @@
+//! Stitching of recordings split into consecutive files.
+//!
+//! Recorders often split continuous audio into fixed-length files. When a
+//! file's name timestamp follows on from the end of the previous file in the
+//! same directory, the two are analyzed as one stream: the segment that
+//! crosses the boundary is completed with audio from the next file, and the
+//! next file's segments continue the same grid instead of starting over at
+//! zero. Each segment belongs to the file it starts in, with times relative to
+//! that file, so nothing is analyzed twice and nothing is lost.
+
+use super::processor::{segment_lengths, source_samples};
+use crate::audio::StreamingDecoder;
+use crate::constants::stitch::MAX_GAP_SECS;
+use crate::utils::date::timestamp_from_filename;
+use chrono::NaiveDateTime;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// How one recording joins the recordings around it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stitch {
+    /// Sample at which the recording's first segment starts; the audio before
+    /// it was analyzed with the previous recording.
+    pub start_sample: usize,
+    /// Recording that continues this one.
+    pub next: Option<PathBuf>,
+}
+
+/// A recording with the metadata needed to place it in a sequence.
+#[derive(Debug, Clone)]
+struct Recording {
+    path: PathBuf,
+    start: NaiveDateTime,
+    sample_rate: u32,
+    frames: usize,
+}
+
+impl Recording {
+    /// Read a recording's timestamp and length; `None` if either is unknown.
+    fn probe(path: &Path) -> Option<Self> {
+        let start = timestamp_from_filename(path)?;
+        let decoder = StreamingDecoder::open(path).ok()?;
+        let sample_rate = decoder.sample_rate();
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let frames = (decoder.duration_hint()? * f64::from(sample_rate)).round() as usize;
+        Some(Self {
+            path: path.to_path_buf(),
+            start,
+            sample_rate,
+            frames,
+        })
+    }
+
+    /// Whether `next` starts where this recording ends and is long enough to
+    /// complete a segment of `segment` samples.
+    fn continues_into(&self, next: &Self, segment: usize) -> bool {
+        if next.sample_rate != self.sample_rate || next.frames < segment {
+            return false;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let end = self.frames as f64 / f64::from(self.sample_rate);
+        #[allow(clippy::cast_precision_loss)]
+        let offset = (next.start - self.start).num_milliseconds() as f64 / 1000.0;
+        (offset - end).abs() <= MAX_GAP_SECS
+    }
+}
+
+/// Find recordings among `files` that continue one another and plan how each
+/// is joined to the next.
+///
+/// Recordings without a timestamp in their name or a known length are
+/// analyzed on their own and have no entry in the plan.
+pub fn plan_stitching(
+    files: &[PathBuf],
+    segment_duration: f32,
+    overlap: f32,
+    target_rate: u32,
+) -> HashMap<PathBuf, Stitch> {
+    let recordings = files
+        .iter()
+        .filter_map(|path| {
+            let recording = Recording::probe(path);
+            if recording.is_none() {
+                debug!("Not stitching {}: no timestamp or length", path.display());
+            }
+            recording
+        })
+        .collect();
+    plan(recordings, segment_duration, overlap, target_rate)
+}
+
+/// Chain recordings per directory in timestamp order.
+fn plan(
+    mut recordings: Vec<Recording>,
+    segment_duration: f32,
+    overlap: f32,
+    target_rate: u32,
+) -> HashMap<PathBuf, Stitch> {
+    recordings.sort_by(|a, b| (a.path.parent(), a.start).cmp(&(b.path.parent(), b.start)));
+    let (segment, overlap) = segment_lengths(segment_duration, overlap, target_rate);
+
+    let mut stitches = HashMap::new();
+    let mut start_sample = 0;
+    for (i, recording) in recordings.iter().enumerate() {
+        let rate = recording.sample_rate;
+        let source_segment = source_samples(segment, rate, target_rate);
+        let hop = source_segment
+            .saturating_sub(source_samples(overlap, rate, target_rate))
+            .max(1);
+        let next = recordings.get(i + 1).filter(|next| {
+            next.path.parent() == recording.path.parent()
+                && recording.continues_into(next, source_segment)
+        });
+
+        if start_sample > 0 || next.is_some() {
+            stitches.insert(
+                recording.path.clone(),
+                Stitch {
+                    start_sample,
+                    next: next.map(|next| next.path.clone()),
+                },
+            );
+        }
+        start_sample = if next.is_some() {
+            next_start(recording.frames, start_sample, hop)
+        } else {
+            0
+        };
+    }
+    stitches
+}
+
+/// Where the next recording's first segment starts when segments begin at
+/// `start` and advance by `hop` through a recording of `frames` samples.
+fn next_start(frames: usize, start: usize, hop: usize) -> usize {
+    if start >= frames {
+        return start - frames;
+    }
+    let last = start + (frames - 1 - start) / hop * hop;
+    last + hop - frames
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeDelta};
+
+    /// A 16 kHz recording starting `start` seconds after 06:00 and lasting `length` seconds.
+    fn recording(path: &str, start: i64, length: usize) -> Recording {
+        let six = NaiveDate::from_ymd_opt(2024, 5, 1)
+            .and_then(|d| d.and_hms_opt(6, 0, 0))
+            .unwrap();
+        Recording {
+            path: PathBuf::from(path),
+            start: six + TimeDelta::seconds(start),
+            sample_rate: 16_000,
+            frames: length * 16_000,
+        }
+    }
+
+    #[test]
+    fn test_next_start() {
+        // 10 s of 3 s segments: the last starts at 9 s and runs 2 s into the next file
+        assert_eq!(next_start(160_000, 0, 48_000), 32_000);
+        assert_eq!(next_start(160_000, 32_000, 48_000), 16_000);
+        // Segments that end exactly at the boundary carry nothing over
+        assert_eq!(next_start(144_000, 0, 48_000), 0);
+    }
+
+    #[test]
+    fn test_plan_chains_consecutive_recordings() {
+        // Given out of order
+        let recordings = vec![
+            recording("site/20240501_060100.wav", 60, 60),
+            recording("site/20240501_060000.wav", 0, 60),
+            recording("site/20240501_060200.wav", 120, 60),
+        ];
+        let plan = plan(recordings, 3.0, 0.0, 16_000);
+
+        assert_eq!(
+            plan[Path::new("site/20240501_060000.wav")],
+            Stitch {
+                start_sample: 0,
+                next: Some(PathBuf::from("site/20240501_060100.wav")),
+            }
+        );
+        assert_eq!(
+            plan[Path::new("site/20240501_060100.wav")].next,
+            Some(PathBuf::from("site/20240501_060200.wav"))
+        );
+        // 60 s divides into 3 s segments, so the last file needs no adjustment
+        assert!(!plan.contains_key(Path::new("site/20240501_060200.wav")));
+    }
+
+    #[test]
+    fn test_plan_carries_segment_grid_across_boundary() {
+        let recordings = vec![
+            recording("site/20240501_060000.wav", 0, 61),
+            recording("site/20240501_060101.wav", 61, 61),
+            recording("site/20240501_060202.wav", 122, 60),
+        ];
+        let plan = plan(recordings, 3.0, 0.0, 16_000);
+
+        // The first file's last segment starts at 60 s and covers 2 s of the next
+        assert_eq!(
+            plan[Path::new("site/20240501_060101.wav")].start_sample,
+            32_000
+        );
+        // Segments then start at 2, 5, ... 59 s; the one at 59 s covers 1 s of the third
+        assert_eq!(
+            plan[Path::new("site/20240501_060202.wav")].start_sample,
+            16_000
+        );
+    }
+
+    #[test]
+    fn test_plan_allows_small_timestamp_gaps() {
+        let recordings = vec![
+            recording("site/20240501_060000.wav", 0, 60),
+            // Timestamp rounded up by a second
+            recording("site/20240501_060101.wav", 61, 60),
+        ];
+        let plan = plan(recordings, 3.0, 0.0, 16_000);
+        assert_eq!(
+            plan[Path::new("site/20240501_060000.wav")].next,
+            Some(PathBuf::from("site/20240501_060101.wav"))
+        );
+    }
+
+    #[test]
+    fn test_plan_leaves_gaps_and_other_directories_alone() {
+        let recordings = vec![
+            recording("site/20240501_060000.wav", 0, 60),
+            // Starts five minutes later
+            recording("site/20240501_060500.wav", 300, 60),
+            // Follows on in time, but from another recorder
+            recording("other/20240501_060100.wav", 60, 60),
+        ];
+        assert!(plan(recordings, 3.0, 0.0, 16_000).is_empty());
+    }
+}
@@ -0,0 +1,52 @@
+//! Continuous analysis of a network audio stream.
+//!
+//! The stream is decoded as it arrives and classified segment by segment, with
+//! the same segment grid as a file. Detection times are seconds of audio
+//! received since the stream was opened; audio missed while reconnecting is
+//! not counted.
+
+use super::listen::classify_segment;
+use super::processor::{segment_lengths, source_samples};
+use crate::audio::StreamingDecoder;
+use crate::error::Result;
+use crate::inference::BirdClassifier;
+use crate::output::Detection;
+use std::path::PathBuf;
+use tracing::info;
+
+/// Classify the audio stream at `url` until it ends or reconnecting fails.
+///
+/// `on_detections` receives the detections of every segment that has any.
+pub fn analyze_stream(
+    classifier: &BirdClassifier,
+    url: &str,
+    overlap: f32,
+    min_confidence: f32,
+    mut on_detections: impl FnMut(&[Detection]),
+) -> Result<()> {
+    let mut decoder = StreamingDecoder::open_url(url)?;
+    let source_rate = decoder.sample_rate();
+    let target_rate = classifier.sample_rate();
+    let (segment, overlap) = segment_lengths(classifier.segment_duration(), overlap, target_rate);
+    let source_segment = source_samples(segment, source_rate, target_rate);
+    let source_overlap = source_samples(overlap, source_rate, target_rate);
+    info!("Analyzing stream {url} at {source_rate} Hz");
+
+    let source = PathBuf::from(url);
+    while let Some(raw) = decoder.next_segment(source_segment, source_overlap)? {
+        #[allow(clippy::cast_precision_loss)]
+        let start_time = raw.start_sample as f32 / source_rate as f32;
+        let detections = classify_segment(
+            classifier,
+            raw.samples,
+            source_rate,
+            start_time,
+            min_confidence,
+            &source,
+        )?;
+        if !detections.is_empty() {
+            on_detections(&detections);
+        }
+    }
+    Ok(())
+}
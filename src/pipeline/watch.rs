@@ -0,0 +1,97 @@
+//! Directory monitoring for continuous analysis.
+//!
+//! Recorders write files over minutes, and network shares do not deliver
+//! filesystem notifications, so directories are scanned periodically instead.
+//! A file is reported once its size and modification time stayed unchanged
+//! between two scans, and is never reported again.
+
+use super::coordinator::collect_input_files;
+use crate::error::Result;
+use crate::utils::glob::Glob;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Finds new, completely written audio files in watched directories.
+#[derive(Debug)]
+pub struct DirectoryWatcher {
+    /// Watched directories.
+    dirs: Vec<PathBuf>,
+    /// Patterns of files and directories to leave out.
+    exclude: Vec<Glob>,
+    /// Files already reported.
+    seen: HashSet<PathBuf>,
+    /// Size and modification time of unreported files at the last scan.
+    pending: HashMap<PathBuf, (u64, Option<SystemTime>)>,
+}
+
+impl DirectoryWatcher {
+    /// Watch `dirs` (recursively), leaving out paths matching `exclude`.
+    pub fn new(dirs: Vec<PathBuf>, exclude: Vec<Glob>) -> Self {
+        Self {
+            dirs,
+            exclude,
+            seen: HashSet::new(),
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Scan the directories, returning files that are ready for analysis.
+    ///
+    /// Files that are still growing are reported by a later scan.
+    pub fn poll(&mut self) -> Result<Vec<PathBuf>> {
+        let mut ready = Vec::new();
+        let mut pending = HashMap::new();
+
+        for file in collect_input_files(&self.dirs, &self.exclude)? {
+            if self.seen.contains(&file) {
+                continue;
+            }
+            // Files can vanish between listing and inspection
+            let Ok(metadata) = std::fs::metadata(&file) else {
+                continue;
+            };
+            let state = (metadata.len(), metadata.modified().ok());
+            if self.pending.get(&file) == Some(&state) {
+                self.seen.insert(file.clone());
+                ready.push(file);
+            } else {
+                pending.insert(file, state);
+            }
+        }
+
+        self.pending = pending;
+        ready.sort();
+        Ok(ready)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watcher_reports_settled_files_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut watcher = DirectoryWatcher::new(vec![dir.path().to_path_buf()], Vec::new());
+        assert!(watcher.poll().unwrap().is_empty());
+
+        let wav = dir.path().join("20240601_060000.wav");
+        std::fs::write(&wav, b"RIFF").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), b"not audio").unwrap();
+
+        // First seen: the recorder may still be writing
+        assert!(watcher.poll().unwrap().is_empty());
+        assert_eq!(watcher.poll().unwrap(), vec![wav.clone()]);
+        assert!(watcher.poll().unwrap().is_empty());
+
+        // A growing file waits until it stops changing
+        let growing = dir.path().join("20240601_070000.wav");
+        std::fs::write(&growing, b"RIFF").unwrap();
+        assert!(watcher.poll().unwrap().is_empty());
+        std::fs::write(&growing, b"RIFF....data").unwrap();
+        assert!(watcher.poll().unwrap().is_empty());
+        assert_eq!(watcher.poll().unwrap(), vec![growing]);
+    }
+}
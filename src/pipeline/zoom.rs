@@ -0,0 +1,138 @@
+//! Second, finer pass around detections (`--zoom-overlap`).
+//!
+//! The first pass runs at the normal overlap. Every segment that produced a
+//! detection is then analyzed again with a higher overlap, covering all
+//! segments of the finer grid that overlap it, so detections get fine timing
+//! where there is something to hear at a fraction of the cost of a full
+//! high-overlap run.
+
+use crate::audio::TimeRange;
+use crate::output::Detection;
+
+/// Ranges to analyze again around `detections`, merged where they overlap.
+///
+/// A range covers the starts of all segments of length `segment_duration`,
+/// `step` seconds apart, that overlap a detection, kept within `bounds`.
+pub(super) fn zoom_windows(
+    detections: &[Detection],
+    segment_duration: f32,
+    step: f32,
+    bounds: Option<TimeRange>,
+) -> Vec<TimeRange> {
+    let lower = bounds.map_or(0.0, |b| b.start);
+    let upper = bounds.and_then(|b| b.end).unwrap_or(f64::INFINITY);
+
+    let mut spans: Vec<(f64, f64)> = detections
+        .iter()
+        .map(|d| {
+            let start = f64::from(d.start_time - segment_duration + step).max(lower);
+            (start, f64::from(d.end_time).min(upper))
+        })
+        .filter(|(start, end)| start < end)
+        .collect();
+    spans.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut windows: Vec<TimeRange> = Vec::new();
+    for (start, end) in spans {
+        match windows.last_mut() {
+            Some(last) if last.end.is_some_and(|last_end| start <= last_end) => {
+                last.end = last.end.map(|last_end| last_end.max(end));
+            }
+            _ => windows.push(TimeRange {
+                start,
+                end: Some(end),
+            }),
+        }
+    }
+    windows
+}
+
+/// Add the detections of the zoom pass to those of the first pass.
+///
+/// A zoomed detection of a segment the first pass already analyzed is
+/// dropped. Returns detections sorted by start time, then by confidence
+/// (descending).
+pub(super) fn merge_zoomed(
+    mut detections: Vec<Detection>,
+    zoomed: Vec<Detection>,
+) -> Vec<Detection> {
+    for detection in zoomed {
+        let duplicate = detections.iter().any(|d| {
+            d.scientific_name == detection.scientific_name
+                && d.metadata.channel == detection.metadata.channel
+                && (d.start_time - detection.start_time).abs() < 1e-3
+        });
+        if !duplicate {
+            detections.push(detection);
+        }
+    }
+    detections.sort_unstable_by(|a, b| {
+        a.start_time
+            .total_cmp(&b.start_time)
+            .then_with(|| b.confidence.total_cmp(&a.confidence))
+    });
+    detections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zoom_windows_cover_overlapping_segments() {
+        let detections = vec![
+            Detection::test("Parus major_Great Tit", 0.0, "a.wav").with_confidence(0.8),
+            Detection::test("Parus major_Great Tit", 3.0, "a.wav").with_confidence(0.6),
+            Detection::test("Turdus merula_Eurasian Blackbird", 30.0, "a.wav").with_confidence(0.7),
+        ];
+        let windows = zoom_windows(&detections, 3.0, 0.5, None);
+        assert_eq!(
+            windows,
+            vec![
+                TimeRange {
+                    start: 0.0,
+                    end: Some(6.0)
+                },
+                TimeRange {
+                    start: 27.5,
+                    end: Some(33.0)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_zoom_windows_stay_within_bounds() {
+        let detections =
+            vec![Detection::test("Parus major_Great Tit", 60.0, "a.wav").with_confidence(0.8)];
+        let bounds = TimeRange {
+            start: 60.0,
+            end: Some(62.0),
+        };
+        let windows = zoom_windows(&detections, 3.0, 1.0, Some(bounds));
+        assert_eq!(
+            windows,
+            vec![TimeRange {
+                start: 60.0,
+                end: Some(62.0)
+            }]
+        );
+    }
+
+    #[test]
+    fn test_merge_zoomed_drops_repeated_segments() {
+        let merged = merge_zoomed(
+            vec![Detection::test("Parus major_Great Tit", 3.0, "a.wav").with_confidence(0.6)],
+            vec![
+                Detection::test("Parus major_Great Tit", 1.5, "a.wav").with_confidence(0.9),
+                Detection::test("Parus major_Great Tit", 3.0, "a.wav").with_confidence(0.6),
+                Detection::test("Turdus merula_Eurasian Blackbird", 3.0, "a.wav")
+                    .with_confidence(0.4),
+            ],
+        );
+        assert_eq!(merged.len(), 3);
+        assert!((merged[0].start_time - 1.5).abs() < f32::EPSILON);
+        assert_eq!(merged[1].common_name, "Great Tit");
+        assert_eq!(merged[2].common_name, "Eurasian Blackbird");
+    }
+}
@@ -0,0 +1,198 @@
+//! Curated example recordings for tutorials, self-checks and benchmarks.
+//!
+//! A set is bundled with the binary, so every user of a birda version
+//! fetches the same recordings and can compare results against the expected
+//! detections listed for each one. Only published, checksummed recordings
+//! belong in it; until there are any the bundled set is empty, and a set
+//! published elsewhere can be fetched from its catalog file.
+
+use super::installer::{Download, RetryPolicy, download_files, http_client};
+use crate::constants::examples::MANIFEST_NAME;
+use crate::error::{Error, Result};
+use crate::output::{FetchedExample, ProgressReporter};
+use crate::utils::hash::sha256_file;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Set of example recordings.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ExampleSet {
+    /// Version of the set (increments when recordings change).
+    pub version: u32,
+    /// Example recordings.
+    pub examples: Vec<ExampleRecording>,
+}
+
+/// One example recording.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ExampleRecording {
+    /// Unique identifier.
+    pub id: String,
+    /// File name of the downloaded recording.
+    pub filename: String,
+    /// Download URL.
+    pub url: String,
+    /// Expected SHA256 hash of the recording.
+    pub sha256: String,
+    /// License identifier (SPDX).
+    pub license: String,
+    /// Who to credit for the recording.
+    pub attribution: String,
+    /// Species the recording is expected to produce.
+    #[serde(default)]
+    pub expected: Vec<ExpectedDetection>,
+}
+
+/// Species expected in an example recording.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ExpectedDetection {
+    /// Scientific name.
+    pub scientific_name: String,
+    /// Common name.
+    pub common_name: String,
+    /// Start of the span the species is heard in, in seconds.
+    pub start_time: f32,
+    /// End of the span the species is heard in, in seconds.
+    pub end_time: f32,
+}
+
+/// Load the example set from the catalog file at `catalog`, or the set
+/// bundled with the binary.
+pub fn load_examples(catalog: Option<&Path>) -> Result<ExampleSet> {
+    let Some(path) = catalog else {
+        return bundled_examples();
+    };
+    let content = std::fs::read_to_string(path).map_err(|e| Error::RegistryRead {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    serde_json::from_str(&content).map_err(|e| Error::RegistryParse {
+        path: path.to_path_buf(),
+        source: e,
+    })
+}
+
+/// Load the example set bundled with the binary.
+fn bundled_examples() -> Result<ExampleSet> {
+    const BUNDLED_EXAMPLES: &str = include_str!("../../data/examples.json");
+
+    serde_json::from_str(BUNDLED_EXAMPLES).map_err(|e| Error::RegistryParse {
+        path: PathBuf::from("bundled://examples.json"),
+        source: e,
+    })
+}
+
+/// Download the recordings of `set` into `dir` and write the set next to
+/// them as `examples.json`.
+///
+/// Recordings already in `dir` are kept unless `force` is set or they do not
+/// match their expected hash.
+pub async fn fetch_examples(
+    set: &ExampleSet,
+    dir: &Path,
+    force: bool,
+    connections: usize,
+    reporter: Option<&dyn ProgressReporter>,
+    retry: RetryPolicy,
+) -> Result<Vec<FetchedExample>> {
+    std::fs::create_dir_all(dir).map_err(Error::Io)?;
+
+    let mut fetched = Vec::with_capacity(set.examples.len());
+    let mut downloads: Vec<Download<'_>> = Vec::new();
+    for example in &set.examples {
+        let path = dir.join(&example.filename);
+        let downloaded = force || !is_intact(&path, &example.sha256)?;
+        if downloaded {
+            downloads.push((
+                example.url.as_str(),
+                path.clone(),
+                Some(example.sha256.as_str()),
+            ));
+        }
+        fetched.push(FetchedExample {
+            id: example.id.clone(),
+            path,
+            downloaded,
+        });
+    }
+
+    if !downloads.is_empty() {
+        let client = http_client()?;
+        download_files(
+            &client,
+            &downloads,
+            "examples",
+            connections,
+            reporter,
+            retry,
+        )
+        .await?;
+    }
+
+    let manifest =
+        serde_json::to_string_pretty(set).map_err(|e| Error::RegistrySerialize { source: e })?;
+    std::fs::write(dir.join(MANIFEST_NAME), manifest).map_err(Error::Io)?;
+    Ok(fetched)
+}
+
+/// Whether `path` exists and matches `sha256`.
+fn is_intact(path: &Path, sha256: &str) -> Result<bool> {
+    if !path.exists() {
+        return Ok(false);
+    }
+    Ok(sha256_file(path)?.eq_ignore_ascii_case(sha256))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_examples_parse() {
+        let set = bundled_examples().unwrap();
+        for example in &set.examples {
+            assert!(example.url.starts_with("https://"));
+            assert!(!example.filename.contains('/'));
+            assert_eq!(example.sha256.len(), 64);
+            for expected in &example.expected {
+                assert!(expected.start_time <= expected.end_time);
+            }
+        }
+    }
+
+    #[test]
+    fn test_load_examples_requires_checksums() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("catalog.json");
+        let example = r#"{"id": "a", "filename": "a.flac", "url": "https://example.org/a.flac",
+            "license": "CC0-1.0", "attribution": "someone""#;
+
+        std::fs::write(
+            &path,
+            format!(r#"{{"version": 1, "examples": [{{{example}}}]}}"#),
+        )
+        .unwrap();
+        assert!(load_examples(Some(&path)).is_err());
+
+        let sha = "0".repeat(64);
+        std::fs::write(
+            &path,
+            format!(r#"{{"version": 1, "examples": [{{{example}, "sha256": "{sha}"}}]}}"#),
+        )
+        .unwrap();
+        assert_eq!(load_examples(Some(&path)).unwrap().examples[0].sha256, sha);
+    }
+
+    #[test]
+    fn test_is_intact() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.flac");
+        let abc = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert!(!is_intact(&path, abc).unwrap());
+
+        std::fs::write(&path, b"abc").unwrap();
+        assert!(is_intact(&path, abc).unwrap());
+        assert!(!is_intact(&path, &"0".repeat(64)).unwrap());
+    }
+}
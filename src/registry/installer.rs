@@ -1,13 +1,26 @@
 //! Model download and installation logic.
 
-use super::types::ModelEntry;
+use super::lockfile::LockedModel;
+use super::types::{FileInfo, ModelEntry};
+use crate::config::Config;
+use crate::constants::registry::{
+    DEFAULT_DOWNLOAD_RETRIES, DEFAULT_RETRY_DELAY_SECS, MAX_RETRY_DELAY_SECS,
+};
 use crate::error::{Error, Result};
-use futures_util::StreamExt;
+use crate::output::{
+    DownloadProgress, FileCheckStatus, ModelFileCheck, ProgressReporter, ProgressThrottler,
+};
+use crate::utils::hash::sha256_file;
+use futures_util::{StreamExt, TryStreamExt, stream};
 use indicatif::{ProgressBar, ProgressStyle};
-use reqwest::Client;
+use reqwest::header::{CONTENT_RANGE, ETAG, HeaderMap, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::{Client, StatusCode};
+use std::fmt::Write as _;
 use std::path::{Path, PathBuf};
-use tokio::fs::File;
+use std::time::Duration;
+use tokio::fs::{File, OpenOptions};
 use tokio::io::AsyncWriteExt;
+use tracing::{debug, warn};
 
 /// Result of model installation.
 #[derive(Debug)]
@@ -24,78 +37,573 @@ pub struct InstalledModel {
     pub bsg_migration: Option<PathBuf>,
     /// Path to downloaded BSG distribution maps file (if available).
     pub bsg_distribution_maps: Option<PathBuf>,
+    /// Paths to all downloaded files, including every labels language.
+    pub files: Vec<PathBuf>,
+}
+
+/// Throttled `DownloadProgress` events for one downloaded file.
+struct DownloadEvents<'a> {
+    reporter: &'a dyn ProgressReporter,
+    model: &'a str,
+    file: String,
+    total_bytes: u64,
+    attempt: u32,
+    throttler: ProgressThrottler,
+}
+
+impl DownloadEvents<'_> {
+    /// Emit an event for `downloaded` bytes, throttled unless `done`.
+    fn update(&self, downloaded: u64, done: bool) {
+        #[allow(clippy::cast_precision_loss)]
+        let percent = if done {
+            100.0
+        } else if self.total_bytes > 0 {
+            (downloaded as f32 / self.total_bytes as f32 * 100.0).min(99.9)
+        } else {
+            0.0
+        };
+        // Without a known size only the start and the end are reported
+        let emit = done
+            || downloaded == 0
+            || (self.total_bytes > 0 && percent >= 1.0 && self.throttler.should_emit(percent));
+        if emit {
+            self.reporter.download_progress(&DownloadProgress {
+                operation: "download".to_string(),
+                model: self.model.to_string(),
+                file: self.file.clone(),
+                downloaded_bytes: downloaded,
+                total_bytes: if done { downloaded } else { self.total_bytes },
+                percent,
+                attempt: self.attempt,
+            });
+        }
+    }
+}
+
+/// Style of download progress bars.
+fn progress_style() -> Result<ProgressStyle> {
+    Ok(ProgressStyle::default_bar()
+        .template("{msg}\n{bar:40.cyan/blue} {percent}% ({bytes}/{total_bytes})")
+        .map_err(|e| Error::Internal {
+            message: format!("Failed to create progress bar: {e}"),
+        })?
+        .progress_chars("█▓▒░ "))
+}
+
+/// Progress bar for a download, hidden when progress is reported as events.
+fn progress_bar(reporter: Option<&dyn ProgressReporter>, message: String) -> Result<ProgressBar> {
+    let pb = if reporter.is_some() {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new(0)
+    };
+    pb.set_style(progress_style()?);
+    pb.set_message(message);
+    Ok(pb)
+}
+
+/// A file to download: URL, destination and optional expected SHA256 hash.
+pub type Download<'a> = (&'a str, PathBuf, Option<&'a str>);
+
+/// How failed downloads are retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt.
+    pub retries: u32,
+    /// Delay before the first retry, doubled for each further retry.
+    pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            retries: DEFAULT_DOWNLOAD_RETRIES,
+            backoff: Duration::from_secs(DEFAULT_RETRY_DELAY_SECS),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Retry policy of `config`, with unset values taken from the defaults.
+    pub fn from_config(config: &Config) -> Self {
+        let defaults = Self::default();
+        Self {
+            retries: config.registry.download_retries.unwrap_or(defaults.retries),
+            backoff: config
+                .registry
+                .retry_delay_secs
+                .map_or(defaults.backoff, Duration::from_secs),
+        }
+    }
+
+    /// Delay before retry number `retry` (1-based).
+    fn delay(&self, retry: u32) -> Duration {
+        self.backoff
+            .saturating_mul(1 << retry.saturating_sub(1).min(16))
+            .min(Duration::from_secs(MAX_RETRY_DELAY_SECS))
+    }
+}
+
+/// Shared state of the files downloaded by one install.
+struct Transfer<'a> {
+    client: &'a Client,
+    model: &'a str,
+    reporter: Option<&'a dyn ProgressReporter>,
+    pb: &'a ProgressBar,
+    retry: RetryPolicy,
+}
+
+/// A failed download attempt.
+struct AttemptError {
+    error: Error,
+    /// Whether another attempt may succeed (network errors, server errors).
+    retryable: bool,
+}
+
+impl AttemptError {
+    fn retryable(url: &str, source: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        Self {
+            error: Error::DownloadFailed {
+                url: url.to_string(),
+                source: source.into(),
+            },
+            retryable: true,
+        }
+    }
+
+    fn fatal(error: Error) -> Self {
+        Self {
+            error,
+            retryable: false,
+        }
+    }
+}
+
+/// Bytes of one file counted on the shared progress bar.
+#[derive(Default)]
+struct Counted {
+    length: u64,
+    position: u64,
+}
+
+impl Counted {
+    /// Move the file's share of `pb` to `length` total and `position` done.
+    fn rebase(&mut self, pb: &ProgressBar, length: u64, position: u64) {
+        let bar_length = pb.length().unwrap_or(0);
+        pb.set_length(bar_length.saturating_sub(self.length) + length);
+        pb.set_position(pb.position().saturating_sub(self.position) + position);
+        self.length = length;
+        self.position = position;
+    }
+}
+
+/// HTTP client with timeouts for all downloads.
+pub(super) fn http_client() -> Result<Client> {
+    Client::builder()
+        .connect_timeout(std::time::Duration::from_secs(30))
+        .timeout(std::time::Duration::from_mins(5))
+        .build()
+        .map_err(|e| Error::Internal {
+            message: format!("Failed to create HTTP client: {e}"),
+        })
 }
 
 /// Download a file with progress bar.
-pub async fn download_file(client: &Client, url: &str, dest: &Path) -> Result<()> {
-    let response = client
-        .get(url)
+///
+/// With a `reporter`, progress of `model` is reported as `DownloadProgress`
+/// events instead of a terminal progress bar. If `sha256` is given, the
+/// downloaded file must match it. Interrupted downloads are retried and
+/// resumed according to `retry`.
+pub async fn download_file(
+    client: &Client,
+    url: &str,
+    dest: &Path,
+    sha256: Option<&str>,
+    model: &str,
+    reporter: Option<&dyn ProgressReporter>,
+    retry: RetryPolicy,
+) -> Result<()> {
+    // Use to_string_lossy() to handle non-UTF-8 filenames gracefully
+    let pb = progress_bar(
+        reporter,
+        format!(
+            "Downloading {}...",
+            dest.file_name().map_or_else(
+                || std::borrow::Cow::Borrowed("file"),
+                |n| n.to_string_lossy()
+            )
+        ),
+    )?;
+    let transfer = Transfer {
+        client,
+        model,
+        reporter,
+        pb: &pb,
+        retry,
+    };
+    download_to(&transfer, url, dest, sha256).await?;
+    pb.finish_with_message("Download complete");
+    Ok(())
+}
+
+/// Download several files with at most `connections` in flight at once.
+///
+/// A single progress bar aggregates the bytes of all files; files are added
+/// to its total as their responses arrive.
+pub async fn download_files(
+    client: &Client,
+    downloads: &[Download<'_>],
+    model: &str,
+    connections: usize,
+    reporter: Option<&dyn ProgressReporter>,
+    retry: RetryPolicy,
+) -> Result<()> {
+    let pb = progress_bar(
+        reporter,
+        format!("Downloading {} files for {model}...", downloads.len()),
+    )?;
+    let transfer = Transfer {
+        client,
+        model,
+        reporter,
+        pb: &pb,
+        retry,
+    };
+    stream::iter(downloads)
+        .map(|(url, dest, sha256)| download_to(&transfer, url, dest, *sha256))
+        .buffer_unordered(connections.max(1))
+        .try_collect::<Vec<()>>()
+        .await?;
+    pb.finish_with_message("Download complete");
+    Ok(())
+}
+
+/// Download `url` to `dest`, retrying failed attempts with backoff.
+///
+/// Bytes are written to `<dest>.part`, and a retry (or a later install)
+/// resumes from its end with an HTTP range request guarded by `If-Range`, so
+/// a file that changed on the server is downloaded again. With an expected
+/// `sha256`, the finished file is verified before it is moved to `dest` and
+/// removed if it does not match.
+async fn download_to(
+    transfer: &Transfer<'_>,
+    url: &str,
+    dest: &Path,
+    sha256: Option<&str>,
+) -> Result<()> {
+    let part = partial_path(dest);
+    let mut counted = Counted::default();
+    let mut attempt = 1;
+    loop {
+        match fetch(transfer, url, dest, attempt, &mut counted).await {
+            Ok(()) => break,
+            Err(failure) if failure.retryable && attempt <= transfer.retry.retries => {
+                let delay = transfer.retry.delay(attempt);
+                warn!(
+                    "Download of {} failed (attempt {attempt}/{}): {}; retrying in {:.1}s",
+                    file_name(dest),
+                    transfer.retry.retries + 1,
+                    error_chain(&failure.error),
+                    delay.as_secs_f64()
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(failure) => return Err(failure.error),
+        }
+    }
+
+    if let Some(expected) = sha256 {
+        let path = part.clone();
+        let actual = tokio::task::spawn_blocking(move || sha256_file(&path))
+            .await
+            .map_err(|e| Error::Internal {
+                message: format!("Checksum task failed: {e}"),
+            })??;
+        if !actual.eq_ignore_ascii_case(expected) {
+            // Never leave a corrupted or tampered file where it could be
+            // loaded or resumed
+            discard_partial(dest).await;
+            return Err(Error::DownloadChecksumMismatch {
+                file: file_name(dest),
+                expected: expected.to_string(),
+                actual,
+            });
+        }
+    }
+
+    tokio::fs::rename(&part, dest).await.map_err(Error::Io)?;
+    let _ = tokio::fs::remove_file(validator_path(dest)).await;
+    Ok(())
+}
+
+/// One attempt at streaming `url` into the partial file of `dest`, resuming
+/// from its length.
+async fn fetch(
+    transfer: &Transfer<'_>,
+    url: &str,
+    dest: &Path,
+    attempt: u32,
+    counted: &mut Counted,
+) -> std::result::Result<(), AttemptError> {
+    let part = &partial_path(dest);
+    let validator_file = validator_path(dest);
+    let offset = tokio::fs::metadata(part).await.map_or(0, |m| m.len());
+    // Without the validator of the partial file it cannot be proven to be a
+    // prefix of the remote file, so the download starts over
+    let validator = if offset > 0 {
+        tokio::fs::read_to_string(&validator_file).await.ok()
+    } else {
+        None
+    };
+    let mut request = transfer.client.get(url);
+    if let Some(validator) = &validator {
+        request = request
+            .header(RANGE, format!("bytes={offset}-"))
+            .header(IF_RANGE, validator.trim());
+    }
+    let response = request
         .send()
         .await
-        .map_err(|e| Error::DownloadFailed {
-            url: url.to_string(),
-            source: Box::new(e),
-        })?;
+        .map_err(|e| AttemptError::retryable(url, e))?;
 
-    if !response.status().is_success() {
-        return Err(Error::DownloadFailed {
+    let status = response.status();
+    if status == StatusCode::RANGE_NOT_SATISFIABLE {
+        // The partial file is not a prefix of the remote file; start over
+        discard_partial(dest).await;
+        return Err(AttemptError::retryable(url, format!("HTTP {status}")));
+    }
+    if !status.is_success() {
+        let error = Error::DownloadFailed {
             url: url.to_string(),
-            source: format!("HTTP {}", response.status()).into(),
-        });
+            source: format!("HTTP {status}").into(),
+        };
+        let retryable = status.is_server_error()
+            || status == StatusCode::REQUEST_TIMEOUT
+            || status == StatusCode::TOO_MANY_REQUESTS;
+        return Err(AttemptError { error, retryable });
     }
 
-    let total_size = response.content_length().unwrap_or(0);
+    // Servers that ignore the range, or whose file changed since the partial
+    // download (If-Range), send the whole file again
+    let resumed = validator.is_some() && status == StatusCode::PARTIAL_CONTENT;
+    if resumed && content_range_start(response.headers()) != Some(offset) {
+        discard_partial(dest).await;
+        return Err(AttemptError::retryable(
+            url,
+            format!("server resumed at an unexpected position instead of byte {offset}"),
+        ));
+    }
+    if !resumed {
+        match response_validator(response.headers()) {
+            Some(value) => tokio::fs::write(&validator_file, value).await,
+            None => match tokio::fs::remove_file(&validator_file).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            },
+        }
+        .map_err(|e| AttemptError::fatal(Error::Io(e)))?;
+    }
+    let start = if resumed { offset } else { 0 };
+    let total_size = response.content_length().map_or(0, |len| start + len);
+    counted.rebase(transfer.pb, total_size, start);
+    if resumed {
+        debug!("Resuming {} at {start} bytes", part.display());
+    }
 
-    // Create progress bar
-    let pb = ProgressBar::new(total_size);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{msg}\n{bar:40.cyan/blue} {percent}% ({bytes}/{total_bytes})")
-            .map_err(|e| Error::Internal {
-                message: format!("Failed to create progress bar: {e}"),
-            })?
-            .progress_chars("█▓▒░ "),
-    );
-    // Use to_string_lossy() to handle non-UTF-8 filenames gracefully
-    pb.set_message(format!(
-        "Downloading {}...",
-        dest.file_name().map_or_else(
-            || std::borrow::Cow::Borrowed("file"),
-            |n| n.to_string_lossy()
-        )
-    ));
-
-    // Stream download
-    let mut file = File::create(dest).await.map_err(Error::Io)?;
+    let events = transfer.reporter.map(|reporter| DownloadEvents {
+        reporter,
+        model: transfer.model,
+        file: file_name(dest),
+        total_bytes: total_size,
+        attempt,
+        throttler: ProgressThrottler::new(),
+    });
+
+    let mut file = if resumed {
+        OpenOptions::new().append(true).open(part).await
+    } else {
+        File::create(part).await
+    }
+    .map_err(|e| AttemptError::fatal(Error::Io(e)))?;
     let mut stream = response.bytes_stream();
-    let mut downloaded = 0u64;
+    let mut downloaded = start;
+    if let Some(events) = &events {
+        events.update(downloaded, false);
+    }
 
     while let Some(chunk) = stream.next().await {
-        let chunk = chunk.map_err(|e| Error::DownloadFailed {
-            url: url.to_string(),
-            source: Box::new(e),
-        })?;
+        let chunk = chunk.map_err(|e| AttemptError::retryable(url, e))?;
 
-        file.write_all(&chunk).await.map_err(Error::Io)?;
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| AttemptError::fatal(Error::Io(e)))?;
 
         downloaded += chunk.len() as u64;
-        pb.set_position(downloaded);
+        counted.position = downloaded;
+        transfer.pb.inc(chunk.len() as u64);
+        if let Some(events) = &events {
+            events.update(downloaded, false);
+        }
     }
+    file.flush()
+        .await
+        .map_err(|e| AttemptError::fatal(Error::Io(e)))?;
 
-    pb.finish_with_message("Download complete");
+    if let Some(events) = &events {
+        events.update(downloaded, true);
+    }
+    Ok(())
+}
+
+/// Path of the partial download of `dest`.
+fn partial_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest.with_file_name(name)
+}
 
-    // TODO: Implement SHA256 checksum verification for downloaded files
-    // Currently all SHA256 checksums in registry.json are null, which is a security risk.
-    // Future work should:
-    // 1. Generate SHA256 checksums for all model and label files
-    // 2. Update registry.json with the checksums
-    // 3. Verify downloaded files against checksums here before returning
-    // See: https://github.com/tphakala/birda/issues/XX
+/// Path of the file holding the `ETag` or `Last-Modified` value of the
+/// partial download of `dest`.
+fn validator_path(dest: &Path) -> PathBuf {
+    let mut name = partial_path(dest).into_os_string();
+    name.push(".validator");
+    PathBuf::from(name)
+}
 
-    Ok(())
+/// Remove the partial download of `dest` and its validator.
+async fn discard_partial(dest: &Path) {
+    let _ = tokio::fs::remove_file(partial_path(dest)).await;
+    let _ = tokio::fs::remove_file(validator_path(dest)).await;
+}
+
+/// The value to send in `If-Range` when resuming a download of this
+/// response: a strong `ETag`, or else `Last-Modified`.
+fn response_validator(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(ETAG)
+        .and_then(|v| v.to_str().ok())
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| headers.get(LAST_MODIFIED).and_then(|v| v.to_str().ok()))
+}
+
+/// First byte position of a `Content-Range: bytes <start>-<end>/<total>`
+/// header.
+fn content_range_start(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .strip_prefix("bytes ")?
+        .split_once('-')?
+        .0
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// `error` followed by its sources, for retry warnings.
+fn error_chain(error: &Error) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        let _ = write!(message, ": {cause}");
+        source = cause.source();
+    }
+    message
 }
 
-/// Get models directory path.
+/// File name of `path` for messages.
+fn file_name(path: &Path) -> String {
+    path.file_name().map_or_else(
+        || path.to_string_lossy().to_string(),
+        |n| n.to_string_lossy().to_string(),
+    )
+}
+
+/// Expected SHA-256 of downloaded file `filename`: the hash pinned in
+/// `locked`, else the `registry` hash.
+fn expected_hash<'a>(
+    locked: Option<&'a LockedModel>,
+    filename: &str,
+    registry: Option<&'a str>,
+) -> Option<&'a str> {
+    locked
+        .and_then(|l| l.files.get(filename))
+        .map(String::as_str)
+        .or(registry)
+}
+
+/// Queue an optional model file for download, returning its destination.
+fn queue_optional<'a>(
+    downloads: &mut Vec<Download<'a>>,
+    models_dir: &Path,
+    info: Option<&'a FileInfo>,
+    locked: Option<&'a LockedModel>,
+) -> Option<PathBuf> {
+    info.map(|info| {
+        let dest = models_dir.join(&info.filename);
+        let sha256 = expected_hash(locked, &info.filename, info.sha256.as_deref());
+        downloads.push((info.url.as_str(), dest.clone(), sha256));
+        dest
+    })
+}
+
+/// Verify the files of `model` installed in `dir`.
+///
+/// Each file is checked against the SHA256 hash declared in the registry, or
+/// else the hash pinned in `locked`. Files with neither are reported as
+/// unverified. Label files only have lockfile hashes.
+pub fn verify_installed(
+    model: &ModelEntry,
+    dir: &Path,
+    locked: Option<&LockedModel>,
+) -> Result<Vec<ModelFileCheck>> {
+    let files = &model.files;
+    let registry_files = std::iter::once(&files.model)
+        .chain(files.meta_model.as_ref())
+        .chain(files.bsg_calibration.as_ref())
+        .chain(files.bsg_migration.as_ref())
+        .chain(files.bsg_distribution_maps.as_ref())
+        .map(|info| (info.filename.as_str(), info.sha256.as_deref()));
+    let label_files = files
+        .labels
+        .languages
+        .iter()
+        .map(|l| (l.filename.as_str(), None));
+
+    registry_files
+        .chain(label_files)
+        .map(|(name, declared)| {
+            let path = dir.join(name);
+            let expected = declared
+                .map(str::to_ascii_lowercase)
+                .or_else(|| locked.and_then(|l| l.files.get(name).cloned()));
+            if !path.exists() {
+                return Ok(ModelFileCheck {
+                    file: name.to_string(),
+                    status: FileCheckStatus::Missing,
+                    expected,
+                    actual: None,
+                });
+            }
+            let actual = sha256_file(&path)?;
+            let status = match &expected {
+                Some(expected) if *expected == actual => FileCheckStatus::Ok,
+                Some(_) => FileCheckStatus::Mismatch,
+                None => FileCheckStatus::Unverified,
+            };
+            Ok(ModelFileCheck {
+                file: name.to_string(),
+                status,
+                expected,
+                actual: Some(actual),
+            })
+        })
+        .collect()
+}
+
+/// Get the default models directory path.
 pub fn models_dir() -> Result<PathBuf> {
     let data_dir = directories::ProjectDirs::from("", "", "birda")
         .ok_or(Error::ConfigDirNotFound)?
@@ -105,14 +613,39 @@ pub fn models_dir() -> Result<PathBuf> {
     Ok(data_dir.join("models"))
 }
 
-/// Install model from registry entry.
+/// Get the models directory of `config`: `registry.models_dir` if set,
+/// otherwise the default models directory.
+pub fn configured_models_dir(config: &Config) -> Result<PathBuf> {
+    config
+        .registry
+        .models_dir
+        .clone()
+        .map_or_else(models_dir, Ok)
+}
+
+/// Install model from registry entry into `models_dir`.
 ///
 /// Downloads the model file, all available language label files,
 /// and meta model if available. Returns paths to all downloaded files.
 /// The `language` parameter determines which labels file is set as the default.
-pub async fn install_model(model: &ModelEntry, language: Option<&str>) -> Result<InstalledModel> {
-    let models_dir = models_dir()?;
-    std::fs::create_dir_all(&models_dir).map_err(Error::Io)?;
+/// Up to `connections` files are downloaded concurrently, each retried and
+/// resumed according to `retry`. With a `reporter`, download progress is
+/// reported as events.
+///
+/// Files pinned in `locked` must match their locked hash, otherwise the
+/// registry hash if it declares one. Each file is verified before it
+/// replaces an installed copy, so a refused download leaves the previous
+/// install intact.
+pub async fn install_model(
+    model: &ModelEntry,
+    language: Option<&str>,
+    models_dir: &Path,
+    connections: usize,
+    reporter: Option<&dyn ProgressReporter>,
+    retry: RetryPolicy,
+    locked: Option<&LockedModel>,
+) -> Result<InstalledModel> {
+    std::fs::create_dir_all(models_dir).map_err(Error::Io)?;
 
     // Determine which language to use as default
     let language_code = language.unwrap_or(&model.files.labels.default_language);
@@ -129,64 +662,57 @@ pub async fn install_model(model: &ModelEntry, language: Option<&str>) -> Result
             model_id: model.id.clone(),
         })?;
 
-    // Create HTTP client with timeouts for all downloads
-    let client = Client::builder()
-        .connect_timeout(std::time::Duration::from_secs(30))
-        .timeout(std::time::Duration::from_mins(5))
-        .build()
-        .map_err(|e| Error::Internal {
-            message: format!("Failed to create HTTP client: {e}"),
-        })?;
+    let client = http_client()?;
 
-    // Download model file
+    // Collect all files: the model, ALL language label files and the
+    // optional meta model and BSG files
     let model_dest = models_dir.join(&model.files.model.filename);
-    download_file(&client, &model.files.model.url, &model_dest).await?;
-
-    // Download ALL language label files
+    let mut downloads = vec![(
+        model.files.model.url.as_str(),
+        model_dest.clone(),
+        expected_hash(
+            locked,
+            &model.files.model.filename,
+            model.files.model.sha256.as_deref(),
+        ),
+    )];
     for language_variant in &model.files.labels.languages {
-        let labels_dest = models_dir.join(&language_variant.filename);
-        download_file(&client, &language_variant.url, &labels_dest).await?;
+        downloads.push((
+            language_variant.url.as_str(),
+            models_dir.join(&language_variant.filename),
+            expected_hash(locked, &language_variant.filename, None),
+        ));
     }
+    let meta_model_path = queue_optional(
+        &mut downloads,
+        models_dir,
+        model.files.meta_model.as_ref(),
+        locked,
+    );
+    let bsg_calibration_path = queue_optional(
+        &mut downloads,
+        models_dir,
+        model.files.bsg_calibration.as_ref(),
+        locked,
+    );
+    let bsg_migration_path = queue_optional(
+        &mut downloads,
+        models_dir,
+        model.files.bsg_migration.as_ref(),
+        locked,
+    );
+    let bsg_maps_path = queue_optional(
+        &mut downloads,
+        models_dir,
+        model.files.bsg_distribution_maps.as_ref(),
+        locked,
+    );
+
+    download_files(&client, &downloads, &model.id, connections, reporter, retry).await?;
 
     // Set the default labels path to the requested/default language
     let labels_dest = models_dir.join(&default_language_variant.filename);
 
-    // Download meta model if available
-    let meta_model_path = if let Some(meta_info) = &model.files.meta_model {
-        let meta_dest = models_dir.join(&meta_info.filename);
-        download_file(&client, &meta_info.url, &meta_dest).await?;
-        Some(meta_dest)
-    } else {
-        None
-    };
-
-    // Download BSG calibration file if available
-    let bsg_calibration_path = if let Some(cal_info) = &model.files.bsg_calibration {
-        let cal_dest = models_dir.join(&cal_info.filename);
-        download_file(&client, &cal_info.url, &cal_dest).await?;
-        Some(cal_dest)
-    } else {
-        None
-    };
-
-    // Download BSG migration file if available
-    let bsg_migration_path = if let Some(mig_info) = &model.files.bsg_migration {
-        let mig_dest = models_dir.join(&mig_info.filename);
-        download_file(&client, &mig_info.url, &mig_dest).await?;
-        Some(mig_dest)
-    } else {
-        None
-    };
-
-    // Download BSG distribution maps file if available
-    let bsg_maps_path = if let Some(maps_info) = &model.files.bsg_distribution_maps {
-        let maps_dest = models_dir.join(&maps_info.filename);
-        download_file(&client, &maps_info.url, &maps_dest).await?;
-        Some(maps_dest)
-    } else {
-        None
-    };
-
     Ok(InstalledModel {
         model: model_dest,
         labels: labels_dest,
@@ -194,6 +720,7 @@ pub async fn install_model(model: &ModelEntry, language: Option<&str>) -> Result
         bsg_calibration: bsg_calibration_path,
         bsg_migration: bsg_migration_path,
         bsg_distribution_maps: bsg_maps_path,
+        files: downloads.into_iter().map(|(_, dest, _)| dest).collect(),
     })
 }
 
@@ -211,6 +738,18 @@ mod tests {
         assert!(path.to_string_lossy().ends_with("models"));
     }
 
+    #[test]
+    fn test_configured_models_dir_override() {
+        let mut config = Config::default();
+        assert_eq!(configured_models_dir(&config).ok(), models_dir().ok());
+
+        config.registry.models_dir = Some(PathBuf::from("/mnt/lab/models"));
+        assert_eq!(
+            configured_models_dir(&config).ok(),
+            Some(PathBuf::from("/mnt/lab/models"))
+        );
+    }
+
     #[test]
     fn test_installed_model_default_labels_path() {
         let installed = InstalledModel {
@@ -220,6 +759,7 @@ mod tests {
             bsg_calibration: None,
             bsg_migration: None,
             bsg_distribution_maps: None,
+            files: Vec::new(),
         };
 
         assert_eq!(
@@ -227,4 +767,169 @@ mod tests {
             "/models/birdnet-v24-en.txt"
         );
     }
+
+    #[test]
+    fn test_verify_installed_file_statuses() {
+        use crate::registry::types::{LabelsInfo, LanguageVariant, LicenseInfo, ModelFiles};
+        use std::collections::BTreeMap;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("model.onnx"), b"hello world").unwrap();
+        std::fs::write(dir.path().join("meta.onnx"), b"tampered").unwrap();
+        std::fs::write(dir.path().join("labels-en.txt"), b"Turdus merula").unwrap();
+
+        let file = |filename: &str, sha256: Option<&str>| FileInfo {
+            url: String::new(),
+            filename: filename.to_string(),
+            sha256: sha256.map(str::to_string),
+        };
+        let model = ModelEntry {
+            id: "birdnet-v24".to_string(),
+            name: "BirdNET".to_string(),
+            description: String::new(),
+            vendor: String::new(),
+            version: "2.4".to_string(),
+            model_type: "birdnet-v24".to_string(),
+            license: LicenseInfo {
+                r#type: "CC-BY-NC-SA-4.0".to_string(),
+                url: String::new(),
+                commercial_use: false,
+                attribution_required: true,
+                share_alike: true,
+            },
+            files: ModelFiles {
+                model: file(
+                    "model.onnx",
+                    Some("B94D27B9934D3E08A52E52D7DA7DABFAC484EFE37A5380EE9088F7ACE2EFCDE9"),
+                ),
+                labels: LabelsInfo {
+                    default_language: "en".to_string(),
+                    languages: ["en", "fi"]
+                        .iter()
+                        .map(|code| LanguageVariant {
+                            code: (*code).to_string(),
+                            name: (*code).to_string(),
+                            url: String::new(),
+                            filename: format!("labels-{code}.txt"),
+                        })
+                        .collect(),
+                },
+                meta_model: Some(file("meta.onnx", Some(&"0".repeat(64)))),
+                bsg_calibration: None,
+                bsg_migration: None,
+                bsg_distribution_maps: None,
+            },
+            recommended: false,
+        };
+
+        let statuses = |locked: Option<&LockedModel>| -> Vec<FileCheckStatus> {
+            verify_installed(&model, dir.path(), locked)
+                .unwrap()
+                .iter()
+                .map(|check| check.status)
+                .collect()
+        };
+        assert_eq!(
+            statuses(None),
+            [
+                FileCheckStatus::Ok,
+                FileCheckStatus::Mismatch,
+                FileCheckStatus::Unverified,
+                FileCheckStatus::Missing,
+            ]
+        );
+
+        // Label files fall back to the lockfile hashes
+        let locked = LockedModel {
+            version: "2.4".to_string(),
+            files: BTreeMap::from([(
+                "labels-en.txt".to_string(),
+                sha256_file(&dir.path().join("labels-en.txt")).unwrap(),
+            )]),
+        };
+        assert_eq!(statuses(Some(&locked))[2], FileCheckStatus::Ok);
+    }
+
+    #[test]
+    fn test_retry_delay_doubles_up_to_cap() {
+        let retry = RetryPolicy {
+            retries: 20,
+            backoff: Duration::from_secs(2),
+        };
+        assert_eq!(retry.delay(1), Duration::from_secs(2));
+        assert_eq!(retry.delay(2), Duration::from_secs(4));
+        assert_eq!(retry.delay(3), Duration::from_secs(8));
+        assert_eq!(retry.delay(20), Duration::from_secs(MAX_RETRY_DELAY_SECS));
+    }
+
+    #[test]
+    fn test_retry_policy_from_config() {
+        let mut config = Config::default();
+        assert_eq!(RetryPolicy::from_config(&config), RetryPolicy::default());
+
+        config.registry.download_retries = Some(0);
+        config.registry.retry_delay_secs = Some(10);
+        let retry = RetryPolicy::from_config(&config);
+        assert_eq!(retry.retries, 0);
+        assert_eq!(retry.backoff, Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_expected_hash_prefers_locked_hash() {
+        let locked = LockedModel {
+            version: "2.4".to_string(),
+            files: std::collections::BTreeMap::from([("model.onnx".to_string(), "aa".to_string())]),
+        };
+        assert_eq!(
+            expected_hash(Some(&locked), "model.onnx", Some("bb")),
+            Some("aa")
+        );
+        assert_eq!(
+            expected_hash(Some(&locked), "labels.txt", Some("bb")),
+            Some("bb")
+        );
+        assert_eq!(expected_hash(None, "model.onnx", None), None);
+    }
+
+    #[test]
+    fn test_partial_path_appends_suffix() {
+        assert_eq!(
+            partial_path(Path::new("/models/model.onnx")),
+            PathBuf::from("/models/model.onnx.part")
+        );
+        assert_eq!(
+            validator_path(Path::new("/models/model.onnx")),
+            PathBuf::from("/models/model.onnx.part.validator")
+        );
+    }
+
+    #[test]
+    fn test_response_validator_prefers_strong_etag() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            LAST_MODIFIED,
+            "Wed, 01 Jan 2025 00:00:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(
+            response_validator(&headers),
+            Some("Wed, 01 Jan 2025 00:00:00 GMT")
+        );
+        headers.insert(ETAG, "W/\"weak\"".parse().unwrap());
+        assert_eq!(
+            response_validator(&headers),
+            Some("Wed, 01 Jan 2025 00:00:00 GMT")
+        );
+        headers.insert(ETAG, "\"abc\"".parse().unwrap());
+        assert_eq!(response_validator(&headers), Some("\"abc\""));
+    }
+
+    #[test]
+    fn test_content_range_start() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(content_range_start(&headers), None);
+        headers.insert(CONTENT_RANGE, "bytes 1024-2047/2048".parse().unwrap());
+        assert_eq!(content_range_start(&headers), Some(1024));
+        headers.insert(CONTENT_RANGE, "bytes */2048".parse().unwrap());
+        assert_eq!(content_range_start(&headers), None);
+    }
 }
@@ -1,8 +1,13 @@
 //! Registry loading and bootstrapping.
 
 use super::types::{ModelEntry, Registry};
+use crate::config::Config;
+use crate::constants::registry::{SOURCE_CACHE_DIR, SOURCE_TIMEOUT_SECS};
 use crate::error::{Error, Result};
-use std::path::PathBuf;
+use crate::utils::hash::hex_digest;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Load registry from user config or bundled default.
 ///
@@ -48,6 +53,116 @@ pub fn load_registry() -> Result<Registry> {
     }
 }
 
+/// Load the registry and merge the models of the `registry.sources` of
+/// `config` into it.
+///
+/// Sources are registry JSON files given as `http(s)://` URLs or local paths,
+/// merged in order; a model whose ID is already known replaces the earlier
+/// entry. Fetched remote registries are cached, and an unreachable source
+/// falls back to its cached copy. Sources that cannot be loaded at all are
+/// skipped with a warning.
+pub fn load_configured_registry(config: &Config) -> Result<Registry> {
+    let mut registry = load_registry()?;
+    for source in &config.registry.sources {
+        match load_source(source) {
+            Ok(extra) => merge_source(&mut registry, extra, source),
+            Err(e) => tracing::warn!("Skipping registry source {source}: {e}"),
+        }
+    }
+    Ok(registry)
+}
+
+/// Load the registry of one configured source.
+fn load_source(source: &str) -> Result<Registry> {
+    if !is_remote(source) {
+        let path = source.strip_prefix("file://").unwrap_or(source);
+        return load_from_file(Path::new(path));
+    }
+
+    let cache_path = source_cache_path(source)?;
+    match fetch_source(source) {
+        Ok(content) => {
+            let registry = serde_json::from_str(&content).map_err(|e| Error::RegistryParse {
+                path: PathBuf::from(source),
+                source: e,
+            })?;
+            if let Some(parent) = cache_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            if let Err(e) = std::fs::write(&cache_path, content) {
+                tracing::debug!("Failed to cache registry source {source}: {e}");
+            }
+            Ok(registry)
+        }
+        Err(e) if cache_path.exists() => {
+            tracing::warn!("Failed to fetch registry source {source}: {e}. Using cached copy.");
+            load_from_file(&cache_path)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Whether `source` is fetched over HTTP(S) rather than read from disk.
+fn is_remote(source: &str) -> bool {
+    source.starts_with("https://") || source.starts_with("http://")
+}
+
+/// Download the registry JSON at `url`.
+fn fetch_source(url: &str) -> Result<String> {
+    let download_error = |e: reqwest::Error| Error::DownloadFailed {
+        url: url.to_string(),
+        source: Box::new(e),
+    };
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| Error::Internal {
+            message: format!("Failed to create async runtime: {e}"),
+        })?;
+    runtime.block_on(async {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(SOURCE_TIMEOUT_SECS))
+            .build()
+            .map_err(download_error)?;
+        let response = client.get(url).send().await.map_err(download_error)?;
+        if !response.status().is_success() {
+            return Err(Error::DownloadFailed {
+                url: url.to_string(),
+                source: format!("HTTP {}", response.status()).into(),
+            });
+        }
+        response.text().await.map_err(download_error)
+    })
+}
+
+/// Cache file of the remote registry `url`.
+fn source_cache_path(url: &str) -> Result<PathBuf> {
+    let mut hasher = Sha256::new();
+    hasher.update(url.as_bytes());
+    Ok(crate::config::config_dir()?
+        .join(SOURCE_CACHE_DIR)
+        .join(format!("{}.json", &hex_digest(hasher)[..16])))
+}
+
+/// Merge the models of `extra`, loaded from `source`, into `registry`.
+fn merge_source(registry: &mut Registry, extra: Registry, source: &str) {
+    for model in extra.models {
+        if let Some(existing) = registry.models.iter_mut().find(|m| m.id == model.id) {
+            // Replacing a model changes where its files are downloaded from,
+            // so make it visible on every load rather than only in debug logs
+            tracing::warn!(
+                "Registry source {source} replaces model {} (version {} -> {}); installs and updates of it use this source",
+                model.id,
+                existing.version,
+                model.version
+            );
+            *existing = model;
+        } else {
+            registry.models.push(model);
+        }
+    }
+}
+
 /// Get path to registry file in user config.
 fn registry_file_path() -> Result<PathBuf> {
     Ok(crate::config::config_dir()?.join("registry.json"))
@@ -228,4 +343,44 @@ mod tests {
         assert!(find_model(&registry, "perch-v2").is_some());
         assert!(find_model(&registry, "bsg-fi-v44").is_some());
     }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_merge_source_adds_and_replaces_models() {
+        let mut registry = load_bundled_registry().unwrap();
+        let count = registry.models.len();
+        let mut replaced = registry.models[0].clone();
+        replaced.version = "99.0".into();
+        let mut added = registry.models[0].clone();
+        added.id = "lab-finetuned".into();
+        let extra = Registry {
+            schema_version: "1.0".into(),
+            registry_version: 0,
+            models: vec![replaced.clone(), added],
+        };
+
+        merge_source(
+            &mut registry,
+            extra,
+            "https://internal.example/registry.json",
+        );
+        assert_eq!(registry.models.len(), count + 1);
+        assert_eq!(find_model(&registry, &replaced.id).unwrap().version, "99.0");
+        assert!(find_model(&registry, "lab-finetuned").is_some());
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_load_local_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lab-registry.json");
+        let registry = load_bundled_registry().unwrap();
+        std::fs::write(&path, serde_json::to_string(&registry).unwrap()).unwrap();
+
+        let source = path.to_string_lossy().to_string();
+        assert!(!is_remote(&source));
+        assert_eq!(load_source(&source).unwrap(), registry);
+        assert_eq!(load_source(&format!("file://{source}")).unwrap(), registry);
+        assert!(load_source(&dir.path().join("missing.json").to_string_lossy()).is_err());
+    }
 }
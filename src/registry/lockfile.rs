@@ -0,0 +1,323 @@
+//! Model lockfile pinning installed model versions and file hashes.
+//!
+//! Every `models install` records the model version and the SHA-256 hash of
+//! each downloaded file. Installs that would change a pinned version or hash
+//! are refused unless `--upgrade` is given, and `models check` verifies the
+//! files on disk against the lock; `models update` reinstalls models whose
+//! registry version is newer than the locked one. Sharing the lockfile (see
+//! [`LOCKFILE_ENV`]) pins the same models across machines.
+
+use super::types::{ModelEntry, Registry};
+use crate::constants::registry::{LOCKFILE_ENV, LOCKFILE_NAME};
+use crate::error::{Error, Result};
+use crate::utils::hash::sha256_file;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Pinned models by registry ID.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockfile {
+    /// Locked models by registry ID.
+    #[serde(default)]
+    pub models: BTreeMap<String, LockedModel>,
+}
+
+/// Pinned version and file hashes of one model.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedModel {
+    /// Registry version of the model.
+    pub version: String,
+    /// Hex SHA-256 hashes by file name.
+    pub files: BTreeMap<String, String>,
+}
+
+impl Lockfile {
+    /// Path of the lockfile: `$BIRDA_LOCKFILE` if set, otherwise
+    /// `birda.lock` in the config directory.
+    pub fn path() -> Result<PathBuf> {
+        match std::env::var_os(LOCKFILE_ENV) {
+            Some(path) if !path.is_empty() => Ok(PathBuf::from(path)),
+            _ => Ok(crate::config::config_dir()?.join(LOCKFILE_NAME)),
+        }
+    }
+
+    /// Load the lockfile, returning an empty lock if it does not exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(path)?;
+        toml::from_str(&contents).map_err(|e| Error::LockfileParse {
+            path: path.to_path_buf(),
+            source: e,
+        })
+    }
+
+    /// Write the lockfile, creating parent directories as needed.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let contents = toml::to_string_pretty(self).map_err(|e| Error::Internal {
+            message: format!("Failed to serialize lockfile: {e}"),
+        })?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| Error::LockfileWrite {
+                path: path.to_path_buf(),
+                source: e,
+            })?;
+        }
+        std::fs::write(path, contents).map_err(|e| Error::LockfileWrite {
+            path: path.to_path_buf(),
+            source: e,
+        })
+    }
+
+    /// Check that installing `model` keeps its pinned version.
+    ///
+    /// Always passes for unlocked models and with `upgrade`.
+    pub fn check_install(&self, model: &ModelEntry, upgrade: bool) -> Result<()> {
+        match self.models.get(&model.id) {
+            Some(locked) if !upgrade && locked.version != model.version => {
+                Err(Error::LockfileVersionMismatch {
+                    id: model.id.clone(),
+                    locked: locked.version.clone(),
+                    available: model.version.clone(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Pin `model` to its version and the hashes of its installed `files`.
+    ///
+    /// Without `upgrade`, a file whose hash differs from the pinned hash is
+    /// an error and the lock is left unchanged.
+    pub fn lock(&mut self, model: &ModelEntry, files: &[PathBuf], upgrade: bool) -> Result<()> {
+        let mut hashes = BTreeMap::new();
+        for file in files {
+            hashes.insert(file_name(file), sha256_file(file)?);
+        }
+
+        if !upgrade && let Some(locked) = self.models.get(&model.id) {
+            for (name, actual) in &hashes {
+                if let Some(expected) = locked.files.get(name)
+                    && expected != actual
+                {
+                    return Err(Error::LockfileHashMismatch {
+                        id: model.id.clone(),
+                        file: name.clone(),
+                        expected: expected.clone(),
+                        actual: actual.clone(),
+                    });
+                }
+            }
+        }
+
+        self.models.insert(
+            model.id.clone(),
+            LockedModel {
+                version: model.version.clone(),
+                files: hashes,
+            },
+        );
+        Ok(())
+    }
+
+    /// Locked models for which `registry` has a newer version, with their
+    /// locked version, sorted by ID.
+    pub fn outdated<'a>(&'a self, registry: &'a Registry) -> Vec<(&'a str, &'a ModelEntry)> {
+        self.models
+            .iter()
+            .filter_map(|(id, locked)| {
+                let entry = registry.models.iter().find(|m| &m.id == id)?;
+                is_newer_version(&entry.version, &locked.version)
+                    .then_some((locked.version.as_str(), entry))
+            })
+            .collect()
+    }
+}
+
+impl LockedModel {
+    /// Verify the locked files of model `id` in `dir` against their hashes.
+    pub fn verify(&self, id: &str, dir: &Path) -> Result<()> {
+        for (name, expected) in &self.files {
+            let actual = sha256_file(&dir.join(name))?;
+            if &actual != expected {
+                return Err(Error::LockfileHashMismatch {
+                    id: id.to_string(),
+                    file: name.clone(),
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether registry version `available` is newer than `installed`.
+///
+/// Versions compare as semver after padding missing minor and patch numbers,
+/// so `2.4` and `v2.4.0` are equal and `3.0.0-rc.1` is older than `3.0`. Any
+/// other change of the version string counts as newer, as the registry is
+/// authoritative.
+fn is_newer_version(available: &str, installed: &str) -> bool {
+    match (parse_version(available), parse_version(installed)) {
+        (Some(available), Some(installed)) => available > installed,
+        _ => available != installed,
+    }
+}
+
+/// Parse a registry version as semver, padding `2` and `2.4` to `2.0.0` and
+/// `2.4.0`.
+fn parse_version(version: &str) -> Option<semver::Version> {
+    let version = version.trim_start_matches('v');
+    let (core, suffix) = version
+        .find(['-', '+'])
+        .map_or((version, ""), |i| version.split_at(i));
+    let padding = match core.split('.').count() {
+        1 => ".0.0",
+        2 => ".0",
+        _ => "",
+    };
+    semver::Version::parse(&format!("{core}{padding}{suffix}")).ok()
+}
+
+/// File name of `path` as used for lockfile keys.
+fn file_name(path: &Path) -> String {
+    path.file_name().map_or_else(
+        || path.to_string_lossy().to_string(),
+        |n| n.to_string_lossy().to_string(),
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::registry::types::{FileInfo, LabelsInfo, LicenseInfo, ModelFiles};
+
+    fn entry(version: &str) -> ModelEntry {
+        ModelEntry {
+            id: "birdnet-v24".to_string(),
+            name: "BirdNET".to_string(),
+            description: String::new(),
+            vendor: String::new(),
+            version: version.to_string(),
+            model_type: "birdnet-v24".to_string(),
+            license: LicenseInfo {
+                r#type: "CC-BY-NC-SA-4.0".to_string(),
+                url: String::new(),
+                commercial_use: false,
+                attribution_required: true,
+                share_alike: true,
+            },
+            files: ModelFiles {
+                model: FileInfo {
+                    url: String::new(),
+                    filename: "model.onnx".to_string(),
+                    sha256: None,
+                },
+                labels: LabelsInfo {
+                    default_language: "en".to_string(),
+                    languages: Vec::new(),
+                },
+                meta_model: None,
+                bsg_calibration: None,
+                bsg_migration: None,
+                bsg_distribution_maps: None,
+            },
+            recommended: false,
+        }
+    }
+
+    #[test]
+    fn test_lockfile_pins_version_and_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let model_file = dir.path().join("model.onnx");
+        std::fs::write(&model_file, b"v1").unwrap();
+
+        let mut lock = Lockfile::default();
+        lock.lock(&entry("2.4"), std::slice::from_ref(&model_file), false)
+            .unwrap();
+        assert!(lock.check_install(&entry("2.4"), false).is_ok());
+        assert!(matches!(
+            lock.check_install(&entry("2.5"), false),
+            Err(Error::LockfileVersionMismatch { .. })
+        ));
+        assert!(lock.check_install(&entry("2.5"), true).is_ok());
+
+        // Changed file contents are refused without an upgrade
+        std::fs::write(&model_file, b"v2").unwrap();
+        assert!(matches!(
+            lock.models["birdnet-v24"].verify("birdnet-v24", dir.path()),
+            Err(Error::LockfileHashMismatch { .. })
+        ));
+        assert!(
+            lock.lock(&entry("2.4"), std::slice::from_ref(&model_file), false)
+                .is_err()
+        );
+        lock.lock(&entry("2.4"), std::slice::from_ref(&model_file), true)
+            .unwrap();
+        assert!(
+            lock.models["birdnet-v24"]
+                .verify("birdnet-v24", dir.path())
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_lockfile_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join(LOCKFILE_NAME);
+        assert_eq!(Lockfile::load(&path).unwrap(), Lockfile::default());
+
+        let mut lock = Lockfile::default();
+        lock.models.insert(
+            "perch-v2".to_string(),
+            LockedModel {
+                version: "2.0".to_string(),
+                files: BTreeMap::from([("perch.onnx".to_string(), "abc".to_string())]),
+            },
+        );
+        lock.save(&path).unwrap();
+        assert_eq!(Lockfile::load(&path).unwrap(), lock);
+    }
+
+    #[test]
+    fn test_is_newer_version() {
+        assert!(is_newer_version("2.5", "2.4"));
+        assert!(is_newer_version("2.10", "2.9"));
+        assert!(is_newer_version("v3.0.1", "3.0"));
+        assert!(!is_newer_version("2.4", "2.4"));
+        assert!(!is_newer_version("2.3", "2.4"));
+        assert!(is_newer_version("2024-06", "2024-01"));
+        assert!(!is_newer_version("2.4.0", "2.4"));
+        assert!(!is_newer_version("v2.4", "2.4.0"));
+        assert!(is_newer_version("3.0", "3.0.0-rc.1"));
+        assert!(!is_newer_version("3.0.0-rc.1", "3.0"));
+    }
+
+    #[test]
+    fn test_outdated_lists_models_with_newer_registry_version() {
+        let registry = Registry {
+            schema_version: "1.0".to_string(),
+            registry_version: 1,
+            models: vec![entry("2.5")],
+        };
+        let mut lock = Lockfile::default();
+        let locked = |version: &str| LockedModel {
+            version: version.to_string(),
+            files: BTreeMap::new(),
+        };
+        lock.models.insert("birdnet-v24".to_string(), locked("2.4"));
+        lock.models.insert("perch-v2".to_string(), locked("1.0"));
+
+        let outdated = lock.outdated(&registry);
+        assert_eq!(outdated.len(), 1);
+        assert_eq!(outdated[0].0, "2.4");
+        assert_eq!(outdated[0].1.version, "2.5");
+
+        lock.models.insert("birdnet-v24".to_string(), locked("2.5"));
+        assert!(lock.outdated(&registry).is_empty());
+    }
+}
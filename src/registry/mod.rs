@@ -2,15 +2,24 @@
 
 #![allow(clippy::print_stdout)]
 
+pub mod examples;
 pub mod installer;
 pub mod license;
 pub mod loader;
+pub mod lockfile;
 pub mod types;
 
 // Re-export commonly used types and functions
-pub use installer::{download_file, install_model, models_dir};
+pub use examples::{
+    ExampleRecording, ExampleSet, ExpectedDetection, fetch_examples, load_examples,
+};
+pub use installer::{
+    Download, RetryPolicy, configured_models_dir, download_file, download_files, install_model,
+    models_dir, verify_installed,
+};
 pub use license::prompt_license_acceptance;
-pub use loader::{find_model, load_registry};
+pub use loader::{find_model, load_configured_registry, load_registry};
+pub use lockfile::{LockedModel, Lockfile};
 pub use types::{
     FileInfo, LabelsInfo, LanguageVariant, LicenseInfo, ModelEntry, ModelFiles, Registry,
 };
@@ -0,0 +1,285 @@
+//! Species accumulation curves.
+//!
+//! The curve counts distinct species against the amount of audio analyzed,
+//! in recording order. When it levels off, more recording effort at the same
+//! sites is unlikely to add many species.
+
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use super::calendar::escape_xml;
+use crate::constants::report::{
+    ACCUMULATION_CHART_HEIGHT, ACCUMULATION_CHART_MARGIN, ACCUMULATION_CHART_WIDTH,
+    ACCUMULATION_TAIL_FRACTION,
+};
+
+/// Species detected in one recording.
+#[derive(Debug, Clone)]
+pub struct RecordingSpecies {
+    /// Results file the species were read from.
+    pub result_file: PathBuf,
+    /// When the recording started, if known.
+    pub start: Option<NaiveDateTime>,
+    /// Length of the recording in seconds.
+    pub audio_secs: f64,
+    /// Scientific names of the detected species.
+    pub species: BTreeSet<String>,
+}
+
+/// Cumulative totals after one recording.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccumulationPoint {
+    /// Results file of the recording.
+    pub result_file: PathBuf,
+    /// Recordings analyzed so far.
+    pub recordings: usize,
+    /// Audio analyzed so far, in hours.
+    pub audio_hours: f64,
+    /// Distinct species found so far.
+    pub species: usize,
+    /// Scientific names of the species first found in this recording.
+    pub new_species: Vec<String>,
+}
+
+/// Build the accumulation curve, one point per recording.
+///
+/// Recordings are taken in order of their start time, then by results file;
+/// recordings without a known start time come last.
+pub fn accumulation_curve(mut recordings: Vec<RecordingSpecies>) -> Vec<AccumulationPoint> {
+    recordings.sort_by(|a, b| {
+        (a.start.is_none(), a.start, &a.result_file).cmp(&(
+            b.start.is_none(),
+            b.start,
+            &b.result_file,
+        ))
+    });
+
+    let mut seen = BTreeSet::new();
+    let mut audio_secs = 0.0;
+    recordings
+        .into_iter()
+        .enumerate()
+        .map(|(index, recording)| {
+            audio_secs += recording.audio_secs;
+            let new_species = recording
+                .species
+                .into_iter()
+                .filter(|name| seen.insert(name.clone()))
+                .collect();
+            AccumulationPoint {
+                result_file: recording.result_file,
+                recordings: index + 1,
+                audio_hours: audio_secs / 3600.0,
+                species: seen.len(),
+                new_species,
+            }
+        })
+        .collect()
+}
+
+/// Number of species first found in the last `ACCUMULATION_TAIL_FRACTION`
+/// of the audio.
+///
+/// A small number relative to the total suggests the curve has levelled off.
+pub fn tail_new_species(points: &[AccumulationPoint]) -> usize {
+    let Some(last) = points.last() else {
+        return 0;
+    };
+    let cutoff = last.audio_hours * (1.0 - ACCUMULATION_TAIL_FRACTION);
+    points
+        .iter()
+        .filter(|p| p.audio_hours > cutoff)
+        .map(|p| p.new_species.len())
+        .sum()
+}
+
+/// Render the curve as a standalone SVG line chart.
+///
+/// Audio hours run left to right and species found bottom to top.
+pub fn accumulation_svg(points: &[AccumulationPoint]) -> String {
+    let margin = ACCUMULATION_CHART_MARGIN;
+    let width = ACCUMULATION_CHART_WIDTH + 2 * margin;
+    let height = ACCUMULATION_CHART_HEIGHT + 2 * margin;
+    let max_hours = points
+        .last()
+        .map_or(0.0, |p| p.audio_hours)
+        .max(f64::EPSILON);
+    let max_species = points.last().map_or(0, |p| p.species).max(1);
+
+    #[allow(clippy::cast_precision_loss)]
+    let scale = |hours: f64, species: usize| {
+        let x = (hours / max_hours).mul_add(ACCUMULATION_CHART_WIDTH as f64, margin as f64);
+        let y = (1.0 - species as f64 / max_species as f64)
+            .mul_add(ACCUMULATION_CHART_HEIGHT as f64, margin as f64);
+        (x, y)
+    };
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif" font-size="10">"#
+    );
+    svg.push('\n');
+    let (x0, y0) = scale(0.0, 0);
+    let (x1, y1) = scale(max_hours, max_species);
+    let _ = writeln!(
+        svg,
+        r##"<path d="M{x0:.1},{y1:.1} V{y0:.1} H{x1:.1}" fill="none" stroke="#999"/>"##
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="{x0:.1}" y="{:.1}">0</text><text x="{x1:.1}" y="{:.1}" text-anchor="end">{max_hours:.1} h</text>"#,
+        y0 + 14.0,
+        y0 + 14.0
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="{:.1}" y="{y1:.1}" text-anchor="end">{max_species}</text>"#,
+        x0 - 4.0
+    );
+
+    // Species counts only change at recording boundaries, so draw steps
+    let mut path = format!("M{x0:.1},{y0:.1}");
+    for point in points {
+        let (x, y) = scale(point.audio_hours, point.species);
+        let _ = write!(path, " H{x:.1} V{y:.1}");
+    }
+    let _ = writeln!(
+        svg,
+        r##"<path d="{path}" fill="none" stroke="#2c7fb8" stroke-width="2"/>"##
+    );
+    for point in points.iter().filter(|p| !p.new_species.is_empty()) {
+        let (x, y) = scale(point.audio_hours, point.species);
+        let _ = writeln!(
+            svg,
+            r##"<circle cx="{x:.1}" cy="{y:.1}" r="2.5" fill="#2c7fb8"><title>{:.1} h: {}</title></circle>"##,
+            point.audio_hours,
+            escape_xml(&point.new_species.join(", "))
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Render the curve as a self-contained HTML page.
+pub fn accumulation_html(source: &Path, points: &[AccumulationPoint]) -> String {
+    let last = points.last();
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    let _ = writeln!(
+        html,
+        "<title>Species accumulation: {}</title>",
+        escape_xml(&source.display().to_string())
+    );
+    html.push_str(
+        "<style>\
+         body{font-family:sans-serif;margin:2em}\
+         table{border-collapse:collapse}\
+         th,td{border-bottom:1px solid #ddd;padding:.3em .8em;text-align:left;vertical-align:top}\
+         td.n{text-align:right}\
+         </style>\n</head>\n<body>\n<h1>Species accumulation</h1>\n",
+    );
+    let _ = writeln!(
+        html,
+        "<p>{} species in {:.1} hours of audio from {} recordings in {}. \
+         {} species were first found in the last {:.0}% of the audio.</p>",
+        last.map_or(0, |p| p.species),
+        last.map_or(0.0, |p| p.audio_hours),
+        points.len(),
+        escape_xml(&source.display().to_string()),
+        tail_new_species(points),
+        ACCUMULATION_TAIL_FRACTION * 100.0
+    );
+    html.push_str(&accumulation_svg(points));
+
+    html.push_str(
+        "<table>\n<thead><tr><th>Audio hours</th><th>Recordings</th><th>Species</th>\
+         <th>New species</th><th>Results file</th></tr></thead>\n<tbody>\n",
+    );
+    for point in points.iter().filter(|p| !p.new_species.is_empty()) {
+        let _ = writeln!(
+            html,
+            "<tr><td class=\"n\">{:.2}</td><td class=\"n\">{}</td><td class=\"n\">{}</td>\
+             <td><i>{}</i></td><td>{}</td></tr>",
+            point.audio_hours,
+            point.recordings,
+            point.species,
+            escape_xml(&point.new_species.join(", ")),
+            escape_xml(&point.result_file.display().to_string())
+        );
+    }
+    html.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn recording(name: &str, start: Option<&str>, secs: f64, species: &[&str]) -> RecordingSpecies {
+        RecordingSpecies {
+            result_file: PathBuf::from(name),
+            start: start.map(|s| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()),
+            audio_secs: secs,
+            species: species.iter().map(|s| (*s).to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_accumulation_curve_in_recording_order() {
+        let points = accumulation_curve(vec![
+            recording("c.csv", None, 1800.0, &["Apus apus"]),
+            recording(
+                "b.csv",
+                Some("2024-05-02 05:00:00"),
+                3600.0,
+                &["Parus major", "Turdus merula"],
+            ),
+            recording(
+                "a.csv",
+                Some("2024-05-01 05:00:00"),
+                3600.0,
+                &["Turdus merula"],
+            ),
+        ]);
+
+        assert_eq!(points.len(), 3);
+        assert_eq!(points[0].result_file, PathBuf::from("a.csv"));
+        assert_eq!(points[0].species, 1);
+        assert_eq!(points[1].new_species, vec!["Parus major".to_string()]);
+        assert!((points[1].audio_hours - 2.0).abs() < 1e-9);
+        assert_eq!(points[2].result_file, PathBuf::from("c.csv"));
+        assert_eq!(points[2].species, 3);
+        assert!((points[2].audio_hours - 2.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_tail_new_species() {
+        let mut recordings: Vec<_> = (0..10)
+            .map(|i| recording(&format!("{i}.csv"), None, 3600.0, &["Turdus merula"]))
+            .collect();
+        recordings[9].species.insert("Apus apus".to_string());
+
+        let points = accumulation_curve(recordings);
+        assert_eq!(tail_new_species(&points), 1);
+        assert_eq!(tail_new_species(&[]), 0);
+    }
+
+    #[test]
+    fn test_accumulation_html() {
+        let points =
+            accumulation_curve(vec![recording("a.csv", None, 3600.0, &["Turdus <merula>"])]);
+        let svg = accumulation_svg(&points);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+
+        let html = accumulation_html(Path::new("results"), &points);
+        assert!(html.contains("<svg"));
+        assert!(html.contains("Turdus &lt;merula&gt;"));
+        assert!(!html.contains("<merula>"));
+    }
+}
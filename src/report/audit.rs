@@ -0,0 +1,349 @@
+//! Staleness audit of existing results against the current configuration.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::Error;
+use crate::constants::output_extensions;
+use crate::output::{AuditStatus, RunManifest, RunSettings};
+
+/// Settings the current configuration would use for a new run.
+///
+/// Fields that cannot be known without a specific run (e.g. the range filter
+/// week) are not compared, nor are the model, model type and model hash when
+/// `None`.
+#[derive(Debug, Clone, Default)]
+pub struct ExpectedSettings {
+    /// Model name.
+    pub model: Option<String>,
+    /// Model type.
+    pub model_type: Option<String>,
+    /// Hex SHA-256 hash of the current model file.
+    pub model_sha256: Option<String>,
+    /// Minimum confidence threshold.
+    pub min_confidence: f32,
+    /// Segment overlap in seconds.
+    pub overlap: f32,
+    /// Detection sensitivity, `None` for the default.
+    pub sensitivity: Option<f32>,
+    /// Range filter latitude.
+    pub latitude: Option<f64>,
+    /// Range filter longitude.
+    pub longitude: Option<f64>,
+    /// Species list file.
+    pub species_list: Option<PathBuf>,
+}
+
+/// Audit result for one set of results.
+#[derive(Debug, Clone)]
+pub struct AuditFinding {
+    /// Result file the finding refers to (the first found for the input).
+    pub result: PathBuf,
+    /// Whether the results match the current configuration.
+    pub status: AuditStatus,
+    /// Why the results are stale.
+    pub reasons: Vec<String>,
+    /// Recorded birda version, if a manifest exists.
+    pub birda_version: Option<String>,
+    /// Recorded model, if a manifest exists.
+    pub model: Option<String>,
+}
+
+/// Extensions of per-file result outputs.
+const RESULT_EXTENSIONS: [&str; 6] = [
+    output_extensions::CSV,
+    output_extensions::RAVEN,
+    output_extensions::AUDACITY,
+    output_extensions::KALEIDOSCOPE,
+    output_extensions::JSON,
+    output_extensions::PARQUET,
+];
+
+/// Audit every set of results under `dir`.
+///
+/// Results are grouped by input (directory and file stem); each group is
+/// checked against its run manifest.
+///
+/// # Errors
+///
+/// Returns an error if a directory cannot be read.
+pub fn audit_directory(
+    dir: &Path,
+    expected: &ExpectedSettings,
+) -> Result<Vec<AuditFinding>, Error> {
+    let mut results = BTreeMap::new();
+    collect_results(dir, &mut results)?;
+
+    Ok(results
+        .into_iter()
+        .map(|(manifest_path, result)| audit_result(result, &manifest_path, expected))
+        .collect())
+}
+
+/// Collect the first result file per input, keyed by its manifest path.
+fn collect_results(dir: &Path, results: &mut BTreeMap<PathBuf, PathBuf>) -> Result<(), Error> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_results(&path, results)?;
+            continue;
+        }
+        let Some(name) = path.file_name().map(|n| n.to_string_lossy().into_owned()) else {
+            continue;
+        };
+        let Some(stem) = RESULT_EXTENSIONS
+            .iter()
+            .find_map(|ext| name.strip_suffix(ext))
+        else {
+            continue;
+        };
+        let manifest = path.with_file_name(format!("{stem}{}", output_extensions::MANIFEST));
+        results
+            .entry(manifest)
+            .and_modify(|first: &mut PathBuf| {
+                if path < *first {
+                    first.clone_from(&path);
+                }
+            })
+            .or_insert(path);
+    }
+    Ok(())
+}
+
+fn audit_result(
+    result: PathBuf,
+    manifest_path: &Path,
+    expected: &ExpectedSettings,
+) -> AuditFinding {
+    let manifest = match RunManifest::read(manifest_path) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            let reason = if manifest_path.exists() {
+                format!("unreadable run manifest: {e}")
+            } else {
+                "no run manifest (produced by an older birda)".to_string()
+            };
+            return AuditFinding {
+                result,
+                status: AuditStatus::Unknown,
+                reasons: vec![reason],
+                birda_version: None,
+                model: None,
+            };
+        }
+    };
+
+    let reasons = stale_reasons(&manifest, expected, env!("CARGO_PKG_VERSION"));
+    AuditFinding {
+        result,
+        status: if reasons.is_empty() {
+            AuditStatus::UpToDate
+        } else {
+            AuditStatus::Stale
+        },
+        reasons,
+        birda_version: Some(manifest.birda_version),
+        model: Some(manifest.settings.model),
+    }
+}
+
+impl ExpectedSettings {
+    /// `recorded` with the settings known from the current configuration
+    /// applied; settings only a run can know are kept as recorded.
+    fn apply(&self, recorded: &RunSettings) -> RunSettings {
+        let mut settings = recorded.clone();
+        if let Some(model) = &self.model {
+            settings.model.clone_from(model);
+        }
+        if let Some(model_type) = &self.model_type {
+            settings.model_type.clone_from(model_type);
+        }
+        if self.model_sha256.is_some() {
+            settings.model_sha256.clone_from(&self.model_sha256);
+        }
+        settings.min_confidence = self.min_confidence;
+        settings.overlap = self.overlap;
+        settings.sensitivity = self.sensitivity;
+        settings.latitude = self.latitude;
+        settings.longitude = self.longitude;
+        settings.species_list.clone_from(&self.species_list);
+        settings
+    }
+}
+
+/// List the differences between a manifest and the expected settings.
+///
+/// The settings hash decides whether results are stale, exactly as
+/// `--skip-policy hash` does; the serialized settings are only compared to
+/// explain the difference. Patch releases of birda do not change results, so
+/// only a different major or minor version counts.
+fn stale_reasons(
+    manifest: &RunManifest,
+    expected: &ExpectedSettings,
+    current_version: &str,
+) -> Vec<String> {
+    let mut reasons = Vec::new();
+
+    let minor = |version: &str| {
+        semver::Version::parse(version).map_or_else(
+            |_| version.to_string(),
+            |v| format!("{}.{}", v.major, v.minor),
+        )
+    };
+    let (was, now) = (minor(&manifest.birda_version), minor(current_version));
+    if was != now {
+        reasons.push(format!("birda version: {was} -> {now}"));
+    }
+
+    let settings = expected.apply(&manifest.settings);
+    if settings.hash() != manifest.settings_hash {
+        let differences = settings_differences(&manifest.settings, &settings);
+        if differences.is_empty() {
+            reasons.push("settings hash differs".to_string());
+        } else {
+            reasons.extend(differences);
+        }
+    }
+
+    reasons
+}
+
+/// `field: was -> now` for every serialized field that differs.
+fn settings_differences(recorded: &RunSettings, expected: &RunSettings) -> Vec<String> {
+    let fields = |settings: &RunSettings| match serde_json::to_value(settings) {
+        Ok(Value::Object(fields)) => fields,
+        _ => serde_json::Map::new(),
+    };
+    let (recorded, expected) = (fields(recorded), fields(expected));
+    let show = |value: Option<&Value>| match value {
+        None | Some(Value::Null) => "none".to_string(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    };
+
+    let names: BTreeSet<&String> = recorded.keys().chain(expected.keys()).collect();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let (was, now) = (recorded.get(name), expected.get(name));
+            (was != now).then(|| format!("{name}: {} -> {}", show(was), show(now)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> RunManifest {
+        RunManifest::new(RunSettings {
+            model: "birdnet".to_string(),
+            model_type: "birdnet-v24".to_string(),
+            model_sha256: Some("aa".to_string()),
+            min_confidence: 0.1,
+            overlap: 0.0,
+            sensitivity: None,
+            latitude: None,
+            longitude: None,
+            week: None,
+            species_list: None,
+            bat: false,
+            postprocess_hook: None,
+            merge_overlapping: false,
+            zoom_overlap: None,
+            channel: None,
+            filter: None,
+            custom_head: None,
+            time_range: None,
+            stitch: false,
+            confidence_decimals: None,
+            time_decimals: None,
+        })
+    }
+
+    fn expected() -> ExpectedSettings {
+        ExpectedSettings {
+            model: Some("birdnet".to_string()),
+            model_type: Some("birdnet-v24".to_string()),
+            model_sha256: Some("aa".to_string()),
+            min_confidence: 0.1,
+            overlap: 0.0,
+            ..ExpectedSettings::default()
+        }
+    }
+
+    #[test]
+    fn test_up_to_date_manifest_has_no_reasons() {
+        let reasons = stale_reasons(&manifest(), &expected(), env!("CARGO_PKG_VERSION"));
+        assert!(reasons.is_empty(), "{reasons:?}");
+    }
+
+    #[test]
+    fn test_model_upgrade_is_stale() {
+        let mut expected = expected();
+        expected.model_sha256 = Some("bb".to_string());
+        expected.min_confidence = 0.25;
+
+        let reasons = stale_reasons(&manifest(), &expected, env!("CARGO_PKG_VERSION"));
+        assert_eq!(
+            reasons,
+            vec![
+                "min_confidence: 0.1 -> 0.25".to_string(),
+                "model_sha256: aa -> bb".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sensitivity_change_is_stale() {
+        let mut expected = expected();
+        expected.sensitivity = Some(1.25);
+
+        let reasons = stale_reasons(&manifest(), &expected, env!("CARGO_PKG_VERSION"));
+        assert_eq!(reasons, vec!["sensitivity: none -> 1.25".to_string()]);
+    }
+
+    #[test]
+    fn test_unexplained_hash_difference_is_stale() {
+        let mut manifest = manifest();
+        manifest.settings_hash = "0".repeat(64);
+
+        let reasons = stale_reasons(&manifest, &expected(), env!("CARGO_PKG_VERSION"));
+        assert_eq!(reasons, vec!["settings hash differs".to_string()]);
+    }
+
+    #[test]
+    fn test_patch_version_ignored() {
+        let mut manifest = manifest();
+        manifest.birda_version = "1.4.0".to_string();
+        assert!(stale_reasons(&manifest, &expected(), "1.4.7").is_empty());
+        assert_eq!(stale_reasons(&manifest, &expected(), "1.5.0").len(), 1);
+    }
+
+    #[test]
+    fn test_audit_directory_groups_by_input() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.BirdNET.results.csv"), "").unwrap();
+        std::fs::write(dir.path().join("a.BirdNET.json"), "{}").unwrap();
+        std::fs::write(dir.path().join("b.BirdNET.results.csv"), "").unwrap();
+        manifest()
+            .write(&dir.path().join("a.BirdNET.run.json"))
+            .unwrap();
+
+        let findings = audit_directory(dir.path(), &expected()).unwrap();
+        assert_eq!(findings.len(), 2);
+        let a = findings
+            .iter()
+            .find(|f| f.result.to_string_lossy().contains("a.BirdNET"))
+            .unwrap();
+        assert_eq!(a.status, AuditStatus::UpToDate);
+        let b = findings
+            .iter()
+            .find(|f| f.result.to_string_lossy().contains("b.BirdNET"))
+            .unwrap();
+        assert_eq!(b.status, AuditStatus::Unknown);
+    }
+}
@@ -0,0 +1,195 @@
+//! Per-species diel/seasonal activity calendars.
+//!
+//! A calendar counts detections per day and hour of day, so one chart shows
+//! both the daily activity pattern and its change across the season.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use chrono::{NaiveDate, Timelike};
+
+use super::ResultRecord;
+use crate::constants::report::{CALENDAR_CELL_HEIGHT, CALENDAR_CELL_WIDTH, CALENDAR_MARGIN};
+
+/// Hours in a day; one calendar column per hour.
+const HOURS: usize = 24;
+
+/// Detection counts per day and hour for one species.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpeciesCalendar {
+    /// Scientific name of the species.
+    pub scientific_name: String,
+    /// Common name of the species.
+    pub common_name: String,
+    /// Detections per hour (index 0-23), keyed by date.
+    pub days: BTreeMap<NaiveDate, [u32; HOURS]>,
+}
+
+impl SpeciesCalendar {
+    /// Highest count in any single day/hour cell.
+    pub fn max_count(&self) -> u32 {
+        self.days
+            .values()
+            .flat_map(|hours| hours.iter().copied())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Render the calendar as a standalone SVG heatmap.
+    ///
+    /// Days run left to right over the full date range (days without
+    /// detections are left blank) and hours run top to bottom.
+    pub fn to_svg(&self) -> String {
+        let (Some(first), Some(last)) = (self.days.keys().next(), self.days.keys().next_back())
+        else {
+            return String::new();
+        };
+        let span = usize::try_from((*last - *first).num_days()).unwrap_or(0) + 1;
+        let max = self.max_count().max(1);
+
+        let width = CALENDAR_MARGIN * 2 + span * CALENDAR_CELL_WIDTH;
+        let height = CALENDAR_MARGIN * 2 + HOURS * CALENDAR_CELL_HEIGHT;
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif" font-size="10">"#
+        );
+        let _ = writeln!(
+            svg,
+            "<title>{} ({}) {first} to {last}</title>",
+            escape_xml(&self.common_name),
+            escape_xml(&self.scientific_name)
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="{CALENDAR_MARGIN}" y="{}">{first}</text><text x="{}" y="{}" text-anchor="end">{last}</text>"#,
+            CALENDAR_MARGIN - 4,
+            width - CALENDAR_MARGIN,
+            CALENDAR_MARGIN - 4
+        );
+        for hour in (0..HOURS).step_by(6) {
+            let _ = writeln!(
+                svg,
+                r#"<text x="{}" y="{}" text-anchor="end">{hour:02}</text>"#,
+                CALENDAR_MARGIN - 4,
+                CALENDAR_MARGIN + hour * CALENDAR_CELL_HEIGHT + CALENDAR_CELL_HEIGHT
+            );
+        }
+
+        for (date, hours) in &self.days {
+            let day = usize::try_from((*date - *first).num_days()).unwrap_or(0);
+            let x = CALENDAR_MARGIN + day * CALENDAR_CELL_WIDTH;
+            for (hour, &count) in hours.iter().enumerate().filter(|(_, c)| **c > 0) {
+                let y = CALENDAR_MARGIN + hour * CALENDAR_CELL_HEIGHT;
+                let _ = writeln!(
+                    svg,
+                    r#"<rect x="{x}" y="{y}" width="{CALENDAR_CELL_WIDTH}" height="{CALENDAR_CELL_HEIGHT}" fill="{}"><title>{date} {hour:02}:00 {count}</title></rect>"#,
+                    heat_color(count, max)
+                );
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Build one calendar per species, sorted by scientific name.
+pub fn calendar_table(records: &[ResultRecord]) -> Vec<SpeciesCalendar> {
+    let mut calendars: BTreeMap<&str, SpeciesCalendar> = BTreeMap::new();
+
+    for record in records {
+        let calendar = calendars
+            .entry(record.scientific_name.as_str())
+            .or_insert_with(|| SpeciesCalendar {
+                scientific_name: record.scientific_name.clone(),
+                common_name: record.common_name.clone(),
+                days: BTreeMap::new(),
+            });
+        let hours = calendar
+            .days
+            .entry(record.timestamp.date())
+            .or_insert([0; HOURS]);
+        hours[record.timestamp.hour() as usize] += 1;
+    }
+
+    calendars.into_values().collect()
+}
+
+/// Map a count onto a white-to-dark-green scale.
+fn heat_color(count: u32, max: u32) -> String {
+    let intensity = f64::from(count) / f64::from(max);
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let channel = |full: f64, empty: f64| (intensity.mul_add(full - empty, empty)).round() as u8;
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        channel(0.0, 229.0),
+        channel(100.0, 245.0),
+        channel(0.0, 224.0)
+    )
+}
+
+/// Escape text for HTML and SVG content and attributes.
+pub(crate) fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn record(timestamp: &str, species: &str) -> ResultRecord {
+        ResultRecord {
+            site: "north".to_string(),
+            timestamp: chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S")
+                .unwrap(),
+            scientific_name: species.to_string(),
+            common_name: "Common & Name".to_string(),
+            confidence: 0.9,
+        }
+    }
+
+    #[test]
+    fn test_calendar_counts_per_day_and_hour() {
+        let records = vec![
+            record("2024-05-01 05:10:00", "Turdus merula"),
+            record("2024-05-01 05:40:00", "Turdus merula"),
+            record("2024-05-03 21:00:00", "Turdus merula"),
+            record("2024-05-01 06:00:00", "Apus apus"),
+        ];
+
+        let calendars = calendar_table(&records);
+        assert_eq!(calendars.len(), 2);
+        assert_eq!(calendars[0].scientific_name, "Apus apus");
+
+        let blackbird = &calendars[1];
+        let may1 = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        assert_eq!(blackbird.days[&may1][5], 2);
+        assert_eq!(blackbird.days.len(), 2);
+        assert_eq!(blackbird.max_count(), 2);
+    }
+
+    #[test]
+    fn test_calendar_svg() {
+        let calendars = calendar_table(&[
+            record("2024-05-01 05:10:00", "Turdus merula"),
+            record("2024-05-03 21:00:00", "Turdus merula"),
+        ]);
+        let svg = calendars[0].to_svg();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<rect").count(), 2);
+        assert!(svg.contains("Common &amp; Name"));
+    }
+
+    #[test]
+    fn test_heat_color_scale() {
+        assert_eq!(heat_color(0, 10), "#e5f5e0");
+        assert_eq!(heat_color(10, 10), "#006400");
+    }
+}
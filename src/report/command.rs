@@ -0,0 +1,703 @@
+//! Report command execution.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use tracing::{info, warn};
+
+use crate::Error;
+use crate::audio::get_audio_duration;
+use crate::cli::{
+    AccumulationArgs, AuditArgs, CalendarArgs, ExportArgs, PhenologyArgs, QueryArgs, ReportAction,
+    ReviewArgs,
+};
+use crate::clipper::command::find_source_audio;
+use crate::clipper::{
+    ClipExtractor, ClipWriter, DetectionGroup, encode_wav, parse_detection_file, sanitize_filename,
+};
+use crate::config::{self, Config, OutputMode};
+use crate::constants::export::MAPPING_SUFFIX;
+use crate::constants::report::{
+    ACCUMULATION_TAIL_FRACTION, REVIEW_CLIPS_DIR_SUFFIX, REVIEW_SNIPPET_PADDING,
+};
+use crate::output::{
+    AccumulationPayload, AuditEntry, AuditPayload, AuditStatus, CalendarEntry, CalendarPayload,
+    ExportPayload, PhenologyPayload, QueryPayload, ResultType, ReviewEntry, ReviewPayload,
+    SensitiveSpecies, emit_json_result, run_query,
+};
+use crate::utils::hash::sha256_file;
+
+use super::results::resolve_recording_start;
+use super::{
+    AccumulationPoint, Anonymizer, ExpectedSettings, PhenologyRow, RecordingSpecies, ResultRecord,
+    ReviewSnippet, SpeciesCalendar, accumulation_curve, accumulation_html, audit_directory,
+    calendar_table, collect_result_files, path_url, phenology_table, read_result_file, review_html,
+    species_reviews, tail_new_species, wav_data_url,
+};
+
+/// Execute a report command.
+///
+/// # Errors
+///
+/// Returns an error if the report cannot be generated.
+pub fn execute(action: &ReportAction, output_mode: OutputMode) -> Result<(), Error> {
+    match action {
+        ReportAction::Phenology(args) => execute_phenology(args, output_mode),
+        ReportAction::Calendar(args) => execute_calendar(args, output_mode),
+        ReportAction::Review(args) => execute_review(args, output_mode),
+        ReportAction::Accumulation(args) => execute_accumulation(args, output_mode),
+    }
+}
+
+/// Read every results file under `dir`, skipping unreadable ones.
+///
+/// Returns the records and the number of files read successfully.
+fn load_results(dir: &Path, min_confidence: f32) -> Result<(Vec<ResultRecord>, usize), Error> {
+    let files = collect_result_files(dir)?;
+    if files.is_empty() {
+        return Err(Error::NoResultFiles {
+            path: dir.to_path_buf(),
+        });
+    }
+
+    let mut records = Vec::new();
+    let mut total_files = 0;
+    for file in &files {
+        match read_result_file(dir, file, min_confidence) {
+            Ok(file_records) => {
+                records.extend(file_records);
+                total_files += 1;
+            }
+            Err(e) => warn!("Skipping {}: {e}", file.display()),
+        }
+    }
+
+    Ok((records, total_files))
+}
+
+fn execute_phenology(args: &PhenologyArgs, output_mode: OutputMode) -> Result<(), Error> {
+    let (records, total_files) = load_results(&args.input, args.confidence)?;
+    let rows = phenology_table(&records);
+
+    write_phenology_csv(&args.output, &rows).map_err(|e| Error::ReportWrite {
+        path: args.output.clone(),
+        source: Box::new(e),
+    })?;
+
+    if output_mode.is_structured() {
+        let payload = PhenologyPayload {
+            result_type: ResultType::Phenology,
+            output_file: args.output.clone(),
+            total_files,
+            total_detections: records.len(),
+            species: rows,
+        };
+        emit_json_result(&payload);
+        return Ok(());
+    }
+
+    info!(
+        "Wrote {} phenology rows from {} detections in {total_files} files to {}",
+        rows.len(),
+        records.len(),
+        args.output.display()
+    );
+    Ok(())
+}
+
+fn write_phenology_csv(path: &Path, rows: &[PhenologyRow]) -> csv::Result<()> {
+    let mut writer = csv::Writer::from_path(path)?;
+    writer.write_record([
+        "Site",
+        "Year",
+        "Scientific name",
+        "Common name",
+        "First detection",
+        "Last detection",
+        "First day of year",
+        "Last day of year",
+        "Detection days",
+        "Detections",
+    ])?;
+
+    for row in rows {
+        writer.write_record([
+            row.site.clone(),
+            row.year.to_string(),
+            row.scientific_name.clone(),
+            row.common_name.clone(),
+            row.first_detection.to_string(),
+            row.last_detection.to_string(),
+            row.first_day_of_year().to_string(),
+            row.last_day_of_year().to_string(),
+            row.detection_days.to_string(),
+            row.detections.to_string(),
+        ])?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+fn execute_calendar(args: &CalendarArgs, output_mode: OutputMode) -> Result<(), Error> {
+    let (records, total_files) = load_results(&args.input, args.confidence)?;
+    let calendars = calendar_table(&records);
+
+    write_calendar_csv(&args.output, &calendars).map_err(|e| Error::ReportWrite {
+        path: args.output.clone(),
+        source: Box::new(e),
+    })?;
+
+    let mut entries = Vec::with_capacity(calendars.len());
+    if let Some(charts_dir) = &args.charts {
+        std::fs::create_dir_all(charts_dir).map_err(|e| Error::OutputDirCreateFailed {
+            path: charts_dir.clone(),
+            source: e,
+        })?;
+    }
+    for calendar in &calendars {
+        let chart_file = match &args.charts {
+            Some(charts_dir) => {
+                let path = charts_dir.join(format!(
+                    "{}.svg",
+                    sanitize_filename(&calendar.scientific_name)
+                ));
+                std::fs::write(&path, calendar.to_svg()).map_err(|e| Error::ReportWrite {
+                    path: path.clone(),
+                    source: Box::new(e),
+                })?;
+                Some(path)
+            }
+            None => None,
+        };
+        entries.push(CalendarEntry {
+            scientific_name: calendar.scientific_name.clone(),
+            common_name: calendar.common_name.clone(),
+            active_days: calendar.days.len(),
+            hourly_totals: (0..24)
+                .map(|hour| calendar.days.values().map(|hours| hours[hour]).sum())
+                .collect(),
+            chart_file,
+        });
+    }
+
+    if output_mode.is_structured() {
+        let payload = CalendarPayload {
+            result_type: ResultType::Calendar,
+            output_file: args.output.clone(),
+            charts_dir: args.charts.clone(),
+            total_files,
+            total_detections: records.len(),
+            species: entries,
+        };
+        emit_json_result(&payload);
+        return Ok(());
+    }
+
+    info!(
+        "Wrote activity calendars for {} species from {} detections in {total_files} files to {}",
+        calendars.len(),
+        records.len(),
+        args.output.display()
+    );
+    if let Some(charts_dir) = &args.charts {
+        info!("Wrote calendar heatmaps to {}", charts_dir.display());
+    }
+    Ok(())
+}
+
+fn write_calendar_csv(path: &Path, calendars: &[SpeciesCalendar]) -> csv::Result<()> {
+    let mut writer = csv::Writer::from_path(path)?;
+
+    let mut header = vec![
+        "Scientific name".to_string(),
+        "Common name".to_string(),
+        "Date".to_string(),
+    ];
+    header.extend((0..24).map(|hour| format!("{hour:02}")));
+    header.push("Total".to_string());
+    writer.write_record(&header)?;
+
+    for calendar in calendars {
+        for (date, hours) in &calendar.days {
+            let mut record = vec![
+                calendar.scientific_name.clone(),
+                calendar.common_name.clone(),
+                date.to_string(),
+            ];
+            record.extend(hours.iter().map(ToString::to_string));
+            record.push(hours.iter().sum::<u32>().to_string());
+            writer.write_record(&record)?;
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+fn execute_review(args: &ReviewArgs, output_mode: OutputMode) -> Result<(), Error> {
+    let files = collect_result_files(&args.input)?;
+    if files.is_empty() {
+        return Err(Error::NoResultFiles {
+            path: args.input.clone(),
+        });
+    }
+
+    let mut results = Vec::with_capacity(files.len());
+    for file in files {
+        match parse_detection_file(&file) {
+            Ok(mut detections) => {
+                detections.retain(|d| d.confidence >= args.confidence);
+                results.push((file, detections));
+            }
+            Err(e) => warn!("Skipping {}: {e}", file.display()),
+        }
+    }
+    let total_detections = results.iter().map(|(_, d)| d.len()).sum();
+    let mut reviews = species_reviews(&results, args.top);
+
+    // Linked snippets go next to the page so it can be moved with them
+    let page_dir = args
+        .output
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let clips_dir = (!args.embed).then(|| {
+        let stem = args
+            .output
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy();
+        page_dir.join(format!("{stem}{REVIEW_CLIPS_DIR_SUFFIX}"))
+    });
+    let writer = clips_dir.clone().map(ClipWriter::new);
+    let extractor = ClipExtractor::new();
+
+    for review in &mut reviews {
+        for snippet in &mut review.snippets {
+            snippet.audio_src = match snippet_source(
+                snippet,
+                &review.scientific_name,
+                args,
+                &extractor,
+                writer.as_ref(),
+                page_dir,
+            ) {
+                Ok(src) => Some(src),
+                Err(e) => {
+                    warn!(
+                        "No snippet for {} at {:.1}s in {}: {e}",
+                        review.scientific_name,
+                        snippet.start,
+                        snippet.detection_file.display()
+                    );
+                    None
+                }
+            };
+        }
+    }
+
+    std::fs::write(&args.output, review_html(&args.input, &reviews)).map_err(|e| {
+        Error::ReportWrite {
+            path: args.output.clone(),
+            source: Box::new(e),
+        }
+    })?;
+
+    if output_mode.is_structured() {
+        let payload = ReviewPayload {
+            result_type: ResultType::Review,
+            output_file: args.output.clone(),
+            clips_dir,
+            total_files: results.len(),
+            total_detections,
+            species: reviews
+                .iter()
+                .map(|r| ReviewEntry {
+                    scientific_name: r.scientific_name.clone(),
+                    common_name: r.common_name.clone(),
+                    detections: r.detections,
+                    max_confidence: r.max_confidence,
+                    snippets: r.snippets.iter().filter(|s| s.audio_src.is_some()).count(),
+                })
+                .collect(),
+        };
+        emit_json_result(&payload);
+        return Ok(());
+    }
+
+    info!(
+        "Wrote review of {} species from {total_detections} detections in {} files to {}",
+        reviews.len(),
+        results.len(),
+        args.output.display()
+    );
+    Ok(())
+}
+
+/// Extract a snippet and return its audio `src` for the review page.
+///
+/// With a `writer` the snippet is written as a WAV file and linked relative
+/// to `page_dir`; otherwise it is embedded as a `data:` URL.
+fn snippet_source(
+    snippet: &mut ReviewSnippet,
+    species: &str,
+    args: &ReviewArgs,
+    extractor: &ClipExtractor,
+    writer: Option<&ClipWriter>,
+    page_dir: &Path,
+) -> Result<String, Error> {
+    let audio_file = match snippet.audio_file.take().filter(|f| f.exists()) {
+        Some(file) => file,
+        None => find_source_audio(&snippet.detection_file, None, args.audio_dir.as_ref())?,
+    };
+    let group = DetectionGroup {
+        scientific_name: species.to_string(),
+        common_name: String::new(),
+        start: (snippet.start - REVIEW_SNIPPET_PADDING).max(0.0),
+        end: snippet.end + REVIEW_SNIPPET_PADDING,
+        max_confidence: snippet.confidence,
+        detection_count: 1,
+    };
+    let clip = extractor.extract_clip(&audio_file, &group)?;
+    snippet.audio_file = Some(audio_file);
+
+    match writer {
+        Some(writer) => {
+            let path = writer.write_clip(
+                &clip.samples,
+                clip.sample_rate,
+                species,
+                snippet.confidence,
+                group.start,
+                group.end,
+            )?;
+            Ok(path_url(path.strip_prefix(page_dir).unwrap_or(&path)))
+        }
+        None => {
+            let wav =
+                encode_wav(&clip.samples, clip.sample_rate).map_err(|e| Error::ReportWrite {
+                    path: args.output.clone(),
+                    source: Box::new(e),
+                })?;
+            Ok(wav_data_url(&wav))
+        }
+    }
+}
+
+fn execute_accumulation(args: &AccumulationArgs, output_mode: OutputMode) -> Result<(), Error> {
+    let files = collect_result_files(&args.input)?;
+    if files.is_empty() {
+        return Err(Error::NoResultFiles {
+            path: args.input.clone(),
+        });
+    }
+
+    let mut recordings = Vec::with_capacity(files.len());
+    let mut estimated = 0;
+    for file in files {
+        let detections = match parse_detection_file(&file) {
+            Ok(detections) => detections,
+            Err(e) => {
+                warn!("Skipping {}: {e}", file.display());
+                continue;
+            }
+        };
+
+        let audio_file = detections
+            .iter()
+            .find_map(|d| d.file.clone())
+            .filter(|f| f.exists())
+            .or_else(|| find_source_audio(&file, None, args.audio_dir.as_ref()).ok());
+        // Without the recording, the last detection is the best lower bound
+        let audio_secs = match audio_file
+            .as_deref()
+            .and_then(|f| get_audio_duration(f).ok().flatten())
+        {
+            Some(secs) => secs,
+            None => {
+                estimated += 1;
+                detections.iter().map(|d| d.end).fold(0.0, f64::max)
+            }
+        };
+
+        recordings.push(RecordingSpecies {
+            start: resolve_recording_start(&file, audio_file.as_deref()),
+            audio_secs,
+            species: detections
+                .into_iter()
+                .filter(|d| d.confidence >= args.confidence)
+                .map(|d| d.scientific_name)
+                .collect::<BTreeSet<_>>(),
+            result_file: file,
+        });
+    }
+    if estimated > 0 {
+        warn!(
+            "Source audio not found for {estimated} results files; their length is taken from the last detection"
+        );
+    }
+
+    let total_files = recordings.len();
+    let points = accumulation_curve(recordings);
+
+    write_accumulation_csv(&args.output, &points).map_err(|e| Error::ReportWrite {
+        path: args.output.clone(),
+        source: Box::new(e),
+    })?;
+    if let Some(html) = &args.html {
+        std::fs::write(html, accumulation_html(&args.input, &points)).map_err(|e| {
+            Error::ReportWrite {
+                path: html.clone(),
+                source: Box::new(e),
+            }
+        })?;
+    }
+
+    let audio_hours = points.last().map_or(0.0, |p| p.audio_hours);
+    let total_species = points.last().map_or(0, |p| p.species);
+    let tail = tail_new_species(&points);
+
+    if output_mode.is_structured() {
+        let payload = AccumulationPayload {
+            result_type: ResultType::Accumulation,
+            output_file: args.output.clone(),
+            html_file: args.html.clone(),
+            total_files,
+            audio_hours,
+            total_species,
+            tail_new_species: tail,
+            points,
+        };
+        emit_json_result(&payload);
+        return Ok(());
+    }
+
+    info!(
+        "Wrote accumulation of {total_species} species over {audio_hours:.1} hours of audio in {total_files} files to {}",
+        args.output.display()
+    );
+    info!(
+        "{tail} species were first found in the last {:.0}% of the audio",
+        ACCUMULATION_TAIL_FRACTION * 100.0
+    );
+    if let Some(html) = &args.html {
+        info!("Wrote accumulation chart to {}", html.display());
+    }
+    Ok(())
+}
+
+fn write_accumulation_csv(path: &Path, points: &[AccumulationPoint]) -> csv::Result<()> {
+    let mut writer = csv::Writer::from_path(path)?;
+    writer.write_record([
+        "Recordings",
+        "Audio hours",
+        "Species",
+        "New species",
+        "New species names",
+        "Results file",
+    ])?;
+
+    for point in points {
+        writer.write_record([
+            point.recordings.to_string(),
+            format!("{:.4}", point.audio_hours),
+            point.species.to_string(),
+            point.new_species.len().to_string(),
+            point.new_species.join("; "),
+            point.result_file.display().to_string(),
+        ])?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Execute the audit command.
+///
+/// # Errors
+///
+/// Returns an error if the model is unknown or the directory cannot be read.
+pub fn audit(args: &AuditArgs, config: &Config, output_mode: OutputMode) -> Result<(), Error> {
+    let model_name = args.model.clone().or_else(|| config.defaults.model.clone());
+    let model_config = model_name
+        .as_deref()
+        .map(|name| config::get_model(config, name))
+        .transpose()?;
+
+    let expected = ExpectedSettings {
+        model: model_name.clone(),
+        model_type: model_config.map(|m| m.model_type.to_string()),
+        model_sha256: model_config.and_then(|m| sha256_file(&m.path).ok()),
+        min_confidence: args
+            .min_confidence
+            .unwrap_or(config.defaults.min_confidence),
+        overlap: args.overlap.unwrap_or(config.defaults.overlap),
+        sensitivity: (!crate::inference::is_default_sensitivity(config.defaults.sensitivity))
+            .then_some(config.defaults.sensitivity),
+        latitude: config.defaults.latitude,
+        longitude: config.defaults.longitude,
+        species_list: config.defaults.species_list_file.clone(),
+    };
+
+    let findings = audit_directory(&args.input, &expected)?;
+    let count = |status| findings.iter().filter(|f| f.status == status).count();
+    let (up_to_date, stale, unknown) = (
+        count(AuditStatus::UpToDate),
+        count(AuditStatus::Stale),
+        count(AuditStatus::Unknown),
+    );
+
+    let listed = findings
+        .into_iter()
+        .filter(|f| !args.stale_only || f.status != AuditStatus::UpToDate);
+
+    if output_mode.is_structured() {
+        let payload = AuditPayload {
+            result_type: ResultType::Audit,
+            directory: args.input.clone(),
+            up_to_date,
+            stale,
+            unknown,
+            results: listed
+                .map(|f| AuditEntry {
+                    result: f.result,
+                    status: f.status,
+                    reasons: f.reasons,
+                    birda_version: f.birda_version,
+                    model: f.model,
+                })
+                .collect(),
+        };
+        emit_json_result(&payload);
+        return Ok(());
+    }
+
+    for finding in listed {
+        let label = match finding.status {
+            AuditStatus::UpToDate => "ok",
+            AuditStatus::Stale => "stale",
+            AuditStatus::Unknown => "unknown",
+        };
+        println!("{label:<8} {}", finding.result.display());
+        for reason in &finding.reasons {
+            println!("         {reason}");
+        }
+    }
+    println!();
+    println!("{up_to_date} up to date, {stale} stale, {unknown} without run manifest");
+    Ok(())
+}
+
+/// Execute the query command.
+///
+/// Human output is tab-separated with a header row, ready for piping.
+///
+/// # Errors
+///
+/// Returns an error if the source cannot be opened or the query fails.
+pub fn query(args: &QueryArgs, output_mode: OutputMode) -> Result<(), Error> {
+    let result = run_query(&args.sql, &args.source)?;
+
+    if output_mode.is_structured() {
+        let payload = QueryPayload {
+            result_type: ResultType::Query,
+            source: args.source.clone(),
+            columns: result.columns,
+            rows: result.rows,
+        };
+        emit_json_result(&payload);
+        return Ok(());
+    }
+
+    println!("{}", result.columns.join("\t"));
+    for row in &result.rows {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|value| match value {
+                serde_json::Value::Null => String::new(),
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+            .collect();
+        println!("{}", cells.join("\t"));
+    }
+    Ok(())
+}
+
+/// Execute the export command.
+///
+/// Writes the sanitized copy to the output directory and the mapping of
+/// original to exported values next to it, so the mapping is not shared
+/// along with the results by accident.
+///
+/// # Errors
+///
+/// Returns an error if the mapping would land inside the output directory or
+/// the results cannot be read or written.
+pub fn export(args: &ExportArgs, config: &Config, output_mode: OutputMode) -> Result<(), Error> {
+    let mapping = args.mapping.clone().unwrap_or_else(|| {
+        let mut name = args.output.as_os_str().to_os_string();
+        name.push(MAPPING_SUFFIX);
+        name.into()
+    });
+    let output_dir = std::path::absolute(&args.output)?;
+    if std::path::absolute(&mapping)?.starts_with(&output_dir) {
+        return Err(Error::ConfigValidation {
+            message: format!(
+                "mapping file '{}' must not be inside the export directory '{}'",
+                mapping.display(),
+                args.output.display()
+            ),
+        });
+    }
+
+    std::fs::create_dir_all(&args.output)?;
+    let sensitive = SensitiveSpecies::load(&config.sensitive)?;
+    let mut anonymizer = Anonymizer::new(args.grid).with_sensitive(sensitive);
+    anonymizer.export_directory(&args.input, &args.output)?;
+    anonymizer.write_mapping(&mapping)?;
+    let summary = anonymizer.summary();
+
+    for skipped in &summary.files_skipped {
+        warn!(
+            "Not exported (format cannot be sanitized): {}",
+            skipped.display()
+        );
+    }
+
+    if output_mode.is_structured() {
+        let payload = ExportPayload {
+            result_type: ResultType::Export,
+            output_dir: args.output.clone(),
+            mapping,
+            files_exported: summary.files_exported,
+            files_skipped: summary.files_skipped.clone(),
+            paths_replaced: summary.paths_replaced,
+            coordinates_fuzzed: summary.coordinates_fuzzed,
+            detections_withheld: summary.detections_withheld,
+        };
+        emit_json_result(&payload);
+        return Ok(());
+    }
+
+    println!(
+        "Exported {} files to {} ({} paths replaced, {} coordinates snapped to a {}\u{b0} grid)",
+        summary.files_exported,
+        args.output.display(),
+        summary.paths_replaced,
+        summary.coordinates_fuzzed,
+        args.grid
+    );
+    if !summary.files_skipped.is_empty() {
+        println!("Skipped {} files", summary.files_skipped.len());
+    }
+    if summary.detections_withheld > 0 {
+        println!(
+            "Withheld {} detections of sensitive species",
+            summary.detections_withheld
+        );
+    }
+    println!("Mapping kept locally: {}", mapping.display());
+    Ok(())
+}
@@ -0,0 +1,581 @@
+//! Sanitized copies of detection results for data sharing.
+//!
+//! `birda export` copies the result files under a directory with identifying
+//! details removed: recording paths are cut down to their file names (which
+//! also drops hostnames of network shares and URLs), directory columns are
+//! emptied and coordinates are snapped to the centre of a grid cell. Every
+//! replacement is recorded in a mapping file that stays with the original
+//! data, so shared findings can be traced back locally.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::Error;
+use crate::config::SensitiveAction;
+use crate::constants::output_extensions;
+use crate::output::SensitiveSpecies;
+use crate::utils::geo::snap_to_grid;
+
+/// Columns and JSON keys holding a path, reduced to the file name.
+const PATH_FIELDS: [&str; 8] = [
+    "File",
+    "Begin Path",
+    "path",
+    "source_file",
+    "species_list",
+    "postprocess_hook",
+    "custom_head",
+    "IN FILE",
+];
+
+/// Columns and JSON keys holding a species name.
+const SPECIES_FIELDS: [&str; 6] = [
+    "Scientific name",
+    "Common name",
+    "Common Name",
+    "TOP1MATCH",
+    "scientific_name",
+    "common_name",
+];
+
+/// Columns holding a full directory path, emptied.
+const DIRECTORY_FIELDS: [&str; 2] = ["INDIR", "OUTDIR"];
+
+/// Columns and JSON keys holding a latitude.
+const LATITUDE_FIELDS: [&str; 2] = ["lat", "latitude"];
+
+/// Columns and JSON keys holding a longitude.
+const LONGITUDE_FIELDS: [&str; 2] = ["lon", "longitude"];
+
+/// Kind of a replaced value in the mapping file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Replacement {
+    /// Path reduced to its file name.
+    Path,
+    /// Directory removed.
+    Directory,
+    /// Latitude snapped to the grid.
+    Latitude,
+    /// Longitude snapped to the grid.
+    Longitude,
+}
+
+impl Replacement {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Path => "path",
+            Self::Directory => "directory",
+            Self::Latitude => "lat",
+            Self::Longitude => "lon",
+        }
+    }
+}
+
+/// How a result file is sanitized, decided by its name and header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileKind {
+    /// Delimited table with a header row.
+    Table(u8),
+    /// Result or run manifest JSON.
+    Json,
+    /// Copied unchanged (Audacity labels hold no paths or coordinates).
+    Verbatim,
+}
+
+/// Counts of an export.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportSummary {
+    /// Files written to the output directory.
+    pub files_exported: usize,
+    /// Files that were not exported (unsupported formats).
+    pub files_skipped: Vec<PathBuf>,
+    /// Path and directory values replaced.
+    pub paths_replaced: usize,
+    /// Coordinates snapped to the grid.
+    pub coordinates_fuzzed: usize,
+    /// Detections of sensitive species left out.
+    pub detections_withheld: usize,
+}
+
+/// Sanitizes result files, remembering every replacement.
+#[derive(Debug)]
+pub struct Anonymizer {
+    grid: f64,
+    sensitive: Option<SensitiveSpecies>,
+    mapping: BTreeSet<(Replacement, String, String)>,
+    summary: ExportSummary,
+}
+
+impl Anonymizer {
+    /// Create an anonymizer snapping coordinates to a `grid` of degrees.
+    pub fn new(grid: f64) -> Self {
+        Self {
+            grid,
+            sensitive: None,
+            mapping: BTreeSet::new(),
+            summary: ExportSummary::default(),
+        }
+    }
+
+    /// Apply the action configured for sensitive species to their detections.
+    #[must_use]
+    pub fn with_sensitive(mut self, sensitive: Option<SensitiveSpecies>) -> Self {
+        self.sensitive = sensitive;
+        self
+    }
+
+    /// Counts of the files exported so far.
+    pub const fn summary(&self) -> &ExportSummary {
+        &self.summary
+    }
+
+    /// Export every result file under `input` into `output`, keeping the
+    /// relative layout.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a directory cannot be read or a file cannot be
+    /// parsed or written.
+    pub fn export_directory(&mut self, input: &Path, output: &Path) -> Result<(), Error> {
+        let mut files = Vec::new();
+        collect_files(input, output, &mut files)?;
+        if files.is_empty() {
+            return Err(Error::NoResultFiles {
+                path: input.to_path_buf(),
+            });
+        }
+        files.sort();
+
+        for file in files {
+            let relative = file.strip_prefix(input).unwrap_or(&file);
+            let dest = output.join(relative);
+            if self.export_file(&file, &dest)? {
+                self.summary.files_exported += 1;
+            } else {
+                self.summary.files_skipped.push(file);
+            }
+        }
+        Ok(())
+    }
+
+    /// Export one result file to `dest`, returning false for unsupported
+    /// files.
+    fn export_file(&mut self, path: &Path, dest: &Path) -> Result<bool, Error> {
+        let Some(kind) = file_kind(path)? else {
+            return Ok(false);
+        };
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        match kind {
+            FileKind::Table(delimiter) => self.export_table(path, dest, delimiter)?,
+            FileKind::Json => {
+                let content = std::fs::read_to_string(path)?;
+                let mut value: Value =
+                    serde_json::from_str(&content).map_err(|e| Error::DetectionParseFailed {
+                        path: path.to_path_buf(),
+                        source: Box::new(e),
+                    })?;
+                let grid = self.mask_json(&mut value);
+                self.sanitize_json(&mut value, grid);
+                let json = serde_json::to_string_pretty(&value).map_err(|e| Error::Internal {
+                    message: format!("Failed to serialize {}: {e}", path.display()),
+                })?;
+                std::fs::write(dest, json)?;
+            }
+            FileKind::Verbatim => {
+                std::fs::copy(path, dest)?;
+            }
+        }
+        Ok(true)
+    }
+
+    /// Rewrite a delimited table, sanitizing cells by column name.
+    fn export_table(&mut self, path: &Path, dest: &Path, delimiter: u8) -> Result<(), Error> {
+        let parse_error = |e: csv::Error| Error::DetectionParseFailed {
+            path: path.to_path_buf(),
+            source: Box::new(e),
+        };
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .has_headers(false)
+            .flexible(true)
+            .from_path(path)
+            .map_err(parse_error)?;
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .from_path(dest)
+            .map_err(parse_error)?;
+
+        let mut columns: Vec<String> = Vec::new();
+        for (index, record) in reader.records().enumerate() {
+            let record = record.map_err(parse_error)?;
+            if index == 0 {
+                columns = record
+                    .iter()
+                    .map(|c| c.trim_start_matches('\u{feff}').trim().to_string())
+                    .collect();
+                writer.write_record(&record).map_err(parse_error)?;
+                continue;
+            }
+            let sensitive = columns
+                .iter()
+                .zip(record.iter())
+                .any(|(name, cell)| self.is_sensitive(name, cell));
+            let Some(grid) = self.grid_for(sensitive) else {
+                self.summary.detections_withheld += 1;
+                continue;
+            };
+            let row: Vec<String> = record
+                .iter()
+                .enumerate()
+                .map(|(i, cell)| {
+                    columns.get(i).map_or_else(
+                        || cell.to_string(),
+                        |name| self.sanitize_field(name, cell, grid),
+                    )
+                })
+                .collect();
+            writer.write_record(&row).map_err(parse_error)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Whether a field named `name` holds the name of a sensitive species.
+    fn is_sensitive(&self, name: &str, value: &str) -> bool {
+        SPECIES_FIELDS.contains(&name)
+            && self
+                .sensitive
+                .as_ref()
+                .is_some_and(|sensitive| sensitive.contains(value))
+    }
+
+    /// Grid for coordinates of a record, or `None` if the record is withheld.
+    fn grid_for(&self, sensitive: bool) -> Option<f64> {
+        match self.sensitive.as_ref() {
+            Some(species) if sensitive => match species.action() {
+                SensitiveAction::Flag => Some(self.grid),
+                SensitiveAction::Exclude => None,
+                SensitiveAction::Fuzz => Some(self.grid.max(species.grid())),
+            },
+            _ => Some(self.grid),
+        }
+    }
+
+    /// Drop sensitive entries from the `detections` of a result JSON file,
+    /// returning the grid for the file's coordinates.
+    ///
+    /// Locations are per file, so one sensitive detection coarsens them all.
+    fn mask_json(&mut self, value: &mut Value) -> f64 {
+        let Some(Value::Array(detections)) = value.get_mut("detections") else {
+            return self.grid;
+        };
+        let mut grid = self.grid;
+        let mut withheld = 0;
+        detections.retain(|detection| {
+            let sensitive = detection.as_object().is_some_and(|fields| {
+                fields.iter().any(|(key, field)| {
+                    field
+                        .as_str()
+                        .is_some_and(|text| self.is_sensitive(key, text))
+                })
+            });
+            match self.grid_for(sensitive) {
+                Some(row_grid) => {
+                    grid = grid.max(row_grid);
+                    true
+                }
+                None => {
+                    withheld += 1;
+                    false
+                }
+            }
+        });
+        self.summary.detections_withheld += withheld;
+        grid
+    }
+
+    /// Sanitized value of a text field named `name`.
+    fn sanitize_field(&mut self, name: &str, value: &str, grid: f64) -> String {
+        if value.is_empty() {
+            return String::new();
+        }
+        if PATH_FIELDS.contains(&name) {
+            let file_name = file_name_of(value);
+            if file_name != value {
+                self.record(Replacement::Path, value, file_name);
+            }
+            return file_name.to_string();
+        }
+        if DIRECTORY_FIELDS.contains(&name) {
+            self.record(Replacement::Directory, value, "");
+            return String::new();
+        }
+        if let Some(kind) = coordinate_kind(name)
+            && let Ok(coordinate) = value.trim().parse::<f64>()
+        {
+            return self.fuzz(kind, coordinate, grid).to_string();
+        }
+        value.to_string()
+    }
+
+    /// Sanitize a JSON document in place, by key.
+    fn sanitize_json(&mut self, value: &mut Value, grid: f64) {
+        match value {
+            Value::Object(map) => {
+                for (key, field) in map.iter_mut() {
+                    match field {
+                        Value::String(text) => *text = self.sanitize_field(key, text, grid),
+                        Value::Number(number) => {
+                            if let (Some(kind), Some(coordinate)) =
+                                (coordinate_kind(key), number.as_f64())
+                                && let Some(fuzzed) =
+                                    serde_json::Number::from_f64(self.fuzz(kind, coordinate, grid))
+                            {
+                                *number = fuzzed;
+                            }
+                        }
+                        _ => self.sanitize_json(field, grid),
+                    }
+                }
+            }
+            Value::Array(items) => items
+                .iter_mut()
+                .for_each(|item| self.sanitize_json(item, grid)),
+            _ => {}
+        }
+    }
+
+    /// Snap `coordinate` to the centre of its grid cell.
+    fn fuzz(&mut self, kind: Replacement, coordinate: f64, grid: f64) -> f64 {
+        let fuzzed = snap_to_grid(coordinate, grid);
+        self.record(kind, &coordinate.to_string(), &fuzzed.to_string());
+        self.summary.coordinates_fuzzed += 1;
+        fuzzed
+    }
+
+    /// Remember that `original` was exported as `exported`.
+    fn record(&mut self, kind: Replacement, original: &str, exported: &str) {
+        if matches!(kind, Replacement::Path | Replacement::Directory) {
+            self.summary.paths_replaced += 1;
+        }
+        self.mapping
+            .insert((kind, original.to_string(), exported.to_string()));
+    }
+
+    /// Write the mapping of original to exported values as CSV.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn write_mapping(&self, path: &Path) -> Result<(), Error> {
+        let write_error = |e: csv::Error| Error::ReportWrite {
+            path: path.to_path_buf(),
+            source: Box::new(e),
+        };
+        let mut writer = csv::Writer::from_path(path).map_err(write_error)?;
+        writer
+            .write_record(["kind", "original", "exported"])
+            .map_err(write_error)?;
+        for (kind, original, exported) in &self.mapping {
+            writer
+                .write_record([kind.as_str(), original, exported])
+                .map_err(write_error)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Recursively collect files under `dir`, leaving out `output`.
+fn collect_files(dir: &Path, output: &Path, files: &mut Vec<PathBuf>) -> Result<(), Error> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path == output {
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(&path, output, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// How `path` is exported, or `None` if it is not a supported result file.
+fn file_kind(path: &Path) -> Result<Option<FileKind>, Error> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase());
+
+    Ok(match extension.as_deref() {
+        Some("csv") => Some(FileKind::Table(b',')),
+        Some("json")
+            if name.ends_with(output_extensions::JSON)
+                || name.ends_with(output_extensions::MANIFEST) =>
+        {
+            Some(FileKind::Json)
+        }
+        Some("txt") => {
+            // Raven tables have a header row; Audacity labels have none
+            let first_line = std::fs::read_to_string(path)?
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            if first_line.contains("Begin Path") {
+                Some(FileKind::Table(b'\t'))
+            } else if name.ends_with(output_extensions::AUDACITY) {
+                Some(FileKind::Verbatim)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    })
+}
+
+/// Kind of coordinate held by a field named `name`.
+fn coordinate_kind(name: &str) -> Option<Replacement> {
+    if LATITUDE_FIELDS.contains(&name) {
+        Some(Replacement::Latitude)
+    } else if LONGITUDE_FIELDS.contains(&name) {
+        Some(Replacement::Longitude)
+    } else {
+        None
+    }
+}
+
+/// File name of a path in any platform's notation, without directories,
+/// drive letters or hosts.
+fn file_name_of(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_name_of_strips_directories_and_hosts() {
+        assert_eq!(file_name_of("/data/site1/rec.wav"), "rec.wav");
+        assert_eq!(file_name_of(r"\\nas01\audio\site1\rec.wav"), "rec.wav");
+        assert_eq!(file_name_of(r"C:\recordings\rec.wav"), "rec.wav");
+        assert_eq!(file_name_of("rec.wav"), "rec.wav");
+    }
+
+    #[test]
+    fn test_export_sanitizes_csv_json_and_records_mapping() {
+        let input = tempfile::tempdir().unwrap();
+        let output = tempfile::tempdir().unwrap();
+        let site = input.path().join("site1");
+        std::fs::create_dir(&site).unwrap();
+        std::fs::write(
+            site.join("rec.BirdNET.results.csv"),
+            "\u{feff}Start (s),End (s),Scientific name,Common name,Confidence,File,lat,lon\n\
+             0.0,3.0,Parus major,Great Tit,0.9000,/home/alice/site1/rec.wav,60.1699,24.9384\n",
+        )
+        .unwrap();
+        std::fs::write(
+            site.join("rec.BirdNET.json"),
+            r#"{"source_file":"rec.wav","settings":{"lat":60.1699,"lon":24.9384}}"#,
+        )
+        .unwrap();
+        std::fs::write(site.join("rec.BirdNET.results.parquet"), b"PAR1").unwrap();
+
+        let mut anonymizer = Anonymizer::new(0.1);
+        anonymizer
+            .export_directory(input.path(), output.path())
+            .unwrap();
+        let summary = anonymizer.summary();
+        assert_eq!(summary.files_exported, 2);
+        assert_eq!(summary.files_skipped.len(), 1);
+        assert_eq!(summary.paths_replaced, 1);
+        assert_eq!(summary.coordinates_fuzzed, 4);
+
+        let csv =
+            std::fs::read_to_string(output.path().join("site1/rec.BirdNET.results.csv")).unwrap();
+        assert!(csv.starts_with('\u{feff}'));
+        assert!(csv.contains(",rec.wav,60.15,24.95"));
+        assert!(!csv.contains("alice"));
+
+        let json: Value = serde_json::from_str(
+            &std::fs::read_to_string(output.path().join("site1/rec.BirdNET.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(json["settings"]["lat"], 60.15);
+
+        let mapping = output.path().join("mapping.csv");
+        anonymizer.write_mapping(&mapping).unwrap();
+        let mapping = std::fs::read_to_string(mapping).unwrap();
+        assert!(mapping.contains("path,/home/alice/site1/rec.wav,rec.wav"));
+        assert!(mapping.contains("lat,60.1699,60.15"));
+    }
+
+    #[test]
+    fn test_export_empties_kaleidoscope_directories() {
+        let input = tempfile::tempdir().unwrap();
+        let output = tempfile::tempdir().unwrap();
+        std::fs::write(
+            input.path().join("rec.BirdNET.results.kaleidoscope.csv"),
+            "INDIR,OUTDIR,FOLDER,IN FILE,CHANNEL,OFFSET,DURATION,TOP1MATCH,TOP1DIST\n\
+             /mnt/field,/mnt/results,site1,rec.wav,0,0.0,3.0,Great_Tit,0.9000\n",
+        )
+        .unwrap();
+
+        let mut anonymizer = Anonymizer::new(0.1);
+        anonymizer
+            .export_directory(input.path(), output.path())
+            .unwrap();
+        let csv =
+            std::fs::read_to_string(output.path().join("rec.BirdNET.results.kaleidoscope.csv"))
+                .unwrap();
+        assert!(csv.contains("\n,,site1,rec.wav,"));
+    }
+
+    #[test]
+    fn test_export_withholds_or_coarsens_sensitive_species() {
+        let input = tempfile::tempdir().unwrap();
+        std::fs::write(
+            input.path().join("rec.BirdNET.results.csv"),
+            "Start (s),End (s),Scientific name,Common name,Confidence,File,lat,lon\n\
+             0.0,3.0,Aquila chrysaetos,Golden Eagle,0.9000,rec.wav,60.1699,24.9384\n\
+             3.0,6.0,Parus major,Great Tit,0.8000,rec.wav,60.1699,24.9384\n",
+        )
+        .unwrap();
+        let sensitive = |action| {
+            SensitiveSpecies::new(&["Aquila chrysaetos_Golden Eagle".to_string()], action, 1.0)
+        };
+
+        let output = tempfile::tempdir().unwrap();
+        let mut anonymizer =
+            Anonymizer::new(0.1).with_sensitive(Some(sensitive(SensitiveAction::Exclude)));
+        anonymizer
+            .export_directory(input.path(), output.path())
+            .unwrap();
+        assert_eq!(anonymizer.summary().detections_withheld, 1);
+        let csv = std::fs::read_to_string(output.path().join("rec.BirdNET.results.csv")).unwrap();
+        assert!(!csv.contains("Golden Eagle"));
+        assert!(csv.contains("Great Tit"));
+
+        let output = tempfile::tempdir().unwrap();
+        let mut anonymizer =
+            Anonymizer::new(0.1).with_sensitive(Some(sensitive(SensitiveAction::Fuzz)));
+        anonymizer
+            .export_directory(input.path(), output.path())
+            .unwrap();
+        let csv = std::fs::read_to_string(output.path().join("rec.BirdNET.results.csv")).unwrap();
+        assert!(csv.contains("Golden Eagle,0.9000,rec.wav,60.5,24.5"));
+        assert!(csv.contains("Great Tit,0.8000,rec.wav,60.15,24.95"));
+    }
+}
@@ -0,0 +1,27 @@
+//! Aggregate reports over directories of detection results.
+//!
+//! Reports read existing birda CSV result files rather than audio, so they
+//! can be rerun cheaply over a long-term monitoring archive.
+
+mod accumulation;
+mod audit;
+mod calendar;
+pub mod command;
+mod export;
+mod phenology;
+mod results;
+mod review;
+
+pub use accumulation::{
+    AccumulationPoint, RecordingSpecies, accumulation_curve, accumulation_html, accumulation_svg,
+    tail_new_species,
+};
+pub use audit::{AuditFinding, ExpectedSettings, audit_directory};
+pub(crate) use calendar::escape_xml;
+pub use calendar::{SpeciesCalendar, calendar_table};
+pub use export::{Anonymizer, ExportSummary};
+pub use phenology::{PhenologyRow, phenology_table};
+pub use results::{ResultRecord, collect_result_files, read_result_file};
+pub use review::{
+    ReviewSnippet, SpeciesReview, path_url, review_html, species_reviews, wav_data_url,
+};
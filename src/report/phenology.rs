@@ -0,0 +1,152 @@
+//! Arrival/departure phenology tables.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use super::ResultRecord;
+
+/// First and last detection of one species at one site in one year.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhenologyRow {
+    /// Site name.
+    pub site: String,
+    /// Calendar year.
+    pub year: i32,
+    /// Scientific name of the species.
+    pub scientific_name: String,
+    /// Common name of the species.
+    pub common_name: String,
+    /// Date of the first detection.
+    pub first_detection: NaiveDate,
+    /// Date of the last detection.
+    pub last_detection: NaiveDate,
+    /// Number of distinct days with detections.
+    pub detection_days: usize,
+    /// Total number of detections.
+    pub detections: usize,
+}
+
+impl PhenologyRow {
+    /// Day of year (1-366) of the first detection.
+    pub fn first_day_of_year(&self) -> u32 {
+        self.first_detection.ordinal()
+    }
+
+    /// Day of year (1-366) of the last detection.
+    pub fn last_day_of_year(&self) -> u32 {
+        self.last_detection.ordinal()
+    }
+}
+
+#[derive(Default)]
+struct SpeciesYear {
+    common_name: String,
+    days: BTreeSet<NaiveDate>,
+    detections: usize,
+}
+
+/// Build the phenology table for a set of detections.
+///
+/// Rows are sorted by site, year, and first detection date, so each
+/// site-year reads as an arrival order.
+pub fn phenology_table(records: &[ResultRecord]) -> Vec<PhenologyRow> {
+    let mut groups: BTreeMap<(String, i32, String), SpeciesYear> = BTreeMap::new();
+
+    for record in records {
+        let date = record.timestamp.date();
+        let entry = groups
+            .entry((
+                record.site.clone(),
+                date.year(),
+                record.scientific_name.clone(),
+            ))
+            .or_default();
+        if entry.common_name.is_empty() {
+            entry.common_name.clone_from(&record.common_name);
+        }
+        entry.days.insert(date);
+        entry.detections += 1;
+    }
+
+    let mut rows: Vec<PhenologyRow> = groups
+        .into_iter()
+        .filter_map(|((site, year, scientific_name), group)| {
+            Some(PhenologyRow {
+                site,
+                year,
+                scientific_name,
+                common_name: group.common_name,
+                first_detection: *group.days.first()?,
+                last_detection: *group.days.last()?,
+                detection_days: group.days.len(),
+                detections: group.detections,
+            })
+        })
+        .collect();
+
+    rows.sort_by(|a, b| {
+        (&a.site, a.year, a.first_detection, &a.scientific_name).cmp(&(
+            &b.site,
+            b.year,
+            b.first_detection,
+            &b.scientific_name,
+        ))
+    });
+    rows
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn record(site: &str, date: &str, species: &str) -> ResultRecord {
+        ResultRecord {
+            site: site.to_string(),
+            timestamp: NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .unwrap()
+                .and_hms_opt(6, 0, 0)
+                .unwrap(),
+            scientific_name: species.to_string(),
+            common_name: String::new(),
+            confidence: 0.9,
+        }
+    }
+
+    #[test]
+    fn test_phenology_first_and_last_dates() {
+        let records = vec![
+            record("north", "2024-05-10", "Apus apus"),
+            record("north", "2024-04-20", "Apus apus"),
+            record("north", "2024-04-20", "Apus apus"),
+            record("north", "2024-08-02", "Apus apus"),
+            record("north", "2024-03-01", "Turdus merula"),
+        ];
+
+        let rows = phenology_table(&records);
+        assert_eq!(rows.len(), 2);
+        // Sorted by arrival
+        assert_eq!(rows[0].scientific_name, "Turdus merula");
+        let swift = &rows[1];
+        assert_eq!(swift.first_detection.to_string(), "2024-04-20");
+        assert_eq!(swift.last_detection.to_string(), "2024-08-02");
+        assert_eq!(swift.detection_days, 3);
+        assert_eq!(swift.detections, 4);
+    }
+
+    #[test]
+    fn test_phenology_splits_sites_and_years() {
+        let records = vec![
+            record("north", "2023-05-01", "Apus apus"),
+            record("north", "2024-05-03", "Apus apus"),
+            record("south", "2024-04-28", "Apus apus"),
+        ];
+
+        let rows = phenology_table(&records);
+        assert_eq!(rows.len(), 3);
+        assert_eq!((rows[0].site.as_str(), rows[0].year), ("north", 2023));
+        assert_eq!((rows[2].site.as_str(), rows[2].year), ("south", 2024));
+    }
+}
@@ -0,0 +1,233 @@
+//! Loading detection result files for reports.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{NaiveDateTime, TimeDelta};
+use serde::Deserialize;
+
+use crate::Error;
+use crate::constants::{dead_letter, output_extensions};
+use crate::output::is_combined_filename;
+use crate::utils::date::{recording_timestamp, timestamp_from_filename};
+
+/// Internal record for CSV deserialization.
+#[derive(Debug, Deserialize)]
+struct ResultCsvRecord {
+    #[serde(rename = "Start (s)")]
+    start: f64,
+    #[serde(rename = "Scientific name")]
+    scientific_name: String,
+    #[serde(rename = "Common name")]
+    common_name: String,
+    #[serde(rename = "Confidence")]
+    confidence: f32,
+    #[serde(rename = "File", default)]
+    file: Option<PathBuf>,
+}
+
+/// A detection read from a results file, placed in time and space.
+#[derive(Debug, Clone)]
+pub struct ResultRecord {
+    /// Site the recording belongs to.
+    pub site: String,
+    /// Wall-clock time of the detection.
+    pub timestamp: NaiveDateTime,
+    /// Scientific name of the species.
+    pub scientific_name: String,
+    /// Common name of the species.
+    pub common_name: String,
+    /// Detection confidence (0.0-1.0).
+    pub confidence: f32,
+}
+
+/// Recursively collect birda CSV result files under `dir`, sorted by path.
+///
+/// Combined tables, which repeat the per-file detections, and the
+/// dead-letter directory with failed recordings and their `.error.json`
+/// records are skipped.
+///
+/// # Errors
+///
+/// Returns an error if a directory cannot be read.
+pub fn collect_result_files(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut files = Vec::new();
+    collect_recursive(dir, &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+fn collect_recursive(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), Error> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(name) = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+        else {
+            continue;
+        };
+        if path.is_dir() {
+            if name != dead_letter::DIR_NAME {
+                collect_recursive(&path, files)?;
+            }
+        } else if is_result_filename(&name) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Whether `name` is a per-file results CSV.
+fn is_result_filename(name: &str) -> bool {
+    name.ends_with(output_extensions::CSV) && !is_combined_filename(name)
+}
+
+/// Read a results file below `root`, keeping detections at or above
+/// `min_confidence`.
+///
+/// The site is the first directory below `root` containing the file (or the
+/// name of `root` itself for files directly inside it). The recording start is
+/// taken from a `YYYYMMDD[_HHMMSS]` timestamp in the audio or result file name,
+/// falling back to the audio file's modification time and then the result
+/// file's; each detection's time is the recording start plus its offset.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be parsed or no recording time can be
+/// determined.
+pub fn read_result_file(
+    root: &Path,
+    path: &Path,
+    min_confidence: f32,
+) -> Result<Vec<ResultRecord>, Error> {
+    let site = site_name(root, path);
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .map_err(|e| Error::DetectionParseFailed {
+            path: path.to_path_buf(),
+            source: Box::new(e),
+        })?;
+
+    let mut records = Vec::new();
+    let mut recording_start = None;
+
+    for (line_num, result) in reader.deserialize::<ResultCsvRecord>().enumerate() {
+        let record = result.map_err(|e| Error::InvalidDetectionFormat {
+            message: format!("{}: line {}: {e}", path.display(), line_num + 2),
+        })?;
+        if record.confidence < min_confidence {
+            continue;
+        }
+
+        let start = match recording_start {
+            Some(start) => start,
+            None => {
+                let start =
+                    resolve_recording_start(path, record.file.as_deref()).ok_or_else(|| {
+                        Error::InvalidDetectionFormat {
+                            message: format!("{}: cannot determine recording date", path.display()),
+                        }
+                    })?;
+                recording_start = Some(start);
+                start
+            }
+        };
+
+        #[allow(clippy::cast_possible_truncation)]
+        let offset = TimeDelta::milliseconds((record.start * 1000.0).round() as i64);
+        records.push(ResultRecord {
+            site: site.clone(),
+            timestamp: start + offset,
+            scientific_name: record.scientific_name,
+            common_name: record.common_name,
+            confidence: record.confidence,
+        });
+    }
+
+    Ok(records)
+}
+
+/// Determine when the recording behind a results file started.
+pub(super) fn resolve_recording_start(
+    result_path: &Path,
+    audio_path: Option<&Path>,
+) -> Option<NaiveDateTime> {
+    audio_path
+        .and_then(timestamp_from_filename)
+        .or_else(|| timestamp_from_filename(result_path))
+        .or_else(|| audio_path.and_then(recording_timestamp))
+        .or_else(|| recording_timestamp(result_path))
+}
+
+/// Site name for a results file: the first directory below `root`.
+fn site_name(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let mut components = relative.components();
+    components.next_back();
+    components
+        .next()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .or_else(|| root.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| ".".to_string())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_site_name() {
+        let root = Path::new("/results");
+        assert_eq!(
+            site_name(root, Path::new("/results/north/2024/a.BirdNET.results.csv")),
+            "north"
+        );
+        assert_eq!(
+            site_name(root, Path::new("/results/a.BirdNET.results.csv")),
+            "results"
+        );
+    }
+
+    #[test]
+    fn test_collect_result_files_skips_combined_and_failed() {
+        let dir = tempfile::tempdir().unwrap();
+        let failed = dir.path().join(dead_letter::DIR_NAME);
+        std::fs::create_dir(&failed).unwrap();
+        for path in [
+            dir.path().join("a.BirdNET.results.csv"),
+            dir.path().join("BirdNET_CombinedTable.csv"),
+            dir.path().join("north_CombinedTable.csv"),
+            dir.path().join("BirdNET_Kaleidoscope.csv"),
+            failed.join("b.wav.error.json"),
+            failed.join("c.BirdNET.results.csv"),
+        ] {
+            std::fs::write(path, "").unwrap();
+        }
+
+        let files = collect_result_files(dir.path()).unwrap();
+        assert_eq!(files, vec![dir.path().join("a.BirdNET.results.csv")]);
+    }
+
+    #[test]
+    fn test_read_result_file_uses_filename_timestamp() {
+        let dir = tempfile::tempdir().unwrap();
+        let site = dir.path().join("north");
+        std::fs::create_dir(&site).unwrap();
+        let path = site.join("20240501_050000.BirdNET.results.csv");
+        std::fs::write(
+            &path,
+            "Start (s),End (s),Scientific name,Common name,Confidence,File\n\
+             60.0,63.0,Parus major,Great Tit,0.9,/audio/20240501_050000.wav\n\
+             90.0,93.0,Turdus merula,Eurasian Blackbird,0.2,/audio/20240501_050000.wav\n",
+        )
+        .unwrap();
+
+        let records = read_result_file(dir.path(), &path, 0.5).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].site, "north");
+        assert_eq!(records[0].timestamp.to_string(), "2024-05-01 05:01:00");
+    }
+}
@@ -0,0 +1,257 @@
+//! Species review page with audio snippets of the top detections.
+//!
+//! The page lists every detected species with its detection count and plays
+//! short snippets of the most confident detections in the browser, so a
+//! site's species list can be validated without opening the recordings.
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Component, Path, PathBuf};
+
+use super::calendar::escape_xml;
+use crate::clipper::ParsedDetection;
+
+/// One of the top detections of a species.
+#[derive(Debug, Clone)]
+pub struct ReviewSnippet {
+    /// Results file the detection was read from.
+    pub detection_file: PathBuf,
+    /// Source recording, if known.
+    pub audio_file: Option<PathBuf>,
+    /// Detection start in seconds.
+    pub start: f64,
+    /// Detection end in seconds.
+    pub end: f64,
+    /// Detection confidence (0.0-1.0).
+    pub confidence: f32,
+    /// Audio `src` on the page: a relative clip path or a `data:` URL.
+    pub audio_src: Option<String>,
+}
+
+/// Review summary of one species.
+#[derive(Debug, Clone)]
+pub struct SpeciesReview {
+    /// Scientific name of the species.
+    pub scientific_name: String,
+    /// Common name of the species.
+    pub common_name: String,
+    /// Number of detections.
+    pub detections: usize,
+    /// Highest detection confidence.
+    pub max_confidence: f32,
+    /// Most confident detections, best first.
+    pub snippets: Vec<ReviewSnippet>,
+}
+
+/// Summarize detections per species, keeping the `top` most confident
+/// detections of each.
+///
+/// Species are ordered by detection count, most detected first.
+pub fn species_reviews(
+    results: &[(PathBuf, Vec<ParsedDetection>)],
+    top: usize,
+) -> Vec<SpeciesReview> {
+    let mut species: HashMap<&str, SpeciesReview> = HashMap::new();
+    for (detection_file, detections) in results {
+        for d in detections {
+            let review = species
+                .entry(d.scientific_name.as_str())
+                .or_insert_with(|| SpeciesReview {
+                    scientific_name: d.scientific_name.clone(),
+                    common_name: d.common_name.clone(),
+                    detections: 0,
+                    max_confidence: 0.0,
+                    snippets: Vec::new(),
+                });
+            review.detections += 1;
+            review.max_confidence = review.max_confidence.max(d.confidence);
+
+            // Keep the snippets sorted and bounded instead of collecting all
+            let pos = review
+                .snippets
+                .partition_point(|s| s.confidence >= d.confidence);
+            if pos < top {
+                review.snippets.insert(
+                    pos,
+                    ReviewSnippet {
+                        detection_file: detection_file.clone(),
+                        audio_file: d.file.clone(),
+                        start: d.start,
+                        end: d.end,
+                        confidence: d.confidence,
+                        audio_src: None,
+                    },
+                );
+                review.snippets.truncate(top);
+            }
+        }
+    }
+
+    let mut reviews: Vec<SpeciesReview> = species.into_values().collect();
+    reviews.sort_by(|a, b| {
+        b.detections
+            .cmp(&a.detections)
+            .then_with(|| a.scientific_name.cmp(&b.scientific_name))
+    });
+    reviews
+}
+
+/// Render the review as a self-contained HTML page.
+pub fn review_html(source: &Path, reviews: &[SpeciesReview]) -> String {
+    let total: usize = reviews.iter().map(|r| r.detections).sum();
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    let _ = writeln!(
+        html,
+        "<title>Species review: {}</title>",
+        escape_xml(&source.display().to_string())
+    );
+    html.push_str(
+        "<style>\
+         body{font-family:sans-serif;margin:2em}\
+         table{border-collapse:collapse}\
+         th,td{border-bottom:1px solid #ddd;padding:.4em .8em;text-align:left;vertical-align:top}\
+         .snippet{margin-bottom:.4em}\
+         .snippet span{display:block;font-size:.85em;color:#555}\
+         audio{height:2em}\
+         </style>\n</head>\n<body>\n",
+    );
+    let _ = writeln!(
+        html,
+        "<h1>Species review</h1>\n<p>{} species, {total} detections in {}</p>",
+        reviews.len(),
+        escape_xml(&source.display().to_string())
+    );
+    html.push_str(
+        "<table>\n<thead><tr><th>Species</th><th>Detections</th><th>Max confidence</th>\
+         <th>Top detections</th></tr></thead>\n<tbody>\n",
+    );
+
+    for review in reviews {
+        let _ = write!(
+            html,
+            "<tr><td><b>{}</b><br><i>{}</i></td><td>{}</td><td>{:.0}%</td><td>",
+            escape_xml(&review.common_name),
+            escape_xml(&review.scientific_name),
+            review.detections,
+            review.max_confidence * 100.0
+        );
+        for snippet in &review.snippets {
+            let source = snippet
+                .audio_file
+                .as_deref()
+                .unwrap_or(&snippet.detection_file);
+            let _ = write!(
+                html,
+                "<div class=\"snippet\"><span>{:.0}% &middot; {} &middot; {:.1}&ndash;{:.1} s</span>",
+                snippet.confidence * 100.0,
+                escape_xml(&source.file_name().unwrap_or_default().to_string_lossy()),
+                snippet.start,
+                snippet.end
+            );
+            if let Some(src) = &snippet.audio_src {
+                let _ = write!(
+                    html,
+                    "<audio controls preload=\"none\" src=\"{}\"></audio>",
+                    escape_xml(src)
+                );
+            }
+            html.push_str("</div>");
+        }
+        html.push_str("</td></tr>\n");
+    }
+
+    html.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+    html
+}
+
+/// `data:` URL embedding a WAV file.
+pub fn wav_data_url(wav: &[u8]) -> String {
+    format!("data:audio/wav;base64,{}", STANDARD.encode(wav))
+}
+
+/// Relative URL of `path`, with components percent-encoded.
+pub fn path_url(path: &Path) -> String {
+    let mut url = String::new();
+    for component in path.components() {
+        let Component::Normal(part) = component else {
+            continue;
+        };
+        if !url.is_empty() {
+            url.push('/');
+        }
+        for byte in part.to_string_lossy().bytes() {
+            if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+                url.push(char::from(byte));
+            } else {
+                let _ = write!(url, "%{byte:02X}");
+            }
+        }
+    }
+    url
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn detection(species: &str, confidence: f32, start: f64) -> ParsedDetection {
+        ParsedDetection {
+            start,
+            end: start + 3.0,
+            scientific_name: species.to_string(),
+            common_name: format!("{species} common"),
+            confidence,
+            file: None,
+        }
+    }
+
+    #[test]
+    fn test_species_reviews_keep_top_detections() {
+        let results = vec![(
+            PathBuf::from("a.BirdNET.results.csv"),
+            vec![
+                detection("Parus major", 0.6, 0.0),
+                detection("Parus major", 0.9, 3.0),
+                detection("Turdus merula", 0.7, 6.0),
+                detection("Parus major", 0.8, 9.0),
+            ],
+        )];
+
+        let reviews = species_reviews(&results, 2);
+        assert_eq!(reviews.len(), 2);
+        assert_eq!(reviews[0].scientific_name, "Parus major");
+        assert_eq!(reviews[0].detections, 3);
+        let starts: Vec<f64> = reviews[0].snippets.iter().map(|s| s.start).collect();
+        assert_eq!(starts, vec![3.0, 9.0]);
+    }
+
+    #[test]
+    fn test_review_html_escapes_and_embeds_audio() {
+        let mut reviews = species_reviews(
+            &[(PathBuf::from("a.csv"), vec![detection("A <b>", 0.9, 0.0)])],
+            1,
+        );
+        reviews[0].snippets[0].audio_src = Some("clips/A%20b.wav".to_string());
+        let html = review_html(Path::new("results"), &reviews);
+        assert!(html.contains("A &lt;b&gt;"));
+        assert!(html.contains(r#"src="clips/A%20b.wav""#));
+    }
+
+    #[test]
+    fn test_path_url() {
+        assert_eq!(
+            path_url(Path::new("review_clips/Parus major/x#1.wav")),
+            "review_clips/Parus%20major/x%231.wav"
+        );
+    }
+
+    #[test]
+    fn test_wav_data_url() {
+        assert_eq!(wav_data_url(b"fo"), "data:audio/wav;base64,Zm8=");
+    }
+}
@@ -0,0 +1,282 @@
+//! Append-only log of analysis runs.
+//!
+//! Every analysis invocation appends one JSON line to `runs.jsonl` in the
+//! config directory, recording the command line, resolved settings, file
+//! counts, birda version, duration and outcome. `birda runs list` and
+//! `birda runs show` read it back, giving a provenance trail of how results
+//! were produced. Lines are never rewritten; unreadable lines are skipped.
+//! A run stopped with Ctrl+C, including every watch session, is appended by
+//! the interrupt handler with the counts reached so far.
+
+use crate::constants::runs::LOG_FILE;
+use crate::error::{Error, Result};
+use crate::output::{InputSample, RunSettings, Station};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use tracing::warn;
+
+/// Run in progress, appended by the interrupt handler if the process is
+/// stopped before the run ends.
+static ACTIVE_RUN: Mutex<Option<RunRecord>> = Mutex::new(None);
+
+/// How an analysis run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunOutcome {
+    /// Every file was processed.
+    Success,
+    /// The run finished, but some files failed.
+    CompletedWithErrors,
+    /// The run stopped with an error.
+    Failed,
+    /// The process was interrupted (Ctrl+C) before the run ended.
+    Interrupted,
+}
+
+impl std::fmt::Display for RunOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Success => write!(f, "success"),
+            Self::CompletedWithErrors => write!(f, "completed with errors"),
+            Self::Failed => write!(f, "failed"),
+            Self::Interrupted => write!(f, "interrupted"),
+        }
+    }
+}
+
+/// One analysis run in the runs log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Run identifier (start time and process ID).
+    pub run_id: String,
+    /// When the run started.
+    pub started: DateTime<Utc>,
+    /// Wall-clock duration in seconds.
+    pub duration_secs: f64,
+    /// Version of birda that ran the analysis.
+    pub birda_version: String,
+    /// Host the run was executed on.
+    pub hostname: String,
+    /// Station the run was executed as.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub station: Option<Station>,
+    /// Command line arguments, including the program name.
+    pub command_line: Vec<String>,
+    /// Input files and directories.
+    pub inputs: Vec<PathBuf>,
+    /// Settings that affect detections, once they were resolved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<RunSettings>,
+    /// Random sample of the inputs that was analyzed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sample: Option<InputSample>,
+    /// Files analyzed.
+    pub files_processed: usize,
+    /// Files skipped because results already existed.
+    pub files_skipped: usize,
+    /// Files that failed.
+    pub files_failed: usize,
+    /// Detections written.
+    pub detections: usize,
+    /// How the run ended.
+    pub outcome: RunOutcome,
+    /// Error that stopped the run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl RunRecord {
+    /// Identifier for a run started at `started` by this process.
+    pub fn new_id(started: DateTime<Utc>) -> String {
+        format!(
+            "{}-{:x}",
+            started.format("%Y%m%dT%H%M%SZ"),
+            std::process::id()
+        )
+    }
+}
+
+/// Path of the runs log in the config directory.
+///
+/// # Errors
+///
+/// Returns an error if the config directory cannot be determined.
+pub fn log_path() -> Result<PathBuf> {
+    Ok(crate::config::config_dir()?.join(LOG_FILE))
+}
+
+/// Append `record` to the runs log at `path`, creating it if needed.
+///
+/// # Errors
+///
+/// Returns an error if the log cannot be written.
+pub fn append(path: &Path, record: &RunRecord) -> Result<()> {
+    let mut line = serde_json::to_string(record).map_err(|e| Error::Internal {
+        message: format!("Failed to serialize run record: {e}"),
+    })?;
+    line.push('\n');
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // One write per record keeps concurrent runs from interleaving lines
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// Register `record` as the run in progress, or clear it with `None`.
+pub fn set_active(record: Option<RunRecord>) {
+    *ACTIVE_RUN.lock().unwrap_or_else(PoisonError::into_inner) = record;
+}
+
+/// Apply `update` to the run in progress, if there is one.
+pub fn update_active(update: impl FnOnce(&mut RunRecord)) {
+    if let Some(record) = ACTIVE_RUN
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_mut()
+    {
+        update(record);
+    }
+}
+
+/// Take the run in progress, marked as interrupted now.
+fn take_interrupted() -> Option<RunRecord> {
+    let mut record = ACTIVE_RUN
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take()?;
+    record.outcome = RunOutcome::Interrupted;
+    #[allow(clippy::cast_precision_loss)]
+    let duration_secs = (Utc::now() - record.started).num_milliseconds() as f64 / 1000.0;
+    record.duration_secs = duration_secs;
+    Some(record)
+}
+
+/// Append the run in progress to the runs log as interrupted. Called on
+/// signal, before the process exits.
+pub fn record_interrupted() {
+    let Some(record) = take_interrupted() else {
+        return;
+    };
+    if let Err(e) = log_path().and_then(|path| append(&path, &record)) {
+        warn!("Failed to record interrupted run in the runs log: {e}");
+    }
+}
+
+/// Read every run from the log at `path`, oldest first.
+///
+/// A missing log has no runs.
+///
+/// # Errors
+///
+/// Returns an error if the log exists but cannot be read.
+pub fn load(path: &Path) -> Result<Vec<RunRecord>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let reader = BufReader::new(std::fs::File::open(path)?);
+    let mut runs = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(record) => runs.push(record),
+            Err(e) => warn!("Skipping line {} of {}: {e}", index + 1, path.display()),
+        }
+    }
+    Ok(runs)
+}
+
+/// Find the run whose ID starts with `id`.
+///
+/// # Errors
+///
+/// Returns [`Error::RunNotFound`] if no run or more than one run matches.
+pub fn find<'a>(runs: &'a [RunRecord], id: &str) -> Result<&'a RunRecord> {
+    let mut matches = runs.iter().filter(|run| run.run_id.starts_with(id));
+    match (matches.next(), matches.next()) {
+        (Some(run), None) => Ok(run),
+        _ => Err(Error::RunNotFound { id: id.to_string() }),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn record(run_id: &str) -> RunRecord {
+        RunRecord {
+            run_id: run_id.to_string(),
+            started: Utc::now(),
+            duration_secs: 1.5,
+            birda_version: "1.0.0".to_string(),
+            hostname: "lab-01".to_string(),
+            station: None,
+            command_line: vec!["birda".to_string(), "rec.wav".to_string()],
+            inputs: vec![PathBuf::from("rec.wav")],
+            settings: None,
+            sample: None,
+            files_processed: 1,
+            files_skipped: 0,
+            files_failed: 0,
+            detections: 3,
+            outcome: RunOutcome::Success,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_append_and_load_skip_broken_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join(LOG_FILE);
+        assert!(load(&path).unwrap().is_empty());
+
+        append(&path, &record("20250101T000000Z-1")).unwrap();
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"{\"run_id\":\n")
+            .unwrap();
+        append(&path, &record("20250102T000000Z-2")).unwrap();
+
+        let runs = load(&path).unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[1].run_id, "20250102T000000Z-2");
+    }
+
+    #[test]
+    fn test_take_interrupted_run() {
+        set_active(Some(record("20250103T000000Z-3")));
+        update_active(|run| run.files_processed = 5);
+
+        let run = take_interrupted().unwrap();
+        assert_eq!(run.outcome, RunOutcome::Interrupted);
+        assert_eq!(run.files_processed, 5);
+        assert!(take_interrupted().is_none());
+    }
+
+    #[test]
+    fn test_find_by_unique_prefix() {
+        let runs = vec![record("20250101T000000Z-1"), record("20250102T000000Z-2")];
+        assert_eq!(
+            find(&runs, "20250102").unwrap().run_id,
+            "20250102T000000Z-2"
+        );
+        assert!(matches!(
+            find(&runs, "2025"),
+            Err(Error::RunNotFound { .. })
+        ));
+        assert!(find(&runs, "2024").is_err());
+    }
+}
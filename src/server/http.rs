@@ -0,0 +1,70 @@
+//! Request and response helpers on top of `tiny_http`.
+
+use std::collections::HashMap;
+use tiny_http::{Header, Request, Response};
+
+/// A request that could not be served, with the status to answer it with.
+#[derive(Debug)]
+pub struct HttpError {
+    /// HTTP status code.
+    pub status: u16,
+    /// Description of the problem.
+    pub message: String,
+}
+
+impl HttpError {
+    /// Create an error with `status` and `message`.
+    pub fn new(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+/// Split a request URL into its path and decoded query parameters.
+pub fn split_url(url: &str) -> (&str, HashMap<String, String>) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let query = form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect();
+    (path, query)
+}
+
+/// Value of the header `name`, if the request has one.
+pub fn header<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv(name))
+        .map(|h| h.value.as_str())
+}
+
+/// Answer `request` with a JSON body.
+pub fn respond_json(request: Request, status: u16, body: String) -> std::io::Result<()> {
+    let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+        .map_err(|()| std::io::Error::other("invalid Content-Type header"))?;
+    request.respond(
+        Response::from_string(body)
+            .with_status_code(status)
+            .with_header(content_type),
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_url() {
+        let (path, query) = split_url("/analyze?min_confidence=0.5&name=dawn%20chorus.mp3");
+        assert_eq!(path, "/analyze");
+        assert_eq!(query["min_confidence"], "0.5");
+        assert_eq!(query["name"], "dawn chorus.mp3");
+
+        let (path, query) = split_url("/models");
+        assert_eq!(path, "/models");
+        assert!(query.is_empty());
+    }
+}
@@ -0,0 +1,410 @@
+//! HTTP API for `birda serve`.
+//!
+//! Keeps one classifier loaded, with any `TensorRT` engine already built, so
+//! GUI and web integrations can analyze recordings without paying model load
+//! and warmup costs per file. Requests are handled by a few worker threads,
+//! so one slow upload does not hold up the others.
+//!
+//! - `GET /models`: configured models (`model_list` payload)
+//! - `GET /providers`: compiled-in execution providers (`providers` payload)
+//! - `POST /analyze`: classify audio sent as the request body, or a local
+//!   file named by a JSON body `{"path": "..."}` when the server was started
+//!   with `--allow-path` and the file is under that directory (`analysis`
+//!   payload)
+//!
+//! Responses use the JSON envelope of `--output-mode json`: a `result` event
+//! on success and an `error` event otherwise.
+
+mod http;
+
+use crate::audio::extension_for_content_type;
+use crate::cli::validators::parse_confidence;
+use crate::constants::serve::{MAX_JSON_BODY_BYTES, WORKERS};
+use crate::error::Error;
+use crate::inference::BirdClassifier;
+use crate::output::{
+    AnalysisPayload, Detection, DetectionInfo, ErrorPayload, ErrorSeverity, EventType,
+    JsonEnvelope, ModelListPayload, ProvidersPayload, ResultType, Station,
+};
+use crate::pipeline::{classify_chunks, for_each_batch};
+use http::HttpError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tiny_http::{Method, Request};
+use tracing::{info, warn};
+
+/// Analysis settings that requests can override.
+#[derive(Debug, Clone)]
+pub struct ServerSettings {
+    /// Model name from configuration, reported with each analysis.
+    pub model: String,
+    /// Default minimum confidence (query parameter `min_confidence`).
+    pub min_confidence: f32,
+    /// Default segment overlap in seconds (query parameter `overlap`).
+    pub overlap: f32,
+    /// Inference batch size.
+    pub batch_size: usize,
+    /// Largest accepted upload in bytes.
+    pub max_upload_bytes: u64,
+    /// Canonical directory whose files may be analyzed by path; `None`
+    /// disables analysis by path.
+    pub allowed_root: Option<PathBuf>,
+    /// Station recorded on every response, if one is configured.
+    pub station: Option<Station>,
+}
+
+/// JSON body naming a local file to analyze.
+#[derive(Debug, Deserialize)]
+struct AnalyzeRequest {
+    path: PathBuf,
+}
+
+/// An uploaded recording, written to a temporary file that is removed on drop.
+struct Upload(tempfile::NamedTempFile);
+
+/// The API server and the state it keeps between requests.
+pub struct Server {
+    classifier: BirdClassifier,
+    settings: ServerSettings,
+    models: ModelListPayload,
+    providers: ProvidersPayload,
+    /// Directory only this user can access, holding uploads while they are
+    /// analyzed.
+    upload_dir: tempfile::TempDir,
+}
+
+impl Server {
+    /// Create a server around a loaded (and warmed up) classifier.
+    ///
+    /// # Errors
+    /// Returns an error if the upload directory cannot be created.
+    pub fn new(
+        classifier: BirdClassifier,
+        settings: ServerSettings,
+        models: ModelListPayload,
+        providers: ProvidersPayload,
+    ) -> std::io::Result<Self> {
+        let upload_dir = tempfile::Builder::new().prefix("birda-serve-").tempdir()?;
+        Ok(Self {
+            classifier,
+            settings,
+            models,
+            providers,
+            upload_dir,
+        })
+    }
+
+    /// Serve requests from `http` on `WORKERS` threads until the process is
+    /// stopped.
+    pub fn run(&self, http: &tiny_http::Server) {
+        std::thread::scope(|scope| {
+            for _ in 0..WORKERS {
+                scope.spawn(|| {
+                    loop {
+                        match http.recv() {
+                            Ok(request) => self.handle(request),
+                            Err(e) => warn!("Failed to accept request: {e}"),
+                        }
+                    }
+                });
+            }
+        });
+    }
+
+    /// Answer one request.
+    fn handle(&self, mut request: Request) {
+        info!("{} {}", request.method(), request.url());
+        let (status, body) = match self.respond(&mut request) {
+            Ok(body) => (200, body),
+            Err(e) => {
+                warn!("Request failed ({}): {}", e.status, e.message);
+                (e.status, error_body(&e, self.settings.station.as_ref()))
+            }
+        };
+        if let Err(e) = http::respond_json(request, status, body) {
+            warn!("Failed to answer request: {e}");
+        }
+    }
+
+    /// Route a request to its endpoint.
+    fn respond(&self, request: &mut Request) -> Result<String, HttpError> {
+        let url = request.url().to_string();
+        let (path, query) = http::split_url(&url);
+        let method = request.method().clone();
+        let station = self.settings.station.as_ref();
+        match (&method, path) {
+            (Method::Get, "/models") => result_body(&self.models, station),
+            (Method::Get, "/providers") => result_body(&self.providers, station),
+            (Method::Post, "/analyze") => result_body(&self.analyze(request, &query)?, station),
+            (method, path @ ("/models" | "/providers" | "/analyze")) => Err(HttpError::new(
+                405,
+                format!("{method} is not allowed on {path}"),
+            )),
+            (_, path) => Err(HttpError::new(404, format!("no such endpoint: {path}"))),
+        }
+    }
+
+    /// Handle `POST /analyze`.
+    fn analyze(
+        &self,
+        request: &mut Request,
+        query: &HashMap<String, String>,
+    ) -> Result<AnalysisPayload, HttpError> {
+        let min_confidence = match query.get("min_confidence") {
+            Some(value) => parse_confidence(value).map_err(|e| HttpError::new(400, e))?,
+            None => self.settings.min_confidence,
+        };
+        let overlap = match query.get("overlap") {
+            Some(value) => value
+                .parse()
+                .map_err(|_| HttpError::new(400, format!("invalid overlap '{value}'")))?,
+            None => self.settings.overlap,
+        };
+        let segment_duration = self.classifier.segment_duration();
+        if !(0.0..segment_duration).contains(&overlap) {
+            return Err(HttpError::new(
+                400,
+                format!(
+                    "overlap must be at least 0 and less than the {segment_duration}s segment duration, got {overlap}"
+                ),
+            ));
+        }
+
+        let content_type = http::header(request, "Content-Type").map(str::to_string);
+        let is_json = content_type
+            .as_deref()
+            .is_some_and(|t| t.to_ascii_lowercase().starts_with("application/json"));
+        let (path, name, _upload) = if is_json {
+            let path = self.requested_path(request)?;
+            (path.clone(), path, None)
+        } else {
+            let upload = self.save_upload(request, query, content_type.as_deref())?;
+            let name = query
+                .get("name")
+                .map_or_else(|| PathBuf::from("upload"), PathBuf::from);
+            (upload.0.path().to_path_buf(), name, Some(upload))
+        };
+
+        let (duration_secs, detections) = self
+            .detect(&path, &name, overlap, min_confidence)
+            .map_err(|e| HttpError::new(status_for(&e), e.to_string()))?;
+        Ok(AnalysisPayload {
+            result_type: ResultType::Analysis,
+            file: name,
+            model: self.settings.model.clone(),
+            duration_secs,
+            detections: detections.iter().map(DetectionInfo::from).collect(),
+        })
+    }
+
+    /// Read the JSON body of a request naming a local file, and check the
+    /// file is under the allowed root.
+    fn requested_path(&self, request: &mut Request) -> Result<PathBuf, HttpError> {
+        let Some(root) = &self.settings.allowed_root else {
+            return Err(HttpError::new(
+                403,
+                "analysis by path is disabled; start the server with --allow-path <DIR>",
+            ));
+        };
+        let mut body = Vec::new();
+        request
+            .as_reader()
+            .take(MAX_JSON_BODY_BYTES + 1)
+            .read_to_end(&mut body)
+            .map_err(|e| HttpError::new(400, format!("failed to read request body: {e}")))?;
+        if body.len() as u64 > MAX_JSON_BODY_BYTES {
+            return Err(HttpError::new(
+                413,
+                format!("JSON body exceeds {MAX_JSON_BODY_BYTES} bytes"),
+            ));
+        }
+        let body: AnalyzeRequest = serde_json::from_slice(&body)
+            .map_err(|e| HttpError::new(400, format!("invalid request body: {e}")))?;
+        allowed_path(root, &body.path)
+    }
+
+    /// Stream an uploaded recording to a temporary file for decoding.
+    fn save_upload(
+        &self,
+        request: &mut Request,
+        query: &HashMap<String, String>,
+        content_type: Option<&str>,
+    ) -> Result<Upload, HttpError> {
+        let max = self.settings.max_upload_bytes;
+        let too_large = || HttpError::new(413, format!("request body exceeds {max} bytes"));
+        if request.body_length().is_some_and(|len| len as u64 > max) {
+            return Err(too_large());
+        }
+        // The extension only hints at the format; decoding probes the content
+        let extension = query
+            .get("name")
+            .and_then(|name| Path::new(name).extension())
+            .and_then(|ext| ext.to_str())
+            .map(str::to_string)
+            .or_else(|| {
+                content_type
+                    .and_then(extension_for_content_type)
+                    .map(str::to_string)
+            })
+            .unwrap_or_else(|| "bin".to_string());
+        // A fresh file with a random name, so nothing already at the path is
+        // followed or overwritten
+        let mut file = tempfile::Builder::new()
+            .prefix("upload-")
+            .suffix(&format!(".{extension}"))
+            .tempfile_in(self.upload_dir.path())
+            .map_err(|e| HttpError::new(500, format!("failed to store upload: {e}")))?;
+        let written = std::io::copy(&mut request.as_reader().take(max + 1), &mut file)
+            .map_err(|e| HttpError::new(400, format!("failed to read request body: {e}")))?;
+        if written > max {
+            return Err(too_large());
+        }
+        if written == 0 {
+            return Err(HttpError::new(
+                400,
+                "empty request body; send audio, or JSON with a \"path\"",
+            ));
+        }
+        Ok(Upload(file))
+    }
+
+    /// Decode and classify a recording, reporting detections under `name`.
+    ///
+    /// The recording is streamed a batch at a time, so a small compressed
+    /// upload cannot expand to more decoded audio than fits in memory.
+    fn detect(
+        &self,
+        path: &Path,
+        name: &Path,
+        overlap: f32,
+        min_confidence: f32,
+    ) -> crate::error::Result<(f32, Vec<Detection>)> {
+        let batch_size = self.settings.batch_size;
+        let mut detections = Vec::new();
+        let duration_secs = for_each_batch(&self.classifier, path, overlap, batch_size, |batch| {
+            detections.extend(classify_chunks(
+                &self.classifier,
+                batch,
+                min_confidence,
+                batch_size,
+                name,
+            )?);
+            Ok(())
+        })?;
+        detections.sort_by(|a, b| {
+            a.start_time
+                .total_cmp(&b.start_time)
+                .then_with(|| b.confidence.total_cmp(&a.confidence))
+        });
+        Ok((duration_secs, detections))
+    }
+}
+
+/// Status for an analysis error: the recording's fault or the server's.
+const fn status_for(error: &Error) -> u16 {
+    match error {
+        Error::AudioOpen { .. }
+        | Error::UnsupportedAudioFormat { .. }
+        | Error::AudioDecode { .. }
+        | Error::AudioChannel { .. }
+        | Error::NoAudioTracks { .. }
+        | Error::Io(_) => 422,
+        _ => 500,
+    }
+}
+
+/// Resolve `path` and require it to be inside the canonical `root`.
+fn allowed_path(root: &Path, path: &Path) -> Result<PathBuf, HttpError> {
+    let resolved = path
+        .canonicalize()
+        .map_err(|e| HttpError::new(404, format!("cannot open {}: {e}", path.display())))?;
+    if !resolved.starts_with(root) {
+        return Err(HttpError::new(
+            403,
+            format!("{} is outside {}", path.display(), root.display()),
+        ));
+    }
+    Ok(resolved)
+}
+
+/// Serialize a payload as a `result` event from `station`.
+fn result_body<T: Serialize>(payload: &T, station: Option<&Station>) -> Result<String, HttpError> {
+    serde_json::to_string(
+        &JsonEnvelope::new(EventType::Result, payload).with_station(station.cloned()),
+    )
+    .map_err(|e| HttpError::new(500, format!("failed to serialize response: {e}")))
+}
+
+/// Serialize a failed request as an `error` event from `station`.
+fn error_body(error: &HttpError, station: Option<&Station>) -> String {
+    let code = match error.status {
+        400 => "bad_request",
+        403 => "forbidden",
+        404 => "not_found",
+        405 => "method_not_allowed",
+        413 => "request_too_large",
+        422 => "audio_error",
+        _ => "internal_error",
+    };
+    let payload = ErrorPayload {
+        code: code.to_string(),
+        severity: ErrorSeverity::Fatal,
+        message: error.message.clone(),
+        suggestion: None,
+    };
+    serde_json::to_string(
+        &JsonEnvelope::new(EventType::Error, payload).with_station(station.cloned()),
+    )
+    .unwrap_or_else(|_| "{}".to_string())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_body_is_error_event() {
+        let body = error_body(&HttpError::new(404, "no such endpoint: /nope"), None);
+        let value: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(value["event"], "error");
+        assert_eq!(value["payload"]["code"], "not_found");
+        assert_eq!(value["payload"]["message"], "no such endpoint: /nope");
+    }
+
+    #[test]
+    fn test_allowed_path_stays_under_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let inside = root.join("dawn.wav");
+        std::fs::write(&inside, b"").unwrap();
+
+        assert_eq!(allowed_path(&root, &inside).unwrap(), inside);
+        let escape = root.join("..").join(root.file_name().unwrap()).join("..");
+        assert_eq!(allowed_path(&root, &escape).unwrap_err().status, 403);
+        assert_eq!(
+            allowed_path(&root, &root.join("missing.wav"))
+                .unwrap_err()
+                .status,
+            404
+        );
+    }
+
+    #[test]
+    fn test_status_for_audio_errors() {
+        assert_eq!(
+            status_for(&Error::UnsupportedAudioFormat {
+                format: "ogg".to_string()
+            }),
+            422
+        );
+        assert_eq!(
+            status_for(&Error::Internal {
+                message: "boom".to_string()
+            }),
+            500
+        );
+    }
+}
@@ -1,6 +1,7 @@
 //! SHA256 checksum verification for downloaded archives.
 
 use crate::error::{Error, Result};
+use crate::utils::hash::to_hex;
 use sha2::{Digest, Sha256};
 use std::path::Path;
 
@@ -28,14 +29,7 @@ pub fn verify_sha256(path: &Path, expected_hex: &str) -> Result<()> {
 
 /// Compute the SHA256 hex digest of raw bytes.
 fn hex_digest(data: &[u8]) -> String {
-    let hash = Sha256::digest(data);
-    // Format each byte as two lowercase hex characters
-    hash.iter()
-        .fold(String::with_capacity(64), |mut acc, byte| {
-            use std::fmt::Write;
-            let _ = write!(acc, "{byte:02x}");
-            acc
-        })
+    to_hex(&Sha256::digest(data))
 }
 
 #[cfg(test)]
@@ -3,7 +3,7 @@
 use crate::constants::calendar::DAYS_IN_MONTH;
 use crate::constants::range_filter::{DAYS_PER_WEEK, WEEKS_PER_YEAR, YEAR_START_DAY};
 use crate::error::{Error, Result};
-use chrono::Datelike;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
 use std::path::Path;
 
 /// Convert month/day to week number (1-48).
@@ -102,6 +102,67 @@ pub fn auto_detect_day_of_year(file_path: &Path) -> Result<u32> {
     Ok(day_of_year)
 }
 
+/// Extract a recording timestamp from a file name.
+///
+/// Recognizes the `YYYYMMDD` date used by `AudioMoth`, Song Meter and most
+/// other recorders, optionally followed by a `_`, `-` or `T` separator and an
+/// `HHMMSS` time (e.g. `20240315_063000.WAV`, `SITE1_20240315_063000.wav`).
+/// A date without a time resolves to midnight.
+///
+/// Returns `None` if the name contains no valid date.
+pub fn timestamp_from_filename(path: &Path) -> Option<NaiveDateTime> {
+    let name = path.file_name()?.to_string_lossy();
+    let bytes = name.as_bytes();
+
+    for start in 0..bytes.len() {
+        // Only consider digit runs that start at a digit boundary
+        if start > 0 && bytes[start - 1].is_ascii_digit() {
+            continue;
+        }
+        let Some(date) = parse_digits(bytes, start, 8)
+            .and_then(|digits| NaiveDate::parse_from_str(digits, "%Y%m%d").ok())
+        else {
+            continue;
+        };
+
+        let rest = start + 8;
+        let time = match bytes.get(rest) {
+            Some(b'_' | b'-' | b'T') => parse_digits(bytes, rest + 1, 6)
+                .and_then(|digits| NaiveTime::parse_from_str(digits, "%H%M%S").ok()),
+            Some(b) if b.is_ascii_digit() => continue,
+            _ => None,
+        };
+
+        return Some(date.and_time(time.unwrap_or(NaiveTime::MIN)));
+    }
+
+    None
+}
+
+/// Return the `len` ASCII digits at `start` if they form a complete digit run.
+fn parse_digits(bytes: &[u8], start: usize, len: usize) -> Option<&str> {
+    let digits = bytes.get(start..start + len)?;
+    if !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    if bytes.get(start + len).is_some_and(u8::is_ascii_digit) {
+        return None;
+    }
+    std::str::from_utf8(digits).ok()
+}
+
+/// Recording timestamp of a file from its name, falling back to its
+/// modification time.
+///
+/// Returns `None` if neither is available.
+pub fn recording_timestamp(path: &Path) -> Option<NaiveDateTime> {
+    timestamp_from_filename(path).or_else(|| {
+        let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+        let datetime: chrono::DateTime<chrono::Local> = modified.into();
+        Some(datetime.naive_local())
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -193,4 +254,23 @@ mod tests {
         // Clean up
         std::fs::remove_file(&temp_file).ok();
     }
+
+    #[test]
+    fn test_timestamp_from_filename_audiomoth() {
+        let ts = timestamp_from_filename(Path::new("/data/20240315_063000.WAV")).unwrap();
+        assert_eq!(ts.to_string(), "2024-03-15 06:30:00");
+    }
+
+    #[test]
+    fn test_timestamp_from_filename_prefixed_date_only() {
+        let ts = timestamp_from_filename(Path::new("SITE1_20240315.BirdNET.results.csv")).unwrap();
+        assert_eq!(ts.to_string(), "2024-03-15 00:00:00");
+    }
+
+    #[test]
+    fn test_timestamp_from_filename_rejects_invalid() {
+        assert!(timestamp_from_filename(Path::new("recording.wav")).is_none());
+        assert!(timestamp_from_filename(Path::new("20241345_000000.wav")).is_none());
+        assert!(timestamp_from_filename(Path::new("1234567890.wav")).is_none());
+    }
 }
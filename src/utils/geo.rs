@@ -0,0 +1,25 @@
+//! Coordinate helpers.
+
+/// Decimal places kept when snapping coordinates, hiding float noise.
+const COORDINATE_DECIMALS: i32 = 6;
+
+/// Centre of the `grid`-sized cell (in degrees) containing `coordinate`.
+///
+/// Used to share locations at a coarser resolution than they were recorded.
+pub fn snap_to_grid(coordinate: f64, grid: f64) -> f64 {
+    let snapped = ((coordinate / grid).floor() + 0.5) * grid;
+    let scale = 10f64.powi(COORDINATE_DECIMALS);
+    (snapped * scale).round() / scale
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snap_to_grid_uses_cell_centre() {
+        assert!((snap_to_grid(60.1699, 0.1) - 60.15).abs() < 1e-9);
+        assert!((snap_to_grid(-24.9384, 0.1) - -24.95).abs() < 1e-9);
+        assert!((snap_to_grid(60.1699, 1.0) - 60.5).abs() < 1e-9);
+    }
+}
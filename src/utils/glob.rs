@@ -0,0 +1,141 @@
+//! Shell-style glob patterns for input paths and `--exclude`.
+//!
+//! Supports `*` (any characters within a path component), `?` (one
+//! character), `[abc]`, `[a-z]` and `[!abc]` character classes, and `**` as a
+//! whole component matching any number of directories.
+
+use globset::{GlobBuilder, GlobMatcher};
+use std::path::{Component, Path};
+
+/// Characters that make a path a glob pattern.
+const GLOB_CHARS: [char; 3] = ['*', '?', '['];
+
+/// A compiled glob pattern.
+#[derive(Debug, Clone)]
+pub struct Glob {
+    /// Matcher for paths joined with `/`.
+    matcher: GlobMatcher,
+    /// Whether the pattern is a single component other than `**`.
+    name_pattern: bool,
+}
+
+impl Glob {
+    /// Parse `pattern`, checking that character classes are closed.
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        let components: Vec<&str> = pattern
+            .split(['/', '\\'])
+            .filter(|c| !c.is_empty() && *c != ".")
+            .collect();
+        if components.is_empty() {
+            return Err("empty pattern".to_string());
+        }
+        let matcher = GlobBuilder::new(&components.join("/"))
+            .literal_separator(true)
+            .backslash_escape(false)
+            .build()
+            .map_err(|e| e.to_string())?
+            .compile_matcher();
+        Ok(Self {
+            matcher,
+            name_pattern: components.len() == 1 && components[0] != "**",
+        })
+    }
+
+    /// Whether the pattern has a single component, matched against names.
+    pub const fn is_name_pattern(&self) -> bool {
+        self.name_pattern
+    }
+
+    /// Whether `name` (one path component) matches a single-component pattern.
+    pub fn matches_name(&self, name: &str) -> bool {
+        self.name_pattern && self.matcher.is_match(name)
+    }
+
+    /// Whether the relative `path` matches the whole pattern.
+    pub fn matches_path(&self, path: &Path) -> bool {
+        let names: Vec<String> = path
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect();
+        self.matcher.is_match(names.join("/"))
+    }
+}
+
+/// Whether `path` contains glob characters.
+pub fn is_glob(path: &Path) -> bool {
+    path.to_string_lossy().contains(GLOB_CHARS)
+}
+
+/// Split a glob path into the directory to walk and the pattern below it.
+///
+/// The directory is made of the leading components without glob characters.
+pub fn split_glob(path: &Path) -> (std::path::PathBuf, String) {
+    let mut base = std::path::PathBuf::new();
+    let mut components = path.components().peekable();
+    while let Some(component) = components.next_if(|c| !is_glob(Path::new(c.as_os_str()))) {
+        base.push(component);
+    }
+    let pattern = components
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join("/");
+    if base.as_os_str().is_empty() {
+        base.push(".");
+    }
+    (base, pattern)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_matches_name() {
+        let matches = |pattern: &str, name: &str| Glob::new(pattern).unwrap().matches_name(name);
+        assert!(matches("*.wav", "dawn.wav"));
+        assert!(!matches("*.wav", "dawn.flac"));
+        assert!(matches("S4A?????_*", "S4A01234_20240501.wav"));
+        assert!(matches("[a-c]*", "bird.wav"));
+        assert!(!matches("[!a-c]*", "bird.wav"));
+        assert!(matches("calibration", "calibration"));
+    }
+
+    #[test]
+    fn test_matches_path_with_double_star() {
+        let glob = Glob::new("**/*.wav").unwrap();
+        assert!(glob.matches_path(Path::new("a.wav")));
+        assert!(glob.matches_path(Path::new("site1/night/a.wav")));
+        assert!(!glob.matches_path(Path::new("site1/a.flac")));
+
+        let glob = Glob::new("site*/**/noise/*").unwrap();
+        assert!(glob.matches_path(Path::new("site2/noise/ref.wav")));
+        assert!(glob.matches_path(Path::new("site2/2024/noise/ref.wav")));
+        assert!(!glob.matches_path(Path::new("site2/ref.wav")));
+    }
+
+    #[test]
+    fn test_name_patterns() {
+        let glob = Glob::new("calib*").unwrap();
+        assert!(glob.is_name_pattern());
+        assert!(glob.matches_name("calibration"));
+        assert!(!Glob::new("a/b").unwrap().is_name_pattern());
+        assert!(Glob::new("[ab").is_err());
+    }
+
+    #[test]
+    fn test_split_glob() {
+        assert_eq!(
+            split_glob(Path::new("/data/site1/**/*.wav")),
+            (PathBuf::from("/data/site1"), "**/*.wav".to_string())
+        );
+        assert_eq!(
+            split_glob(Path::new("*.flac")),
+            (PathBuf::from("."), "*.flac".to_string())
+        );
+    }
+}
@@ -0,0 +1,71 @@
+//! File hashing.
+
+use crate::constants::HASH_BUFFER_SIZE;
+use crate::error::Result;
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::io::Read;
+use std::path::Path;
+
+/// Hex SHA-256 hash of a file, read in chunks so large models are not loaded
+/// into memory.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; HASH_BUFFER_SIZE];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hex_digest(hasher))
+}
+
+/// Finish `hasher` and format the digest as lowercase hex.
+pub fn hex_digest(hasher: Sha256) -> String {
+    to_hex(&hasher.finalize())
+}
+
+/// Format `bytes` as lowercase hex.
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut acc, byte| {
+            let _ = write!(acc, "{byte:02x}");
+            acc
+        })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha256_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hello.txt");
+        std::fs::write(&path, b"hello world").unwrap();
+        assert_eq!(
+            sha256_file(&path).unwrap(),
+            "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+        );
+    }
+
+    #[test]
+    fn test_hex_digest_matches_file_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hello.txt");
+        std::fs::write(&path, b"hello world").unwrap();
+        let mut hasher = Sha256::new();
+        hasher.update(b"hello ");
+        hasher.update(b"world");
+        assert_eq!(hex_digest(hasher), sha256_file(&path).unwrap());
+    }
+}
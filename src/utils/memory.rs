@@ -0,0 +1,94 @@
+//! Process memory usage.
+
+/// Resident memory of this process in bytes.
+///
+/// Read from `/proc/self/status`; `None` on platforms without procfs.
+pub fn resident_memory_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    parse_status_kib(&status, "VmRSS:").map(|kib| kib * 1024)
+}
+
+/// Peak resident memory of this process so far in bytes.
+///
+/// Read from `/proc/self/status`, or from `getrusage` on Unix platforms
+/// without procfs; `None` elsewhere.
+pub fn peak_resident_memory_bytes() -> Option<u64> {
+    std::fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| parse_status_kib(&status, "VmHWM:"))
+        .map(|kib| kib * 1024)
+        .or_else(rusage_peak_bytes)
+}
+
+/// Peak resident memory reported by `getrusage(RUSAGE_SELF)`.
+#[cfg(unix)]
+#[allow(unsafe_code)]
+fn rusage_peak_bytes() -> Option<u64> {
+    let mut usage = std::mem::MaybeUninit::<libc::rusage>::uninit();
+    // SAFETY: getrusage only writes the struct it is given and reports
+    // failure through its return value, checked before the struct is read.
+    let usage = unsafe {
+        if libc::getrusage(libc::RUSAGE_SELF, usage.as_mut_ptr()) != 0 {
+            return None;
+        }
+        usage.assume_init()
+    };
+    let max_rss = u64::try_from(usage.ru_maxrss).ok()?;
+    // macOS reports bytes, the other Unixes kibibytes
+    if cfg!(target_vendor = "apple") {
+        Some(max_rss)
+    } else {
+        Some(max_rss * 1024)
+    }
+}
+
+#[cfg(not(unix))]
+const fn rusage_peak_bytes() -> Option<u64> {
+    None
+}
+
+/// Memory available for new allocations in bytes.
+///
+/// Read from `/proc/meminfo`; `None` on platforms without procfs.
+pub fn available_memory_bytes() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    parse_status_kib(&meminfo, "MemAvailable:").map(|kib| kib * 1024)
+}
+
+/// Value of a `<field> <n> kB` line in `/proc/<pid>/status`.
+fn parse_status_kib(status: &str, field: &str) -> Option<u64> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix(field))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|value| value.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_status_kib() {
+        let status = "Name:\tbirda\nVmPeak:\t  204800 kB\nVmRSS:\t   51200 kB\n";
+        assert_eq!(parse_status_kib(status, "VmRSS:"), Some(51200));
+        assert_eq!(parse_status_kib(status, "VmHWM:"), None);
+
+        let meminfo = "MemTotal:       16384000 kB\nMemAvailable:    8192000 kB\n";
+        assert_eq!(parse_status_kib(meminfo, "MemAvailable:"), Some(8_192_000));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_resident_memory_bytes() {
+        // Memory changes between the reads, so they are not compared
+        assert!(resident_memory_bytes().is_some_and(|bytes| bytes > 0));
+        assert!(peak_resident_memory_bytes().is_some_and(|bytes| bytes > 0));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rusage_peak_bytes() {
+        assert!(rusage_peak_bytes().is_some_and(|bytes| bytes > 0));
+    }
+}
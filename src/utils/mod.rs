@@ -1,4 +1,10 @@
 //! Utility modules.
 
 pub mod date;
+pub mod geo;
+pub mod glob;
+pub mod hash;
+pub mod memory;
 pub mod species_list;
+pub mod timestamp;
+pub mod track;
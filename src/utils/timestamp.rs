@@ -0,0 +1,206 @@
+//! Recording start times for absolute (wall-clock) detection timestamps.
+//!
+//! Detections are timed in seconds from the start of their file. When the
+//! recording start is known, [`TimestampParser::stamp`] adds the absolute
+//! `datetime_start` and `datetime_end` of each detection. The start is read
+//! from the file name, first with the configured `strftime` patterns and then
+//! with the `AudioMoth`/Song Meter convention, or else from the WAMD or GUANO
+//! timestamp of the recorder.
+
+use crate::audio::RecorderMetadata;
+use crate::constants::wall_clock::{DATETIME_FORMAT, RECORDER_FORMATS};
+use crate::error::{Error, Result};
+use crate::output::Detection;
+use crate::utils::date::timestamp_from_filename;
+use chrono::format::{Item, ParseErrorKind, StrftimeItems};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+use std::path::Path;
+
+/// Finds recording start times in file names and metadata.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TimestampParser {
+    /// `strftime` patterns tried before the built-in file name convention.
+    patterns: Vec<String>,
+}
+
+impl TimestampParser {
+    /// Parser trying `patterns` (e.g. `%Y-%m-%d_%H-%M-%S`) in order before
+    /// the built-in file name convention.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a pattern is not a valid `strftime` format or has
+    /// no date.
+    pub fn new(patterns: Vec<String>) -> Result<Self> {
+        for pattern in &patterns {
+            validate_pattern(pattern).map_err(|reason| Error::ConfigValidation {
+                message: format!("invalid timestamp pattern '{pattern}': {reason}"),
+            })?;
+        }
+        Ok(Self { patterns })
+    }
+
+    /// Start time of the recording at `path`, if its name or recorder
+    /// metadata has one.
+    pub fn recording_start(
+        &self,
+        path: &Path,
+        recorder: Option<&RecorderMetadata>,
+    ) -> Option<NaiveDateTime> {
+        let name = path.file_name()?.to_string_lossy();
+        self.patterns
+            .iter()
+            .find_map(|pattern| find_in_name(&name, pattern))
+            .or_else(|| timestamp_from_filename(path))
+            .or_else(|| {
+                recorder?
+                    .timestamp
+                    .as_deref()
+                    .and_then(parse_recorder_timestamp)
+            })
+    }
+
+    /// Set the absolute start and end times of `detections`, which are
+    /// relative to a recording that started at `start`.
+    pub fn stamp(start: NaiveDateTime, detections: &mut [Detection]) {
+        let at = |offset: f32| {
+            #[allow(clippy::cast_possible_truncation)]
+            let millis = (f64::from(offset) * 1000.0).round() as i64;
+            (start + TimeDelta::milliseconds(millis))
+                .format(DATETIME_FORMAT)
+                .to_string()
+        };
+        for detection in detections {
+            detection.metadata.datetime_start = Some(at(detection.start_time));
+            detection.metadata.datetime_end = Some(at(detection.end_time));
+        }
+    }
+}
+
+/// Check that `pattern` is a valid `strftime` format with a date.
+fn validate_pattern(pattern: &str) -> std::result::Result<(), String> {
+    if StrftimeItems::new(pattern).any(|item| matches!(item, Item::Error)) {
+        return Err("not a valid strftime format".to_string());
+    }
+    let has_date = ["%Y", "%F", "%D", "%y"]
+        .iter()
+        .any(|spec| pattern.contains(spec));
+    if has_date {
+        Ok(())
+    } else {
+        Err("no year (%Y, %y, %F or %D)".to_string())
+    }
+}
+
+/// First date and time in `name` matching `pattern`.
+///
+/// Matches start at a digit boundary, so a date is not found inside a longer
+/// number. A pattern without a time resolves to midnight.
+fn find_in_name(name: &str, pattern: &str) -> Option<NaiveDateTime> {
+    let mut previous = None;
+    name.char_indices().find_map(|(start, c)| {
+        let after_digit = previous.is_some_and(|p: char| p.is_ascii_digit());
+        previous = Some(c);
+        if after_digit {
+            return None;
+        }
+        let rest = &name[start..];
+        match NaiveDateTime::parse_and_remainder(rest, pattern) {
+            Ok((datetime, _)) => Some(datetime),
+            Err(e) if e.kind() == ParseErrorKind::NotEnough => {
+                NaiveDate::parse_and_remainder(rest, pattern)
+                    .ok()
+                    .map(|(date, _)| date.and_time(NaiveTime::MIN))
+            }
+            Err(_) => None,
+        }
+    })
+}
+
+/// Local start time from a recorder timestamp such as
+/// `2024-03-15 06:30:00-05:00` (WAMD) or `2024-03-15T06:30:00-05:00` (GUANO);
+/// the UTC offset is ignored like in file names.
+fn parse_recorder_timestamp(timestamp: &str) -> Option<NaiveDateTime> {
+    RECORDER_FORMATS.iter().find_map(|format| {
+        NaiveDateTime::parse_and_remainder(timestamp.trim(), format)
+            .ok()
+            .map(|(datetime, _)| datetime)
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn datetime(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn test_builtin_convention() {
+        let parser = TimestampParser::default();
+        assert_eq!(
+            parser.recording_start(Path::new("/data/SITE1_20240315_063000.wav"), None),
+            Some(datetime("2024-03-15 06:30:00"))
+        );
+        assert_eq!(parser.recording_start(Path::new("dawn.wav"), None), None);
+    }
+
+    #[test]
+    fn test_custom_pattern() {
+        let parser = TimestampParser::new(vec!["%Y-%m-%d_%H-%M-%S".to_string()]).unwrap();
+        assert_eq!(
+            parser.recording_start(Path::new("site1 2024-03-15_06-30-00.flac"), None),
+            Some(datetime("2024-03-15 06:30:00"))
+        );
+        let parser = TimestampParser::new(vec!["rec_%d%m%Y".to_string()]).unwrap();
+        assert_eq!(
+            parser.recording_start(Path::new("rec_15032024.wav"), None),
+            Some(datetime("2024-03-15 00:00:00"))
+        );
+    }
+
+    #[test]
+    fn test_invalid_patterns() {
+        assert!(TimestampParser::new(vec!["%H%M%S".to_string()]).is_err());
+        assert!(TimestampParser::new(vec!["%Y%Q".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_recorder_timestamp_fallback() {
+        for timestamp in ["2024-03-15 06:30:00-05:00", "2024-03-15T06:30:00-05:00"] {
+            let recorder = RecorderMetadata {
+                timestamp: Some(timestamp.to_string()),
+                ..RecorderMetadata::default()
+            };
+            assert_eq!(
+                TimestampParser::default()
+                    .recording_start(Path::new("S4A01234.wav"), Some(&recorder)),
+                Some(datetime("2024-03-15 06:30:00"))
+            );
+        }
+    }
+
+    #[test]
+    fn test_stamp_detections() {
+        let mut detections = vec![Detection::from_label(
+            "Parus major_Great Tit",
+            0.9,
+            61.5,
+            64.5,
+            PathBuf::from("a.wav"),
+        )];
+        TimestampParser::stamp(datetime("2024-03-15 23:59:00"), &mut detections);
+        let metadata = &detections[0].metadata;
+        assert_eq!(
+            metadata.datetime_start.as_deref(),
+            Some("2024-03-16T00:00:01.500")
+        );
+        assert_eq!(
+            metadata.datetime_end.as_deref(),
+            Some("2024-03-16T00:00:04.500")
+        );
+    }
+}
@@ -0,0 +1,337 @@
+//! GPS tracks for mobile (walked or driven) transect surveys.
+//!
+//! A [`GpsTrack`] is loaded from a GPX (`<trkpt>` with `<time>`) or KML
+//! (`<gx:Track>` with `<when>`/`<gx:coord>`) file. Each detection is placed
+//! at the track position interpolated at its time, computed from the
+//! recording start timestamp plus the detection offset. A GPX file beside a
+//! stationary recording gives its position with [`sidecar_position`].
+
+use crate::error::{Error, Result};
+use crate::output::Detection;
+use crate::utils::date::recording_timestamp;
+use chrono::{DateTime, NaiveDateTime, TimeDelta, Utc};
+use std::path::Path;
+use tracing::warn;
+
+/// A timestamped track position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackPoint {
+    /// Time of the fix.
+    pub time: DateTime<Utc>,
+    /// Latitude in degrees.
+    pub lat: f64,
+    /// Longitude in degrees.
+    pub lon: f64,
+}
+
+/// GPS track used to locate detections.
+#[derive(Debug, Clone)]
+pub struct GpsTrack {
+    /// Points sorted by time.
+    points: Vec<TrackPoint>,
+    /// Offset of recording timestamps from UTC.
+    utc_offset: TimeDelta,
+}
+
+impl GpsTrack {
+    /// Load a track from a `.gpx` or `.kml` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, has an unsupported
+    /// extension, or contains no timestamped points.
+    pub fn load(path: &Path) -> Result<Self> {
+        let xml = std::fs::read_to_string(path)?;
+        let extension = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_ascii_lowercase());
+        let points = match extension.as_deref() {
+            Some("gpx") => parse_gpx(&xml),
+            Some("kml") => parse_kml(&xml),
+            _ => {
+                return Err(Error::TrackParse {
+                    path: path.to_path_buf(),
+                    reason: "expected a .gpx or .kml file".to_string(),
+                });
+            }
+        }
+        .map_err(|reason| Error::TrackParse {
+            path: path.to_path_buf(),
+            reason,
+        })?;
+
+        Self::from_points(points).ok_or_else(|| Error::TrackParse {
+            path: path.to_path_buf(),
+            reason: "no timestamped track points found".to_string(),
+        })
+    }
+
+    /// Build a track from points in any order, or `None` if there are none.
+    pub fn from_points(mut points: Vec<TrackPoint>) -> Option<Self> {
+        if points.is_empty() {
+            return None;
+        }
+        points.sort_by_key(|p| p.time);
+        Some(Self {
+            points,
+            utc_offset: TimeDelta::zero(),
+        })
+    }
+
+    /// Interpret recording timestamps as local time `hours` ahead of UTC.
+    #[must_use]
+    pub fn with_utc_offset(mut self, hours: f32) -> Self {
+        #[allow(clippy::cast_possible_truncation)]
+        let seconds = (f64::from(hours) * 3600.0).round() as i64;
+        self.utc_offset = TimeDelta::seconds(seconds);
+        self
+    }
+
+    /// Time span covered by the track.
+    pub fn span(&self) -> (DateTime<Utc>, DateTime<Utc>) {
+        let first = self.points[0].time;
+        let last = self.points.last().map_or(first, |p| p.time);
+        (first, last)
+    }
+
+    /// Number of points in the track.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Whether the track has no points (never true for a loaded track).
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Position at `time`, interpolated between the surrounding points.
+    ///
+    /// Returns `None` outside the time span of the track.
+    pub fn position_at(&self, time: DateTime<Utc>) -> Option<(f64, f64)> {
+        let (first, last) = self.span();
+        if time < first || time > last {
+            return None;
+        }
+        let after = self.points.partition_point(|p| p.time < time);
+        let b = self.points[after];
+        if b.time == time || after == 0 {
+            return Some((b.lat, b.lon));
+        }
+        let a = self.points[after - 1];
+
+        #[allow(clippy::cast_precision_loss)]
+        let fraction =
+            (time - a.time).num_milliseconds() as f64 / (b.time - a.time).num_milliseconds() as f64;
+        Some((
+            (b.lat - a.lat).mul_add(fraction, a.lat),
+            (b.lon - a.lon).mul_add(fraction, a.lon),
+        ))
+    }
+
+    /// Set the location of each detection of `file` from the track.
+    ///
+    /// Detections are located at the middle of their segment. Files without a
+    /// recording timestamp, and detections outside the track, keep no
+    /// location and are reported once per file.
+    pub fn locate(&self, file: &Path, detections: &mut [Detection]) {
+        let Some(start) = recording_timestamp(file) else {
+            warn!(
+                "{}: no recording timestamp, cannot locate detections on the track",
+                file.display()
+            );
+            return;
+        };
+
+        let mut outside = 0;
+        for detection in detections.iter_mut() {
+            match self.position_at(self.detection_time(start, detection)) {
+                Some((lat, lon)) => {
+                    detection.metadata.lat = Some(lat);
+                    detection.metadata.lon = Some(lon);
+                }
+                None => outside += 1,
+            }
+        }
+        if outside > 0 {
+            warn!(
+                "{}: {} detection(s) fall outside the GPS track and have no location",
+                file.display(),
+                outside
+            );
+        }
+    }
+
+    /// UTC time of the middle of a detection's segment.
+    fn detection_time(&self, start: NaiveDateTime, detection: &Detection) -> DateTime<Utc> {
+        let middle = f64::from(detection.start_time + detection.end_time) / 2.0;
+        #[allow(clippy::cast_possible_truncation)]
+        let offset = TimeDelta::milliseconds((middle * 1000.0).round() as i64);
+        (start - self.utc_offset + offset).and_utc()
+    }
+}
+
+/// Position of a stationary recording from the GPX file beside it
+/// (`rec.gpx` for `rec.wav`): its first waypoint, or else its first track
+/// point.
+///
+/// Returns `None` when there is no sidecar or it has no valid position.
+pub fn sidecar_position(file: &Path) -> Option<(f64, f64)> {
+    let xml = ["gpx", "GPX"]
+        .iter()
+        .find_map(|extension| std::fs::read_to_string(file.with_extension(extension)).ok())?;
+    let doc = roxmltree::Document::parse(&xml).ok()?;
+    ["wpt", "trkpt"].iter().find_map(|name| {
+        let point = doc.descendants().find(|n| n.has_tag_name(*name))?;
+        let lat = parse_coordinate(point.attribute("lat")?).ok()?;
+        let lon = parse_coordinate(point.attribute("lon")?).ok()?;
+        Some((lat, lon))
+    })
+}
+
+/// Parse `<trkpt lat=".." lon=".."><time>..</time></trkpt>` points.
+///
+/// Points without a time are skipped.
+fn parse_gpx(xml: &str) -> std::result::Result<Vec<TrackPoint>, String> {
+    let doc = roxmltree::Document::parse(xml).map_err(|e| e.to_string())?;
+    let mut points = Vec::new();
+    for point in doc.descendants().filter(|n| n.has_tag_name("trkpt")) {
+        let Some(time) = point
+            .children()
+            .find(|n| n.has_tag_name("time"))
+            .and_then(|n| n.text())
+        else {
+            continue;
+        };
+        let lat = point.attribute("lat").ok_or("track point without lat")?;
+        let lon = point.attribute("lon").ok_or("track point without lon")?;
+        points.push(TrackPoint {
+            time: parse_time(time)?,
+            lat: parse_coordinate(lat)?,
+            lon: parse_coordinate(lon)?,
+        });
+    }
+    Ok(points)
+}
+
+/// Parse `<gx:Track>` elements, pairing each `<when>` with its `<gx:coord>`.
+fn parse_kml(xml: &str) -> std::result::Result<Vec<TrackPoint>, String> {
+    let doc = roxmltree::Document::parse(xml).map_err(|e| e.to_string())?;
+    let mut points = Vec::new();
+    for track in doc.descendants().filter(|n| n.has_tag_name("Track")) {
+        let texts = |name: &'static str| {
+            track
+                .children()
+                .filter(move |n| n.has_tag_name(name))
+                .map(|n| n.text().unwrap_or_default())
+        };
+        for (time, coord) in texts("when").zip(texts("coord")) {
+            // "lon lat [alt]"
+            let mut parts = coord.split_whitespace();
+            let (Some(lon), Some(lat)) = (parts.next(), parts.next()) else {
+                return Err(format!("invalid gx:coord '{coord}'"));
+            };
+            points.push(TrackPoint {
+                time: parse_time(time)?,
+                lat: parse_coordinate(lat)?,
+                lon: parse_coordinate(lon)?,
+            });
+        }
+    }
+    Ok(points)
+}
+
+fn parse_time(value: &str) -> std::result::Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(value.trim())
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|e| format!("invalid time '{}': {e}", value.trim()))
+}
+
+fn parse_coordinate(value: &str) -> std::result::Result<f64, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid coordinate '{}'", value.trim()))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const GPX: &str = r#"<?xml version="1.0"?>
+<gpx version="1.1"><trk><trkseg>
+  <trkpt lat="60.0" lon="24.0"><ele>10</ele><time>2024-05-01T05:00:00Z</time></trkpt>
+  <trkpt lon="24.2" lat="60.2"><time>2024-05-01T05:10:00Z</time></trkpt>
+  <trkpt lat="61.0" lon="25.0"/>
+</trkseg></trk></gpx>"#;
+
+    const KML: &str = r#"<kml xmlns:gx="http://www.google.com/kml/ext/2.2"><Document><Placemark><gx:Track>
+  <when>2024-05-01T05:00:00Z</when><when>2024-05-01T05:10:00Z</when>
+  <gx:coord>24.0 60.0 10</gx:coord><gx:coord>24.2 60.2 12</gx:coord>
+</gx:Track></Placemark></Document></kml>"#;
+
+    fn time(s: &str) -> DateTime<Utc> {
+        parse_time(s).unwrap()
+    }
+
+    #[test]
+    fn test_sidecar_position() {
+        let dir = tempfile::tempdir().unwrap();
+        let recording = dir.path().join("pond_20240501_050000.wav");
+        assert_eq!(sidecar_position(&recording), None);
+
+        std::fs::write(recording.with_extension("gpx"), GPX).unwrap();
+        assert_eq!(sidecar_position(&recording), Some((60.0, 24.0)));
+
+        std::fs::write(
+            recording.with_extension("gpx"),
+            r#"<gpx><wpt lat="61.5" lon="23.8"><name>Pond</name></wpt></gpx>"#,
+        )
+        .unwrap();
+        assert_eq!(sidecar_position(&recording), Some((61.5, 23.8)));
+    }
+
+    #[test]
+    fn test_parse_gpx_and_kml() {
+        for points in [parse_gpx(GPX).unwrap(), parse_kml(KML).unwrap()] {
+            assert_eq!(points.len(), 2);
+            assert!((points[1].lat - 60.2).abs() < 1e-9);
+            assert!((points[1].lon - 24.2).abs() < 1e-9);
+            assert_eq!(points[1].time, time("2024-05-01T05:10:00Z"));
+        }
+    }
+
+    #[test]
+    fn test_position_is_interpolated() {
+        let track = GpsTrack::from_points(parse_gpx(GPX).unwrap()).unwrap();
+        let (lat, lon) = track.position_at(time("2024-05-01T05:05:00Z")).unwrap();
+        assert!((lat - 60.1).abs() < 1e-9);
+        assert!((lon - 24.1).abs() < 1e-9);
+        assert!(track.position_at(time("2024-05-01T04:59:59Z")).is_none());
+        assert!(track.position_at(time("2024-05-01T05:10:01Z")).is_none());
+    }
+
+    #[test]
+    fn test_locate_uses_recording_timestamp_and_offset() {
+        let track = GpsTrack::from_points(parse_gpx(GPX).unwrap())
+            .unwrap()
+            .with_utc_offset(3.0);
+        // Local 08:02:30 is 05:02:30 UTC; the segment middle adds 2.5 minutes
+        let mut detections = vec![Detection::from_label(
+            "Parus major_Great Tit",
+            0.9,
+            147.0,
+            153.0,
+            PathBuf::from("/data/20240501_080230.wav"),
+        )];
+        track.locate(Path::new("/data/20240501_080230.wav"), &mut detections);
+        assert!((detections[0].metadata.lat.unwrap() - 60.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_malformed_xml_is_an_error() {
+        assert!(parse_gpx("<gpx><trkpt lat=\"60.0\"").is_err());
+        assert!(parse_kml("<kml><gx:Track></kml>").is_err());
+    }
+}
@@ -147,3 +147,41 @@ fn test_csv_roundtrip_parse() {
     assert_eq!(parsed[1].scientific_name, "Cyanistes caeruleus");
     assert!((parsed[0].confidence - 0.8542).abs() < 0.001);
 }
+
+#[test]
+fn test_extract_clip_applies_fades() {
+    use birda::clipper::{ClipExtractor, DetectionGroup};
+
+    let temp_dir = TempDir::new().unwrap();
+    let wav_path = temp_dir.path().join("tone.wav");
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate: 1000,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+    let mut writer = WavWriter::create(&wav_path, spec).unwrap();
+    for _ in 0..4000 {
+        writer.write_sample(i16::MAX / 2).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    let group = DetectionGroup {
+        scientific_name: "Parus major".to_string(),
+        common_name: "Great Tit".to_string(),
+        start: 1.0,
+        end: 3.0,
+        max_confidence: 0.9,
+        detection_count: 1,
+    };
+    let clip = ClipExtractor::new()
+        .with_fades(0.5, 0.25)
+        .extract_clip(&wav_path, &group)
+        .unwrap();
+
+    assert_eq!(clip.samples.len(), 2000);
+    assert!(clip.samples[0].abs() < f32::EPSILON);
+    assert!((clip.samples[250] - 0.25).abs() < 0.01);
+    assert!((clip.samples[1000] - 0.5).abs() < 0.01);
+    assert!(clip.samples[1999].abs() < 0.01);
+}
@@ -1,6 +1,6 @@
 //! Tests for detection grouping.
 
-use birda::clipper::{ParsedDetection, group_detections};
+use birda::clipper::{ParsedDetection, group_detections, top_per_species};
 
 fn make_detection(start: f64, end: f64, species: &str, confidence: f32) -> ParsedDetection {
     ParsedDetection {
@@ -9,6 +9,7 @@ fn make_detection(start: f64, end: f64, species: &str, confidence: f32) -> Parse
         scientific_name: species.to_string(),
         common_name: format!("{species} Common"),
         confidence,
+        file: None,
     }
 }
 
@@ -99,3 +100,37 @@ fn test_groups_sorted_by_start_time() {
         assert!(groups[1].start <= groups[2].start);
     }
 }
+
+#[test]
+fn test_top_per_species_across_files() {
+    let group = |file: &'static str, start: f64, species: &str, confidence: f32| {
+        let mut groups = group_detections(
+            vec![make_detection(start, start + 3.0, species, confidence)],
+            0.0,
+            0.0,
+        );
+        (file, groups.remove(0))
+    };
+    let groups = vec![
+        group("a.csv", 0.0, "Parus major", 0.6),
+        group("a.csv", 3.0, "Turdus merula", 0.5),
+        group("b.csv", 0.0, "Parus major", 0.9),
+        group("b.csv", 9.0, "Parus major", 0.8),
+        group("c.csv", 6.0, "Parus major", 0.9),
+    ];
+
+    let kept: Vec<_> = top_per_species(groups, 2)
+        .into_iter()
+        .map(|(file, g)| (file, g.scientific_name, g.start))
+        .collect();
+
+    // The two best Parus major clips, in their original order
+    assert_eq!(
+        kept,
+        vec![
+            ("a.csv", "Turdus merula".to_string(), 3.0),
+            ("b.csv", "Parus major".to_string(), 0.0),
+            ("c.csv", "Parus major".to_string(), 6.0),
+        ]
+    );
+}